@@ -0,0 +1,44 @@
+//! Local app usage metrics
+//!
+//! Counts how the app itself is used (pauses, idle prompt answers, rule
+//! edits, ...) per day in the `app_metrics` table so users can see which parts
+//! of their setup need tuning. The counts never leave the device: they are not
+//! sent anywhere, published on the event bus or exposed to plugins.
+
+use crate::database::Database;
+use crate::events::{
+    EventBus, EVENT_MANUAL_ENTRY_CREATED, EVENT_PROFILE_SWITCHED, EVENT_TRACKING_PAUSED,
+    EVENT_TRACKING_RESUMED,
+};
+use std::sync::Arc;
+
+pub const METRIC_TRACKING_PAUSED: &str = "tracking_paused";
+pub const METRIC_TRACKING_RESUMED: &str = "tracking_resumed";
+pub const METRIC_IDLE_PROMPT_SHOWN: &str = "idle_prompt_shown";
+pub const METRIC_IDLE_PROMPT_ANSWERED: &str = "idle_prompt_answered";
+pub const METRIC_IDLE_PROMPT_DISMISSED: &str = "idle_prompt_dismissed";
+pub const METRIC_RULE_EDITED: &str = "rule_edited";
+pub const METRIC_MANUAL_ENTRY_CREATED: &str = "manual_entry_created";
+pub const METRIC_CHECKIN_ANSWERED: &str = "checkin_answered";
+pub const METRIC_PROFILE_SWITCHED: &str = "profile_switched";
+
+/// Count one occurrence of `metric` now; failures are only logged
+pub fn record(db: &Database, metric: &str) {
+    if let Err(e) = db.increment_app_metric(metric, chrono::Utc::now().timestamp()) {
+        eprintln!("Warning: Failed to record app metric {}: {}", metric, e);
+    }
+}
+
+/// Count app events published on the event bus
+pub fn start(db: Arc<Database>, event_bus: &EventBus) {
+    event_bus.subscribe(move |event| {
+        let metric = match event.name.as_str() {
+            EVENT_TRACKING_PAUSED => METRIC_TRACKING_PAUSED,
+            EVENT_TRACKING_RESUMED => METRIC_TRACKING_RESUMED,
+            EVENT_MANUAL_ENTRY_CREATED => METRIC_MANUAL_ENTRY_CREATED,
+            EVENT_PROFILE_SWITCHED => METRIC_PROFILE_SWITCHED,
+            _ => return,
+        };
+        record(&db, metric);
+    });
+}
@@ -0,0 +1,106 @@
+//! Work-life boundaries (right to disconnect)
+//!
+//! Work outside work hours is time in quiet hours (`quiet_hours_start` to
+//! `quiet_hours_end`, local time) and, with `quiet_weekends`, any time on a
+//! weekend. While `boundary_alerts_enabled` is `true`, a day on which that
+//! goes over `boundary_threshold_minutes` is logged in `boundary_violations`
+//! and, with `boundary_notifications`, reported once through `on_alert`.
+//! `weekly_report` summarizes a week for the boundary report.
+
+use crate::database::boundaries::{BOUNDARY_QUIET_HOURS, BOUNDARY_WEEKEND};
+use crate::database::{BoundaryViolation, Database};
+use crate::wellness::{daily_totals, QuietHours, WellnessDay};
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often today's work is checked
+const POLL_INTERVAL_SECS: u64 = 300;
+
+/// Work-life boundary report of a week (see `get_boundary_report`)
+#[derive(Debug, Clone, Serialize)]
+pub struct BoundaryReport {
+    /// First day, `YYYY-MM-DD`
+    pub week_start: String,
+    /// Days with tracked time
+    pub days: Vec<WellnessDay>,
+    pub quiet_hours_seconds: i64,
+    /// Zero unless `quiet_weekends`
+    pub weekend_seconds: i64,
+    pub violations: Vec<BoundaryViolation>,
+}
+
+/// Kind and time of work outside work hours on `day`. With `quiet_weekends`
+/// a whole weekend day counts as weekend work, quiet hours included.
+pub fn outside_hours(day: &WellnessDay, quiet: &QuietHours) -> (&'static str, i64) {
+    if day.weekend && quiet.weekends {
+        (BOUNDARY_WEEKEND, day.screen_seconds)
+    } else {
+        (BOUNDARY_QUIET_HOURS, day.late_night_seconds)
+    }
+}
+
+fn local_midnight(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map(|t| t.timestamp())
+        .unwrap_or_default()
+}
+
+/// Report of the 7 days starting on the local day of `week_start`
+pub fn weekly_report(db: &Database, week_start: i64) -> rusqlite::Result<BoundaryReport> {
+    let quiet = QuietHours::from_settings(&crate::settings::load(db));
+    let first = Local.timestamp_opt(week_start, 0).single().unwrap_or_else(Local::now).date_naive();
+    let last = first + Days::new(6);
+    let spans = db.get_active_spans(local_midnight(first), local_midnight(last + Days::new(1)) - 1)?;
+    let (days, _) = daily_totals(&spans, &Local, &quiet);
+
+    let (mut quiet_hours_seconds, mut weekend_seconds) = (0, 0);
+    for day in &days {
+        match outside_hours(day, &quiet) {
+            (BOUNDARY_WEEKEND, seconds) => weekend_seconds += seconds,
+            (_, seconds) => quiet_hours_seconds += seconds,
+        }
+    }
+    let format = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+    Ok(BoundaryReport {
+        week_start: format(first),
+        violations: db.get_boundary_violations(&format(first), &format(last))?,
+        days,
+        quiet_hours_seconds,
+        weekend_seconds,
+    })
+}
+
+/// Start the boundary monitor; `on_alert` gets the kind and seconds of work
+/// outside work hours the first time today goes over the threshold
+pub fn start<F>(db: Arc<Database>, on_alert: F)
+where
+    F: Fn(&str, i64) + Send + 'static,
+{
+    std::thread::spawn(move || loop {
+        let settings = crate::settings::load(&db);
+        if settings.boundary_alerts_enabled {
+            let quiet = QuietHours::from_settings(&settings);
+            let now = Local::now();
+            let spans = db
+                .get_active_spans(local_midnight(now.date_naive()), now.timestamp())
+                .unwrap_or_default();
+            let (days, _) = daily_totals(&spans, &Local, &quiet);
+            let today = now.date_naive().format("%Y-%m-%d").to_string();
+            if let Some(day) = days.iter().find(|d| d.date == today) {
+                let (kind, seconds) = outside_hours(day, &quiet);
+                if seconds >= settings.boundary_threshold_minutes * 60 {
+                    match db.log_boundary_violation(&today, kind, seconds, now.timestamp()) {
+                        Ok(true) if settings.boundary_notifications => on_alert(kind, seconds),
+                        Ok(_) => {}
+                        Err(e) => eprintln!("Warning: Failed to log boundary violation: {}", e),
+                    }
+                }
+            }
+        }
+        std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+    });
+}
@@ -0,0 +1,63 @@
+//! Random productivity check-ins (experience sampling)
+//!
+//! While `checkins_enabled` is `true`, prompts at random intervals averaging
+//! `checkins_interval_minutes` (default 90). A prompt is only shown while the
+//! user is actively working (a recent non-idle activity exists); otherwise it
+//! waits until they are. Answers are stored via `submit_checkin`.
+
+use crate::database::Database;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the schedule (and settings) are checked
+const POLL_INTERVAL_SECS: u64 = 60;
+
+/// Activity that ended longer ago than this means the user is away
+const ACTIVE_WINDOW_SECS: i64 = 120;
+
+fn configured_interval_secs(db: &Database) -> Option<i64> {
+    let settings = crate::settings::load(db);
+    settings.checkins_enabled.then_some(settings.checkins_interval_minutes * 60)
+}
+
+/// Random delay between half and one and a half times the interval,
+/// so prompts cannot be anticipated
+fn random_delay(interval_secs: i64) -> i64 {
+    let random = RandomState::new().build_hasher().finish();
+    interval_secs / 2 + (random % (interval_secs.max(1) as u64)) as i64
+}
+
+/// Whether the user is working: the latest activity is not idle and recent
+pub(crate) fn is_user_active(db: &Database, now: i64) -> bool {
+    match db.get_latest_activity() {
+        Ok(Some(activity)) => !activity.is_idle && activity.started_at + activity.duration_sec >= now - ACTIVE_WINDOW_SECS,
+        _ => false,
+    }
+}
+
+/// Start the check-in scheduler; `on_prompt` is called when a check-in is due
+pub fn start<F>(db: Arc<Database>, on_prompt: F)
+where
+    F: Fn() + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut next_due: Option<i64> = None;
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            match configured_interval_secs(&db) {
+                None => next_due = None,
+                Some(interval) => match next_due {
+                    None => next_due = Some(now + random_delay(interval)),
+                    Some(due) if now >= due && is_user_active(&db, now) => {
+                        on_prompt();
+                        next_due = Some(now + random_delay(interval));
+                    }
+                    Some(_) => {}
+                },
+            }
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
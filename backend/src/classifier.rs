@@ -0,0 +1,232 @@
+//! Local category classifier
+//!
+//! A multinomial Naive Bayes model over app name, window title and domain
+//! tokens, trained on the user's manual category corrections. The model is
+//! small enough to be rebuilt from the database on every request.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::database::suggestions::TrainingSample;
+use crate::database::Activity;
+
+/// Minimum number of training samples before suggestions are offered
+const MIN_TRAINING_SAMPLES: usize = 5;
+
+/// Number of suggestions returned per activity
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Suggested category for an activity
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CategorySuggestion {
+    pub category_id: i64,
+    /// Posterior probability in the range 0.0..=1.0
+    pub confidence: f64,
+}
+
+/// Activity similar to a reference activity
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SimilarActivity {
+    pub activity: Activity,
+    /// Title similarity in the range 0.0..=1.0
+    pub similarity: f64,
+}
+
+/// Split activity fields into classifier tokens.
+/// App name and domain are kept whole (prefixed) since they are strong signals;
+/// window titles are split into lowercase words.
+pub fn tokenize(app_name: &str, window_title: Option<&str>, domain: Option<&str>) -> Vec<String> {
+    let mut tokens = vec![format!("app:{}", app_name.to_lowercase())];
+
+    if let Some(domain) = domain.filter(|d| !d.is_empty()) {
+        tokens.push(format!("domain:{}", domain.to_lowercase()));
+    }
+
+    if let Some(title) = window_title {
+        tokens.extend(title_tokens(title));
+    }
+
+    tokens
+}
+
+/// Split a window title into lowercase words of two or more characters
+fn title_tokens(title: &str) -> impl Iterator<Item = String> + '_ {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() >= 2)
+        .map(|w| w.to_lowercase())
+}
+
+/// Jaccard similarity (0.0..=1.0) of the word sets of two window titles
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<String> = title_tokens(a).collect();
+    let b: HashSet<String> = title_tokens(b).collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(&b).count() as f64;
+    let union = a.union(&b).count() as f64;
+    intersection / union
+}
+
+/// Naive Bayes category classifier
+#[derive(Debug, Default)]
+pub struct CategoryClassifier {
+    /// Number of training samples per category
+    category_counts: HashMap<i64, usize>,
+    /// Token counts per category
+    token_counts: HashMap<i64, HashMap<String, usize>>,
+    /// Total number of tokens per category
+    category_token_totals: HashMap<i64, usize>,
+    vocabulary_size: usize,
+    sample_count: usize,
+}
+
+impl CategoryClassifier {
+    /// Train a classifier from manual correction samples
+    pub fn train(samples: &[TrainingSample]) -> Self {
+        let mut classifier = Self::default();
+        let mut vocabulary: HashSet<String> = HashSet::new();
+
+        for (app_name, window_title, domain, category_id) in samples {
+            let tokens = tokenize(app_name, window_title.as_deref(), domain.as_deref());
+            *classifier.category_counts.entry(*category_id).or_insert(0) += 1;
+            *classifier.category_token_totals.entry(*category_id).or_insert(0) += tokens.len();
+
+            let counts = classifier.token_counts.entry(*category_id).or_default();
+            for token in tokens {
+                *counts.entry(token.clone()).or_insert(0) += 1;
+                vocabulary.insert(token);
+            }
+        }
+
+        classifier.vocabulary_size = vocabulary.len();
+        classifier.sample_count = samples.len();
+        classifier
+    }
+
+    /// Whether there is enough training data to make suggestions
+    pub fn is_ready(&self) -> bool {
+        self.sample_count >= MIN_TRAINING_SAMPLES && self.category_counts.len() >= 2
+    }
+
+    /// Suggest categories for an activity, best match first
+    pub fn suggest(&self, app_name: &str, window_title: Option<&str>, domain: Option<&str>) -> Vec<CategorySuggestion> {
+        if !self.is_ready() {
+            return Vec::new();
+        }
+
+        let tokens = tokenize(app_name, window_title, domain);
+        let vocabulary = self.vocabulary_size as f64;
+
+        // Log-probabilities with Laplace smoothing
+        let scores: Vec<(i64, f64)> = self
+            .category_counts
+            .iter()
+            .map(|(&category_id, &count)| {
+                let prior = (count as f64 / self.sample_count as f64).ln();
+                let counts = &self.token_counts[&category_id];
+                let total = self.category_token_totals[&category_id] as f64;
+                let likelihood: f64 = tokens
+                    .iter()
+                    .map(|t| {
+                        let token_count = counts.get(t).copied().unwrap_or(0) as f64;
+                        ((token_count + 1.0) / (total + vocabulary)).ln()
+                    })
+                    .sum();
+                (category_id, prior + likelihood)
+            })
+            .collect();
+
+        // Normalize into probabilities (log-sum-exp)
+        let max_score = scores.iter().map(|(_, s)| *s).fold(f64::NEG_INFINITY, f64::max);
+        let denominator: f64 = scores.iter().map(|(_, s)| (s - max_score).exp()).sum();
+
+        let mut suggestions: Vec<CategorySuggestion> = scores
+            .into_iter()
+            .map(|(category_id, score)| CategorySuggestion {
+                category_id,
+                confidence: (score - max_score).exp() / denominator,
+            })
+            .collect();
+
+        suggestions.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        suggestions.truncate(MAX_SUGGESTIONS);
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(app_name: &str, title: &str, category_id: i64) -> TrainingSample {
+        (app_name.to_string(), Some(title.to_string()), None, category_id)
+    }
+
+    fn samples() -> Vec<TrainingSample> {
+        vec![
+            sample("Code", "main.rs - backend", 1),
+            sample("Code", "lib.rs - backend", 1),
+            sample("Code", "tests.rs - backend", 1),
+            sample("Firefox", "News - Firefox", 2),
+            sample("Firefox", "Forum - Firefox", 2),
+            sample("Slack", "general - Slack", 3),
+        ]
+    }
+
+    #[test]
+    fn training_counts_samples_and_tokens_per_category() {
+        let classifier = CategoryClassifier::train(&samples());
+        assert_eq!(classifier.sample_count, 6);
+        let counts = &classifier.category_counts;
+        assert_eq!((counts[&1], counts[&2], counts[&3]), (3, 2, 1));
+        // The app token plus three title words per sample
+        assert_eq!(classifier.category_token_totals[&1], 12);
+        assert_eq!(classifier.token_counts[&1]["app:code"], 3);
+        assert_eq!(classifier.token_counts[&1]["backend"], 3);
+        assert!(!classifier.token_counts[&2].contains_key("backend"));
+        // app:code main rs backend lib tests, app:firefox news firefox forum, app:slack general slack
+        assert_eq!(classifier.vocabulary_size, 13);
+    }
+
+    #[test]
+    fn suggestions_need_enough_samples_and_two_categories() {
+        let few = CategoryClassifier::train(&samples()[..4]);
+        assert!(!few.is_ready());
+        assert!(few.suggest("Code", Some("main.rs"), None).is_empty());
+
+        let one_category: Vec<_> =
+            (0..MIN_TRAINING_SAMPLES).map(|i| sample("Code", &format!("file{i}.rs"), 1)).collect();
+        assert!(!CategoryClassifier::train(&one_category).is_ready());
+
+        assert!(CategoryClassifier::train(&samples()[..5]).is_ready());
+        assert!(!CategoryClassifier::default().is_ready());
+    }
+
+    #[test]
+    fn suggestions_are_probabilities_best_first() {
+        let classifier = CategoryClassifier::train(&samples());
+        let suggestions = classifier.suggest("Code", Some("mod.rs - backend"), None);
+        assert_eq!(suggestions.len(), 3);
+        assert_eq!(suggestions[0].category_id, 1);
+        assert!(suggestions.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+        let total: f64 = suggestions.iter().map(|s| s.confidence).sum();
+        assert!((total - 1.0).abs() < 1e-9, "{total}");
+
+        // An unseen app still gets smoothed probabilities, led by the most trained category
+        let unknown = classifier.suggest("Terminal", None, None);
+        assert_eq!(unknown.iter().map(|s| s.category_id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(unknown[0].confidence < 0.5, "{unknown:?}");
+        assert!((unknown.iter().map(|s| s.confidence).sum::<f64>() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn at_most_three_suggestions_are_returned() {
+        let mut samples = samples();
+        samples.push(sample("Mail", "Inbox - Mail", 4));
+        let suggestions = CategoryClassifier::train(&samples).suggest("Mail", Some("Inbox - Mail"), None);
+        assert_eq!(suggestions.len(), MAX_SUGGESTIONS);
+        assert_eq!(suggestions[0].category_id, 4);
+        assert!(suggestions.iter().map(|s| s.confidence).sum::<f64>() < 1.0);
+    }
+}
@@ -0,0 +1,200 @@
+//! Category colors
+//!
+//! Category colors are drawn on light and dark backgrounds in every chart, so
+//! they are checked against both themes with the WCAG contrast ratio. New
+//! categories get a suggestion from a generated palette: hues spread by the
+//! golden angle, each at a lightness that stays readable on both themes, and
+//! the one farthest from the colors already in use is picked.
+
+use serde::Serialize;
+
+/// Page backgrounds of the light and dark themes
+const LIGHT_BACKGROUND: (u8, u8, u8) = (0xFF, 0xFF, 0xFF);
+const DARK_BACKGROUND: (u8, u8, u8) = (0x1F, 0x29, 0x37);
+
+/// WCAG minimum contrast for graphical objects such as chart segments
+pub const MIN_CONTRAST: f64 = 3.0;
+
+/// Relative luminance the palette aims for; about 3.5:1 on both backgrounds
+const PALETTE_LUMINANCE: f64 = 0.2;
+
+const PALETTE_SIZE: usize = 24;
+const PALETTE_SATURATION: f64 = 0.7;
+const GOLDEN_ANGLE: f64 = 137.508;
+
+/// How readable a color is on both themes
+#[derive(Debug, Clone, Serialize)]
+pub struct ColorContrast {
+    pub color: String,
+    pub light_ratio: f64,
+    pub dark_ratio: f64,
+    /// Both ratios reach `MIN_CONTRAST`
+    pub readable: bool,
+    /// Closest readable color with the same hue, when this one is not readable
+    pub suggestion: Option<String>,
+}
+
+/// Parse `#RGB` or `#RRGGBB`
+pub fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let mut digits = hex.chars().map(|c| c.to_digit(16).map(|d| (d * 17) as u8));
+            Some((digits.next()??, digits.next()??, digits.next()??))
+        }
+        6 => Some((channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        _ => None,
+    }
+}
+
+pub fn to_hex((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f64 {
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b)
+}
+
+fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let h = (hue.rem_euclid(360.0)) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = lightness - c / 2.0;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+fn rgb_to_hue_saturation((r, g, b): (u8, u8, u8)) -> (f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0);
+    }
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    let lightness = (max + min) / 2.0;
+    (hue, delta / (1.0 - (2.0 * lightness - 1.0).abs()))
+}
+
+/// Color of this hue and saturation whose luminance is closest to `PALETTE_LUMINANCE`
+fn readable_color(hue: f64, saturation: f64) -> (u8, u8, u8) {
+    // Luminance grows with HSL lightness, so bisect on lightness
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..20 {
+        let mid = (low + high) / 2.0;
+        if relative_luminance(hsl_to_rgb(hue, saturation, mid)) < PALETTE_LUMINANCE {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    hsl_to_rgb(hue, saturation, (low + high) / 2.0)
+}
+
+/// Check a color against the light and dark themes
+pub fn check_contrast(color: &str) -> Option<ColorContrast> {
+    let rgb = parse_hex(color)?;
+    let light_ratio = contrast_ratio(rgb, LIGHT_BACKGROUND);
+    let dark_ratio = contrast_ratio(rgb, DARK_BACKGROUND);
+    let readable = light_ratio >= MIN_CONTRAST && dark_ratio >= MIN_CONTRAST;
+    let suggestion = (!readable).then(|| {
+        let (hue, saturation) = rgb_to_hue_saturation(rgb);
+        to_hex(readable_color(hue, saturation))
+    });
+    Some(ColorContrast {
+        color: to_hex(rgb),
+        light_ratio: (light_ratio * 100.0).round() / 100.0,
+        dark_ratio: (dark_ratio * 100.0).round() / 100.0,
+        readable,
+        suggestion,
+    })
+}
+
+/// Readable colors with well-separated hues
+pub fn palette() -> Vec<String> {
+    (0..PALETTE_SIZE)
+        .map(|i| to_hex(readable_color(i as f64 * GOLDEN_ANGLE, PALETTE_SATURATION)))
+        .collect()
+}
+
+/// Palette color most different from the colors already in use
+pub fn suggest_color(used: &[String]) -> String {
+    let used: Vec<(u8, u8, u8)> = used.iter().filter_map(|c| parse_hex(c)).collect();
+    let distance = |a: (u8, u8, u8), b: (u8, u8, u8)| {
+        let d = |x: u8, y: u8| (x as f64 - y as f64).powi(2);
+        d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+    };
+    let palette = palette();
+    palette
+        .iter()
+        .filter_map(|color| parse_hex(color).map(|rgb| (color, rgb)))
+        .map(|(color, rgb)| {
+            let nearest = used.iter().map(|u| distance(rgb, *u)).fold(f64::INFINITY, f64::min);
+            (color, nearest)
+        })
+        // Earlier palette entries win ties, so suggestions are stable
+        .fold(None, |best: Option<(&String, f64)>, (color, nearest)| match best {
+            Some((_, best_distance)) if best_distance >= nearest => best,
+            _ => Some((color, nearest)),
+        })
+        .map(|(color, _)| color.clone())
+        .unwrap_or_else(|| palette[0].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_is_readable_on_both_themes() {
+        for color in palette() {
+            let contrast = check_contrast(&color).unwrap();
+            assert!(contrast.readable, "{} is not readable: {:?}", color, contrast);
+        }
+    }
+
+    #[test]
+    fn flags_unreadable_colors_with_a_suggestion() {
+        let yellow = check_contrast("#FFEB3B").unwrap();
+        assert!(!yellow.readable);
+        assert!(check_contrast(yellow.suggestion.as_deref().unwrap()).unwrap().readable);
+        assert_eq!(check_contrast("#fff").unwrap().color, "#FFFFFF");
+        assert!(check_contrast("blue").is_none());
+    }
+
+    #[test]
+    fn suggests_an_unused_color() {
+        let palette = palette();
+        assert_eq!(suggest_color(&[]), palette[0]);
+        assert_ne!(suggest_color(&[palette[0].clone()]), palette[0]);
+    }
+}
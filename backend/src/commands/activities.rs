@@ -2,35 +2,206 @@
 
 use std::collections::HashMap;
 
-use crate::commands::common::AppState;
-use crate::database::Activity;
+use crate::commands::common::{select_fields, AppState};
+use crate::database::{Activity, ActivityCursor, RuleRunReport};
+use crate::database::common::SYSTEM_CATEGORY_UNCATEGORIZED;
+use crate::events::EVENT_ACTIVITIES_CHANGED;
 use crate::plugin_system::extensions::EntityType;
+use crate::timeline::{merge_activities, TimelineBlock};
+use serde::{Deserialize, Serialize};
 use tauri::State;
 
+/// Default and maximum page sizes for `get_activities_page`
+const DEFAULT_PAGE_SIZE: i64 = 500;
+const MAX_PAGE_SIZE: i64 = 5000;
+
+/// `get_activities` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ActivitiesRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub offset: Option<i64>,
+    /// Passed to plugin query filters
+    #[serde(default)]
+    pub filter_params: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+}
+
+/// `get_activities_page` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ActivitiesPageRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+}
+
+/// `get_timeline` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct TimelineRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub min_block_sec: Option<i64>,
+}
+
+/// `update_activity_category` request; a `null` category clears it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateActivityCategoryRequest {
+    pub activity_id: i64,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+}
+
+/// `set_billable_override` request; a `null` override clears it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct BillableOverrideRequest {
+    #[serde(default)]
+    pub activity_ids: Vec<i64>,
+    #[serde(default)]
+    pub manual_entry_ids: Vec<i64>,
+    #[serde(default)]
+    pub billable_override: Option<bool>,
+}
+
+/// Page of activities with the cursor for the next page
+#[derive(Serialize)]
+pub struct ActivityPage {
+    pub activities: Vec<serde_json::Value>,
+    /// Pass as `cursor` to fetch the next page; `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+fn validate_include(include: &[String]) -> Result<(), String> {
+    match include.iter().find(|i| i.as_str() != "category") {
+        Some(unknown) => Err(format!("Unknown include: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+/// Apply the `fields` selection and `include` expansions to activities
+fn shape_activities(
+    state: &AppState,
+    activities: &[Activity],
+    fields: Option<&[String]>,
+    include: &[String],
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut values = select_fields(activities, fields)?;
+    if !include.is_empty() {
+        let categories: HashMap<i64, serde_json::Value> = state
+            .storage
+            .get_categories()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|c| (c.id, serde_json::json!({ "id": c.id, "name": c.name, "color": c.color })))
+            .collect();
+        for (value, activity) in values.iter_mut().zip(activities) {
+            value["category"] = activity
+                .category_id
+                .and_then(|id| categories.get(&id).cloned())
+                .unwrap_or(serde_json::Value::Null);
+        }
+    }
+    Ok(values)
+}
+
 /// Get activities for a time range with optional pagination (lazy loading).
 /// If limit is None, returns all activities (backward compatibility).
+/// `sources` limits results to the given sources (e.g. `tracker`, `import:screen_time`).
 /// When extension_registry is available, plugin query filters are applied after the database query.
+///
+/// To keep payloads small on large ranges, `fields` selects which activity fields are
+/// returned (e.g. `["started_at", "duration_sec", "category_id"]`) and `include: ["category"]`
+/// adds a compact `category` object (`id`, `name`, `color`) to each activity.
 #[tauri::command]
 pub fn get_activities(
     state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-    limit: Option<i64>,
-    offset: Option<i64>,
-    filter_params: Option<HashMap<String, serde_json::Value>>,
-) -> Result<Vec<Activity>, String> {
+    request: ActivitiesRequest,
+) -> Result<Vec<serde_json::Value>, String> {
+    let include = request.include.unwrap_or_default();
+    validate_include(&include)?;
+
     let activities = state
-        .db
-        .get_activities(start, end, limit, offset, None, None)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .storage
+        .get_activities(
+            request.start,
+            request.end,
+            request.limit,
+            request.offset,
+            None,
+            None,
+            request.sources.as_deref(),
+        )
+        .map_err(|e| e.to_string())?;
 
-    if let Some(reg) = &state.extension_registry {
-        let params = filter_params.unwrap_or_default();
+    let activities = if let Some(reg) = &state.extension_registry {
+        let params = request.filter_params.unwrap_or_default();
         reg.apply_query_filters(EntityType::Activity, activities, params)
-            .map_err(|e| format!("Query filter error: {}", e))
+            .map_err(|e| format!("Query filter error: {}", e))?
     } else {
-        Ok(activities)
-    }
+        activities
+    };
+
+    shape_activities(&state, &activities, request.fields.as_deref(), &include)
+}
+
+/// Get activities page by page using a keyset cursor (`next_cursor` from the previous page).
+/// Stable under concurrent tracker writes, unlike `limit`/`offset`.
+/// `sources`, `fields` and `include` behave as in `get_activities`.
+#[tauri::command]
+pub fn get_activities_page(
+    state: State<'_, AppState>,
+    request: ActivitiesPageRequest,
+) -> Result<ActivityPage, String> {
+    let include = request.include.unwrap_or_default();
+    validate_include(&include)?;
+    let after = match request.cursor.as_deref() {
+        Some(token) => Some(ActivityCursor::decode(token).ok_or_else(|| format!("Invalid cursor: {}", token))?),
+        None => None,
+    };
+    let limit = request.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    let (activities, next_cursor) = state
+        .storage
+        .get_activities_page(request.start, request.end, after, limit, request.sources.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    Ok(ActivityPage {
+        activities: shape_activities(&state, &activities, request.fields.as_deref(), &include)?,
+        next_cursor: next_cursor.map(|c| c.encode()),
+    })
+}
+
+/// Get the activities of a range merged into timeline blocks (see `timeline`).
+/// Blocks shorter than `min_block_sec` (default 0) are folded into the block before them.
+#[tauri::command]
+pub fn get_timeline(state: State<'_, AppState>, request: TimelineRequest) -> Result<Vec<TimelineBlock>, String> {
+    let activities = state
+        .storage
+        .get_activities(request.start, request.end, None, None, None, None, None)
+        .map_err(|e| e.to_string())?;
+    Ok(merge_activities(&activities, request.min_block_sec.unwrap_or(0).max(0)))
 }
 
 /// Get activity by ID
@@ -40,26 +211,36 @@ pub fn get_activity(
     id: i64,
 ) -> Result<Option<Activity>, String> {
     state
-        .db
+        .storage
         .get_activity_by_id(id)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e| e.to_string())
 }
 
 /// Update activity category
 #[tauri::command]
 pub fn update_activity_category(
     state: State<'_, AppState>,
-    activity_id: i64,
-    category_id: Option<i64>,
+    request: UpdateActivityCategoryRequest,
 ) -> Result<(), String> {
+    let UpdateActivityCategoryRequest { activity_id, category_id } = request;
     state
-        .db
+        .storage
         .update_activity_category(activity_id, category_id)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "activity_id": activity_id }));
+
+    // Remember manual corrections so category suggestions can learn from them
+    let correction = match category_id {
+        Some(id) if id != SYSTEM_CATEGORY_UNCATEGORIZED => state.storage.record_category_correction(activity_id, id),
+        _ => state.storage.clear_category_correction(activity_id),
+    };
+    if let Err(e) = correction {
+        eprintln!("Warning: Failed to record category correction: {}", e);
+    }
     
     // Apply plugin hooks if extension registry is available
     if let Some(extension_registry) = &state.extension_registry {
-        if let Ok(Some(mut activity)) = state.db.get_activity_by_id(activity_id) {
+        if let Ok(Some(mut activity)) = state.storage.get_activity_by_id(activity_id) {
             use std::sync::Arc;
             if let Err(e) = extension_registry.apply_activity_hooks(&mut activity, &Arc::clone(&state.db)) {
                 eprintln!("Warning: Failed to apply activity hooks: {}", e);
@@ -70,14 +251,51 @@ pub fn update_activity_category(
     Ok(())
 }
 
+/// Set or clear (`null`) the billable override of several activities and
+/// manual entries at once; approved entries are skipped. Returns the number updated.
+#[tauri::command]
+pub fn set_billable_override(state: State<'_, AppState>, request: BillableOverrideRequest) -> Result<usize, String> {
+    let BillableOverrideRequest { activity_ids, manual_entry_ids, billable_override } = request;
+    let activities = state
+        .storage
+        .set_activities_billable_override(&activity_ids, billable_override)
+        .map_err(|e| e.to_string())?;
+    let manual_entries = state
+        .storage
+        .set_manual_entries_billable_override(&manual_entry_ids, billable_override)
+        .map_err(|e| e.to_string())?;
+    Ok(activities + manual_entries)
+}
+
 /// Delete activity
 #[tauri::command]
 pub fn delete_activity(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.db.delete_activity(id).map_err(|e: rusqlite::Error| e.to_string())
+    state.storage.delete_activity(id).map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "activity_id": id }));
+    Ok(())
 }
 
-/// Reapply categorization rules to all activities
+/// Reapply categorization rules to all activities. The changes are kept for
+/// `get_last_rule_run_report` and `undo_last_rule_run`.
 #[tauri::command]
 pub fn reapply_categorization_rules(state: State<'_, AppState>) -> Result<(), String> {
-    state.db.reapply_categorization_rules().map_err(|e: rusqlite::Error| e.to_string())
+    state.storage.reapply_categorization_rules().map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({}));
+    Ok(())
+}
+
+/// Get what the last `reapply_categorization_rules` run changed: the moved
+/// activities and the time each category gained or lost
+#[tauri::command]
+pub fn get_last_rule_run_report(state: State<'_, AppState>) -> Result<Option<RuleRunReport>, String> {
+    state.storage.get_last_rule_run_report().map_err(|e| e.to_string())
+}
+
+/// Restore the categories the last rule run changed, except on activities
+/// recategorized since. Returns the number of activities restored.
+#[tauri::command]
+pub fn undo_last_rule_run(state: State<'_, AppState>) -> Result<usize, String> {
+    let restored = state.storage.undo_last_rule_run().map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "restored": restored }));
+    Ok(restored)
 }
@@ -0,0 +1,21 @@
+//! Local app usage metrics commands
+
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::AppUsage;
+use tauri::State;
+
+/// Get local app usage metrics in a time range
+#[tauri::command]
+pub fn get_app_usage(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<AppUsage, String> {
+    let RangeRequest { start, end } = request;
+    state.db.get_app_usage(start, end).map_err(|e| e.to_string())
+}
+
+/// Delete all local app usage metrics
+#[tauri::command]
+pub fn clear_app_usage(state: State<'_, AppState>) -> Result<usize, String> {
+    state.db.clear_app_metrics().map_err(|e| e.to_string())
+}
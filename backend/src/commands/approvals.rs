@@ -0,0 +1,86 @@
+//! Entry approval commands
+
+use crate::database::approvals::{APPROVAL_STATUS_APPROVED, APPROVAL_STATUS_REJECTED};
+use crate::database::{ApprovalSummary, EntryApproval};
+use crate::commands::common::{AppState, RangeRequest};
+use serde::Deserialize;
+use tauri::State;
+use time_tracker_plugin_sdk::validation;
+
+/// `submit_entries_for_approval` request; `project_id` limits it to one project
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SubmitForApprovalRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+}
+
+/// `approve_entries` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ApproveEntriesRequest {
+    #[serde(default)]
+    pub manual_entry_ids: Vec<i64>,
+    #[serde(default)]
+    pub activity_ids: Vec<i64>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// `reject_entries` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RejectEntriesRequest {
+    #[serde(default)]
+    pub manual_entry_ids: Vec<i64>,
+    #[serde(default)]
+    pub activity_ids: Vec<i64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Submit manual entries and activities in a time range for approval
+#[tauri::command]
+pub fn submit_entries_for_approval(
+    state: State<'_, AppState>,
+    request: SubmitForApprovalRequest,
+) -> Result<ApprovalSummary, String> {
+    validation::query_range(Some(request.start), Some(request.end))?;
+    state
+        .db
+        .submit_entries_for_approval(request.start, request.end, request.project_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Approve submitted entries
+#[tauri::command]
+pub fn approve_entries(state: State<'_, AppState>, request: ApproveEntriesRequest) -> Result<ApprovalSummary, String> {
+    state
+        .db
+        .review_entries(&request.manual_entry_ids, &request.activity_ids, APPROVAL_STATUS_APPROVED, request.note.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Reject submitted entries; rejected entries can be edited and resubmitted
+#[tauri::command]
+pub fn reject_entries(state: State<'_, AppState>, request: RejectEntriesRequest) -> Result<ApprovalSummary, String> {
+    state
+        .db
+        .review_entries(&request.manual_entry_ids, &request.activity_ids, APPROVAL_STATUS_REJECTED, request.reason.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Get approval states for entries in a time range
+#[tauri::command]
+pub fn get_entry_approvals(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<EntryApproval>, String> {
+    let RangeRequest { start, end } = request;
+    state
+        .db
+        .get_entry_approvals(start, end)
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,67 @@
+//! Focus-mode blocklist commands
+
+use crate::commands::common::AppState;
+use crate::database::blocklist::{BLOCKLIST_KIND_APP, BLOCKLIST_KIND_DOMAIN};
+use crate::database::BlocklistEntry;
+use crate::focus_blocker::FocusBlockingStatus;
+use serde::Deserialize;
+use tauri::State;
+
+/// Normalize a domain pattern (`https://www.example.com/path` -> `example.com`);
+/// anything that is not a plain host name is rejected
+pub(crate) fn normalize_domain(pattern: &str) -> Result<String, String> {
+    let without_scheme = pattern.split("://").last().unwrap_or(pattern);
+    let host = without_scheme.split(['/', ':', '?']).next().unwrap_or("");
+    let domain = host.trim_start_matches("www.").to_lowercase();
+    if domain.is_empty() {
+        return Err("Domain cannot be empty".to_string());
+    }
+    if !crate::focus_blocker::is_valid_domain(&domain) {
+        return Err(format!("Invalid domain: {}", pattern));
+    }
+    Ok(domain)
+}
+
+/// Get all blocklist entries
+#[tauri::command]
+pub fn get_blocklist(state: State<'_, AppState>) -> Result<Vec<BlocklistEntry>, String> {
+    state.db.get_blocklist().map_err(|e| e.to_string())
+}
+
+/// `add_blocklist_entry` request; `kind` is `app` or `domain`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct AddBlocklistEntryRequest {
+    pub kind: String,
+    pub pattern: String,
+}
+
+/// Add an app or domain to the blocklist
+#[tauri::command]
+pub fn add_blocklist_entry(
+    state: State<'_, AppState>,
+    request: AddBlocklistEntryRequest,
+) -> Result<i64, String> {
+    let AddBlocklistEntryRequest { kind, pattern } = request;
+    let pattern = match kind.as_str() {
+        BLOCKLIST_KIND_APP => pattern.trim().to_string(),
+        BLOCKLIST_KIND_DOMAIN => normalize_domain(pattern.trim())?,
+        _ => return Err(format!("Invalid blocklist kind: {}", kind)),
+    };
+    if pattern.is_empty() {
+        return Err("Pattern cannot be empty".to_string());
+    }
+    state.db.add_blocklist_entry(&kind, &pattern).map_err(|e| e.to_string())
+}
+
+/// Delete a blocklist entry
+#[tauri::command]
+pub fn delete_blocklist_entry(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.db.delete_blocklist_entry(id).map_err(|e| e.to_string())
+}
+
+/// Get whether focus blocking is currently active
+#[tauri::command]
+pub fn get_focus_blocking_status(state: State<'_, AppState>) -> Result<FocusBlockingStatus, String> {
+    Ok(state.focus_blocker.status())
+}
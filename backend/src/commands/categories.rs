@@ -1,49 +1,87 @@
 //! Category management commands
 
+use crate::colors::{self, ColorContrast};
 use crate::database::Category;
 use crate::commands::common::{AppState, CategoryResponse, i32_to_opt_bool};
+use crate::events::EVENT_ACTIVITIES_CHANGED;
+use serde::Deserialize;
 use tauri::State;
 
 /// Get all categories
 #[tauri::command]
 pub fn get_categories(state: State<'_, AppState>) -> Result<Vec<Category>, String> {
-    state.db.get_categories().map_err(|e: rusqlite::Error| e.to_string())
+    state.storage.get_categories().map_err(|e| e.to_string())
+}
+
+/// `create_category` request
+/// is_productive передаётся как i32 (-1 для null/neutral, 0 для false, 1 для true)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CreateCategoryRequest {
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub is_productive: i32,
+    pub sort_order: i64,
+    #[serde(default)]
+    pub is_system: Option<bool>,
+    #[serde(default)]
+    pub is_pinned: Option<bool>,
+    #[serde(default)]
+    pub exclude_from_export: Option<bool>,
+}
+
+/// `update_category` request; unset `is_pinned`/`exclude_from_export` keep their values
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateCategoryRequest {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub icon: Option<String>,
+    pub is_productive: i32,
+    pub sort_order: i64,
+    #[serde(default)]
+    pub is_pinned: Option<bool>,
+    #[serde(default)]
+    pub exclude_from_export: Option<bool>,
 }
 
 /// Create category
-/// Принимает is_productive как i32 (-1 для null/neutral, 0 для false, 1 для true)
-/// и конвертирует его в Option<bool> для избежания проблем с десериализацией Option<bool> в Tauri
-/// Tauri автоматически конвертирует camelCase (sortOrder) в snake_case (sort_order)
 #[tauri::command]
 pub fn create_category(
     state: State<'_, AppState>,
-    name: String,
-    color: String,
-    icon: Option<String>,
-    is_productive: i32,
-    sort_order: i64,
-    is_system: Option<bool>,
-    is_pinned: Option<bool>,
+    request: CreateCategoryRequest,
 ) -> Result<CategoryResponse, String> {
-    // Конвертируем числа в Option<bool>: 1 -> Some(true), 0 -> Some(false), -1 -> None
-    let is_productive_bool = if is_productive == -1 {
-        None
-    } else {
-        Some(is_productive == 1)
-    };
+    let CreateCategoryRequest {
+        name,
+        color,
+        icon,
+        is_productive,
+        sort_order,
+        is_system,
+        is_pinned,
+        exclude_from_export,
+    } = request;
+    let is_productive_bool = i32_to_opt_bool(is_productive);
     
     let is_system_bool = is_system.unwrap_or(false);
     let is_pinned_bool = is_pinned.unwrap_or(false);
     
     let id = state
-        .db
+        .storage
         .create_category_core(&name, &color, icon.as_deref(), is_productive_bool, sort_order, is_system_bool, is_pinned_bool)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
+    if exclude_from_export == Some(true) {
+        state.storage.set_category_exclude_from_export(id, true).map_err(|e| e.to_string())?;
+    }
     
     let category = state
-        .db
+        .storage
         .get_categories()
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|c| c.id == id)
         .ok_or_else(|| "Failed to retrieve created category".to_string())?;
@@ -52,36 +90,43 @@ pub fn create_category(
 }
 
 /// Update category
-/// Принимает is_productive как i32 (-1 для null/neutral, 0 для false, 1 для true)
-/// и конвертирует его в Option<bool> для избежания проблем с десериализацией Option<bool> в Tauri
-/// Tauri автоматически конвертирует camelCase (sortOrder) в snake_case (sort_order)
 #[tauri::command]
 pub fn update_category(
     state: State<'_, AppState>,
-    id: i64,
-    name: String,
-    color: String,
-    icon: Option<String>,
-    is_productive: i32,
-    sort_order: i64,
-    is_pinned: Option<bool>,
+    request: UpdateCategoryRequest,
 ) -> Result<CategoryResponse, String> {
+    let UpdateCategoryRequest {
+        id,
+        name,
+        color,
+        icon,
+        is_productive,
+        sort_order,
+        is_pinned,
+        exclude_from_export,
+    } = request;
     let is_productive_bool = i32_to_opt_bool(is_productive);
 
     let current_category = state
-        .db
+        .storage
         .get_categories()
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|c| c.id == id)
         .ok_or_else(|| "Category not found".to_string())?;
     
     let is_pinned_bool = is_pinned.unwrap_or(current_category.is_pinned);
+    let exclude_from_export = exclude_from_export.unwrap_or(current_category.exclude_from_export);
     
     state
-        .db
+        .storage
         .update_category_core(id, &name, &color, icon.as_deref(), is_productive_bool, sort_order, is_pinned_bool)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
+    if exclude_from_export != current_category.exclude_from_export {
+        state.storage.set_category_exclude_from_export(id, exclude_from_export).map_err(|e| e.to_string())?;
+    }
+    // Reports show category names, colors and productivity
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "category_id": id }));
     
     Ok(CategoryResponse {
         id,
@@ -92,27 +137,97 @@ pub fn update_category(
         sort_order,
         is_system: current_category.is_system,
         is_pinned: is_pinned_bool,
+        exclude_from_export,
+        is_archived: current_category.is_archived,
     })
 }
 
 /// Delete category
 #[tauri::command]
 pub fn delete_category(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.db.delete_category(id).map_err(|e| e.to_string())
+    state.storage.delete_category(id).map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "category_id": id }));
+    Ok(())
+}
+
+fn set_archived(state: &AppState, id: i64, archived: bool) -> Result<CategoryResponse, String> {
+    state.storage.set_category_archived(id, archived).map_err(|e| e.to_string())?;
+    state
+        .storage
+        .get_categories()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|c| c.id == id)
+        .map(CategoryResponse::from)
+        .ok_or_else(|| "Category not found".to_string())
+}
+
+/// Archive a category: it disappears from pickers and can't get new rules,
+/// but its activities, entries and rules stay as they are
+#[tauri::command]
+pub fn archive_category(state: State<'_, AppState>, id: i64) -> Result<CategoryResponse, String> {
+    set_archived(&state, id, true)
+}
+
+/// Restore an archived category
+#[tauri::command]
+pub fn unarchive_category(state: State<'_, AppState>, id: i64) -> Result<CategoryResponse, String> {
+    set_archived(&state, id, false)
 }
 
 /// Reset system category to default values
 #[tauri::command]
 pub fn reset_system_category(state: State<'_, AppState>, id: i64) -> Result<CategoryResponse, String> {
-    state.db.reset_system_category(id).map_err(|e| e.to_string())?;
+    state.storage.reset_system_category(id).map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "category_id": id }));
     
     let category = state
-        .db
+        .storage
         .get_categories()
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|c| c.id == id)
         .ok_or_else(|| "Category not found".to_string())?;
     
     Ok(CategoryResponse::from(category))
 }
+
+/// Suggest a readable color for a new category that differs from the ones in use
+#[tauri::command]
+pub fn suggest_category_color(state: State<'_, AppState>) -> Result<String, String> {
+    state.storage.suggest_category_color().map_err(|e| e.to_string())
+}
+
+/// Check a category color's contrast against the light and dark themes
+#[tauri::command]
+pub fn check_category_color(color: String) -> Result<ColorContrast, String> {
+    colors::check_contrast(&color).ok_or_else(|| format!("Invalid color: {} (expected #RRGGBB)", color))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_use_snake_case_fields() {
+        let request: CreateCategoryRequest = serde_json::from_value(serde_json::json!({
+            "name": "Deep work",
+            "color": "#3366CC",
+            "is_productive": 1,
+            "sort_order": 3,
+        }))
+        .unwrap();
+        assert_eq!((request.sort_order, request.is_pinned), (3, None));
+
+        // Misspelled or camelCase keys are errors instead of silently ignored options
+        let camel_case = serde_json::from_value::<UpdateCategoryRequest>(serde_json::json!({
+            "id": 1,
+            "name": "Deep work",
+            "color": "#3366CC",
+            "is_productive": 1,
+            "sort_order": 3,
+            "isPinned": true,
+        }));
+        assert!(camel_case.is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! Productivity check-in commands
+
+use crate::app_metrics;
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::{Checkin, CheckinStats};
+use chrono::{Local, Utc};
+use serde::Deserialize;
+use tauri::State;
+
+/// `submit_checkin` request; `energy` (1-5) is optional
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CheckinRequest {
+    pub focused: bool,
+    #[serde(default)]
+    pub energy: Option<i64>,
+}
+
+/// Answer a check-in prompt
+#[tauri::command]
+pub fn submit_checkin(state: State<'_, AppState>, request: CheckinRequest) -> Result<i64, String> {
+    let CheckinRequest { focused, energy } = request;
+    if let Some(energy) = energy {
+        if !(1..=5).contains(&energy) {
+            return Err("Energy must be between 1 and 5".to_string());
+        }
+    }
+    let id = state
+        .db
+        .insert_checkin(Utc::now().timestamp(), focused, energy)
+        .map_err(|e| e.to_string())?;
+    app_metrics::record(&state.db, app_metrics::METRIC_CHECKIN_ANSWERED);
+    Ok(id)
+}
+
+/// Get check-in answers in a time range
+#[tauri::command]
+pub fn get_checkins(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<Checkin>, String> {
+    let RangeRequest { start, end } = request;
+    state.db.get_checkins(start, end).map_err(|e| e.to_string())
+}
+
+/// Get check-in stats by local hour of day and by category
+#[tauri::command]
+pub fn get_checkin_stats(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<CheckinStats, String> {
+    let RangeRequest { start, end } = request;
+    let utc_offset_sec = Local::now().offset().local_minus_utc() as i64;
+    state
+        .db
+        .get_checkin_stats(start, end, utc_offset_sec)
+        .map_err(|e| e.to_string())
+}
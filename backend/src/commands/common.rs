@@ -1,14 +1,31 @@
 //! Common types and utilities for commands
 
-use crate::database::Category;
+use crate::database::{Category, ManualEntry};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::sync::Mutex;
+use crate::data_sources::DataSourceRegistry;
 use crate::database::Database;
+use crate::database::recovery::DatabaseRecovery;
+use crate::database::storage::Storage;
+use crate::events::{AppEvent, EventBus};
+use crate::focus_blocker::FocusBlocker;
+use crate::jobs::JobRunner;
+use crate::replay::ReplayRunner;
+use crate::report_cache::WeekCache;
+use crate::settings::SettingsService;
 use crate::tracker::Tracker;
-use crate::plugin_system::{PluginRegistry, ExtensionRegistry};
+use crate::plugin_system::{PluginRegistry, ExtensionRegistry, PluginIndex};
 use crate::plugin_system::loader::PluginLoader;
 
+/// Request of commands that take only a time range
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RangeRequest {
+    pub start: i64,
+    pub end: i64,
+}
+
 /// Category response from core commands
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CategoryResponse {
@@ -20,6 +37,8 @@ pub struct CategoryResponse {
     pub sort_order: i64,
     pub is_system: bool,
     pub is_pinned: bool,
+    pub exclude_from_export: bool,
+    pub is_archived: bool,
 }
 
 impl From<Category> for CategoryResponse {
@@ -33,6 +52,8 @@ impl From<Category> for CategoryResponse {
             sort_order: category.sort_order,
             is_system: category.is_system,
             is_pinned: category.is_pinned,
+            exclude_from_export: category.exclude_from_export,
+            is_archived: category.is_archived,
         }
     }
 }
@@ -46,12 +67,70 @@ pub fn i32_to_opt_bool(val: i32) -> Option<bool> {
     }
 }
 
+/// Reject development tools (replay, demo data) in release builds
+pub fn ensure_dev_build(feature: &str) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        Ok(())
+    } else {
+        Err(format!("{} is only available in development builds", feature))
+    }
+}
+
+/// Serialize records keeping only the requested top-level fields.
+/// `None` or an empty list keeps every field; unknown names are rejected.
+pub fn select_fields<T: Serialize>(
+    records: &[T],
+    fields: Option<&[String]>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut values = records
+        .iter()
+        .map(|r| serde_json::to_value(r).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let fields = match fields.filter(|f| !f.is_empty()) {
+        Some(fields) => fields,
+        None => return Ok(values),
+    };
+    if let Some(serde_json::Value::Object(first)) = values.first() {
+        if let Some(unknown) = fields.iter().find(|f| !first.contains_key(f.as_str())) {
+            return Err(format!("Unknown field: {}", unknown));
+        }
+    }
+    for value in &mut values {
+        if let serde_json::Value::Object(map) = value {
+            map.retain(|key, _| fields.iter().any(|f| f == key));
+        }
+    }
+    Ok(values)
+}
+
 /// Application state containing database reference
 pub struct AppState {
     pub db: Arc<Database>,
+    /// The same database behind the backend-neutral `Storage` interface
+    pub storage: Arc<dyn Storage>,
     pub tracker: Arc<Mutex<Option<Arc<Tracker>>>>,
     pub thinking_mode_entry_id: Arc<Mutex<Option<i64>>>,
+    /// Running manual entry recording why tracking is paused
+    pub pause_entry: Arc<Mutex<Option<ManualEntry>>>,
     pub plugin_registry: Option<Arc<PluginRegistry>>,
     pub extension_registry: Option<Arc<ExtensionRegistry>>,
     pub plugin_loader: Option<Arc<PluginLoader>>,
+    /// Cached marketplace metadata from the configured registries
+    pub plugin_index: Arc<PluginIndex>,
+    /// Current-week reports, invalidated by activity writes on `event_bus`
+    pub week_cache: Arc<WeekCache>,
+    /// Maintenance jobs (domain backfill, retention purges)
+    pub jobs: Arc<JobRunner>,
+    pub event_bus: Arc<EventBus>,
+    pub data_sources: Arc<DataSourceRegistry>,
+    pub focus_blocker: Arc<FocusBlocker>,
+    /// Latest `pomodoro.*` event from the pomodoro plugin (for the mini timer)
+    pub pomodoro_status: Arc<Mutex<Option<AppEvent>>>,
+    /// Validated settings access with change notifications
+    pub settings: Arc<SettingsService>,
+    /// Set when the database was corrupted and had to be salvaged at startup
+    pub startup_recovery: Option<DatabaseRecovery>,
+    /// Recorded sample replay (development builds)
+    pub replay: Arc<ReplayRunner>,
 }
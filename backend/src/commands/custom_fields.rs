@@ -0,0 +1,153 @@
+//! Custom field commands
+
+use crate::database::{CustomFieldDef, CustomFieldValue};
+use crate::commands::common::AppState;
+use serde::Deserialize;
+use tauri::State;
+
+/// `create_custom_field_def` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CreateCustomFieldDefRequest {
+    pub entity_type: String,
+    pub name: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
+    #[serde(default)]
+    pub sort_order: Option<i64>,
+}
+
+/// `update_custom_field_def` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateCustomFieldDefRequest {
+    pub id: i64,
+    pub name: String,
+    pub field_type: String,
+    #[serde(default)]
+    pub options: Option<Vec<String>>,
+    #[serde(default)]
+    pub sort_order: Option<i64>,
+}
+
+/// `get_custom_field_defs` request; without `entity_type` all are returned
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CustomFieldDefsRequest {
+    #[serde(default)]
+    pub entity_type: Option<String>,
+}
+
+/// `get_custom_field_values` request; without `entity_id` all entities are returned
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CustomFieldValuesRequest {
+    pub entity_type: String,
+    #[serde(default)]
+    pub entity_id: Option<i64>,
+}
+
+/// `set_custom_field_value` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SetCustomFieldValueRequest {
+    pub field_id: i64,
+    pub entity_id: i64,
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+/// Get custom field definitions, optionally for a single entity type
+#[tauri::command]
+pub fn get_custom_field_defs(
+    state: State<'_, AppState>,
+    request: CustomFieldDefsRequest,
+) -> Result<Vec<CustomFieldDef>, String> {
+    state
+        .db
+        .get_custom_field_defs(request.entity_type.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Create custom field definition
+#[tauri::command]
+pub fn create_custom_field_def(
+    state: State<'_, AppState>,
+    request: CreateCustomFieldDefRequest,
+) -> Result<CustomFieldDef, String> {
+    let entity_type = request.entity_type;
+    let id = state
+        .db
+        .create_custom_field_def(
+            &entity_type,
+            request.name.trim(),
+            &request.field_type,
+            &request.options.unwrap_or_default(),
+            request.sort_order.unwrap_or(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    state
+        .db
+        .get_custom_field_defs(Some(&entity_type))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| "Failed to retrieve created custom field".to_string())
+}
+
+/// Update custom field definition; values that no longer fit a changed type
+/// or option list are cleared
+#[tauri::command]
+pub fn update_custom_field_def(
+    state: State<'_, AppState>,
+    request: UpdateCustomFieldDefRequest,
+) -> Result<CustomFieldDef, String> {
+    let id = request.id;
+    state
+        .db
+        .update_custom_field_def(
+            id,
+            request.name.trim(),
+            &request.field_type,
+            &request.options.unwrap_or_default(),
+            request.sort_order.unwrap_or(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    state
+        .db
+        .get_custom_field_defs(None)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .find(|d| d.id == id)
+        .ok_or_else(|| "Failed to retrieve updated custom field".to_string())
+}
+
+/// Delete custom field definition and its values
+#[tauri::command]
+pub fn delete_custom_field_def(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.db.delete_custom_field_def(id).map_err(|e| e.to_string())
+}
+
+/// Get custom field values for an entity type, optionally for a single entity
+#[tauri::command]
+pub fn get_custom_field_values(
+    state: State<'_, AppState>,
+    request: CustomFieldValuesRequest,
+) -> Result<Vec<CustomFieldValue>, String> {
+    state
+        .db
+        .get_custom_field_values(&request.entity_type, request.entity_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Set custom field value; an empty or missing value clears it
+#[tauri::command]
+pub fn set_custom_field_value(state: State<'_, AppState>, request: SetCustomFieldValueRequest) -> Result<(), String> {
+    state
+        .db
+        .set_custom_field_value(request.field_id, request.entity_id, request.value.as_deref())
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,79 @@
+//! Daily plan commands
+//!
+//! Days are given as a timestamp anywhere in the local day, like `get_dashboard`.
+
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::{DailyPlan, DailyPlanItem, DailyPlanReview};
+use chrono::{Days, Local, NaiveDate, TimeZone};
+use serde::Deserialize;
+use tauri::State;
+use time_tracker_plugin_sdk::validation;
+
+/// Local date of `date` and the bounds of that day
+fn local_day(date: i64) -> Result<(NaiveDate, i64, i64), String> {
+    let day = Local.timestamp_opt(date, 0).single().ok_or("Invalid date")?.date_naive();
+    let midnight = |day: NaiveDate| {
+        Local
+            .from_local_datetime(&day.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|t| t.timestamp())
+    };
+    let next = day + Days::new(1);
+    Ok((day, midnight(day).ok_or("Invalid date")?, midnight(next).ok_or("Invalid date")?))
+}
+
+fn day_key(day: NaiveDate) -> String {
+    day.format("%Y-%m-%d").to_string()
+}
+
+/// Get the plan of a day
+#[tauri::command]
+pub fn get_daily_plan(state: State<'_, AppState>, date: i64) -> Result<Option<DailyPlan>, String> {
+    let (day, _, _) = local_day(date)?;
+    state.db.get_daily_plan(&day_key(day)).map_err(|e| e.to_string())
+}
+
+/// Get the plans of the days from `start` to `end`
+#[tauri::command]
+pub fn get_daily_plans(state: State<'_, AppState>, request: RangeRequest) -> Result<Vec<DailyPlan>, String> {
+    let RangeRequest { start, end } = request;
+    validation::query_range(Some(start), Some(end))?;
+    let ((first, _, _), (last, _, _)) = (local_day(start)?, local_day(end)?);
+    state.db.get_daily_plans(&day_key(first), &day_key(last)).map_err(|e| e.to_string())
+}
+
+/// `save_daily_plan` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SaveDailyPlanRequest {
+    pub date: i64,
+    pub items: Vec<DailyPlanItem>,
+}
+
+/// Create or replace the plan of a day; items keep the given order
+#[tauri::command]
+pub fn save_daily_plan(state: State<'_, AppState>, request: SaveDailyPlanRequest) -> Result<DailyPlan, String> {
+    let SaveDailyPlanRequest { date, mut items } = request;
+    for item in items.iter_mut() {
+        item.title = validation::required("title", Some(&item.title))?.to_string();
+        validation::in_range("target_minutes", item.target_minutes, 1, 1440)?;
+    }
+    let (day, _, _) = local_day(date)?;
+    state.db.save_daily_plan(&day_key(day), &items).map_err(|e| e.to_string())
+}
+
+/// Delete the plan of a day
+#[tauri::command]
+pub fn delete_daily_plan(state: State<'_, AppState>, date: i64) -> Result<(), String> {
+    let (day, _, _) = local_day(date)?;
+    state.db.delete_daily_plan(&day_key(day)).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Compare the plan of a day with the time tracked that day: target and
+/// actual time per item. `None` if the day has no plan.
+#[tauri::command]
+pub fn get_daily_plan_review(state: State<'_, AppState>, date: i64) -> Result<Option<DailyPlanReview>, String> {
+    let (day, start, end) = local_day(date)?;
+    state.db.get_daily_plan_review(&day_key(day), start, end).map_err(|e| e.to_string())
+}
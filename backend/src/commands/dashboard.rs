@@ -0,0 +1,149 @@
+//! Dashboard commands
+//!
+//! `get_dashboard` computes the dashboard's KPI cards in one call. Core
+//! computes the time-based cards; plugins contribute theirs (top project,
+//! pomodoros, goals) through their `dashboard_kpis` command. Which cards are
+//! shown, and in what order, is stored in the `dashboard_kpis` setting.
+
+use crate::commands::common::AppState;
+use crate::commands::plugins::invoke_plugin_command_with_api;
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+pub const KPI_TOTAL_TIME: &str = "total_time";
+pub const KPI_PRODUCTIVE_TIME: &str = "productive_time";
+pub const KPI_PRODUCTIVE_PERCENT: &str = "productive_percent";
+pub const KPI_TOP_CATEGORY: &str = "top_category";
+pub const KPI_TOP_APP: &str = "top_app";
+
+/// Cards shown when `dashboard_kpis` is not set; plugin cards are skipped
+/// when no plugin provides them
+const DEFAULT_DASHBOARD_KPIS: &[&str] = &[
+    KPI_TOTAL_TIME,
+    KPI_PRODUCTIVE_PERCENT,
+    "top_project",
+    "pomodoros_done",
+    "goal_nearest_completion",
+];
+
+/// Plugin command that returns a plugin's KPI cards for `{date, start, end}`
+const PLUGIN_KPI_COMMAND: &str = "dashboard_kpis";
+
+/// One KPI card
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardKpi {
+    pub id: String,
+    pub label: String,
+    /// A number (for `seconds`, `percent` and `count`) or text
+    pub value: serde_json::Value,
+    /// `seconds`, `percent`, `count` or `text`
+    pub unit: String,
+    #[serde(default)]
+    pub detail: Option<String>,
+    /// `core` or the ID of the plugin that computed the card
+    #[serde(default)]
+    pub source: String,
+}
+
+/// KPI cards for one day
+#[derive(Debug, Clone, Serialize)]
+pub struct Dashboard {
+    pub date: i64,
+    pub kpis: Vec<DashboardKpi>,
+    /// Every card that can be configured, whether shown or not
+    pub available: Vec<String>,
+}
+
+fn core_kpi(id: &str, label: &str, value: serde_json::Value, unit: &str, detail: Option<String>) -> DashboardKpi {
+    DashboardKpi {
+        id: id.to_string(),
+        label: label.to_string(),
+        value,
+        unit: unit.to_string(),
+        detail,
+        source: "core".to_string(),
+    }
+}
+
+fn configured_kpis(state: &AppState) -> Vec<String> {
+    state
+        .settings
+        .get()
+        .dashboard_kpis
+        .unwrap_or_else(|| DEFAULT_DASHBOARD_KPIS.iter().map(|id| id.to_string()).collect())
+}
+
+/// Cards from every loaded plugin that implements `dashboard_kpis`
+pub(crate) fn plugin_kpis(state: &AppState, date: i64) -> Vec<DashboardKpi> {
+    let Some(registry) = &state.plugin_registry else {
+        return Vec::new();
+    };
+    let params = serde_json::json!({ "date": date, "start": date, "end": date + 86400 });
+    let mut kpis = Vec::new();
+    for plugin_id in registry.get_plugin_ids() {
+        // Plugins without the command return an error; they simply have no cards
+        let Ok(value) = invoke_plugin_command_with_api(state, &plugin_id, PLUGIN_KPI_COMMAND, params.clone()) else {
+            continue;
+        };
+        match serde_json::from_value::<Vec<DashboardKpi>>(value) {
+            Ok(cards) => kpis.extend(cards.into_iter().map(|card| DashboardKpi {
+                source: plugin_id.clone(),
+                ..card
+            })),
+            Err(e) => eprintln!("Warning: Plugin {} returned invalid dashboard KPIs: {}", plugin_id, e),
+        }
+    }
+    kpis
+}
+
+/// Get the configured KPI cards for the day starting at `date`
+#[tauri::command]
+pub fn get_dashboard(state: State<'_, AppState>, date: i64) -> Result<Dashboard, String> {
+    let stats = state.db.get_stats_for_range(date, date + 86400, None, None, false).map_err(|e| e.to_string())?;
+    let productive_percent = if stats.total_seconds > 0 {
+        (stats.productive_seconds as f64 / stats.total_seconds as f64 * 100.0).round()
+    } else {
+        0.0
+    };
+
+    let mut all = vec![
+        core_kpi(KPI_TOTAL_TIME, "Total Time", stats.total_seconds.into(), "seconds", None),
+        core_kpi(KPI_PRODUCTIVE_TIME, "Productive", stats.productive_seconds.into(), "seconds", None),
+        core_kpi(KPI_PRODUCTIVE_PERCENT, "Productivity", productive_percent.into(), "percent", None),
+    ];
+    if let Some((_, name, _, seconds)) = stats.category_breakdown.first() {
+        all.push(core_kpi(KPI_TOP_CATEGORY, "Top Category", name.clone().into(), "text", Some(seconds.to_string())));
+    }
+    if let Some((name, seconds)) = stats.app_breakdown.first() {
+        all.push(core_kpi(KPI_TOP_APP, "Top App", name.clone().into(), "text", Some(seconds.to_string())));
+    }
+    all.extend(plugin_kpis(&state, date));
+
+    let available: Vec<String> = all.iter().map(|kpi| kpi.id.clone()).collect();
+    let selected = configured_kpis(&state);
+    let kpis = selected
+        .iter()
+        .filter_map(|id| all.iter().find(|kpi| &kpi.id == id).cloned())
+        .collect();
+
+    Ok(Dashboard { date, kpis, available })
+}
+
+/// Get the KPI card IDs shown on the dashboard, in order
+#[tauri::command]
+pub fn get_dashboard_kpis(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    Ok(configured_kpis(&state))
+}
+
+/// Set the KPI card IDs shown on the dashboard, in order
+#[tauri::command]
+pub fn set_dashboard_kpis(state: State<'_, AppState>, kpis: Vec<String>) -> Result<(), String> {
+    let mut ids: Vec<String> = Vec::new();
+    for id in kpis.iter().map(|id| id.trim()).filter(|id| !id.is_empty()) {
+        if !ids.iter().any(|existing| existing == id) {
+            ids.push(id.to_string());
+        }
+    }
+    let json = serde_json::to_string(&ids).map_err(|e| e.to_string())?;
+    state.settings.set("dashboard_kpis", &json)
+}
@@ -0,0 +1,68 @@
+//! Demo data commands (development builds only)
+
+use crate::commands::common::{ensure_dev_build, AppState};
+use crate::commands::plugins::invoke_plugin_command_with_api;
+use crate::database::demo_data::DemoDataSummary;
+use crate::demo_data::{self, DemoDay, DemoProfile};
+use crate::events::EVENT_ACTIVITIES_CHANGED;
+use chrono::{Datelike, Duration, Local, Weekday};
+use serde::Deserialize;
+use tauri::State;
+
+/// Plugin command that adds a plugin's own demo rows for `{days, profile, start, end}`
+const PLUGIN_DEMO_DATA_COMMAND: &str = "generate_demo_data";
+
+const MAX_DEMO_DAYS: i64 = 365;
+
+/// `generate_demo_data` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct DemoDataRequest {
+    pub days: i64,
+    pub profile: DemoProfile,
+}
+
+/// Replace earlier demo data with `days` made-up days (ending today) for a
+/// `developer`, `designer` or `manager`. Loaded plugins that implement
+/// `generate_demo_data` add their own rows (projects, focus sessions, ...).
+#[tauri::command]
+pub fn generate_demo_data(state: State<'_, AppState>, request: DemoDataRequest) -> Result<DemoDataSummary, String> {
+    let DemoDataRequest { days, profile } = request;
+    ensure_dev_build("Demo data")?;
+    if !(1..=MAX_DEMO_DAYS).contains(&days) {
+        return Err(format!("Days must be between 1 and {}", MAX_DEMO_DAYS));
+    }
+
+    let today = Local::now().date_naive();
+    let demo_days: Vec<DemoDay> = (0..days)
+        .rev()
+        .filter_map(|ago| {
+            let date = today - Duration::days(ago);
+            let start = date.and_hms_opt(0, 0, 0)?.and_local_timezone(Local).earliest()?.timestamp();
+            Some(DemoDay {
+                start,
+                weekend: matches!(date.weekday(), Weekday::Sat | Weekday::Sun),
+            })
+        })
+        .collect();
+    let (Some(first), Some(last)) = (demo_days.first(), demo_days.last()) else {
+        return Err("No days to generate".to_string());
+    };
+    let (start, end) = (first.start, last.start + 86400);
+
+    let data = demo_data::generate(profile, &demo_days, days as u64);
+    let mut summary = state.db.replace_demo_data(&data).map_err(|e| e.to_string())?;
+
+    if let Some(registry) = &state.plugin_registry {
+        let params = serde_json::json!({ "days": days, "profile": profile, "start": start, "end": end });
+        for plugin_id in registry.get_plugin_ids() {
+            // Plugins without the command return an error; they simply have no demo data
+            if invoke_plugin_command_with_api(&state, &plugin_id, PLUGIN_DEMO_DATA_COMMAND, params.clone()).is_ok() {
+                summary.plugins.push(plugin_id);
+            }
+        }
+        summary.plugins.sort();
+    }
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "demo_days": days }));
+    Ok(summary)
+}
@@ -0,0 +1,34 @@
+//! Diagnostics commands
+
+use crate::commands::common::AppState;
+use crate::database::recovery::DatabaseRecovery;
+use crate::database::slow_queries::SlowQuery;
+use serde::Serialize;
+use tauri::State;
+
+/// Troubleshooting information about the local database
+#[derive(Serialize)]
+pub struct Diagnostics {
+    /// Outcome of the startup database recovery, if one was needed
+    pub startup_recovery: Option<DatabaseRecovery>,
+    /// Slow statement threshold (`slow_query_log_ms`); 0 while logging is off
+    pub slow_query_log_ms: i64,
+    /// Statements slower than the threshold, slowest first
+    pub slow_queries: Vec<SlowQuery>,
+}
+
+/// Get the outcome of the database recovery run at startup, if one was needed
+#[tauri::command]
+pub fn get_startup_diagnostics(state: State<'_, AppState>) -> Result<Option<DatabaseRecovery>, String> {
+    Ok(state.startup_recovery.clone())
+}
+
+/// Get database diagnostics, including logged slow queries with their query plans
+#[tauri::command]
+pub fn get_diagnostics(state: State<'_, AppState>) -> Result<Diagnostics, String> {
+    Ok(Diagnostics {
+        startup_recovery: state.startup_recovery.clone(),
+        slow_query_log_ms: state.settings.get().slow_query_log_ms,
+        slow_queries: state.db.get_slow_queries(),
+    })
+}
@@ -1,19 +1,94 @@
-//! Domain statistics commands
+//! Domain and document statistics commands
 
-use crate::commands::common::AppState;
-use crate::database::DomainStat;
+use crate::commands::blocklist::normalize_domain;
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::domain_ratings::{
+    DOMAIN_RATING_DISTRACTING, DOMAIN_RATING_NEUTRAL, DOMAIN_RATING_PRODUCTIVE,
+};
+use crate::database::{ContextStat, DomainRating, DomainStat, Job};
+use crate::jobs::JOB_DOMAIN_BACKFILL;
+use serde::Deserialize;
 use tauri::State;
 
+/// `get_top_domains` and `get_top_contexts` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct TopListRequest {
+    pub start: i64,
+    pub end: i64,
+    pub limit: i64,
+}
+
 /// Get top domains for a time range
 #[tauri::command]
 pub fn get_top_domains(
     state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-    limit: i64,
+    request: TopListRequest,
 ) -> Result<Vec<DomainStat>, String> {
+    let TopListRequest { start, end, limit } = request;
     state
         .db
         .get_top_domains(start, end, limit)
         .map_err(|e| e.to_string())
 }
+
+/// Get the documents, files and URLs with the most time for a time range
+/// (activities recorded while `capture_context_ref` was enabled)
+#[tauri::command]
+pub fn get_top_contexts(
+    state: State<'_, AppState>,
+    request: TopListRequest,
+) -> Result<Vec<ContextStat>, String> {
+    let TopListRequest { start, end, limit } = request;
+    state
+        .db
+        .get_top_contexts(start, end, limit)
+        .map_err(|e| e.to_string())
+}
+
+/// Rebuild missing domains of activities in a time range (rows recorded
+/// before domains were extracted) from their window titles, as a background
+/// job (see `list_jobs`). Domain rules apply to the filled in rows after
+/// `reapply_categorization_rules`.
+#[tauri::command]
+pub fn backfill_domains(state: State<'_, AppState>, request: RangeRequest) -> Result<Job, String> {
+    let RangeRequest { start, end } = request;
+    state
+        .jobs
+        .submit(JOB_DOMAIN_BACKFILL, serde_json::json!({ "start": start, "end": end }))
+}
+
+/// Get all domain productivity ratings
+#[tauri::command]
+pub fn get_domain_ratings(state: State<'_, AppState>) -> Result<Vec<DomainRating>, String> {
+    state.db.get_domain_ratings().map_err(|e| e.to_string())
+}
+
+/// `set_domain_rating` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct DomainRatingRequest {
+    pub domain: String,
+    pub rating: String,
+}
+
+/// Rate a domain `productive`, `neutral` or `distracting`. The rating applies
+/// to activities on the domain and its subdomains whose category is neutral.
+#[tauri::command]
+pub fn set_domain_rating(
+    state: State<'_, AppState>,
+    request: DomainRatingRequest,
+) -> Result<(), String> {
+    let DomainRatingRequest { domain, rating } = request;
+    if ![DOMAIN_RATING_PRODUCTIVE, DOMAIN_RATING_NEUTRAL, DOMAIN_RATING_DISTRACTING].contains(&rating.as_str()) {
+        return Err(format!("Invalid domain rating: {}", rating));
+    }
+    let domain = normalize_domain(domain.trim())?;
+    state.db.set_domain_rating(&domain, &rating).map_err(|e| e.to_string())
+}
+
+/// Remove a domain's rating
+#[tauri::command]
+pub fn delete_domain_rating(state: State<'_, AppState>, domain: String) -> Result<(), String> {
+    state.db.delete_domain_rating(&domain).map_err(|e| e.to_string())
+}
@@ -1,31 +1,141 @@
 //! Export commands
 
 use crate::commands::common::AppState;
-use chrono::{Utc, TimeZone};
+use crate::commands::dashboard::plugin_kpis;
+use crate::daily_note::{self, DailyNoteData};
+use crate::export_archive;
+use crate::database::anonymize::AnonymizeSummary;
+use crate::database::{Activity, CustomFieldDef};
+use crate::importers;
+use crate::parquet_export;
+use crate::report;
+use crate::templates::{self, TemplateInfo};
+use chrono::{Local, NaiveDate, Utc, TimeZone};
+use serde::{Deserialize, Serialize};
 use tauri::State;
-use std::fs::File;
-use std::io::Write;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
-/// Export to CSV
-#[tauri::command]
-pub fn export_to_csv(
-    state: State<'_, AppState>,
+/// Custom field values keyed by (field_id, entity_id)
+type CustomFieldValueMap = HashMap<(i64, i64), String>;
+
+/// Activity custom field definitions and their values
+fn activity_custom_fields(state: &AppState) -> Result<(Vec<CustomFieldDef>, CustomFieldValueMap), String> {
+    let defs = state.db.get_custom_field_defs(Some("activity")).map_err(|e| e.to_string())?;
+    let values = state
+        .db
+        .get_custom_field_values("activity", None)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|v| ((v.field_id, v.entity_id), v.value))
+        .collect();
+    Ok((defs, values))
+}
+
+/// Passphrase an export is encrypted with: the request's, else the
+/// `export_passphrase` setting; `None` writes the export unencrypted
+fn export_passphrase(state: &AppState, passphrase: Option<String>) -> Result<Option<String>, String> {
+    let passphrase = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => Some(passphrase),
+        None => state.settings.get_secret(export_archive::PASSPHRASE_SETTING)?,
+    };
+    if let Some(passphrase) = &passphrase {
+        export_archive::validate_passphrase(passphrase)?;
+    }
+    Ok(passphrase)
+}
+
+/// `export_to_csv`, `export_to_json` and `export_to_activitywatch` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ExportRequest {
+    pub start: i64,
+    pub end: i64,
+    pub file_path: String,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// `export_parquet` request; `path` is the directory written to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ParquetExportRequest {
+    pub start: i64,
+    pub end: i64,
+    pub path: String,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub passphrase: Option<String>,
+}
+
+/// `write_daily_note` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct DailyNoteRequest {
+    pub date: i64,
+    #[serde(default)]
+    pub path_template: Option<String>,
+}
+
+/// `publish_report` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct PublishReportRequest {
+    pub start: i64,
+    pub end: i64,
+    /// `full` (default) or `client`
+    #[serde(default)]
+    pub profile: Option<String>,
+    pub file_path: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+}
+
+/// Activities that may leave the machine: categories and projects marked
+/// `exclude_from_export` are dropped
+fn exportable_activities(
+    state: &AppState,
     start: i64,
     end: i64,
-    file_path: String,
-) -> Result<(), String> {
-    let activities = state.db.get_activities(start, end, None, None, None, None).map_err(|e| e.to_string())?;
+    exclude_idle: Option<bool>,
+    sources: Option<&[String]>,
+) -> Result<Vec<Activity>, String> {
+    let excluded = state.db.get_export_excluded_activity_ids(start, end).map_err(|e| e.to_string())?;
+    let mut activities = state
+        .db
+        .get_activities(start, end, None, None, exclude_idle, None, sources)
+        .map_err(|e| e.to_string())?;
+    activities.retain(|activity| !excluded.contains(&activity.id));
+    Ok(activities)
+}
+
+/// Export to CSV, optionally only activities from the given sources. Custom
+/// fields follow the core columns as `custom:<name>`. With a passphrase (or
+/// the `export_passphrase` setting) the file is an encrypted zip archive
+/// named `<file_path>.zip`. Returns the path written.
+#[tauri::command]
+pub fn export_to_csv(state: State<'_, AppState>, request: ExportRequest) -> Result<String, String> {
+    let ExportRequest { start, end, file_path, sources, passphrase } = request;
+    let passphrase = export_passphrase(&state, passphrase)?;
+    let activities = exportable_activities(&state, start, end, None, sources.as_deref())?;
     let categories = state.db.get_categories().map_err(|e| e.to_string())?;
+    let (field_defs, field_values) = activity_custom_fields(&state)?;
     
-    let mut file = File::create(&file_path)
-        .map_err(|e| format!("Failed to create CSV file: {}", e))?;
-    
-    file.write_all(&[0xEF, 0xBB, 0xBF])
-        .map_err(|e| format!("Failed to write UTF-8 BOM: {}", e))?;
-    
-    let mut wtr = csv::Writer::from_writer(file);
+    // UTF-8 BOM, so spreadsheet apps detect the encoding
+    let mut wtr = csv::Writer::from_writer(vec![0xEF, 0xBB, 0xBF]);
     
-    wtr.write_record(&["id", "app_name", "window_title", "category", "started_at", "duration", "is_idle"])
+    let mut header: Vec<String> = ["id", "app_name", "window_title", "category", "started_at", "duration", "is_idle", "source"]
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    // Prefixed, so a field named like a core column (e.g. "source") stays apart
+    header.extend(field_defs.iter().map(|d| format!("custom:{}", d.name)));
+    wtr.write_record(&header)
         .map_err(|e| format!("Failed to write CSV header: {}", e))?;
     
     for activity in &activities {
@@ -44,7 +154,7 @@ pub fn export_to_csv(
         let seconds = activity.duration_sec % 60;
         let duration_formatted = format!("{:02}:{:02}:{:02}", hours, minutes, seconds);
         
-        wtr.write_record(&[
+        let mut record = vec![
             activity.id.to_string(),
             activity.app_name.clone(),
             activity.window_title.clone().unwrap_or_else(|| "".to_string()),
@@ -52,28 +162,255 @@ pub fn export_to_csv(
             started_at_formatted,
             duration_formatted,
             activity.is_idle.to_string(),
-        ]).map_err(|e| format!("Failed to write CSV row: {}", e))?;
+            activity.source.clone(),
+        ];
+        record.extend(field_defs.iter().map(|d| {
+            field_values.get(&(d.id, activity.id)).cloned().unwrap_or_default()
+        }));
+        wtr.write_record(&record).map_err(|e| format!("Failed to write CSV row: {}", e))?;
     }
     
-    wtr.flush().map_err(|e| format!("Failed to flush CSV: {}", e))?;
-    Ok(())
+    let csv = wtr.into_inner().map_err(|e| format!("Failed to flush CSV: {}", e))?;
+    let written = export_archive::write(Path::new(&file_path), &csv, passphrase.as_deref(), "activities.csv")?;
+    Ok(written.to_string_lossy().to_string())
 }
 
-/// Export to JSON
+/// Export to JSON, optionally only activities from the given sources, and
+/// encrypted like `export_to_csv`
 #[tauri::command]
-pub fn export_to_json(
-    state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-    file_path: String,
-) -> Result<(), String> {
-    let activities = state.db.get_activities(start, end, None, None, None, None).map_err(|e| e.to_string())?;
-    
-    let json = serde_json::to_string_pretty(&activities)
+pub fn export_to_json(state: State<'_, AppState>, request: ExportRequest) -> Result<String, String> {
+    let ExportRequest { start, end, file_path, sources, passphrase } = request;
+    let passphrase = export_passphrase(&state, passphrase)?;
+    let activities = exportable_activities(&state, start, end, None, sources.as_deref())?;
+    let (field_defs, field_values) = activity_custom_fields(&state)?;
+
+    let mut activities_json = serde_json::to_value(&activities)
         .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    if !field_defs.is_empty() {
+        if let Some(items) = activities_json.as_array_mut() {
+            for (item, activity) in items.iter_mut().zip(&activities) {
+                let custom_fields: serde_json::Map<String, serde_json::Value> = field_defs
+                    .iter()
+                    .filter_map(|d| {
+                        field_values
+                            .get(&(d.id, activity.id))
+                            .map(|v| (d.name.clone(), serde_json::Value::String(v.clone())))
+                    })
+                    .collect();
+                item["custom_fields"] = serde_json::Value::Object(custom_fields);
+            }
+        }
+    }
     
-    std::fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write JSON file: {}", e))?;
-    
+    let json = serde_json::to_string_pretty(&activities_json)
+        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    let written = export_archive::write(Path::new(&file_path), json.as_bytes(), passphrase.as_deref(), "activities.json")?;
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Export to ActivityWatch's bucket/event JSON format (importable in
+/// ActivityWatch), encrypted like `export_to_csv`
+#[tauri::command]
+pub fn export_to_activitywatch(state: State<'_, AppState>, request: ExportRequest) -> Result<String, String> {
+    let ExportRequest { start, end, file_path, sources, passphrase } = request;
+    let passphrase = export_passphrase(&state, passphrase)?;
+    let activities = exportable_activities(&state, start, end, None, sources.as_deref())?;
+    let hostname = state.db.device().map_or_else(crate::device::hostname, |device| device.name);
+    let export = importers::activitywatch::export(&activities, &hostname);
+
+    let json = serde_json::to_string_pretty(&export)
+        .map_err(|e| format!("Failed to serialize JSON: {}", e))?;
+    let written = export_archive::write(Path::new(&file_path), json.as_bytes(), passphrase.as_deref(), "activitywatch.json")?;
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Files written by `export_parquet`
+#[derive(Debug, Clone, Serialize)]
+pub struct ParquetExport {
+    pub activities_path: String,
+    pub activities: usize,
+    pub manual_entries_path: String,
+    pub manual_entries: usize,
+}
+
+/// Export activities and manual entries of a range as Parquet files
+/// (`activities.parquet`, `manual_entries.parquet`) into the directory `path`,
+/// for analysis in DuckDB, pandas or polars. Categories excluded from export
+/// are left out, and files are encrypted, as in the other formats.
+#[tauri::command]
+pub fn export_parquet(state: State<'_, AppState>, request: ParquetExportRequest) -> Result<ParquetExport, String> {
+    let ParquetExportRequest { start, end, path, sources, passphrase } = request;
+    let passphrase = export_passphrase(&state, passphrase)?;
+    let directory = std::path::Path::new(&path);
+    std::fs::create_dir_all(directory).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let activities = exportable_activities(&state, start, end, None, sources.as_deref())?;
+    let categories = state.db.get_categories().map_err(|e| e.to_string())?;
+    let mut entries = state.db.get_manual_entries(start, end).map_err(|e| e.to_string())?;
+    entries.retain(|entry| {
+        !entry
+            .category_id
+            .and_then(|id| categories.iter().find(|c| c.id == id))
+            .is_some_and(|c| c.exclude_from_export)
+    });
+
+    let activities_path = directory.join(parquet_export::ACTIVITIES_FILE);
+    let manual_entries_path = directory.join(parquet_export::MANUAL_ENTRIES_FILE);
+    let passphrase = passphrase.as_deref();
+    let written = |path: &Path| export_archive::output_path(path, passphrase.is_some()).to_string_lossy().to_string();
+    Ok(ParquetExport {
+        activities: parquet_export::write_activities(&activities_path, passphrase, &activities, &categories)?,
+        activities_path: written(&activities_path),
+        manual_entries: parquet_export::write_manual_entries(&manual_entries_path, passphrase, &entries, &categories)?,
+        manual_entries_path: written(&manual_entries_path),
+    })
+}
+
+/// Write the summary of the day starting at `date` into its daily note.
+/// `path_template` (strftime, e.g. `~/Vault/Daily/%Y-%m-%d.md`) defaults to
+/// the `daily_note_path` setting; returns the path written.
+#[tauri::command]
+pub fn write_daily_note(state: State<'_, AppState>, request: DailyNoteRequest) -> Result<String, String> {
+    write_daily_note_for(&state, request.date, request.path_template)
+}
+
+/// `write_daily_note` for callers outside a command (the end-of-day writer)
+pub fn write_daily_note_for(state: &AppState, date: i64, path_template: Option<String>) -> Result<String, String> {
+    let path_template = match path_template {
+        Some(path_template) => Some(path_template),
+        None => state.settings.get().daily_note_path,
+    }
+    .filter(|p| !p.trim().is_empty())
+    .ok_or("No daily note path set (daily_note_path)")?;
+
+    let end = date + 86400;
+    // The note leaves the app (vaults are often synced), so excluded categories and projects stay out
+    let stats = state.db.get_stats_for_range(date, end, None, None, true).map_err(|e| e.to_string())?;
+    let day = Local.timestamp_opt(date, 0).single().ok_or("Invalid date")?.date_naive();
+    let data = DailyNoteData {
+        date: day.format("%Y-%m-%d").to_string(),
+        total_seconds: stats.total_seconds,
+        productive_seconds: stats.productive_seconds,
+        categories: stats
+            .category_breakdown
+            .into_iter()
+            .map(|(_, name, _, seconds)| (name, seconds))
+            .collect(),
+        projects: state.db.get_project_breakdown(date, end, true).map_err(|e| e.to_string())?,
+        pomodoros: plugin_kpis(state, date)
+            .into_iter()
+            .find(|kpi| kpi.id == "pomodoros_done")
+            .and_then(|kpi| kpi.value.as_i64()),
+        notes: state
+            .db
+            .get_mood_entries(date, end - 1)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter_map(|entry| Some((entry.timestamp, entry.note.filter(|n| !n.trim().is_empty())?)))
+            .collect(),
+    };
+
+    let path = daily_note::note_path(&path_template, day)?;
+    daily_note::write_summary(&path, &daily_note::render(&data)?)?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Publish a read-only report as a self-contained HTML file.
+/// `profile` is `full` (default) or `client`, which leaves out app details.
+#[tauri::command]
+pub fn publish_report(state: State<'_, AppState>, request: PublishReportRequest) -> Result<(), String> {
+    let PublishReportRequest { start, end, profile, file_path, title, sources } = request;
+    let profile = profile.unwrap_or_else(|| "full".to_string());
+    if !report::REPORT_PROFILES.contains(&profile.as_str()) {
+        return Err(format!(
+            "Unknown report profile: {} (expected one of: {})",
+            profile,
+            report::REPORT_PROFILES.join(", ")
+        ));
+    }
+
+    let stats = state.db.get_stats_for_range(start, end, sources.as_deref(), None, true).map_err(|e| e.to_string())?;
+    let activities = exportable_activities(&state, start, end, Some(true), sources.as_deref())?;
+
+    let to_local = |ts: i64| Local.timestamp_opt(ts, 0).single().unwrap_or_default();
+    let mut daily: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+    let (first_day, last_day) = (to_local(start).date_naive(), to_local(end).date_naive());
+    let mut day = first_day;
+    while day <= last_day {
+        daily.insert(day, 0);
+        match day.succ_opt() {
+            Some(next) => day = next,
+            None => break,
+        }
+    }
+    for activity in &activities {
+        *daily.entry(to_local(activity.started_at).date_naive()).or_insert(0) += activity.duration_sec;
+    }
+
+    let data = report::ReportData {
+        title: title.filter(|t| !t.trim().is_empty()).unwrap_or_else(|| "Time Report".to_string()),
+        period: format!("{} – {}", first_day.format("%Y-%m-%d"), last_day.format("%Y-%m-%d")),
+        total_seconds: stats.total_seconds,
+        productive_seconds: stats.productive_seconds,
+        categories: stats
+            .category_breakdown
+            .into_iter()
+            .map(|(_, name, color, seconds)| (name, color, seconds))
+            .collect(),
+        apps: if profile == "client" { Vec::new() } else { stats.app_breakdown },
+        daily: daily
+            .into_iter()
+            .map(|(day, seconds)| (day.format("%a %d").to_string(), seconds))
+            .collect(),
+    };
+
+    std::fs::write(&file_path, report::render_html(&data)?)
+        .map_err(|e| format!("Failed to write report file: {}", e))?;
+
     Ok(())
 }
+
+/// Write a copy of the database with titles, domains, descriptions and other
+/// free text replaced by placeholders, for sharing when debugging
+#[tauri::command]
+pub fn anonymize_copy(state: State<'_, AppState>, path: String) -> Result<AnonymizeSummary, String> {
+    state
+        .db
+        .anonymize_copy(std::path::Path::new(&path))
+        .map_err(|e| match e {
+            rusqlite::Error::InvalidPath(path) => format!("Cannot write an anonymized copy to {}", path.display()),
+            e => format!("Failed to create anonymized copy: {}", e),
+        })
+}
+
+/// List the report and summary templates
+#[tauri::command]
+pub fn get_templates() -> Vec<TemplateInfo> {
+    templates::list()
+}
+
+/// Get the source of a template (the user's version when customized)
+#[tauri::command]
+pub fn get_template(name: String) -> Result<String, String> {
+    templates::source(&name)
+}
+
+/// `save_template` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SaveTemplateRequest {
+    pub name: String,
+    pub source: String,
+}
+
+/// Save a customized template; it must parse
+#[tauri::command]
+pub fn save_template(request: SaveTemplateRequest) -> Result<(), String> {
+    let SaveTemplateRequest { name, source } = request;
+    templates::save(&name, &source)
+}
+
+/// Restore a template to its built-in default
+#[tauri::command]
+pub fn reset_template(name: String) -> Result<(), String> {
+    templates::reset(&name)
+}
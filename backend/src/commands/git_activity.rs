@@ -0,0 +1,82 @@
+//! Git activity commands
+
+use crate::commands::common::{AppState, RangeRequest};
+use crate::git_activity::{self, GitCommit};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Commits and tracked time for one repository
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RepositoryActivity {
+    pub name: String,
+    pub path: String,
+    pub commit_count: usize,
+    /// Tracked time whose window title mentions the repository name
+    pub tracked_seconds: i64,
+    pub commits: Vec<GitCommit>,
+    /// Set when the repository could not be scanned
+    pub error: Option<String>,
+}
+
+fn configured_repositories(state: &AppState) -> Result<Vec<String>, String> {
+    let value = state.db.get_setting("git_repositories").map_err(|e| e.to_string())?;
+    Ok(value
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+/// Get configured repository paths
+#[tauri::command]
+pub fn get_git_repositories(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    configured_repositories(&state)
+}
+
+/// Set repository paths to scan for commits
+#[tauri::command]
+pub fn set_git_repositories(state: State<'_, AppState>, paths: Vec<String>) -> Result<(), String> {
+    let mut repositories: Vec<String> = Vec::new();
+    for path in paths.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        if !git_activity::is_repository(Path::new(path)) {
+            return Err(format!("Not a git repository: {}", path));
+        }
+        if !repositories.iter().any(|r| r == path) {
+            repositories.push(path.to_string());
+        }
+    }
+    let json = serde_json::to_string(&repositories).map_err(|e| e.to_string())?;
+    state.settings.set("git_repositories", &json)
+}
+
+/// Get commits per configured repository for a time range, with the time tracked on each
+#[tauri::command]
+pub fn get_commit_timeline(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<RepositoryActivity>, String> {
+    let RangeRequest { start, end } = request;
+    let author_email = state.db.get_setting("git_author_email").map_err(|e| e.to_string())?;
+
+    configured_repositories(&state)?
+        .into_iter()
+        .map(|path| {
+            let repo = PathBuf::from(&path);
+            let name = git_activity::repository_name(&repo);
+            let (commits, error) = match git_activity::scan_commits(&repo, start, end, author_email.as_deref()) {
+                Ok(commits) => (commits, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            let tracked_seconds = state
+                .db
+                .get_tracked_seconds_for_title(start, end, &name)
+                .map_err(|e| e.to_string())?;
+            Ok(RepositoryActivity {
+                name,
+                path,
+                commit_count: commits.len(),
+                tracked_seconds,
+                commits,
+                error,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+//! REST API commands
+
+use crate::commands::common::AppState;
+use crate::http_api;
+use serde::Serialize;
+use tauri::State;
+
+/// How external tools reach the REST API
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpApiInfo {
+    pub enabled: bool,
+    /// e.g. `http://127.0.0.1:9901/api/v1`
+    pub base_url: String,
+    /// Bearer token for the `Authorization` header
+    pub token: String,
+    /// Why the API is not listening although enabled (e.g. the port is
+    /// taken); binding is retried
+    pub error: Option<String>,
+}
+
+fn http_api_info(state: &AppState, token: String) -> HttpApiInfo {
+    let settings = state.settings.get();
+    HttpApiInfo {
+        enabled: settings.http_api_enabled,
+        base_url: format!("http://127.0.0.1:{}{}", settings.http_api_port, http_api::PATH_PREFIX),
+        token,
+        error: http_api::bind_error(),
+    }
+}
+
+/// Get the REST API address and token (the token is created on first use)
+#[tauri::command]
+pub fn get_http_api_info(state: State<'_, AppState>) -> Result<HttpApiInfo, String> {
+    let token = http_api::token(&state.settings)?;
+    Ok(http_api_info(&state, token))
+}
+
+/// Replace the REST API token; clients using the old one get 401 from now on
+#[tauri::command]
+pub fn reset_http_api_token(state: State<'_, AppState>) -> Result<HttpApiInfo, String> {
+    let token = http_api::reset_token(&state.settings)?;
+    Ok(http_api_info(&state, token))
+}
@@ -1,8 +1,30 @@
 //! Idle detection commands
 
 use crate::commands::common::AppState;
+use crate::database::common::SOURCE_IDLE_CLASSIFIED;
+use serde::Deserialize;
 use tauri::State;
 
+/// `classify_idle_time` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ClassifyIdleTimeRequest {
+    pub idle_start: i64,
+    pub idle_end: i64,
+    /// Category name
+    pub classification: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// `check_idle_state` request; without `seconds` the idle threshold is used
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct IdleStateRequest {
+    #[serde(default)]
+    pub seconds: Option<u64>,
+}
+
 /// Get idle time
 #[tauri::command]
 pub fn get_idle_time() -> Result<u64, String> {
@@ -12,9 +34,9 @@ pub fn get_idle_time() -> Result<u64, String> {
 
 /// Check idle state
 #[tauri::command]
-pub fn check_idle_state(seconds: Option<u64>) -> Result<bool, String> {
+pub fn check_idle_state(request: IdleStateRequest) -> Result<bool, String> {
     let monitor = crate::idle::IdleMonitor::new();
-    Ok(if let Some(secs) = seconds {
+    Ok(if let Some(secs) = request.seconds {
         monitor.is_idle_for(secs)
     } else {
         monitor.is_idle()
@@ -25,23 +47,21 @@ pub fn check_idle_state(seconds: Option<u64>) -> Result<bool, String> {
 #[tauri::command]
 pub fn classify_idle_time(
     state: State<'_, AppState>,
-    idle_start: i64,
-    idle_end: i64,
-    classification: String,
-    description: Option<String>,
+    request: ClassifyIdleTimeRequest,
 ) -> Result<(), String> {
     let category_id = state
         .db
-        .find_category_by_name(&classification)
+        .find_category_by_name(&request.classification)
         .map_err(|e| e.to_string())?;
     
     state
         .db
         .add_manual_entry(
-            description.as_deref(),
+            request.description.as_deref(),
             category_id,
-            idle_start,
-            idle_end,
+            request.idle_start,
+            request.idle_end,
+            SOURCE_IDLE_CLASSIFIED,
         )
         .map_err(|e| e.to_string())?;
     
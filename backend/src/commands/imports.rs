@@ -0,0 +1,36 @@
+//! OS usage history import commands
+
+use crate::database::ImportSummary;
+use crate::database::common::SOURCE_IMPORT_PREFIX;
+use crate::commands::common::AppState;
+use crate::events::EVENT_ACTIVITIES_CHANGED;
+use crate::importers;
+use serde::Deserialize;
+use std::path::Path;
+use tauri::State;
+
+/// `import_os_usage` request; `format` names the importer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ImportOsUsageRequest {
+    pub file_path: String,
+    pub format: String,
+}
+
+/// Import app usage from a macOS Screen Time or Windows Activity History database,
+/// or an ActivityWatch JSON export.
+/// Time that overlaps already tracked activities is skipped.
+#[tauri::command]
+pub fn import_os_usage(
+    state: State<'_, AppState>,
+    request: ImportOsUsageRequest,
+) -> Result<ImportSummary, String> {
+    let ImportOsUsageRequest { file_path, format } = request;
+    let rows = importers::read_usage_history(&format, Path::new(&file_path))?;
+    let summary = state
+        .db
+        .import_activities(&rows, &format!("{}{}", SOURCE_IMPORT_PREFIX, format))
+        .map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "imported": summary.imported }));
+    Ok(summary)
+}
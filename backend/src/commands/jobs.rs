@@ -0,0 +1,39 @@
+//! Maintenance job commands
+
+use crate::commands::common::AppState;
+use crate::database::Job;
+use serde::Deserialize;
+use tauri::State;
+
+/// Jobs listed when no limit is given
+const DEFAULT_JOB_LIMIT: i64 = 50;
+
+/// `list_jobs` request
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ListJobsRequest {
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// List the most recent maintenance jobs with their progress, newest first
+#[tauri::command]
+pub fn list_jobs(state: State<'_, AppState>, request: ListJobsRequest) -> Result<Vec<Job>, String> {
+    state
+        .db
+        .get_jobs(request.limit.unwrap_or(DEFAULT_JOB_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+/// Cancel a job: a queued job right away, a running one after its current step
+#[tauri::command]
+pub fn cancel_job(state: State<'_, AppState>, id: i64) -> Result<Job, String> {
+    if !state.db.request_job_cancel(id).map_err(|e| e.to_string())? {
+        return Err(format!("Job {} is not queued or running", id));
+    }
+    state
+        .db
+        .get_job(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Job not found".to_string())
+}
@@ -1,107 +1,332 @@
 //! Manual entry commands
 
-use crate::database::ManualEntry;
-use crate::commands::common::AppState;
-use chrono::Utc;
+use crate::app_metrics;
+use crate::database::{IdleSegment, ManualEntry};
+use crate::database::common::SOURCE_MANUAL;
+use crate::entry_parser::ManualEntryDraft;
+use crate::events::{
+    EVENT_ACTIVITIES_CHANGED, EVENT_MANUAL_ENTRY_CREATED, EVENT_MANUAL_ENTRY_DELETED, EVENT_MANUAL_ENTRY_UPDATED,
+};
+use crate::commands::common::{select_fields, AppState};
+use chrono::{Local, Utc};
+use serde::Deserialize;
 use tauri::State;
+use time_tracker_plugin_sdk::validation::{self, ValidationCode, ValidationError};
+
+/// `add_manual_entry` / `create_manual_entry` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ManualEntryRequest {
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+/// `update_manual_entry` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateManualEntryRequest {
+    pub id: i64,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+    pub started_at: i64,
+    pub ended_at: i64,
+    #[serde(default)]
+    pub billable_override: Option<bool>,
+}
+
+/// `get_manual_entries` request; `fields` optionally selects the returned fields
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ManualEntriesRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub fields: Option<Vec<String>>,
+}
+
+/// `start_manual_entry` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct StartManualEntryRequest {
+    pub category_id: i64,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// `submit_idle_activity` request; without `chunks` the whole idle period
+/// gets `category_id`, `comment`, `project_id` and `task_id`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SubmitIdleActivityRequest {
+    pub category_id: i64,
+    #[serde(default)]
+    pub comment: Option<String>,
+    pub started_at: i64,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub task_id: Option<i64>,
+    #[serde(default)]
+    pub chunks: Option<Vec<IdleChunk>>,
+}
 
 /// Add manual entry
 #[tauri::command]
-pub fn add_manual_entry(
-    state: State<'_, AppState>,
-    description: Option<String>,
-    category_id: Option<i64>,
-    started_at: i64,
-    ended_at: i64,
-) -> Result<i64, String> {
-    state
-        .db
+pub fn add_manual_entry(state: State<'_, AppState>, request: ManualEntryRequest) -> Result<i64, String> {
+    let ManualEntryRequest { description, category_id, started_at, ended_at } = request;
+    validation::past_entry(started_at, ended_at, Utc::now().timestamp())?;
+    let id = state
+        .storage
         .add_manual_entry(
             description.as_deref(),
             category_id,
             started_at,
             ended_at,
+            SOURCE_MANUAL,
         )
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_CREATED, serde_json::json!({
+        "id": id,
+        "description": description,
+        "category_id": category_id,
+        "started_at": started_at,
+        "ended_at": ended_at,
+    }));
+
+    Ok(id)
 }
 
-/// Get manual entries for a time range
+/// Get manual entries for a time range; `fields` optionally selects the returned fields
 #[tauri::command]
 pub fn get_manual_entries(
     state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-) -> Result<Vec<ManualEntry>, String> {
-    state
-        .db
+    request: ManualEntriesRequest,
+) -> Result<Vec<serde_json::Value>, String> {
+    let ManualEntriesRequest { start, end, fields } = request;
+    validation::query_range(Some(start), Some(end))?;
+    let entries = state
+        .storage
         .get_manual_entries(start, end)
-        .map_err(|e: rusqlite::Error| e.to_string())
+        .map_err(|e| e.to_string())?;
+    select_fields(&entries, fields.as_deref())
+}
+
+/// Parse quick-capture text (e.g. "1.5h yesterday design review #billable")
+/// into a manual entry draft. Nothing is saved; the frontend confirms the draft
+/// and creates the entry with `create_manual_entry`.
+#[tauri::command]
+pub fn parse_time_entry(
+    state: State<'_, AppState>,
+    text: String,
+) -> Result<ManualEntryDraft, String> {
+    let categories = state
+        .storage
+        .get_categories()
+        .map_err(|e| e.to_string())?;
+    let projects = state.storage.get_project_names().map_err(|e| e.to_string())?;
+    crate::entry_parser::parse_time_entry(&text, &categories, &projects, Local::now())
+}
+
+/// Part of an idle period classified from the idle prompt
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdleChunk {
+    pub category_id: i64,
+    #[serde(default)]
+    pub comment: Option<String>,
+    /// Length of the chunk; the last chunk covers the rest of the period
+    #[serde(default)]
+    pub duration_sec: Option<i64>,
+    /// Stored in the `project_id`/`task_id` activity columns when the
+    /// projects plugin provides them
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub task_id: Option<i64>,
+}
+
+/// Lay chunks out back to back from `started_at`
+fn idle_chunk_segments(started_at: i64, total_sec: i64, chunks: &[IdleChunk]) -> Result<Vec<IdleSegment>, String> {
+    let mut segments = Vec::with_capacity(chunks.len());
+    let mut offset = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let duration_sec = if i + 1 == chunks.len() {
+            total_sec - offset
+        } else {
+            let duration = chunk.duration_sec.ok_or_else(|| {
+                ValidationError::new("duration_sec", ValidationCode::Required, "Every chunk but the last needs a duration")
+            })?;
+            validation::in_range("duration_sec", duration, 1, total_sec - offset - 1)?
+        };
+        segments.push(IdleSegment {
+            started_at: started_at + offset,
+            duration_sec,
+            category_id: chunk.category_id,
+            description: chunk.comment.clone().filter(|c| !c.trim().is_empty()),
+            project_id: chunk.project_id,
+            task_id: chunk.task_id,
+        });
+        offset += duration_sec;
+    }
+    Ok(segments)
+}
+
+/// Part of an idle period with explicit bounds, for `classify_idle_segments`
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdleSegmentInput {
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub category_id: i64,
+    #[serde(default)]
+    pub comment: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub task_id: Option<i64>,
 }
 
 /// Submit idle activity (from idle prompt)
-/// Updates the existing idle activity with category and description instead of creating a manual entry
+/// Classifies the existing idle activity instead of creating a manual entry.
+/// With `chunks` the period is split into consecutive parts (e.g. 30 minutes
+/// meeting, then a break), each with its own category, project and task.
 #[tauri::command]
 pub fn submit_idle_activity(
     state: State<'_, AppState>,
-    category_id: i64,
-    comment: Option<String>,
-    started_at: i64,
+    request: SubmitIdleActivityRequest,
 ) -> Result<(), String> {
+    let started_at = request.started_at;
+    let chunks = request.chunks.filter(|chunks| !chunks.is_empty()).unwrap_or_else(|| {
+        vec![IdleChunk {
+            category_id: request.category_id,
+            comment: request.comment,
+            duration_sec: None,
+            project_id: request.project_id,
+            task_id: request.task_id,
+        }]
+    });
+    let total_sec = state
+        .storage
+        .get_idle_duration(started_at)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Idle period not found".to_string())?;
+    let segments = idle_chunk_segments(started_at, total_sec, &chunks)?;
     state
-        .db
-        .update_idle_activity(
-            started_at,
-            category_id,
-            comment.as_deref(),
-        )
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .storage
+        .split_idle_activity(started_at, &segments)
+        .map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "idle_started_at": started_at }));
+    app_metrics::record(&state.db, app_metrics::METRIC_IDLE_PROMPT_ANSWERED);
+
+    Ok(())
+}
+
+/// Classify an idle period as segments with their own bounds and categories
+/// in one call. The idle period is the one starting at the earliest segment;
+/// segments must cover it completely without overlapping.
+#[tauri::command]
+pub fn classify_idle_segments(
+    state: State<'_, AppState>,
+    mut segments: Vec<IdleSegmentInput>,
+) -> Result<(), String> {
+    segments.sort_by_key(|segment| segment.started_at);
+    let started_at = segments
+        .first()
+        .map(|segment| segment.started_at)
+        .ok_or_else(|| ValidationError::new("segments", ValidationCode::Required, "At least one segment is required"))?;
+    let total_sec = state
+        .storage
+        .get_idle_duration(started_at)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Idle period not found".to_string())?;
+    let ranges: Vec<(i64, i64)> = segments.iter().map(|s| (s.started_at, s.ended_at)).collect();
+    validation::covers_range("segments", &ranges, started_at, started_at + total_sec)?;
+
+    let idle_segments: Vec<IdleSegment> = segments
+        .iter()
+        .map(|segment| IdleSegment {
+            started_at: segment.started_at,
+            duration_sec: segment.ended_at - segment.started_at,
+            category_id: segment.category_id,
+            description: segment.comment.clone().filter(|c| !c.trim().is_empty()),
+            project_id: segment.project_id,
+            task_id: segment.task_id,
+        })
+        .collect();
+    state
+        .storage
+        .split_idle_activity(started_at, &idle_segments)
+        .map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "idle_started_at": started_at }));
+    app_metrics::record(&state.db, app_metrics::METRIC_IDLE_PROMPT_ANSWERED);
 
     Ok(())
 }
 
+/// Dismiss the idle prompt without classifying the idle period
+#[tauri::command]
+pub fn dismiss_idle_prompt(state: State<'_, AppState>) -> Result<(), String> {
+    app_metrics::record(&state.db, app_metrics::METRIC_IDLE_PROMPT_DISMISSED);
+    Ok(())
+}
+
 /// Create manual entry
 #[tauri::command]
 pub fn create_manual_entry(
     state: State<'_, AppState>,
-    description: Option<String>,
-    category_id: Option<i64>,
-    started_at: i64,
-    ended_at: i64,
+    request: ManualEntryRequest,
 ) -> Result<ManualEntry, String> {
+    let ManualEntryRequest { description, category_id, started_at, ended_at } = request;
+    validation::past_entry(started_at, ended_at, Utc::now().timestamp())?;
     let id = state
-        .db
+        .storage
         .add_manual_entry(
             description.as_deref(),
             category_id,
             started_at,
             ended_at,
+            SOURCE_MANUAL,
         )
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
     
     // Return the created entry
     let entries = state
-        .db
+        .storage
         .get_manual_entries(started_at - 1, ended_at + 1)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
     
-    entries
+    let entry = entries
         .into_iter()
         .find(|e| e.id == id)
-        .ok_or_else(|| "Failed to retrieve created entry".to_string())
+        .ok_or_else(|| "Failed to retrieve created entry".to_string())?;
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_CREATED, serde_json::json!(entry));
+
+    Ok(entry)
 }
 
 /// Update manual entry
 #[tauri::command]
 pub fn update_manual_entry(
     state: State<'_, AppState>,
-    id: i64,
-    description: Option<String>,
-    category_id: Option<i64>,
-    started_at: i64,
-    ended_at: i64,
+    request: UpdateManualEntryRequest,
 ) -> Result<ManualEntry, String> {
+    let UpdateManualEntryRequest {
+        id,
+        description,
+        category_id,
+        started_at,
+        ended_at,
+        billable_override,
+    } = request;
+    validation::past_entry(started_at, ended_at, Utc::now().timestamp())?;
     state
-        .db
+        .storage
         .update_manual_entry(
             id,
             description.as_deref(),
@@ -109,15 +334,21 @@ pub fn update_manual_entry(
             started_at,
             ended_at,
         )
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
+    state
+        .storage
+        .set_manual_entries_billable_override(&[id], billable_override)
+        .map_err(|e| e.to_string())?;
     
     let updated_entry = state
-        .db
+        .storage
         .get_manual_entries(0, i64::MAX)
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|e| e.id == id)
         .ok_or_else(|| "Failed to retrieve updated entry".to_string())?;
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_UPDATED, serde_json::json!(updated_entry));
     
     Ok(updated_entry)
 }
@@ -125,29 +356,37 @@ pub fn update_manual_entry(
 /// Delete manual entry
 #[tauri::command]
 pub fn delete_manual_entry(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.db.delete_manual_entry(id).map_err(|e| e.to_string())
+    state.storage.delete_manual_entry(id).map_err(|e| e.to_string())?;
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_DELETED, serde_json::json!({ "id": id }));
+    Ok(())
 }
 
 /// Start manual entry (for thinking mode, etc.)
 #[tauri::command]
-pub fn start_manual_entry(
-    state: State<'_, AppState>,
-    category_id: i64,
-    description: Option<String>,
-) -> Result<i64, String> {
+pub fn start_manual_entry(state: State<'_, AppState>, request: StartManualEntryRequest) -> Result<i64, String> {
+    let StartManualEntryRequest { category_id, description } = request;
     let now = Utc::now().timestamp();
     let id = state
-        .db
+        .storage
         .add_manual_entry(
             description.as_deref(),
             Some(category_id),
             now,
             now, // Will be updated when stopped
+            SOURCE_MANUAL,
         )
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
     
     // Store the entry ID for later update
     *state.thinking_mode_entry_id.lock().unwrap() = Some(id);
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_CREATED, serde_json::json!({
+        "id": id,
+        "description": description,
+        "category_id": category_id,
+        "started_at": now,
+        "ended_at": now,
+    }));
     
     Ok(id)
 }
@@ -162,16 +401,16 @@ pub fn stop_manual_entry(state: State<'_, AppState>) -> Result<ManualEntry, Stri
     
     // Get the entry to find its start time
     let entry = state
-        .db
+        .storage
         .get_manual_entries(now - 86400, now + 86400)
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|e| e.id == entry_id)
         .ok_or_else(|| "Entry not found".to_string())?;
     
     // Update with end time
     state
-        .db
+        .storage
         .update_manual_entry(
             entry_id,
             entry.description.as_deref(),
@@ -179,15 +418,17 @@ pub fn stop_manual_entry(state: State<'_, AppState>) -> Result<ManualEntry, Stri
             entry.started_at,
             now,
         )
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .map_err(|e| e.to_string())?;
     
     let updated_entry = state
-        .db
+        .storage
         .get_manual_entries(now - 86400, now + 86400)
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|e| e.id == entry_id)
         .ok_or_else(|| "Failed to retrieve updated entry".to_string())?;
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_UPDATED, serde_json::json!(updated_entry));
     
     Ok(updated_entry)
 }
@@ -0,0 +1,30 @@
+//! Micro-break commands
+
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::micro_breaks::{MICRO_BREAK_COMPLETED, MICRO_BREAK_SKIPPED};
+use crate::database::MicroBreakStats;
+use serde::Deserialize;
+use tauri::State;
+
+/// `finish_micro_break` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct FinishMicroBreakRequest {
+    pub id: i64,
+    pub skipped: bool,
+}
+
+/// Record how the micro-break overlay was closed
+#[tauri::command]
+pub fn finish_micro_break(state: State<'_, AppState>, request: FinishMicroBreakRequest) -> Result<(), String> {
+    let FinishMicroBreakRequest { id, skipped } = request;
+    let status = if skipped { MICRO_BREAK_SKIPPED } else { MICRO_BREAK_COMPLETED };
+    state.db.finish_micro_break(id, status).map_err(|e| e.to_string())
+}
+
+/// Get micro-breaks offered in a time range by outcome
+#[tauri::command]
+pub fn get_micro_break_stats(state: State<'_, AppState>, request: RangeRequest) -> Result<MicroBreakStats, String> {
+    let RangeRequest { start, end } = request;
+    state.db.get_micro_break_stats(start, end).map_err(|e| e.to_string())
+}
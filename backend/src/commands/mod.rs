@@ -1,34 +1,94 @@
 //! Tauri commands - IPC handlers for frontend communication
 //! 
 //! This module is organized into submodules by domain:
-//! - activities: Activity-related commands
-//! - categories: Category management commands
-//! - rules: Rule management commands
-//! - manual_entries: Manual entry commands
+//! - activities: Activity-related commands (through the `Storage` backend)
+//! - categories: Category management commands (through the `Storage` backend)
+//! - rules: Rule management commands (through the `Storage` backend)
+//! - task_hints: Window title keyword to task commands
+//! - suggestions: Categorization suggestion commands
+//! - manual_entries: Manual entry commands (through the `Storage` backend)
+//! - approvals: Entry approval commands
+//! - rounding: Time entry rounding preview commands
+//! - custom_fields: Custom field commands
 //! - settings: Settings management commands
-//! - stats: Statistics commands
+//! - stats: Statistics commands (through the `Storage` backend)
+//! - wellbeing: Wellbeing and work-life boundary report commands
+//! - dashboard: Dashboard KPI commands
 //! - tracking: Tracking control commands
 //! - idle: Idle detection commands
 //! - export: Export commands
+//! - imports: OS usage history import commands
 //! - window: Window management commands
 //! - domains: Domain statistics commands
+//! - git_activity: Git commit correlation commands
+//! - terminal: Terminal/SSH session reporting commands
+//! - blocklist: Focus-mode blocklist commands
+//! - checkins: Productivity check-in commands
+//! - micro_breaks: Eye-strain micro-break commands
+//! - mood: Energy/mood journal commands
+//! - daily_plans: Daily plan (intentions) commands
+//! - profiles: Settings profile commands
+//! - workspace: Workspace (database) switching commands
+//! - diagnostics: Startup and database diagnostics commands
+//! - jobs: Maintenance job commands
+//! - updates: App update commands
+//! - app_metrics: Local app usage metrics commands
 //! - plugins: Plugin management commands
+//! - webhooks: Webhook management commands
+//! - http_api: REST API address and token commands
+//! - replay: Recorded sample replay commands (development builds)
+//! - demo_data: Demo data generation commands (development builds)
 //! - common: Shared types and utilities
+//!
+//! Commands with an optional argument or with two or more arguments take a
+//! single request struct (`#[serde(rename_all = "snake_case",
+//! deny_unknown_fields)]`), sent as `{ request: { ... } }`: a misnamed key is
+//! then rejected instead of silently becoming `None`, and every command is
+//! called the same way. Date ranges use the shared `RangeRequest`. Only
+//! commands with a single required argument stay positional.
 
 pub mod activities;
 pub mod categories;
 pub mod rules;
+pub mod task_hints;
+pub mod suggestions;
 pub mod manual_entries;
+pub mod approvals;
+pub mod rounding;
+pub mod custom_fields;
 pub mod settings;
 pub mod stats;
+pub mod wellbeing;
+pub mod dashboard;
 pub mod tracking;
 pub mod idle;
 pub mod export;
+pub mod imports;
 pub mod window;
 pub mod domains;
+pub mod git_activity;
+pub mod terminal;
+pub mod blocklist;
+pub mod checkins;
+pub mod micro_breaks;
+pub mod mood;
+pub mod daily_plans;
+pub mod profiles;
+pub mod workspace;
+pub mod diagnostics;
+pub mod jobs;
+pub mod updates;
+pub mod app_metrics;
 pub mod plugins;
+pub mod webhooks;
+pub mod http_api;
+pub mod replay;
+pub mod demo_data;
 pub mod common;
 
+#[cfg(test)]
+mod tests;
+
 // Re-export AppState and common types
 pub use common::AppState;
 
@@ -36,12 +96,37 @@ pub use common::AppState;
 pub use activities::*;
 pub use categories::*;
 pub use rules::*;
+pub use task_hints::*;
+pub use suggestions::*;
 pub use manual_entries::*;
+pub use approvals::*;
+pub use rounding::*;
+pub use custom_fields::*;
 pub use settings::*;
 pub use stats::*;
+pub use wellbeing::*;
+pub use dashboard::*;
 pub use tracking::*;
 pub use idle::*;
 pub use export::*;
+pub use imports::*;
 pub use window::*;
 pub use domains::*;
+pub use git_activity::*;
+pub use terminal::*;
+pub use blocklist::*;
+pub use checkins::*;
+pub use micro_breaks::*;
+pub use mood::*;
+pub use daily_plans::*;
+pub use profiles::*;
+pub use workspace::*;
+pub use diagnostics::*;
+pub use jobs::*;
+pub use updates::*;
+pub use app_metrics::*;
 pub use plugins::*;
+pub use webhooks::*;
+pub use http_api::*;
+pub use replay::*;
+pub use demo_data::*;
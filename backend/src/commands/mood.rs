@@ -0,0 +1,44 @@
+//! Energy/mood journal commands
+
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::MoodEntry;
+use serde::Deserialize;
+use tauri::State;
+
+/// `log_mood` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct LogMoodRequest {
+    pub timestamp: i64,
+    /// 1-5
+    pub score: i64,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Log how you felt at a point in time (score 1-5)
+#[tauri::command]
+pub fn log_mood(state: State<'_, AppState>, request: LogMoodRequest) -> Result<i64, String> {
+    let LogMoodRequest { timestamp, score, note } = request;
+    if !(1..=5).contains(&score) {
+        return Err("Score must be between 1 and 5".to_string());
+    }
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    state.db.log_mood(timestamp, score, note).map_err(|e| e.to_string())
+}
+
+/// Get mood entries in a time range
+#[tauri::command]
+pub fn get_mood_entries(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<MoodEntry>, String> {
+    let RangeRequest { start, end } = request;
+    state.db.get_mood_entries(start, end).map_err(|e| e.to_string())
+}
+
+/// Delete a mood entry
+#[tauri::command]
+pub fn delete_mood_entry(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.db.delete_mood_entry(id).map_err(|e| e.to_string())
+}
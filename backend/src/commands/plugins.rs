@@ -1,11 +1,29 @@
 //! Plugin management commands
 
 use crate::commands::common::AppState;
+use crate::database::common::SOURCE_PLUGIN_PREFIX;
+use crate::database::plugins::InstalledPluginRow;
+use crate::plugin_system::index::{self, PluginCategory, PluginSort};
+use crate::plugin_system::loader::{check_core_compatibility, check_manifest_compatibility, StagedInstall};
+use crate::plugin_system::registry::PluginMetrics;
 use crate::plugin_system::{PluginDiscovery, PluginLoader};
+use crate::updates::CURRENT_VERSION;
 use dirs::data_dir;
+use serde::Deserialize;
 use std::sync::Arc;
 use tauri::State;
 
+/// `search_plugins` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SearchPluginsRequest {
+    pub query: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub sort: Option<PluginSort>,
+}
+
 /// Plugin info structure for frontend
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct InstalledPluginInfo {
@@ -38,23 +56,27 @@ pub struct RegistryPluginInfo {
     pub min_core_version: Option<String>,
     pub max_core_version: Option<String>,
     pub api_version: Option<String>,
+    pub updated_at: Option<String>,
+    pub rating: Option<f64>,
+    pub rating_count: Option<u64>,
+    /// Whether `min_core_version`/`max_core_version` allow this app version
+    pub compatible: bool,
+    pub compatibility_issue: Option<String>,
 }
 
 /// Helper function to get registry URLs from settings
-fn get_registry_urls(state: &AppState) -> Result<Vec<String>, String> {
-    if let Ok(Some(urls_json)) = state.db.get_setting("plugin_registry_urls") {
-        if let Ok(urls) = serde_json::from_str::<Vec<String>>(&urls_json) {
-            if !urls.is_empty() {
-                return Ok(urls);
-            }
-        }
-    }
-    
-    Ok(vec!["https://raw.githubusercontent.com/tmtrckr/plugins-registry/main/registry.json".to_string()])
+fn get_registry_urls(state: &AppState) -> Vec<String> {
+    index::registry_urls(&state.settings.get())
 }
 
 /// Helper to convert registry plugin to frontend info
 fn registry_plugin_to_info(plugin: &crate::plugin_system::discovery::RegistryPlugin) -> RegistryPluginInfo {
+    let compatibility_issue = check_core_compatibility(
+        plugin.min_core_version.as_deref(),
+        plugin.max_core_version.as_deref(),
+        CURRENT_VERSION,
+    )
+    .err();
     RegistryPluginInfo {
         id: plugin.id.clone(),
         name: plugin.name.clone(),
@@ -70,120 +92,52 @@ fn registry_plugin_to_info(plugin: &crate::plugin_system::discovery::RegistryPlu
         min_core_version: plugin.min_core_version.clone(),
         max_core_version: plugin.max_core_version.clone(),
         api_version: plugin.api_version.clone(),
+        updated_at: plugin.updated_at.clone(),
+        rating: plugin.rating,
+        rating_count: plugin.rating_count,
+        compatible: compatibility_issue.is_none(),
+        compatibility_issue,
     }
 }
 
-/// Helper function to fetch plugins from multiple registries and merge them
-async fn fetch_plugins_from_registries(urls: Vec<String>) -> Result<Vec<RegistryPluginInfo>, String> {
-    use futures::future::join_all;
-
-    let mut all_plugins: std::collections::HashMap<String, RegistryPluginInfo> = std::collections::HashMap::new();
-
-    let fetch_tasks: Vec<_> = urls
-        .into_iter()
-        .map(|url| {
-            let mut discovery = PluginDiscovery::new(url);
-            async move { discovery.get_registry().await }
-        })
-        .collect();
-
-    let results = join_all(fetch_tasks).await;
-
-    for result in results {
-        match result {
-            Ok(registry) => {
-                for plugin in registry.plugins {
-                    let plugin_info = registry_plugin_to_info(&plugin);
-
-                    match all_plugins.get(&plugin.id) {
-                        Some(existing) => {
-                            if compare_versions(&plugin.latest_version, &existing.latest_version) > 0 {
-                                all_plugins.insert(plugin.id, plugin_info);
-                            }
-                        }
-                        None => {
-                            all_plugins.insert(plugin.id.clone(), plugin_info);
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                eprintln!("Warning: Failed to fetch registry: {}", e);
-            }
-        }
-    }
-
-    Ok(all_plugins.into_values().collect())
+/// Get plugin registry from the local index (fetched from the registries if empty)
+#[tauri::command]
+pub async fn get_plugin_registry(state: State<'_, AppState>) -> Result<Vec<RegistryPluginInfo>, String> {
+    let registry_urls = get_registry_urls(&state);
+    let plugins = state.plugin_index.get_or_refresh(&registry_urls).await?;
+    Ok(index::filter_plugins(&plugins, None, None, PluginSort::default())
+        .iter()
+        .map(registry_plugin_to_info)
+        .collect())
 }
 
-/// Simple version comparison (assumes semver format)
-fn compare_versions(v1: &str, v2: &str) -> i32 {
-    let v1_parts: Vec<u32> = v1.split('.').filter_map(|s| s.parse().ok()).collect();
-    let v2_parts: Vec<u32> = v2.split('.').filter_map(|s| s.parse().ok()).collect();
-    
-    for (i, &v1_part) in v1_parts.iter().enumerate() {
-        let v2_part = v2_parts.get(i).copied().unwrap_or(0);
-        if v1_part > v2_part {
-            return 1;
-        } else if v1_part < v2_part {
-            return -1;
-        }
-    }
-    
-    if v1_parts.len() < v2_parts.len() {
-        -1
-    } else if v1_parts.len() > v2_parts.len() {
-        1
-    } else {
-        0
-    }
+/// Search the local plugin index by text and category, sorted by `sort`
+#[tauri::command]
+pub async fn search_plugins(
+    state: State<'_, AppState>,
+    request: SearchPluginsRequest,
+) -> Result<Vec<RegistryPluginInfo>, String> {
+    let registry_urls = get_registry_urls(&state);
+    let plugins = state.plugin_index.get_or_refresh(&registry_urls).await?;
+    Ok(index::filter_plugins(&plugins, Some(&request.query), request.category.as_deref(), request.sort.unwrap_or_default())
+        .iter()
+        .map(registry_plugin_to_info)
+        .collect())
 }
 
-/// Get plugin registry from remote source(s)
+/// Get plugin categories with plugin counts from the local index
 #[tauri::command]
-pub async fn get_plugin_registry(state: State<'_, AppState>) -> Result<Vec<RegistryPluginInfo>, String> {
-    let registry_urls = get_registry_urls(&state)?;
-    fetch_plugins_from_registries(registry_urls).await
+pub async fn get_plugin_categories(state: State<'_, AppState>) -> Result<Vec<PluginCategory>, String> {
+    let registry_urls = get_registry_urls(&state);
+    let plugins = state.plugin_index.get_or_refresh(&registry_urls).await?;
+    Ok(index::categories(&plugins))
 }
 
-/// Search plugins in registry(ies) using PluginDiscovery::search_plugins per registry
+/// Re-fetch all registries into the local index; returns the plugin count
 #[tauri::command]
-pub async fn search_plugins(state: State<'_, AppState>, query: String) -> Result<Vec<RegistryPluginInfo>, String> {
-    let registry_urls = get_registry_urls(&state)?;
-    use futures::future::join_all;
-    use std::collections::HashMap;
-
-    let search_tasks: Vec<_> = registry_urls
-        .into_iter()
-        .map(|url| {
-            let q = query.clone();
-            async move {
-                let mut discovery = PluginDiscovery::new(url);
-                discovery.search_plugins(&q).await
-            }
-        })
-        .collect();
-
-    let results = join_all(search_tasks).await;
-    let mut by_id: HashMap<String, RegistryPluginInfo> = HashMap::new();
-    for result in results {
-        if let Ok(plugins) = result {
-            for p in plugins {
-                let info = registry_plugin_to_info(&p);
-                match by_id.get(&p.id) {
-                    Some(existing) => {
-                        if compare_versions(&p.latest_version, &existing.latest_version) > 0 {
-                            by_id.insert(p.id, info);
-                        }
-                    }
-                    None => {
-                        by_id.insert(p.id.clone(), info);
-                    }
-                }
-            }
-        }
-    }
-    Ok(by_id.into_values().collect())
+pub async fn refresh_plugin_index(state: State<'_, AppState>) -> Result<usize, String> {
+    let registry_urls = get_registry_urls(&state);
+    state.plugin_index.refresh(&registry_urls).await
 }
 
 /// Get plugin info from repository URL
@@ -199,7 +153,7 @@ pub async fn get_plugin_info(repository_url: String) -> Result<serde_json::Value
 /// Uses PluginDiscovery::get_plugin_by_id when a plugin id can be derived from the URL.
 #[tauri::command]
 pub async fn discover_plugin(state: State<'_, AppState>, repository_url: String) -> Result<RegistryPluginInfo, String> {
-    let registry_urls = get_registry_urls(&state)?;
+    let registry_urls = get_registry_urls(&state);
 
     if let Ok((_owner, repo)) = PluginDiscovery::parse_github_url_static(&repository_url) {
         let plugin_id_from_repo = repo.trim_end_matches("-plugin");
@@ -213,9 +167,9 @@ pub async fn discover_plugin(state: State<'_, AppState>, repository_url: String)
         }
     }
 
-    let all_plugins = fetch_plugins_from_registries(registry_urls).await?;
-    if let Some(plugin) = all_plugins.into_iter().find(|p| p.repository == repository_url) {
-        return Ok(plugin);
+    let all_plugins = state.plugin_index.get_or_refresh(&registry_urls).await?;
+    if let Some(plugin) = all_plugins.iter().find(|p| p.repository == repository_url) {
+        return Ok(registry_plugin_to_info(plugin));
     }
 
     let discovery = PluginDiscovery::new("".to_string());
@@ -224,6 +178,12 @@ pub async fn discover_plugin(state: State<'_, AppState>, repository_url: String)
     let (_owner, repo) = PluginDiscovery::parse_github_url_static(&repository_url)
         .map_err(|e| format!("Invalid GitHub URL: {}", e))?;
     let plugin_id = repo.trim_end_matches("-plugin");
+    let compatibility_issue = check_core_compatibility(
+        manifest.plugin.min_core_version.as_deref(),
+        manifest.plugin.max_core_version.as_deref(),
+        CURRENT_VERSION,
+    )
+    .err();
 
     Ok(RegistryPluginInfo {
         id: plugin_id.to_string(),
@@ -240,15 +200,19 @@ pub async fn discover_plugin(state: State<'_, AppState>, repository_url: String)
         min_core_version: manifest.plugin.min_core_version,
         max_core_version: manifest.plugin.max_core_version,
         api_version: manifest.plugin.api_version,
+        updated_at: None,
+        rating: None,
+        rating_count: None,
+        compatible: compatibility_issue.is_none(),
+        compatibility_issue,
     })
 }
 
-/// Install plugin from repository URL
+/// Install the latest release of the plugin at a repository URL
 #[tauri::command]
 pub async fn install_plugin(
     state: State<'_, AppState>,
     repository_url: String,
-    _version: Option<String>,
 ) -> Result<(), String> {
     let discovery = PluginDiscovery::new("".to_string());
     
@@ -261,6 +225,7 @@ pub async fn install_plugin(
     if author.is_empty() {
         return Err("Plugin author is required in manifest".to_string());
     }
+    check_manifest_compatibility(&manifest)?;
     
     let data_dir = data_dir()
         .unwrap_or_else(|| std::path::PathBuf::from("."))
@@ -268,10 +233,40 @@ pub async fn install_plugin(
     let plugins_dir = data_dir.join("plugins");
     
     let loader = PluginLoader::new(plugins_dir);
-    let manifest_path = loader.install_from_release(&author, &plugin_id, asset).await?;
-    
-    let installed_manifest = loader.load_manifest(&manifest_path)?;
-    loader.validate_manifest(&installed_manifest)?;
+    // Staged install: download and validate in a temp dir, then move into place.
+    // Dropping `staged` without `finish` removes the files and restores a previous version.
+    let mut staged = loader.stage_release(&author, &plugin_id, asset).await?;
+    // A running instance points into the library about to be replaced
+    let was_running = release_plugin(&state, &plugin_id);
+    match install_staged(&state, &mut staged, &author, &plugin_id, &repository_url) {
+        Ok(()) => {
+            staged.finish();
+            Ok(())
+        }
+        Err(e) => {
+            drop(staged);
+            if was_running {
+                let manifest_path = loader.get_plugin_dir(&author, &plugin_id).join("plugin.toml");
+                if let Err(reload_error) = activate_installed_plugin(&state, &author, &plugin_id, &manifest_path) {
+                    eprintln!("Warning: Failed to reload previous version of plugin {}: {}", plugin_id, reload_error);
+                }
+            }
+            Err(e)
+        }
+    }
+}
+
+/// Move a staged plugin into place, record it and activate its backend.
+/// On error the DB row is restored; the caller rolls back the files.
+fn install_staged(
+    state: &AppState,
+    staged: &mut StagedInstall,
+    author: &str,
+    plugin_id: &str,
+    repository_url: &str,
+) -> Result<(), String> {
+    let installed_manifest = staged.manifest().clone();
+    let manifest_path = staged.commit()?;
     
     let frontend_entry = installed_manifest.frontend.as_ref()
         .and_then(|f| f.entry.clone());
@@ -279,59 +274,200 @@ pub async fn install_plugin(
         .and_then(|f| f.components.clone())
         .map(|components| serde_json::to_string(&components).unwrap_or_default());
     
+    let previous_row = state.db.get_installed_plugins()?
+        .into_iter()
+        .find(|(id, _, _, _, _, _, _, _, _, _)| id == plugin_id);
     state.db.install_plugin_with_repo(
-        &plugin_id,
-        &installed_manifest.plugin.display_name.unwrap_or(installed_manifest.plugin.name.clone()),
+        plugin_id,
+        &installed_manifest.plugin.display_name.clone().unwrap_or(installed_manifest.plugin.name.clone()),
         &installed_manifest.plugin.version,
         Some(&installed_manifest.plugin.description),
-        Some(&repository_url),
+        Some(repository_url),
         manifest_path.to_str(),
         frontend_entry.as_deref(),
         frontend_components.as_deref(),
-        Some(&author),
+        Some(author),
     )?;
     
+    // Plugins with a backend must load and initialize (which applies their schema);
+    // otherwise the DB row is rolled back too
+    if installed_manifest.backend.is_some() {
+        if let Err(e) = activate_installed_plugin(state, author, plugin_id, &manifest_path) {
+            if let Some(plugin_loader) = &state.plugin_loader {
+                plugin_loader.unload_plugin_library(plugin_id).ok();
+            }
+            if let Err(restore_error) = restore_plugin_row(state, plugin_id, previous_row) {
+                eprintln!("Warning: Failed to roll back plugin {} record: {}", plugin_id, restore_error);
+            }
+            return Err(format!("Failed to install plugin {}: {}", plugin_id, e));
+        }
+    }
+    Ok(())
+}
+
+/// Load, initialize and register a freshly installed plugin
+fn activate_installed_plugin(
+    state: &AppState,
+    author: &str,
+    plugin_id: &str,
+    manifest_path: &std::path::Path,
+) -> Result<(), String> {
+    let (Some(plugin_registry), Some(extension_registry)) = (&state.plugin_registry, &state.extension_registry) else {
+        return Ok(());
+    };
+    let app_loader = state.plugin_loader.as_ref()
+        .ok_or_else(|| "Plugin loader not available".to_string())?;
+    
+    let mut plugin = app_loader.load_dynamic_plugin(author, plugin_id)?;
+    
+    use crate::plugin_system::api::PluginAPI;
+    use time_tracker_plugin_sdk::PluginAPIInterface;
+    
+    // Load manifest and register exposed tables before initialization
+    if let Ok(manifest) = app_loader.load_manifest(manifest_path) {
+        if let Some(ref exposed_tables) = manifest.plugin.exposed_tables {
+            if let Err(e) = extension_registry.register_exposed_tables(plugin_id, exposed_tables) {
+                eprintln!("Warning: Failed to register exposed tables for plugin {}: {}", plugin_id, e);
+            }
+        }
+    }
+    
+    let api = PluginAPI::new(Arc::clone(&state.db), Arc::clone(extension_registry), Arc::clone(&state.event_bus), Arc::clone(&state.data_sources), plugin_id.to_string());
+    plugin.initialize(&api as &dyn PluginAPIInterface)
+        .map_err(|e| format!("initialization failed: {}", e))?;
+    plugin_registry.register(plugin)?;
+    eprintln!("Loaded and registered plugin after installation: {}", plugin_id);
+    Ok(())
+}
+
+/// Shut down and unregister a running plugin and release its library.
+/// Returns true if the plugin was registered.
+fn release_plugin(state: &AppState, plugin_id: &str) -> bool {
+    let Some(plugin_registry) = &state.plugin_registry else {
+        return false;
+    };
+    let registered = match plugin_registry.shutdown(plugin_id) {
+        Ok(registered) => registered,
+        Err(e) => {
+            eprintln!("Warning: Plugin {} failed to shut down: {}", plugin_id, e);
+            true
+        }
+    };
+    if registered {
+        plugin_registry.unregister(plugin_id).ok();
+    }
+    state.data_sources.unregister(&format!("{}{}", SOURCE_PLUGIN_PREFIX, plugin_id));
+    if let Some(extension_registry) = &state.extension_registry {
+        if let Err(e) = extension_registry.unregister_plugin(plugin_id) {
+            eprintln!("Warning: Failed to drop extensions of plugin {}: {}", plugin_id, e);
+        }
+    }
+    if let Some(plugin_loader) = &state.plugin_loader {
+        plugin_loader.unload_plugin_library(plugin_id).ok();
+    }
+    registered
+}
+
+/// Shut down and unregister every loaded plugin and release all plugin libraries
+pub(crate) fn unload_all_plugins(state: &AppState) {
     if let Some(plugin_registry) = &state.plugin_registry {
-        if let Some(extension_registry) = &state.extension_registry {
-            let app_loader = state.plugin_loader.as_ref()
-                .ok_or_else(|| "Plugin loader not available".to_string())?;
-            
-            match app_loader.load_dynamic_plugin(&author, &plugin_id) {
-                Ok(mut plugin) => {
-                    use crate::plugin_system::api::PluginAPI;
-                    use time_tracker_plugin_sdk::PluginAPIInterface;
-                    
-                    // Load manifest and register exposed tables before initialization
-                    if let Ok(manifest) = app_loader.load_manifest(&manifest_path) {
-                        if let Some(ref exposed_tables) = manifest.plugin.exposed_tables {
-                            if let Err(e) = extension_registry.register_exposed_tables(&plugin_id, exposed_tables) {
-                                eprintln!("Warning: Failed to register exposed tables for plugin {}: {}", plugin_id, e);
-                            }
+        for plugin_id in plugin_registry.get_plugin_ids() {
+            release_plugin(state, &plugin_id);
+        }
+    }
+    // Libraries of plugins that failed to initialize are loaded but not registered
+    if let Some(plugin_loader) = &state.plugin_loader {
+        plugin_loader.unload_all_libraries();
+    }
+}
+
+/// Load, initialize and register every enabled plugin installed in the current database
+pub(crate) fn load_installed_plugins(state: &AppState) {
+    let (Some(plugin_registry), Some(extension_registry), Some(plugin_loader)) =
+        (&state.plugin_registry, &state.extension_registry, &state.plugin_loader)
+    else {
+        return;
+    };
+    use crate::plugin_system::api::PluginAPI;
+    use time_tracker_plugin_sdk::PluginAPIInterface;
+
+    match plugin_loader.load_all_installed_plugins(&state.db) {
+        Ok(dynamic_plugins) => {
+            // First pass: Load manifests and register exposed tables
+            let installed_plugins = state.db.get_installed_plugins().unwrap_or_default();
+            for (plugin_id, _name, _version, _description, _repo_url, manifest_path, _frontend_entry, _frontend_components, _author, enabled) in installed_plugins {
+                if !enabled {
+                    continue;
+                }
+                let Some(manifest_path) = manifest_path else {
+                    continue;
+                };
+                if let Ok(manifest) = plugin_loader.load_manifest(std::path::Path::new(&manifest_path)) {
+                    if let Some(ref exposed_tables) = manifest.plugin.exposed_tables {
+                        if let Err(e) = extension_registry.register_exposed_tables(&plugin_id, exposed_tables) {
+                            eprintln!("Warning: Failed to register exposed tables for plugin {}: {}", plugin_id, e);
                         }
                     }
-                    
-                    let api = PluginAPI::new(Arc::clone(&state.db), Arc::clone(extension_registry), plugin_id.clone());
-                    match plugin.initialize(&api as &dyn PluginAPIInterface) {
-                        Ok(()) => {
-                            if let Err(e) = plugin_registry.register(plugin) {
-                                eprintln!("Warning: Failed to register plugin {} after installation: {}", plugin_id, e);
-                            } else {
-                                eprintln!("Loaded and registered plugin after installation: {}", plugin_id);
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Failed to initialize plugin {} after installation: {}", plugin_id, e);
+                }
+            }
+
+            // Second pass: Initialize plugins
+            for (plugin_id, mut plugin) in dynamic_plugins {
+                let api = PluginAPI::new(
+                    Arc::clone(&state.db),
+                    Arc::clone(extension_registry),
+                    Arc::clone(&state.event_bus),
+                    Arc::clone(&state.data_sources),
+                    plugin_id.clone(),
+                );
+                match plugin.initialize(&api as &dyn PluginAPIInterface) {
+                    Ok(()) => {
+                        if let Err(e) = plugin_registry.register(plugin) {
+                            eprintln!("Warning: Failed to register dynamic plugin {}: {}", plugin_id, e);
+                        } else {
+                            eprintln!("Initialized and registered dynamic plugin: {}", plugin_id);
                         }
                     }
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to load plugin {} after installation: {}", plugin_id, e);
+                    Err(e) => {
+                        eprintln!("Warning: Failed to initialize dynamic plugin {}: {}", plugin_id, e);
+                    }
                 }
             }
         }
+        Err(e) => {
+            eprintln!("Warning: Failed to load dynamic plugins: {}", e);
+        }
+    }
+
+    // Apply plugin extensions to database schema
+    if let Err(e) = state.db.apply_plugin_extensions(extension_registry) {
+        eprintln!("Warning: Failed to apply plugin extensions: {}", e);
+    }
+}
+
+/// Put back the plugin's DB record as it was before a failed install
+fn restore_plugin_row(
+    state: &AppState,
+    plugin_id: &str,
+    previous: Option<InstalledPluginRow>,
+) -> Result<(), String> {
+    match previous {
+        Some((id, name, version, description, repository_url, manifest_path, frontend_entry, frontend_components, author, enabled)) => {
+            state.db.install_plugin_with_repo(
+                &id,
+                &name,
+                &version,
+                description.as_deref(),
+                repository_url.as_deref(),
+                manifest_path.as_deref(),
+                frontend_entry.as_deref(),
+                frontend_components.as_deref(),
+                author.as_deref(),
+            )?;
+            state.db.set_plugin_enabled(&id, enabled)
+        }
+        None => state.db.uninstall_plugin(plugin_id),
     }
-    
-    Ok(())
 }
 
 /// List all installed plugins
@@ -418,19 +554,7 @@ pub fn disable_plugin(
     plugin_id: String,
 ) -> Result<(), String> {
     state.db.set_plugin_enabled(&plugin_id, false)?;
-    
-    if let Some(plugin_registry) = &state.plugin_registry {
-        if let Err(e) = plugin_registry.unregister(&plugin_id) {
-            eprintln!("Warning: Failed to unregister plugin {}: {}", plugin_id, e);
-        }
-    }
-    
-    if let Some(plugin_loader) = &state.plugin_loader {
-        if let Err(e) = plugin_loader.unload_plugin_library(&plugin_id) {
-            eprintln!("Warning: Failed to unload plugin {} library: {}", plugin_id, e);
-        }
-    }
-    
+    release_plugin(&state, &plugin_id);
     Ok(())
 }
 
@@ -449,6 +573,12 @@ pub fn load_plugin(
         return Err("Plugin is disabled".to_string());
     }
     
+    if let (Some(loader), Some(manifest_path_str)) = (&state.plugin_loader, &plugin_info.5) {
+        if let Ok(manifest) = loader.load_manifest(std::path::Path::new(manifest_path_str)) {
+            check_manifest_compatibility(&manifest)?;
+        }
+    }
+    
     let author = if let Some(auth) = &plugin_info.8 {
         auth.clone()
     } else {
@@ -491,7 +621,7 @@ pub fn load_plugin(
                         }
                     }
                     
-                    let api = PluginAPI::new(Arc::clone(&state.db), Arc::clone(extension_registry), plugin_id.clone());
+                    let api = PluginAPI::new(Arc::clone(&state.db), Arc::clone(extension_registry), Arc::clone(&state.event_bus), Arc::clone(&state.data_sources), plugin_id.clone());
                     match plugin.initialize(&api as &dyn PluginAPIInterface) {
                         Ok(()) => {
                             plugin_registry.register(plugin)
@@ -518,25 +648,17 @@ pub fn unload_plugin(
     state: State<'_, AppState>,
     plugin_id: String,
 ) -> Result<(), String> {
-    if let Some(plugin_registry) = &state.plugin_registry {
-        if let Err(e) = plugin_registry.unregister(&plugin_id) {
-            return Err(format!("Failed to unregister plugin: {}", e));
-        }
-    } else {
+    if state.plugin_registry.is_none() {
         return Err("Plugin registry not available".to_string());
     }
-    
-    if let Some(plugin_loader) = &state.plugin_loader {
-        if let Err(e) = plugin_loader.unload_plugin_library(&plugin_id) {
-            eprintln!("Warning: Failed to unload plugin {} library: {}", plugin_id, e);
-        }
+    if !release_plugin(&state, &plugin_id) {
+        return Err(format!("Failed to unregister plugin: Plugin {} not found in registry", plugin_id));
     }
-    
     Ok(())
 }
 
 /// Helper to invoke a plugin command with PluginAPI
-fn invoke_plugin_command_with_api(
+pub(crate) fn invoke_plugin_command_with_api(
     state: &AppState,
     plugin_id: &str,
     command: &str,
@@ -553,23 +675,50 @@ fn invoke_plugin_command_with_api(
     let api = PluginAPI::new(
         Arc::clone(&state.db),
         Arc::clone(extension_registry),
+        Arc::clone(&state.event_bus),
+        Arc::clone(&state.data_sources),
         plugin_id.to_string(),
     );
     
     registry.invoke_plugin_command(plugin_id, command, params, &api as &dyn PluginAPIInterface)
 }
 
+/// `invoke_plugin_command` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct InvokePluginCommandRequest {
+    pub plugin_id: String,
+    pub command: String,
+    pub params: serde_json::Value,
+}
+
 /// Invoke a command on a plugin
 #[tauri::command]
 pub fn invoke_plugin_command(
     state: State<'_, AppState>,
-    plugin_id: String,
-    command: String,
-    params: serde_json::Value,
+    request: InvokePluginCommandRequest,
 ) -> Result<serde_json::Value, String> {
+    let InvokePluginCommandRequest { plugin_id, command, params } = request;
     invoke_plugin_command_with_api(&state, &plugin_id, &command, params)
 }
 
+/// Get call counts and wall time per plugin since the app started
+#[tauri::command]
+pub fn get_plugin_metrics(state: State<'_, AppState>) -> Result<Vec<PluginMetrics>, String> {
+    let registry = state.plugin_registry.as_ref()
+        .ok_or_else(|| "Plugin registry not available".to_string())?;
+    Ok(registry.get_metrics())
+}
+
+/// Reset plugin usage metrics
+#[tauri::command]
+pub fn reset_plugin_metrics(state: State<'_, AppState>) -> Result<(), String> {
+    let registry = state.plugin_registry.as_ref()
+        .ok_or_else(|| "Plugin registry not available".to_string())?;
+    registry.reset_metrics();
+    Ok(())
+}
+
 /// Check if a plugin is installed
 #[tauri::command]
 pub fn is_plugin_installed(
@@ -639,13 +788,21 @@ pub fn get_plugins_directory(state: State<'_, AppState>) -> Result<String, Strin
         .map(|loader| loader.plugins_dir().to_string_lossy().to_string())
 }
 
+/// `check_plugin_installed` and `get_plugin_manifest_path` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct PluginLocationRequest {
+    pub author: String,
+    pub plugin_id: String,
+}
+
 /// Check if a plugin is installed on disk (by author and plugin_id)
 #[tauri::command]
 pub fn check_plugin_installed(
     state: State<'_, AppState>,
-    author: String,
-    plugin_id: String,
+    request: PluginLocationRequest,
 ) -> Result<bool, String> {
+    let PluginLocationRequest { author, plugin_id } = request;
     Ok(state
         .plugin_loader
         .as_ref()
@@ -657,9 +814,9 @@ pub fn check_plugin_installed(
 #[tauri::command]
 pub fn get_plugin_manifest_path(
     state: State<'_, AppState>,
-    author: String,
-    plugin_id: String,
+    request: PluginLocationRequest,
 ) -> Result<Option<String>, String> {
+    let PluginLocationRequest { author, plugin_id } = request;
     let loader = state
         .plugin_loader
         .as_ref()
@@ -0,0 +1,49 @@
+//! Settings profile commands
+
+use crate::commands::common::AppState;
+use crate::database::settings::canonicalize_settings;
+use crate::database::SettingsProfile;
+use crate::profiles;
+use crate::settings::validate_value;
+use tauri::State;
+
+/// Get all settings profiles
+#[tauri::command]
+pub fn get_profiles(state: State<'_, AppState>) -> Result<Vec<SettingsProfile>, String> {
+    state.db.get_settings_profiles().map_err(|e| e.to_string())
+}
+
+/// Create or update a settings profile
+#[tauri::command]
+pub fn save_profile(state: State<'_, AppState>, mut profile: SettingsProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    canonicalize_settings(&mut profile.settings);
+    for (key, value) in &profile.settings {
+        validate_value(key, value)?;
+    }
+    state.db.save_settings_profile(&profile).map_err(|e| e.to_string())
+}
+
+/// Delete a settings profile
+#[tauri::command]
+pub fn delete_profile(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    state.db.delete_settings_profile(&name).map_err(|e| e.to_string())?;
+    if state.db.get_setting("active_profile").map_err(|e| e.to_string())?.as_deref() == Some(name.as_str()) {
+        state.db.delete_setting("active_profile").map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Switch to a settings profile
+#[tauri::command]
+pub fn switch_profile(state: State<'_, AppState>, name: String) -> Result<(), String> {
+    profiles::switch_profile(&state.db, &state.settings, &state.event_bus, &name)
+}
+
+/// Get the name of the active profile
+#[tauri::command]
+pub fn get_active_profile(state: State<'_, AppState>) -> Result<Option<String>, String> {
+    state.db.get_setting("active_profile").map_err(|e| e.to_string())
+}
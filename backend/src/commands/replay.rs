@@ -0,0 +1,55 @@
+//! Replay commands (development builds only)
+
+use crate::commands::common::{ensure_dev_build, AppState};
+use crate::replay::{self, ReplayOptions, ReplayStatus, ReplayTarget};
+use serde::Deserialize;
+use std::path::Path;
+use tauri::State;
+
+/// `start_replay` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct StartReplayRequest {
+    pub file_path: String,
+    #[serde(default)]
+    pub speed: Option<f64>,
+}
+
+/// Replay a recorded sample file into the current workspace.
+/// `speed` is recording seconds per second (default 1); 0 replays without waiting.
+#[tauri::command]
+pub fn start_replay(state: State<'_, AppState>, request: StartReplayRequest) -> Result<ReplayStatus, String> {
+    ensure_dev_build("Replay")?;
+    let StartReplayRequest { file_path, speed } = request;
+    let speed = speed.unwrap_or(1.0);
+    if !speed.is_finite() || speed < 0.0 {
+        return Err(format!("Invalid replay speed: {}", speed));
+    }
+    let samples = replay::load_recording(Path::new(&file_path))?;
+    let settings = state.settings.get();
+    let target = ReplayTarget {
+        db: state.db.clone(),
+        extension_registry: state.extension_registry.clone(),
+        plugin_registry: state.plugin_registry.clone(),
+    };
+    let options = ReplayOptions {
+        speed,
+        idle_threshold_secs: settings.idle_threshold_seconds as u64,
+        capture_context: settings.capture_context_ref,
+    };
+    state.replay.start(target, samples, options, file_path)?;
+    Ok(state.replay.status())
+}
+
+/// Stop the running replay
+#[tauri::command]
+pub fn stop_replay(state: State<'_, AppState>) -> Result<(), String> {
+    state.replay.stop();
+    Ok(())
+}
+
+/// Get the progress of the current or last replay
+#[tauri::command]
+pub fn get_replay_status(state: State<'_, AppState>) -> Result<ReplayStatus, String> {
+    Ok(state.replay.status())
+}
@@ -0,0 +1,67 @@
+//! Time entry rounding commands
+
+use crate::commands::common::AppState;
+use crate::database::approvals::{APPROVAL_ENTITY_ACTIVITY, APPROVAL_ENTITY_MANUAL_ENTRY};
+use crate::rounding::{self, RoundedEntry, RoundingPolicy, RoundingPreview};
+use crate::timeline::merge_activities;
+use serde::Deserialize;
+use tauri::State;
+use time_tracker_plugin_sdk::validation;
+
+/// `preview_rounding` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct PreviewRoundingRequest {
+    pub start: i64,
+    pub end: i64,
+    pub policy: RoundingPolicy,
+    /// Activity blocks shorter than this are folded into the block before
+    /// them, as in `get_timeline` (default 0)
+    #[serde(default)]
+    pub min_block_sec: Option<i64>,
+}
+
+/// Preview `policy` on the manual entries and (non-idle) activity blocks of a
+/// range: raw and rounded duration per entry and the total difference.
+/// Activities are merged into the contiguous blocks of `get_timeline` first.
+/// Nothing is changed.
+#[tauri::command]
+pub fn preview_rounding(
+    state: State<'_, AppState>,
+    request: PreviewRoundingRequest,
+) -> Result<RoundingPreview, String> {
+    let PreviewRoundingRequest { start, end, policy, min_block_sec } = request;
+    validation::query_range(Some(start), Some(end))?;
+    policy.validate()?;
+
+    let entry = |entity_type: &str, entity_id, label, activity_count, category_id, started_at, raw_sec| RoundedEntry {
+        entity_type: entity_type.to_string(),
+        entity_id,
+        label,
+        activity_count,
+        category_id,
+        started_at,
+        raw_sec,
+        rounded_sec: 0,
+        delta_sec: 0,
+    };
+    let mut entries: Vec<RoundedEntry> = state
+        .db
+        .get_manual_entries(start, end)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|e| {
+            let label = e.description.unwrap_or_default();
+            entry(APPROVAL_ENTITY_MANUAL_ENTRY, e.id, label, 1, e.category_id, e.started_at, e.ended_at - e.started_at)
+        })
+        .collect();
+    let activities = state
+        .db
+        .get_activities(start, end, None, None, Some(true), None, None)
+        .map_err(|e| e.to_string())?;
+    entries.extend(merge_activities(&activities, min_block_sec.unwrap_or(0).max(0)).into_iter().map(|b| {
+        let (id, count) = (b.first_activity_id, b.activity_count);
+        entry(APPROVAL_ENTITY_ACTIVITY, id, b.app_name, count, b.category_id, b.started_at, b.duration_sec)
+    }));
+    Ok(rounding::preview(&policy, entries))
+}
@@ -1,67 +1,185 @@
 //! Rule management commands
 
+use crate::app_metrics;
 use crate::database::Rule;
+use crate::database::rules::{
+    merged_pattern, normalize_pattern, RuleImportStrategy, RuleImportSummary, RulePack, RULE_PACK_FORMAT,
+    RULE_PACK_VERSION,
+};
 use crate::commands::common::AppState;
+use serde::Deserialize;
 use tauri::State;
 
+/// What `add_rule` / `create_rule` do when a similar rule exists
+/// (same type, pattern looking for the same text, see `find_similar_rule`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SimilarRuleAction {
+    /// Fail, so the user can choose to merge or create anyway
+    #[default]
+    Reject,
+    /// Add the rule next to the similar one
+    Create,
+    /// Update the similar rule instead: the requested category, the higher
+    /// priority, the pattern matching more and the requested project if any
+    Merge,
+}
+
+/// `add_rule` / `create_rule` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RuleRequest {
+    pub rule_type: String,
+    pub pattern: String,
+    pub category_id: i64,
+    pub priority: i64,
+    /// Project matching activities are assigned to (projects plugin)
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default)]
+    pub on_similar: SimilarRuleAction,
+}
+
+/// `update_rule` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateRuleRequest {
+    pub id: i64,
+    pub rule_type: String,
+    pub pattern: String,
+    pub category_id: i64,
+    pub priority: i64,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+}
+
+/// `find_similar_rule` request; `exclude_id` skips the rule being edited
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SimilarRuleRequest {
+    pub rule_type: String,
+    pub pattern: String,
+    #[serde(default)]
+    pub exclude_id: Option<i64>,
+}
+
+/// `export_rules` request; without `category_ids` all rules are exported
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ExportRulesRequest {
+    pub file_path: String,
+    #[serde(default)]
+    pub category_ids: Option<Vec<i64>>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
 /// Get all rules
 #[tauri::command]
 pub fn get_rules(state: State<'_, AppState>) -> Result<Vec<Rule>, String> {
-    state.db.get_rules().map_err(|e| e.to_string())
+    state.storage.get_rules().map_err(|e| e.to_string())
+}
+
+/// Rules can't point at archived categories
+fn ensure_category_active(state: &AppState, category_id: i64) -> Result<(), String> {
+    let archived = state
+        .storage
+        .get_categories()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .any(|c| c.id == category_id && c.is_archived);
+    if archived {
+        return Err("Category is archived; unarchive it to add rules".to_string());
+    }
+    Ok(())
+}
+
+/// Add the rule of `request`, or merge it into a similar one; returns the rule ID
+pub(crate) fn insert_rule(state: &AppState, request: RuleRequest) -> Result<i64, String> {
+    ensure_category_active(state, request.category_id)?;
+    let pattern = normalize_pattern(&request.pattern);
+    let similar = match request.on_similar {
+        SimilarRuleAction::Create => None,
+        _ => state.storage.find_similar_rule(&request.rule_type, &pattern, None).map_err(|e| e.to_string())?,
+    };
+    let id = match similar {
+        None => state
+            .storage
+            .add_rule(&request.rule_type, &pattern, request.category_id, request.priority, request.project_id)
+            .map_err(|e| e.to_string())?,
+        Some(similar) if request.on_similar == SimilarRuleAction::Merge => {
+            state
+                .storage
+                .update_rule(
+                    similar.id,
+                    &similar.rule_type,
+                    &merged_pattern(&similar.pattern, &pattern),
+                    request.category_id,
+                    similar.priority.max(request.priority),
+                    request.project_id.or(similar.project_id),
+                )
+                .map_err(|e| e.to_string())?;
+            similar.id
+        }
+        Some(similar) => {
+            return Err(format!(
+                "A similar rule already exists ({} \"{}\"); merge into it or create the rule anyway",
+                similar.rule_type, similar.pattern
+            ))
+        }
+    };
+    app_metrics::record(&state.db, app_metrics::METRIC_RULE_EDITED);
+    Ok(id)
 }
 
 /// Add a new rule
 #[tauri::command]
-pub fn add_rule(
-    state: State<'_, AppState>,
-    rule_type: String,
-    pattern: String,
-    category_id: i64,
-    priority: i64,
-) -> Result<i64, String> {
-    state
-        .db
-        .add_rule(&rule_type, &pattern, category_id, priority)
-        .map_err(|e: rusqlite::Error| e.to_string())
+pub fn add_rule(state: State<'_, AppState>, request: RuleRequest) -> Result<i64, String> {
+    insert_rule(&state, request)
 }
 
 /// Create rule
 #[tauri::command]
-pub fn create_rule(
-    state: State<'_, AppState>,
-    rule_type: String,
-    pattern: String,
-    category_id: i64,
-    priority: i64,
-) -> Result<Rule, String> {
-    let id = state
-        .db
-        .add_rule(&rule_type, &pattern, category_id, priority)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
-    
+pub fn create_rule(state: State<'_, AppState>, request: RuleRequest) -> Result<Rule, String> {
+    let id = insert_rule(&state, request)?;
+
     state
-        .db
+        .storage
         .get_rules()
-        .map_err(|e: rusqlite::Error| e.to_string())?
+        .map_err(|e| e.to_string())?
         .into_iter()
         .find(|r| r.id == id)
         .ok_or_else(|| "Failed to retrieve created rule".to_string())
 }
 
+/// Find a rule similar to one about to be added (same type, pattern looking
+/// for the same text ignoring case and wildcards), to offer merging into it
+#[tauri::command]
+pub fn find_similar_rule(state: State<'_, AppState>, request: SimilarRuleRequest) -> Result<Option<Rule>, String> {
+    state
+        .storage
+        .find_similar_rule(&request.rule_type, &request.pattern, request.exclude_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Update rule
 #[tauri::command]
-pub fn update_rule(
-    state: State<'_, AppState>,
-    id: i64,
-    rule_type: String,
-    pattern: String,
-    category_id: i64,
-    priority: i64,
-) -> Result<Rule, String> {
+pub fn update_rule(state: State<'_, AppState>, request: UpdateRuleRequest) -> Result<Rule, String> {
+    let UpdateRuleRequest { id, rule_type, pattern, category_id, priority, project_id } = request;
+    let moved = state
+        .storage
+        .get_rules()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .any(|r| r.id == id && r.category_id != category_id);
+    if moved {
+        ensure_category_active(&state, category_id)?;
+    }
     state
-        .db
-        .update_rule(id, &rule_type, &pattern, category_id, priority)
-        .map_err(|e: rusqlite::Error| e.to_string())?;
+        .storage
+        .update_rule(id, &rule_type, &pattern, category_id, priority, project_id)
+        .map_err(|e| e.to_string())?;
+    app_metrics::record(&state.db, app_metrics::METRIC_RULE_EDITED);
     
     // Return updated rule without querying DB again
     Ok(Rule {
@@ -70,11 +188,65 @@ pub fn update_rule(
         pattern,
         category_id,
         priority,
+        project_id,
     })
 }
 
 /// Delete a rule
 #[tauri::command]
 pub fn delete_rule(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.db.delete_rule(id).map_err(|e| e.to_string())
+    state.storage.delete_rule(id).map_err(|e| e.to_string())?;
+    app_metrics::record(&state.db, app_metrics::METRIC_RULE_EDITED);
+    Ok(())
+}
+
+/// Export rules as a shareable rule pack (all rules, or those of some categories)
+#[tauri::command]
+pub fn export_rules(state: State<'_, AppState>, request: ExportRulesRequest) -> Result<usize, String> {
+    let ExportRulesRequest { file_path, category_ids, name } = request;
+    let pack = state
+        .storage
+        .export_rule_pack(name.as_deref(), category_ids.as_deref())
+        .map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&pack)
+        .map_err(|e| format!("Failed to serialize rules: {}", e))?;
+    std::fs::write(&file_path, json)
+        .map_err(|e| format!("Failed to write rules file: {}", e))?;
+    Ok(pack.rules.len())
+}
+
+/// `import_rules` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ImportRulesRequest {
+    pub file_path: String,
+    pub strategy: RuleImportStrategy,
+}
+
+/// Import a rule pack. `strategy` decides what happens to rules similar to an
+/// existing one (see `find_similar_rule`): `skip`, `overwrite` or `merge`.
+#[tauri::command]
+pub fn import_rules(
+    state: State<'_, AppState>,
+    request: ImportRulesRequest,
+) -> Result<RuleImportSummary, String> {
+    let ImportRulesRequest { file_path, strategy } = request;
+    let json = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Failed to read rules file: {}", e))?;
+    let pack: RulePack = serde_json::from_str(&json)
+        .map_err(|e| format!("Invalid rules file: {}", e))?;
+    if pack.format != RULE_PACK_FORMAT {
+        return Err("Not a rules file".to_string());
+    }
+    if pack.version > RULE_PACK_VERSION {
+        return Err(format!("Rules file version {} is not supported", pack.version));
+    }
+    let summary = state
+        .storage
+        .import_rule_pack(&pack, strategy)
+        .map_err(|e| e.to_string())?;
+    if summary.added > 0 || summary.updated > 0 {
+        app_metrics::record(&state.db, app_metrics::METRIC_RULE_EDITED);
+    }
+    Ok(summary)
 }
@@ -1,9 +1,36 @@
 //! Statistics commands
+//!
+//! Every query here goes through `AppState::storage` (see `database::storage`).
 
-use crate::commands::common::AppState;
-use crate::database::RangeStats;
+use crate::commands::common::{AppState, RangeRequest};
+use crate::database::devices::DeviceSummary;
+use crate::database::{CodingStats, InputIntensityStat, MoodSummary, RangeStats};
+use crate::report_cache::Dimension;
 use tauri::State;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+/// `get_stats` request; `sources` and `devices` narrow it down
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct StatsRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub sources: Option<Vec<String>>,
+    #[serde(default)]
+    pub devices: Option<Vec<String>>,
+}
+
+/// `get_top_apps` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct TopAppsRequest {
+    pub start: i64,
+    pub end: i64,
+    pub limit: i64,
+    #[serde(default)]
+    pub half_life_days: Option<f64>,
+}
 
 /// Stats response structure
 #[derive(Serialize)]
@@ -12,6 +39,8 @@ pub struct StatsResponse {
     pub productive_seconds: i64,
     pub category_breakdown: Vec<CategoryTime>,
     pub app_breakdown: Vec<AppTime>,
+    /// Mood journal entries logged in the range
+    pub mood: MoodSummary,
 }
 
 #[derive(Serialize)]
@@ -28,19 +57,25 @@ pub struct AppTime {
     pub seconds: i64,
 }
 
-/// Get aggregated stats for a time range (SQL aggregation)
+/// Get aggregated stats for a time range (SQL aggregation), optionally only for
+/// some sources or devices (see `get_devices`). Unfiltered stats for the
+/// current week come from the week cache.
 #[tauri::command]
-pub fn get_stats(
-    state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-) -> Result<StatsResponse, String> {
+pub fn get_stats(state: State<'_, AppState>, request: StatsRequest) -> Result<StatsResponse, String> {
+    let StatsRequest { start, end, sources, devices } = request;
+    let compute = || state.storage.get_stats_for_range(start, end, sources.as_deref(), devices.as_deref(), false);
+    let stats = if sources.is_none() && devices.is_none() {
+        state.week_cache.get_or_compute(start, end, Dimension::Summary, compute)
+    } else {
+        compute()
+    };
     let RangeStats {
         total_seconds,
         productive_seconds,
         category_breakdown: category_rows,
         app_breakdown: app_rows,
-    } = state.db.get_stats_for_range(start, end).map_err(|e| e.to_string())?;
+    } = stats.map_err(|e| e.to_string())?;
+    let mood = state.storage.get_mood_summary(start, end).map_err(|e| e.to_string())?;
 
     let category_breakdown: Vec<CategoryTime> = category_rows
         .into_iter()
@@ -62,6 +97,7 @@ pub fn get_stats(
         productive_seconds,
         category_breakdown,
         app_breakdown,
+        mood,
     })
 }
 
@@ -71,7 +107,8 @@ pub fn get_daily_stats(
     state: State<'_, AppState>,
     date: i64,
 ) -> Result<serde_json::Value, String> {
-    let stats = state.db.get_daily_stats(date).map_err(|e| e.to_string())?;
+    let stats = state.storage.get_daily_stats(date).map_err(|e| e.to_string())?;
+    let mood = state.storage.get_mood_summary(date, date + 86400).map_err(|e| e.to_string())?;
     
     Ok(serde_json::json!({
         "total_duration_sec": stats.total_seconds,
@@ -100,18 +137,22 @@ pub fn get_daily_stats(
                 "sort_order": c.sort_order,
             })),
         })).collect::<Vec<_>>(),
+        "mood": mood,
     }))
 }
 
-/// Get top apps
+/// Get top apps. With `half_life_days` (e.g. 7 for "frequent apps" pickers)
+/// they are ranked by decayed time, so recent usage weighs more than history.
 #[tauri::command]
-pub fn get_top_apps(
-    state: State<'_, AppState>,
-    start: i64,
-    end: i64,
-    limit: i64,
-) -> Result<Vec<serde_json::Value>, String> {
-    let apps = state.db.get_top_apps(start, end, limit).map_err(|e| e.to_string())?;
+pub fn get_top_apps(state: State<'_, AppState>, request: TopAppsRequest) -> Result<Vec<serde_json::Value>, String> {
+    let TopAppsRequest { start, end, limit, half_life_days } = request;
+    let apps = match half_life_days {
+        Some(half_life_days) => state.storage.get_top_apps_decayed(start, end, limit, half_life_days),
+        None => state
+            .week_cache
+            .get_or_compute(start, end, Dimension::Apps(limit), || state.storage.get_top_apps(start, end, limit)),
+    }
+    .map_err(|e| e.to_string())?;
     
     Ok(apps.iter().map(|app| serde_json::json!({
         "app_name": app.app_name,
@@ -131,10 +172,13 @@ pub fn get_top_apps(
 #[tauri::command]
 pub fn get_category_usage(
     state: State<'_, AppState>,
-    start: i64,
-    end: i64,
+    request: RangeRequest,
 ) -> Result<Vec<serde_json::Value>, String> {
-    let usage = state.db.get_category_usage(start, end).map_err(|e| e.to_string())?;
+    let RangeRequest { start, end } = request;
+    let usage = state
+        .week_cache
+        .get_or_compute(start, end, Dimension::Categories, || state.storage.get_category_usage(start, end))
+        .map_err(|e| e.to_string())?;
     
     Ok(usage.iter().map(|u| serde_json::json!({
         "category": u.category.as_ref().map(|c| serde_json::json!({
@@ -156,7 +200,7 @@ pub fn get_hourly_activity(
     state: State<'_, AppState>,
     date: i64,
 ) -> Result<Vec<serde_json::Value>, String> {
-    let hourly = state.db.get_hourly_activity(date).map_err(|e| e.to_string())?;
+    let hourly = state.storage.get_hourly_activity(date).map_err(|e| e.to_string())?;
     
     Ok(hourly.iter().map(|h| serde_json::json!({
         "hour": h.hour,
@@ -168,8 +212,35 @@ pub fn get_hourly_activity(
 #[tauri::command]
 pub fn get_productive_time(
     state: State<'_, AppState>,
-    start: i64,
-    end: i64,
+    request: RangeRequest,
 ) -> Result<i64, String> {
-    state.db.get_productive_time(start, end).map_err(|e| e.to_string())
+    let RangeRequest { start, end } = request;
+    state.storage.get_productive_time(start, end).map_err(|e| e.to_string())
+}
+
+/// Get coding time by language, project and file from editor heartbeats
+#[tauri::command]
+pub fn get_coding_stats(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<CodingStats, String> {
+    let RangeRequest { start, end } = request;
+    state.storage.get_coding_stats(start, end).map_err(|e| e.to_string())
+}
+
+/// Get active vs. passive time per app from input intensity counts
+/// (recorded while `record_input_intensity` is enabled)
+#[tauri::command]
+pub fn get_input_intensity(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<InputIntensityStat>, String> {
+    let RangeRequest { start, end } = request;
+    state.storage.get_input_intensity(start, end).map_err(|e| e.to_string())
+}
+
+/// Get the devices activities were recorded on (for the `devices` filter of `get_stats`)
+#[tauri::command]
+pub fn get_devices(state: State<'_, AppState>) -> Result<Vec<DeviceSummary>, String> {
+    state.storage.get_devices().map_err(|e| e.to_string())
 }
@@ -0,0 +1,267 @@
+//! Categorization suggestion commands
+
+use crate::classifier::{title_similarity, CategoryClassifier, CategorySuggestion, SimilarActivity};
+use crate::commands::common::{AppState, RangeRequest};
+use crate::commands::rules::{insert_rule, RuleRequest, SimilarRuleAction};
+use crate::database::common::SYSTEM_CATEGORY_UNCATEGORIZED;
+use crate::database::UncategorizedGroup;
+use crate::events::EVENT_ACTIVITIES_CHANGED;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::State;
+
+/// Default confidence required by `accept_category_suggestions`
+const DEFAULT_MIN_CONFIDENCE: f64 = 0.9;
+
+/// Default title similarity required by `find_similar_activities`
+const DEFAULT_MIN_SIMILARITY: f64 = 0.5;
+
+/// How far back `find_similar_activities` looks for candidates (90 days)
+const SIMILARITY_LOOKBACK_SECS: i64 = 90 * 86400;
+
+/// `accept_category_suggestions` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct AcceptSuggestionsRequest {
+    pub start: i64,
+    pub end: i64,
+    #[serde(default)]
+    pub min_confidence: Option<f64>,
+}
+
+/// `find_similar_activities` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct SimilarActivitiesRequest {
+    pub activity_id: i64,
+    #[serde(default)]
+    pub min_similarity: Option<f64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// `apply_category_to_activities` request; a `null` category clears it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ApplyCategoryRequest {
+    pub activity_ids: Vec<i64>,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+}
+
+fn train_classifier(state: &AppState) -> Result<CategoryClassifier, String> {
+    let samples = state
+        .db
+        .get_category_training_samples()
+        .map_err(|e| e.to_string())?;
+    Ok(CategoryClassifier::train(&samples))
+}
+
+/// Get suggested categories for an activity, learned from manual corrections.
+/// Returns an empty list until enough corrections have been recorded.
+#[tauri::command]
+pub fn get_category_suggestions(
+    state: State<'_, AppState>,
+    activity_id: i64,
+) -> Result<Vec<CategorySuggestion>, String> {
+    let activity = state
+        .db
+        .get_activity_by_id(activity_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Activity not found".to_string())?;
+
+    let classifier = train_classifier(&state)?;
+    Ok(classifier.suggest(
+        &activity.app_name,
+        activity.window_title.as_deref(),
+        activity.domain.as_deref(),
+    ))
+}
+
+/// Apply the top suggestion to every uncategorized activity in a time range
+/// whose confidence is at least `min_confidence` (default 0.9).
+/// Returns the number of activities categorized.
+#[tauri::command]
+pub fn accept_category_suggestions(
+    state: State<'_, AppState>,
+    request: AcceptSuggestionsRequest,
+) -> Result<i64, String> {
+    let AcceptSuggestionsRequest { start, end, min_confidence } = request;
+    let min_confidence = min_confidence.unwrap_or(DEFAULT_MIN_CONFIDENCE);
+    let classifier = train_classifier(&state)?;
+    if !classifier.is_ready() {
+        return Ok(0);
+    }
+
+    let activities = state
+        .db
+        .get_uncategorized_activities(start, end)
+        .map_err(|e| e.to_string())?;
+
+    let mut applied = 0;
+    for activity in activities {
+        let best = classifier
+            .suggest(&activity.app_name, activity.window_title.as_deref(), activity.domain.as_deref())
+            .into_iter()
+            .next();
+        if let Some(suggestion) = best.filter(|s| s.confidence >= min_confidence) {
+            // Accepted suggestions are not recorded as corrections so the model does not train on itself.
+            // Approved activities are skipped.
+            applied += state
+                .db
+                .update_activities_category(&[activity.id], Some(suggestion.category_id))
+                .map_err(|e| e.to_string())?
+                .len() as i64;
+        }
+    }
+
+    if applied > 0 {
+        state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "categorized": applied }));
+    }
+    Ok(applied)
+}
+
+/// Find activities whose window titles resemble the given activity's title,
+/// most similar first. Candidates are non-idle activities from 90 days before
+/// the activity onwards.
+#[tauri::command]
+pub fn find_similar_activities(
+    state: State<'_, AppState>,
+    request: SimilarActivitiesRequest,
+) -> Result<Vec<SimilarActivity>, String> {
+    let SimilarActivitiesRequest { activity_id, min_similarity, limit } = request;
+    let activity = state
+        .db
+        .get_activity_by_id(activity_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Activity not found".to_string())?;
+
+    let title = match activity.window_title.as_deref() {
+        Some(title) if !title.trim().is_empty() => title,
+        _ => return Ok(Vec::new()),
+    };
+    let min_similarity = min_similarity.unwrap_or(DEFAULT_MIN_SIMILARITY);
+
+    let candidates = state
+        .db
+        .get_activities(activity.started_at - SIMILARITY_LOOKBACK_SECS, i64::MAX, None, None, Some(true), None, None)
+        .map_err(|e| e.to_string())?;
+
+    let mut similar: Vec<SimilarActivity> = candidates
+        .into_iter()
+        .filter(|a| a.id != activity.id)
+        .filter_map(|a| {
+            let similarity = title_similarity(title, a.window_title.as_deref().unwrap_or(""));
+            (similarity >= min_similarity).then_some(SimilarActivity { activity: a, similarity })
+        })
+        .collect();
+
+    similar.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    if let Some(limit) = limit {
+        similar.truncate(limit);
+    }
+
+    Ok(similar)
+}
+
+/// Apply a category to several activities at once (e.g. the results of
+/// `find_similar_activities`). Recorded as manual corrections; approved
+/// activities are skipped.
+#[tauri::command]
+pub fn apply_category_to_activities(state: State<'_, AppState>, request: ApplyCategoryRequest) -> Result<(), String> {
+    apply_category(&state, &request.activity_ids, request.category_id).map(|_| ())
+}
+
+/// Get uncategorized time in a range grouped by domain or app, largest first,
+/// to work through the backlog with `categorize_group`
+#[tauri::command]
+pub fn get_uncategorized_summary(
+    state: State<'_, AppState>,
+    request: RangeRequest,
+) -> Result<Vec<UncategorizedGroup>, String> {
+    let RangeRequest { start, end } = request;
+    state.db.get_uncategorized_summary(start, end).map_err(|e| e.to_string())
+}
+
+/// `categorize_group` result
+#[derive(Debug, Clone, Serialize)]
+pub struct CategorizeGroupResult {
+    /// Activities categorized (approved ones are skipped)
+    pub updated: usize,
+    /// Rule created (or merged into) for future activities of the group
+    pub rule_id: Option<i64>,
+}
+
+/// `categorize_group` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CategorizeGroupRequest {
+    pub kind: String,
+    pub name: String,
+    pub category_id: i64,
+    pub create_rule: bool,
+}
+
+/// Categorize all uncategorized activities of a group from
+/// `get_uncategorized_summary` (`kind` `app` or `domain`), whatever their date.
+/// With `create_rule`, also adds a rule for the app or domain (merged into a
+/// similar rule if there is one) so new activities are categorized too.
+#[tauri::command]
+pub fn categorize_group(
+    state: State<'_, AppState>,
+    request: CategorizeGroupRequest,
+) -> Result<CategorizeGroupResult, String> {
+    let CategorizeGroupRequest { kind, name, category_id, create_rule } = request;
+    let rule_type = match kind.as_str() {
+        "app" => "app_name",
+        "domain" => "domain",
+        _ => return Err(format!("Unknown group kind: {} (expected app or domain)", kind)),
+    };
+    let rule_id = if create_rule {
+        let request = RuleRequest {
+            rule_type: rule_type.to_string(),
+            pattern: name.clone(),
+            category_id,
+            priority: 0,
+            project_id: None,
+            on_similar: SimilarRuleAction::Merge,
+        };
+        Some(insert_rule(&state, request)?)
+    } else {
+        None
+    };
+
+    let ids = state.db.get_uncategorized_group_ids(&kind, &name).map_err(|e| e.to_string())?;
+    let updated = apply_category(&state, &ids, Some(category_id))?;
+    Ok(CategorizeGroupResult { updated, rule_id })
+}
+
+/// Set the category of activities, run activity hooks and record the change as
+/// corrections. Approved activities are skipped; returns the number updated.
+fn apply_category(state: &AppState, activity_ids: &[i64], category_id: Option<i64>) -> Result<usize, String> {
+    let updated = state
+        .db
+        .update_activities_category(activity_ids, category_id)
+        .map_err(|e| e.to_string())?;
+
+    for &activity_id in &updated {
+        if let Some(extension_registry) = &state.extension_registry {
+            if let Ok(Some(mut activity)) = state.db.get_activity_by_id(activity_id) {
+                if let Err(e) = extension_registry.apply_activity_hooks(&mut activity, &Arc::clone(&state.db)) {
+                    eprintln!("Warning: Failed to apply activity hooks: {}", e);
+                }
+            }
+        }
+
+        let correction = match category_id {
+            Some(id) if id != SYSTEM_CATEGORY_UNCATEGORIZED => state.db.record_category_correction(activity_id, id),
+            _ => state.db.clear_category_correction(activity_id),
+        };
+        if let Err(e) = correction {
+            eprintln!("Warning: Failed to record category correction: {}", e);
+        }
+    }
+    state.event_bus.emit(EVENT_ACTIVITIES_CHANGED, serde_json::json!({ "category_id": category_id }));
+
+    Ok(updated.len())
+}
@@ -0,0 +1,38 @@
+//! Task hint commands
+
+use crate::commands::common::AppState;
+use crate::database::TaskHint;
+use serde::Deserialize;
+use tauri::State;
+
+/// Get all task hints
+#[tauri::command]
+pub fn get_task_hints(state: State<'_, AppState>) -> Result<Vec<TaskHint>, String> {
+    state.db.get_task_hints().map_err(|e| e.to_string())
+}
+
+/// `add_task_hint` request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct AddTaskHintRequest {
+    pub keyword: String,
+    pub task_id: i64,
+}
+
+/// Attach activities whose window title mentions `keyword` (e.g. a ticket
+/// number) to `task_id`; returns the hint ID
+#[tauri::command]
+pub fn add_task_hint(state: State<'_, AppState>, request: AddTaskHintRequest) -> Result<i64, String> {
+    let AddTaskHintRequest { keyword, task_id } = request;
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Err("Keyword cannot be empty".to_string());
+    }
+    state.db.add_task_hint(keyword, task_id).map_err(|e| e.to_string())
+}
+
+/// Delete a task hint
+#[tauri::command]
+pub fn delete_task_hint(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.db.delete_task_hint(id).map_err(|e| e.to_string())
+}
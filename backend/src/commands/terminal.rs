@@ -0,0 +1,11 @@
+//! Terminal session reporting commands
+
+use crate::commands::common::AppState;
+use crate::terminal::{self, TerminalReport};
+use tauri::State;
+
+/// Report the host and working directory of a terminal session
+#[tauri::command]
+pub fn report_terminal_activity(state: State<'_, AppState>, report: TerminalReport) -> Result<(), String> {
+    terminal::record_report(&state.data_sources, report)
+}
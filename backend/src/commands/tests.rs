@@ -0,0 +1,705 @@
+//! Command tests
+//!
+//! Commands are called directly, without IPC: `test_app` manages an
+//! `AppState` over an in-memory database on tauri's mock app, and commands
+//! take their `State` from it. A stub `goals` plugin is registered so plugin
+//! command routing (and the dashboard cards plugins contribute) can be checked
+//! without loading a plugin library.
+
+use super::*;
+use crate::commands::common::{CategoryResponse, RangeRequest};
+use crate::database::common::{SOURCE_PAUSE, SYSTEM_CATEGORY_UNCATEGORIZED};
+use crate::database::rules::{merged_pattern, similar_patterns};
+use crate::database::storage::Storage;
+use crate::database::{Database, Rule};
+use crate::plugin_system::{ExtensionRegistry, PluginIndex, PluginRegistry};
+use crate::rounding::{RoundingMode, RoundingPolicy};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tauri::test::{mock_app, MockRuntime};
+use tauri::{App, Manager};
+use time_tracker_plugin_sdk::{Plugin, PluginAPIInterface, PluginInfo};
+
+/// Start of the day the tests record activities on
+const DAY: i64 = 1_700_006_400;
+
+struct GoalsPlugin {
+    info: PluginInfo,
+}
+
+impl Plugin for GoalsPlugin {
+    fn info(&self) -> &PluginInfo {
+        &self.info
+    }
+
+    fn initialize(&mut self, _api: &dyn PluginAPIInterface) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn invoke_command(&self, command: &str, params: serde_json::Value, _api: &dyn PluginAPIInterface) -> Result<serde_json::Value, String> {
+        match command {
+            "dashboard_kpis" => Ok(json!([{
+                "id": "goal_nearest_completion",
+                "label": "Goal",
+                "value": 80,
+                "unit": "percent",
+            }])),
+            "echo" => Ok(params),
+            _ => Err(format!("Unknown command: {}", command)),
+        }
+    }
+
+    fn shutdown(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn test_app() -> App<MockRuntime> {
+    let db = Arc::new(Database::new(PathBuf::from(":memory:")).unwrap());
+    test_app_with_storage(Arc::clone(&db), db)
+}
+
+/// App whose `Storage` backend is `storage` rather than the database itself
+fn test_app_with_storage(db: Arc<Database>, storage: Arc<dyn Storage>) -> App<MockRuntime> {
+    let plugin_registry = Arc::new(PluginRegistry::new(Arc::clone(&db)));
+    plugin_registry
+        .register(Box::new(GoalsPlugin {
+            info: PluginInfo {
+                id: "goals".to_string(),
+                name: "Goals".to_string(),
+                version: "1.0.0".to_string(),
+                description: None,
+            },
+        }))
+        .unwrap();
+
+    let event_bus = Arc::new(crate::events::EventBus::new());
+    let app = mock_app();
+    app.manage(AppState {
+        db: Arc::clone(&db),
+        storage,
+        tracker: Arc::new(Mutex::new(None)),
+        thinking_mode_entry_id: Arc::new(Mutex::new(None)),
+        pause_entry: Arc::new(Mutex::new(None)),
+        plugin_registry: Some(plugin_registry),
+        extension_registry: Some(Arc::new(ExtensionRegistry::new())),
+        plugin_loader: None,
+        plugin_index: Arc::new(PluginIndex::load(std::env::temp_dir().join("time-tracker-test-plugin-index.json"))),
+        week_cache: crate::report_cache::WeekCache::subscribe(&event_bus),
+        jobs: Arc::new(crate::jobs::JobRunner::new(Arc::clone(&db))),
+        event_bus,
+        data_sources: Arc::new(crate::data_sources::DataSourceRegistry::new()),
+        focus_blocker: Arc::new(crate::focus_blocker::FocusBlocker::new()),
+        pomodoro_status: Arc::new(Mutex::new(None)),
+        settings: Arc::new(crate::settings::SettingsService::new(db)),
+        startup_recovery: None,
+        replay: Arc::new(crate::replay::ReplayRunner::new()),
+    });
+    app
+}
+
+fn create_test_category(app: &App<MockRuntime>, name: &str) -> CategoryResponse {
+    let request = serde_json::from_value(json!({
+        "name": name,
+        "color": "#2563EB",
+        "is_productive": 1,
+        "sort_order": 100,
+    }))
+    .unwrap();
+    create_category(app.state(), request).unwrap()
+}
+
+fn add_test_rule(app: &App<MockRuntime>, pattern: &str, category_id: i64) -> Result<Rule, String> {
+    let request = serde_json::from_value(json!({
+        "rule_type": "app_name",
+        "pattern": pattern,
+        "category_id": category_id,
+        // Above the default rules, which also match "Code"
+        "priority": 100,
+        "on_similar": "create",
+    }))
+    .unwrap();
+    create_rule(app.state(), request)
+}
+
+fn record(app: &App<MockRuntime>, app_name: &str, timestamp: i64) -> i64 {
+    let state = app.state::<AppState>();
+    state.db.upsert_activity(app_name, Some(app_name), None, timestamp, "tracker", None).unwrap()
+}
+
+#[test]
+fn categories_round_trip() {
+    let app = test_app();
+    let created = create_test_category(&app, "Deep Work");
+    assert_eq!(created.is_productive, Some(true));
+    assert!(get_categories(app.state()).unwrap().iter().any(|c| c.id == created.id));
+
+    let request = serde_json::from_value(json!({
+        "id": created.id,
+        "name": "Focus",
+        "color": "#16A34A",
+        "is_productive": -1,
+        "sort_order": 100,
+    }))
+    .unwrap();
+    let updated = update_category(app.state(), request).unwrap();
+    assert_eq!((updated.name.as_str(), updated.is_productive), ("Focus", None));
+
+    delete_category(app.state(), created.id).unwrap();
+    assert!(!get_categories(app.state()).unwrap().iter().any(|c| c.id == created.id));
+}
+
+#[test]
+fn rules_categorize_new_activities() {
+    let app = test_app();
+    let category = create_test_category(&app, "Coding");
+    let rule = add_test_rule(&app, "Code", category.id).unwrap();
+    assert!(get_rules(app.state()).unwrap().iter().any(|r| r.id == rule.id));
+
+    let id = record(&app, "Code", DAY + 60);
+    let activity = get_activity(app.state(), id).unwrap().unwrap();
+    assert_eq!(activity.category_id, Some(category.id));
+
+    archive_category(app.state(), category.id).unwrap();
+    assert!(add_test_rule(&app, "Terminal", category.id).is_err());
+    unarchive_category(app.state(), category.id).unwrap();
+
+    delete_rule(app.state(), rule.id).unwrap();
+    assert!(get_rules(app.state()).unwrap().iter().all(|r| r.id != rule.id));
+}
+
+#[test]
+fn similar_rules_merge_instead_of_piling_up() {
+    let app = test_app();
+    let category = create_test_category(&app, "Browsing");
+    let request = |pattern: &str, on_similar: &str| {
+        serde_json::from_value(json!({
+            "rule_type": "app_name",
+            "pattern": pattern,
+            "category_id": category.id,
+            "priority": 20,
+            "on_similar": on_similar,
+        }))
+        .unwrap()
+    };
+    let chrome = get_rules(app.state()).unwrap().into_iter().find(|r| r.pattern == "Chrome").unwrap();
+
+    let error = create_rule(app.state(), request(" chrome** ", "reject")).unwrap_err();
+    assert!(error.contains("similar rule"), "{}", error);
+    let merged = create_rule(app.state(), request(" chrome** ", "merge")).unwrap();
+    assert_eq!(
+        (merged.id, merged.pattern.as_str(), merged.category_id, merged.priority),
+        (chrome.id, "Chrome", category.id, 20)
+    );
+    let created = create_rule(app.state(), request("*chrome", "create")).unwrap();
+    assert_eq!(created.pattern, "*chrome");
+    let similar = find_similar_rule(app.state(), SimilarRuleRequest { rule_type: "app_name".into(), pattern: "CHROME".into(), exclude_id: Some(chrome.id) }).unwrap();
+    assert_eq!(similar.map(|r| r.id), Some(created.id));
+
+    assert_eq!(merged_pattern("chrome*", "*chrome"), "chrome");
+    assert_eq!(merged_pattern("chrome*", "*Chrome*"), "*Chrome*");
+    assert!(!similar_patterns("*", "**"));
+}
+
+#[test]
+fn uncategorized_groups_are_fixed_at_once() {
+    let app = test_app();
+    let category = create_test_category(&app, "Design");
+    record(&app, "Figmaa", DAY + 60);
+    record(&app, "Figmaa", DAY + 600);
+    record(&app, "Code", DAY + 1200);
+
+    let summary = get_uncategorized_summary(app.state(), RangeRequest { start: DAY, end: DAY + 86400 }).unwrap();
+    assert_eq!(summary.len(), 1);
+    assert_eq!((summary[0].kind.as_str(), summary[0].name.as_str(), summary[0].activity_count), ("app", "Figmaa", 2));
+
+    let result = categorize_group(
+        app.state(),
+        CategorizeGroupRequest { kind: "app".into(), name: "Figmaa".into(), category_id: category.id, create_rule: true },
+    ).unwrap();
+    assert_eq!(result.updated, 2);
+    assert!(get_uncategorized_summary(app.state(), RangeRequest { start: DAY, end: DAY + 86400 }).unwrap().is_empty());
+    let rule = get_rules(app.state()).unwrap().into_iter().find(|r| Some(r.id) == result.rule_id).unwrap();
+    assert_eq!((rule.pattern.as_str(), rule.category_id), ("Figmaa", category.id));
+    let id = record(&app, "Figmaa", DAY + 7200);
+    assert_eq!(get_activity(app.state(), id).unwrap().unwrap().category_id, Some(category.id));
+}
+
+#[test]
+fn categorizing_history_refreshes_the_cached_week() {
+    use chrono::{Datelike, Days, Local};
+    let app = test_app();
+    let category = create_test_category(&app, "Design");
+    let today = Local::now().date_naive();
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let midnight = |date: chrono::NaiveDate| {
+        date.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(Local).earliest().unwrap().timestamp()
+    };
+    let (week_start, week_end) = (midnight(monday), midnight(monday + Days::new(7)));
+    record(&app, "Figmaa", week_start + 60);
+    record(&app, "Figmaa", week_start + 120);
+
+    let design_seconds = || {
+        get_stats(app.state(), StatsRequest { start: week_start, end: week_end, sources: None, devices: None })
+            .unwrap()
+            .category_breakdown
+            .iter()
+            .find(|c| c.category_id == category.id)
+            .map_or(0, |c| c.seconds)
+    };
+    assert_eq!(design_seconds(), 0);
+    let request = CategorizeGroupRequest { kind: "app".into(), name: "Figmaa".into(), category_id: category.id, create_rule: false };
+    categorize_group(app.state(), request).unwrap();
+    assert!(design_seconds() > 0, "served from a stale cache");
+}
+
+#[test]
+fn decayed_top_apps_favor_recent_use() {
+    let app = test_app();
+    for minute in 0..5 {
+        record(&app, "Old", DAY + minute * 60);
+    }
+    record(&app, "New", DAY + 20 * 86400);
+    record(&app, "New", DAY + 20 * 86400 + 60);
+
+    let end = DAY + 21 * 86400;
+    let names = |apps: Vec<serde_json::Value>| -> Vec<String> {
+        apps.iter().map(|a| a["app_name"].as_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(names(get_top_apps(app.state(), TopAppsRequest { start: DAY, end, limit: 10, half_life_days: None }).unwrap()), ["Old", "New"]);
+    assert_eq!(names(get_top_apps(app.state(), TopAppsRequest { start: DAY, end, limit: 10, half_life_days: Some(7.0) }).unwrap()), ["New", "Old"]);
+}
+
+#[test]
+fn input_intensity_separates_active_time() {
+    let app = test_app();
+    record(&app, "Video", DAY + 60);
+    let id = record(&app, "Video", DAY + 120);
+    let state = app.state::<AppState>();
+    state.db.add_input_sample(id, true).unwrap();
+    state.db.add_input_sample(id, false).unwrap();
+    state.db.add_input_sample(id, true).unwrap();
+    record(&app, "Untracked", DAY + 600);
+
+    let stats = get_input_intensity(app.state(), RangeRequest { start: DAY, end: DAY + 86400 }).unwrap();
+    assert_eq!(stats.len(), 1);
+    assert_eq!((stats[0].app_name.as_str(), stats[0].duration_sec, stats[0].active_seconds), ("Video", 60, 10));
+}
+
+#[test]
+fn micro_break_compliance_counts_completed_breaks() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    let ids: Vec<i64> = (0..3).map(|i| state.db.insert_micro_break(DAY + i * 1200, 20).unwrap()).collect();
+    finish_micro_break(app.state(), FinishMicroBreakRequest { id: ids[0], skipped: false }).unwrap();
+    finish_micro_break(app.state(), FinishMicroBreakRequest { id: ids[1], skipped: true }).unwrap();
+    // A late answer does not change a finished break
+    finish_micro_break(app.state(), FinishMicroBreakRequest { id: ids[1], skipped: false }).unwrap();
+
+    let stats = get_micro_break_stats(app.state(), RangeRequest { start: DAY, end: DAY + 86400 }).unwrap();
+    assert_eq!((stats.offered, stats.completed, stats.skipped, stats.missed), (3, 1, 1, 1));
+    assert_eq!(stats.compliance_percent, Some(33));
+}
+
+#[test]
+fn boundary_violations_are_logged_once_per_day() {
+    let app = test_app();
+    let db = &app.state::<AppState>().db;
+    assert!(db.log_boundary_violation("2024-03-02", "weekend", 1800, DAY).unwrap());
+    assert!(!db.log_boundary_violation("2024-03-02", "weekend", 2400, DAY + 300).unwrap());
+    assert!(db.log_boundary_violation("2024-03-04", "quiet_hours", 1800, DAY).unwrap());
+
+    let logged = db.get_boundary_violations("2024-03-01", "2024-03-03").unwrap();
+    assert_eq!(logged.len(), 1);
+    assert_eq!((logged[0].seconds, logged[0].logged_at), (2400, DAY));
+}
+
+#[test]
+fn domains_are_rebuilt_from_old_titles() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    let browser = state
+        .db
+        .upsert_activity("Google Chrome", Some("Issues - github.com - Google Chrome"), None, DAY + 60, "tracker", None)
+        .unwrap();
+    record(&app, "Code", DAY + 120);
+
+    let job = backfill_domains(app.state(), RangeRequest { start: DAY, end: DAY + 86400 }).unwrap();
+    assert_eq!(job.status, "queued");
+    assert!(crate::jobs::run(&state.db, job.id, &|_| {}));
+    let job = state.db.get_job(job.id).unwrap().unwrap();
+    assert_eq!((job.status.as_str(), job.total, job.processed), ("completed", Some(2), 2));
+    assert_eq!(job.cursor.unwrap()["updated"], 1);
+    assert_eq!(state.db.get_activity_by_id(browser).unwrap().unwrap().domain.as_deref(), Some("github.com"));
+    assert!(list_jobs(app.state(), ListJobsRequest::default()).unwrap().iter().any(|j| j.id == job.id));
+    assert!(cancel_job(app.state(), job.id).is_err());
+}
+
+#[test]
+fn activities_are_shaped_and_paged() {
+    let app = test_app();
+    let category = create_test_category(&app, "Coding");
+    let first = record(&app, "Code", DAY + 60);
+    record(&app, "Slack", DAY + 120);
+    update_activity_category(app.state(), UpdateActivityCategoryRequest { activity_id: first, category_id: Some(category.id) }).unwrap();
+
+    let request = serde_json::from_value(json!({
+        "start": DAY,
+        "end": DAY + 86400,
+        "fields": ["id", "app_name"],
+        "include": ["category"],
+    }))
+    .unwrap();
+    let activities = get_activities(app.state(), request).unwrap();
+    assert_eq!(activities.len(), 2);
+    let code = activities.iter().find(|a| a["id"] == first).unwrap();
+    assert_eq!(code["category"]["name"], "Coding");
+    assert!(code.get("window_title").is_none());
+
+    let request = serde_json::from_value(json!({ "start": DAY, "end": DAY + 86400, "limit": 1 })).unwrap();
+    let page = get_activities_page(app.state(), request).unwrap();
+    assert_eq!(page.activities.len(), 1);
+    let request = serde_json::from_value(json!({
+        "start": DAY,
+        "end": DAY + 86400,
+        "limit": 1,
+        "cursor": page.next_cursor.unwrap(),
+    }))
+    .unwrap();
+    let page = get_activities_page(app.state(), request).unwrap();
+    assert_eq!(page.activities.len(), 1);
+    assert!(page.next_cursor.is_none());
+
+    assert_eq!(get_timeline(app.state(), TimelineRequest { start: DAY, end: DAY + 86400, min_block_sec: None }).unwrap().len(), 2);
+}
+
+#[test]
+fn requests_reject_unknown_fields() {
+    let request = json!({ "start": DAY, "end": DAY + 86400, "startTime": DAY });
+    assert!(serde_json::from_value::<ActivitiesRequest>(request).is_err());
+}
+
+#[test]
+fn plugin_commands_are_routed_by_id() {
+    let app = test_app();
+    let params = json!({ "goal_id": 1 });
+    let invoke = |plugin_id: &str, command: &str, params: serde_json::Value| {
+        let request = InvokePluginCommandRequest { plugin_id: plugin_id.into(), command: command.into(), params };
+        invoke_plugin_command(app.state(), request)
+    };
+    assert_eq!(invoke("goals", "echo", params.clone()).unwrap(), params);
+    assert!(invoke("goals", "missing", json!({})).is_err());
+    assert!(invoke("projects", "echo", json!({})).is_err());
+
+    let metrics = get_plugin_metrics(app.state()).unwrap();
+    let goals = metrics.iter().find(|m| m.plugin_id == "goals").unwrap();
+    assert_eq!((goals.totals.calls, goals.totals.errors), (2, 1));
+}
+
+#[test]
+fn dashboard_includes_plugin_goal_cards() {
+    let app = test_app();
+    record(&app, "Code", DAY + 60);
+    let dashboard = get_dashboard(app.state(), DAY).unwrap();
+    let goal = dashboard.kpis.iter().find(|kpi| kpi.id == "goal_nearest_completion").unwrap();
+    assert_eq!((goal.source.as_str(), goal.value.clone()), ("goals", json!(80)));
+    assert!(dashboard.kpis.iter().any(|kpi| kpi.id == KPI_TOTAL_TIME && kpi.value == json!(5)));
+}
+
+#[test]
+fn paused_time_with_a_reason_becomes_an_entry() {
+    let app = test_app();
+    assert!(pause_tracking(app.state(), PauseRequest { duration_min: Some(30), until: Some(DAY), ..Default::default() }).is_err());
+    assert!(pause_tracking(app.state(), PauseRequest { duration_min: Some(0), ..Default::default() }).is_err());
+
+    pause_tracking(app.state(), PauseRequest { duration_min: Some(30), reason: Some("Lunch".to_string()), ..Default::default() }).unwrap();
+    let state = app.state::<AppState>();
+    let entry = state.pause_entry.lock().unwrap().clone().unwrap();
+    let break_id = state.db.find_category_by_name("Break").unwrap();
+    assert_eq!((entry.description.as_deref(), entry.category_id), (Some("Lunch"), break_id));
+
+    resume_tracking(app.state()).unwrap();
+    assert!(state.pause_entry.lock().unwrap().is_none());
+    let now = chrono::Utc::now().timestamp();
+    let stored = state.db.get_manual_entries(now - 60, now).unwrap();
+    let stored = stored.iter().find(|e| e.id == entry.id).unwrap();
+    assert_eq!(stored.source, SOURCE_PAUSE);
+    assert!(stored.ended_at >= stored.started_at);
+
+    // Without a reason or category the pause is just a pause
+    pause_tracking(app.state(), PauseRequest::default()).unwrap();
+    assert!(state.pause_entry.lock().unwrap().is_none());
+}
+
+#[test]
+fn rest_api_routes_to_commands() {
+    use crate::http_api::route;
+    use std::collections::HashMap;
+    use tiny_http::Method;
+
+    let app = test_app();
+    let no_query = HashMap::new();
+    let created = route(app.state(), &Method::Post, "/categories", &no_query, json!({ "name": "Api", "color": "#112233", "is_productive": 1, "sort_order": 0 }));
+    assert_eq!(created.status, 201, "{}", created.body);
+    let id = created.body["data"]["id"].as_i64().unwrap();
+
+    let listed = route(app.state(), &Method::Get, "/categories", &no_query, serde_json::Value::Null);
+    assert!(listed.body["data"].as_array().unwrap().iter().any(|c| c["id"] == id));
+    let missing_range = route(app.state(), &Method::Get, "/activities", &no_query, serde_json::Value::Null);
+    assert_eq!(missing_range.status, 400);
+    assert_eq!(route(app.state(), &Method::Get, "/nothing", &no_query, serde_json::Value::Null).status, 404);
+    assert_eq!(route(app.state(), &Method::Delete, &format!("/categories/{}", id), &no_query, serde_json::Value::Null).status, 200);
+}
+
+/// Submit everything on `DAY` and approve the given entries
+fn approve(app: &App<MockRuntime>, manual_entry_ids: Vec<i64>, activity_ids: Vec<i64>) {
+    submit_entries_for_approval(app.state(), SubmitForApprovalRequest { start: DAY, end: DAY + 86400, project_id: None }).unwrap();
+    approve_entries(app.state(), ApproveEntriesRequest { manual_entry_ids, activity_ids, note: None }).unwrap();
+}
+
+#[test]
+fn approved_activities_cannot_be_recategorized_or_deleted() {
+    let app = test_app();
+    let category = create_test_category(&app, "Design");
+    let approved = record(&app, "Figmaa", DAY + 60);
+    let open = record(&app, "Sketch", DAY + 3600);
+    approve(&app, vec![], vec![approved]);
+
+    assert!(update_activity_category(app.state(), UpdateActivityCategoryRequest { activity_id: approved, category_id: Some(category.id) }).is_err());
+    assert!(delete_activity(app.state(), approved).is_err());
+    apply_category_to_activities(app.state(), ApplyCategoryRequest { activity_ids: vec![approved, open], category_id: Some(category.id) }).unwrap();
+    assert_ne!(get_activity(app.state(), approved).unwrap().unwrap().category_id, Some(category.id));
+    assert_eq!(get_activity(app.state(), open).unwrap().unwrap().category_id, Some(category.id));
+
+    // New samples start a new activity instead of extending the approved one
+    assert_ne!(record(&app, "Figmaa", DAY + 120), approved);
+}
+
+#[test]
+fn retention_keeps_approved_activities_and_their_approvals() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    let approved = record(&app, "Figmaa", DAY + 60);
+    let open = record(&app, "Sketch", DAY + 3600);
+    approve(&app, vec![], vec![approved]);
+
+    crate::retention::purge_step(&state.db, &json!({ "before": DAY + 86400 })).unwrap();
+
+    assert!(get_activity(app.state(), approved).unwrap().is_some());
+    assert!(get_activity(app.state(), open).unwrap().is_none());
+    let approvals = state.db.get_entry_approvals(DAY, DAY + 86400).unwrap();
+    assert!(approvals.iter().any(|a| a.entity_id == approved && a.status == "approved"));
+}
+
+#[test]
+fn accepted_suggestions_meet_the_threshold_and_are_not_learned_from() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    let dev = create_test_category(&app, "Development");
+    let browsing = create_test_category(&app, "Browsing");
+    for (i, (app_name, category)) in [("Code", &dev), ("Firefox", &browsing)].into_iter().cycle().take(6).enumerate() {
+        let id = record(&app, app_name, DAY + i as i64 * 600);
+        update_activity_category(app.state(), UpdateActivityCategoryRequest { activity_id: id, category_id: Some(category.id) })
+            .unwrap();
+    }
+    assert_eq!(state.db.get_category_training_samples().unwrap().len(), 6);
+
+    // A known app is confidently Development; an unknown one is a coin toss
+    let code = record(&app, "Code", DAY + 7200);
+    let slack = record(&app, "Slack", DAY + 7800);
+    let request = AcceptSuggestionsRequest { start: DAY, end: DAY + 86400, min_confidence: None };
+    assert_eq!(accept_category_suggestions(app.state(), request).unwrap(), 1);
+    let category_of = |id| get_activity(app.state(), id).unwrap().unwrap().category_id;
+    assert_eq!(category_of(code), Some(dev.id));
+    assert_ne!(category_of(slack), Some(dev.id));
+    assert_ne!(category_of(slack), Some(browsing.id));
+    assert_eq!(state.db.get_category_training_samples().unwrap().len(), 6, "accepted suggestions became corrections");
+
+    let request = AcceptSuggestionsRequest { start: DAY, end: DAY + 86400, min_confidence: Some(0.4) };
+    assert_eq!(accept_category_suggestions(app.state(), request).unwrap(), 1);
+    assert_eq!(state.db.get_category_training_samples().unwrap().len(), 6);
+}
+
+#[test]
+fn rounding_bills_activity_blocks_not_tracker_rows() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    // Ten minutes of editing, a new tracker row for each file opened every five seconds
+    for i in 0..120 {
+        let title = format!("file{i}.rs - Code");
+        state.db.upsert_activity("Code", Some(&title), None, DAY + i * 5, "tracker", None).unwrap();
+    }
+    let request = ManualEntryRequest { description: Some("Call".into()), category_id: None, started_at: DAY + 3600, ended_at: DAY + 4200 };
+    add_manual_entry(app.state(), request).unwrap();
+
+    let policy = RoundingPolicy { increment_minutes: 15, mode: RoundingMode::Up, minimum_minutes: None };
+    let request = PreviewRoundingRequest { start: DAY, end: DAY + 86400, policy, min_block_sec: None };
+    let preview = preview_rounding(app.state(), request).unwrap();
+    let entries: Vec<_> = preview.entries.iter().map(|e| (e.entity_type.as_str(), e.activity_count, e.rounded_sec)).collect();
+    assert_eq!(entries, vec![("activity", 120, 900), ("manual_entry", 1, 900)]);
+    assert_eq!((preview.raw_total_sec, preview.rounded_total_sec), (1200, 1800));
+}
+
+#[test]
+fn approved_idle_periods_cannot_be_split() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    let category = create_test_category(&app, "Meetings");
+    state.db.record_idle_start(DAY + 60).unwrap();
+    state.db.update_idle_duration(DAY + 60, 1800).unwrap();
+    let idle_id = state.db.get_activities(DAY, DAY + 86400, None, None, None, None, None).unwrap()[0].id;
+    // Idle periods are never submitted from the app, so approve this one directly
+    state.db.conn.lock().unwrap().execute(
+        "INSERT INTO entry_approvals (entity_type, entity_id, status) VALUES ('activity', ?, 'approved')",
+        [idle_id],
+    ).unwrap();
+
+    let request = serde_json::from_value(json!({ "category_id": category.id, "started_at": DAY + 60 })).unwrap();
+    assert!(submit_idle_activity(app.state(), request).is_err());
+    let idle = get_activity(app.state(), idle_id).unwrap().unwrap();
+    assert_eq!((idle.category_id, idle.duration_sec), (Some(SYSTEM_CATEGORY_UNCATEGORIZED), 1800));
+}
+
+#[test]
+fn rule_runs_and_undo_leave_approved_activities_alone() {
+    let app = test_app();
+    let category = create_test_category(&app, "Design");
+    let approved = record(&app, "Figmaa", DAY + 60);
+    let open = record(&app, "Figmaa", DAY + 3600);
+    let category_of = |id: i64| get_activity(app.state(), id).unwrap().unwrap().category_id;
+    let original = category_of(approved);
+    approve(&app, vec![], vec![approved]);
+
+    add_test_rule(&app, "Figmaa", category.id).unwrap();
+    reapply_categorization_rules(app.state()).unwrap();
+    assert_eq!((category_of(approved), category_of(open)), (original, Some(category.id)));
+
+    // Approved after the run: undo keeps the category it was approved with
+    approve(&app, vec![], vec![open]);
+    assert_eq!(undo_last_rule_run(app.state()).unwrap(), 0);
+    assert_eq!(category_of(open), Some(category.id));
+}
+
+#[test]
+fn approved_manual_entries_cannot_be_deleted() {
+    let app = test_app();
+    let request = serde_json::from_value(json!({ "description": "Review", "started_at": DAY + 60, "ended_at": DAY + 660 })).unwrap();
+    let approved = add_manual_entry(app.state(), request).unwrap();
+    let request = serde_json::from_value(json!({ "description": "Draft", "started_at": DAY + 3600, "ended_at": DAY + 4200 })).unwrap();
+    let draft = add_manual_entry(app.state(), request).unwrap();
+    approve(&app, vec![approved], vec![]);
+
+    assert!(delete_manual_entry(app.state(), approved).is_err());
+    delete_manual_entry(app.state(), draft).unwrap();
+    let remaining: Vec<i64> = app.state::<AppState>().db.get_manual_entries(DAY, DAY + 86400).unwrap().iter().map(|e| e.id).collect();
+    assert_eq!(remaining, vec![approved]);
+}
+
+#[test]
+fn core_commands_read_and_write_through_storage() {
+    let db = Arc::new(Database::new(PathBuf::from(":memory:")).unwrap());
+    let backend = Arc::new(Database::new(PathBuf::from(":memory:")).unwrap());
+    let app = test_app_with_storage(Arc::clone(&db), Arc::clone(&backend) as Arc<dyn Storage>);
+
+    let category = create_test_category(&app, "Design");
+    add_test_rule(&app, "Figma", category.id).unwrap();
+    let request = ManualEntryRequest { description: None, category_id: Some(category.id), started_at: DAY, ended_at: DAY + 600 };
+    add_manual_entry(app.state(), request).unwrap();
+
+    let names = |db: &Database| db.get_categories().unwrap().into_iter().map(|c| c.name).collect::<Vec<_>>();
+    assert!(names(&backend).contains(&"Design".to_string()));
+    assert!(!names(&db).contains(&"Design".to_string()));
+    assert_eq!((backend.get_rules().unwrap().len(), db.get_rules().unwrap().len()), (1, 0));
+    assert_eq!(backend.get_manual_entries(DAY, DAY + 86400).unwrap().len(), 1);
+    assert!(db.get_manual_entries(DAY, DAY + 86400).unwrap().is_empty());
+    assert_eq!(get_categories(app.state()).unwrap().len(), backend.get_categories().unwrap().len());
+}
+
+#[test]
+fn requests_accept_snake_case_keys_only() {
+    fn check<T: serde::de::DeserializeOwned>(snake: serde_json::Value, camel: serde_json::Value) {
+        let name = std::any::type_name::<T>();
+        assert!(serde_json::from_value::<T>(snake.clone()).is_ok(), "{name} rejects {snake}");
+        assert!(serde_json::from_value::<T>(camel.clone()).is_err(), "{name} accepts {camel}");
+        let mut unknown = snake;
+        unknown["extra"] = json!(1);
+        assert!(serde_json::from_value::<T>(unknown).is_err(), "{name} accepts an unknown key");
+    }
+
+    check::<RangeRequest>(json!({ "start": DAY, "end": DAY + 86400 }), json!({ "startTime": DAY, "end": DAY + 86400 }));
+    check::<PauseRequest>(json!({ "duration_min": 30 }), json!({ "durationMin": 30 }));
+    check::<FinishMicroBreakRequest>(json!({ "id": 1, "skipped": true }), json!({ "id": 1, "wasSkipped": true }));
+    check::<InvokePluginCommandRequest>(
+        json!({ "plugin_id": "goals", "command": "echo", "params": {} }),
+        json!({ "pluginId": "goals", "command": "echo", "params": {} }),
+    );
+    check::<PluginLocationRequest>(
+        json!({ "author": "acme", "plugin_id": "goals" }),
+        json!({ "author": "acme", "pluginId": "goals" }),
+    );
+    check::<CategorizeGroupRequest>(
+        json!({ "kind": "app", "name": "Figma", "category_id": 1, "create_rule": true }),
+        json!({ "kind": "app", "name": "Figma", "categoryId": 1, "createRule": true }),
+    );
+    check::<IdlePromptRequest>(
+        json!({ "idle_duration": 300, "idle_start": DAY }),
+        json!({ "idleDuration": 300, "idleStart": DAY }),
+    );
+    check::<AddTaskHintRequest>(json!({ "keyword": "JIRA-1", "task_id": 2 }), json!({ "keyword": "JIRA-1", "taskId": 2 }));
+    check::<SetSettingRequest>(json!({ "key": "theme", "value": "dark" }), json!({ "settingKey": "theme", "value": "dark" }));
+    check::<TopListRequest>(
+        json!({ "start": DAY, "end": DAY + 86400, "limit": 10 }),
+        json!({ "start": DAY, "endTime": DAY + 86400, "limit": 10 }),
+    );
+}
+
+#[test]
+fn csv_custom_field_headers_do_not_collide_with_core_columns() {
+    let app = test_app();
+    let request = serde_json::from_value(json!({ "entity_type": "activity", "name": "source", "field_type": "text" })).unwrap();
+    create_custom_field_def(app.state(), request).unwrap();
+    let path = std::env::temp_dir().join(format!("time-tracker-test-export-{}.csv", std::process::id()));
+    let request = ExportRequest { start: DAY, end: DAY + 86400, file_path: path.to_string_lossy().to_string(), sources: None, passphrase: None };
+    let written = export_to_csv(app.state(), request).unwrap();
+
+    let csv = std::fs::read_to_string(&written).unwrap();
+    std::fs::remove_file(&written).unwrap();
+    let header = csv.trim_start_matches('\u{feff}').lines().next().unwrap();
+    assert!(header.ends_with(",source,custom:source"), "{header}");
+}
+
+#[test]
+fn opening_a_workspace_broadcasts_the_settings_it_changes() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    state.settings.set("mqtt_host", "broker.local").unwrap();
+    let changed = Arc::new(Mutex::new(Vec::new()));
+    let changed_in_listener = Arc::clone(&changed);
+    state.settings.subscribe(move |keys| changed_in_listener.lock().unwrap().extend_from_slice(keys));
+
+    let path = std::env::temp_dir().join(format!("time-tracker-test-workspace-{}.db", std::process::id()));
+    super::workspace::open_workspace(&state, path.clone()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(changed.lock().unwrap().contains(&"mqtt_host".to_string()));
+    assert_eq!(state.db.get_setting("mqtt_host").unwrap(), None);
+}
+
+#[test]
+fn opening_a_file_that_is_not_a_database_is_refused() {
+    let app = test_app();
+    let state = app.state::<AppState>();
+    state.settings.set("mqtt_host", "broker.local").unwrap();
+
+    let path = std::env::temp_dir().join(format!("time-tracker-test-not-a-db-{}.txt", std::process::id()));
+    std::fs::write(&path, "meeting notes, definitely not SQLite").unwrap();
+    let result = super::workspace::open_workspace(&state, path.clone());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(result.unwrap_err().contains("not a time tracker database"));
+    // Neither salvaged nor moved aside, and the current workspace stays open
+    assert_eq!(contents, "meeting notes, definitely not SQLite");
+    assert_eq!(state.db.get_setting("mqtt_host").unwrap().as_deref(), Some("broker.local"));
+}
@@ -1,55 +1,173 @@
 //! Tracking control commands
 
 use crate::commands::common::AppState;
+use crate::database::common::SOURCE_PAUSE;
+use crate::database::{Database, ManualEntry};
+use crate::events::{
+    EventBus, EVENT_MANUAL_ENTRY_CREATED, EVENT_MANUAL_ENTRY_UPDATED, EVENT_TRACKING_PAUSED, EVENT_TRACKING_RESUMED,
+};
+use crate::tracker::Tracker;
 use chrono::Utc;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::State;
 
+/// How often a timed pause checks the clock. Waiting on the wall clock in
+/// steps (rather than one long sleep) keeps the deadline right across system sleep.
+const AUTO_RESUME_CHECK: Duration = Duration::from_secs(15);
+
+/// `pause_tracking` request; all fields are optional (see `pause_tracking`)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct PauseRequest {
+    #[serde(default)]
+    pub duration_min: Option<i64>,
+    #[serde(default)]
+    pub until: Option<i64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+    #[serde(default)]
+    pub category_id: Option<i64>,
+}
+
 /// Get today's total tracked time
 #[tauri::command]
 pub fn get_today_total(state: State<'_, AppState>) -> Result<i64, String> {
-    let now = Utc::now().timestamp();
-    let start_of_day = Utc::now()
-        .date_naive()
-        .and_hms_opt(0, 0, 0)
-        .unwrap()
-        .and_utc()
-        .timestamp();
-    
-    let activities = state.db.get_activities(start_of_day, now, None, None, None, None)
-        .map_err(|e| e.to_string())?;
-    
-    let total: i64 = activities.iter().map(|a| a.duration_sec).sum();
-    Ok(total)
+    state.db.get_today_total().map_err(|e| e.to_string())
 }
 
-/// Pause tracking
+/// Pause tracking, optionally for `duration_min` minutes or until the `until`
+/// timestamp, after which tracking resumes by itself.
+///
+/// With a `reason` or `category_id` (lunch, an errand, a meeting away from the
+/// computer) the pause is recorded as a manual entry that ends when tracking
+/// resumes; the category defaults to Break.
 #[tauri::command]
-pub fn pause_tracking(state: State<'_, AppState>) -> Result<(), String> {
+pub fn pause_tracking(state: State<'_, AppState>, request: PauseRequest) -> Result<(), String> {
+    let PauseRequest { duration_min, until, reason, category_id } = request;
+    let now = Utc::now().timestamp();
+    let until = match (duration_min, until) {
+        (Some(_), Some(_)) => return Err("Pass either a duration or an end time, not both".to_string()),
+        (Some(minutes), None) if minutes <= 0 => return Err("Pause duration must be positive".to_string()),
+        (Some(minutes), None) => Some(now + minutes * 60),
+        (None, Some(until)) if until <= now => return Err("Pause end time must be in the future".to_string()),
+        (None, until) => until,
+    };
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    let category_id = match category_id {
+        Some(id) => Some(id),
+        None if reason.is_some() => Some(
+            state
+                .db
+                .find_category_by_name("Break")
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| "Break category not found".to_string())?,
+        ),
+        None => None,
+    };
+
+    // A new pause replaces the previous one, including its entry
+    end_pause_entry(&state.db, &state.event_bus, &state.pause_entry, now)?;
+    if let Some(category_id) = category_id {
+        let id = state
+            .db
+            .add_manual_entry(reason.as_deref(), Some(category_id), now, now, SOURCE_PAUSE)
+            .map_err(|e| e.to_string())?;
+        let entry = ManualEntry {
+            id,
+            description: reason,
+            category_id: Some(category_id),
+            started_at: now,
+            ended_at: now, // Set when tracking resumes
+            source: SOURCE_PAUSE.to_string(),
+            billable_override: None,
+        };
+        state.event_bus.emit(EVENT_MANUAL_ENTRY_CREATED, serde_json::json!(entry));
+        *state.pause_entry.lock().unwrap() = Some(entry);
+    }
+
     if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
-        tracker.pause();
+        tracker.pause_until(until);
+        if let Some(until) = until {
+            schedule_auto_resume(
+                Arc::clone(tracker),
+                Arc::clone(&state.db),
+                Arc::clone(&state.event_bus),
+                Arc::clone(&state.pause_entry),
+                until,
+            );
+        }
     }
+    let entry_id = state.pause_entry.lock().unwrap().as_ref().map(|entry| entry.id);
+    state.event_bus.emit(EVENT_TRACKING_PAUSED, serde_json::json!({ "until": until, "entry_id": entry_id }));
     Ok(())
 }
 
-/// Resume tracking
+/// Close the entry recording the current pause at `ended_at`; returns its ID if there was one
+fn end_pause_entry(
+    db: &Database,
+    event_bus: &EventBus,
+    pause_entry: &Mutex<Option<ManualEntry>>,
+    ended_at: i64,
+) -> Result<Option<i64>, String> {
+    let Some(mut entry) = pause_entry.lock().unwrap().take() else {
+        return Ok(None);
+    };
+    entry.ended_at = ended_at.max(entry.started_at);
+    db.update_manual_entry(entry.id, entry.description.as_deref(), entry.category_id, entry.started_at, entry.ended_at)
+        .map_err(|e| e.to_string())?;
+    event_bus.emit(EVENT_MANUAL_ENTRY_UPDATED, serde_json::json!(entry));
+    Ok(Some(entry.id))
+}
+
+/// Resume tracking at `until` unless the pause was ended or replaced before
+/// then, and announce it with `auto: true` on the resumed event
+fn schedule_auto_resume(
+    tracker: Arc<Tracker>,
+    db: Arc<Database>,
+    event_bus: Arc<EventBus>,
+    pause_entry: Arc<Mutex<Option<ManualEntry>>>,
+    until: i64,
+) {
+    std::thread::spawn(move || loop {
+        let now = Utc::now().timestamp();
+        if tracker.resume_if_due(until, now) {
+            // The pause ended at `until`, even if the check came late (e.g. after system sleep)
+            if let Err(e) = end_pause_entry(&db, &event_bus, &pause_entry, until) {
+                eprintln!("Failed to close pause entry: {}", e);
+            }
+            event_bus.emit(EVENT_TRACKING_RESUMED, serde_json::json!({ "auto": true, "paused_until": until }));
+            return;
+        }
+        if tracker.paused_until() != Some(until) {
+            return;
+        }
+        std::thread::sleep(Duration::from_secs((until - now) as u64).min(AUTO_RESUME_CHECK));
+    });
+}
+
+/// Resume tracking and close the pause entry, if the pause had one
 #[tauri::command]
 pub fn resume_tracking(state: State<'_, AppState>) -> Result<(), String> {
     if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
         tracker.resume();
     }
+    end_pause_entry(&state.db, &state.event_bus, &state.pause_entry, Utc::now().timestamp())?;
+    state.event_bus.emit(EVENT_TRACKING_RESUMED, serde_json::json!({ "auto": false }));
     Ok(())
 }
 
 /// Get tracking status
 #[tauri::command]
 pub fn get_tracking_status(state: State<'_, AppState>) -> Result<serde_json::Value, String> {
-    let (is_running, is_paused, current_app) = if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
+    let (is_running, is_paused, paused_until, current_app) = if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
         let is_running = tracker.is_running();
         let is_paused = tracker.is_paused();
         let current_app = if !is_paused { tracker.get_current_app() } else { None };
-        (is_running, is_paused, current_app)
+        (is_running, is_paused, tracker.paused_until(), current_app)
     } else {
-        (false, false, None)
+        (false, false, None, None)
     };
 
     let active_session_duration: Option<i64> = state
@@ -69,6 +187,7 @@ pub fn get_tracking_status(state: State<'_, AppState>) -> Result<serde_json::Val
     Ok(serde_json::json!({
         "isTracking": is_running,
         "isPaused": is_paused,
+        "pausedUntil": paused_until,
         "currentApp": current_app,
         "activeSessionDuration": active_session_duration,
     }))
@@ -89,14 +208,30 @@ pub fn start_thinking_mode(
         .map_err(|e| e.to_string())?
         .ok_or_else(|| "Thinking category not found".to_string())?;
     
-    crate::commands::manual_entries::start_manual_entry(state, thinking_category_id, Some("Thinking mode".to_string()))
+    let request = crate::commands::manual_entries::StartManualEntryRequest {
+        category_id: thinking_category_id,
+        description: Some("Thinking mode".to_string()),
+    };
+    crate::commands::manual_entries::start_manual_entry(state, request)
 }
 
 /// Stop thinking mode
 #[tauri::command]
 pub fn stop_thinking_mode(state: State<'_, AppState>) -> Result<(), String> {
-    let entry_id = state.thinking_mode_entry_id.lock().unwrap().take()
-        .ok_or_else(|| "No active manual entry".to_string())?;
+    end_thinking_mode(&state)?.ok_or_else(|| "No active manual entry".to_string())?;
+    
+    if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
+        tracker.resume();
+    }
+    
+    Ok(())
+}
+
+/// Close the running thinking-mode entry at the current time; returns its ID if one was running
+pub fn end_thinking_mode(state: &AppState) -> Result<Option<i64>, String> {
+    let Some(entry_id) = state.thinking_mode_entry_id.lock().unwrap().take() else {
+        return Ok(None);
+    };
     
     let now = Utc::now().timestamp();
     
@@ -118,10 +253,14 @@ pub fn stop_thinking_mode(state: State<'_, AppState>) -> Result<(), String> {
             now,
         )
         .map_err(|e| e.to_string())?;
+
+    state.event_bus.emit(EVENT_MANUAL_ENTRY_UPDATED, serde_json::json!({
+        "id": entry_id,
+        "description": entry.description,
+        "category_id": entry.category_id,
+        "started_at": entry.started_at,
+        "ended_at": now,
+    }));
     
-    if let Some(tracker) = state.tracker.lock().unwrap().as_ref() {
-        tracker.resume();
-    }
-    
-    Ok(())
+    Ok(Some(entry_id))
 }
@@ -0,0 +1,9 @@
+//! App update commands
+
+use crate::updates::{self, UpdateInfo};
+
+/// Check GitHub for a newer release (works even when automatic checks are off)
+#[tauri::command]
+pub async fn check_for_updates() -> Result<UpdateInfo, String> {
+    updates::check().await
+}
@@ -0,0 +1,129 @@
+//! Webhook commands
+
+use crate::commands::common::AppState;
+use crate::database::{Webhook, WebhookDelivery};
+use crate::events::AppEvent;
+use crate::webhooks::delivery_body;
+use serde::Deserialize;
+use tauri::State;
+
+/// Default number of deliveries returned by `get_webhook_deliveries`
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+
+/// `create_webhook` request; an empty event list subscribes to all events
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// `get_webhook_deliveries` request; without `webhook_id` deliveries of all webhooks are returned
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct WebhookDeliveriesRequest {
+    #[serde(default)]
+    pub webhook_id: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+/// `update_webhook` request; an empty secret removes it
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct UpdateWebhookRequest {
+    pub id: i64,
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub enabled: bool,
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    if url.starts_with("http://") || url.starts_with("https://") {
+        Ok(())
+    } else {
+        Err("Webhook URL must start with http:// or https://".to_string())
+    }
+}
+
+fn find_webhook(state: &AppState, id: i64) -> Result<Webhook, String> {
+    state
+        .db
+        .get_webhook(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "Webhook not found".to_string())
+}
+
+/// Get all webhooks
+#[tauri::command]
+pub fn get_webhooks(state: State<'_, AppState>) -> Result<Vec<Webhook>, String> {
+    state.db.get_webhooks().map_err(|e| e.to_string())
+}
+
+/// Create webhook; an empty event list subscribes to all events
+#[tauri::command]
+pub fn create_webhook(state: State<'_, AppState>, request: CreateWebhookRequest) -> Result<Webhook, String> {
+    let url = request.url.trim();
+    validate_url(url)?;
+    let id = state
+        .db
+        .create_webhook(url, request.secret.as_deref().filter(|s| !s.is_empty()), &request.events)
+        .map_err(|e| e.to_string())?;
+    find_webhook(&state, id)
+}
+
+/// Update webhook
+#[tauri::command]
+pub fn update_webhook(state: State<'_, AppState>, request: UpdateWebhookRequest) -> Result<Webhook, String> {
+    let url = request.url.trim();
+    validate_url(url)?;
+    state
+        .db
+        .update_webhook(
+            request.id,
+            url,
+            request.secret.as_deref().filter(|s| !s.is_empty()),
+            &request.events,
+            request.enabled,
+        )
+        .map_err(|e| e.to_string())?;
+    find_webhook(&state, request.id)
+}
+
+/// Delete webhook and its delivery history
+#[tauri::command]
+pub fn delete_webhook(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.db.delete_webhook(id).map_err(|e| e.to_string())
+}
+
+/// Get recent webhook deliveries, newest first
+#[tauri::command]
+pub fn get_webhook_deliveries(
+    state: State<'_, AppState>,
+    request: WebhookDeliveriesRequest,
+) -> Result<Vec<WebhookDelivery>, String> {
+    state
+        .db
+        .get_webhook_deliveries(request.webhook_id, request.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT))
+        .map_err(|e| e.to_string())
+}
+
+/// Queue a `webhook.test` delivery for one webhook, regardless of its event filters
+#[tauri::command]
+pub fn test_webhook(state: State<'_, AppState>, id: i64) -> Result<i64, String> {
+    let webhook = find_webhook(&state, id)?;
+    let event = AppEvent {
+        name: "webhook.test".to_string(),
+        payload: serde_json::json!({ "webhook_id": webhook.id }),
+        timestamp: chrono::Utc::now().timestamp(),
+    };
+    state
+        .db
+        .enqueue_webhook_delivery(webhook.id, &event.name, &delivery_body(&event))
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,21 @@
+//! Wellbeing report commands
+
+use crate::boundaries::{self, BoundaryReport};
+use crate::commands::common::{AppState, RangeRequest};
+use crate::wellness::{self, WellnessStats};
+use tauri::State;
+
+/// Get wellbeing stats: screen time per day, longest sessions without a
+/// break, late-night and weekend work and micro-break compliance
+#[tauri::command]
+pub fn get_wellness_stats(state: State<'_, AppState>, request: RangeRequest) -> Result<WellnessStats, String> {
+    let RangeRequest { start, end } = request;
+    wellness::get_wellness_stats(&state.db, start, end).map_err(|e| e.to_string())
+}
+
+/// Get the work-life boundary report of the week starting on the local day
+/// of `week_start`: work in quiet hours and on weekends, and logged violations
+#[tauri::command]
+pub fn get_boundary_report(state: State<'_, AppState>, week_start: i64) -> Result<BoundaryReport, String> {
+    boundaries::weekly_report(&state.db, week_start).map_err(|e| e.to_string())
+}
@@ -1,6 +1,31 @@
 //! Window management commands
 
-use tauri::{AppHandle, Manager};
+use crate::commands::common::AppState;
+use crate::events::AppEvent;
+use crate::settings::AppSettings;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, PhysicalPosition, State, Window, WindowBuilder, WindowUrl};
+
+/// Label of the always-on-top mini timer window
+pub const MINI_TIMER_LABEL: &str = "mini_timer";
+
+const MINI_TIMER_WIDTH: f64 = 240.0;
+const MINI_TIMER_HEIGHT: f64 = 72.0;
+/// Label of the idle-return prompt window
+pub const IDLE_PROMPT_LABEL: &str = "idle_prompt";
+
+const IDLE_PROMPT_WIDTH: f64 = 420.0;
+const IDLE_PROMPT_HEIGHT: f64 = 500.0;
+
+/// Label of the micro-break overlay window
+pub const MICRO_BREAK_LABEL: &str = "micro_break";
+
+const MICRO_BREAK_WIDTH: f64 = 360.0;
+const MICRO_BREAK_HEIGHT: f64 = 160.0;
+
+/// Gap between snapped windows and the screen edge (logical pixels)
+const SCREEN_MARGIN: f64 = 16.0;
 
 /// Show main window
 #[tauri::command]
@@ -21,19 +46,164 @@ pub fn hide_main_window(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+/// `show_idle_prompt` request; `idle_duration` is in seconds
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct IdlePromptRequest {
+    pub idle_duration: u64,
+    pub idle_start: i64,
+}
+
 /// Show idle prompt
 #[tauri::command]
-pub fn show_idle_prompt(
+pub async fn show_idle_prompt(
     app: AppHandle,
-    idle_duration: u64,
-    _idle_start: i64,
+    request: IdlePromptRequest,
 ) -> Result<(), String> {
-    if let Some(window) = app.get_window("main") {
-        window
-            .emit("idle-return", serde_json::json!({ "duration_minutes": idle_duration / 60 }))
-            .map_err(|e| e.to_string())?;
-        window.show().map_err(|e| e.to_string())?;
-        window.set_focus().map_err(|e| e.to_string())?;
+    let IdlePromptRequest { idle_duration, idle_start } = request;
+    open_idle_prompt(&app, idle_duration / 60, idle_start)
+}
+
+/// Show the idle prompt window near the tray (top right on macOS, bottom
+/// right elsewhere) without raising the main window
+pub fn open_idle_prompt(app: &AppHandle, duration_minutes: u64, started_at: i64) -> Result<(), String> {
+    let payload = serde_json::json!({ "duration_minutes": duration_minutes, "started_at": started_at });
+    if let Some(window) = app.get_window(IDLE_PROMPT_LABEL) {
+        // Already open: update it with the latest idle period
+        window.emit("idle-return", payload).map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let url = format!(
+        "index.html?window=idle-prompt&duration_minutes={}&started_at={}",
+        duration_minutes, started_at
+    );
+    let window = WindowBuilder::new(app, IDLE_PROMPT_LABEL, WindowUrl::App(url.into()))
+        .title("You're back!")
+        .inner_size(IDLE_PROMPT_WIDTH, IDLE_PROMPT_HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let corner = if cfg!(target_os = "macos") { "top-right" } else { "bottom-right" };
+    snap_to_corner(&window, corner)?;
+    window.show().map_err(|e| e.to_string())?;
+    window.set_focus().map_err(|e| e.to_string())
+}
+
+/// Show the micro-break overlay in the middle of the screen; `id` is the
+/// logged break the overlay reports back on (see `finish_micro_break`)
+pub fn open_micro_break(app: &AppHandle, id: i64, seconds: i64) -> Result<(), String> {
+    if let Some(window) = app.get_window(MICRO_BREAK_LABEL) {
+        // The previous break is still on screen; it stays logged as missed
+        return window.set_focus().map_err(|e| e.to_string());
     }
+    let url = format!("index.html?window=micro-break&id={}&seconds={}", id, seconds);
+    WindowBuilder::new(app, MICRO_BREAK_LABEL, WindowUrl::App(url.into()))
+        .title("Micro-break")
+        .inner_size(MICRO_BREAK_WIDTH, MICRO_BREAK_HEIGHT)
+        .center()
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .build()
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
+
+/// Move a window into a corner of its monitor
+fn snap_to_corner(window: &Window, corner: &str) -> Result<(), String> {
+    let Some(monitor) = window
+        .current_monitor()
+        .map_err(|e| e.to_string())?
+        .or(window.primary_monitor().map_err(|e| e.to_string())?)
+    else {
+        return Ok(());
+    };
+    let window_size = window.outer_size().map_err(|e| e.to_string())?;
+    let margin = (SCREEN_MARGIN * monitor.scale_factor()) as i32;
+    let (origin, size) = (monitor.position(), monitor.size());
+
+    let x = if corner.ends_with("left") {
+        origin.x + margin
+    } else {
+        origin.x + size.width as i32 - window_size.width as i32 - margin
+    };
+    let y = if corner.starts_with("top") {
+        origin.y + margin
+    } else {
+        origin.y + size.height as i32 - window_size.height as i32 - margin
+    };
+    window.set_position(PhysicalPosition::new(x, y)).map_err(|e| e.to_string())
+}
+
+/// `show_mini_timer` request
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct MiniTimerRequest {
+    #[serde(default)]
+    pub corner: Option<String>,
+    #[serde(default)]
+    pub click_through: Option<bool>,
+}
+
+/// Show the mini timer window; `corner` and `click_through` are remembered for next time
+#[tauri::command]
+pub async fn show_mini_timer(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    request: MiniTimerRequest,
+) -> Result<(), String> {
+    let MiniTimerRequest { corner, click_through } = request;
+    let mut changes = HashMap::new();
+    if let Some(corner) = corner {
+        changes.insert("mini_timer_corner".to_string(), corner);
+    }
+    if let Some(click_through) = click_through {
+        changes.insert("mini_timer_click_through".to_string(), click_through.to_string());
+    }
+    state.settings.set_many(&changes)?;
+    open_mini_timer(&app, &state.settings.get())
+}
+
+/// Create (or reposition) and show the mini timer window
+pub fn open_mini_timer(app: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    let window = match app.get_window(MINI_TIMER_LABEL) {
+        Some(window) => window,
+        None => WindowBuilder::new(app, MINI_TIMER_LABEL, WindowUrl::App("index.html?window=mini-timer".into()))
+            .title("Timer")
+            .inner_size(MINI_TIMER_WIDTH, MINI_TIMER_HEIGHT)
+            .resizable(false)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(false)
+            .build()
+            .map_err(|e| e.to_string())?,
+    };
+    snap_to_corner(&window, &settings.mini_timer_corner)?;
+    window
+        .set_ignore_cursor_events(settings.mini_timer_click_through)
+        .map_err(|e| e.to_string())?;
+    window.show().map_err(|e| e.to_string())
+}
+
+/// Close the mini timer window
+#[tauri::command]
+pub fn hide_mini_timer(app: AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_window(MINI_TIMER_LABEL) {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Latest pomodoro event (phase, countdown and task as reported by the plugin)
+#[tauri::command]
+pub fn get_pomodoro_status(state: State<'_, AppState>) -> Result<Option<AppEvent>, String> {
+    Ok(state.pomodoro_status.lock().unwrap().clone())
+}
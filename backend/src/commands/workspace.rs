@@ -0,0 +1,70 @@
+//! Workspace (database) switching commands
+
+use crate::commands::common::AppState;
+use crate::commands::plugins::{load_installed_plugins, unload_all_plugins};
+use crate::events::EVENT_WORKSPACE_SWITCHED;
+use crate::workspace::{self, WorkspaceConfig};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Point the app state at the database at `path`, swap the loaded plugins for
+/// the ones installed there, and tell settings subscribers (tracker thresholds,
+/// reminders, plugins, the frontend) which settings the new workspace changes
+pub(crate) fn open_workspace(state: &AppState, path: PathBuf) -> Result<(), String> {
+    let previous = state.db.get_all_settings().map_err(|e| e.to_string())?;
+    unload_all_plugins(state);
+    let switched = state
+        .db
+        .switch_to(path.clone())
+        .map_err(|e| format!("Failed to open workspace {}: {}", path.display(), e));
+    // The new workspace's plugins, or the current ones again if the switch failed
+    load_installed_plugins(state);
+    switched?;
+    // The switch already happened; a failed broadcast must not undo it
+    if let Err(e) = state.settings.reload(&previous) {
+        eprintln!("Warning: Failed to broadcast workspace settings: {}", e);
+    }
+    Ok(())
+}
+
+/// Get the active and recently used workspaces
+#[tauri::command]
+pub fn get_workspaces() -> Result<WorkspaceConfig, String> {
+    let mut config = workspace::load_config();
+    config.active = Some(workspace::active_db_path());
+    Ok(config)
+}
+
+/// Switch to another database file, creating it if it does not exist.
+/// Tracking is paused during the handover so no activity lands in the wrong workspace.
+#[tauri::command]
+pub fn switch_workspace(state: State<'_, AppState>, path: String) -> Result<WorkspaceConfig, String> {
+    let path = PathBuf::from(path.trim());
+    if path.as_os_str().is_empty() || path.is_dir() {
+        return Err("Workspace path must be a database file".to_string());
+    }
+    if path == workspace::active_db_path() {
+        return Ok(workspace::load_config());
+    }
+
+    let tracker = state.tracker.lock().unwrap().clone();
+    let was_paused = tracker.as_ref().is_some_and(|t| t.is_paused());
+    if let Some(tracker) = &tracker {
+        tracker.pause();
+    }
+
+    // Idle thresholds and the like follow through the settings broadcast
+    let result = open_workspace(&state, path.clone());
+
+    if let (Some(tracker), false) = (&tracker, was_paused) {
+        tracker.resume();
+    }
+    result?;
+
+    let config = workspace::set_active(&path)?;
+    state.event_bus.emit(
+        EVENT_WORKSPACE_SWITCHED,
+        serde_json::json!({ "path": path.to_string_lossy() }),
+    );
+    Ok(config)
+}
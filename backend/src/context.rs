@@ -0,0 +1,115 @@
+//! Document context extraction
+//!
+//! While `capture_context_ref` is enabled, each activity gets a `context_ref`:
+//! the document, file or URL the window shows, parsed from the window title
+//! (e.g. `main.rs - tracker - Visual Studio Code` -> `main.rs`). Reports can
+//! then group time by document instead of only by app. Plugins with better
+//! sources (accessibility APIs, editor integrations) can set or replace it in
+//! `before_activity_save`.
+
+/// Longest context reference stored
+const MAX_CONTEXT_REF_LEN: usize = 512;
+
+/// Separators editors and office apps put between document and app name
+const TITLE_SEPARATORS: &[&str] = &[" - ", " — ", " – ", " | "];
+
+/// Markers for unsaved documents, e.g. `● main.rs` or `*notes.txt`
+const MODIFIED_MARKERS: &[char] = &['●', '•', '*'];
+
+/// Document, file or URL shown in a window, if the title names one
+pub fn extract_context_ref(window_title: Option<&str>) -> Option<String> {
+    let title = window_title?.trim();
+    if title.is_empty() {
+        return None;
+    }
+
+    if let Some(url) = title.split_whitespace().find(|word| is_url(word)) {
+        return Some(truncate(url));
+    }
+
+    let parts = split_title(title);
+    // Full paths are the most specific reference, then bare file names
+    parts
+        .iter()
+        .find(|part| is_path(part))
+        .or_else(|| parts.iter().find(|part| is_file_name(part)))
+        .map(|part| truncate(part))
+}
+
+fn split_title(title: &str) -> Vec<&str> {
+    let mut parts = vec![title];
+    for separator in TITLE_SEPARATORS {
+        parts = parts.iter().flat_map(|part| part.split(separator)).collect();
+    }
+    parts
+        .into_iter()
+        .map(|part| part.trim().trim_start_matches(MODIFIED_MARKERS).trim())
+        // Vim-style titles put the directory in parentheses: `main.rs (~/src)`
+        .map(|part| part.split(" (").next().unwrap_or(part).trim())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+fn is_url(word: &str) -> bool {
+    (word.starts_with("https://") || word.starts_with("http://")) && word.len() > "https://".len()
+}
+
+fn is_path(part: &str) -> bool {
+    let absolute = part.starts_with('/')
+        || part.starts_with("~/")
+        || (part.chars().nth(1) == Some(':') && part.chars().nth(2).is_some_and(|c| c == '\\' || c == '/'));
+    absolute && part.len() > 3 && !part.contains("  ")
+}
+
+/// `report.docx`, `main.rs`: a name with a short alphanumeric extension
+fn is_file_name(part: &str) -> bool {
+    match part.rsplit_once('.') {
+        Some((stem, extension)) => {
+            !stem.is_empty()
+                && !stem.ends_with('.')
+                && (1..=5).contains(&extension.len())
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+                && extension.chars().any(|c| c.is_ascii_alphabetic())
+                && !stem.contains("  ")
+        }
+        None => false,
+    }
+}
+
+fn truncate(value: &str) -> String {
+    value.chars().take(MAX_CONTEXT_REF_LEN).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_file_names_from_editor_titles() {
+        assert_eq!(
+            extract_context_ref(Some("● main.rs - tracker - Visual Studio Code")).as_deref(),
+            Some("main.rs")
+        );
+        assert_eq!(extract_context_ref(Some("Q3 Plan.docx - Word")).as_deref(), Some("Q3 Plan.docx"));
+        assert_eq!(extract_context_ref(Some("notes.md (~/work) - VIM")).as_deref(), Some("notes.md"));
+    }
+
+    #[test]
+    fn prefers_paths_and_urls() {
+        assert_eq!(
+            extract_context_ref(Some("lib.rs - /home/me/src/lib.rs - Editor")).as_deref(),
+            Some("/home/me/src/lib.rs")
+        );
+        assert_eq!(
+            extract_context_ref(Some("Preview https://example.com/doc/1")).as_deref(),
+            Some("https://example.com/doc/1")
+        );
+    }
+
+    #[test]
+    fn ignores_titles_without_documents() {
+        assert_eq!(extract_context_ref(Some("Slack - General")), None);
+        assert_eq!(extract_context_ref(Some("Version 1.2 - Release notes")), None);
+        assert_eq!(extract_context_ref(None), None);
+    }
+}
@@ -0,0 +1,194 @@
+//! Daily notes (Obsidian and other markdown vaults)
+//!
+//! `write_daily_note` renders a markdown summary of a day (totals, categories,
+//! projects, pomodoros and mood notes) into the note at `daily_note_path`, a
+//! strftime template such as `~/Vault/Daily/%Y-%m-%d.md`. The summary is kept
+//! between two HTML comments, so the rest of an existing note is left alone
+//! and writing again replaces only the summary.
+//!
+//! The summary is rendered with the `daily_note.md` template (see `templates`).
+//! While `daily_note_enabled` is `true`, today's note is written once a day
+//! after `daily_note_hour` (local time, default 22).
+
+use crate::settings::SettingsService;
+use crate::templates;
+use chrono::{Local, NaiveDate, TimeZone, Timelike};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the schedule is checked
+const POLL_INTERVAL_SECS: u64 = 60;
+
+const BLOCK_BEGIN: &str = "<!-- time-tracker -->";
+const BLOCK_END: &str = "<!-- /time-tracker -->";
+
+/// What a daily note shows
+#[derive(Debug, Clone, Default)]
+pub struct DailyNoteData {
+    pub date: String,
+    pub total_seconds: i64,
+    pub productive_seconds: i64,
+    /// (name, seconds)
+    pub categories: Vec<(String, i64)>,
+    /// (name, seconds); empty without the projects plugin
+    pub projects: Vec<(String, i64)>,
+    /// `None` without a pomodoro plugin
+    pub pomodoros: Option<i64>,
+    /// (timestamp, text)
+    pub notes: Vec<(i64, String)>,
+}
+
+/// `1h 05m`, or `25m` under an hour
+fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn durations(items: &[(String, i64)]) -> Vec<serde_json::Value> {
+    items
+        .iter()
+        .map(|(name, seconds)| {
+            serde_json::json!({ "name": name, "seconds": seconds, "duration": format_duration(*seconds) })
+        })
+        .collect()
+}
+
+/// Template context for `data`: `date`, `total`, `productive` (`1h 05m`),
+/// `total_seconds`, `productive_seconds`, `productive_percent`, `pomodoros`
+/// (unset without a pomodoro plugin), `categories` and `projects` (lists of
+/// `name`/`seconds`/`duration`) and `notes` (`timestamp`/`time`/`text`)
+pub fn context(data: &DailyNoteData) -> tera::Context {
+    let percent = if data.total_seconds > 0 {
+        (data.productive_seconds as f64 / data.total_seconds as f64 * 100.0).round() as i64
+    } else {
+        0
+    };
+    let notes: Vec<serde_json::Value> = data
+        .notes
+        .iter()
+        .map(|(timestamp, text)| {
+            let time = Local.timestamp_opt(*timestamp, 0).single().unwrap_or_default().format("%H:%M");
+            serde_json::json!({ "timestamp": timestamp, "time": time.to_string(), "text": text.replace('\n', " ") })
+        })
+        .collect();
+
+    let mut context = tera::Context::new();
+    context.insert("date", &data.date);
+    context.insert("total", &format_duration(data.total_seconds));
+    context.insert("productive", &format_duration(data.productive_seconds));
+    context.insert("total_seconds", &data.total_seconds);
+    context.insert("productive_seconds", &data.productive_seconds);
+    context.insert("productive_percent", &percent);
+    if let Some(pomodoros) = data.pomodoros {
+        context.insert("pomodoros", &pomodoros);
+    }
+    context.insert("categories", &durations(&data.categories));
+    context.insert("projects", &durations(&data.projects));
+    context.insert("notes", &notes);
+    context
+}
+
+/// Render the summary of `data`
+pub fn render(data: &DailyNoteData) -> Result<String, String> {
+    templates::render(templates::DAILY_NOTE, &context(data))
+}
+
+/// Note path for `date` from a strftime template; a leading `~` is the home directory
+pub fn note_path(path_template: &str, date: NaiveDate) -> Result<PathBuf, String> {
+    let mut path = String::new();
+    write!(path, "{}", date.format(path_template)).map_err(|_| format!("Invalid daily note path: {}", path_template))?;
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .ok_or_else(|| "Home directory not found".to_string()),
+        None => Ok(PathBuf::from(path)),
+    }
+}
+
+/// `existing` note with its summary block replaced by `summary` (appended when missing)
+pub fn merge_summary(existing: &str, summary: &str) -> String {
+    let block = format!("{}\n{}\n{}", BLOCK_BEGIN, summary.trim_end(), BLOCK_END);
+    if let (Some(begin), Some(end)) = (existing.find(BLOCK_BEGIN), existing.find(BLOCK_END)) {
+        if begin < end {
+            return format!("{}{}{}", &existing[..begin], block, &existing[end + BLOCK_END.len()..]);
+        }
+    }
+    if existing.trim().is_empty() {
+        format!("{}\n", block)
+    } else {
+        format!("{}\n\n{}\n", existing.trim_end(), block)
+    }
+}
+
+/// Write `summary` into the note at `path`, creating folders and the note as needed
+pub fn write_summary(path: &std::path::Path, summary: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let existing = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    std::fs::write(path, merge_summary(&existing, summary)).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Start the end-of-day writer; `write` gets the local day start and writes its note
+pub fn start<F>(settings_service: Arc<SettingsService>, write: F)
+where
+    F: Fn(i64) -> Result<String, String> + Send + 'static,
+{
+    std::thread::spawn(move || loop {
+        let settings = settings_service.get();
+        let now = Local::now();
+        let today = now.date_naive().format("%Y-%m-%d").to_string();
+        let due = settings.daily_note_enabled
+            && now.hour() as i64 >= settings.daily_note_hour
+            && settings.daily_note_last_date.as_deref() != Some(today.as_str());
+        if due {
+            match write(crate::database::activities::local_day_start()) {
+                Ok(_) => {
+                    if let Err(e) = settings_service.set("daily_note_last_date", &today) {
+                        eprintln!("Warning: Failed to remember daily note date: {}", e);
+                    }
+                }
+                Err(e) => eprintln!("Warning: Failed to write daily note: {}", e),
+            }
+        }
+        std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_replaces_only_its_block() {
+        let data = DailyNoteData {
+            date: "2024-03-04".to_string(),
+            total_seconds: 3 * 3600 + 300,
+            productive_seconds: 3600,
+            categories: vec![("Development".to_string(), 3 * 3600)],
+            ..Default::default()
+        };
+        let source = include_str!("../templates/daily_note.md");
+        let summary = templates::render_source(templates::DAILY_NOTE, source, &context(&data)).unwrap();
+        assert!(summary.contains("- Total: 3h 05m\n- Productive: 1h 00m (32%)\n- Pomodoros: -"), "{}", summary);
+        assert!(summary.contains("### Categories\n- Development: 3h 00m\n\n### Projects\n- None"), "{}", summary);
+
+        let note = merge_summary("# Monday\n\nMet with Ana.\n", &summary);
+        assert!(note.starts_with("# Monday\n\nMet with Ana.\n\n<!-- time-tracker -->\n## Time tracked"));
+        let rewritten = merge_summary(&format!("{}\nLater thoughts.\n", note), "Updated");
+        assert_eq!(
+            rewritten,
+            "# Monday\n\nMet with Ana.\n\n<!-- time-tracker -->\nUpdated\n<!-- /time-tracker -->\n\nLater thoughts.\n"
+        );
+    }
+}
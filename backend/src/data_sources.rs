@@ -0,0 +1,274 @@
+//! Activity data sources
+//!
+//! The tracker loop polls every registered `DataSource` and funnels the samples
+//! through one pipeline: plugin `before_activity_save` hooks (which may redact,
+//! enrich or drop the sample), `Database::upsert_activity` (merging and
+//! rule-based categorization), then plugin activity hooks. The OS foreground window
+//! is the built-in source (`tracker::ForegroundWindowSource`); other trackers
+//! (IDE heartbeats, terminal/SSH shell hooks, plugins) push samples into a
+//! `SampleQueue` or implement `DataSource` directly.
+//!
+//! Samples from different sources are stored separately (see `activities.source`),
+//! so stats can be filtered when sources overlap in time.
+
+use crate::context::extract_context_ref;
+use crate::database::Database;
+use crate::plugin_system::{ExtensionRegistry, PluginRegistry};
+use time_tracker_plugin_sdk::ActivityDraft;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Samples kept per queue while nothing drains it (e.g. tracking is stopped)
+const MAX_QUEUED_SAMPLES: usize = 10_000;
+
+/// A single observation of what the user is working on
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ActivitySample {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub domain: Option<String>,
+    /// Unix timestamp of the observation
+    pub timestamp: i64,
+    /// Stored as the activity source (e.g. `tracker`, `ide`, `plugin:ssh-tracker`)
+    pub source: String,
+}
+
+/// Producer of activity samples
+pub trait DataSource: Send + Sync {
+    /// Unique identifier of the source
+    fn id(&self) -> &str;
+
+    /// Return the samples observed since the previous poll
+    fn poll(&self, now: i64) -> Vec<ActivitySample>;
+}
+
+/// Data source fed by pushes from commands, receivers or plugins
+pub struct SampleQueue {
+    id: String,
+    samples: Mutex<Vec<ActivitySample>>,
+}
+
+impl SampleQueue {
+    /// Create an empty queue
+    pub fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            samples: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Queue a sample for the next tracker poll; the oldest samples are dropped when full
+    pub fn push(&self, sample: ActivitySample) {
+        let mut samples = self.samples.lock().unwrap();
+        if samples.len() >= MAX_QUEUED_SAMPLES {
+            samples.remove(0);
+        }
+        samples.push(sample);
+    }
+}
+
+impl DataSource for SampleQueue {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn poll(&self, _now: i64) -> Vec<ActivitySample> {
+        std::mem::take(&mut *self.samples.lock().unwrap())
+    }
+}
+
+/// Registry of additional data sources polled by the tracker
+pub struct DataSourceRegistry {
+    sources: RwLock<Vec<Arc<dyn DataSource>>>,
+    queues: Mutex<HashMap<String, Arc<SampleQueue>>>,
+}
+
+impl DataSourceRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            sources: RwLock::new(Vec::new()),
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a data source, replacing any source with the same id
+    pub fn register(&self, source: Arc<dyn DataSource>) {
+        let mut sources = self.sources.write().unwrap();
+        sources.retain(|s| s.id() != source.id());
+        sources.push(source);
+    }
+
+    /// Remove a data source
+    pub fn unregister(&self, id: &str) {
+        self.sources.write().unwrap().retain(|s| s.id() != id);
+        self.queues.lock().unwrap().remove(id);
+    }
+
+    /// Get the push queue with the given id, registering it on first use
+    pub fn queue(&self, id: &str) -> Arc<SampleQueue> {
+        let mut queues = self.queues.lock().unwrap();
+        if let Some(queue) = queues.get(id) {
+            return Arc::clone(queue);
+        }
+        let queue = Arc::new(SampleQueue::new(id));
+        queues.insert(id.to_string(), Arc::clone(&queue));
+        self.register(Arc::clone(&queue) as Arc<dyn DataSource>);
+        queue
+    }
+
+    /// Poll all registered sources
+    pub fn poll_all(&self, now: i64) -> Vec<ActivitySample> {
+        let sources: Vec<Arc<dyn DataSource>> = self.sources.read().unwrap().clone();
+        sources.iter().flat_map(|s| s.poll(now)).collect()
+    }
+}
+
+impl Default for DataSourceRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Store a sample: let plugins veto or modify it, merge/categorize it and apply
+/// plugin activity hooks. With `capture_context` the document reference is
+/// parsed from the window title (see `context`). Returns the id of the created
+/// or extended activity, or `None` if a plugin dropped the sample.
+pub fn record_sample(
+    db: &Arc<Database>,
+    extension_registry: Option<&Arc<ExtensionRegistry>>,
+    plugin_registry: Option<&Arc<PluginRegistry>>,
+    sample: &ActivitySample,
+    capture_context: bool,
+) -> Result<Option<i64>, String> {
+    let draft = ActivityDraft {
+        app_name: sample.app_name.clone(),
+        window_title: sample.window_title.clone(),
+        domain: sample.domain.clone(),
+        timestamp: sample.timestamp,
+        source: sample.source.clone(),
+        context_ref: if capture_context {
+            extract_context_ref(sample.window_title.as_deref())
+        } else {
+            None
+        },
+        extensions: serde_json::Map::new(),
+    };
+    let draft = match plugin_registry {
+        Some(registry) => match registry.before_activity_save(draft) {
+            Some(draft) => draft,
+            None => return Ok(None),
+        },
+        None => draft,
+    };
+
+    let activity_id = db
+        .upsert_activity(
+            &draft.app_name,
+            draft.window_title.as_deref(),
+            draft.domain.as_deref(),
+            draft.timestamp,
+            &draft.source,
+            draft.context_ref.as_deref(),
+        )
+        .map_err(|e| e.to_string())?;
+    if !draft.extensions.is_empty() {
+        db.update_activity_extended(activity_id, &draft.extensions)?;
+    }
+
+    // Apply plugin data hooks if extension registry is available
+    if let Some(reg) = extension_registry {
+        if let Ok(Some(mut activity)) = db.get_activity_by_id(activity_id) {
+            if let Err(e) = reg.apply_activity_hooks(&mut activity, db) {
+                eprintln!("Warning: Failed to apply activity hooks: {}", e);
+            } else if let Err(e) = db.update_activity_row(&activity) {
+                eprintln!("Warning: Failed to persist activity after hooks: {}", e);
+            }
+        }
+    }
+
+    Ok(Some(activity_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use time_tracker_plugin_sdk::{ActivityWriteDecision, Plugin, PluginAPIInterface, PluginInfo};
+
+    /// Drops samples from `Vault` and strips the window title of `Bank`
+    struct RedactingPlugin {
+        info: PluginInfo,
+    }
+
+    impl Plugin for RedactingPlugin {
+        fn info(&self) -> &PluginInfo {
+            &self.info
+        }
+
+        fn initialize(&mut self, _api: &dyn PluginAPIInterface) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn invoke_command(&self, command: &str, _params: serde_json::Value, _api: &dyn PluginAPIInterface) -> Result<serde_json::Value, String> {
+            Err(format!("Unknown command: {}", command))
+        }
+
+        fn shutdown(&self) -> Result<(), String> {
+            Ok(())
+        }
+
+        fn before_activity_save(&self, activity: &ActivityDraft) -> ActivityWriteDecision {
+            match activity.app_name.as_str() {
+                "Vault" => ActivityWriteDecision::Drop,
+                "Bank" => ActivityWriteDecision::Modify(ActivityDraft {
+                    window_title: None,
+                    ..activity.clone()
+                }),
+                _ => ActivityWriteDecision::Allow,
+            }
+        }
+    }
+
+    fn sample(app_name: &str, timestamp: i64) -> ActivitySample {
+        ActivitySample {
+            app_name: app_name.to_string(),
+            window_title: Some(format!("{} - account 1234", app_name)),
+            domain: None,
+            timestamp,
+            source: "tracker".to_string(),
+        }
+    }
+
+    #[test]
+    fn plugin_hooks_modify_and_drop_samples_before_they_are_stored() {
+        let db = Arc::new(Database::new(PathBuf::from(":memory:")).unwrap());
+        let plugins = Arc::new(PluginRegistry::new(Arc::clone(&db)));
+        plugins
+            .register(Box::new(RedactingPlugin {
+                info: PluginInfo {
+                    id: "redactor".to_string(),
+                    name: "Redactor".to_string(),
+                    version: "1.0.0".to_string(),
+                    description: None,
+                },
+            }))
+            .unwrap();
+
+        let dropped = record_sample(&db, None, Some(&plugins), &sample("Vault", 1_700_000_000), false).unwrap();
+        assert_eq!(dropped, None);
+        assert!(db.get_activities(1_699_999_000, 1_700_001_000, None, None, None, None, None).unwrap().is_empty());
+
+        let id = record_sample(&db, None, Some(&plugins), &sample("Bank", 1_700_000_100), false)
+            .unwrap()
+            .unwrap();
+        let stored = db.get_activity_by_id(id).unwrap().unwrap();
+        assert_eq!((stored.app_name.as_str(), stored.window_title), ("Bank", None));
+
+        let id = record_sample(&db, None, Some(&plugins), &sample("Editor", 1_700_000_200), false)
+            .unwrap()
+            .unwrap();
+        let stored = db.get_activity_by_id(id).unwrap().unwrap();
+        assert_eq!(stored.window_title.as_deref(), Some("Editor - account 1234"));
+    }
+}
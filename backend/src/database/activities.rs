@@ -2,13 +2,93 @@
 
 use rusqlite::{Connection, Result, params};
 use rusqlite::types::Value as SqliteValue;
-use super::common::Database;
-use super::models::Activity;
-use super::common::SYSTEM_CATEGORY_UNCATEGORIZED;
+use super::common::{Database, TodayTotal};
+use super::models::{Activity, ActivityCursor, IdleSegment};
+use super::common::{SOURCE_IDLE_CLASSIFIED, SOURCE_TRACKER, SYSTEM_CATEGORY_UNCATEGORIZED};
+use super::approvals::{ACTIVITY_NOT_APPROVED_SQL, APPROVAL_ENTITY_ACTIVITY, APPROVAL_STATUS_APPROVED};
 use chrono::Local;
 
+fn map_activity_row(row: &rusqlite::Row) -> Result<Activity> {
+    Ok(Activity {
+        id: row.get(0)?,
+        app_name: row.get(1)?,
+        window_title: row.get(2)?,
+        domain: row.get(3)?,
+        category_id: row.get(4)?,
+        started_at: row.get(5)?,
+        duration_sec: row.get(6)?,
+        is_idle: row.get(7)?,
+        source: row.get(8)?,
+        billable_override: row.get(9)?,
+        context_ref: row.get(10)?,
+        device_id: row.get(11)?,
+        device_name: row.get(12)?,
+    })
+}
+
+/// Local midnight of the current day
+pub(crate) fn local_day_start() -> i64 {
+    Local::now()
+        .date_naive()
+        .and_hms_opt(0, 0, 0)
+        .unwrap()
+        .and_local_timezone(Local)
+        .unwrap()
+        .timestamp()
+}
+
+/// Rows changed through this connection since it was opened
+fn total_changes(conn: &Connection) -> Result<i64> {
+    conn.query_row("SELECT total_changes()", [], |row| row.get(0))
+}
+
+/// Seconds between tracker samples; `input_intensity` counts samples
+pub const INPUT_SAMPLE_SECS: i64 = 5;
+
+/// Lowercase char by char. Unlike `str::to_lowercase` there are no context
+/// rules (Greek final sigma), so a substring of a value stays a substring.
+pub(crate) fn fold_case(value: &str) -> String {
+    value.chars().flat_map(char::to_lowercase).collect()
+}
+
+/// Whether a rule pattern matches a value, ignoring case.
+/// `*x*` matches values containing `x`, `*x` values ending with it and `x*`
+/// values starting with it; a pattern without wildcards matches anywhere in
+/// the value, and a pattern of only `*` matches everything.
+pub(crate) fn rule_pattern_matches(pattern: &str, value: &str) -> bool {
+    let value = fold_case(value);
+    let pattern = fold_case(pattern);
+    match (pattern.starts_with('*'), pattern.ends_with('*')) {
+        (true, true) => value.contains(pattern.trim_matches('*')),
+        (true, false) => value.ends_with(pattern.trim_start_matches('*')),
+        (false, true) => value.starts_with(pattern.trim_end_matches('*')),
+        (false, false) => value.contains(&pattern),
+    }
+}
+
 impl Database {
-    /// Insert or update an activity record.
+    /// Add `seconds` of time starting at `started_at` to the cached today total
+    /// after a write through `conn`. The cache stays valid only if it was
+    /// current before the write (`changes_before`).
+    pub(crate) fn add_to_today_total(&self, conn: &Connection, changes_before: i64, started_at: i64, seconds: i64) -> Result<()> {
+        let changes = total_changes(conn)?;
+        let mut cache = self.today_total.lock().unwrap();
+        *cache = match *cache {
+            Some(total) if total.changes == changes_before && total.day_start == local_day_start() => Some(TodayTotal {
+                seconds: if started_at >= total.day_start { total.seconds + seconds } else { total.seconds },
+                changes,
+                ..total
+            }),
+            _ => None,
+        };
+        Ok(())
+    }
+
+    /// Insert or update an activity record on this device (see `set_device`).
+    /// Samples only extend recent activities from the same `source` and device.
+    /// `context_ref` fills in the activity's reference if it has none yet, and
+    /// so do the project of a matching rule (see `match_rules_for_activity`)
+    /// and the task of a hint the title mentions (see `task_hints`).
     /// Returns the activity id (existing or newly inserted).
     pub fn upsert_activity(
         &self,
@@ -16,57 +96,149 @@ impl Database {
         window_title: Option<&str>,
         domain: Option<&str>,
         timestamp: i64,
+        source: &str,
+        context_ref: Option<&str>,
     ) -> Result<i64> {
+        let device = self.device();
+        let (device_id, device_name) = (device.as_ref().map(|d| &d.id), device.as_ref().map(|d| &d.name));
         let conn = self.conn.lock().unwrap();
+        let changes_before = total_changes(&conn)?;
 
         // Try to find matching category
-        let category_id = self.find_category_for_activity(&conn, app_name, window_title, domain);
+        let (category_id, project_id) = self.match_rules_for_activity(&conn, app_name, window_title, domain);
+        let project_id = project_id.filter(|_| Self::column_exists(&conn, "activities", "project_id"));
+        let task_id = match window_title {
+            Some(title) => Self::find_task_for_title(&conn, title)?,
+            None => None,
+        };
 
         // Check if there's a recent activity for the same app and window title (within 5 minutes)
-        let existing: Option<(i64, i64, i64)> = if let Some(title) = window_title {
+        let existing: Option<(i64, i64, i64, bool)> = if let Some(title) = window_title {
             conn.query_row(
-                "SELECT id, duration_sec, started_at FROM activities 
-                 WHERE app_name = ? AND window_title = ? AND started_at > ? - 300 
-                 ORDER BY started_at DESC LIMIT 1",
-                params![app_name, title, timestamp],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                &format!("SELECT id, duration_sec, started_at, is_idle FROM activities 
+                 WHERE app_name = ? AND window_title = ? AND started_at > ? - 300 AND source = ? AND device_id IS ?
+                   AND {ACTIVITY_NOT_APPROVED_SQL}
+                 ORDER BY started_at DESC LIMIT 1"),
+                params![app_name, title, timestamp, source, device_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
             )
             .ok()
         } else {
             conn.query_row(
-                "SELECT id, duration_sec, started_at FROM activities 
-                 WHERE app_name = ? AND window_title IS NULL AND started_at > ? - 300 
-                 ORDER BY started_at DESC LIMIT 1",
-                params![app_name, timestamp],
-                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                &format!("SELECT id, duration_sec, started_at, is_idle FROM activities 
+                 WHERE app_name = ? AND window_title IS NULL AND started_at > ? - 300 AND source = ? AND device_id IS ?
+                   AND {ACTIVITY_NOT_APPROVED_SQL}
+                 ORDER BY started_at DESC LIMIT 1"),
+                params![app_name, timestamp, source, device_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
             )
             .ok()
         };
 
-        let id = if let Some((id, duration, started_at)) = existing {
+        let id = if let Some((id, duration, started_at, is_idle)) = existing {
             let time_diff = timestamp - started_at;
             let new_duration = std::cmp::max(duration + 5, time_diff);
 
             conn.execute(
-                "UPDATE activities SET duration_sec = ?, category_id = ? WHERE id = ?",
-                params![new_duration, category_id, id],
+                "UPDATE activities SET duration_sec = ?, category_id = ?, context_ref = COALESCE(context_ref, ?) WHERE id = ?",
+                params![new_duration, category_id, context_ref, id],
             )?;
+            if let Some(project_id) = project_id {
+                conn.execute(
+                    "UPDATE activities SET project_id = COALESCE(project_id, ?) WHERE id = ?",
+                    params![project_id, id],
+                )?;
+            }
+            if let Some(task_id) = task_id {
+                conn.execute(
+                    "UPDATE activities SET task_id = COALESCE(task_id, ?) WHERE id = ?",
+                    params![task_id, id],
+                )?;
+            }
+            let added = if is_idle { 0 } else { new_duration - duration };
+            self.add_to_today_total(&conn, changes_before, started_at, added)?;
             id
         } else {
             conn.execute(
-                "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle)
-                 VALUES (?, ?, ?, ?, ?, 5, FALSE)",
-                params![app_name, window_title, domain, category_id, timestamp],
+                "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, context_ref, device_id, device_name)
+                 VALUES (?, ?, ?, ?, ?, 5, FALSE, ?, ?, ?, ?)",
+                params![app_name, window_title, domain, category_id, timestamp, source, context_ref, device_id, device_name],
             )?;
-            conn.last_insert_rowid()
+            let id = conn.last_insert_rowid();
+            if let Some(project_id) = project_id {
+                conn.execute("UPDATE activities SET project_id = ? WHERE id = ?", params![project_id, id])?;
+            }
+            if let Some(task_id) = task_id {
+                conn.execute("UPDATE activities SET task_id = ? WHERE id = ?", params![task_id, id])?;
+            }
+            self.add_to_today_total(&conn, changes_before, timestamp, 5)?;
+            id
         };
 
         Ok(id)
     }
 
+    /// Count one tracker sample of an activity for `input_intensity`, which
+    /// goes up by one for each sample with keyboard or mouse input since the
+    /// previous one. Only counts are kept, never which keys or where.
+    /// Approved activities are left unchanged.
+    pub fn add_input_sample(&self, id: i64, had_input: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "UPDATE activities SET input_intensity = COALESCE(input_intensity, 0) + ?
+                 WHERE id = ? AND {ACTIVITY_NOT_APPROVED_SQL}"
+            ),
+            params![had_input as i64, id],
+        )?;
+        Ok(())
+    }
+
+    /// Up to `limit` activities in a time range with a window title but no
+    /// domain, after `after_id`, by id: (id, app name, window title)
+    pub fn get_activities_without_domain(&self, start: i64, end: i64, after_id: i64, limit: i64) -> Result<Vec<(i64, String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, app_name, window_title FROM activities
+             WHERE started_at >= ? AND started_at <= ? AND id > ? AND domain IS NULL AND window_title IS NOT NULL
+             ORDER BY id LIMIT ?",
+        )?;
+        let rows = stmt.query_map(params![start, end, after_id, limit], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect()
+    }
+
+    /// Number of activities in a time range with a window title but no domain
+    pub fn count_activities_without_domain(&self, start: i64, end: i64) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COUNT(*) FROM activities
+             WHERE started_at >= ? AND started_at <= ? AND domain IS NULL AND window_title IS NOT NULL",
+            params![start, end],
+            |row| row.get(0),
+        )
+    }
+
+    /// Set the domain of activities that have none, in one transaction.
+    /// Approved activities are left unchanged.
+    pub fn set_activity_domains(&self, domains: &[(i64, String)]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = 0;
+        for (id, domain) in domains {
+            updated += tx.execute(
+                &format!("UPDATE activities SET domain = ? WHERE id = ? AND domain IS NULL AND {ACTIVITY_NOT_APPROVED_SQL}"),
+                params![domain, id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
     /// Update an activity row by id (used after plugin hooks modify the activity).
+    /// Approved activities cannot be edited.
     pub fn update_activity_row(&self, activity: &Activity) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        Self::ensure_not_approved(&conn, APPROVAL_ENTITY_ACTIVITY, activity.id)?;
         conn.execute(
             "UPDATE activities SET app_name = ?, window_title = ?, domain = ?, category_id = ?, started_at = ?, duration_sec = ?, is_idle = ? WHERE id = ?",
             params![
@@ -91,85 +263,54 @@ impl Database {
         window_title: Option<&str>,
         domain: Option<&str>,
     ) -> Option<i64> {
-        // Get rules ordered by priority
-        let mut stmt = conn
-            .prepare("SELECT rule_type, pattern, category_id FROM rules ORDER BY priority DESC")
-            .ok()?;
-
-        let rules = stmt
-            .query_map([], |row| {
-                Ok((
-                    row.get::<_, String>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, i64>(2)?,
-                ))
-            })
-            .ok()?;
+        self.match_rules_for_activity(conn, app_name, window_title, domain).0
+    }
+
+    /// Category and project for an activity based on rules. The category is
+    /// that of the highest priority matching rule, the project that of the
+    /// highest priority matching rule setting one, so a specific project rule
+    /// works next to a broader rule for the same app.
+    pub(crate) fn match_rules_for_activity(
+        &self,
+        conn: &Connection,
+        app_name: &str,
+        window_title: Option<&str>,
+        domain: Option<&str>,
+    ) -> (Option<i64>, Option<i64>) {
+        // Get rules ordered by priority (only the active profile's rules, if it restricts them)
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT rule_type, pattern, category_id, project_id FROM rules
+             WHERE NOT EXISTS (SELECT 1 FROM settings WHERE key = 'profile_rule_ids')
+                OR id IN (SELECT value FROM json_each((SELECT value FROM settings WHERE key = 'profile_rule_ids')))
+             ORDER BY priority DESC",
+        ) else {
+            return (None, None);
+        };
 
+        let Ok(rules) = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        }) else {
+            return (None, None);
+        };
+
+        let (mut category, mut project) = (None, None);
         for rule in rules.flatten() {
-            let (rule_type, pattern, category_id) = rule;
-            let matches = match rule_type.as_str() {
-                "app_name" => {
-                    let app_lower = app_name.to_lowercase();
-                    let pattern_lower = pattern.to_lowercase();
-                    
-                    if pattern_lower.starts_with('*') && pattern_lower.ends_with('*') {
-                        let pattern_clean = pattern_lower.trim_start_matches('*').trim_end_matches('*');
-                        app_lower.contains(&pattern_clean)
-                    } else if pattern_lower.starts_with('*') {
-                        let pattern_clean = pattern_lower.trim_start_matches('*');
-                        app_lower.ends_with(&pattern_clean)
-                    } else if pattern_lower.ends_with('*') {
-                        let pattern_clean = pattern_lower.trim_end_matches('*');
-                        app_lower.starts_with(&pattern_clean)
-                    } else {
-                        app_lower.contains(&pattern_lower)
-                    }
-                }
-                "window_title" => {
-                    if let Some(title) = window_title {
-                        let title_lower = title.to_lowercase();
-                        let pattern_lower = pattern.to_lowercase();
-                        
-                        if pattern_lower.starts_with('*') && pattern_lower.ends_with('*') {
-                            let pattern_clean = pattern_lower.trim_start_matches('*').trim_end_matches('*');
-                            title_lower.contains(&pattern_clean)
-                        } else if pattern_lower.starts_with('*') {
-                            let pattern_clean = pattern_lower.trim_start_matches('*');
-                            title_lower.ends_with(&pattern_clean)
-                        } else if pattern_lower.ends_with('*') {
-                            let pattern_clean = pattern_lower.trim_end_matches('*');
-                            title_lower.starts_with(&pattern_clean)
-                        } else {
-                            title_lower.contains(&pattern_lower)
-                        }
-                    } else {
-                        false
-                    }
-                }
-                "domain" => {
-                    if let Some(d) = domain {
-                        let domain_lower = d.to_lowercase();
-                        let pattern_lower = pattern.to_lowercase();
-                        
-                        if pattern_lower.starts_with('*') && pattern_lower.ends_with('*') {
-                            let pattern_clean = pattern_lower.trim_start_matches('*').trim_end_matches('*');
-                            domain_lower.contains(&pattern_clean)
-                        } else if pattern_lower.starts_with('*') {
-                            let pattern_clean = pattern_lower.trim_start_matches('*');
-                            domain_lower.ends_with(&pattern_clean)
-                        } else if pattern_lower.ends_with('*') {
-                            let pattern_clean = pattern_lower.trim_end_matches('*');
-                            domain_lower.starts_with(&pattern_clean)
-                        } else {
-                            domain_lower.contains(&pattern_lower)
-                        }
-                    } else {
-                        false
-                    }
-                }
-                _ => false,
+            let (rule_type, pattern, category_id, project_id) = rule;
+            if category.is_some() && (project.is_some() || project_id.is_none()) {
+                continue;
+            }
+            let value = match rule_type.as_str() {
+                "app_name" => Some(app_name),
+                "window_title" => window_title,
+                "domain" => domain,
+                _ => None,
             };
+            let matches = value.is_some_and(|value| rule_pattern_matches(&pattern, value));
 
             if matches {
                 let category_exists: bool = conn
@@ -181,10 +322,14 @@ impl Database {
                     .unwrap_or(false);
                 
                 if category_exists {
-                    return Some(category_id);
+                    category = category.or(Some(category_id));
+                    project = project.or(project_id);
                 }
             }
         }
+        if category.is_some() {
+            return (category, project);
+        }
 
         let uncategorized_exists: bool = conn
             .query_row(
@@ -195,14 +340,15 @@ impl Database {
             .unwrap_or(false);
         
         if uncategorized_exists {
-            Some(SYSTEM_CATEGORY_UNCATEGORIZED)
+            (Some(SYSTEM_CATEGORY_UNCATEGORIZED), project)
         } else {
-            None
+            (None, project)
         }
     }
 
     /// Record idle start time
     pub fn record_idle_start(&self, timestamp: i64) -> Result<()> {
+        let device = self.device();
         let conn = self.conn.lock().unwrap();
         
         let category_exists: bool = conn.query_row(
@@ -219,9 +365,15 @@ impl Database {
         }
         
         conn.execute(
-            "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle)
-             VALUES ('Idle', NULL, NULL, ?, ?, 0, TRUE)",
-            params![SYSTEM_CATEGORY_UNCATEGORIZED, timestamp],
+            "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, device_id, device_name)
+             VALUES ('Idle', NULL, NULL, ?, ?, 0, TRUE, ?, ?, ?)",
+            params![
+                SYSTEM_CATEGORY_UNCATEGORIZED,
+                timestamp,
+                SOURCE_TRACKER,
+                device.as_ref().map(|d| &d.id),
+                device.as_ref().map(|d| &d.name),
+            ],
         )?;
         Ok(())
     }
@@ -236,19 +388,73 @@ impl Database {
         Ok(())
     }
 
-    /// Update idle activity with category and description
-    pub fn update_idle_activity(
-        &self,
-        started_at: i64,
-        category_id: i64,
-        description: Option<&str>,
-    ) -> Result<()> {
+    /// Duration of the idle activity that started at `started_at`
+    pub fn get_idle_duration(&self, started_at: i64) -> Result<Option<i64>> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "UPDATE activities SET category_id = ?, window_title = ? WHERE app_name = 'Idle' AND started_at = ?",
-            params![category_id, description, started_at],
+        conn.query_row(
+            "SELECT duration_sec FROM activities WHERE app_name = 'Idle' AND started_at = ? ORDER BY id LIMIT 1",
+            params![started_at],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Replace the idle activity that started at `started_at` with classified
+    /// segments. The first segment reuses the idle row, the others are inserted;
+    /// project and task links are written in the same transaction, so a link
+    /// that fails leaves the idle period untouched. Returns the row ids in
+    /// segment order. An approved idle activity cannot be split.
+    pub fn split_idle_activity(&self, started_at: i64, segments: &[IdleSegment]) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let (idle_id, device_id, device_name): (i64, Option<String>, Option<String>) = tx.query_row(
+            "SELECT id, device_id, device_name FROM activities WHERE app_name = 'Idle' AND started_at = ? ORDER BY id LIMIT 1",
+            params![started_at],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
         )?;
-        Ok(())
+        Self::ensure_not_approved(&tx, APPROVAL_ENTITY_ACTIVITY, idle_id)?;
+
+        let mut ids = Vec::with_capacity(segments.len());
+        for (i, segment) in segments.iter().enumerate() {
+            if i == 0 {
+                tx.execute(
+                    "UPDATE activities SET category_id = ?, window_title = ?, source = ?, started_at = ?, duration_sec = ? WHERE id = ?",
+                    params![
+                        segment.category_id,
+                        segment.description,
+                        SOURCE_IDLE_CLASSIFIED,
+                        segment.started_at,
+                        segment.duration_sec,
+                        idle_id,
+                    ],
+                )?;
+                ids.push(idle_id);
+            } else {
+                tx.execute(
+                    "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, device_id, device_name)
+                     VALUES ('Idle', ?, NULL, ?, ?, ?, TRUE, ?, ?, ?)",
+                    params![
+                        segment.description,
+                        segment.category_id,
+                        segment.started_at,
+                        segment.duration_sec,
+                        SOURCE_IDLE_CLASSIFIED,
+                        device_id,
+                        device_name,
+                    ],
+                )?;
+                ids.push(tx.last_insert_rowid());
+            }
+        }
+        for (id, segment) in ids.iter().zip(segments) {
+            for (column, value) in [("project_id", segment.project_id), ("task_id", segment.task_id)] {
+                if let Some(value) = value.filter(|_| Self::column_exists(&tx, "activities", column)) {
+                    tx.execute(&format!("UPDATE activities SET {} = ? WHERE id = ?", column), params![value, id])?;
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(ids)
     }
 
     /// Get activities for a time range with optional pagination and filters
@@ -260,22 +466,10 @@ impl Database {
         offset: Option<i64>,
         exclude_idle: Option<bool>,
         category_ids: Option<&[i64]>,
+        sources: Option<&[String]>,
     ) -> Result<Vec<Activity>> {
         let conn = self.conn.lock().unwrap();
         
-        let map_row = |row: &rusqlite::Row| -> Result<Activity> {
-            Ok(Activity {
-                id: row.get(0)?,
-                app_name: row.get(1)?,
-                window_title: row.get(2)?,
-                domain: row.get(3)?,
-                category_id: row.get(4)?,
-                started_at: row.get(5)?,
-                duration_sec: row.get(6)?,
-                is_idle: row.get(7)?,
-            })
-        };
-        
         // Build WHERE clause components
         let mut where_parts: Vec<String> = vec!["started_at >= ?".to_string(), "started_at <= ?".to_string()];
         let mut params_vec: Vec<SqliteValue> = vec![
@@ -297,10 +491,20 @@ impl Database {
                 }
             }
         }
+
+        if let Some(sources) = sources {
+            if !sources.is_empty() {
+                let placeholders: Vec<String> = (0..sources.len()).map(|_| "?".to_string()).collect();
+                where_parts.push(format!("source IN ({})", placeholders.join(",")));
+                for source in sources {
+                    params_vec.push(SqliteValue::Text(source.clone()));
+                }
+            }
+        }
         
         let where_clause = where_parts.join(" AND ");
         let mut query = format!(
-            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle
+            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, billable_override, context_ref, device_id, device_name
              FROM activities
              WHERE {}
              ORDER BY started_at ASC",
@@ -322,17 +526,77 @@ impl Database {
         }
         
         let mut stmt = conn.prepare(&query)?;
-        let rows = stmt.query_map(rusqlite::params_from_iter(params_vec.iter()), map_row)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(params_vec.iter()), map_activity_row)?;
         let activities = rows.collect::<Result<Vec<_>>>()?;
 
         Ok(activities)
     }
 
+    /// Get a page of activities in (started_at, id) order, starting after `after`.
+    /// Unlike offset pagination this stays stable while the tracker inserts rows.
+    /// Returns the page and the cursor for the next one (`None` on the last page).
+    pub fn get_activities_page(
+        &self,
+        start: i64,
+        end: i64,
+        after: Option<ActivityCursor>,
+        limit: i64,
+        sources: Option<&[String]>,
+    ) -> Result<(Vec<Activity>, Option<ActivityCursor>)> {
+        let conn = self.conn.lock().unwrap();
+
+        let mut where_parts: Vec<String> = vec!["started_at >= ?".to_string(), "started_at <= ?".to_string()];
+        let mut params_vec: Vec<SqliteValue> = vec![
+            SqliteValue::Integer(start),
+            SqliteValue::Integer(end),
+        ];
+
+        if let Some(cursor) = after {
+            where_parts.push("(started_at, id) > (?, ?)".to_string());
+            params_vec.push(SqliteValue::Integer(cursor.after_started_at));
+            params_vec.push(SqliteValue::Integer(cursor.after_id));
+        }
+
+        if let Some(sources) = sources.filter(|s| !s.is_empty()) {
+            let placeholders: Vec<String> = (0..sources.len()).map(|_| "?".to_string()).collect();
+            where_parts.push(format!("source IN ({})", placeholders.join(",")));
+            params_vec.extend(sources.iter().map(|s| SqliteValue::Text(s.clone())));
+        }
+
+        // Fetch one extra row to know whether another page follows
+        params_vec.push(SqliteValue::Integer(limit + 1));
+        let query = format!(
+            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, billable_override, context_ref, device_id, device_name
+             FROM activities
+             WHERE {}
+             ORDER BY started_at ASC, id ASC
+             LIMIT ?",
+            where_parts.join(" AND ")
+        );
+
+        let mut stmt = conn.prepare(&query)?;
+        let mut activities = stmt
+            .query_map(rusqlite::params_from_iter(params_vec.iter()), map_activity_row)?
+            .collect::<Result<Vec<_>>>()?;
+
+        let next_cursor = if activities.len() as i64 > limit {
+            activities.truncate(limit as usize);
+            activities.last().map(|a| ActivityCursor {
+                after_started_at: a.started_at,
+                after_id: a.id,
+            })
+        } else {
+            None
+        };
+
+        Ok((activities, next_cursor))
+    }
+
     /// Get activity by ID
     pub fn get_activity_by_id(&self, id: i64) -> Result<Option<Activity>> {
         let conn = self.conn.lock().unwrap();
         conn.query_row(
-            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle
+            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, billable_override, context_ref, device_id, device_name
              FROM activities WHERE id = ?",
             params![id],
             |row| {
@@ -345,15 +609,49 @@ impl Database {
                     started_at: row.get(5)?,
                     duration_sec: row.get(6)?,
                     is_idle: row.get(7)?,
+                    source: row.get(8)?,
+                    billable_override: row.get(9)?,
+                    context_ref: row.get(10)?,
+                    device_id: row.get(11)?,
+                    device_name: row.get(12)?,
                 })
             },
         )
         .optional()
     }
 
-    /// Update activity category
+    /// Get the most recently started activity (idle or not)
+    pub fn get_latest_activity(&self) -> Result<Option<Activity>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, billable_override, context_ref, device_id, device_name
+             FROM activities ORDER BY started_at DESC LIMIT 1",
+            [],
+            |row| {
+                Ok(Activity {
+                    id: row.get(0)?,
+                    app_name: row.get(1)?,
+                    window_title: row.get(2)?,
+                    domain: row.get(3)?,
+                    category_id: row.get(4)?,
+                    started_at: row.get(5)?,
+                    duration_sec: row.get(6)?,
+                    is_idle: row.get(7)?,
+                    source: row.get(8)?,
+                    billable_override: row.get(9)?,
+                    context_ref: row.get(10)?,
+                    device_id: row.get(11)?,
+                    device_name: row.get(12)?,
+                })
+            },
+        )
+        .optional()
+    }
+
+    /// Update activity category; approved activities cannot be edited
     pub fn update_activity_category(&self, id: i64, category_id: Option<i64>) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        Self::ensure_not_approved(&conn, APPROVAL_ENTITY_ACTIVITY, id)?;
         conn.execute(
             "UPDATE activities SET category_id = ? WHERE id = ?",
             params![category_id, id],
@@ -361,20 +659,134 @@ impl Database {
         Ok(())
     }
 
-    /// Delete activity
+    /// Set the category of several activities in one transaction.
+    /// Approved activities are left unchanged; returns the IDs updated.
+    pub fn update_activities_category(&self, ids: &[i64], category_id: Option<i64>) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = Vec::new();
+        {
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE activities SET category_id = ? WHERE id = ? AND {ACTIVITY_NOT_APPROVED_SQL}"
+            ))?;
+            for &id in ids {
+                if stmt.execute(params![category_id, id])? > 0 {
+                    updated.push(id);
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Set or clear (`None`) the billable override of several activities.
+    /// Approved activities are left unchanged; returns the number updated.
+    pub fn set_activities_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        Self::set_billable_override(&conn, "activities", APPROVAL_ENTITY_ACTIVITY, ids, billable_override)
+    }
+
+    /// Shared by activities and manual entries; `table` is a trusted constant
+    pub(crate) fn set_billable_override(
+        conn: &Connection,
+        table: &str,
+        entity_type: &str,
+        ids: &[i64],
+        billable_override: Option<bool>,
+    ) -> Result<usize> {
+        let tx = conn.unchecked_transaction()?;
+        let mut updated = 0;
+        {
+            let mut stmt = tx.prepare(&format!(
+                "UPDATE {} SET billable_override = ?1 WHERE id = ?2 AND NOT EXISTS (
+                     SELECT 1 FROM entry_approvals
+                     WHERE entity_type = ?3 AND entity_id = ?2 AND status = ?4
+                 )",
+                table
+            ))?;
+            for id in ids {
+                updated += stmt.execute(params![billable_override, id, entity_type, APPROVAL_STATUS_APPROVED])?;
+            }
+        }
+        tx.commit()?;
+        Ok(updated)
+    }
+
+    /// Delete activity; approved activities cannot be deleted
     pub fn delete_activity(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        Self::ensure_not_approved(&conn, APPROVAL_ENTITY_ACTIVITY, id)?;
         conn.execute("DELETE FROM activities WHERE id = ?", params![id])?;
+        conn.execute(
+            "DELETE FROM entry_approvals WHERE entity_type = ? AND entity_id = ?",
+            params![APPROVAL_ENTITY_ACTIVITY, id],
+        )?;
+        conn.execute("DELETE FROM category_corrections WHERE activity_id = ?", params![id])?;
+        Self::delete_custom_field_values_for(&conn, "activity", id)?;
         Ok(())
     }
 
-    /// Reapply categorization rules to all activities
-    pub fn reapply_categorization_rules(&self) -> Result<()> {
+    /// Delete up to `limit` activities that ended before `before`; returns the number deleted.
+    /// Approved activities and their approvals are kept.
+    pub fn delete_activities_before(&self, before: i64, limit: i64) -> Result<usize> {
         let conn = self.conn.lock().unwrap();
-        
-        let mut stmt = conn.prepare(
-            "SELECT id, app_name, window_title, domain FROM activities"
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "CREATE TEMP TABLE IF NOT EXISTS expired_activities (id INTEGER PRIMARY KEY)",
+            [],
+        )?;
+        tx.execute("DELETE FROM expired_activities", [])?;
+        tx.execute(
+            &format!(
+                "INSERT INTO expired_activities (id)
+                 SELECT id FROM activities WHERE started_at + duration_sec < ? AND {ACTIVITY_NOT_APPROVED_SQL} LIMIT ?"
+            ),
+            params![before, limit],
+        )?;
+        tx.execute(
+            "DELETE FROM entry_approvals
+             WHERE entity_type = ? AND entity_id IN (SELECT id FROM expired_activities)",
+            params![APPROVAL_ENTITY_ACTIVITY],
+        )?;
+        tx.execute(
+            "DELETE FROM category_corrections WHERE activity_id IN (SELECT id FROM expired_activities)",
+            [],
         )?;
+        tx.execute(
+            "DELETE FROM custom_field_values
+             WHERE entity_id IN (SELECT id FROM expired_activities)
+               AND field_id IN (SELECT id FROM custom_field_defs WHERE entity_type = 'activity')",
+            [],
+        )?;
+        let deleted = tx.execute(
+            "DELETE FROM activities WHERE id IN (SELECT id FROM expired_activities)",
+            [],
+        )?;
+        tx.execute("DELETE FROM expired_activities", [])?;
+        tx.commit()?;
+        Ok(deleted)
+    }
+
+    /// Reapply categorization rules to all activities except approved ones.
+    /// Activities matching a rule with a project are moved to it; other project
+    /// assignments stay.
+    /// Category changes replace the last rule run (see `rule_runs`), whose ID
+    /// is returned.
+    pub fn reapply_categorization_rules(&self) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let has_projects = Self::column_exists(&conn, "activities", "project_id");
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM rule_run_changes", [])?;
+        tx.execute("DELETE FROM rule_runs", [])?;
+        tx.execute(
+            "INSERT INTO rule_runs (ran_at) VALUES (?)",
+            params![chrono::Utc::now().timestamp()],
+        )?;
+        let run_id = tx.last_insert_rowid();
+        
+        let mut stmt = tx.prepare(&format!(
+            "SELECT id, app_name, window_title, domain, category_id FROM activities WHERE {ACTIVITY_NOT_APPROVED_SQL}"
+        ))?;
         
         let activities = stmt.query_map([], |row| {
             Ok((
@@ -382,47 +794,60 @@ impl Database {
                 row.get::<_, String>(1)?,
                 row.get::<_, Option<String>>(2)?,
                 row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<i64>>(4)?,
             ))
         })?;
         
+        let mut checked = 0;
         for activity in activities {
-            let (id, app_name, window_title, domain) = activity?;
-            let category_id = self.find_category_for_activity(
-                &conn,
+            let (id, app_name, window_title, domain, old_category_id) = activity?;
+            checked += 1;
+            let (category_id, project_id) = self.match_rules_for_activity(
+                &tx,
                 &app_name,
                 window_title.as_deref(),
                 domain.as_deref(),
             );
+            if let Some(project_id) = project_id.filter(|_| has_projects) {
+                tx.execute(
+                    "UPDATE activities SET project_id = ? WHERE id = ?",
+                    params![project_id, id],
+                )?;
+            }
             
-            match category_id {
-                Some(cat_id) => {
-                    conn.execute(
-                        "UPDATE activities SET category_id = ? WHERE id = ?",
-                        params![cat_id, id],
-                    )?;
-                }
-                None => {
-                    conn.execute(
-                        "UPDATE activities SET category_id = NULL WHERE id = ?",
-                        params![id],
-                    )?;
-                }
+            if category_id != old_category_id {
+                tx.execute(
+                    "UPDATE activities SET category_id = ? WHERE id = ?",
+                    params![category_id, id],
+                )?;
+                tx.execute(
+                    "INSERT INTO rule_run_changes (run_id, activity_id, old_category_id, new_category_id)
+                     VALUES (?, ?, ?, ?)",
+                    params![run_id, id, old_category_id, category_id],
+                )?;
             }
         }
+        drop(stmt);
+        tx.execute(
+            "UPDATE rule_runs SET activities_checked = ? WHERE id = ?",
+            params![checked, run_id],
+        )?;
+        tx.commit()?;
         
-        Ok(())
+        Ok(run_id)
     }
 
-    /// Get total time for today
+    /// Get total time for today. Served from the cache while no write other
+    /// than tracker samples and new manual entries happened since it was computed.
     pub fn get_today_total(&self) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
-        let today_start = Local::now()
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-            .timestamp();
+        let today_start = local_day_start();
+        let changes = total_changes(&conn)?;
+        if let Some(total) = *self.today_total.lock().unwrap() {
+            if total.day_start == today_start && total.changes == changes {
+                return Ok(total.seconds);
+            }
+        }
 
         let activities_total: i64 = conn
             .query_row(
@@ -440,19 +865,19 @@ impl Database {
             )
             .unwrap_or(0);
 
-        Ok(activities_total + manual_total)
+        let seconds = activities_total + manual_total;
+        *self.today_total.lock().unwrap() = Some(TodayTotal {
+            day_start: today_start,
+            seconds,
+            changes,
+        });
+        Ok(seconds)
     }
 
     /// Get last activity started today (for active session calculation)
     pub fn get_last_activity_today(&self) -> Result<Option<(i64, i64, i64, String)>> {
         let conn = self.conn.lock().unwrap();
-        let today_start = Local::now()
-            .date_naive()
-            .and_hms_opt(0, 0, 0)
-            .unwrap()
-            .and_local_timezone(Local)
-            .unwrap()
-            .timestamp();
+        let today_start = local_day_start();
         
         conn.query_row(
             "SELECT id, started_at, duration_sec, app_name FROM activities WHERE started_at >= ? AND is_idle = FALSE ORDER BY started_at DESC LIMIT 1",
@@ -465,3 +890,155 @@ impl Database {
 
 // Use OptionalExtension from common module
 use super::common::OptionalExtension;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::common::SYSTEM_CATEGORY_BREAK;
+    use proptest::prelude::*;
+    use std::path::PathBuf;
+
+    const BASE: i64 = 1_700_000_000;
+
+    fn stored(db: &Database) -> Vec<(i64, i64, i64)> {
+        let conn = db.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id, started_at, duration_sec FROM activities ORDER BY id").unwrap();
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?))).unwrap();
+        rows.map(|row| row.unwrap()).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn values_match_themselves(value in "\\PC{0,24}") {
+            prop_assert!(rule_pattern_matches(&value, &value));
+        }
+
+        #[test]
+        fn wildcards_match_substrings_prefixes_and_suffixes(
+            a in "\\PC{0,12}",
+            b in "\\PC{0,12}",
+            c in "\\PC{0,12}",
+        ) {
+            let b = b.trim_matches('*');
+            let value = format!("{}{}{}", a, b, c);
+            let (contains, prefix, suffix) = (format!("*{}*", b), format!("{}{}*", a, b), format!("*{}{}", b, c));
+            prop_assert!(rule_pattern_matches(&contains, &value));
+            prop_assert!(rule_pattern_matches(b, &value));
+            prop_assert!(rule_pattern_matches(&prefix, &value));
+            prop_assert!(rule_pattern_matches(&suffix, &value));
+        }
+
+        #[test]
+        fn stars_alone_match_everything(stars in 1usize..4, value in "\\PC{0,24}") {
+            prop_assert!(rule_pattern_matches(&"*".repeat(stars), &value));
+        }
+
+        #[test]
+        fn ascii_case_is_ignored(pattern in "\\*?[a-zA-Z0-9 .]{0,8}\\*?", value in "[a-zA-Z0-9 .]{0,24}") {
+            prop_assert_eq!(
+                rule_pattern_matches(&pattern, &value),
+                rule_pattern_matches(&pattern.to_ascii_uppercase(), &value.to_ascii_lowercase())
+            );
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        #[test]
+        fn rules_categorize_unicode_titles(a in "\\PC{0,12}", b in "\\PC{1,12}", c in "\\PC{0,12}") {
+            let db = Database::new(PathBuf::from(":memory:")).unwrap();
+            let b = b.trim_matches('*');
+            let pattern = format!("*{}*", b);
+            db.add_rule("window_title", &pattern, SYSTEM_CATEGORY_BREAK, 100, None).unwrap();
+            let title = format!("{}{}{}", a, b, c);
+            let id = db.upsert_activity("Browser", Some(&title), None, BASE, SOURCE_TRACKER, None).unwrap();
+            prop_assert_eq!(db.get_activity_by_id(id).unwrap().unwrap().category_id, Some(SYSTEM_CATEGORY_BREAK));
+        }
+
+        /// Samples are merged into the newest activity of the same app and
+        /// title that started less than 5 minutes earlier. Clock skew (a sample
+        /// older than that activity) must not shrink it or open a new one.
+        #[test]
+        fn samples_merge_within_five_minutes(
+            steps in prop::collection::vec(-120i64..600, 1..24),
+            title in proptest::option::of("\\PC{0,16}"),
+        ) {
+            let db = Database::new(PathBuf::from(":memory:")).unwrap();
+            let mut timestamp = BASE;
+            for step in steps {
+                timestamp += step;
+                let before = stored(&db);
+                let open = before.iter().filter(|(_, started_at, _)| *started_at > timestamp - 300).max_by_key(|(_, started_at, _)| *started_at).copied();
+
+                let id = db.upsert_activity("Code", title.as_deref(), None, timestamp, SOURCE_TRACKER, None).unwrap();
+                let after = stored(&db);
+                let (_, started_at, duration) = *after.iter().find(|(row_id, _, _)| *row_id == id).unwrap();
+
+                match open {
+                    Some((open_id, _, open_duration)) => {
+                        prop_assert_eq!(id, open_id);
+                        prop_assert_eq!(after.len(), before.len());
+                        prop_assert!(duration >= open_duration + 5);
+                    }
+                    None => {
+                        prop_assert_eq!(after.len(), before.len() + 1);
+                        prop_assert_eq!((started_at, duration), (timestamp, 5));
+                    }
+                }
+                prop_assert!(started_at + duration >= timestamp);
+                for (row_id, _, old_duration) in &before {
+                    let (_, _, new_duration) = after.iter().find(|(id, _, _)| id == row_id).unwrap();
+                    prop_assert!(new_duration >= old_duration);
+                }
+            }
+        }
+    }
+
+    fn project_of(db: &Database, id: i64) -> Option<i64> {
+        let conn = db.conn.lock().unwrap();
+        conn.query_row("SELECT project_id FROM activities WHERE id = ?", params![id], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn rules_assign_projects() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        // Added by the projects plugin
+        db.conn.lock().unwrap().execute("ALTER TABLE activities ADD COLUMN project_id INTEGER", []).unwrap();
+        let before = db.upsert_activity("Code", Some("acme-api - main.rs"), None, BASE, SOURCE_TRACKER, None).unwrap();
+        assert_eq!(project_of(&db, before), None);
+
+        // The category still comes from the broader rule with a higher priority
+        db.add_rule("app_name", "Code", SYSTEM_CATEGORY_BREAK, 50, None).unwrap();
+        db.add_rule("window_title", "*acme*", SYSTEM_CATEGORY_UNCATEGORIZED, 0, Some(7)).unwrap();
+        let id = db.upsert_activity("Code", Some("acme-web - app.ts"), None, BASE + 600, SOURCE_TRACKER, None).unwrap();
+        let activity = db.get_activity_by_id(id).unwrap().unwrap();
+        assert_eq!((activity.category_id, project_of(&db, id)), (Some(SYSTEM_CATEGORY_BREAK), Some(7)));
+
+        db.reapply_categorization_rules().unwrap();
+        assert_eq!(project_of(&db, before), Some(7));
+    }
+
+    #[test]
+    fn idle_splits_link_projects_in_the_same_transaction() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.conn.lock().unwrap().execute("ALTER TABLE activities ADD COLUMN project_id INTEGER CHECK (project_id > 0)", []).unwrap();
+        db.record_idle_start(BASE).unwrap();
+        db.update_idle_duration(BASE, 3600).unwrap();
+        let segment = |started_at, duration_sec, project_id| IdleSegment {
+            started_at,
+            duration_sec,
+            category_id: SYSTEM_CATEGORY_BREAK,
+            description: None,
+            project_id,
+            task_id: Some(3),
+        };
+
+        // A link the column rejects leaves the idle period as it was
+        assert!(db.split_idle_activity(BASE, &[segment(BASE, 1800, Some(7)), segment(BASE + 1800, 1800, Some(-1))]).is_err());
+        assert_eq!(stored(&db).iter().map(|(_, started_at, duration)| (*started_at, *duration)).collect::<Vec<_>>(), vec![(BASE, 3600)]);
+
+        let ids = db.split_idle_activity(BASE, &[segment(BASE, 1800, Some(7)), segment(BASE + 1800, 1800, None)]).unwrap();
+        assert_eq!(ids.iter().map(|id| project_of(&db, *id)).collect::<Vec<_>>(), vec![Some(7), None]);
+    }
+}
@@ -0,0 +1,268 @@
+//! Anonymized database copies for sharing
+//!
+//! The copy is written with `VACUUM INTO` and its free text is replaced by
+//! placeholders such as `window_title-12`. Equal values get the same
+//! placeholder, so grouping, merging and durations behave as in the original.
+//! Plugin tables get the same treatment for text columns whose names suggest
+//! user content (`name`, `title`, `notes`, `client`, ...), JSON columns per
+//! field (daily plan item titles). Credentials are
+//! removed: secret settings (see `SECRET_SETTINGS`), webhook secrets, and
+//! settings profiles, whose snapshots may hold any setting.
+
+use super::common::Database;
+use crate::settings::SECRET_SETTINGS;
+use rusqlite::{params, Connection, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Core columns that may contain personal content
+const CORE_TEXT_COLUMNS: &[(&str, &str)] = &[
+    ("activities", "window_title"),
+    ("activities", "domain"),
+    ("activities", "context_ref"),
+    ("activities", "device_name"),
+    ("manual_entries", "description"),
+    ("entry_approvals", "note"),
+    ("mood_entries", "note"),
+    ("custom_field_values", "value"),
+    ("editor_heartbeats", "entity"),
+    ("editor_heartbeats", "project"),
+    ("editor_heartbeats", "branch"),
+    ("rules", "pattern"),
+    ("task_hints", "keyword"),
+    ("blocklist", "pattern"),
+    ("domain_ratings", "domain"),
+    ("webhooks", "url"),
+    ("webhook_deliveries", "payload"),
+    ("webhook_deliveries", "last_error"),
+];
+
+/// Core JSON columns (arrays of objects) and the object field that may
+/// contain personal content
+const CORE_JSON_TEXT_FIELDS: &[(&str, &str, &str)] = &[("daily_plans", "items", "title")];
+
+/// Core columns holding credentials; cleared instead of replaced
+const CORE_SECRET_COLUMNS: &[(&str, &str)] = &[("webhooks", "secret")];
+
+/// Plugin table column names treated as user content
+const PLUGIN_TEXT_COLUMNS: &[&str] = &[
+    "name", "title", "description", "notes", "note", "comment", "client", "client_name", "email", "url",
+];
+
+/// Core tables dropped from the copy entirely
+const CORE_DROPPED_TABLES: &[&str] = &["settings_profiles"];
+
+/// What an anonymized copy replaced
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AnonymizeSummary {
+    pub path: String,
+    /// Replaced columns as `table.column`
+    pub columns: Vec<String>,
+    /// Distinct values replaced by placeholders
+    pub values: i64,
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(\"{}\")", table.replace('"', "\"\"")))?;
+    let columns = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?
+        .collect();
+    columns
+}
+
+fn table_names(conn: &Connection) -> Result<HashSet<String>> {
+    let mut stmt = conn.prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")?;
+    let names = stmt.query_map([], |row| row.get(0))?.collect();
+    names
+}
+
+/// Replace the values of one column with `<column>-<n>` placeholders
+fn replace_column(conn: &Connection, table: &str, column: &str) -> Result<i64> {
+    conn.execute("DELETE FROM temp.anonymize_map", [])?;
+    conn.execute(
+        &format!(
+            "INSERT INTO temp.anonymize_map (original) SELECT DISTINCT \"{column}\" FROM \"{table}\" WHERE \"{column}\" IS NOT NULL"
+        ),
+        [],
+    )?;
+    conn.execute(
+        &format!(
+            "UPDATE \"{table}\" SET \"{column}\" = ?1 || '-' || (SELECT id FROM temp.anonymize_map WHERE original = \"{table}\".\"{column}\")
+             WHERE \"{column}\" IS NOT NULL"
+        ),
+        params![column],
+    )?;
+    conn.query_row("SELECT COUNT(*) FROM temp.anonymize_map", [], |row| row.get(0))
+}
+
+/// Anonymize the fresh copy at `path`
+fn scrub_copy(path: &Path, target: String, core_tables: &HashSet<String>) -> Result<AnonymizeSummary> {
+    let conn = Connection::open(path)?;
+    conn.execute_batch(
+        "CREATE TEMP TABLE anonymize_map (id INTEGER PRIMARY KEY, original TEXT NOT NULL UNIQUE);",
+    )?;
+    let tables = table_names(&conn)?;
+
+    let mut targets: Vec<(String, String)> = CORE_TEXT_COLUMNS
+        .iter()
+        .filter(|(table, _)| tables.contains(*table))
+        .map(|(table, column)| (table.to_string(), column.to_string()))
+        .collect();
+    let mut plugin_tables: Vec<&String> = tables.difference(core_tables).collect();
+    plugin_tables.sort();
+    for table in plugin_tables {
+        for (column, column_type) in table_columns(&conn, table)? {
+            if column_type.eq_ignore_ascii_case("TEXT") && PLUGIN_TEXT_COLUMNS.contains(&column.as_str()) {
+                targets.push((table.clone(), column));
+            }
+        }
+    }
+
+    let mut summary = AnonymizeSummary {
+        path: target,
+        columns: Vec::new(),
+        values: 0,
+    };
+    for (table, column) in targets {
+        let existing: Vec<String> = table_columns(&conn, &table)?.into_iter().map(|(name, _)| name).collect();
+        if !existing.contains(&column) {
+            continue;
+        }
+        summary.values += replace_column(&conn, &table, &column)?;
+        summary.columns.push(format!("{}.{}", table, column));
+    }
+    for (table, column, field) in CORE_JSON_TEXT_FIELDS.iter().filter(|(table, _, _)| tables.contains(*table)) {
+        summary.values += replace_json_field(&conn, table, column, field)?;
+        summary.columns.push(format!("{}.{}", table, column));
+    }
+    for (table, column) in CORE_SECRET_COLUMNS.iter().filter(|(table, _)| tables.contains(*table)) {
+        conn.execute(&format!("UPDATE \"{}\" SET \"{}\" = NULL", table, column), [])?;
+    }
+    for key in SECRET_SETTINGS {
+        conn.execute("DELETE FROM settings WHERE key = ?", params![key])?;
+    }
+    for table in CORE_DROPPED_TABLES.iter().filter(|table| tables.contains(**table)) {
+        conn.execute(&format!("DELETE FROM \"{}\"", table), [])?;
+    }
+
+    // Rebuild the file so no original text is left in free pages
+    conn.execute_batch("DROP TABLE temp.anonymize_map; VACUUM;")?;
+    Ok(summary)
+}
+
+/// Replace `field` of the objects in the JSON arrays of one column with
+/// `<field>-<n>` placeholders; values that are not JSON become `[]`
+fn replace_json_field(conn: &Connection, table: &str, column: &str, field: &str) -> Result<i64> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn.prepare(&format!("SELECT rowid, \"{column}\" FROM \"{table}\" WHERE \"{column}\" IS NOT NULL"))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_>>()?;
+        rows
+    };
+    let mut placeholders: HashMap<String, String> = HashMap::new();
+    for (rowid, json) in rows {
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap_or_else(|_| serde_json::json!([]));
+        for item in value.as_array_mut().into_iter().flatten() {
+            let Some(text) = item.get(field).and_then(|v| v.as_str()).map(str::to_string) else {
+                continue;
+            };
+            let next = placeholders.len() + 1;
+            let placeholder = placeholders.entry(text).or_insert_with(|| format!("{}-{}", field, next)).clone();
+            item[field] = serde_json::Value::String(placeholder);
+        }
+        conn.execute(
+            &format!("UPDATE \"{table}\" SET \"{column}\" = ?1 WHERE rowid = ?2"),
+            params![value.to_string(), rowid],
+        )?;
+    }
+    Ok(placeholders.len() as i64)
+}
+
+impl Database {
+    /// Path of the open database file
+    fn file_path(&self) -> Result<Option<PathBuf>> {
+        let conn = self.conn.lock().unwrap();
+        let path: String = conn.query_row("SELECT file FROM pragma_database_list WHERE name = 'main'", [], |row| row.get(0))?;
+        Ok(Some(path).filter(|p| !p.is_empty()).map(PathBuf::from))
+    }
+
+    /// Write an anonymized copy of the database to `path` (replacing any file there)
+    pub fn anonymize_copy(&self, path: &Path) -> Result<AnonymizeSummary> {
+        let target = path.to_string_lossy().to_string();
+        let same_file = self
+            .file_path()?
+            .and_then(|own| own.canonicalize().ok())
+            .is_some_and(|own| path.canonicalize().is_ok_and(|p| p == own));
+        if same_file {
+            return Err(rusqlite::Error::InvalidPath(path.to_path_buf()));
+        }
+        if path.exists() {
+            std::fs::remove_file(path).map_err(|_| rusqlite::Error::InvalidPath(path.to_path_buf()))?;
+        }
+
+        // Tables a fresh database has are core tables; everything else belongs to plugins
+        let core_tables = {
+            let fresh = Database::new(PathBuf::from(":memory:"))?;
+            let conn = fresh.conn.lock().unwrap();
+            table_names(&conn)?
+        };
+
+        let copied = {
+            let conn = self.conn.lock().unwrap();
+            conn.execute("VACUUM INTO ?1", params![target])
+        };
+        // Never leave a partial or unscrubbed copy behind
+        let result = copied.and_then(|_| scrub_copy(path, target, &core_tables));
+        if result.is_err() {
+            std::fs::remove_file(path).ok();
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::SettingsProfile;
+
+    #[test]
+    fn copies_leave_out_credentials_and_plan_titles() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        db.set_setting("mqtt_password", "hunter22").unwrap();
+        db.set_setting("date_format", "DD.MM.YYYY").unwrap();
+        let mut settings = std::collections::HashMap::new();
+        settings.insert("mqtt_password".to_string(), "hunter22".to_string());
+        db.save_settings_profile(&SettingsProfile {
+            name: "Work".to_string(),
+            settings,
+            rule_ids: None,
+            hidden_category_ids: Vec::new(),
+            auto_switch: None,
+            created_at: 0,
+        })
+        .unwrap();
+
+        let item = |title: &str| crate::database::DailyPlanItem {
+            title: title.to_string(),
+            category_id: None,
+            task_id: None,
+            target_minutes: 30,
+        };
+        db.save_daily_plan("2023-11-14", &[item("Call the dentist"), item("Call the dentist")]).unwrap();
+
+        let path = std::env::temp_dir().join(format!("time-tracker-anonymized-{}.db", std::process::id()));
+        db.anonymize_copy(&path).unwrap();
+        let copy = Connection::open(&path).unwrap();
+        let setting = |key: &str| -> Option<String> {
+            copy.query_row("SELECT value FROM settings WHERE key = ?", params![key], |row| row.get(0)).ok()
+        };
+        assert_eq!(setting("mqtt_password"), None);
+        assert_eq!(setting("date_format").as_deref(), Some("DD.MM.YYYY"));
+        let profiles: i64 = copy.query_row("SELECT COUNT(*) FROM settings_profiles", [], |row| row.get(0)).unwrap();
+        assert_eq!(profiles, 0);
+        let items: String = copy.query_row("SELECT items FROM daily_plans", [], |row| row.get(0)).unwrap();
+        let items: Vec<crate::database::DailyPlanItem> = serde_json::from_str(&items).unwrap();
+        assert_eq!((items[0].title.as_str(), items[1].title.as_str()), ("title-1", "title-1"));
+        drop(copy);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,62 @@
+//! Local app usage metrics database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::{AppMetricCount, AppUsage};
+
+const SECONDS_PER_DAY: i64 = 86400;
+
+impl Database {
+    /// Count one occurrence of `metric` on the (UTC) day containing `at`
+    pub fn increment_app_metric(&self, metric: &str, at: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO app_metrics (day, metric, count) VALUES (?1, ?2, 1)
+             ON CONFLICT (day, metric) DO UPDATE SET count = count + 1",
+            params![at.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY, metric],
+        )?;
+        Ok(())
+    }
+
+    /// Get metric totals and daily counts for days overlapping a time range
+    pub fn get_app_usage(&self, start: i64, end: i64) -> Result<AppUsage> {
+        let conn = self.conn.lock().unwrap();
+        let first_day = start.div_euclid(SECONDS_PER_DAY) * SECONDS_PER_DAY;
+
+        let mut stmt = conn.prepare(
+            "SELECT day, metric, count FROM app_metrics
+             WHERE day >= ? AND day <= ? ORDER BY day, metric",
+        )?;
+        let by_day = stmt
+            .query_map(params![first_day, end], |row| {
+                Ok(AppMetricCount {
+                    day: row.get(0)?,
+                    metric: row.get(1)?,
+                    count: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT metric, SUM(count) FROM app_metrics
+             WHERE day >= ? AND day <= ? GROUP BY metric ORDER BY SUM(count) DESC",
+        )?;
+        let totals = stmt
+            .query_map(params![first_day, end], |row| {
+                Ok(AppMetricCount {
+                    day: None,
+                    metric: row.get(0)?,
+                    count: row.get(1)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(AppUsage { totals, by_day })
+    }
+
+    /// Delete all recorded app usage metrics
+    pub fn clear_app_metrics(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM app_metrics", [])
+    }
+}
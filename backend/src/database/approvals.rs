@@ -0,0 +1,204 @@
+//! Entry approval database operations
+//!
+//! Approval state lives in `entry_approvals`, keyed by entity type and id, so
+//! manual entries and tracked activities share one state machine:
+//! `draft -> submitted -> approved | rejected`, and `rejected -> submitted`.
+//! Approved entries are final.
+
+use rusqlite::{Connection, Result, params};
+use super::common::Database;
+use super::models::{ApprovalSummary, EntryApproval};
+
+pub const APPROVAL_ENTITY_MANUAL_ENTRY: &str = "manual_entry";
+pub const APPROVAL_ENTITY_ACTIVITY: &str = "activity";
+
+pub const APPROVAL_STATUS_APPROVED: &str = "approved";
+pub const APPROVAL_STATUS_REJECTED: &str = "rejected";
+
+/// SQL condition on `activities` that holds for activities that are not approved,
+/// for bulk writes that skip approved rows
+pub(crate) const ACTIVITY_NOT_APPROVED_SQL: &str = "NOT EXISTS (
+    SELECT 1 FROM entry_approvals ea
+    WHERE ea.entity_type = 'activity' AND ea.entity_id = activities.id AND ea.status = 'approved'
+)";
+
+impl Database {
+    /// Submit all manual entries and non-idle activities in a time range for approval.
+    /// `project_id` filters on the `project_id` column added by the projects plugin.
+    /// Entries that are already submitted or approved are left untouched.
+    pub fn submit_entries_for_approval(
+        &self,
+        start: i64,
+        end: i64,
+        project_id: Option<i64>,
+    ) -> Result<ApprovalSummary> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+
+        let manual_ids = Self::approval_candidate_ids(
+            &conn,
+            "SELECT id FROM manual_entries WHERE started_at >= ? AND started_at <= ?",
+            "manual_entries",
+            start,
+            end,
+            project_id,
+        )?;
+        let activity_ids = Self::approval_candidate_ids(
+            &conn,
+            "SELECT id FROM activities WHERE started_at >= ? AND started_at <= ? AND is_idle = FALSE",
+            "activities",
+            start,
+            end,
+            project_id,
+        )?;
+
+        let tx = conn.unchecked_transaction()?;
+        let mut summary = ApprovalSummary::default();
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO entry_approvals (entity_type, entity_id, status, submitted_at)
+                 VALUES (?1, ?2, 'submitted', ?3)
+                 ON CONFLICT(entity_type, entity_id) DO UPDATE
+                 SET status = 'submitted', note = NULL, submitted_at = ?3, reviewed_at = NULL
+                 WHERE entry_approvals.status IN ('draft', 'rejected')",
+            )?;
+            for id in manual_ids {
+                summary.manual_entries +=
+                    stmt.execute(params![APPROVAL_ENTITY_MANUAL_ENTRY, id, now])? as i64;
+            }
+            for id in activity_ids {
+                summary.activities +=
+                    stmt.execute(params![APPROVAL_ENTITY_ACTIVITY, id, now])? as i64;
+            }
+        }
+        tx.commit()?;
+
+        Ok(summary)
+    }
+
+    /// Move submitted entries to `approved` or `rejected`.
+    /// Entries that are not currently submitted are skipped.
+    pub fn review_entries(
+        &self,
+        manual_entry_ids: &[i64],
+        activity_ids: &[i64],
+        status: &str,
+        note: Option<&str>,
+    ) -> Result<ApprovalSummary> {
+        if status != APPROVAL_STATUS_APPROVED && status != APPROVAL_STATUS_REJECTED {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                Some(format!("Invalid review status: {}", status)),
+            ));
+        }
+
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let tx = conn.unchecked_transaction()?;
+        let mut summary = ApprovalSummary::default();
+        {
+            let mut stmt = tx.prepare(
+                "UPDATE entry_approvals SET status = ?, note = ?, reviewed_at = ?
+                 WHERE entity_type = ? AND entity_id = ? AND status = 'submitted'",
+            )?;
+            for id in manual_entry_ids {
+                summary.manual_entries += stmt.execute(params![
+                    status, note, now, APPROVAL_ENTITY_MANUAL_ENTRY, id
+                ])? as i64;
+            }
+            for id in activity_ids {
+                summary.activities += stmt.execute(params![
+                    status, note, now, APPROVAL_ENTITY_ACTIVITY, id
+                ])? as i64;
+            }
+        }
+        tx.commit()?;
+
+        Ok(summary)
+    }
+
+    /// Get approval states for manual entries and activities starting in a time range.
+    /// Entries that were never submitted have no approval record and are not returned.
+    pub fn get_entry_approvals(&self, start: i64, end: i64) -> Result<Vec<EntryApproval>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT ea.entity_type, ea.entity_id, ea.status, ea.note, ea.submitted_at, ea.reviewed_at
+             FROM entry_approvals ea
+             LEFT JOIN manual_entries m ON ea.entity_type = 'manual_entry' AND m.id = ea.entity_id
+             LEFT JOIN activities a ON ea.entity_type = 'activity' AND a.id = ea.entity_id
+             WHERE COALESCE(m.started_at, a.started_at) >= ?
+               AND COALESCE(m.started_at, a.started_at) <= ?
+             ORDER BY COALESCE(m.started_at, a.started_at) ASC",
+        )?;
+
+        let approvals = stmt
+            .query_map(params![start, end], |row| {
+                Ok(EntryApproval {
+                    entity_type: row.get(0)?,
+                    entity_id: row.get(1)?,
+                    status: row.get(2)?,
+                    note: row.get(3)?,
+                    submitted_at: row.get(4)?,
+                    reviewed_at: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(approvals)
+    }
+
+    /// Whether an entry has been approved (approved entries must not be edited)
+    pub(crate) fn is_entry_approved(conn: &Connection, entity_type: &str, entity_id: i64) -> Result<bool> {
+        let status: Option<String> = conn
+            .query_row(
+                "SELECT status FROM entry_approvals WHERE entity_type = ? AND entity_id = ?",
+                params![entity_type, entity_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(status.as_deref() == Some(APPROVAL_STATUS_APPROVED))
+    }
+
+    /// Fail if an entry has been approved, before editing or deleting it
+    pub(crate) fn ensure_not_approved(conn: &Connection, entity_type: &str, entity_id: i64) -> Result<()> {
+        if Self::is_entry_approved(conn, entity_type, entity_id)? {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                Some("Approved entries cannot be edited".to_string()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn approval_candidate_ids(
+        conn: &Connection,
+        base_query: &str,
+        table: &str,
+        start: i64,
+        end: i64,
+        project_id: Option<i64>,
+    ) -> Result<Vec<i64>> {
+        let ids = match project_id {
+            Some(project_id) => {
+                if !Self::column_exists(conn, table, "project_id") {
+                    return Err(rusqlite::Error::SqliteFailure(
+                        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                        Some("Filtering by project requires the projects plugin".to_string()),
+                    ));
+                }
+                let query = format!("{} AND project_id = ?", base_query);
+                let mut stmt = conn.prepare(&query)?;
+                let rows = stmt.query_map(params![start, end, project_id], |row| row.get(0))?;
+                rows.collect::<Result<Vec<i64>>>()?
+            }
+            None => {
+                let mut stmt = conn.prepare(base_query)?;
+                let rows = stmt.query_map(params![start, end], |row| row.get(0))?;
+                rows.collect::<Result<Vec<i64>>>()?
+            }
+        };
+        Ok(ids)
+    }
+}
+
+use super::common::OptionalExtension;
@@ -0,0 +1,50 @@
+//! Focus-mode blocklist database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::BlocklistEntry;
+
+pub const BLOCKLIST_KIND_APP: &str = "app";
+pub const BLOCKLIST_KIND_DOMAIN: &str = "domain";
+
+impl Database {
+    /// Get all blocklist entries
+    pub fn get_blocklist(&self) -> Result<Vec<BlocklistEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, pattern, created_at FROM blocklist ORDER BY kind, pattern",
+        )?;
+        let entries = stmt
+            .query_map([], |row| {
+                Ok(BlocklistEntry {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    pattern: row.get(2)?,
+                    created_at: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Add a blocklist entry; an existing identical entry is kept
+    pub fn add_blocklist_entry(&self, kind: &str, pattern: &str) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO blocklist (kind, pattern, created_at) VALUES (?, ?, ?)",
+            params![kind, pattern, chrono::Utc::now().timestamp()],
+        )?;
+        conn.query_row(
+            "SELECT id FROM blocklist WHERE kind = ? AND pattern = ?",
+            params![kind, pattern],
+            |row| row.get(0),
+        )
+    }
+
+    /// Delete a blocklist entry
+    pub fn delete_blocklist_entry(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM blocklist WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
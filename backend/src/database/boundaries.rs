@@ -0,0 +1,45 @@
+//! Work-life boundary database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::BoundaryViolation;
+
+pub const BOUNDARY_QUIET_HOURS: &str = "quiet_hours";
+pub const BOUNDARY_WEEKEND: &str = "weekend";
+
+impl Database {
+    /// Log work outside work hours on `day`, or update the logged time.
+    /// Returns whether the day was newly logged for `kind`.
+    pub fn log_boundary_violation(&self, day: &str, kind: &str, seconds: i64, logged_at: i64) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO boundary_violations (day, kind, seconds, logged_at) VALUES (?, ?, ?, ?)",
+            params![day, kind, seconds, logged_at],
+        )?;
+        if inserted == 0 {
+            conn.execute(
+                "UPDATE boundary_violations SET seconds = MAX(seconds, ?) WHERE day = ? AND kind = ?",
+                params![seconds, day, kind],
+            )?;
+        }
+        Ok(inserted > 0)
+    }
+
+    /// Get logged violations from `first_day` to `last_day` (`YYYY-MM-DD`, inclusive)
+    pub fn get_boundary_violations(&self, first_day: &str, last_day: &str) -> Result<Vec<BoundaryViolation>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT day, kind, seconds, logged_at FROM boundary_violations
+             WHERE day >= ? AND day <= ? ORDER BY day, kind",
+        )?;
+        let rows = stmt.query_map(params![first_day, last_day], |row| {
+            Ok(BoundaryViolation {
+                day: row.get(0)?,
+                kind: row.get(1)?,
+                seconds: row.get(2)?,
+                logged_at: row.get(3)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
@@ -1,15 +1,17 @@
 //! Category management database operations
 
-use rusqlite::{Result, params};
+use rusqlite::{Connection, Result, params};
 use super::common::Database;
 use super::models::Category;
+use std::collections::HashSet;
 
 impl Database {
     /// Get all categories
     pub fn get_categories(&self) -> Result<Vec<Category>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, color, icon, is_productive, sort_order, is_system, is_pinned
+            "SELECT id, name, color, icon, is_productive, sort_order, is_system, is_pinned,
+                    exclude_from_export, is_archived
              FROM categories
              ORDER BY sort_order ASC",
         )?;
@@ -25,6 +27,8 @@ impl Database {
                     sort_order: row.get(5)?,
                     is_system: row.get(6)?,
                     is_pinned: row.get(7)?,
+                    exclude_from_export: row.get::<_, Option<bool>>(8)?.unwrap_or(false),
+                    is_archived: row.get::<_, Option<bool>>(9)?.unwrap_or(false),
                 })
             })?
             .collect::<Result<Vec<_>>>()?;
@@ -190,10 +194,108 @@ impl Database {
             ));
         }
 
+        conn.execute("DELETE FROM category_corrections WHERE category_id = ?", params![id])?;
         conn.execute("DELETE FROM categories WHERE id = ?", params![id])?;
         Ok(())
     }
 
+    /// Set whether a category's activities are kept out of exports, reports and webhooks
+    pub fn set_category_exclude_from_export(&self, id: i64, exclude: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE categories SET exclude_from_export = ? WHERE id = ?",
+            params![exclude, id],
+        )?;
+        Ok(())
+    }
+
+    /// Archive or restore a category. System categories cannot be archived.
+    pub fn set_category_archived(&self, id: i64, archived: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let is_system: bool = conn.query_row(
+            "SELECT is_system FROM categories WHERE id = ?",
+            params![id],
+            |row| row.get(0),
+        )?;
+        if is_system && archived {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+                Some("Cannot archive system category".to_string()),
+            ));
+        }
+        conn.execute(
+            "UPDATE categories SET is_archived = ?, is_pinned = is_pinned AND NOT ? WHERE id = ?",
+            params![archived, archived, id],
+        )?;
+        Ok(())
+    }
+
+    /// SQL condition on `activities` aliased as `a` that holds for activities
+    /// allowed to leave the machine. Projects count too once the projects
+    /// plugin has added `projects.exclude_from_export`.
+    pub(crate) fn exportable_condition(conn: &Connection) -> String {
+        let mut condition = String::from(
+            "NOT EXISTS (SELECT 1 FROM categories xc WHERE xc.id = a.category_id AND xc.exclude_from_export = 1)",
+        );
+        if Self::column_exists(conn, "activities", "project_id")
+            && Self::column_exists(conn, "projects", "exclude_from_export")
+        {
+            condition.push_str(
+                " AND NOT EXISTS (SELECT 1 FROM projects xp WHERE xp.id = a.project_id AND xp.exclude_from_export = 1)",
+            );
+        }
+        condition
+    }
+
+    /// IDs of activities in a range that must not be exported
+    pub fn get_export_excluded_activity_ids(&self, start: i64, end: i64) -> Result<HashSet<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let query = format!(
+            "SELECT a.id FROM activities a
+             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND NOT ({})",
+            Self::exportable_condition(&conn)
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let ids = stmt.query_map(params![start, end], |row| row.get(0))?.collect();
+        ids
+    }
+
+    /// Whether a record with this category and project must not leave the machine
+    pub fn is_excluded_from_export(&self, category_id: Option<i64>, project_id: Option<i64>) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let category_excluded = match category_id {
+            Some(id) => conn
+                .query_row(
+                    "SELECT COALESCE(exclude_from_export, 0) FROM categories WHERE id = ?",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .unwrap_or(false),
+            None => false,
+        };
+        if category_excluded {
+            return Ok(true);
+        }
+        match project_id {
+            Some(id) if Self::column_exists(&conn, "projects", "exclude_from_export") => Ok(conn
+                .query_row(
+                    "SELECT COALESCE(exclude_from_export, 0) FROM projects WHERE id = ?",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .unwrap_or(false)),
+            _ => Ok(false),
+        }
+    }
+
+    /// Readable color for a new category, as different as possible from those in use
+    pub fn suggest_category_color(&self) -> Result<String> {
+        let used: Vec<String> = self.get_categories()?.into_iter().map(|c| c.color).collect();
+        Ok(crate::colors::suggest_color(&used))
+    }
+
     /// Find category by name
     pub fn find_category_by_name(&self, name: &str) -> Result<Option<i64>> {
         let conn = self.conn.lock().unwrap();
@@ -0,0 +1,111 @@
+//! Productivity check-in database operations
+
+use rusqlite::{Result, params};
+use super::common::{Database, SOURCE_TRACKER};
+use super::models::{Checkin, CheckinBucket, CheckinStats};
+
+/// Activities that ended longer ago than this are not considered current
+const CURRENT_ACTIVITY_WINDOW_SECS: i64 = 120;
+
+fn map_bucket_row(row: &rusqlite::Row) -> Result<(Option<i64>, i64, i64, Option<f64>)> {
+    Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+}
+
+impl Database {
+    /// Store a check-in answer, linked to the activity current at `created_at`
+    pub fn insert_checkin(&self, created_at: i64, focused: bool, energy: Option<i64>) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO checkins (created_at, focused, energy, activity_id, app_name, category_id)
+             SELECT ?1, ?2, ?3, a.id, a.app_name, a.category_id
+             FROM (SELECT 1) LEFT JOIN (
+                 SELECT id, app_name, category_id FROM activities
+                 WHERE source = ?4 AND is_idle = FALSE
+                   AND started_at <= ?1 AND started_at + duration_sec >= ?1 - ?5
+                 ORDER BY started_at DESC LIMIT 1
+             ) a",
+            params![created_at, focused, energy, SOURCE_TRACKER, CURRENT_ACTIVITY_WINDOW_SECS],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get check-ins in a time range
+    pub fn get_checkins(&self, start: i64, end: i64) -> Result<Vec<Checkin>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, created_at, focused, energy, activity_id, app_name, category_id
+             FROM checkins WHERE created_at >= ? AND created_at <= ? ORDER BY created_at",
+        )?;
+        let checkins = stmt
+            .query_map(params![start, end], |row| {
+                Ok(Checkin {
+                    id: row.get(0)?,
+                    created_at: row.get(1)?,
+                    focused: row.get(2)?,
+                    energy: row.get(3)?,
+                    activity_id: row.get(4)?,
+                    app_name: row.get(5)?,
+                    category_id: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(checkins)
+    }
+
+    /// Get check-in stats by local hour of day and by category.
+    /// `utc_offset_sec` shifts timestamps to local time for the hourly buckets.
+    pub fn get_checkin_stats(&self, start: i64, end: i64, utc_offset_sec: i64) -> Result<CheckinStats> {
+        let conn = self.conn.lock().unwrap();
+        let (total, focused_count, average_energy): (i64, i64, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(focused), 0), AVG(energy)
+             FROM checkins WHERE created_at >= ? AND created_at <= ?",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT ((created_at + ?3) % 86400 + 86400) % 86400 / 3600 AS hour,
+                    COUNT(*), SUM(focused), AVG(energy)
+             FROM checkins WHERE created_at >= ?1 AND created_at <= ?2
+             GROUP BY hour ORDER BY hour",
+        )?;
+        let by_hour = stmt
+            .query_map(params![start, end, utc_offset_sec], map_bucket_row)?
+            .map(|row| {
+                row.map(|(hour, count, focused_count, average_energy)| CheckinBucket {
+                    hour,
+                    category_id: None,
+                    count,
+                    focused_count,
+                    average_energy,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut stmt = conn.prepare(
+            "SELECT category_id, COUNT(*), SUM(focused), AVG(energy)
+             FROM checkins WHERE created_at >= ? AND created_at <= ?
+             GROUP BY category_id ORDER BY COUNT(*) DESC",
+        )?;
+        let by_category = stmt
+            .query_map(params![start, end], map_bucket_row)?
+            .map(|row| {
+                row.map(|(category_id, count, focused_count, average_energy)| CheckinBucket {
+                    hour: None,
+                    category_id,
+                    count,
+                    focused_count,
+                    average_energy,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(CheckinStats {
+            total,
+            focused_count,
+            average_energy,
+            by_hour,
+            by_category,
+        })
+    }
+}
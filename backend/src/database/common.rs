@@ -1,20 +1,58 @@
 //! Common database utilities, constants, and schema initialization
 
-use rusqlite::{Connection, Result, params};
-use std::path::PathBuf;
+use rusqlite::{Connection, OpenFlags, Result, params};
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use crate::device::Device;
 
 /// Latest schema version; new installs get this without running migrations.
-const LATEST_SCHEMA_VERSION: i64 = 13;
+const LATEST_SCHEMA_VERSION: i64 = 40;
+
+/// First 16 bytes of every SQLite 3 database file
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
 
 /// System category IDs (negative to avoid conflicts with regular categories)
 pub const SYSTEM_CATEGORY_UNCATEGORIZED: i64 = -1;
 pub const SYSTEM_CATEGORY_BREAK: i64 = -2;
 pub const SYSTEM_CATEGORY_THINKING: i64 = -3;
 
+/// Source attribution values for activities and manual entries.
+/// Integrations may use their own values (e.g. `calendar`).
+pub const SOURCE_TRACKER: &str = "tracker";
+pub const SOURCE_MANUAL: &str = "manual";
+pub const SOURCE_IDLE_CLASSIFIED: &str = "idle-classified";
+/// Manual entry covering a tracking pause that was given a reason
+pub const SOURCE_PAUSE: &str = "pause";
+pub const SOURCE_IDE: &str = "ide";
+pub const SOURCE_TERMINAL: &str = "terminal";
+/// Samples replayed from a recording (development builds, see `replay`)
+pub const SOURCE_REPLAY: &str = "replay";
+/// Generated demo data (development builds, see `demo_data`)
+pub const SOURCE_DEMO: &str = "demo";
+/// Prefix for imported data, e.g. `import:toggl` or `import:screen_time`
+pub const SOURCE_IMPORT_PREFIX: &str = "import:";
+/// Prefix for entries created by plugins, e.g. `plugin:pomodoro`
+pub const SOURCE_PLUGIN_PREFIX: &str = "plugin:";
+
+/// Cached result of `get_today_total`
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TodayTotal {
+    /// Local midnight the total counts from
+    pub day_start: i64,
+    pub seconds: i64,
+    /// `total_changes()` of the connection when `seconds` was last correct
+    pub changes: i64,
+}
+
 /// Database wrapper
 pub struct Database {
     pub(crate) conn: Mutex<Connection>,
+    /// Today's total, kept current by tracker and manual entry writes.
+    /// Any other write changes `total_changes()` and makes it stale.
+    pub(crate) today_total: Mutex<Option<TodayTotal>>,
+    /// Device new activities are attributed to (see `set_device`)
+    pub(crate) device: Mutex<Option<Device>>,
 }
 
 impl Database {
@@ -25,14 +63,68 @@ impl Database {
             std::fs::create_dir_all(parent).ok();
         }
 
-        let conn = Connection::open(&path)?;
+        let mut conn = Connection::open(&path)?;
+        conn.profile(Some(super::slow_queries::profile));
         let db = Self {
             conn: Mutex::new(conn),
+            today_total: Mutex::new(None),
+            device: Mutex::new(None),
         };
         db.init()?;
         Ok(db)
     }
 
+    /// Replace the connection with one to another database file (created and
+    /// migrated if needed). The current connection is kept if opening fails.
+    /// Unlike the app's own database at startup, a picked file is never
+    /// salvaged or moved aside: anything but a missing file or a readable
+    /// SQLite database is refused.
+    pub fn switch_to(&self, path: PathBuf) -> Result<()> {
+        Self::check_database_file(&path)?;
+        let fresh = Self::new(path)?;
+        let conn = fresh.conn.into_inner().unwrap_or_else(|e| e.into_inner());
+        let device = self.device();
+        let mut current = self.conn.lock().unwrap();
+        *current = conn;
+        *self.today_total.lock().unwrap() = None;
+        if let Some(device) = device {
+            Self::claim_unattributed_activities(&current, &device)?;
+        }
+        Ok(())
+    }
+
+    /// Accept a missing file (a new database) or an SQLite database that opens read-only
+    fn check_database_file(path: &Path) -> Result<()> {
+        let not_a_database = |reason: String| {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_NOTADB),
+                Some(format!("{} is not a time tracker database: {}", path.display(), reason)),
+            )
+        };
+        let mut header = [0u8; 16];
+        match std::fs::File::open(path) {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(not_a_database(e.to_string())),
+            Ok(mut file) => {
+                if file.read_exact(&mut header).is_err() || &header != SQLITE_HEADER {
+                    return Err(not_a_database("missing SQLite header".to_string()));
+                }
+            }
+        }
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| not_a_database(e.to_string()))?;
+        conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |row| row.get::<_, i64>(0))
+            .map_err(|e| not_a_database(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Write pending WAL pages back to the database file (no-op outside WAL mode)
+    pub fn checkpoint(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+        Ok(())
+    }
+
     /// Initialize the database schema
     pub(crate) fn init(&self) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -49,14 +141,22 @@ impl Database {
                 started_at INTEGER NOT NULL,
                 duration_sec INTEGER NOT NULL,
                 is_idle BOOLEAN DEFAULT FALSE,
+                source TEXT NOT NULL DEFAULT 'tracker',
+                billable_override BOOLEAN,
+                context_ref TEXT,
+                device_id TEXT,
+                device_name TEXT,
+                input_intensity INTEGER,
                 FOREIGN KEY (category_id) REFERENCES categories(id)
             );
 
             CREATE INDEX IF NOT EXISTS idx_activities_started ON activities(started_at);
+            CREATE INDEX IF NOT EXISTS idx_activities_started_at_is_idle ON activities(started_at, is_idle);
             CREATE INDEX IF NOT EXISTS idx_activities_app ON activities(app_name);
             CREATE INDEX IF NOT EXISTS idx_activities_category ON activities(category_id);
             CREATE INDEX IF NOT EXISTS idx_activities_app_category ON activities(app_name, category_id);
             CREATE INDEX IF NOT EXISTS idx_activities_domain ON activities(domain);
+            CREATE INDEX IF NOT EXISTS idx_activities_context_ref ON activities(context_ref);
 
             -- Categories table
             CREATE TABLE IF NOT EXISTS categories (
@@ -67,7 +167,9 @@ impl Database {
                 is_productive BOOLEAN DEFAULT TRUE,
                 sort_order INTEGER DEFAULT 0,
                 is_system BOOLEAN DEFAULT FALSE,
-                is_pinned BOOLEAN DEFAULT FALSE
+                is_pinned BOOLEAN DEFAULT FALSE,
+                exclude_from_export BOOLEAN DEFAULT FALSE,
+                is_archived BOOLEAN DEFAULT FALSE
             );
 
             -- Rules table
@@ -77,9 +179,18 @@ impl Database {
                 pattern TEXT NOT NULL,
                 category_id INTEGER NOT NULL,
                 priority INTEGER DEFAULT 0,
+                project_id INTEGER,
                 FOREIGN KEY (category_id) REFERENCES categories(id)
             );
 
+            -- Window title keywords attaching activities to tasks (see task_hints)
+            CREATE TABLE IF NOT EXISTS task_hints (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                keyword TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                task_id INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+
             -- Manual entries table
             CREATE TABLE IF NOT EXISTS manual_entries (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -88,11 +199,219 @@ impl Database {
                 category_id INTEGER,
                 started_at INTEGER NOT NULL,
                 ended_at INTEGER NOT NULL,
+                source TEXT NOT NULL DEFAULT 'manual',
+                billable_override BOOLEAN,
                 FOREIGN KEY (category_id) REFERENCES categories(id)
             );
 
             CREATE INDEX IF NOT EXISTS idx_manual_entries_started ON manual_entries(started_at);
 
+            -- Entry approvals table (review state of manual entries and activities)
+            CREATE TABLE IF NOT EXISTS entry_approvals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_id INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'draft',
+                note TEXT,
+                submitted_at INTEGER,
+                reviewed_at INTEGER,
+                UNIQUE(entity_type, entity_id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_entry_approvals_status ON entry_approvals(status);
+
+            -- Category corrections table (manual recategorizations, used to train suggestions)
+            CREATE TABLE IF NOT EXISTS category_corrections (
+                activity_id INTEGER PRIMARY KEY,
+                category_id INTEGER NOT NULL,
+                corrected_at INTEGER NOT NULL,
+                FOREIGN KEY (activity_id) REFERENCES activities(id),
+                FOREIGN KEY (category_id) REFERENCES categories(id)
+            );
+
+            -- Custom fields (user-defined fields on activities, manual entries and plugin entities)
+            CREATE TABLE IF NOT EXISTS custom_field_defs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                field_type TEXT NOT NULL,
+                options TEXT,
+                sort_order INTEGER DEFAULT 0,
+                UNIQUE(entity_type, name)
+            );
+
+            CREATE TABLE IF NOT EXISTS custom_field_values (
+                field_id INTEGER NOT NULL,
+                entity_id INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (field_id, entity_id),
+                FOREIGN KEY (field_id) REFERENCES custom_field_defs(id)
+            );
+
+            -- Outbound webhooks and their delivery queue
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL,
+                secret TEXT,
+                events TEXT NOT NULL DEFAULT '[]',
+                enabled BOOLEAN DEFAULT TRUE,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                webhook_id INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at INTEGER NOT NULL,
+                last_error TEXT,
+                created_at INTEGER NOT NULL,
+                delivered_at INTEGER,
+                FOREIGN KEY (webhook_id) REFERENCES webhooks(id)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+
+            CREATE TABLE IF NOT EXISTS editor_heartbeats (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                time INTEGER NOT NULL,
+                entity TEXT NOT NULL,
+                entity_type TEXT NOT NULL DEFAULT 'file',
+                project TEXT,
+                language TEXT,
+                branch TEXT,
+                is_write BOOLEAN DEFAULT FALSE,
+                editor TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_editor_heartbeats_time ON editor_heartbeats(time);
+
+            CREATE TABLE IF NOT EXISTS blocklist (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL CHECK (kind IN ('app', 'domain')),
+                pattern TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE (kind, pattern)
+            );
+
+            CREATE TABLE IF NOT EXISTS domain_ratings (
+                domain TEXT PRIMARY KEY,
+                rating TEXT NOT NULL CHECK (rating IN ('productive', 'neutral', 'distracting')),
+                updated_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS checkins (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                created_at INTEGER NOT NULL,
+                focused BOOLEAN NOT NULL,
+                energy INTEGER CHECK (energy BETWEEN 1 AND 5),
+                activity_id INTEGER,
+                app_name TEXT,
+                category_id INTEGER,
+                FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_checkins_created_at ON checkins(created_at);
+
+            CREATE TABLE IF NOT EXISTS mood_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                score INTEGER NOT NULL CHECK (score BETWEEN 1 AND 5),
+                note TEXT,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_mood_entries_timestamp ON mood_entries(timestamp);
+
+            CREATE TABLE IF NOT EXISTS settings_profiles (
+                name TEXT PRIMARY KEY,
+                settings TEXT NOT NULL DEFAULT '{}',
+                rule_ids TEXT,
+                hidden_category_ids TEXT NOT NULL DEFAULT '[]',
+                auto_switch TEXT,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS app_metrics (
+                day INTEGER NOT NULL,
+                metric TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, metric)
+            );
+
+            -- Eye-strain micro-breaks offered by the overlay (see micro_breaks)
+            CREATE TABLE IF NOT EXISTS micro_breaks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at INTEGER NOT NULL,
+                duration_sec INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'missed' CHECK (status IN ('completed', 'skipped', 'missed'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_micro_breaks_started ON micro_breaks(started_at);
+
+            -- Days with work outside work hours (see boundaries)
+            CREATE TABLE IF NOT EXISTS boundary_violations (
+                day TEXT NOT NULL,
+                kind TEXT NOT NULL CHECK (kind IN ('quiet_hours', 'weekend')),
+                seconds INTEGER NOT NULL,
+                logged_at INTEGER NOT NULL,
+                PRIMARY KEY (day, kind)
+            );
+
+            -- Maintenance jobs (see jobs)
+            CREATE TABLE IF NOT EXISTS jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                params TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'queued'
+                    CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled')),
+                cursor TEXT,
+                processed INTEGER NOT NULL DEFAULT 0,
+                total INTEGER,
+                error TEXT,
+                cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                finished_at INTEGER
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);
+
+            -- Last reapply_categorization_rules run and the categories it changed (see rule_runs)
+            CREATE TABLE IF NOT EXISTS rule_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ran_at INTEGER NOT NULL,
+                activities_checked INTEGER NOT NULL DEFAULT 0,
+                undone_at INTEGER
+            );
+
+            CREATE TABLE IF NOT EXISTS rule_run_changes (
+                run_id INTEGER NOT NULL,
+                activity_id INTEGER NOT NULL,
+                old_category_id INTEGER,
+                new_category_id INTEGER,
+                PRIMARY KEY (run_id, activity_id)
+            );
+
+            -- Intended work per local day, items as JSON (see daily_plans)
+            CREATE TABLE IF NOT EXISTS daily_plans (
+                date TEXT PRIMARY KEY,
+                items TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+
+            -- UUIDs of synced records (see sync_ids)
+            CREATE TABLE IF NOT EXISTS sync_ids (
+                entity_type TEXT NOT NULL,
+                local_id INTEGER NOT NULL,
+                uuid TEXT NOT NULL UNIQUE,
+                deleted_at INTEGER,
+                PRIMARY KEY (entity_type, local_id)
+            );
+
             -- Settings table
             CREATE TABLE IF NOT EXISTS settings (
                 key TEXT PRIMARY KEY,
@@ -137,6 +456,10 @@ impl Database {
                 self.migrate(&conn)?;
             }
         }
+        Self::migrate_settings(&conn)?;
+        // After migrations, since older databases get the column from one
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_activities_device ON activities(device_id)", [])?;
+        Self::ensure_sync_ids(&conn)?;
 
         // Check if default data has already been initialized
         let default_data_initialized: bool = conn.query_row(
@@ -267,10 +590,569 @@ impl Database {
         if version < 11 { self.migrate_v11(conn)?; }
         if version < 12 { self.migrate_v12(conn)?; }
         if version < 13 { self.migrate_v13(conn)?; }
+        if version < 14 { self.migrate_v14(conn)?; }
+        if version < 15 { self.migrate_v15(conn)?; }
+        if version < 16 { self.migrate_v16(conn)?; }
+        if version < 17 { self.migrate_v17(conn)?; }
+        if version < 18 { self.migrate_v18(conn)?; }
+        if version < 19 { self.migrate_v19(conn)?; }
+        if version < 20 { self.migrate_v20(conn)?; }
+        if version < 21 { self.migrate_v21(conn)?; }
+        if version < 22 { self.migrate_v22(conn)?; }
+        if version < 23 { self.migrate_v23(conn)?; }
+        if version < 24 { self.migrate_v24(conn)?; }
+        if version < 25 { self.migrate_v25(conn)?; }
+        if version < 26 { self.migrate_v26(conn)?; }
+        if version < 27 { self.migrate_v27(conn)?; }
+        if version < 28 { self.migrate_v28(conn)?; }
+        if version < 29 { self.migrate_v29(conn)?; }
+        if version < 30 { self.migrate_v30(conn)?; }
+        if version < 31 { self.migrate_v31(conn)?; }
+        if version < 32 { self.migrate_v32(conn)?; }
+        if version < 33 { self.migrate_v33(conn)?; }
+        if version < 34 { self.migrate_v34(conn)?; }
+        if version < 35 { self.migrate_v35(conn)?; }
+        if version < 36 { self.migrate_v36(conn)?; }
+        if version < 37 { self.migrate_v37(conn)?; }
+        if version < 38 { self.migrate_v38(conn)?; }
+        if version < 39 { self.migrate_v39(conn)?; }
+        if version < 40 { self.migrate_v40(conn)?; }
 
         Ok(())
     }
 
+    fn migrate_v40(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS daily_plans (
+                date TEXT PRIMARY KEY,
+                items TEXT NOT NULL DEFAULT '[]',
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '40')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v39(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS rule_runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                ran_at INTEGER NOT NULL,
+                activities_checked INTEGER NOT NULL DEFAULT 0,
+                undone_at INTEGER
+            );
+
+            CREATE TABLE IF NOT EXISTS rule_run_changes (
+                run_id INTEGER NOT NULL,
+                activity_id INTEGER NOT NULL,
+                old_category_id INTEGER,
+                new_category_id INTEGER,
+                PRIMARY KEY (run_id, activity_id)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '39')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v38(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS jobs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL,
+                params TEXT NOT NULL DEFAULT '{}',
+                status TEXT NOT NULL DEFAULT 'queued'
+                    CHECK (status IN ('queued', 'running', 'completed', 'failed', 'cancelled')),
+                cursor TEXT,
+                processed INTEGER NOT NULL DEFAULT 0,
+                total INTEGER,
+                error TEXT,
+                cancel_requested BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL,
+                finished_at INTEGER
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_status ON jobs(status);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '38')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v37(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS boundary_violations (
+                day TEXT NOT NULL,
+                kind TEXT NOT NULL CHECK (kind IN ('quiet_hours', 'weekend')),
+                seconds INTEGER NOT NULL,
+                logged_at INTEGER NOT NULL,
+                PRIMARY KEY (day, kind)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '37')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v36(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS micro_breaks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at INTEGER NOT NULL,
+                duration_sec INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'missed' CHECK (status IN ('completed', 'skipped', 'missed'))
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_micro_breaks_started ON micro_breaks(started_at);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '36')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v35(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS task_hints (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                keyword TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                task_id INTEGER NOT NULL,
+                created_at INTEGER NOT NULL
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '35')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v34(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "rules", "project_id") {
+            tx.execute("ALTER TABLE rules ADD COLUMN project_id INTEGER", [])?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '34')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v33(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "activities", "input_intensity") {
+            tx.execute("ALTER TABLE activities ADD COLUMN input_intensity INTEGER", [])?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '33')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v32(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        // Rows get their UUIDs from `ensure_sync_ids` once migrations are done
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS sync_ids (
+                entity_type TEXT NOT NULL,
+                local_id INTEGER NOT NULL,
+                uuid TEXT NOT NULL UNIQUE,
+                deleted_at INTEGER,
+                PRIMARY KEY (entity_type, local_id)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '32')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v31(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        for column in ["device_id", "device_name"] {
+            if !Self::column_exists(conn, "activities", column) {
+                tx.execute(&format!("ALTER TABLE activities ADD COLUMN {} TEXT", column), [])?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '31')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v30(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS domain_ratings (
+                domain TEXT PRIMARY KEY,
+                rating TEXT NOT NULL CHECK (rating IN ('productive', 'neutral', 'distracting')),
+                updated_at INTEGER NOT NULL
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '30')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v29(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "categories", "is_archived") {
+            tx.execute("ALTER TABLE categories ADD COLUMN is_archived BOOLEAN DEFAULT FALSE", [])?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '29')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v28(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "categories", "exclude_from_export") {
+            tx.execute("ALTER TABLE categories ADD COLUMN exclude_from_export BOOLEAN DEFAULT FALSE", [])?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '28')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v27(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "activities", "context_ref") {
+            tx.execute("ALTER TABLE activities ADD COLUMN context_ref TEXT", [])?;
+        }
+        tx.execute("CREATE INDEX IF NOT EXISTS idx_activities_context_ref ON activities(context_ref)", [])?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '27')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v26(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        for table in ["activities", "manual_entries"] {
+            if !Self::column_exists(conn, table, "billable_override") {
+                tx.execute(&format!("ALTER TABLE {} ADD COLUMN billable_override BOOLEAN", table), [])?;
+            }
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '26')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v25(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS app_metrics (
+                day INTEGER NOT NULL,
+                metric TEXT NOT NULL,
+                count INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, metric)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '25')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v24(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS settings_profiles (
+                name TEXT PRIMARY KEY,
+                settings TEXT NOT NULL DEFAULT '{}',
+                rule_ids TEXT,
+                hidden_category_ids TEXT NOT NULL DEFAULT '[]',
+                auto_switch TEXT,
+                created_at INTEGER NOT NULL
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '24')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v23(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS mood_entries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                score INTEGER NOT NULL CHECK (score BETWEEN 1 AND 5),
+                note TEXT,
+                created_at INTEGER NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_mood_entries_timestamp ON mood_entries(timestamp);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '23')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v22(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS checkins (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                created_at INTEGER NOT NULL,
+                focused BOOLEAN NOT NULL,
+                energy INTEGER CHECK (energy BETWEEN 1 AND 5),
+                activity_id INTEGER,
+                app_name TEXT,
+                category_id INTEGER,
+                FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE SET NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_checkins_created_at ON checkins(created_at);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '22')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v21(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS blocklist (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                kind TEXT NOT NULL CHECK (kind IN ('app', 'domain')),
+                pattern TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                UNIQUE (kind, pattern)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '21')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v20(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS editor_heartbeats (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                time INTEGER NOT NULL,
+                entity TEXT NOT NULL,
+                entity_type TEXT NOT NULL DEFAULT 'file',
+                project TEXT,
+                language TEXT,
+                branch TEXT,
+                is_write BOOLEAN DEFAULT FALSE,
+                editor TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_editor_heartbeats_time ON editor_heartbeats(time);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '20')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v19(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "manual_entries", "source") {
+            tx.execute(
+                "ALTER TABLE manual_entries ADD COLUMN source TEXT NOT NULL DEFAULT 'manual'",
+                [],
+            )?;
+        }
+        // Idle periods that were already given a category came from the idle prompt
+        tx.execute(
+            "UPDATE activities SET source = 'idle-classified'
+             WHERE is_idle = TRUE AND source = 'tracker' AND category_id IS NOT NULL AND category_id != ?",
+            params![SYSTEM_CATEGORY_UNCATEGORIZED],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '19')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v18(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        if !Self::column_exists(conn, "activities", "source") {
+            tx.execute(
+                "ALTER TABLE activities ADD COLUMN source TEXT NOT NULL DEFAULT 'tracker'",
+                [],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '18')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v17(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS webhooks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                url TEXT NOT NULL,
+                secret TEXT,
+                events TEXT NOT NULL DEFAULT '[]',
+                enabled BOOLEAN DEFAULT TRUE,
+                created_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                webhook_id INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'pending',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at INTEGER NOT NULL,
+                last_error TEXT,
+                created_at INTEGER NOT NULL,
+                delivered_at INTEGER,
+                FOREIGN KEY (webhook_id) REFERENCES webhooks(id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due ON webhook_deliveries(status, next_attempt_at);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '17')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v16(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS custom_field_defs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                name TEXT NOT NULL,
+                field_type TEXT NOT NULL,
+                options TEXT,
+                sort_order INTEGER DEFAULT 0,
+                UNIQUE(entity_type, name)
+            );
+            CREATE TABLE IF NOT EXISTS custom_field_values (
+                field_id INTEGER NOT NULL,
+                entity_id INTEGER NOT NULL,
+                value TEXT NOT NULL,
+                PRIMARY KEY (field_id, entity_id),
+                FOREIGN KEY (field_id) REFERENCES custom_field_defs(id)
+            );
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '16')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v15(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "CREATE TABLE IF NOT EXISTS category_corrections (
+                activity_id INTEGER PRIMARY KEY,
+                category_id INTEGER NOT NULL,
+                corrected_at INTEGER NOT NULL,
+                FOREIGN KEY (activity_id) REFERENCES activities(id),
+                FOREIGN KEY (category_id) REFERENCES categories(id)
+            )",
+            [],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '15')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn migrate_v14(&self, conn: &Connection) -> Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS entry_approvals (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                entity_type TEXT NOT NULL,
+                entity_id INTEGER NOT NULL,
+                status TEXT NOT NULL DEFAULT 'draft',
+                note TEXT,
+                submitted_at INTEGER,
+                reviewed_at INTEGER,
+                UNIQUE(entity_type, entity_id)
+            );
+            CREATE INDEX IF NOT EXISTS idx_entry_approvals_status ON entry_approvals(status);
+        "#)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('schema_version', '14')",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
     fn migrate_v13(&self, conn: &Connection) -> Result<()> {
         let tx = conn.unchecked_transaction()?;
         tx.execute(
@@ -304,7 +1186,7 @@ impl Database {
     }
 
     /// Check if a column exists in a table
-    fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    pub(crate) fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
         let query = format!("PRAGMA table_info({})", table);
         let mut stmt = match conn.prepare(&query) {
             Ok(s) => s,
@@ -0,0 +1,271 @@
+//! Custom field database operations
+//!
+//! Custom fields are user-defined text/number/select fields attached to core
+//! entities (activities, manual entries) and to plugin entities such as
+//! projects and tasks, which are referenced by entity type and id.
+
+use rusqlite::{Connection, Result, params};
+use super::common::Database;
+use super::models::{CustomFieldDef, CustomFieldValue};
+
+/// Entity types that custom fields can be defined on
+pub const CUSTOM_FIELD_ENTITY_TYPES: &[&str] = &["activity", "manual_entry", "project", "task"];
+
+/// Supported custom field types
+pub const CUSTOM_FIELD_TYPES: &[&str] = &["text", "number", "select"];
+
+fn invalid(msg: String) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(
+        rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CONSTRAINT),
+        Some(msg),
+    )
+}
+
+fn validate_def(entity_type: &str, field_type: &str, options: &[String]) -> Result<()> {
+    if !CUSTOM_FIELD_ENTITY_TYPES.contains(&entity_type) {
+        return Err(invalid(format!("Unsupported entity type: {}", entity_type)));
+    }
+    if !CUSTOM_FIELD_TYPES.contains(&field_type) {
+        return Err(invalid(format!("Unsupported field type: {}", field_type)));
+    }
+    if field_type == "select" && options.is_empty() {
+        return Err(invalid("Select fields need at least one option".to_string()));
+    }
+    Ok(())
+}
+
+/// Why `value` is not valid for a field of `field_type` with `options`, if it isn't
+fn value_error(field_type: &str, options: &[String], value: &str) -> Option<String> {
+    match field_type {
+        "number" if value.parse::<f64>().is_err() => Some(format!("'{}' is not a number", value)),
+        "select" if !options.iter().any(|o| o == value) => Some(format!("'{}' is not one of the field options", value)),
+        _ => None,
+    }
+}
+
+fn map_unique_error(e: rusqlite::Error) -> rusqlite::Error {
+    if let rusqlite::Error::SqliteFailure(ref err, Some(ref msg)) = e {
+        if err.code == rusqlite::ffi::ErrorCode::ConstraintViolation
+            && msg.contains("UNIQUE constraint")
+            && msg.contains("custom_field_defs")
+        {
+            return invalid("A custom field with this name already exists".to_string());
+        }
+    }
+    e
+}
+
+impl Database {
+    /// Get custom field definitions, optionally for a single entity type
+    pub fn get_custom_field_defs(&self, entity_type: Option<&str>) -> Result<Vec<CustomFieldDef>> {
+        let conn = self.conn.lock().unwrap();
+        Self::query_custom_field_defs(&conn, entity_type)
+    }
+
+    fn query_custom_field_defs(conn: &Connection, entity_type: Option<&str>) -> Result<Vec<CustomFieldDef>> {
+        let mut stmt = conn.prepare(
+            "SELECT id, entity_type, name, field_type, options, sort_order
+             FROM custom_field_defs
+             WHERE ?1 IS NULL OR entity_type = ?1
+             ORDER BY entity_type, sort_order, name",
+        )?;
+
+        let defs = stmt
+            .query_map(params![entity_type], |row| {
+                let options: Option<String> = row.get(4)?;
+                Ok(CustomFieldDef {
+                    id: row.get(0)?,
+                    entity_type: row.get(1)?,
+                    name: row.get(2)?,
+                    field_type: row.get(3)?,
+                    options: options
+                        .and_then(|o| serde_json::from_str(&o).ok())
+                        .unwrap_or_default(),
+                    sort_order: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(defs)
+    }
+
+    /// Create a custom field definition
+    pub fn create_custom_field_def(
+        &self,
+        entity_type: &str,
+        name: &str,
+        field_type: &str,
+        options: &[String],
+        sort_order: i64,
+    ) -> Result<i64> {
+        validate_def(entity_type, field_type, options)?;
+        let options_json = serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string());
+
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO custom_field_defs (entity_type, name, field_type, options, sort_order)
+             VALUES (?, ?, ?, ?, ?)",
+            params![entity_type, name, field_type, options_json, sort_order],
+        )
+        .map_err(map_unique_error)?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Update a custom field definition (the entity type cannot change).
+    /// Values that no longer fit a changed type or option list are cleared;
+    /// returns how many were.
+    pub fn update_custom_field_def(
+        &self,
+        id: i64,
+        name: &str,
+        field_type: &str,
+        options: &[String],
+        sort_order: i64,
+    ) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let entity_type: String = conn.query_row(
+            "SELECT entity_type FROM custom_field_defs WHERE id = ?",
+            params![id],
+            |row| row.get(0),
+        )?;
+        validate_def(&entity_type, field_type, options)?;
+        let options_json = serde_json::to_string(options).unwrap_or_else(|_| "[]".to_string());
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE custom_field_defs SET name = ?, field_type = ?, options = ?, sort_order = ?
+             WHERE id = ?",
+            params![name, field_type, options_json, sort_order, id],
+        )
+        .map_err(map_unique_error)?;
+
+        let invalid_entities: Vec<i64> = {
+            let mut stmt = tx.prepare("SELECT entity_id, value FROM custom_field_values WHERE field_id = ?")?;
+            let values = stmt
+                .query_map(params![id], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>>>()?;
+            values
+                .into_iter()
+                .filter(|(_, value)| value_error(field_type, options, value).is_some())
+                .map(|(entity_id, _)| entity_id)
+                .collect()
+        };
+        for entity_id in &invalid_entities {
+            tx.execute(
+                "DELETE FROM custom_field_values WHERE field_id = ? AND entity_id = ?",
+                params![id, entity_id],
+            )?;
+        }
+        tx.commit()?;
+        Ok(invalid_entities.len())
+    }
+
+    /// Delete a custom field definition and all its values
+    pub fn delete_custom_field_def(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM custom_field_values WHERE field_id = ?", params![id])?;
+        tx.execute("DELETE FROM custom_field_defs WHERE id = ?", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Get custom field values for an entity type, optionally for a single entity
+    pub fn get_custom_field_values(&self, entity_type: &str, entity_id: Option<i64>) -> Result<Vec<CustomFieldValue>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT v.field_id, v.entity_id, v.value
+             FROM custom_field_values v
+             JOIN custom_field_defs d ON d.id = v.field_id
+             WHERE d.entity_type = ?1 AND (?2 IS NULL OR v.entity_id = ?2)
+             ORDER BY v.entity_id, d.sort_order",
+        )?;
+
+        let values = stmt
+            .query_map(params![entity_type, entity_id], |row| {
+                Ok(CustomFieldValue {
+                    field_id: row.get(0)?,
+                    entity_id: row.get(1)?,
+                    value: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(values)
+    }
+
+    /// Set (or clear, when `value` is None) a custom field value.
+    /// Number values must parse as numbers and select values must be one of the options.
+    pub fn set_custom_field_value(&self, field_id: i64, entity_id: i64, value: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+
+        let value = match value.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(value) => value,
+            None => {
+                conn.execute(
+                    "DELETE FROM custom_field_values WHERE field_id = ? AND entity_id = ?",
+                    params![field_id, entity_id],
+                )?;
+                return Ok(());
+            }
+        };
+
+        let (field_type, options): (String, Option<String>) = conn.query_row(
+            "SELECT field_type, options FROM custom_field_defs WHERE id = ?",
+            params![field_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let options: Vec<String> = options
+            .and_then(|o| serde_json::from_str(&o).ok())
+            .unwrap_or_default();
+        if let Some(error) = value_error(&field_type, &options, value) {
+            return Err(invalid(error));
+        }
+
+        conn.execute(
+            "INSERT OR REPLACE INTO custom_field_values (field_id, entity_id, value) VALUES (?, ?, ?)",
+            params![field_id, entity_id, value],
+        )?;
+        Ok(())
+    }
+
+    /// Remove all custom field values of a deleted entity
+    pub(crate) fn delete_custom_field_values_for(conn: &Connection, entity_type: &str, entity_id: i64) -> Result<()> {
+        conn.execute(
+            "DELETE FROM custom_field_values
+             WHERE entity_id = ? AND field_id IN (SELECT id FROM custom_field_defs WHERE entity_type = ?)",
+            params![entity_id, entity_type],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn changing_a_field_clears_values_that_no_longer_fit() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let options = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let id = db.create_custom_field_def("activity", "Client", "select", &options(&["Acme", "Globex"]), 0).unwrap();
+        db.set_custom_field_value(id, 1, Some("Acme")).unwrap();
+        db.set_custom_field_value(id, 2, Some("Globex")).unwrap();
+
+        assert_eq!(db.update_custom_field_def(id, "Client", "select", &options(&["Acme", "Initech"]), 0).unwrap(), 1);
+        let values: Vec<(i64, String)> = db
+            .get_custom_field_values("activity", None)
+            .unwrap()
+            .into_iter()
+            .map(|v| (v.entity_id, v.value))
+            .collect();
+        assert_eq!(values, vec![(1, "Acme".to_string())]);
+
+        assert_eq!(db.update_custom_field_def(id, "Client", "number", &[], 0).unwrap(), 1);
+        assert!(db.get_custom_field_values("activity", None).unwrap().is_empty());
+        db.set_custom_field_value(id, 1, Some("42")).unwrap();
+        assert_eq!(db.update_custom_field_def(id, "Client", "text", &[], 0).unwrap(), 0);
+    }
+}
@@ -0,0 +1,169 @@
+//! Daily plan database operations
+//!
+//! A plan lists what the user intends to work on during a local day
+//! (`YYYY-MM-DD`), each item with a target duration. The review measures an
+//! item by its task (activities attached by the projects plugin) or else its
+//! category (activities and manual entries). Items sharing a category each
+//! count the whole category's time.
+
+use rusqlite::{params, OptionalExtension, Result};
+use std::collections::HashMap;
+use super::common::Database;
+use super::models::{DailyPlan, DailyPlanItem, DailyPlanItemReview, DailyPlanReview};
+
+fn map_plan_row(row: &rusqlite::Row) -> Result<DailyPlan> {
+    let items: String = row.get(1)?;
+    Ok(DailyPlan {
+        date: row.get(0)?,
+        items: serde_json::from_str(&items).unwrap_or_default(),
+        created_at: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
+
+impl Database {
+    /// Get the plan of `day`
+    pub fn get_daily_plan(&self, day: &str) -> Result<Option<DailyPlan>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT date, items, created_at, updated_at FROM daily_plans WHERE date = ?",
+            params![day],
+            map_plan_row,
+        )
+        .optional()
+    }
+
+    /// Get the plans from `first_day` to `last_day` (`YYYY-MM-DD`, inclusive)
+    pub fn get_daily_plans(&self, first_day: &str, last_day: &str) -> Result<Vec<DailyPlan>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT date, items, created_at, updated_at FROM daily_plans
+             WHERE date >= ? AND date <= ? ORDER BY date",
+        )?;
+        let plans = stmt.query_map(params![first_day, last_day], map_plan_row)?.collect();
+        plans
+    }
+
+    /// Create the plan of `day` or replace its items
+    pub fn save_daily_plan(&self, day: &str, items: &[DailyPlanItem]) -> Result<DailyPlan> {
+        let items_json = serde_json::to_string(items).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let now = chrono::Utc::now().timestamp();
+        {
+            let conn = self.conn.lock().unwrap();
+            conn.execute(
+                "INSERT INTO daily_plans (date, items, created_at, updated_at) VALUES (?1, ?2, ?3, ?3)
+                 ON CONFLICT(date) DO UPDATE SET items = excluded.items, updated_at = excluded.updated_at",
+                params![day, items_json, now],
+            )?;
+        }
+        self.get_daily_plan(day)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+    }
+
+    /// Delete the plan of `day`; returns whether there was one
+    pub fn delete_daily_plan(&self, day: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        Ok(conn.execute("DELETE FROM daily_plans WHERE date = ?", params![day])? > 0)
+    }
+
+    /// Compare the plan of `day` with the time tracked from `start` to `end`
+    /// (the bounds of that day); `None` if the day has no plan
+    pub fn get_daily_plan_review(&self, day: &str, start: i64, end: i64) -> Result<Option<DailyPlanReview>> {
+        let Some(plan) = self.get_daily_plan(day)? else {
+            return Ok(None);
+        };
+        let conn = self.conn.lock().unwrap();
+
+        let mut by_category: HashMap<i64, i64> = HashMap::new();
+        let mut tracked_sec = 0;
+        let mut stmt = conn.prepare(
+            "SELECT category_id, SUM(duration_sec) FROM activities
+             WHERE started_at >= ?1 AND started_at < ?2 AND is_idle = 0
+             GROUP BY category_id
+             UNION ALL
+             SELECT category_id, SUM(ended_at - started_at) FROM manual_entries
+             WHERE started_at >= ?1 AND started_at < ?2
+             GROUP BY category_id",
+        )?;
+        let rows = stmt.query_map(params![start, end], |row| Ok((row.get::<_, Option<i64>>(0)?, row.get::<_, i64>(1)?)))?;
+        for row in rows {
+            let (category_id, seconds) = row?;
+            tracked_sec += seconds;
+            if let Some(category_id) = category_id {
+                *by_category.entry(category_id).or_insert(0) += seconds;
+            }
+        }
+
+        // Tasks come from the projects plugin, which adds the column
+        let mut by_task: HashMap<i64, i64> = HashMap::new();
+        if Self::column_exists(&conn, "activities", "task_id") {
+            let mut stmt = conn.prepare(
+                "SELECT task_id, SUM(duration_sec) FROM activities
+                 WHERE started_at >= ?1 AND started_at < ?2 AND is_idle = 0 AND task_id IS NOT NULL
+                 GROUP BY task_id",
+            )?;
+            by_task = stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<Result<_>>()?;
+        }
+
+        let items: Vec<DailyPlanItemReview> = plan
+            .items
+            .into_iter()
+            .map(|item| {
+                let target_sec = item.target_minutes * 60;
+                let actual_sec = match (item.task_id, item.category_id) {
+                    (Some(task_id), _) => Some(by_task.get(&task_id).copied().unwrap_or(0)),
+                    (None, Some(category_id)) => Some(by_category.get(&category_id).copied().unwrap_or(0)),
+                    (None, None) => None,
+                };
+                DailyPlanItemReview {
+                    item,
+                    target_sec,
+                    actual_sec,
+                    difference_sec: actual_sec.map(|actual| actual - target_sec),
+                }
+            })
+            .collect();
+        Ok(Some(DailyPlanReview {
+            date: plan.date,
+            planned_sec: items.iter().map(|review| review.target_sec).sum(),
+            items,
+            tracked_sec,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::common::{SOURCE_MANUAL, SOURCE_TRACKER, SYSTEM_CATEGORY_BREAK};
+    use std::path::PathBuf;
+
+    #[test]
+    fn plans_are_reviewed_against_tracked_time() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let start = 1_700_000_000;
+        let item = |title: &str, category_id, target_minutes| DailyPlanItem {
+            title: title.to_string(),
+            category_id,
+            task_id: None,
+            target_minutes,
+        };
+        db.save_daily_plan("2023-11-14", &[item("Inbox", None, 30)]).unwrap();
+        let plan = db
+            .save_daily_plan("2023-11-14", &[item("Break", Some(SYSTEM_CATEGORY_BREAK), 20), item("Inbox", None, 30)])
+            .unwrap();
+        assert_eq!(plan.items.len(), 2);
+
+        let id = db.upsert_activity("Coffee", None, None, start + 60, SOURCE_TRACKER, None).unwrap();
+        db.update_activity_category(id, Some(SYSTEM_CATEGORY_BREAK)).unwrap();
+        db.add_manual_entry(Some("Walk"), Some(SYSTEM_CATEGORY_BREAK), start + 600, start + 1800, SOURCE_MANUAL).unwrap();
+
+        let review = db.get_daily_plan_review("2023-11-14", start, start + 86400).unwrap().unwrap();
+        assert_eq!((review.planned_sec, review.tracked_sec), (3000, 1205));
+        assert_eq!(review.items[0].actual_sec, Some(1205));
+        assert_eq!(review.items[0].difference_sec, Some(5));
+        assert_eq!(review.items[1].actual_sec, None);
+
+        assert!(db.delete_daily_plan("2023-11-14").unwrap());
+        assert!(db.get_daily_plan_review("2023-11-14", start, start + 86400).unwrap().is_none());
+    }
+}
@@ -0,0 +1,77 @@
+//! Demo data database operations
+
+use rusqlite::{params, Result};
+use super::common::{Database, SOURCE_DEMO};
+use crate::demo_data::DemoData;
+
+/// What `replace_demo_data` wrote
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct DemoDataSummary {
+    pub activities: i64,
+    pub manual_entries: i64,
+    /// Activities skipped because they overlap real activities
+    pub skipped: i64,
+    /// Plugins that added their own demo data
+    pub plugins: Vec<String>,
+}
+
+impl Database {
+    /// Replace earlier demo data with `data`. Activities are categorized by the
+    /// rules like tracked ones; those overlapping real activities are skipped.
+    pub fn replace_demo_data(&self, data: &DemoData) -> Result<DemoDataSummary> {
+        let device = self.device();
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM activities WHERE source = ?", params![SOURCE_DEMO])?;
+        tx.execute("DELETE FROM manual_entries WHERE source = ?", params![SOURCE_DEMO])?;
+
+        let mut summary = DemoDataSummary::default();
+        for activity in &data.activities {
+            let ended_at = activity.started_at + activity.duration_sec;
+            let overlaps: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM activities WHERE source != ? AND started_at < ? AND started_at + duration_sec > ?)",
+                params![SOURCE_DEMO, ended_at, activity.started_at],
+                |row| row.get(0),
+            )?;
+            if overlaps {
+                summary.skipped += 1;
+                continue;
+            }
+            let category_id = self.find_category_for_activity(
+                &tx,
+                &activity.app_name,
+                activity.window_title.as_deref(),
+                activity.domain.as_deref(),
+            );
+            tx.execute(
+                "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, device_id, device_name)
+                 VALUES (?, ?, ?, ?, ?, ?, FALSE, ?, ?, ?)",
+                params![
+                    activity.app_name,
+                    activity.window_title,
+                    activity.domain,
+                    category_id,
+                    activity.started_at,
+                    activity.duration_sec,
+                    SOURCE_DEMO,
+                    device.as_ref().map(|d| &d.id),
+                    device.as_ref().map(|d| &d.name),
+                ],
+            )?;
+            summary.activities += 1;
+        }
+
+        for entry in &data.manual_entries {
+            tx.execute(
+                "INSERT INTO manual_entries (entry_type, description, category_id, started_at, ended_at, source)
+                 VALUES ('', ?, (SELECT id FROM categories WHERE name = ? ORDER BY is_system DESC LIMIT 1), ?, ?, ?)",
+                params![entry.description, entry.category, entry.started_at, entry.ended_at, SOURCE_DEMO],
+            )?;
+            summary.manual_entries += 1;
+        }
+
+        tx.commit()?;
+        *self.today_total.lock().unwrap() = None;
+        Ok(summary)
+    }
+}
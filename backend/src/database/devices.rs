@@ -0,0 +1,123 @@
+//! Devices activities were recorded on
+//!
+//! Activities carry the `device_id`/`device_name` of the install that recorded
+//! them. Rows from before devices were tracked have none; the first install to
+//! open the database claims them, since it is almost always the one that
+//! recorded them. Imported rows are left alone.
+
+use super::common::{Database, SOURCE_IMPORT_PREFIX};
+use crate::device::Device;
+use rusqlite::{params, Connection, Result};
+
+/// A device with recorded activities
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DeviceSummary {
+    pub device_id: String,
+    pub device_name: Option<String>,
+    pub activities: i64,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    /// Whether this is the device the app runs on
+    pub current: bool,
+}
+
+impl Database {
+    /// Attribute activities recorded from now on to `device`, and claim
+    /// unattributed tracked activities for it. Returns the number claimed.
+    pub fn set_device(&self, device: Device) -> Result<usize> {
+        *self.device.lock().unwrap() = Some(device.clone());
+        let conn = self.conn.lock().unwrap();
+        Self::claim_unattributed_activities(&conn, &device)
+    }
+
+    /// Device new activities are attributed to
+    pub fn device(&self) -> Option<Device> {
+        self.device.lock().unwrap().clone()
+    }
+
+    pub(crate) fn claim_unattributed_activities(conn: &Connection, device: &Device) -> Result<usize> {
+        conn.execute(
+            "UPDATE activities SET device_id = ?, device_name = ?
+             WHERE device_id IS NULL AND source NOT LIKE ? || '%'",
+            params![device.id, device.name, SOURCE_IMPORT_PREFIX],
+        )
+    }
+
+    /// Devices with recorded activities, most recently seen first
+    pub fn get_devices(&self) -> Result<Vec<DeviceSummary>> {
+        let current = self.device().map(|device| device.id);
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT device_id,
+                    (SELECT device_name FROM activities n WHERE n.device_id = a.device_id ORDER BY started_at DESC LIMIT 1),
+                    COUNT(*), MIN(started_at), MAX(started_at)
+             FROM activities a
+             WHERE device_id IS NOT NULL
+             GROUP BY device_id
+             ORDER BY MAX(started_at) DESC",
+        )?;
+        let devices = stmt
+            .query_map([], |row| {
+                let device_id: String = row.get(0)?;
+                Ok(DeviceSummary {
+                    current: current.as_deref() == Some(device_id.as_str()),
+                    device_id,
+                    device_name: row.get(1)?,
+                    activities: row.get(2)?,
+                    first_seen: row.get(3)?,
+                    last_seen: row.get(4)?,
+                })
+            })?
+            .collect();
+        devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::ImportedActivity;
+    use std::path::PathBuf;
+
+    fn device(id: &str, name: &str) -> Device {
+        Device {
+            id: id.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn activities_are_kept_apart_by_device() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let start = 1_700_000_000;
+        let legacy = db.upsert_activity("Code", Some("main.rs"), None, start, "tracker", None).unwrap();
+
+        assert_eq!(db.set_device(device("laptop-id", "laptop")).unwrap(), 1);
+        // A new device does not extend the row another device recorded
+        let laptop = db.upsert_activity("Code", Some("main.rs"), None, start + 5, "tracker", None).unwrap();
+        assert_eq!(laptop, legacy);
+        db.set_device(device("desktop-id", "desktop")).unwrap();
+        let desktop = db.upsert_activity("Code", Some("main.rs"), None, start + 10, "tracker", None).unwrap();
+        assert_ne!(desktop, laptop);
+
+        // Imported history only counts as a duplicate on the device it came from
+        let row = |hostname: &str| ImportedActivity {
+            app_name: "Slack".to_string(),
+            window_title: None,
+            started_at: start,
+            duration_sec: 60,
+            hostname: Some(hostname.to_string()),
+        };
+        let summary = db.import_activities(&[row("laptop"), row("desktop")], "import:activitywatch").unwrap();
+        assert_eq!((summary.imported, summary.skipped), (1, 1));
+
+        let devices = db.get_devices().unwrap();
+        let ids: Vec<(&str, bool)> = devices.iter().map(|d| (d.device_id.as_str(), d.current)).collect();
+        assert!(ids.contains(&("desktop-id", true)) && ids.contains(&("laptop-id", false)));
+        assert!(ids.contains(&("host:laptop", false)));
+        let stats = db
+            .get_stats_for_range(start, start + 60, None, Some(&["desktop-id".to_string()]), false)
+            .unwrap();
+        assert_eq!(stats.app_breakdown.len(), 1);
+    }
+}
@@ -0,0 +1,61 @@
+//! Domain productivity ratings
+//!
+//! Browsers land in a neutral category, so without ratings all web time is
+//! neither productive nor distracting. A rating (`productive`, `neutral` or
+//! `distracting`) for a domain decides the productivity of activities on that
+//! domain or its subdomains, but only when their category is neutral: a
+//! category's own `is_productive` always wins.
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::DomainRating;
+
+pub const DOMAIN_RATING_PRODUCTIVE: &str = "productive";
+pub const DOMAIN_RATING_NEUTRAL: &str = "neutral";
+pub const DOMAIN_RATING_DISTRACTING: &str = "distracting";
+
+/// Productivity of an activity row `a` joined to its category `c`:
+/// 1 productive, 0 distracting, NULL neutral. The most specific rated domain
+/// applies, so `docs.example.com` can override `example.com`.
+pub(crate) const EFFECTIVE_PRODUCTIVE_SQL: &str = "COALESCE(c.is_productive, (
+    SELECT CASE dr.rating WHEN 'productive' THEN 1 WHEN 'distracting' THEN 0 END
+    FROM domain_ratings dr
+    WHERE a.domain = dr.domain OR a.domain LIKE '%.' || dr.domain
+    ORDER BY length(dr.domain) DESC
+    LIMIT 1))";
+
+impl Database {
+    /// Get all domain ratings
+    pub fn get_domain_ratings(&self) -> Result<Vec<DomainRating>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT domain, rating, updated_at FROM domain_ratings ORDER BY domain")?;
+        let ratings = stmt
+            .query_map([], |row| {
+                Ok(DomainRating {
+                    domain: row.get(0)?,
+                    rating: row.get(1)?,
+                    updated_at: row.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ratings)
+    }
+
+    /// Rate a domain, replacing its previous rating
+    pub fn set_domain_rating(&self, domain: &str, rating: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO domain_ratings (domain, rating, updated_at) VALUES (?, ?, ?)
+             ON CONFLICT(domain) DO UPDATE SET rating = excluded.rating, updated_at = excluded.updated_at",
+            params![domain, rating, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a domain's rating
+    pub fn delete_domain_rating(&self, domain: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM domain_ratings WHERE domain = ?", params![domain])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+//! Editor heartbeat database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::{CodingStat, CodingStats, EditorHeartbeat};
+use std::collections::HashMap;
+
+/// Gap between heartbeats after which the time in between is not counted
+const HEARTBEAT_TIMEOUT_SECS: i64 = 15 * 60;
+
+/// Files listed in coding stats
+const MAX_FILES: usize = 100;
+
+/// Sort accumulated durations, longest first
+fn sorted_stats(durations: HashMap<String, i64>, limit: Option<usize>) -> Vec<CodingStat> {
+    let mut stats: Vec<CodingStat> = durations
+        .into_iter()
+        .filter(|(_, duration_sec)| *duration_sec > 0)
+        .map(|(name, duration_sec)| CodingStat { name, duration_sec })
+        .collect();
+    stats.sort_by(|a, b| b.duration_sec.cmp(&a.duration_sec).then_with(|| a.name.cmp(&b.name)));
+    if let Some(limit) = limit {
+        stats.truncate(limit);
+    }
+    stats
+}
+
+impl Database {
+    /// Store editor heartbeats; returns the number of rows inserted
+    pub fn insert_editor_heartbeats(&self, heartbeats: &[EditorHeartbeat]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO editor_heartbeats (time, entity, entity_type, project, language, branch, is_write, editor)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )?;
+            for hb in heartbeats {
+                stmt.execute(params![
+                    hb.time,
+                    hb.entity,
+                    hb.entity_type,
+                    hb.project,
+                    hb.language,
+                    hb.branch,
+                    hb.is_write,
+                    hb.editor,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(heartbeats.len())
+    }
+
+    /// Get coding time by language, project and file for a time range.
+    /// Each heartbeat is credited with the time until the next one, unless the
+    /// gap exceeds 15 minutes (the editor was left alone).
+    pub fn get_coding_stats(&self, start: i64, end: i64) -> Result<CodingStats> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT entity, project, language,
+                    LEAD(time) OVER (ORDER BY time, id) - time AS gap
+             FROM editor_heartbeats
+             WHERE time >= ?1 AND time <= ?2 AND entity_type = 'file'",
+        )?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<i64>>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut total_seconds = 0;
+        let mut languages: HashMap<String, i64> = HashMap::new();
+        let mut projects: HashMap<String, i64> = HashMap::new();
+        let mut files: HashMap<String, i64> = HashMap::new();
+        for (entity, project, language, gap) in rows {
+            let duration = gap.filter(|g| *g <= HEARTBEAT_TIMEOUT_SECS).unwrap_or(0);
+            total_seconds += duration;
+            *languages.entry(language.unwrap_or_else(|| "Other".to_string())).or_default() += duration;
+            *projects.entry(project.unwrap_or_else(|| "Unknown".to_string())).or_default() += duration;
+            *files.entry(entity).or_default() += duration;
+        }
+
+        Ok(CodingStats {
+            total_seconds,
+            languages: sorted_stats(languages, None),
+            projects: sorted_stats(projects, None),
+            files: sorted_stats(files, Some(MAX_FILES)),
+        })
+    }
+}
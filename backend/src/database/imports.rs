@@ -0,0 +1,50 @@
+//! Activity import database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::{ImportSummary, ImportedActivity};
+
+impl Database {
+    /// Store imported activities under the given source (e.g. `import:screen_time`).
+    /// Rows overlapping any activity recorded on the same device are skipped, so
+    /// imports only backfill time that device's tracker did not see and
+    /// re-importing is harmless. History from another machine (by host name)
+    /// is attributed to `host:<name>`.
+    pub fn import_activities(&self, rows: &[ImportedActivity], source: &str) -> Result<ImportSummary> {
+        let local = self.device();
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        let mut summary = ImportSummary::default();
+
+        for row in rows.iter().filter(|r| r.duration_sec > 0) {
+            let ended_at = row.started_at + row.duration_sec;
+            let (device_id, device_name) = match (row.hostname.as_deref(), &local) {
+                (Some(hostname), Some(local)) if hostname.eq_ignore_ascii_case(&local.name) => {
+                    (Some(local.id.clone()), Some(local.name.clone()))
+                }
+                (Some(hostname), _) => (Some(format!("host:{}", hostname)), Some(hostname.to_string())),
+                (None, local) => (local.as_ref().map(|d| d.id.clone()), local.as_ref().map(|d| d.name.clone())),
+            };
+            let overlaps: bool = tx.query_row(
+                "SELECT EXISTS(SELECT 1 FROM activities WHERE started_at < ? AND started_at + duration_sec > ? AND device_id IS ?)",
+                params![ended_at, row.started_at, device_id],
+                |r| r.get(0),
+            )?;
+            if overlaps {
+                summary.skipped += 1;
+                continue;
+            }
+
+            let category_id = self.find_category_for_activity(&tx, &row.app_name, row.window_title.as_deref(), None);
+            tx.execute(
+                "INSERT INTO activities (app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, device_id, device_name)
+                 VALUES (?, ?, NULL, ?, ?, ?, FALSE, ?, ?, ?)",
+                params![row.app_name, row.window_title, category_id, row.started_at, row.duration_sec, source, device_id, device_name],
+            )?;
+            summary.imported += 1;
+        }
+
+        tx.commit()?;
+        Ok(summary)
+    }
+}
@@ -0,0 +1,123 @@
+//! Maintenance job database operations (see `jobs`)
+
+use rusqlite::{params, OptionalExtension, Result};
+use super::common::Database;
+use super::models::Job;
+
+pub const JOB_QUEUED: &str = "queued";
+pub const JOB_RUNNING: &str = "running";
+pub const JOB_COMPLETED: &str = "completed";
+pub const JOB_FAILED: &str = "failed";
+pub const JOB_CANCELLED: &str = "cancelled";
+
+const JOB_COLUMNS: &str =
+    "id, kind, params, status, cursor, processed, total, error, cancel_requested, created_at, updated_at, finished_at";
+
+fn map_job_row(row: &rusqlite::Row) -> Result<Job> {
+    let params: String = row.get(2)?;
+    let cursor: Option<String> = row.get(4)?;
+    Ok(Job {
+        id: row.get(0)?,
+        kind: row.get(1)?,
+        params: serde_json::from_str(&params).unwrap_or_default(),
+        status: row.get(3)?,
+        cursor: cursor.and_then(|c| serde_json::from_str(&c).ok()),
+        processed: row.get(5)?,
+        total: row.get(6)?,
+        error: row.get(7)?,
+        cancel_requested: row.get(8)?,
+        created_at: row.get(9)?,
+        updated_at: row.get(10)?,
+        finished_at: row.get(11)?,
+    })
+}
+
+impl Database {
+    /// Queue a job; returns its ID
+    pub fn insert_job(&self, kind: &str, params: &serde_json::Value) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO jobs (kind, params, created_at, updated_at) VALUES (?, ?, ?, ?)",
+            params![kind, params.to_string(), now, now],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get a job by ID
+    pub fn get_job(&self, id: i64) -> Result<Option<Job>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM jobs WHERE id = ?", JOB_COLUMNS),
+            params![id],
+            map_job_row,
+        )
+        .optional()
+    }
+
+    /// Get the most recent jobs, newest first
+    pub fn get_jobs(&self, limit: i64) -> Result<Vec<Job>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!("SELECT {} FROM jobs ORDER BY id DESC LIMIT ?", JOB_COLUMNS))?;
+        let jobs = stmt.query_map(params![limit], map_job_row)?.collect();
+        jobs
+    }
+
+    /// Oldest unfinished job: a queued one, or one a restart interrupted while running
+    pub fn next_job(&self) -> Result<Option<Job>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM jobs WHERE status IN (?, ?) ORDER BY id LIMIT 1", JOB_COLUMNS),
+            params![JOB_QUEUED, JOB_RUNNING],
+            map_job_row,
+        )
+        .optional()
+    }
+
+    /// Whether a job of `kind` is queued or running
+    pub fn has_active_job(&self, kind: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM jobs WHERE kind = ? AND status IN (?, ?))",
+            params![kind, JOB_QUEUED, JOB_RUNNING],
+            |row| row.get(0),
+        )
+    }
+
+    /// Save where a running job continues after a step
+    pub fn save_job_progress(&self, id: i64, cursor: &serde_json::Value, processed: i64, total: Option<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE jobs SET status = ?, cursor = ?, processed = ?, total = ?, updated_at = ? WHERE id = ?",
+            params![JOB_RUNNING, cursor.to_string(), processed, total, chrono::Utc::now().timestamp(), id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark a job `completed`, `failed` or `cancelled`
+    pub fn finish_job(&self, id: i64, status: &str, error: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "UPDATE jobs SET status = ?, error = ?, updated_at = ?, finished_at = ? WHERE id = ?",
+            params![status, error, now, now, id],
+        )?;
+        Ok(())
+    }
+
+    /// Cancel a job: a queued job right away, a running one after its current
+    /// step. Returns `false` if the job doesn't exist or already finished.
+    pub fn request_job_cancel(&self, id: i64) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let cancelled = conn.execute(
+            "UPDATE jobs SET status = ?, updated_at = ?, finished_at = ? WHERE id = ? AND status = ?",
+            params![JOB_CANCELLED, now, now, id, JOB_QUEUED],
+        )?;
+        let requested = conn.execute(
+            "UPDATE jobs SET cancel_requested = TRUE, updated_at = ? WHERE id = ? AND status = ?",
+            params![now, id, JOB_RUNNING],
+        )?;
+        Ok(cancelled + requested > 0)
+    }
+}
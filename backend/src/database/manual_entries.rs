@@ -3,30 +3,35 @@
 use rusqlite::{Result, params};
 use super::common::Database;
 use super::models::ManualEntry;
+use super::approvals::APPROVAL_ENTITY_MANUAL_ENTRY;
 
 impl Database {
-    /// Add a manual entry
+    /// Add a manual entry, attributed to `source` (e.g. `manual`, `idle-classified`)
     pub fn add_manual_entry(
         &self,
         description: Option<&str>,
         category_id: Option<i64>,
         started_at: i64,
         ended_at: i64,
+        source: &str,
     ) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
+        let changes_before: i64 = conn.query_row("SELECT total_changes()", [], |row| row.get(0))?;
         conn.execute(
-            "INSERT INTO manual_entries (entry_type, description, category_id, started_at, ended_at)
-             VALUES ('', ?, ?, ?, ?)",
-            params![description, category_id, started_at, ended_at],
+            "INSERT INTO manual_entries (entry_type, description, category_id, started_at, ended_at, source)
+             VALUES ('', ?, ?, ?, ?, ?)",
+            params![description, category_id, started_at, ended_at, source],
         )?;
-        Ok(conn.last_insert_rowid())
+        let id = conn.last_insert_rowid();
+        self.add_to_today_total(&conn, changes_before, started_at, ended_at - started_at)?;
+        Ok(id)
     }
 
     /// Get manual entries for a time range
     pub fn get_manual_entries(&self, start: i64, end: i64) -> Result<Vec<ManualEntry>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, description, category_id, started_at, ended_at
+            "SELECT id, description, category_id, started_at, ended_at, source, billable_override
              FROM manual_entries
              WHERE started_at >= ? AND started_at <= ?
              ORDER BY started_at ASC",
@@ -40,6 +45,8 @@ impl Database {
                     category_id: row.get(2)?,
                     started_at: row.get(3)?,
                     ended_at: row.get(4)?,
+                    source: row.get(5)?,
+                    billable_override: row.get(6)?,
                 })
             })?
             .collect::<Result<Vec<_>>>()?;
@@ -57,6 +64,7 @@ impl Database {
         ended_at: i64,
     ) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        Self::ensure_not_approved(&conn, APPROVAL_ENTITY_MANUAL_ENTRY, id)?;
         conn.execute(
             "UPDATE manual_entries SET entry_type = '', description = ?, category_id = ?, 
              started_at = ?, ended_at = ? WHERE id = ?",
@@ -64,12 +72,24 @@ impl Database {
         )?;
         Ok(())
     }
-    
 
-    /// Delete manual entry
+    /// Set or clear (`None`) the billable override of several manual entries.
+    /// Approved entries are left unchanged; returns the number updated.
+    pub fn set_manual_entries_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        Self::set_billable_override(&conn, "manual_entries", APPROVAL_ENTITY_MANUAL_ENTRY, ids, billable_override)
+    }
+
+    /// Delete manual entry; approved entries cannot be deleted
     pub fn delete_manual_entry(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
+        Self::ensure_not_approved(&conn, APPROVAL_ENTITY_MANUAL_ENTRY, id)?;
         conn.execute("DELETE FROM manual_entries WHERE id = ?", params![id])?;
+        conn.execute(
+            "DELETE FROM entry_approvals WHERE entity_type = ? AND entity_id = ?",
+            params![APPROVAL_ENTITY_MANUAL_ENTRY, id],
+        )?;
+        Self::delete_custom_field_values_for(&conn, "manual_entry", id)?;
         Ok(())
     }
 }
@@ -0,0 +1,51 @@
+//! Micro-break database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::MicroBreakStats;
+
+pub const MICRO_BREAK_COMPLETED: &str = "completed";
+pub const MICRO_BREAK_SKIPPED: &str = "skipped";
+
+impl Database {
+    /// Log a micro-break offered at `started_at`; it counts as missed until finished
+    pub fn insert_micro_break(&self, started_at: i64, duration_sec: i64) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO micro_breaks (started_at, duration_sec) VALUES (?, ?)",
+            params![started_at, duration_sec],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Record how an offered micro-break ended (`completed` or `skipped`);
+    /// a break already finished is left alone
+    pub fn finish_micro_break(&self, id: i64, status: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE micro_breaks SET status = ? WHERE id = ? AND status = 'missed'",
+            params![status, id],
+        )?;
+        Ok(())
+    }
+
+    /// Count micro-breaks offered in a time range by outcome
+    pub fn get_micro_break_stats(&self, start: i64, end: i64) -> Result<MicroBreakStats> {
+        let conn = self.conn.lock().unwrap();
+        let (offered, completed, skipped): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(status = 'completed'), 0),
+                    COALESCE(SUM(status = 'skipped'), 0)
+             FROM micro_breaks WHERE started_at >= ? AND started_at <= ?",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        Ok(MicroBreakStats {
+            offered,
+            completed,
+            skipped,
+            missed: offered - completed - skipped,
+            compliance_percent: (offered > 0).then(|| (completed as f64 / offered as f64 * 100.0).round() as i64),
+        })
+    }
+}
@@ -6,10 +6,35 @@
 //! - activities: Activity-related database operations
 //! - categories: Category management operations
 //! - rules: Rule management operations
+//! - rule_runs: What the last rule reapplication changed, and its undo
+//! - task_hints: Window title keyword to task mappings
+//! - suggestions: Category suggestion training data
 //! - manual_entries: Manual entry operations
+//! - approvals: Entry approval workflow operations
+//! - imports: Imported activity history operations
+//! - heartbeats: Editor heartbeat operations
+//! - custom_fields: User-defined custom field operations
 //! - settings: Settings operations
 //! - stats: Statistics and reporting operations
 //! - plugins: Plugin management operations
+//! - webhooks: Webhook endpoints and delivery queue
+//! - blocklist: Focus-mode blocklist operations
+//! - domain_ratings: Domain productivity ratings
+//! - checkins: Productivity check-in operations
+//! - micro_breaks: Eye-strain micro-break log
+//! - boundaries: Work outside work hours (quiet hours, weekends)
+//! - daily_plans: Daily plans and their end-of-day review
+//! - mood: Energy/mood journal operations
+//! - profiles: Named settings profiles
+//! - recovery: Startup integrity check and corruption salvage
+//! - jobs: Maintenance job queue and progress
+//! - slow_queries: Slow statement log with query plans
+//! - app_metrics: Local app usage metrics
+//! - anonymize: Anonymized database copies for sharing
+//! - demo_data: Generated demo data
+//! - devices: Devices activities were recorded on
+//! - sync_ids: Globally unique IDs for synced records
+//! - storage: Backend-neutral storage trait (SQLite is the default backend)
 //!
 
 pub mod models;
@@ -17,11 +42,36 @@ pub mod common;
 pub mod activities;
 pub mod categories;
 pub mod rules;
+pub mod rule_runs;
+pub mod task_hints;
+pub mod suggestions;
 pub mod manual_entries;
+pub mod approvals;
+pub mod imports;
+pub mod heartbeats;
+pub mod custom_fields;
 pub mod settings;
 pub mod stats;
 pub mod plugins;
 pub mod plugin_tables;
+pub mod webhooks;
+pub mod blocklist;
+pub mod domain_ratings;
+pub mod checkins;
+pub mod micro_breaks;
+pub mod boundaries;
+pub mod daily_plans;
+pub mod mood;
+pub mod profiles;
+pub mod recovery;
+pub mod jobs;
+pub mod slow_queries;
+pub mod app_metrics;
+pub mod anonymize;
+pub mod demo_data;
+pub mod devices;
+pub mod sync_ids;
+pub mod storage;
 
 // Re-export Database and constants
 pub use common::Database;
@@ -11,6 +11,55 @@ pub struct Activity {
     pub started_at: i64,
     pub duration_sec: i64,
     pub is_idle: bool,
+    /// Where the record came from: `tracker`, `idle-classified`, `import:<format>`, ...
+    pub source: String,
+    /// Overrides whether this activity is billable; `None` follows its project/category
+    pub billable_override: Option<bool>,
+    /// Document, file or URL the activity is about (see `context`)
+    #[serde(default)]
+    pub context_ref: Option<String>,
+    /// Install the activity was recorded on (see `device`); imported history from
+    /// a machine known only by name uses `host:<name>`
+    #[serde(default)]
+    pub device_id: Option<String>,
+    #[serde(default)]
+    pub device_name: Option<String>,
+}
+
+/// Classified part of an idle period
+#[derive(Debug, Clone)]
+pub struct IdleSegment {
+    pub started_at: i64,
+    pub duration_sec: i64,
+    pub category_id: i64,
+    pub description: Option<String>,
+    /// Stored in the `project_id`/`task_id` activity columns when the
+    /// projects plugin provides them
+    pub project_id: Option<i64>,
+    pub task_id: Option<i64>,
+}
+
+/// Keyset pagination cursor for activities ordered by (started_at, id)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ActivityCursor {
+    pub after_started_at: i64,
+    pub after_id: i64,
+}
+
+impl ActivityCursor {
+    /// Encode as an opaque `next_cursor` token
+    pub fn encode(&self) -> String {
+        format!("{}:{}", self.after_started_at, self.after_id)
+    }
+
+    /// Decode a token produced by `encode`
+    pub fn decode(token: &str) -> Option<Self> {
+        let (started_at, id) = token.split_once(':')?;
+        Some(Self {
+            after_started_at: started_at.parse().ok()?,
+            after_id: id.parse().ok()?,
+        })
+    }
 }
 
 /// Category record
@@ -24,6 +73,12 @@ pub struct Category {
     pub sort_order: i64,
     pub is_system: bool,
     pub is_pinned: bool,
+    /// Keep this category's activities out of exports, reports and webhooks
+    #[serde(default)]
+    pub exclude_from_export: bool,
+    /// Hidden from pickers and new rules; kept in history and stats
+    #[serde(default)]
+    pub is_archived: bool,
 }
 
 /// Rule for auto-categorization
@@ -34,6 +89,20 @@ pub struct Rule {
     pub pattern: String,
     pub category_id: i64,
     pub priority: i64,
+    /// Project matching activities are assigned to (projects plugin)
+    #[serde(default)]
+    pub project_id: Option<i64>,
+}
+
+/// Keyword (e.g. a ticket number) attaching activities to a task
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TaskHint {
+    pub id: i64,
+    /// Matched as a whole word in window titles, ignoring case
+    pub keyword: String,
+    /// Task of the projects plugin
+    pub task_id: i64,
+    pub created_at: i64,
 }
 
 /// Manual entry record
@@ -44,6 +113,358 @@ pub struct ManualEntry {
     pub category_id: Option<i64>,
     pub started_at: i64,
     pub ended_at: i64,
+    /// Where the entry came from: `manual`, `idle-classified`, `calendar`, `plugin:<id>`, ...
+    pub source: String,
+    /// Overrides whether this entry is billable; `None` follows its project/category
+    pub billable_override: Option<bool>,
+}
+
+/// Approval state of a manual entry or activity
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EntryApproval {
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub status: String,
+    pub note: Option<String>,
+    pub submitted_at: Option<i64>,
+    pub reviewed_at: Option<i64>,
+}
+
+/// Number of entries affected by an approval transition
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApprovalSummary {
+    pub manual_entries: i64,
+    pub activities: i64,
+}
+
+/// User-defined custom field definition
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CustomFieldDef {
+    pub id: i64,
+    /// Entity the field belongs to: activity, manual_entry, project, task
+    pub entity_type: String,
+    pub name: String,
+    /// Field type: text, number, select
+    pub field_type: String,
+    /// Allowed values for select fields
+    pub options: Vec<String>,
+    pub sort_order: i64,
+}
+
+/// Custom field value attached to an entity
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CustomFieldValue {
+    pub field_id: i64,
+    pub entity_id: i64,
+    pub value: String,
+}
+
+/// Outbound webhook endpoint
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Webhook {
+    pub id: i64,
+    pub url: String,
+    /// HMAC-SHA256 signing secret; deliveries are unsigned when absent
+    pub secret: Option<String>,
+    /// Event filters (exact names, `prefix.*` or `*`); empty means all events
+    pub events: Vec<String>,
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+/// Queued or completed webhook delivery
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub webhook_id: i64,
+    pub event: String,
+    pub payload: String,
+    /// pending, delivered or failed
+    pub status: String,
+    pub attempts: i64,
+    pub next_attempt_at: i64,
+    pub last_error: Option<String>,
+    pub created_at: i64,
+    pub delivered_at: Option<i64>,
+}
+
+/// App or domain blocked during pomodoro work sessions
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct BlocklistEntry {
+    pub id: i64,
+    /// `app` or `domain`
+    pub kind: String,
+    /// App name (case-insensitive substring) or domain (also blocks `www.`)
+    pub pattern: String,
+    pub created_at: i64,
+}
+
+/// Named configuration profile (e.g. "work", "personal")
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SettingsProfile {
+    pub name: String,
+    /// Settings applied when switching to the profile
+    #[serde(default)]
+    pub settings: std::collections::HashMap<String, String>,
+    /// Rules used for categorization; `None` uses all rules
+    pub rule_ids: Option<Vec<i64>>,
+    /// Categories hidden from pickers and lists while the profile is active
+    #[serde(default)]
+    pub hidden_category_ids: Vec<i64>,
+    /// Conditions for switching to the profile automatically
+    pub auto_switch: Option<ProfileAutoSwitch>,
+    #[serde(default)]
+    pub created_at: i64,
+}
+
+/// Automatic profile switching conditions; all given conditions must hold
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ProfileAutoSwitch {
+    /// Weekdays, 0 = Monday; empty means every day
+    #[serde(default)]
+    pub days: Vec<u32>,
+    /// Local time window as `HH:MM` (may wrap past midnight)
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    /// Wi-Fi network names; empty means any network
+    #[serde(default)]
+    pub networks: Vec<String>,
+}
+
+/// Energy/mood journal entry
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MoodEntry {
+    pub id: i64,
+    /// Time the mood refers to
+    pub timestamp: i64,
+    /// 1 (low) to 5 (great)
+    pub score: i64,
+    pub note: Option<String>,
+    pub created_at: i64,
+}
+
+/// Mood entries summarized for a time range
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MoodSummary {
+    pub average_score: Option<f64>,
+    pub entries: Vec<MoodEntry>,
+}
+
+/// Answer to a random productivity check-in
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Checkin {
+    pub id: i64,
+    pub created_at: i64,
+    pub focused: bool,
+    /// Self-reported energy, 1-5
+    pub energy: Option<i64>,
+    /// Activity that was current when answering
+    pub activity_id: Option<i64>,
+    pub app_name: Option<String>,
+    pub category_id: Option<i64>,
+}
+
+/// Check-in answers aggregated over an hour of day or a category
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CheckinBucket {
+    /// Local hour of day (0-23) for hourly buckets
+    pub hour: Option<i64>,
+    /// Category for category buckets (`None` when no activity was current)
+    pub category_id: Option<i64>,
+    pub count: i64,
+    pub focused_count: i64,
+    pub average_energy: Option<f64>,
+}
+
+/// Check-in statistics for a time range
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CheckinStats {
+    pub total: i64,
+    pub focused_count: i64,
+    pub average_energy: Option<f64>,
+    pub by_hour: Vec<CheckinBucket>,
+    pub by_category: Vec<CheckinBucket>,
+}
+
+/// Micro-breaks offered in a time range and how they went
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct MicroBreakStats {
+    pub offered: i64,
+    pub completed: i64,
+    pub skipped: i64,
+    /// Closed without an answer (or still open)
+    pub missed: i64,
+    /// Share of offered breaks completed; `None` when none were offered
+    pub compliance_percent: Option<i64>,
+}
+
+/// Maintenance job (see `jobs`)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Job {
+    pub id: i64,
+    /// Handler name, e.g. `domain_backfill`
+    pub kind: String,
+    pub params: serde_json::Value,
+    /// queued, running, completed, failed or cancelled
+    pub status: String,
+    /// Where the next step continues, saved after every step
+    pub cursor: Option<serde_json::Value>,
+    pub processed: i64,
+    /// Items to process, when the job knows
+    pub total: Option<i64>,
+    pub error: Option<String>,
+    pub cancel_requested: bool,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub finished_at: Option<i64>,
+}
+
+/// Activity whose category the last rule run changed
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RuleRunChange {
+    pub activity_id: i64,
+    pub old_category_id: Option<i64>,
+    pub new_category_id: Option<i64>,
+    /// `None` (and 0 seconds) once the activity was deleted
+    pub app_name: Option<String>,
+    pub window_title: Option<String>,
+    pub started_at: Option<i64>,
+    pub duration_sec: i64,
+}
+
+/// Time and activities a category gained and lost in a rule run
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct RuleRunCategoryDelta {
+    /// `None` for uncategorized activities
+    pub category_id: Option<i64>,
+    pub activities_gained: i64,
+    pub activities_lost: i64,
+    pub seconds_gained: i64,
+    pub seconds_lost: i64,
+    pub net_seconds: i64,
+}
+
+/// What the last `reapply_categorization_rules` run changed
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RuleRunReport {
+    pub id: i64,
+    pub ran_at: i64,
+    pub activities_checked: i64,
+    /// When `undo_last_rule_run` restored the old categories
+    pub undone_at: Option<i64>,
+    /// Largest net change first
+    pub categories: Vec<RuleRunCategoryDelta>,
+    pub changes: Vec<RuleRunChange>,
+}
+
+/// Something the user intends to work on during a day
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DailyPlanItem {
+    pub title: String,
+    /// Time in this category counts toward the item
+    #[serde(default)]
+    pub category_id: Option<i64>,
+    /// Projects plugin task; when set, only time on the task counts
+    #[serde(default)]
+    pub task_id: Option<i64>,
+    pub target_minutes: i64,
+}
+
+/// Plan for one local day, items in the order the user put them
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyPlan {
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    pub items: Vec<DailyPlanItem>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// Plan item compared with the time tracked for it
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyPlanItemReview {
+    #[serde(flatten)]
+    pub item: DailyPlanItem,
+    pub target_sec: i64,
+    /// `None` when the item has no category or task to measure by
+    pub actual_sec: Option<i64>,
+    /// `actual_sec - target_sec`
+    pub difference_sec: Option<i64>,
+}
+
+/// End-of-day comparison of a plan with the tracked time
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DailyPlanReview {
+    pub date: String,
+    pub items: Vec<DailyPlanItemReview>,
+    pub planned_sec: i64,
+    /// All non-idle time tracked or entered that day, planned or not
+    pub tracked_sec: i64,
+}
+
+/// Day on which work outside work hours went over `boundary_threshold_minutes`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BoundaryViolation {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    /// `quiet_hours` or `weekend`
+    pub kind: String,
+    /// Time worked outside work hours that day (as of the last check)
+    pub seconds: i64,
+    pub logged_at: i64,
+}
+
+/// Editor heartbeat received from a WakaTime-compatible IDE plugin
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct EditorHeartbeat {
+    pub id: i64,
+    pub time: i64,
+    /// File path (or app/domain name for non-file heartbeats)
+    pub entity: String,
+    pub entity_type: String,
+    pub project: Option<String>,
+    pub language: Option<String>,
+    pub branch: Option<String>,
+    pub is_write: bool,
+    /// Editor name from the plugin user agent (e.g. `vscode`)
+    pub editor: String,
+}
+
+/// Coding time for one language, file or project
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CodingStat {
+    pub name: String,
+    pub duration_sec: i64,
+}
+
+/// Coding time breakdown computed from editor heartbeats
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct CodingStats {
+    pub total_seconds: i64,
+    pub languages: Vec<CodingStat>,
+    pub projects: Vec<CodingStat>,
+    pub files: Vec<CodingStat>,
+}
+
+/// Activity read from an external usage history, before it is stored
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ImportedActivity {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub started_at: i64,
+    pub duration_sec: i64,
+    /// Machine the history was recorded on, if it says; `None` means this one
+    #[serde(default)]
+    pub hostname: Option<String>,
+}
+
+/// Result of an activity import
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ImportSummary {
+    pub imported: i64,
+    /// Rows skipped because they overlap already recorded activities
+    pub skipped: i64,
 }
 
 /// Domain statistics
@@ -53,6 +474,34 @@ pub struct DomainStat {
     pub duration_sec: i64,
 }
 
+/// Uncategorized time of one app or domain (see `get_uncategorized_summary`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UncategorizedGroup {
+    /// `domain` for activities with a domain, else `app`
+    pub kind: String,
+    /// Domain or app name
+    pub name: String,
+    pub activity_count: i64,
+    pub duration_sec: i64,
+}
+
+/// Productivity rating of a domain, used when its activities' category is neutral
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DomainRating {
+    /// Domain without `www.`; also rates its subdomains
+    pub domain: String,
+    /// `productive`, `neutral` or `distracting`
+    pub rating: String,
+    pub updated_at: i64,
+}
+
+/// Time spent on one document, file or URL
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ContextStat {
+    pub context_ref: String,
+    pub duration_sec: i64,
+}
+
 /// Daily statistics
 #[derive(Debug, Clone)]
 pub struct DailyStats {
@@ -78,6 +527,17 @@ pub struct AppStat {
     pub category: Option<Category>,
 }
 
+/// Keyboard/mouse activity of an app (see `Database::add_input_sample`)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InputIntensityStat {
+    pub app_name: String,
+    /// Time recorded while input intensity was counted
+    pub duration_sec: i64,
+    /// Part of it with keyboard or mouse input; the rest was passive
+    /// (reading, watching a video)
+    pub active_seconds: i64,
+}
+
 /// Category usage statistics
 #[derive(Debug, Clone)]
 pub struct CategoryUsageStat {
@@ -103,3 +563,19 @@ pub struct RangeStats {
     /// (app_name, seconds)
     pub app_breakdown: Vec<(String, i64)>,
 }
+
+/// Count of a local app usage metric, per day or in total
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppMetricCount {
+    /// Start of the UTC day; `None` for totals over a range
+    pub day: Option<i64>,
+    pub metric: String,
+    pub count: i64,
+}
+
+/// Local app usage metrics for a time range
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AppUsage {
+    pub totals: Vec<AppMetricCount>,
+    pub by_day: Vec<AppMetricCount>,
+}
@@ -0,0 +1,56 @@
+//! Energy/mood journal database operations
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::{MoodEntry, MoodSummary};
+
+impl Database {
+    /// Log a mood score for a point in time
+    pub fn log_mood(&self, timestamp: i64, score: i64, note: Option<&str>) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO mood_entries (timestamp, score, note, created_at) VALUES (?, ?, ?, ?)",
+            params![timestamp, score, note, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get mood entries in a time range
+    pub fn get_mood_entries(&self, start: i64, end: i64) -> Result<Vec<MoodEntry>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, score, note, created_at FROM mood_entries
+             WHERE timestamp >= ? AND timestamp <= ? ORDER BY timestamp",
+        )?;
+        let entries = stmt
+            .query_map(params![start, end], |row| {
+                Ok(MoodEntry {
+                    id: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    score: row.get(2)?,
+                    note: row.get(3)?,
+                    created_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(entries)
+    }
+
+    /// Get mood entries and their average score for a time range
+    pub fn get_mood_summary(&self, start: i64, end: i64) -> Result<MoodSummary> {
+        let entries = self.get_mood_entries(start, end)?;
+        let average_score = if entries.is_empty() {
+            None
+        } else {
+            Some(entries.iter().map(|e| e.score as f64).sum::<f64>() / entries.len() as f64)
+        };
+        Ok(MoodSummary { average_score, entries })
+    }
+
+    /// Delete a mood entry
+    pub fn delete_mood_entry(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM mood_entries WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
@@ -53,8 +53,57 @@ fn sqlite_value_to_json(v: SqliteValue) -> serde_json::Value {
 /// Core category column names (used to distinguish extended columns)
 const CORE_CATEGORY_COLUMNS: &[&str] = &[
     "id", "name", "color", "icon", "is_productive", "sort_order", "is_system", "is_pinned",
+    "exclude_from_export", "is_archived",
 ];
 
+/// Core activity column names (used to distinguish extended columns)
+const CORE_ACTIVITY_COLUMNS: &[&str] = &[
+    "id", "app_name", "window_title", "domain", "category_id", "started_at", "duration_sec",
+    "is_idle", "source", "billable_override", "context_ref", "device_id", "device_name",
+];
+
+/// Write `data` to the plugin-extended columns of row `id`; other keys are ignored
+fn update_extended_columns(
+    conn: &rusqlite::Connection,
+    table: &str,
+    core_columns: &[&str],
+    id: i64,
+    data: &serde_json::Map<String, serde_json::Value>,
+) -> Result<usize, String> {
+    let all_columns = get_table_columns(conn, table)?;
+    let extended: Vec<&String> = all_columns
+        .iter()
+        .filter(|c| !core_columns.contains(&c.as_str()))
+        .collect();
+    let data_filtered: serde_json::Map<String, serde_json::Value> = data
+        .iter()
+        .filter(|(k, _)| is_valid_identifier(k) && extended.contains(k))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    if data_filtered.is_empty() {
+        return Ok(0);
+    }
+    let set_parts: Vec<String> = data_filtered
+        .keys()
+        .map(|k| format!("\"{}\" = ?", k.replace('"', "\"\"")))
+        .collect();
+    let sql = format!(
+        "UPDATE {} SET {} WHERE id = ?",
+        table,
+        set_parts.join(", ")
+    );
+    let mut param_values: Vec<SqliteValue> = data_filtered
+        .keys()
+        .filter_map(|k| data_filtered.get(k))
+        .map(json_to_sqlite_value)
+        .collect();
+    param_values.push(SqliteValue::Integer(id));
+    let n = conn
+        .execute(&sql, rusqlite::params_from_iter(param_values.iter()))
+        .map_err(|e| e.to_string())?;
+    Ok(n)
+}
+
 impl Database {
     /// Get all categories as JSON objects including plugin-extended columns.
     /// Used by the plugin API so plugins can read/write extended fields.
@@ -98,37 +147,17 @@ impl Database {
         data: &serde_json::Map<String, serde_json::Value>,
     ) -> Result<usize, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
-        let all_columns = get_table_columns(&conn, "categories")?;
-        let extended: Vec<&String> = all_columns
-            .iter()
-            .filter(|c| !CORE_CATEGORY_COLUMNS.contains(&c.as_str()))
-            .collect();
-        let data_filtered: serde_json::Map<String, serde_json::Value> = data
-            .iter()
-            .filter(|(k, _)| is_valid_identifier(k) && extended.iter().any(|c| *c == *k))
-            .map(|(k, v)| (k.clone(), v.clone()))
-            .collect();
-        if data_filtered.is_empty() {
-            return Ok(0);
-        }
-        let set_parts: Vec<String> = data_filtered
-            .keys()
-            .map(|k| format!("\"{}\" = ?", k.replace('"', "\"\"")))
-            .collect();
-        let sql = format!(
-            "UPDATE categories SET {} WHERE id = ?",
-            set_parts.join(", ")
-        );
-        let mut param_values: Vec<SqliteValue> = data_filtered
-            .keys()
-            .filter_map(|k| data_filtered.get(k))
-            .map(json_to_sqlite_value)
-            .collect();
-        param_values.push(SqliteValue::Integer(id));
-        let n = conn
-            .execute(&sql, rusqlite::params_from_iter(param_values.iter()))
-            .map_err(|e| e.to_string())?;
-        Ok(n)
+        update_extended_columns(&conn, "categories", CORE_CATEGORY_COLUMNS, id, data)
+    }
+
+    /// Update only plugin-extended columns on an activity row. Core columns are not updated.
+    pub fn update_activity_extended(
+        &self,
+        id: i64,
+        data: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<usize, String> {
+        let conn = self.conn.lock().map_err(|e| e.to_string())?;
+        update_extended_columns(&conn, "activities", CORE_ACTIVITY_COLUMNS, id, data)
     }
 
     /// Insert a row into a plugin table. Returns the new row id.
@@ -185,6 +214,12 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Whether a plugin has created `table`
+    pub fn plugin_table_exists(&self, table: &str) -> bool {
+        let conn = self.conn.lock().unwrap();
+        get_table_columns(&conn, table).is_ok_and(|columns| !columns.is_empty())
+    }
+
     /// Select rows from a plugin table with optional filters, order_by, and limit.
     pub fn plugin_select_table(
         &self,
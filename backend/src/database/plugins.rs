@@ -3,6 +3,10 @@
 use super::common::Database;
 use rusqlite::{Result, params};
 
+/// Installed plugin record: id, name, version, description, repository_url,
+/// manifest_path, frontend_entry, frontend_components, author, enabled
+pub type InstalledPluginRow = (String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, bool);
+
 impl Database {
     /// Check if a plugin is installed
     pub fn is_plugin_installed(&self, plugin_id: &str) -> Result<bool, String> {
@@ -88,7 +92,7 @@ impl Database {
     }
 
     /// Get all installed plugins
-    pub fn get_installed_plugins(&self) -> Result<Vec<(String, String, String, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, Option<String>, bool)>, String> {
+    pub fn get_installed_plugins(&self) -> Result<Vec<InstalledPluginRow>, String> {
         let conn = self.conn.lock().map_err(|e| e.to_string())?;
         let mut stmt = conn
             .prepare("SELECT id, name, version, description, repository_url, manifest_path, frontend_entry, frontend_components, author, enabled FROM installed_plugins")
@@ -283,6 +287,9 @@ impl Database {
             }
         }
         
+        // Plugin tables that are synced (projects, tasks, goals) need their UUIDs
+        Self::ensure_sync_ids(&tx).map_err(|e| format!("Failed to assign sync IDs: {}", e))?;
+
         tx.commit().map_err(|e| format!("Failed to commit transaction: {}", e))?;
         Ok(())
     }
@@ -0,0 +1,76 @@
+//! Settings profile database operations
+
+use rusqlite::{OptionalExtension, Result, params};
+use super::common::Database;
+use super::models::SettingsProfile;
+
+fn map_profile_row(row: &rusqlite::Row) -> Result<SettingsProfile> {
+    let settings: String = row.get(1)?;
+    let rule_ids: Option<String> = row.get(2)?;
+    let hidden_category_ids: String = row.get(3)?;
+    let auto_switch: Option<String> = row.get(4)?;
+    Ok(SettingsProfile {
+        name: row.get(0)?,
+        settings: serde_json::from_str(&settings).unwrap_or_default(),
+        rule_ids: rule_ids.and_then(|v| serde_json::from_str(&v).ok()),
+        hidden_category_ids: serde_json::from_str(&hidden_category_ids).unwrap_or_default(),
+        auto_switch: auto_switch.and_then(|v| serde_json::from_str(&v).ok()),
+        created_at: row.get(5)?,
+    })
+}
+
+const PROFILE_COLUMNS: &str = "name, settings, rule_ids, hidden_category_ids, auto_switch, created_at";
+
+impl Database {
+    /// Get all settings profiles
+    pub fn get_settings_profiles(&self) -> Result<Vec<SettingsProfile>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM settings_profiles ORDER BY name",
+            PROFILE_COLUMNS
+        ))?;
+        let profiles = stmt.query_map([], map_profile_row)?.collect::<Result<Vec<_>>>()?;
+        Ok(profiles)
+    }
+
+    /// Get a settings profile by name
+    pub fn get_settings_profile(&self, name: &str) -> Result<Option<SettingsProfile>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM settings_profiles WHERE name = ?", PROFILE_COLUMNS),
+            params![name],
+            map_profile_row,
+        )
+        .optional()
+    }
+
+    /// Create or replace a settings profile
+    pub fn save_settings_profile(&self, profile: &SettingsProfile) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO settings_profiles (name, settings, rule_ids, hidden_category_ids, auto_switch, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT(name) DO UPDATE SET
+                settings = excluded.settings,
+                rule_ids = excluded.rule_ids,
+                hidden_category_ids = excluded.hidden_category_ids,
+                auto_switch = excluded.auto_switch",
+            params![
+                profile.name,
+                serde_json::to_string(&profile.settings).unwrap_or_else(|_| "{}".to_string()),
+                profile.rule_ids.as_ref().and_then(|ids| serde_json::to_string(ids).ok()),
+                serde_json::to_string(&profile.hidden_category_ids).unwrap_or_else(|_| "[]".to_string()),
+                profile.auto_switch.as_ref().and_then(|a| serde_json::to_string(a).ok()),
+                chrono::Utc::now().timestamp(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a settings profile
+    pub fn delete_settings_profile(&self, name: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM settings_profiles WHERE name = ?", params![name])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+//! Startup integrity check and salvage of corrupted databases
+//!
+//! A database that fails `PRAGMA integrity_check` (or cannot be opened) is
+//! salvaged table by table into a fresh file: rows are copied until the first
+//! unreadable page or row, then the next table is tried. The damaged file is
+//! kept next to it as `<name>.corrupt-<timestamp>` and the outcome is reported
+//! as a `DatabaseRecovery` instead of aborting startup.
+
+use rusqlite::{Connection, OpenFlags};
+use std::path::{Path, PathBuf};
+use super::common::Database;
+
+/// Rows salvaged from one table
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SalvagedTable {
+    pub table: String,
+    pub rows: i64,
+    /// Set when the copy stopped early at an unreadable page or a row that could not be copied
+    pub error: Option<String>,
+}
+
+/// Outcome of a startup recovery
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DatabaseRecovery {
+    /// `recovered` (data salvaged) or `reset` (started with an empty database)
+    pub status: String,
+    /// Integrity problem that triggered the recovery
+    pub problem: String,
+    /// Where the damaged file was moved
+    pub quarantined_path: Option<String>,
+    pub tables: Vec<SalvagedTable>,
+    pub error: Option<String>,
+}
+
+/// Describe why the database at `path` is unusable, or `None` if it is healthy
+fn integrity_problem(path: &Path) -> Option<String> {
+    if !path.exists() {
+        return None;
+    }
+    let conn = match Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY) {
+        Ok(conn) => conn,
+        Err(e) => return Some(e.to_string()),
+    };
+    let result: rusqlite::Result<Vec<String>> = conn
+        .prepare("PRAGMA integrity_check")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect());
+    match result {
+        Ok(lines) if lines.len() == 1 && lines[0] == "ok" => None,
+        Ok(lines) => Some(lines.into_iter().take(5).collect::<Vec<_>>().join("; ")),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+fn table_columns(conn: &Connection, schema: &str, table: &str) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA {}.table_info(\"{}\")", schema, table))?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(1))?.collect();
+    columns
+}
+
+/// Rows read before they are written in one transaction. Reading a corrupt page
+/// rolls back the open transaction, so reads happen outside of it.
+const SALVAGE_BATCH_ROWS: usize = 1000;
+
+/// Write buffered rows in one transaction; rows after the first failing one are dropped
+fn insert_batch(
+    conn: &Connection,
+    insert: &mut rusqlite::Statement<'_>,
+    batch: &mut Vec<Vec<rusqlite::types::Value>>,
+    rows: &mut i64,
+) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    let inserted = batch.drain(..).try_for_each(|values| {
+        insert.execute(rusqlite::params_from_iter(values))?;
+        *rows += 1;
+        Ok(())
+    });
+    tx.commit()?;
+    inserted
+}
+
+/// Copy rows of one table until the first unreadable page or row that cannot be
+/// copied, keeping the rows copied so far. `rows` counts them even when the copy
+/// stops early.
+fn copy_rows(conn: &Connection, table: &str, columns: &[String], rows: &mut i64) -> rusqlite::Result<()> {
+    let column_list = columns.join(", ");
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    let mut select = conn.prepare(&format!("SELECT {} FROM damaged.\"{}\"", column_list, table))?;
+    let mut insert = conn.prepare(&format!(
+        "INSERT OR REPLACE INTO main.\"{}\" ({}) VALUES ({})",
+        table, column_list, placeholders
+    ))?;
+    let mut batch = Vec::new();
+    let mut cursor = select.query([])?;
+    let copied = (|| -> rusqlite::Result<()> {
+        while let Some(row) = cursor.next()? {
+            batch.push((0..columns.len()).map(|i| row.get(i)).collect::<rusqlite::Result<_>>()?);
+            if batch.len() >= SALVAGE_BATCH_ROWS {
+                insert_batch(conn, &mut insert, &mut batch, rows)?;
+            }
+        }
+        Ok(())
+    })();
+    insert_batch(conn, &mut insert, &mut batch, rows)?;
+    copied
+}
+
+/// Copy readable rows of every table that exists in both databases
+fn salvage(damaged: &Path, target: &Path) -> rusqlite::Result<Vec<SalvagedTable>> {
+    // Creates the current schema (including migrations) in the new file
+    drop(Database::new(target.to_path_buf())?);
+
+    let conn = Connection::open(target)?;
+    conn.execute("ATTACH DATABASE ?1 AS damaged", [damaged.to_string_lossy()])?;
+    conn.execute_batch("PRAGMA foreign_keys = OFF")?;
+
+    let tables: Vec<String> = conn
+        .prepare("SELECT name FROM damaged.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'")
+        .and_then(|mut stmt| stmt.query_map([], |row| row.get(0))?.collect())
+        .unwrap_or_default();
+
+    let mut salvaged = Vec::new();
+    for table in tables {
+        let target_columns = table_columns(&conn, "main", &table)?;
+        let source_columns = table_columns(&conn, "damaged", &table).unwrap_or_default();
+        let columns: Vec<String> = target_columns
+            .into_iter()
+            .filter(|c| source_columns.contains(c))
+            .map(|c| format!("\"{}\"", c))
+            .collect();
+        if columns.is_empty() {
+            // Plugin tables are recreated by plugins; copy them as they were
+            if conn
+                .execute(&format!("CREATE TABLE \"{0}\" AS SELECT * FROM damaged.\"{0}\"", table), [])
+                .is_ok()
+            {
+                salvaged.push(SalvagedTable { table, rows: conn.changes() as i64, error: None });
+            }
+            continue;
+        }
+
+        let mut rows = 0;
+        let error = copy_rows(&conn, &table, &columns, &mut rows).err().map(|e| e.to_string());
+        salvaged.push(SalvagedTable { table, rows, error });
+    }
+
+    conn.execute("DETACH DATABASE damaged", [])?;
+    Ok(salvaged)
+}
+
+/// `path` with `suffix` appended to the file name (`data.db` -> `data.db-wal`)
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Move the database and its WAL/SHM files aside
+fn quarantine(path: &Path) -> std::io::Result<PathBuf> {
+    let suffix = format!(".corrupt-{}", chrono::Utc::now().format("%Y%m%d%H%M%S"));
+    let quarantined = sibling(path, &suffix);
+    std::fs::rename(path, &quarantined)?;
+    for extra in ["-wal", "-shm"] {
+        let file = sibling(path, extra);
+        if file.exists() {
+            std::fs::rename(&file, sibling(&quarantined, extra)).ok();
+        }
+    }
+    Ok(quarantined)
+}
+
+/// Open the database, salvaging it first if it is corrupted.
+/// Only fails if not even an empty database can be created.
+pub fn open_with_recovery(path: PathBuf) -> rusqlite::Result<(Database, Option<DatabaseRecovery>)> {
+    let Some(problem) = integrity_problem(&path) else {
+        return Database::new(path).map(|db| (db, None));
+    };
+    eprintln!("Database {} is corrupted: {}", path.display(), problem);
+
+    let recovered_path = sibling(&path, ".recovered");
+    std::fs::remove_file(&recovered_path).ok();
+    let (mut status, tables, mut error) = match salvage(&path, &recovered_path) {
+        Ok(tables) => ("recovered", tables, None),
+        Err(e) => {
+            std::fs::remove_file(&recovered_path).ok();
+            ("reset", Vec::new(), Some(format!("Salvage failed: {}", e)))
+        }
+    };
+
+    let quarantined_path = match quarantine(&path) {
+        Ok(quarantined) => Some(quarantined),
+        Err(e) => {
+            error = Some(format!("Failed to move the damaged database aside: {}", e));
+            None
+        }
+    };
+
+    let db_path = match (status, &quarantined_path) {
+        ("recovered", Some(_)) => match std::fs::rename(&recovered_path, &path) {
+            Ok(()) => path,
+            Err(e) => {
+                error = Some(format!("Failed to install recovered database: {}", e));
+                recovered_path
+            }
+        },
+        // The damaged file is still in place; keep working on the salvaged copy
+        ("recovered", None) => recovered_path,
+        (_, Some(_)) => path,
+        (_, None) => {
+            status = "reset";
+            sibling(&path, ".new")
+        }
+    };
+
+    let db = Database::new(db_path)?;
+    Ok((
+        db,
+        Some(DatabaseRecovery {
+            status: status.to_string(),
+            problem,
+            quarantined_path: quarantined_path.map(|p| p.to_string_lossy().into_owned()),
+            tables,
+            error,
+        }),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty directory of its own for one test's database files
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("time-tracker-recovery-{}-{}", name, std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn rows_before_a_corrupt_page_are_salvaged_and_the_file_is_quarantined() {
+        let dir = scratch_dir("page");
+        let path = dir.join("data.db");
+        let categories = {
+            let db = Database::new(path.clone()).unwrap();
+            let conn = db.conn.lock().unwrap();
+            for i in 0..2000 {
+                conn.execute(
+                    "INSERT INTO activities (app_name, window_title, started_at, duration_sec) VALUES ('Editor', ?1, ?2, 60)",
+                    rusqlite::params![format!("window {:05} {}", i, "x".repeat(150)), 1_700_000_000 + i * 60],
+                )
+                .unwrap();
+            }
+            conn.query_row("SELECT COUNT(*) FROM categories", [], |row| row.get::<_, i64>(0)).unwrap()
+        };
+
+        // Overwrite the page holding the middle of the activities table
+        let mut bytes = std::fs::read(&path).unwrap();
+        let page_size = match u16::from_be_bytes([bytes[16], bytes[17]]) {
+            1 => 65536,
+            size => size as usize,
+        };
+        let marker = b"window 01000 ";
+        let offset = bytes.windows(marker.len()).position(|w| w == marker).unwrap();
+        let page = offset / page_size;
+        bytes[page * page_size..(page + 1) * page_size].fill(0xFF);
+        std::fs::write(&path, bytes).unwrap();
+
+        let (db, recovery) = open_with_recovery(path.clone()).unwrap();
+        let recovery = recovery.unwrap();
+        assert_eq!(recovery.status, "recovered");
+        assert_eq!(recovery.error, None);
+
+        let activities = recovery.tables.iter().find(|t| t.table == "activities").unwrap();
+        assert!(activities.rows > 0 && activities.rows < 1000, "salvaged {} rows", activities.rows);
+        assert!(activities.error.is_some());
+        let restored = recovery.tables.iter().find(|t| t.table == "categories").unwrap();
+        assert_eq!((restored.rows, restored.error.as_deref()), (categories, None));
+
+        let stored: i64 = db.conn.lock().unwrap()
+            .query_row("SELECT COUNT(*) FROM activities", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, activities.rows);
+
+        let quarantined = PathBuf::from(recovery.quarantined_path.unwrap());
+        let name = quarantined.file_name().unwrap().to_string_lossy().into_owned();
+        assert!(name.starts_with("data.db.corrupt-") && name.len() == "data.db.corrupt-".len() + 14, "{}", name);
+        drop(db);
+        assert_eq!(file_names(&dir), vec!["data.db".to_string(), name]);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn a_row_that_cannot_be_copied_stops_only_its_own_table() {
+        let dir = scratch_dir("row");
+        let damaged = dir.join("damaged.db");
+        {
+            let conn = Connection::open(&damaged).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE categories (id INTEGER PRIMARY KEY, name TEXT);
+                 INSERT INTO categories (id, name) VALUES (100, 'Reading'), (101, NULL), (102, 'Writing');
+                 CREATE TABLE activities (id INTEGER PRIMARY KEY, app_name TEXT, started_at INTEGER, duration_sec INTEGER);
+                 INSERT INTO activities VALUES (1, 'Editor', 1700000000, 60), (2, 'Browser', 1700000060, 30);",
+            )
+            .unwrap();
+        }
+
+        let tables = salvage(&damaged, &dir.join("recovered.db")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let categories = tables.iter().find(|t| t.table == "categories").unwrap();
+        assert_eq!(categories.rows, 1);
+        assert!(categories.error.as_deref().unwrap().contains("NOT NULL"));
+        let activities = tables.iter().find(|t| t.table == "activities").unwrap();
+        assert_eq!((activities.rows, activities.error.as_deref()), (2, None));
+    }
+
+    #[test]
+    fn quarantine_moves_the_database_and_its_journal_files_aside() {
+        let dir = scratch_dir("quarantine");
+        let path = dir.join("data.db");
+        for file in ["data.db", "data.db-wal", "data.db-shm"] {
+            std::fs::write(dir.join(file), file).unwrap();
+        }
+
+        let quarantined = quarantine(&path).unwrap();
+        let name = quarantined.file_name().unwrap().to_string_lossy().into_owned();
+        let names = file_names(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(name.starts_with("data.db.corrupt-"), "{}", name);
+        assert_eq!(names, vec![name.clone(), format!("{}-shm", name), format!("{}-wal", name)]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Reports of the last `reapply_categorization_rules` run
+//!
+//! A run records every activity whose category it changed (old and new
+//! category), replacing the previous run. The report adds per-category time
+//! deltas, and the run can be undone as long as nothing else moved the
+//! activities since.
+
+use rusqlite::{params, OptionalExtension, Result};
+use std::collections::HashMap;
+use super::approvals::ACTIVITY_NOT_APPROVED_SQL;
+use super::common::Database;
+use super::models::{RuleRunCategoryDelta, RuleRunChange, RuleRunReport};
+
+/// Time and activities each category gained and lost, largest net change first
+pub(crate) fn category_deltas(changes: &[RuleRunChange]) -> Vec<RuleRunCategoryDelta> {
+    let mut deltas: HashMap<Option<i64>, RuleRunCategoryDelta> = HashMap::new();
+    for change in changes {
+        for (category_id, gained) in [(change.old_category_id, false), (change.new_category_id, true)] {
+            let delta = deltas.entry(category_id).or_insert_with(|| RuleRunCategoryDelta {
+                category_id,
+                ..Default::default()
+            });
+            if gained {
+                delta.activities_gained += 1;
+                delta.seconds_gained += change.duration_sec;
+            } else {
+                delta.activities_lost += 1;
+                delta.seconds_lost += change.duration_sec;
+            }
+        }
+    }
+    let mut deltas: Vec<RuleRunCategoryDelta> = deltas
+        .into_values()
+        .map(|mut d| {
+            d.net_seconds = d.seconds_gained - d.seconds_lost;
+            d
+        })
+        .collect();
+    deltas.sort_by_key(|d| (std::cmp::Reverse(d.net_seconds.abs()), d.category_id));
+    deltas
+}
+
+impl Database {
+    /// What the last rule run changed, or `None` before the first run
+    pub fn get_last_rule_run_report(&self) -> Result<Option<RuleRunReport>> {
+        let conn = self.conn.lock().unwrap();
+        let run = conn
+            .query_row(
+                "SELECT id, ran_at, activities_checked, undone_at FROM rule_runs ORDER BY id DESC LIMIT 1",
+                [],
+                |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .optional()?;
+        let Some((id, ran_at, activities_checked, undone_at)) = run else {
+            return Ok(None);
+        };
+
+        let mut stmt = conn.prepare(
+            "SELECT c.activity_id, c.old_category_id, c.new_category_id,
+                    a.app_name, a.window_title, a.started_at, COALESCE(a.duration_sec, 0)
+             FROM rule_run_changes c
+             LEFT JOIN activities a ON a.id = c.activity_id
+             WHERE c.run_id = ?
+             ORDER BY a.started_at DESC, c.activity_id",
+        )?;
+        let changes = stmt
+            .query_map(params![id], |row| {
+                Ok(RuleRunChange {
+                    activity_id: row.get(0)?,
+                    old_category_id: row.get(1)?,
+                    new_category_id: row.get(2)?,
+                    app_name: row.get(3)?,
+                    window_title: row.get(4)?,
+                    started_at: row.get(5)?,
+                    duration_sec: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(RuleRunReport {
+            id,
+            ran_at,
+            activities_checked,
+            undone_at,
+            categories: category_deltas(&changes),
+            changes,
+        }))
+    }
+
+    /// Put the activities the last rule run moved back in their old category.
+    /// Activities recategorized or approved since keep their category. Returns
+    /// the number restored (0 if there is no run or it was already undone).
+    pub fn undo_last_rule_run(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let run_id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM rule_runs WHERE undone_at IS NULL ORDER BY id DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(run_id) = run_id else {
+            return Ok(0);
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        let restored = tx.execute(
+            &format!(
+                "UPDATE activities
+                 SET category_id = (SELECT c.old_category_id FROM rule_run_changes c
+                                    WHERE c.run_id = ?1 AND c.activity_id = activities.id)
+                 WHERE id IN (SELECT c.activity_id FROM rule_run_changes c
+                              WHERE c.run_id = ?1 AND c.new_category_id IS activities.category_id)
+                   AND {ACTIVITY_NOT_APPROVED_SQL}"
+            ),
+            params![run_id],
+        )?;
+        tx.execute(
+            "UPDATE rule_runs SET undone_at = ? WHERE id = ?",
+            params![chrono::Utc::now().timestamp(), run_id],
+        )?;
+        tx.commit()?;
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::common::{SOURCE_TRACKER, SYSTEM_CATEGORY_BREAK, SYSTEM_CATEGORY_THINKING};
+    use std::path::PathBuf;
+
+    #[test]
+    fn rule_runs_are_reported_and_undone() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let base = 1_700_000_000;
+        let first = db.upsert_activity("Hammerspoon", Some("init.lua"), None, base, SOURCE_TRACKER, None).unwrap();
+        let second = db.upsert_activity("Hammerspoon", Some("Console"), None, base + 600, SOURCE_TRACKER, None).unwrap();
+        let category = |id: i64| db.get_activity_by_id(id).unwrap().unwrap().category_id;
+        let original = category(first);
+
+        db.add_rule("app_name", "Hammerspoon", SYSTEM_CATEGORY_BREAK, 100, None).unwrap();
+        let run_id = db.reapply_categorization_rules().unwrap();
+        let report = db.get_last_rule_run_report().unwrap().unwrap();
+        assert_eq!((report.id, report.changes.len()), (run_id, 2));
+        let gained = report.categories.iter().find(|d| d.category_id == Some(SYSTEM_CATEGORY_BREAK)).unwrap();
+        assert_eq!((gained.activities_gained, gained.activities_lost), (2, 0));
+
+        // Corrections made after the run survive the undo
+        db.update_activity_category(second, Some(SYSTEM_CATEGORY_THINKING)).unwrap();
+        assert_eq!(db.undo_last_rule_run().unwrap(), 1);
+        assert_eq!(category(first), original);
+        assert_eq!(category(second), Some(SYSTEM_CATEGORY_THINKING));
+        assert!(db.get_last_rule_run_report().unwrap().unwrap().undone_at.is_some());
+        assert_eq!(db.undo_last_rule_run().unwrap(), 0);
+    }
+}
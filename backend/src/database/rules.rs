@@ -1,15 +1,124 @@
 //! Rule management database operations
+//!
+//! Rules can be shared as rule packs: JSON files that name categories instead
+//! of using local IDs, so a pack (e.g. a "web developer pack") can be imported
+//! into any database. Missing categories are created from the pack.
+//!
+//! Rules of the same type whose patterns look for the same text (`Chrome`,
+//! `chrome*`, `*CHROME*`) are similar: adding or importing one next to the
+//! other can merge them into a single rule instead.
 
 use rusqlite::{Result, params};
+use serde::{Deserialize, Serialize};
+use super::activities::fold_case;
 use super::common::Database;
 use super::models::Rule;
 
+/// `format` value of rule pack files
+pub const RULE_PACK_FORMAT: &str = "time-tracker-rules";
+pub const RULE_PACK_VERSION: i64 = 1;
+
+/// Category a rule pack refers to, created on import if missing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePackCategory {
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub is_productive: Option<bool>,
+}
+
+/// Rule in a rule pack; `category` is a category name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePackRule {
+    pub rule_type: String,
+    pub pattern: String,
+    pub category: String,
+    #[serde(default)]
+    pub priority: i64,
+}
+
+/// Shareable set of rules
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulePack {
+    pub format: String,
+    pub version: i64,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<RulePackCategory>,
+    pub rules: Vec<RulePackRule>,
+}
+
+/// What to do with an imported rule similar to an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleImportStrategy {
+    /// Keep the existing rule
+    Skip,
+    /// Give the existing rule the imported pattern, category and priority
+    Overwrite,
+    /// Keep the existing category, widen the pattern to cover both and take
+    /// the higher of both priorities
+    Merge,
+}
+
+/// Result of a rule pack import
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleImportSummary {
+    pub added: i64,
+    pub updated: i64,
+    pub skipped: i64,
+    /// Categories created because the pack referred to them
+    pub categories_created: Vec<String>,
+}
+
+/// Pattern without surrounding whitespace and with repeated leading or
+/// trailing `*` collapsed (`**chrome ` becomes `*chrome`)
+pub fn normalize_pattern(pattern: &str) -> String {
+    let pattern = pattern.trim();
+    let core = pattern.trim_matches('*');
+    if core.is_empty() {
+        return if pattern.is_empty() { String::new() } else { "*".to_string() };
+    }
+    let star = |present: bool| if present { "*" } else { "" };
+    format!("{}{}{}", star(pattern.starts_with('*')), core, star(pattern.ends_with('*')))
+}
+
+/// Text a pattern looks for, lowercased and without wildcards
+fn pattern_text(pattern: &str) -> String {
+    fold_case(pattern.trim().trim_matches('*'))
+}
+
+/// Whether two patterns look for the same text, ignoring case and wildcards
+pub fn similar_patterns(a: &str, b: &str) -> bool {
+    let text = pattern_text(a);
+    !text.is_empty() && text == pattern_text(b)
+}
+
+/// Pattern of a rule merged from two similar ones: the one matching more.
+/// Patterns without wildcards (or with both) match anywhere; a prefix and a
+/// suffix pattern merge into one matching anywhere.
+pub fn merged_pattern(existing: &str, new: &str) -> String {
+    let wildcards = |p: &str| (p.starts_with('*'), p.ends_with('*'));
+    let anywhere = |p: &str| matches!(wildcards(p), (false, false) | (true, true));
+    if anywhere(existing) || wildcards(existing) == wildcards(new) {
+        existing.to_string()
+    } else if anywhere(new) {
+        new.to_string()
+    } else {
+        existing.trim_matches('*').to_string()
+    }
+}
+
 impl Database {
     /// Get all rules
     pub fn get_rules(&self) -> Result<Vec<Rule>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, rule_type, pattern, category_id, priority
+            "SELECT id, rule_type, pattern, category_id, priority, project_id
              FROM rules
              ORDER BY priority DESC",
         )?;
@@ -22,6 +131,7 @@ impl Database {
                     pattern: row.get(2)?,
                     category_id: row.get(3)?,
                     priority: row.get(4)?,
+                    project_id: row.get(5)?,
                 })
             })?
             .collect::<Result<Vec<_>>>()?;
@@ -36,12 +146,13 @@ impl Database {
         pattern: &str,
         category_id: i64,
         priority: i64,
+        project_id: Option<i64>,
     ) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO rules (rule_type, pattern, category_id, priority)
-             VALUES (?, ?, ?, ?)",
-            params![rule_type, pattern, category_id, priority],
+            "INSERT INTO rules (rule_type, pattern, category_id, priority, project_id)
+             VALUES (?, ?, ?, ?, ?)",
+            params![rule_type, pattern, category_id, priority, project_id],
         )
         .map_err(|e| {
             if let rusqlite::Error::SqliteFailure(ref err, Some(ref msg)) = e {
@@ -61,6 +172,14 @@ impl Database {
         Ok(conn.last_insert_rowid())
     }
 
+    /// Highest priority rule of `rule_type` whose pattern is similar to
+    /// `pattern` (see `similar_patterns`), other than `exclude_id`
+    pub fn find_similar_rule(&self, rule_type: &str, pattern: &str, exclude_id: Option<i64>) -> Result<Option<Rule>> {
+        Ok(self.get_rules()?.into_iter().find(|rule| {
+            Some(rule.id) != exclude_id && rule.rule_type == rule_type && similar_patterns(&rule.pattern, pattern)
+        }))
+    }
+
     /// Delete a rule
     pub fn delete_rule(&self, id: i64) -> Result<()> {
         let conn = self.conn.lock().unwrap();
@@ -76,12 +195,13 @@ impl Database {
         pattern: &str,
         category_id: i64,
         priority: i64,
+        project_id: Option<i64>,
     ) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "UPDATE rules SET rule_type = ?, pattern = ?, category_id = ?, priority = ?
+            "UPDATE rules SET rule_type = ?, pattern = ?, category_id = ?, priority = ?, project_id = ?
              WHERE id = ?",
-            params![rule_type, pattern, category_id, priority, id],
+            params![rule_type, pattern, category_id, priority, project_id, id],
         )
         .map_err(|e| {
             if let rusqlite::Error::SqliteFailure(ref err, Some(ref msg)) = e {
@@ -100,4 +220,156 @@ impl Database {
         })?;
         Ok(())
     }
+
+    /// Rules as a pack, optionally only those of some categories
+    pub fn export_rule_pack(&self, name: Option<&str>, category_ids: Option<&[i64]>) -> Result<RulePack> {
+        let categories = self.get_categories()?;
+        let rules: Vec<Rule> = self
+            .get_rules()?
+            .into_iter()
+            .filter(|rule| category_ids.is_none_or(|ids| ids.contains(&rule.category_id)))
+            .collect();
+
+        let mut pack_categories: Vec<RulePackCategory> = Vec::new();
+        let mut pack_rules = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let Some(category) = categories.iter().find(|c| c.id == rule.category_id) else {
+                continue;
+            };
+            if !pack_categories.iter().any(|c| c.name == category.name) {
+                pack_categories.push(RulePackCategory {
+                    name: category.name.clone(),
+                    color: Some(category.color.clone()),
+                    icon: category.icon.clone(),
+                    is_productive: category.is_productive,
+                });
+            }
+            pack_rules.push(RulePackRule {
+                rule_type: rule.rule_type,
+                pattern: rule.pattern,
+                category: category.name.clone(),
+                priority: rule.priority,
+            });
+        }
+
+        Ok(RulePack {
+            format: RULE_PACK_FORMAT.to_string(),
+            version: RULE_PACK_VERSION,
+            name: name.map(|n| n.to_string()),
+            categories: pack_categories,
+            rules: pack_rules,
+        })
+    }
+
+    /// Import a rule pack. Rules for archived categories are skipped.
+    pub fn import_rule_pack(&self, pack: &RulePack, strategy: RuleImportStrategy) -> Result<RuleImportSummary> {
+        let mut summary = RuleImportSummary::default();
+        let mut categories = self.get_categories()?;
+        let mut existing_rules = self.get_rules()?;
+        let next_sort_order = categories.iter().map(|c| c.sort_order).max().unwrap_or(0) + 1;
+        let suggested_color = self.suggest_category_color()?;
+
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        for rule in &pack.rules {
+            let category = match categories.iter().find(|c| c.name.eq_ignore_ascii_case(&rule.category)) {
+                Some(category) => category.clone(),
+                None => {
+                    let template = pack.categories.iter().find(|c| c.name.eq_ignore_ascii_case(&rule.category));
+                    tx.execute(
+                        "INSERT INTO categories (name, color, icon, is_productive, sort_order) VALUES (?, ?, ?, ?, ?)",
+                        params![
+                            rule.category,
+                            template.and_then(|c| c.color.clone()).unwrap_or_else(|| suggested_color.clone()),
+                            template.and_then(|c| c.icon.clone()),
+                            template.and_then(|c| c.is_productive),
+                            next_sort_order + summary.categories_created.len() as i64,
+                        ],
+                    )?;
+                    let category = super::models::Category {
+                        id: tx.last_insert_rowid(),
+                        name: rule.category.clone(),
+                        color: String::new(),
+                        icon: None,
+                        is_productive: None,
+                        sort_order: 0,
+                        is_system: false,
+                        is_pinned: false,
+                        exclude_from_export: false,
+                        is_archived: false,
+                    };
+                    summary.categories_created.push(rule.category.clone());
+                    categories.push(category.clone());
+                    category
+                }
+            };
+            if category.is_archived {
+                summary.skipped += 1;
+                continue;
+            }
+
+            // Rules added earlier from the same pack count as existing
+            let pattern = normalize_pattern(&rule.pattern);
+            let conflict = existing_rules
+                .iter_mut()
+                .find(|r| r.rule_type == rule.rule_type && similar_patterns(&r.pattern, &pattern));
+            match (conflict, strategy) {
+                (None, _) => {
+                    // Patterns of only wildcards are never similar; duplicates of
+                    // those hit the unique index and are skipped
+                    let inserted = tx.execute(
+                        "INSERT OR IGNORE INTO rules (rule_type, pattern, category_id, priority) VALUES (?, ?, ?, ?)",
+                        params![rule.rule_type, pattern, category.id, rule.priority],
+                    )?;
+                    if inserted == 0 {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    existing_rules.push(Rule {
+                        id: tx.last_insert_rowid(),
+                        rule_type: rule.rule_type.clone(),
+                        pattern,
+                        category_id: category.id,
+                        priority: rule.priority,
+                        project_id: None,
+                    });
+                    summary.added += 1;
+                }
+                (Some(_), RuleImportStrategy::Skip) => summary.skipped += 1,
+                (Some(existing), RuleImportStrategy::Overwrite) => {
+                    // The unique index skips an overwrite that duplicates another rule
+                    let updated = tx.execute(
+                        "UPDATE OR IGNORE rules SET pattern = ?, category_id = ?, priority = ? WHERE id = ?",
+                        params![pattern, category.id, rule.priority, existing.id],
+                    )?;
+                    if updated > 0 {
+                        (existing.pattern, existing.category_id, existing.priority) = (pattern, category.id, rule.priority);
+                        summary.updated += 1;
+                    } else {
+                        summary.skipped += 1;
+                    }
+                }
+                (Some(existing), RuleImportStrategy::Merge) => {
+                    let merged = merged_pattern(&existing.pattern, &pattern);
+                    let priority = existing.priority.max(rule.priority);
+                    if merged == existing.pattern && priority == existing.priority {
+                        summary.skipped += 1;
+                        continue;
+                    }
+                    let updated = tx.execute(
+                        "UPDATE OR IGNORE rules SET pattern = ?, priority = ? WHERE id = ?",
+                        params![merged, priority, existing.id],
+                    )?;
+                    if updated > 0 {
+                        (existing.pattern, existing.priority) = (merged, priority);
+                        summary.updated += 1;
+                    } else {
+                        summary.skipped += 1;
+                    }
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(summary)
+    }
 }
@@ -1,8 +1,39 @@
 //! Settings database operations
+//!
+//! The layout of the settings keys is versioned separately from the schema
+//! (`settings_version`). `migrate_settings` runs on open and rewrites stored
+//! settings and saved profiles to the current layout, so deprecated keys are
+//! read and written in one place only.
 
-use rusqlite::{Result, params};
+use rusqlite::{Connection, Result, params};
+use std::collections::HashMap;
 use super::common::Database;
 
+/// Current layout of the settings keys
+pub const SETTINGS_VERSION: i64 = 1;
+
+/// Deprecated keys as (legacy key, canonical key, factor to canonical units)
+pub const LEGACY_SETTING_KEYS: &[(&str, &str, i64)] = &[
+    ("idle_threshold_minutes", "idle_threshold_seconds", 60),
+    ("idle_prompt_threshold_minutes", "idle_prompt_threshold_seconds", 60),
+];
+
+/// Replace legacy keys in a settings map by their canonical keys.
+/// A canonical value already in the map wins over the legacy one.
+pub fn canonicalize_settings(map: &mut HashMap<String, String>) {
+    for (legacy, canonical, factor) in LEGACY_SETTING_KEYS {
+        let Some(value) = map.remove(*legacy) else {
+            continue;
+        };
+        if map.contains_key(*canonical) {
+            continue;
+        }
+        if let Ok(value) = value.trim().parse::<i64>() {
+            map.insert(canonical.to_string(), (value * factor).to_string());
+        }
+    }
+}
+
 impl Database {
     /// Get setting value
     pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
@@ -41,6 +72,13 @@ impl Database {
         Ok(settings)
     }
 
+    /// Delete a setting
+    pub fn delete_setting(&self, key: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM settings WHERE key = ?", params![key])?;
+        Ok(())
+    }
+
     /// Set multiple settings
     pub fn set_settings(&self, settings: &std::collections::HashMap<String, String>) -> Result<()> {
         let mut conn = self.conn.lock().unwrap();
@@ -54,6 +92,65 @@ impl Database {
         tx.commit()?;
         Ok(())
     }
+
+    /// Bring stored settings and saved profiles to `SETTINGS_VERSION`
+    pub(crate) fn migrate_settings(conn: &Connection) -> Result<()> {
+        let version: i64 = conn
+            .query_row(
+                "SELECT CAST(value AS INTEGER) FROM settings WHERE key = 'settings_version'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .unwrap_or(0);
+        if version >= SETTINGS_VERSION {
+            return Ok(());
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        let stored: HashMap<String, String> = {
+            let mut stmt = tx.prepare("SELECT key, value FROM settings")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_>>()?
+        };
+        let mut settings = stored.clone();
+        canonicalize_settings(&mut settings);
+        for (key, value) in &settings {
+            if stored.get(key) != Some(value) {
+                tx.execute(
+                    "INSERT OR REPLACE INTO settings (key, value) VALUES (?, ?)",
+                    params![key, value],
+                )?;
+            }
+        }
+        for (legacy, _, _) in LEGACY_SETTING_KEYS {
+            tx.execute("DELETE FROM settings WHERE key = ?", params![legacy])?;
+        }
+
+        let profiles: Vec<(String, String)> = {
+            let mut stmt = tx.prepare("SELECT name, settings FROM settings_profiles")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_>>()?
+        };
+        for (name, json) in profiles {
+            let Ok(mut profile_settings) = serde_json::from_str::<HashMap<String, String>>(&json) else {
+                continue;
+            };
+            canonicalize_settings(&mut profile_settings);
+            let migrated = serde_json::to_string(&profile_settings).unwrap_or(json);
+            tx.execute(
+                "UPDATE settings_profiles SET settings = ? WHERE name = ?",
+                params![migrated, name],
+            )?;
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES ('settings_version', ?)",
+            params![SETTINGS_VERSION.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
 }
 
 // Use OptionalExtension from common module
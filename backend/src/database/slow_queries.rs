@@ -0,0 +1,135 @@
+//! Slow query log
+//!
+//! Every connection gets a SQLite profile hook. While `slow_query_log_ms` is
+//! above 0, statements that take longer than that are remembered (one entry
+//! per SQL text, the most recent `MAX_SLOW_QUERIES`). `get_diagnostics` lists
+//! them with their `EXPLAIN QUERY PLAN` and the tables they scan without an
+//! index, so a missing index shows up on the machine where it hurts.
+
+use super::common::Database;
+use rusqlite::types::Null;
+use rusqlite::{params_from_iter, Connection, Result};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Distinct statements kept; the least recently slow one is dropped first
+const MAX_SLOW_QUERIES: usize = 50;
+
+/// Threshold in milliseconds; 0 disables logging
+static THRESHOLD_MS: AtomicU64 = AtomicU64::new(0);
+
+static SLOW_QUERIES: Mutex<Vec<SlowQuery>> = Mutex::new(Vec::new());
+
+/// A statement that ran longer than the threshold
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SlowQuery {
+    pub sql: String,
+    /// Slow runs since logging was enabled
+    pub count: i64,
+    pub max_ms: f64,
+    pub last_ms: f64,
+    pub last_seen_at: i64,
+    /// `EXPLAIN QUERY PLAN` lines, filled in by `get_slow_queries`
+    pub plan: Vec<String>,
+    /// Tables the plan reads without an index
+    pub full_scans: Vec<String>,
+}
+
+/// Log statements slower than `ms` milliseconds (0 turns logging off and
+/// forgets what was logged)
+pub fn set_threshold(ms: i64) {
+    THRESHOLD_MS.store(ms.max(0) as u64, Ordering::Relaxed);
+    if ms <= 0 {
+        SLOW_QUERIES.lock().unwrap().clear();
+    }
+}
+
+/// Profile hook installed on every connection
+pub(crate) fn profile(sql: &str, duration: Duration) {
+    let threshold = THRESHOLD_MS.load(Ordering::Relaxed);
+    if threshold == 0 || duration < Duration::from_millis(threshold) || sql.starts_with("EXPLAIN") {
+        return;
+    }
+    let ms = duration.as_secs_f64() * 1000.0;
+    let now = chrono::Utc::now().timestamp();
+    let mut queries = SLOW_QUERIES.lock().unwrap();
+    let mut query = match queries.iter().position(|q| q.sql == sql) {
+        Some(index) => queries.remove(index),
+        None => SlowQuery {
+            sql: sql.to_string(),
+            count: 0,
+            max_ms: 0.0,
+            last_ms: 0.0,
+            last_seen_at: 0,
+            plan: Vec::new(),
+            full_scans: Vec::new(),
+        },
+    };
+    query.count += 1;
+    query.max_ms = query.max_ms.max(ms);
+    query.last_ms = ms;
+    query.last_seen_at = now;
+    if queries.len() >= MAX_SLOW_QUERIES {
+        queries.remove(0);
+    }
+    queries.push(query);
+}
+
+/// Table a plan line reads in full, e.g. `activities` for `SCAN activities`.
+/// Scans of subqueries, constant rows and index scans don't count.
+pub(crate) fn full_scan_table(detail: &str) -> Option<&str> {
+    let target = detail.strip_prefix("SCAN ")?;
+    let target = target.strip_prefix("TABLE ").unwrap_or(target);
+    if target.contains(" USING ") || target.starts_with('(') || target.starts_with("CONSTANT ROW") {
+        return None;
+    }
+    target.split_whitespace().next()
+}
+
+/// `EXPLAIN QUERY PLAN` lines of a statement, planned with NULL parameters
+pub(crate) fn query_plan(conn: &Connection, sql: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+    let nulls = vec![Null; stmt.parameter_count()];
+    let plan = stmt.query_map(params_from_iter(nulls), |row| row.get::<_, String>(3))?.collect();
+    plan
+}
+
+impl Database {
+    /// Logged slow statements, slowest first, with their current query plans
+    pub fn get_slow_queries(&self) -> Vec<SlowQuery> {
+        let mut queries = SLOW_QUERIES.lock().unwrap().clone();
+        let conn = self.conn.lock().unwrap();
+        for query in queries.iter_mut() {
+            // Statements on tables that are gone (or not plannable) keep an empty plan
+            query.plan = query_plan(&conn, &query.sql).unwrap_or_default();
+            query.full_scans = query.plan.iter().filter_map(|line| full_scan_table(line)).map(String::from).collect();
+        }
+        queries.sort_by(|a, b| b.max_ms.total_cmp(&a.max_ms));
+        queries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn report_range_queries_use_an_index() {
+        assert_eq!(full_scan_table("SCAN activities"), Some("activities"));
+        assert_eq!(full_scan_table("SCAN a USING INDEX idx_activities_started (started_at>?)"), None);
+        assert_eq!(full_scan_table("SCAN CONSTANT ROW"), None);
+
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let conn = db.conn.lock().unwrap();
+        let scans = |sql: &str| -> Vec<String> {
+            query_plan(&conn, sql).unwrap().iter().filter_map(|l| full_scan_table(l)).map(String::from).collect()
+        };
+        assert_eq!(
+            scans("SELECT a.app_name, SUM(a.duration_sec) FROM activities a WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0 GROUP BY a.app_name"),
+            Vec::<String>::new()
+        );
+        assert_eq!(scans("SELECT id FROM activities WHERE window_title = ?1"), vec!["activities"]);
+    }
+}
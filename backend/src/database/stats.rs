@@ -1,6 +1,8 @@
 //! Statistics and reporting database operations
 
-use super::common::Database;
+use super::activities::INPUT_SAMPLE_SECS;
+use super::common::{Database, SYSTEM_CATEGORY_BREAK};
+use super::domain_ratings::EFFECTIVE_PRODUCTIVE_SQL;
 use super::models::*;
 use rusqlite::{Result, params};
 
@@ -19,12 +21,12 @@ impl Database {
 
         // Query 1: total and productive seconds
         let (total_seconds, productive_seconds): (i64, i64) = conn.query_row(
-            "SELECT
+            &format!("SELECT
                 COALESCE(SUM(a.duration_sec), 0),
-                COALESCE(SUM(CASE WHEN c.is_productive = 1 THEN a.duration_sec ELSE 0 END), 0)
+                COALESCE(SUM(CASE WHEN {EFFECTIVE_PRODUCTIVE_SQL} = 1 THEN a.duration_sec ELSE 0 END), 0)
             FROM activities a
             LEFT JOIN categories c ON a.category_id = c.id
-            WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0",
+            WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0"),
             params![start, end],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
@@ -91,21 +93,51 @@ impl Database {
 
     /// Get top apps (SQL aggregation)
     pub fn get_top_apps(&self, start: i64, end: i64, limit: i64) -> Result<Vec<AppStat>> {
-        let categories = self.get_categories()?;
-        let cat_map: std::collections::HashMap<i64, Category> = categories
-            .iter()
-            .map(|c| (c.id, c.clone()))
-            .collect();
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn.prepare(
+        self.top_apps(
             "SELECT a.app_name, SUM(a.duration_sec) AS duration_sec, MAX(a.category_id) AS category_id
              FROM activities a
              WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0
              GROUP BY a.app_name
              ORDER BY duration_sec DESC
              LIMIT ?3",
-        )?;
-        let rows = stmt.query_map(params![start, end, limit], |row| {
+            params![start, end, limit],
+        )
+    }
+
+    /// Get top apps ranked by exponentially decayed time: time from
+    /// `half_life_days` before `end` (or now, if earlier) counts half, so
+    /// recent usage outweighs history. `duration_sec` is still the plain total.
+    pub fn get_top_apps_decayed(&self, start: i64, end: i64, limit: i64, half_life_days: f64) -> Result<Vec<AppStat>> {
+        // SQLite has no exp(); the per-day weights come from a recursive CTE
+        let daily_factor = 0.5f64.powf(1.0 / half_life_days.max(0.01));
+        let reference = end.min(chrono::Utc::now().timestamp());
+        self.top_apps(
+            "WITH RECURSIVE decay(day, weight) AS (
+                 SELECT 0, 1.0
+                 UNION ALL
+                 SELECT day + 1, weight * ?4 FROM decay WHERE day < (?5 - ?1) / 86400
+             )
+             SELECT a.app_name, SUM(a.duration_sec) AS duration_sec, MAX(a.category_id) AS category_id
+             FROM activities a
+             JOIN decay d ON d.day = MAX(0, (?5 - a.started_at) / 86400)
+             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0
+             GROUP BY a.app_name
+             ORDER BY SUM(a.duration_sec * d.weight) DESC
+             LIMIT ?3",
+            params![start, end, limit, daily_factor, reference],
+        )
+    }
+
+    /// Run a top apps query returning (app_name, duration_sec, category_id) rows
+    fn top_apps(&self, query: &str, params: &[&dyn rusqlite::ToSql]) -> Result<Vec<AppStat>> {
+        let categories = self.get_categories()?;
+        let cat_map: std::collections::HashMap<i64, Category> = categories
+            .iter()
+            .map(|c| (c.id, c.clone()))
+            .collect();
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(query)?;
+        let rows = stmt.query_map(params, |row| {
             Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, i64>(1)?,
@@ -188,10 +220,10 @@ impl Database {
     pub fn get_productive_time(&self, start: i64, end: i64) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         let productive_seconds: i64 = conn.query_row(
-            "SELECT COALESCE(SUM(a.duration_sec), 0) AS productive_seconds
+            &format!("SELECT COALESCE(SUM(a.duration_sec), 0) AS productive_seconds
              FROM activities a
-             INNER JOIN categories c ON a.category_id = c.id
-             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0 AND c.is_productive = 1",
+             LEFT JOIN categories c ON a.category_id = c.id
+             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0 AND {EFFECTIVE_PRODUCTIVE_SQL} = 1"),
             params![start, end],
             |row| row.get(0),
         )?;
@@ -199,6 +231,28 @@ impl Database {
     }
 
 
+    /// Get active (with keyboard/mouse input) and total time per app for a
+    /// time range, counting only activities with `input_intensity` recorded
+    pub fn get_input_intensity(&self, start: i64, end: i64) -> Result<Vec<InputIntensityStat>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT app_name, SUM(duration_sec) AS duration_sec,
+                    SUM(MIN(duration_sec, input_intensity * ?3)) AS active_seconds
+             FROM activities
+             WHERE started_at >= ?1 AND started_at <= ?2 AND is_idle = 0 AND input_intensity IS NOT NULL
+             GROUP BY app_name
+             ORDER BY duration_sec DESC",
+        )?;
+        let rows = stmt.query_map(params![start, end, INPUT_SAMPLE_SECS], |row| {
+            Ok(InputIntensityStat {
+                app_name: row.get(0)?,
+                duration_sec: row.get(1)?,
+                active_seconds: row.get(2)?,
+            })
+        })?;
+        rows.collect()
+    }
+
     /// Get top domains for a time range (SQL aggregation)
     pub fn get_top_domains(&self, start: i64, end: i64, limit: i64) -> Result<Vec<DomainStat>> {
         let conn = self.conn.lock().unwrap();
@@ -220,31 +274,145 @@ impl Database {
         Ok(domain_stats)
     }
 
+    /// Get the documents with the most tracked time for a time range
+    pub fn get_top_contexts(&self, start: i64, end: i64, limit: i64) -> Result<Vec<ContextStat>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT context_ref, SUM(duration_sec) AS duration_sec
+             FROM activities
+             WHERE started_at >= ?1 AND started_at <= ?2 AND is_idle = 0 AND context_ref IS NOT NULL
+             GROUP BY context_ref
+             ORDER BY duration_sec DESC
+             LIMIT ?3",
+        )?;
+        let rows = stmt.query_map(params![start, end, limit], |row| {
+            Ok(ContextStat {
+                context_ref: row.get(0)?,
+                duration_sec: row.get(1)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// Get non-idle tracked seconds whose window title mentions `needle` (case-insensitive)
+    pub fn get_tracked_seconds_for_title(&self, start: i64, end: i64, needle: &str) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT COALESCE(SUM(duration_sec), 0)
+             FROM activities
+             WHERE started_at >= ?1 AND started_at <= ?2 AND is_idle = 0
+               AND instr(lower(window_title), lower(?3)) > 0",
+            params![start, end, needle],
+            |row| row.get(0),
+        )
+    }
+
+    /// (ID, name) of the projects of the projects plugin, leaving out archived ones.
+    /// Empty until the plugin has added the `projects` table.
+    pub fn get_project_names(&self) -> Result<Vec<(i64, String)>> {
+        let conn = self.conn.lock().unwrap();
+        if !Self::column_exists(&conn, "projects", "name") {
+            return Ok(Vec::new());
+        }
+        let active = if Self::column_exists(&conn, "projects", "is_archived") {
+            " WHERE COALESCE(is_archived, 0) = 0"
+        } else {
+            ""
+        };
+        let mut stmt = conn.prepare(&format!("SELECT id, name FROM projects{active} ORDER BY id"))?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Get non-idle tracked seconds per project for a time range, largest first.
+    /// Empty until the projects plugin has added `activities.project_id`.
+    /// `exportable_only` leaves out categories and projects excluded from export.
+    pub fn get_project_breakdown(&self, start: i64, end: i64, exportable_only: bool) -> Result<Vec<(String, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        if !Self::column_exists(&conn, "activities", "project_id") || !Self::column_exists(&conn, "projects", "name") {
+            return Ok(Vec::new());
+        }
+        let exportable = if exportable_only {
+            format!(" AND {}", Self::exportable_condition(&conn))
+        } else {
+            String::new()
+        };
+        let mut stmt = conn.prepare(&format!(
+            "SELECT p.name, SUM(a.duration_sec) AS duration_sec
+             FROM activities a
+             JOIN projects p ON a.project_id = p.id
+             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0{exportable}
+             GROUP BY p.id
+             ORDER BY duration_sec DESC"
+        ))?;
+        let rows = stmt.query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    /// Get (start, end) of non-idle activities in a time range by start time,
+    /// leaving out time logged as a break
+    pub fn get_active_spans(&self, start: i64, end: i64) -> Result<Vec<(i64, i64)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT started_at, started_at + duration_sec
+             FROM activities
+             WHERE started_at >= ?1 AND started_at <= ?2 AND is_idle = 0 AND category_id IS NOT ?3
+             ORDER BY started_at",
+        )?;
+        let rows = stmt.query_map(params![start, end, SYSTEM_CATEGORY_BREAK], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
     /// Get aggregated stats for an arbitrary time range (SQL aggregation, for get_stats command).
-    pub fn get_stats_for_range(&self, start: i64, end: i64) -> Result<RangeStats> {
+    /// `sources` limits the stats to activities from the given sources (e.g. `tracker`)
+    /// and `devices` to those recorded on the given device IDs;
+    /// `exportable_only` leaves out categories and projects excluded from export.
+    pub fn get_stats_for_range(
+        &self,
+        start: i64,
+        end: i64,
+        sources: Option<&[String]>,
+        devices: Option<&[String]>,
+        exportable_only: bool,
+    ) -> Result<RangeStats> {
         let conn = self.conn.lock().unwrap();
+        let as_json = |values: Option<&[String]>| {
+            values
+                .filter(|v| !v.is_empty())
+                .map(|v| serde_json::to_string(v).unwrap_or_else(|_| "[]".to_string()))
+        };
+        let (sources_json, devices_json) = (as_json(sources), as_json(devices));
+        let exportable = if exportable_only {
+            format!(" AND {}", Self::exportable_condition(&conn))
+        } else {
+            String::new()
+        };
 
         let (total_seconds, productive_seconds): (i64, i64) = conn.query_row(
-            "SELECT
+            &format!("SELECT
                 COALESCE(SUM(a.duration_sec), 0),
-                COALESCE(SUM(CASE WHEN c.is_productive = 1 THEN a.duration_sec ELSE 0 END), 0)
+                COALESCE(SUM(CASE WHEN {EFFECTIVE_PRODUCTIVE_SQL} = 1 THEN a.duration_sec ELSE 0 END), 0)
             FROM activities a
             LEFT JOIN categories c ON a.category_id = c.id
-            WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0",
-            params![start, end],
+            WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0
+              AND (?3 IS NULL OR a.source IN (SELECT value FROM json_each(?3)))
+              AND (?4 IS NULL OR a.device_id IN (SELECT value FROM json_each(?4))){exportable}"),
+            params![start, end, sources_json, devices_json],
             |row| Ok((row.get(0)?, row.get(1)?)),
         )?;
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT a.category_id, COALESCE(c.name, 'Unknown'), COALESCE(c.color, '#888'), SUM(a.duration_sec) AS duration_sec
              FROM activities a
              LEFT JOIN categories c ON a.category_id = c.id
-             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0 AND a.category_id IS NOT NULL
+             WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0
+              AND (?3 IS NULL OR a.source IN (SELECT value FROM json_each(?3)))
+              AND (?4 IS NULL OR a.device_id IN (SELECT value FROM json_each(?4))) AND a.category_id IS NOT NULL{exportable}
              GROUP BY a.category_id
-             ORDER BY duration_sec DESC",
-        )?;
+             ORDER BY duration_sec DESC"
+        ))?;
         let category_breakdown: Vec<(i64, String, String, i64)> = stmt
-            .query_map(params![start, end], |row| {
+            .query_map(params![start, end, sources_json, devices_json], |row| {
                 Ok((
                     row.get(0)?,
                     row.get(1)?,
@@ -254,15 +422,17 @@ impl Database {
             })?
             .collect::<Result<Vec<_>>>()?;
 
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare(&format!(
             "SELECT a.app_name, SUM(a.duration_sec) AS duration_sec
              FROM activities a
              WHERE a.started_at >= ?1 AND a.started_at <= ?2 AND a.is_idle = 0
+              AND (?3 IS NULL OR a.source IN (SELECT value FROM json_each(?3)))
+              AND (?4 IS NULL OR a.device_id IN (SELECT value FROM json_each(?4))){exportable}
              GROUP BY a.app_name
-             ORDER BY duration_sec DESC",
-        )?;
+             ORDER BY duration_sec DESC"
+        ))?;
         let app_breakdown: Vec<(String, i64)> = stmt
-            .query_map(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .query_map(params![start, end, sources_json, devices_json], |row| Ok((row.get(0)?, row.get(1)?)))?
             .collect::<Result<Vec<_>>>()?;
 
         Ok(RangeStats {
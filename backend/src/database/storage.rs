@@ -0,0 +1,425 @@
+//! Storage backend abstraction
+//!
+//! `Storage` is the data layer the core commands go through (via
+//! `AppState::storage`) instead of the SQLite `Database`: activities,
+//! categories, rules, manual entries and the reporting queries behind the
+//! statistics commands. Another backend (a Postgres team server, DuckDB for
+//! analytics) implements this trait instead of the commands being rewritten.
+//! SQLite (`Database`) is the default and, for now, only implementation.
+//!
+//! The rest stays on `Database` for now: local-only data (settings, app usage
+//! metrics, plugin tables, jobs) and the plugin API, which hands plugins the
+//! SQLite connection.
+//!
+//! Errors are backend-neutral `StorageError`s, which commands turn into
+//! strings like any other error.
+
+use super::common::Database;
+use super::devices::DeviceSummary;
+use super::models::*;
+use super::rules::{RuleImportStrategy, RuleImportSummary, RulePack};
+use std::fmt;
+
+/// Error from a storage backend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageError(pub String);
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<rusqlite::Error> for StorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        StorageError(e.to_string())
+    }
+}
+
+pub type StorageResult<T> = Result<T, StorageError>;
+
+/// Operations a storage backend provides
+pub trait Storage: Send + Sync {
+    // Activities
+    #[allow(clippy::too_many_arguments)]
+    fn get_activities(
+        &self,
+        start: i64,
+        end: i64,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        exclude_idle: Option<bool>,
+        category_ids: Option<&[i64]>,
+        sources: Option<&[String]>,
+    ) -> StorageResult<Vec<Activity>>;
+    fn get_activities_page(
+        &self,
+        start: i64,
+        end: i64,
+        after: Option<ActivityCursor>,
+        limit: i64,
+        sources: Option<&[String]>,
+    ) -> StorageResult<(Vec<Activity>, Option<ActivityCursor>)>;
+    fn get_activity_by_id(&self, id: i64) -> StorageResult<Option<Activity>>;
+    fn update_activity_category(&self, id: i64, category_id: Option<i64>) -> StorageResult<()>;
+    fn set_activities_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> StorageResult<usize>;
+    fn delete_activity(&self, id: i64) -> StorageResult<()>;
+    fn record_category_correction(&self, activity_id: i64, category_id: i64) -> StorageResult<()>;
+    fn clear_category_correction(&self, activity_id: i64) -> StorageResult<()>;
+    fn get_idle_duration(&self, started_at: i64) -> StorageResult<Option<i64>>;
+    fn split_idle_activity(&self, started_at: i64, segments: &[IdleSegment]) -> StorageResult<Vec<i64>>;
+    fn reapply_categorization_rules(&self) -> StorageResult<i64>;
+    fn get_last_rule_run_report(&self) -> StorageResult<Option<RuleRunReport>>;
+    fn undo_last_rule_run(&self) -> StorageResult<usize>;
+
+    // Categories
+    fn get_categories(&self) -> StorageResult<Vec<Category>>;
+    #[allow(clippy::too_many_arguments)]
+    fn create_category_core(
+        &self,
+        name: &str,
+        color: &str,
+        icon: Option<&str>,
+        is_productive: Option<bool>,
+        sort_order: i64,
+        is_system: bool,
+        is_pinned: bool,
+    ) -> StorageResult<i64>;
+    #[allow(clippy::too_many_arguments)]
+    fn update_category_core(
+        &self,
+        id: i64,
+        name: &str,
+        color: &str,
+        icon: Option<&str>,
+        is_productive: Option<bool>,
+        sort_order: i64,
+        is_pinned: bool,
+    ) -> StorageResult<()>;
+    fn set_category_exclude_from_export(&self, id: i64, exclude: bool) -> StorageResult<()>;
+    fn set_category_archived(&self, id: i64, archived: bool) -> StorageResult<()>;
+    fn reset_system_category(&self, id: i64) -> StorageResult<()>;
+    fn delete_category(&self, id: i64) -> StorageResult<()>;
+    fn suggest_category_color(&self) -> StorageResult<String>;
+
+    // Rules
+    fn get_rules(&self) -> StorageResult<Vec<Rule>>;
+    fn find_similar_rule(&self, rule_type: &str, pattern: &str, exclude_id: Option<i64>) -> StorageResult<Option<Rule>>;
+    fn add_rule(
+        &self,
+        rule_type: &str,
+        pattern: &str,
+        category_id: i64,
+        priority: i64,
+        project_id: Option<i64>,
+    ) -> StorageResult<i64>;
+    fn update_rule(
+        &self,
+        id: i64,
+        rule_type: &str,
+        pattern: &str,
+        category_id: i64,
+        priority: i64,
+        project_id: Option<i64>,
+    ) -> StorageResult<()>;
+    fn delete_rule(&self, id: i64) -> StorageResult<()>;
+    fn export_rule_pack(&self, name: Option<&str>, category_ids: Option<&[i64]>) -> StorageResult<RulePack>;
+    fn import_rule_pack(&self, pack: &RulePack, strategy: RuleImportStrategy) -> StorageResult<RuleImportSummary>;
+
+    // Manual entries
+    fn get_manual_entries(&self, start: i64, end: i64) -> StorageResult<Vec<ManualEntry>>;
+    fn add_manual_entry(
+        &self,
+        description: Option<&str>,
+        category_id: Option<i64>,
+        started_at: i64,
+        ended_at: i64,
+        source: &str,
+    ) -> StorageResult<i64>;
+    fn update_manual_entry(
+        &self,
+        id: i64,
+        description: Option<&str>,
+        category_id: Option<i64>,
+        started_at: i64,
+        ended_at: i64,
+    ) -> StorageResult<()>;
+    fn set_manual_entries_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> StorageResult<usize>;
+    fn delete_manual_entry(&self, id: i64) -> StorageResult<()>;
+    fn get_project_names(&self) -> StorageResult<Vec<(i64, String)>>;
+
+    // Reporting
+    fn get_stats_for_range(
+        &self,
+        start: i64,
+        end: i64,
+        sources: Option<&[String]>,
+        devices: Option<&[String]>,
+        exportable_only: bool,
+    ) -> StorageResult<RangeStats>;
+    fn get_daily_stats(&self, date: i64) -> StorageResult<DailyStats>;
+    fn get_top_apps(&self, start: i64, end: i64, limit: i64) -> StorageResult<Vec<AppStat>>;
+    fn get_top_apps_decayed(&self, start: i64, end: i64, limit: i64, half_life_days: f64) -> StorageResult<Vec<AppStat>>;
+    fn get_category_usage(&self, start: i64, end: i64) -> StorageResult<Vec<CategoryUsageStat>>;
+    fn get_hourly_activity(&self, date: i64) -> StorageResult<Vec<HourlyStat>>;
+    fn get_productive_time(&self, start: i64, end: i64) -> StorageResult<i64>;
+    fn get_coding_stats(&self, start: i64, end: i64) -> StorageResult<CodingStats>;
+    fn get_input_intensity(&self, start: i64, end: i64) -> StorageResult<Vec<InputIntensityStat>>;
+    fn get_mood_summary(&self, start: i64, end: i64) -> StorageResult<MoodSummary>;
+    fn get_devices(&self) -> StorageResult<Vec<DeviceSummary>>;
+}
+
+/// SQLite, the default backend
+impl Storage for Database {
+    fn get_activities(
+        &self,
+        start: i64,
+        end: i64,
+        limit: Option<i64>,
+        offset: Option<i64>,
+        exclude_idle: Option<bool>,
+        category_ids: Option<&[i64]>,
+        sources: Option<&[String]>,
+    ) -> StorageResult<Vec<Activity>> {
+        Ok(Database::get_activities(self, start, end, limit, offset, exclude_idle, category_ids, sources)?)
+    }
+
+    fn get_activities_page(
+        &self,
+        start: i64,
+        end: i64,
+        after: Option<ActivityCursor>,
+        limit: i64,
+        sources: Option<&[String]>,
+    ) -> StorageResult<(Vec<Activity>, Option<ActivityCursor>)> {
+        Ok(Database::get_activities_page(self, start, end, after, limit, sources)?)
+    }
+
+    fn get_activity_by_id(&self, id: i64) -> StorageResult<Option<Activity>> {
+        Ok(Database::get_activity_by_id(self, id)?)
+    }
+
+    fn update_activity_category(&self, id: i64, category_id: Option<i64>) -> StorageResult<()> {
+        Ok(Database::update_activity_category(self, id, category_id)?)
+    }
+
+    fn set_activities_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> StorageResult<usize> {
+        Ok(Database::set_activities_billable_override(self, ids, billable_override)?)
+    }
+
+    fn delete_activity(&self, id: i64) -> StorageResult<()> {
+        Ok(Database::delete_activity(self, id)?)
+    }
+
+    fn record_category_correction(&self, activity_id: i64, category_id: i64) -> StorageResult<()> {
+        Ok(Database::record_category_correction(self, activity_id, category_id)?)
+    }
+
+    fn clear_category_correction(&self, activity_id: i64) -> StorageResult<()> {
+        Ok(Database::clear_category_correction(self, activity_id)?)
+    }
+
+    fn get_idle_duration(&self, started_at: i64) -> StorageResult<Option<i64>> {
+        Ok(Database::get_idle_duration(self, started_at)?)
+    }
+
+    fn split_idle_activity(&self, started_at: i64, segments: &[IdleSegment]) -> StorageResult<Vec<i64>> {
+        Ok(Database::split_idle_activity(self, started_at, segments)?)
+    }
+
+    fn reapply_categorization_rules(&self) -> StorageResult<i64> {
+        Ok(Database::reapply_categorization_rules(self)?)
+    }
+
+    fn get_last_rule_run_report(&self) -> StorageResult<Option<RuleRunReport>> {
+        Ok(Database::get_last_rule_run_report(self)?)
+    }
+
+    fn undo_last_rule_run(&self) -> StorageResult<usize> {
+        Ok(Database::undo_last_rule_run(self)?)
+    }
+
+    fn get_categories(&self) -> StorageResult<Vec<Category>> {
+        Ok(Database::get_categories(self)?)
+    }
+
+    fn create_category_core(
+        &self,
+        name: &str,
+        color: &str,
+        icon: Option<&str>,
+        is_productive: Option<bool>,
+        sort_order: i64,
+        is_system: bool,
+        is_pinned: bool,
+    ) -> StorageResult<i64> {
+        Ok(Database::create_category_core(self, name, color, icon, is_productive, sort_order, is_system, is_pinned)?)
+    }
+
+    fn update_category_core(
+        &self,
+        id: i64,
+        name: &str,
+        color: &str,
+        icon: Option<&str>,
+        is_productive: Option<bool>,
+        sort_order: i64,
+        is_pinned: bool,
+    ) -> StorageResult<()> {
+        Ok(Database::update_category_core(self, id, name, color, icon, is_productive, sort_order, is_pinned)?)
+    }
+
+    fn set_category_exclude_from_export(&self, id: i64, exclude: bool) -> StorageResult<()> {
+        Ok(Database::set_category_exclude_from_export(self, id, exclude)?)
+    }
+
+    fn set_category_archived(&self, id: i64, archived: bool) -> StorageResult<()> {
+        Ok(Database::set_category_archived(self, id, archived)?)
+    }
+
+    fn reset_system_category(&self, id: i64) -> StorageResult<()> {
+        Ok(Database::reset_system_category(self, id)?)
+    }
+
+    fn delete_category(&self, id: i64) -> StorageResult<()> {
+        Ok(Database::delete_category(self, id)?)
+    }
+
+    fn suggest_category_color(&self) -> StorageResult<String> {
+        Ok(Database::suggest_category_color(self)?)
+    }
+
+    fn get_rules(&self) -> StorageResult<Vec<Rule>> {
+        Ok(Database::get_rules(self)?)
+    }
+
+    fn find_similar_rule(&self, rule_type: &str, pattern: &str, exclude_id: Option<i64>) -> StorageResult<Option<Rule>> {
+        Ok(Database::find_similar_rule(self, rule_type, pattern, exclude_id)?)
+    }
+
+    fn add_rule(
+        &self,
+        rule_type: &str,
+        pattern: &str,
+        category_id: i64,
+        priority: i64,
+        project_id: Option<i64>,
+    ) -> StorageResult<i64> {
+        Ok(Database::add_rule(self, rule_type, pattern, category_id, priority, project_id)?)
+    }
+
+    fn update_rule(
+        &self,
+        id: i64,
+        rule_type: &str,
+        pattern: &str,
+        category_id: i64,
+        priority: i64,
+        project_id: Option<i64>,
+    ) -> StorageResult<()> {
+        Ok(Database::update_rule(self, id, rule_type, pattern, category_id, priority, project_id)?)
+    }
+
+    fn delete_rule(&self, id: i64) -> StorageResult<()> {
+        Ok(Database::delete_rule(self, id)?)
+    }
+
+    fn export_rule_pack(&self, name: Option<&str>, category_ids: Option<&[i64]>) -> StorageResult<RulePack> {
+        Ok(Database::export_rule_pack(self, name, category_ids)?)
+    }
+
+    fn import_rule_pack(&self, pack: &RulePack, strategy: RuleImportStrategy) -> StorageResult<RuleImportSummary> {
+        Ok(Database::import_rule_pack(self, pack, strategy)?)
+    }
+
+    fn get_manual_entries(&self, start: i64, end: i64) -> StorageResult<Vec<ManualEntry>> {
+        Ok(Database::get_manual_entries(self, start, end)?)
+    }
+
+    fn add_manual_entry(
+        &self,
+        description: Option<&str>,
+        category_id: Option<i64>,
+        started_at: i64,
+        ended_at: i64,
+        source: &str,
+    ) -> StorageResult<i64> {
+        Ok(Database::add_manual_entry(self, description, category_id, started_at, ended_at, source)?)
+    }
+
+    fn update_manual_entry(
+        &self,
+        id: i64,
+        description: Option<&str>,
+        category_id: Option<i64>,
+        started_at: i64,
+        ended_at: i64,
+    ) -> StorageResult<()> {
+        Ok(Database::update_manual_entry(self, id, description, category_id, started_at, ended_at)?)
+    }
+
+    fn set_manual_entries_billable_override(&self, ids: &[i64], billable_override: Option<bool>) -> StorageResult<usize> {
+        Ok(Database::set_manual_entries_billable_override(self, ids, billable_override)?)
+    }
+
+    fn delete_manual_entry(&self, id: i64) -> StorageResult<()> {
+        Ok(Database::delete_manual_entry(self, id)?)
+    }
+
+    fn get_project_names(&self) -> StorageResult<Vec<(i64, String)>> {
+        Ok(Database::get_project_names(self)?)
+    }
+
+    fn get_stats_for_range(
+        &self,
+        start: i64,
+        end: i64,
+        sources: Option<&[String]>,
+        devices: Option<&[String]>,
+        exportable_only: bool,
+    ) -> StorageResult<RangeStats> {
+        Ok(Database::get_stats_for_range(self, start, end, sources, devices, exportable_only)?)
+    }
+
+    fn get_daily_stats(&self, date: i64) -> StorageResult<DailyStats> {
+        Ok(Database::get_daily_stats(self, date)?)
+    }
+
+    fn get_top_apps(&self, start: i64, end: i64, limit: i64) -> StorageResult<Vec<AppStat>> {
+        Ok(Database::get_top_apps(self, start, end, limit)?)
+    }
+
+    fn get_top_apps_decayed(&self, start: i64, end: i64, limit: i64, half_life_days: f64) -> StorageResult<Vec<AppStat>> {
+        Ok(Database::get_top_apps_decayed(self, start, end, limit, half_life_days)?)
+    }
+
+    fn get_category_usage(&self, start: i64, end: i64) -> StorageResult<Vec<CategoryUsageStat>> {
+        Ok(Database::get_category_usage(self, start, end)?)
+    }
+
+    fn get_hourly_activity(&self, date: i64) -> StorageResult<Vec<HourlyStat>> {
+        Ok(Database::get_hourly_activity(self, date)?)
+    }
+
+    fn get_productive_time(&self, start: i64, end: i64) -> StorageResult<i64> {
+        Ok(Database::get_productive_time(self, start, end)?)
+    }
+
+    fn get_coding_stats(&self, start: i64, end: i64) -> StorageResult<CodingStats> {
+        Ok(Database::get_coding_stats(self, start, end)?)
+    }
+
+    fn get_input_intensity(&self, start: i64, end: i64) -> StorageResult<Vec<InputIntensityStat>> {
+        Ok(Database::get_input_intensity(self, start, end)?)
+    }
+
+    fn get_mood_summary(&self, start: i64, end: i64) -> StorageResult<MoodSummary> {
+        Ok(Database::get_mood_summary(self, start, end)?)
+    }
+
+    fn get_devices(&self) -> StorageResult<Vec<DeviceSummary>> {
+        Ok(Database::get_devices(self)?)
+    }
+}
@@ -0,0 +1,122 @@
+//! Category suggestion data: manual corrections used as training samples
+
+use rusqlite::{Result, params};
+use super::common::{Database, SYSTEM_CATEGORY_UNCATEGORIZED};
+use super::models::{Activity, UncategorizedGroup};
+
+/// Group of an activity in `get_uncategorized_summary`: its domain, else its app
+const GROUP_KIND_SQL: &str = "CASE WHEN COALESCE(domain, '') = '' THEN 'app' ELSE 'domain' END";
+const GROUP_NAME_SQL: &str = "COALESCE(NULLIF(domain, ''), app_name)";
+
+/// Training sample: (app_name, window_title, domain, category_id)
+pub type TrainingSample = (String, Option<String>, Option<String>, i64);
+
+impl Database {
+    /// Record that the user manually assigned a category to an activity
+    pub fn record_category_correction(&self, activity_id: i64, category_id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO category_corrections (activity_id, category_id, corrected_at)
+             VALUES (?, ?, ?)",
+            params![activity_id, category_id, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a recorded correction (e.g. the activity was set back to uncategorized)
+    pub fn clear_category_correction(&self, activity_id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM category_corrections WHERE activity_id = ?",
+            params![activity_id],
+        )?;
+        Ok(())
+    }
+
+    /// Get manually corrected activities with the category the user chose
+    pub fn get_category_training_samples(&self) -> Result<Vec<TrainingSample>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT a.app_name, a.window_title, a.domain, cc.category_id
+             FROM category_corrections cc
+             JOIN activities a ON a.id = cc.activity_id
+             JOIN categories c ON c.id = cc.category_id",
+        )?;
+
+        let samples = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(samples)
+    }
+
+    /// Get non-idle uncategorized activities for a time range
+    pub fn get_uncategorized_activities(&self, start: i64, end: i64) -> Result<Vec<Activity>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, app_name, window_title, domain, category_id, started_at, duration_sec, is_idle, source, billable_override, context_ref, device_id, device_name
+             FROM activities
+             WHERE started_at >= ? AND started_at <= ? AND is_idle = FALSE
+               AND (category_id IS NULL OR category_id = ?)
+             ORDER BY started_at ASC",
+        )?;
+
+        let activities = stmt
+            .query_map(params![start, end, SYSTEM_CATEGORY_UNCATEGORIZED], |row| {
+                Ok(Activity {
+                    id: row.get(0)?,
+                    app_name: row.get(1)?,
+                    window_title: row.get(2)?,
+                    domain: row.get(3)?,
+                    category_id: row.get(4)?,
+                    started_at: row.get(5)?,
+                    duration_sec: row.get(6)?,
+                    is_idle: row.get(7)?,
+                    source: row.get(8)?,
+                    billable_override: row.get(9)?,
+                    context_ref: row.get(10)?,
+                    device_id: row.get(11)?,
+                    device_name: row.get(12)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(activities)
+    }
+
+    /// Get non-idle uncategorized time for a time range grouped by domain
+    /// (activities with one) or app, largest first
+    pub fn get_uncategorized_summary(&self, start: i64, end: i64) -> Result<Vec<UncategorizedGroup>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {GROUP_KIND_SQL} AS kind, {GROUP_NAME_SQL} AS name, COUNT(*), SUM(duration_sec) AS duration_sec
+             FROM activities
+             WHERE started_at >= ? AND started_at <= ? AND is_idle = FALSE
+               AND (category_id IS NULL OR category_id = ?)
+             GROUP BY kind, name
+             ORDER BY duration_sec DESC"
+        ))?;
+        let groups = stmt.query_map(params![start, end, SYSTEM_CATEGORY_UNCATEGORIZED], |row| {
+            Ok(UncategorizedGroup {
+                kind: row.get(0)?,
+                name: row.get(1)?,
+                activity_count: row.get(2)?,
+                duration_sec: row.get(3)?,
+            })
+        })?;
+        groups.collect()
+    }
+
+    /// IDs of all non-idle uncategorized activities of a group from
+    /// `get_uncategorized_summary` (`kind` `app` or `domain`)
+    pub fn get_uncategorized_group_ids(&self, kind: &str, name: &str) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT id FROM activities
+             WHERE {GROUP_KIND_SQL} = ? AND {GROUP_NAME_SQL} = ? AND is_idle = FALSE
+               AND (category_id IS NULL OR category_id = ?)"
+        ))?;
+        let ids = stmt.query_map(params![kind, name, SYSTEM_CATEGORY_UNCATEGORIZED], |row| row.get(0))?;
+        ids.collect()
+    }
+}
@@ -0,0 +1,129 @@
+//! Globally unique IDs for synced records
+//!
+//! Rows keep their integer `id` (foreign keys, plugins and the frontend use
+//! it), but integer IDs collide once two devices create records independently.
+//! `sync_ids` maps each synced row to a UUID that is the same on every device:
+//! triggers give new rows one on insert and mark it deleted (a tombstone for
+//! sync) on delete, and `ensure_sync_ids` backfills rows created before the
+//! table existed. Plugin tables (projects, tasks, goals) are covered as soon as
+//! their plugin creates them.
+
+use super::common::{Database, OptionalExtension};
+use rusqlite::{params, Connection, Result};
+
+/// Synced entity types and the tables they live in
+pub const SYNC_ENTITIES: &[(&str, &str)] = &[
+    ("manual_entry", "manual_entries"),
+    ("project", "projects"),
+    ("task", "tasks"),
+    ("goal", "goals"),
+];
+
+/// SQL expression for a random (version 4) UUID
+const UUID_SQL: &str = "lower(hex(randomblob(4)) || '-' || hex(randomblob(2)) || '-4' || substr(hex(randomblob(2)), 2) || '-'
+    || substr('89ab', 1 + (abs(random()) % 4), 1) || substr(hex(randomblob(2)), 2) || '-' || hex(randomblob(6)))";
+
+/// Table for `entity_type`, if it is synced
+pub(crate) fn sync_table(entity_type: &str) -> Option<&'static str> {
+    SYNC_ENTITIES
+        .iter()
+        .find(|(entity, _)| *entity == entity_type)
+        .map(|(_, table)| *table)
+}
+
+impl Database {
+    /// Install the `sync_ids` triggers on every synced table that exists and
+    /// give existing rows without a UUID one. Safe to run repeatedly.
+    pub(crate) fn ensure_sync_ids(conn: &Connection) -> Result<()> {
+        for (entity, table) in SYNC_ENTITIES {
+            if !Self::column_exists(conn, table, "id") {
+                continue;
+            }
+            conn.execute_batch(&format!(
+                "CREATE TRIGGER IF NOT EXISTS sync_ids_{table}_insert AFTER INSERT ON {table}
+                 BEGIN
+                     INSERT OR IGNORE INTO sync_ids (entity_type, local_id, uuid) VALUES ('{entity}', NEW.id, {UUID_SQL});
+                 END;
+                 CREATE TRIGGER IF NOT EXISTS sync_ids_{table}_delete AFTER DELETE ON {table}
+                 BEGIN
+                     UPDATE sync_ids SET deleted_at = CAST(strftime('%s', 'now') AS INTEGER)
+                     WHERE entity_type = '{entity}' AND local_id = OLD.id;
+                 END;"
+            ))?;
+            conn.execute(
+                &format!(
+                    "INSERT INTO sync_ids (entity_type, local_id, uuid)
+                     SELECT ?1, id, {UUID_SQL} FROM {table}
+                     WHERE id NOT IN (SELECT local_id FROM sync_ids WHERE entity_type = ?1)"
+                ),
+                params![entity],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// UUID of a local row
+    pub fn get_sync_id(&self, entity_type: &str, local_id: i64) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT uuid FROM sync_ids WHERE entity_type = ? AND local_id = ?",
+            params![entity_type, local_id],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Local ID of the row with `uuid`, if it exists here and was not deleted
+    pub fn find_by_sync_id(&self, entity_type: &str, uuid: &str) -> Result<Option<i64>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT local_id FROM sync_ids WHERE entity_type = ? AND uuid = ? AND deleted_at IS NULL",
+            params![entity_type, uuid.to_lowercase()],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// Give a local row the UUID it has elsewhere (a record received from
+    /// another device), replacing the one generated on insert
+    pub fn set_sync_id(&self, entity_type: &str, local_id: i64, uuid: &str) -> Result<()> {
+        if sync_table(entity_type).is_none() {
+            return Err(rusqlite::Error::InvalidParameterName(format!("Unknown sync entity: {}", entity_type)));
+        }
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_ids (entity_type, local_id, uuid, deleted_at) VALUES (?, ?, ?, NULL)",
+            params![entity_type, local_id, uuid.to_lowercase()],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::common::SOURCE_MANUAL;
+    use std::path::PathBuf;
+
+    #[test]
+    fn manual_entries_get_stable_uuids() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let first = db.add_manual_entry(Some("Standup"), None, 100, 200, SOURCE_MANUAL).unwrap();
+        let second = db.add_manual_entry(Some("Lunch"), None, 300, 400, SOURCE_MANUAL).unwrap();
+        let uuid = db.get_sync_id("manual_entry", first).unwrap().unwrap();
+        assert_eq!((uuid.len(), &uuid[14..15]), (36, "4"));
+        assert_ne!(Some(&uuid), db.get_sync_id("manual_entry", second).unwrap().as_ref());
+        assert_eq!(db.find_by_sync_id("manual_entry", &uuid).unwrap(), Some(first));
+
+        // A record received from another device keeps its UUID
+        let remote = "6f1c2a9e-5b7d-4c3e-9a8b-1d2e3f4a5b6c";
+        db.set_sync_id("manual_entry", second, remote).unwrap();
+        assert_eq!(db.find_by_sync_id("manual_entry", remote).unwrap(), Some(second));
+
+        // Deleted rows leave a tombstone instead of a reusable mapping
+        db.delete_manual_entry(first).unwrap();
+        assert_eq!(db.find_by_sync_id("manual_entry", &uuid).unwrap(), None);
+        assert_eq!(db.get_sync_id("manual_entry", first).unwrap(), Some(uuid));
+        assert!(db.set_sync_id("activity", first, remote).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Task hint operations
+//!
+//! A task hint maps a keyword, usually a ticket number like `PROJ-123`, to a
+//! task of the projects plugin. Activities whose window title mentions the
+//! keyword are attached to the task (`activities.task_id`).
+
+use rusqlite::{Connection, Result, params};
+use super::activities::fold_case;
+use super::common::Database;
+use super::models::TaskHint;
+
+/// Byte offset of `keyword` in `title` as a whole word (ignoring case), so
+/// `PROJ-12` is not found in `PROJ-123`
+fn find_word(title: &str, keyword: &str) -> Option<usize> {
+    let (title, keyword) = (fold_case(title), fold_case(keyword));
+    if keyword.is_empty() {
+        return None;
+    }
+    let is_word_char = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    title.match_indices(&keyword).map(|(i, _)| i).find(|&i| {
+        !is_word_char(title[..i].chars().next_back()) && !is_word_char(title[i + keyword.len()..].chars().next())
+    })
+}
+
+/// Hint for a window title. When several match, the longest keyword wins (it
+/// is the more specific one), then the one mentioned first, then the oldest.
+pub fn pick_task_hint<'a>(hints: &'a [TaskHint], title: &str) -> Option<&'a TaskHint> {
+    hints
+        .iter()
+        .filter_map(|hint| find_word(title, &hint.keyword).map(|position| (hint, position)))
+        .min_by_key(|(hint, position)| (std::cmp::Reverse(hint.keyword.chars().count()), *position, hint.id))
+        .map(|(hint, _)| hint)
+}
+
+fn load_hints(conn: &Connection) -> Result<Vec<TaskHint>> {
+    let mut stmt = conn.prepare("SELECT id, keyword, task_id, created_at FROM task_hints ORDER BY keyword")?;
+    let hints = stmt
+        .query_map([], |row| {
+            Ok(TaskHint {
+                id: row.get(0)?,
+                keyword: row.get(1)?,
+                task_id: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(hints)
+}
+
+impl Database {
+    /// Get all task hints
+    pub fn get_task_hints(&self) -> Result<Vec<TaskHint>> {
+        let conn = self.conn.lock().unwrap();
+        load_hints(&conn)
+    }
+
+    /// Task for a window title from the hints (see `pick_task_hint`); `None`
+    /// until the projects plugin has added `activities.task_id`
+    pub(crate) fn find_task_for_title(conn: &Connection, title: &str) -> Result<Option<i64>> {
+        if !Self::column_exists(conn, "activities", "task_id") {
+            return Ok(None);
+        }
+        Ok(pick_task_hint(&load_hints(conn)?, title).map(|hint| hint.task_id))
+    }
+
+    /// Map `keyword` to `task_id`; an existing hint for the keyword (ignoring
+    /// case) is pointed at the new task. Returns the hint ID.
+    pub fn add_task_hint(&self, keyword: &str, task_id: i64) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO task_hints (keyword, task_id, created_at) VALUES (?, ?, ?)
+             ON CONFLICT (keyword) DO UPDATE SET task_id = excluded.task_id",
+            params![keyword, task_id, chrono::Utc::now().timestamp()],
+        )?;
+        conn.query_row("SELECT id FROM task_hints WHERE keyword = ?", params![keyword], |row| row.get(0))
+    }
+
+    /// Delete a task hint
+    pub fn delete_task_hint(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM task_hints WHERE id = ?", params![id])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint(id: i64, keyword: &str, task_id: i64) -> TaskHint {
+        TaskHint { id, keyword: keyword.to_string(), task_id, created_at: 0 }
+    }
+
+    #[test]
+    fn most_specific_hint_wins() {
+        let hints = [hint(1, "PROJ-12", 1), hint(2, "PROJ-123", 2), hint(3, "web", 3), hint(4, "api", 4)];
+        let task = |title: &str| pick_task_hint(&hints, title).map(|h| h.task_id);
+
+        assert_eq!(task("proj-123: fix login - Jira"), Some(2));
+        assert_eq!(task("PROJ-12 review"), Some(1));
+        assert_eq!(task("PROJ-1234"), None);
+        assert_eq!(task("api and web"), Some(4));
+        assert_eq!(task("website"), None);
+    }
+}
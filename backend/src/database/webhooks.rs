@@ -0,0 +1,208 @@
+//! Webhook database operations (endpoints and delivery queue)
+
+use rusqlite::{Result, params};
+use super::common::Database;
+use super::models::{Webhook, WebhookDelivery};
+
+pub const DELIVERY_STATUS_PENDING: &str = "pending";
+pub const DELIVERY_STATUS_DELIVERED: &str = "delivered";
+pub const DELIVERY_STATUS_FAILED: &str = "failed";
+
+fn map_webhook_row(row: &rusqlite::Row) -> Result<Webhook> {
+    let events: String = row.get(3)?;
+    Ok(Webhook {
+        id: row.get(0)?,
+        url: row.get(1)?,
+        secret: row.get(2)?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        enabled: row.get(4)?,
+        created_at: row.get(5)?,
+    })
+}
+
+fn map_delivery_row(row: &rusqlite::Row) -> Result<WebhookDelivery> {
+    Ok(WebhookDelivery {
+        id: row.get(0)?,
+        webhook_id: row.get(1)?,
+        event: row.get(2)?,
+        payload: row.get(3)?,
+        status: row.get(4)?,
+        attempts: row.get(5)?,
+        next_attempt_at: row.get(6)?,
+        last_error: row.get(7)?,
+        created_at: row.get(8)?,
+        delivered_at: row.get(9)?,
+    })
+}
+
+const DELIVERY_COLUMNS: &str =
+    "id, webhook_id, event, payload, status, attempts, next_attempt_at, last_error, created_at, delivered_at";
+
+impl Database {
+    /// Get all webhooks
+    pub fn get_webhooks(&self) -> Result<Vec<Webhook>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, url, secret, events, enabled, created_at FROM webhooks ORDER BY id",
+        )?;
+        let webhooks = stmt.query_map([], map_webhook_row)?.collect::<Result<Vec<_>>>()?;
+        Ok(webhooks)
+    }
+
+    /// Get webhook by ID
+    pub fn get_webhook(&self, id: i64) -> Result<Option<Webhook>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT id, url, secret, events, enabled, created_at FROM webhooks WHERE id = ?",
+            params![id],
+            map_webhook_row,
+        )
+        .optional()
+    }
+
+    /// Create a webhook
+    pub fn create_webhook(&self, url: &str, secret: Option<&str>, events: &[String]) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let events_json = serde_json::to_string(events).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "INSERT INTO webhooks (url, secret, events, enabled, created_at) VALUES (?, ?, ?, TRUE, ?)",
+            params![url, secret, events_json, chrono::Utc::now().timestamp()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Update a webhook
+    pub fn update_webhook(
+        &self,
+        id: i64,
+        url: &str,
+        secret: Option<&str>,
+        events: &[String],
+        enabled: bool,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let events_json = serde_json::to_string(events).unwrap_or_else(|_| "[]".to_string());
+        conn.execute(
+            "UPDATE webhooks SET url = ?, secret = ?, events = ?, enabled = ? WHERE id = ?",
+            params![url, secret, events_json, enabled, id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a webhook and its delivery history
+    pub fn delete_webhook(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let tx = conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM webhook_deliveries WHERE webhook_id = ?", params![id])?;
+        tx.execute("DELETE FROM webhooks WHERE id = ?", params![id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Queue a delivery for immediate sending
+    pub fn enqueue_webhook_delivery(&self, webhook_id: i64, event: &str, payload: &str) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO webhook_deliveries (webhook_id, event, payload, status, attempts, next_attempt_at, created_at)
+             VALUES (?, ?, ?, 'pending', 0, ?, ?)",
+            params![webhook_id, event, payload, now, now],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Get pending deliveries whose next attempt is due
+    pub fn get_due_webhook_deliveries(&self, now: i64, limit: i64) -> Result<Vec<WebhookDelivery>> {
+        let conn = self.conn.lock().unwrap();
+        let query = format!(
+            "SELECT {} FROM webhook_deliveries
+             WHERE status = 'pending' AND next_attempt_at <= ?
+             ORDER BY next_attempt_at ASC LIMIT ?",
+            DELIVERY_COLUMNS
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let deliveries = stmt
+            .query_map(params![now, limit], map_delivery_row)?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(deliveries)
+    }
+
+    /// Get recent deliveries, optionally for a single webhook
+    pub fn get_webhook_deliveries(&self, webhook_id: Option<i64>, limit: i64) -> Result<Vec<WebhookDelivery>> {
+        let conn = self.conn.lock().unwrap();
+        let query = format!(
+            "SELECT {} FROM webhook_deliveries
+             WHERE ?1 IS NULL OR webhook_id = ?1
+             ORDER BY created_at DESC, id DESC LIMIT ?2",
+            DELIVERY_COLUMNS
+        );
+        let mut stmt = conn.prepare(&query)?;
+        let deliveries = stmt
+            .query_map(params![webhook_id, limit], map_delivery_row)?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(deliveries)
+    }
+
+    /// Record the outcome of a delivery attempt.
+    /// `next_attempt_at` is used only while the delivery stays pending.
+    pub fn record_webhook_attempt(
+        &self,
+        id: i64,
+        status: &str,
+        error: Option<&str>,
+        next_attempt_at: i64,
+    ) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        let delivered_at = (status == DELIVERY_STATUS_DELIVERED).then_some(now);
+        conn.execute(
+            "UPDATE webhook_deliveries
+             SET status = ?, attempts = attempts + 1, last_error = ?, next_attempt_at = ?, delivered_at = ?
+             WHERE id = ?",
+            params![status, error, next_attempt_at, delivered_at, id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete delivered and failed deliveries created before `before`; pending ones are kept.
+    /// Returns the number deleted.
+    pub fn delete_finished_webhook_deliveries(&self, before: i64) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM webhook_deliveries WHERE status IN (?, ?) AND created_at < ?",
+            params![DELIVERY_STATUS_DELIVERED, DELIVERY_STATUS_FAILED, before],
+        )
+    }
+}
+
+use super::common::OptionalExtension;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn only_old_finished_deliveries_are_pruned() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let webhook = db.create_webhook("https://example.com/hook", None, &["*".to_string()]).unwrap();
+        let enqueue = |status: &str, created_at: i64| {
+            let id = db.enqueue_webhook_delivery(webhook, "activity.recorded", "{}").unwrap();
+            db.record_webhook_attempt(id, status, None, created_at).unwrap();
+            db.conn.lock().unwrap()
+                .execute("UPDATE webhook_deliveries SET created_at = ? WHERE id = ?", params![created_at, id])
+                .unwrap();
+            id
+        };
+        let old_delivered = enqueue(DELIVERY_STATUS_DELIVERED, 1_000);
+        let old_failed = enqueue(DELIVERY_STATUS_FAILED, 1_000);
+        let old_pending = enqueue(DELIVERY_STATUS_PENDING, 1_000);
+        let recent_delivered = enqueue(DELIVERY_STATUS_DELIVERED, 5_000);
+
+        assert_eq!(db.delete_finished_webhook_deliveries(2_000).unwrap(), 2);
+        let mut remaining: Vec<i64> = db.get_webhook_deliveries(None, 10).unwrap().iter().map(|d| d.id).collect();
+        remaining.sort();
+        assert_eq!(remaining, vec![old_pending, recent_delivered]);
+        assert!(![old_delivered, old_failed].iter().any(|id| remaining.contains(id)));
+    }
+}
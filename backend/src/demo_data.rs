@@ -0,0 +1,251 @@
+//! Demo data
+//!
+//! `generate` makes up a plausible tracking history for screenshots, demos and
+//! frontend work, so nobody has to show their real (private) history. Each
+//! profile has its own mix of apps, window titles and websites, chosen to hit
+//! the default rules so the categories look realistic. Workdays run from
+//! about 9:00 to 17:30 with a lunch break and a daily meeting recorded as
+//! manual entries; weekends get an occasional short session.
+//!
+//! Output only depends on the days, the profile and the seed, so the same
+//! request produces the same data.
+
+use serde::{Deserialize, Serialize};
+
+/// Longest stored activity row; the tracker splits long sessions the same way
+const MAX_ROW_SECS: i64 = 300;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DemoProfile {
+    Developer,
+    Designer,
+    Manager,
+}
+
+/// One app (or website) a profile uses, with its share of the day
+struct DemoApp {
+    app_name: &'static str,
+    titles: &'static [&'static str],
+    domain: Option<&'static str>,
+    weight: u32,
+}
+
+const fn app(app_name: &'static str, titles: &'static [&'static str], domain: Option<&'static str>, weight: u32) -> DemoApp {
+    DemoApp { app_name, titles, domain, weight }
+}
+
+const DEVELOPER_APPS: &[DemoApp] = &[
+    app("Code", &["main.rs - tracker - Visual Studio Code", "lib.rs - tracker - Visual Studio Code", "README.md - tracker - Visual Studio Code", "App.tsx - dashboard - Visual Studio Code"], None, 40),
+    app("Terminal", &["cargo test - tracker", "npm run dev - dashboard", "git log - tracker"], None, 12),
+    app("Google Chrome", &["Pull request #214 - GitHub", "Issues - GitHub"], Some("github.com"), 10),
+    app("Google Chrome", &["How to share state between threads - Stack Overflow"], Some("stackoverflow.com"), 5),
+    app("Google Chrome", &["std::sync - Rust", "tokio - Rust"], Some("docs.rs"), 6),
+    app("Slack", &["#engineering - Acme", "#general - Acme", "Direct message - Acme"], None, 12),
+    app("Google Chrome", &["Conference talk - YouTube"], Some("youtube.com"), 3),
+];
+
+const DESIGNER_APPS: &[DemoApp] = &[
+    app("Figma", &["Onboarding flow - Figma", "Design system - Figma", "Dashboard redesign - Figma"], None, 45),
+    app("Google Chrome", &["Shots - Dribbble", "Mobile onboarding - Dribbble"], Some("dribbble.com"), 8),
+    app("Google Chrome", &["Usability notes - Google Docs"], Some("docs.google.com"), 8),
+    app("Slack", &["#design - Acme", "#product - Acme", "Direct message - Acme"], None, 14),
+    app("Preview", &["moodboard.png", "export@2x.png"], None, 6),
+    app("Google Chrome", &["Design talk - YouTube"], Some("youtube.com"), 4),
+];
+
+const MANAGER_APPS: &[DemoApp] = &[
+    app("Microsoft Teams", &["Chat - Microsoft Teams", "Planning - Microsoft Teams"], None, 18),
+    app("Slack", &["#leads - Acme", "#general - Acme", "Direct message - Acme"], None, 16),
+    app("Google Chrome", &["Inbox - Gmail"], Some("mail.google.com"), 14),
+    app("Google Chrome", &["Q3 roadmap - Google Docs", "Hiring plan - Google Docs"], Some("docs.google.com"), 14),
+    app("Microsoft Excel", &["Budget 2024.xlsx - Excel", "Headcount.xlsx - Excel"], None, 10),
+    app("Zoom", &["1:1 - Zoom Meeting", "Vendor call - Zoom Meeting"], None, 12),
+];
+
+/// Activity as it would have been recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoActivity {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub domain: Option<String>,
+    pub started_at: i64,
+    pub duration_sec: i64,
+}
+
+/// Time away from the computer, logged by hand
+#[derive(Debug, Clone, PartialEq)]
+pub struct DemoManualEntry {
+    pub description: String,
+    /// Name of the category to file it under, if it exists
+    pub category: &'static str,
+    pub started_at: i64,
+    pub ended_at: i64,
+}
+
+/// A generated history
+#[derive(Debug, Clone, Default)]
+pub struct DemoData {
+    pub activities: Vec<DemoActivity>,
+    pub manual_entries: Vec<DemoManualEntry>,
+}
+
+/// A day to fill
+#[derive(Debug, Clone, Copy)]
+pub struct DemoDay {
+    /// Local midnight
+    pub start: i64,
+    pub weekend: bool,
+}
+
+/// Small xorshift generator; demo data needs repeatability, not quality
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn new(seed: u64) -> Self {
+        Self(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `low..=high`
+    fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low + 1) as u64) as i64
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.next() % 100 < percent
+    }
+}
+
+fn pick<'a>(rng: &mut DemoRng, apps: &'a [DemoApp]) -> &'a DemoApp {
+    let total: u32 = apps.iter().map(|a| a.weight).sum();
+    let mut roll = (rng.next() % total as u64) as u32;
+    for app in apps {
+        if roll < app.weight {
+            return app;
+        }
+        roll -= app.weight;
+    }
+    &apps[0]
+}
+
+/// Fill `start..end` with app sessions, split into rows like the tracker stores them
+fn fill(rng: &mut DemoRng, apps: &[DemoApp], start: i64, end: i64, activities: &mut Vec<DemoActivity>) {
+    let mut at = start;
+    while at < end {
+        let app = pick(rng, apps);
+        let title = app.titles[rng.range(0, app.titles.len() as i64 - 1) as usize];
+        let session_end = (at + rng.range(3, 40) * 60).min(end);
+        while at < session_end {
+            let duration_sec = (session_end - at).min(MAX_ROW_SECS);
+            activities.push(DemoActivity {
+                app_name: app.app_name.to_string(),
+                window_title: Some(title.to_string()),
+                domain: app.domain.map(str::to_string),
+                started_at: at,
+                duration_sec,
+            });
+            at += duration_sec;
+        }
+        // Short breaks between sessions are simply not tracked
+        if rng.chance(15) {
+            at += rng.range(2, 10) * 60;
+        }
+    }
+}
+
+/// Make up a history for `days`
+pub fn generate(profile: DemoProfile, days: &[DemoDay], seed: u64) -> DemoData {
+    let apps = match profile {
+        DemoProfile::Developer => DEVELOPER_APPS,
+        DemoProfile::Designer => DESIGNER_APPS,
+        DemoProfile::Manager => MANAGER_APPS,
+    };
+    let meeting = match profile {
+        DemoProfile::Developer => "Team standup",
+        DemoProfile::Designer => "Design critique",
+        DemoProfile::Manager => "Leadership sync",
+    };
+
+    let mut rng = DemoRng::new(seed);
+    let mut data = DemoData::default();
+    for day in days {
+        if day.weekend {
+            if rng.chance(30) {
+                let start = day.start + rng.range(10 * 60, 15 * 60) * 60;
+                let end = start + rng.range(30, 90) * 60;
+                fill(&mut rng, apps, start, end, &mut data.activities);
+            }
+            continue;
+        }
+
+        let start = day.start + 9 * 3600 + rng.range(-30, 30) * 60;
+        let meeting_start = day.start + 10 * 3600;
+        let meeting_end = meeting_start + rng.range(15, 30) * 60;
+        let lunch_start = day.start + 12 * 3600 + rng.range(0, 30) * 60;
+        let lunch_end = lunch_start + rng.range(30, 60) * 60;
+        let end = day.start + 17 * 3600 + rng.range(0, 60) * 60;
+
+        data.manual_entries.push(DemoManualEntry {
+            description: meeting.to_string(),
+            category: "Meetings",
+            started_at: meeting_start,
+            ended_at: meeting_end,
+        });
+        data.manual_entries.push(DemoManualEntry {
+            description: "Lunch".to_string(),
+            category: "Break",
+            started_at: lunch_start,
+            ended_at: lunch_end,
+        });
+        fill(&mut rng, apps, start, meeting_start, &mut data.activities);
+        fill(&mut rng, apps, meeting_end, lunch_start, &mut data.activities);
+        fill(&mut rng, apps, lunch_end, end, &mut data.activities);
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week() -> Vec<DemoDay> {
+        (0..7)
+            .map(|i| DemoDay {
+                start: 1_700_006_400 + i * 86400,
+                weekend: i >= 5,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn workdays_are_filled_without_overlaps() {
+        let data = generate(DemoProfile::Developer, &week(), 7);
+        assert_eq!(data.manual_entries.len(), 10);
+        let mut spans: Vec<(i64, i64)> = data
+            .activities
+            .iter()
+            .map(|a| (a.started_at, a.started_at + a.duration_sec))
+            .chain(data.manual_entries.iter().map(|e| (e.started_at, e.ended_at)))
+            .collect();
+        spans.sort();
+        assert!(spans.windows(2).all(|pair| pair[0].1 <= pair[1].0));
+        assert!(data.activities.iter().all(|a| a.duration_sec > 0 && a.duration_sec <= MAX_ROW_SECS));
+        // Roughly 6-8 tracked hours per workday
+        let tracked: i64 = data.activities.iter().map(|a| a.duration_sec).sum();
+        assert!((5 * 5 * 3600..5 * 9 * 3600).contains(&tracked), "{}", tracked);
+    }
+
+    #[test]
+    fn output_depends_only_on_the_seed() {
+        let first = generate(DemoProfile::Manager, &week(), 1);
+        assert_eq!(first.activities, generate(DemoProfile::Manager, &week(), 1).activities);
+        assert_ne!(first.activities, generate(DemoProfile::Manager, &week(), 2).activities);
+    }
+}
@@ -0,0 +1,60 @@
+//! Identity of this installation
+//!
+//! Each install gets a random device ID on first start. It is kept in
+//! `<data dir>/device.json` rather than in a database, because workspaces and
+//! restored backups move between machines and the ID must not move with them.
+//! Activities are stamped with the device they were recorded on, so data merged
+//! from several machines (sync, imports) can be told apart.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Machine activities are recorded on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Device {
+    /// Random per-install ID (a UUID)
+    pub id: String,
+    /// Host name, for display
+    pub name: String,
+}
+
+/// Name of this machine
+pub fn hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| {
+            let output = Command::new("hostname").output().ok()?;
+            Some(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "This computer".to_string())
+}
+
+/// Load this install's device from `path`, creating it on first start.
+/// The name follows the current host name; the ID never changes.
+pub fn load_or_create(path: &Path) -> Device {
+    let stored: Option<Device> = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok());
+    let device = Device {
+        id: stored
+            .as_ref()
+            .map_or_else(|| uuid::Uuid::new_v4().to_string(), |device| device.id.clone()),
+        name: hostname(),
+    };
+    if stored.as_ref() != Some(&device) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        let written = serde_json::to_string_pretty(&device)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = written {
+            eprintln!("Failed to save device identity: {}", e);
+        }
+    }
+    device
+}
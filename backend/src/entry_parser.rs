@@ -0,0 +1,278 @@
+//! Natural-language manual entry parsing
+//!
+//! Turns quick-capture text such as `1.5h yesterday ACME design review #billable`
+//! into a manual entry draft. Recognized tokens:
+//! - duration: `1.5h`, `90m`, `1h30m`, `1:30`
+//! - date: `today`, `yesterday`, weekday names (most recent past one), `YYYY-MM-DD`
+//! - start time: `at 14:00` / `@14:00`
+//! - tags: `#billable`
+//! - category: any category name appearing in the text
+//! - project: any project name (from the projects plugin) appearing in the rest
+//!
+//! Durations must be positive and at most `MAX_DURATION_SEC`.
+//! Everything else becomes the description.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveTime, TimeZone, Weekday};
+
+use crate::database::Category;
+
+/// Default start time for entries on past days without an explicit time
+const DEFAULT_START_HOUR: u32 = 9;
+
+/// Longest duration accepted for one entry (24 hours)
+const MAX_DURATION_SEC: i64 = 24 * 3600;
+
+/// Structured manual entry draft produced by `parse_time_entry`
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManualEntryDraft {
+    pub description: Option<String>,
+    pub category_id: Option<i64>,
+    /// Project of the projects plugin named in the text
+    pub project_id: Option<i64>,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration_sec: i64,
+    pub tags: Vec<String>,
+}
+
+/// Parse a duration token into seconds. Tokens that look like a duration but
+/// are not a usable one (`0m`, `-1h`, `infh`, `100h`) are an error rather than
+/// part of the description.
+fn parse_duration(token: &str) -> Option<Result<i64, String>> {
+    let token = token.to_lowercase();
+
+    let seconds = if let Some((h, m)) = token.split_once(':') {
+        let hours: f64 = h.parse::<u32>().ok()?.into();
+        let minutes: u32 = m.parse().ok()?;
+        if minutes >= 60 {
+            return None;
+        }
+        hours * 3600.0 + f64::from(minutes) * 60.0
+    } else if let Some((h, rest)) = token.split_once('h') {
+        let hours: f64 = h.parse().ok()?;
+        let minutes: f64 = match rest.strip_suffix('m') {
+            Some(m) => m.parse().ok()?,
+            None if rest.is_empty() => 0.0,
+            None => return None,
+        };
+        hours * 3600.0 + minutes * 60.0
+    } else {
+        let minutes = token.strip_suffix("min").or_else(|| token.strip_suffix('m'))?;
+        let minutes: f64 = minutes.parse().ok()?;
+        minutes * 60.0
+    };
+
+    Some(if !seconds.is_finite() || seconds.round() < 1.0 {
+        Err(format!("Invalid duration: {}", token))
+    } else if seconds.round() > MAX_DURATION_SEC as f64 {
+        Err(format!("Duration {} is longer than 24 hours", token))
+    } else {
+        Ok(seconds.round() as i64)
+    })
+}
+
+/// Remove the longest of `names` appearing as a run of words (ignoring case)
+/// from `words` and return its ID
+fn take_longest_name<'a>(words: &mut Vec<&str>, names: impl IntoIterator<Item = (i64, &'a str)>) -> Option<i64> {
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let mut matched: Option<(i64, usize, usize, usize)> = None;
+    for (id, name) in names {
+        let name_words: Vec<String> = name.split_whitespace().map(|w| w.to_lowercase()).collect();
+        if name_words.is_empty() || name_words.len() > lower.len() {
+            continue;
+        }
+        let position = (0..=lower.len() - name_words.len()).find(|&p| lower[p..p + name_words.len()] == name_words[..]);
+        if let Some(position) = position {
+            if matched.is_none_or(|(_, _, _, length)| length < name.len()) {
+                matched = Some((id, position, name_words.len(), name.len()));
+            }
+        }
+    }
+    matched.map(|(id, position, count, _)| {
+        words.drain(position..position + count);
+        id
+    })
+}
+
+/// Parse a date token relative to `today`
+fn parse_date(token: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let token = token.to_lowercase();
+    match token.as_str() {
+        "today" => return Some(today),
+        "yesterday" => return today.pred_opt(),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&token, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let weekday = match token.as_str() {
+        "monday" | "mon" => Weekday::Mon,
+        "tuesday" | "tue" => Weekday::Tue,
+        "wednesday" | "wed" => Weekday::Wed,
+        "thursday" | "thu" => Weekday::Thu,
+        "friday" | "fri" => Weekday::Fri,
+        "saturday" | "sat" => Weekday::Sat,
+        "sunday" | "sun" => Weekday::Sun,
+        _ => return None,
+    };
+    let days_back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+    // A bare weekday means the most recent past one, never today
+    let days_back = if days_back == 0 { 7 } else { days_back };
+    Some(today - Duration::days(days_back as i64))
+}
+
+/// Parse a start time token (`14:00`, `9:30`)
+fn parse_time(token: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(token, "%H:%M").ok()
+}
+
+/// Parse quick-capture text into a manual entry draft; `projects` are the
+/// (ID, name) pairs of the projects plugin, empty without it
+pub fn parse_time_entry(
+    text: &str,
+    categories: &[Category],
+    projects: &[(i64, String)],
+    now: DateTime<Local>,
+) -> Result<ManualEntryDraft, String> {
+    let mut duration_sec: Option<i64> = None;
+    let mut date: Option<NaiveDate> = None;
+    let mut start_time: Option<NaiveTime> = None;
+    let mut tags: Vec<String> = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        if let Some(tag) = token.strip_prefix('#').filter(|t| !t.is_empty()) {
+            tags.push(tag.to_lowercase());
+        } else if let Some(time) = token.strip_prefix('@').and_then(parse_time) {
+            start_time = Some(time);
+        } else if token.eq_ignore_ascii_case("at")
+            && tokens.get(i + 1).and_then(|t| parse_time(t)).is_some()
+        {
+            start_time = parse_time(tokens[i + 1]);
+            i += 1;
+        } else if let Some(duration) = parse_duration(token).filter(|_| duration_sec.is_none()) {
+            duration_sec = Some(duration?);
+        } else if date.is_none() && parse_date(token, now.date_naive()).is_some() {
+            date = parse_date(token, now.date_naive());
+        } else {
+            words.push(token);
+        }
+        i += 1;
+    }
+
+    let duration_sec = duration_sec.ok_or_else(|| "No duration found (e.g. 1.5h, 90m, 1:30)".to_string())?;
+
+    // The longest category name, then the longest project name, appearing in the remaining text
+    let category_id = take_longest_name(&mut words, categories.iter().map(|c| (c.id, c.name.as_str())));
+    let project_id = take_longest_name(&mut words, projects.iter().map(|(id, name)| (*id, name.as_str())));
+    let description = words.join(" ");
+    let end_of = |start: i64| start.checked_add(duration_sec).ok_or_else(|| "Invalid duration".to_string());
+
+    let today = now.date_naive();
+    let date = date.unwrap_or(today);
+    let (started_at, ended_at) = match start_time {
+        Some(time) => {
+            let start = Local
+                .from_local_datetime(&date.and_time(time))
+                .earliest()
+                .ok_or_else(|| "Invalid start time".to_string())?
+                .timestamp();
+            (start, end_of(start)?)
+        }
+        None if date == today => {
+            // Entries for today without a start time are assumed to have just finished
+            let end = now.timestamp();
+            (end.checked_sub(duration_sec).ok_or_else(|| "Invalid duration".to_string())?, end)
+        }
+        None => {
+            let start = Local
+                .from_local_datetime(&date.and_hms_opt(DEFAULT_START_HOUR, 0, 0).unwrap())
+                .earliest()
+                .ok_or_else(|| "Invalid date".to_string())?
+                .timestamp();
+            (start, end_of(start)?)
+        }
+    };
+
+    Ok(ManualEntryDraft {
+        description: (!description.is_empty()).then_some(description),
+        category_id,
+        project_id,
+        started_at,
+        ended_at,
+        duration_sec,
+        tags,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(id: i64, name: &str) -> Category {
+        Category {
+            id,
+            name: name.to_string(),
+            color: "#888888".to_string(),
+            icon: None,
+            is_productive: Some(true),
+            sort_order: 0,
+            is_system: false,
+            is_pinned: false,
+            exclude_from_export: false,
+            is_archived: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_time_entry() {
+        let now = Local.with_ymd_and_hms(2024, 5, 15, 18, 0, 0).unwrap();
+        let categories = vec![category(1, "Design"), category(2, "Code Review")];
+        let projects = Vec::new();
+
+        let draft = parse_time_entry("1.5h yesterday ACME design review #billable", &categories, &projects, now).unwrap();
+        assert_eq!(draft.duration_sec, 5400);
+        assert_eq!(draft.category_id, Some(1));
+        assert_eq!(draft.description.as_deref(), Some("ACME review"));
+        assert_eq!(draft.tags, vec!["billable".to_string()]);
+        let yesterday_9am = Local.with_ymd_and_hms(2024, 5, 14, 9, 0, 0).unwrap().timestamp();
+        assert_eq!(draft.started_at, yesterday_9am);
+        assert_eq!(draft.ended_at, yesterday_9am + 5400);
+
+        let draft = parse_time_entry("code review 45m at 14:00", &categories, &projects, now).unwrap();
+        assert_eq!(draft.category_id, Some(2));
+        assert_eq!(draft.description, None);
+        assert_eq!(draft.started_at, Local.with_ymd_and_hms(2024, 5, 15, 14, 0, 0).unwrap().timestamp());
+
+        let draft = parse_time_entry("1h30m standup", &categories, &projects, now).unwrap();
+        assert_eq!(draft.duration_sec, 5400);
+        assert_eq!(draft.ended_at, now.timestamp());
+
+        assert!(parse_time_entry("just some notes", &categories, &projects, now).is_err());
+    }
+
+    #[test]
+    fn projects_are_matched_after_categories() {
+        let now = Local.with_ymd_and_hms(2024, 5, 15, 18, 0, 0).unwrap();
+        let categories = vec![category(1, "Design")];
+        let projects = vec![(7, "ACME".to_string()), (8, "ACME Website".to_string())];
+
+        let draft = parse_time_entry("2h acme website design mockups", &categories, &projects, now).unwrap();
+        assert_eq!((draft.category_id, draft.project_id), (Some(1), Some(8)));
+        assert_eq!(draft.description.as_deref(), Some("mockups"));
+    }
+
+    #[test]
+    fn unusable_durations_are_rejected() {
+        let now = Local.with_ymd_and_hms(2024, 5, 15, 18, 0, 0).unwrap();
+        for text in ["0m review", "-1h review", "infh review", "NaNm review", "25h review", "99999999999999:00 review"] {
+            assert!(parse_time_entry(text, &[], &[], now).is_err(), "{}", text);
+        }
+        assert_eq!(parse_time_entry("24h on call", &[], &[], now).unwrap().duration_sec, 86400);
+    }
+}
@@ -0,0 +1,85 @@
+//! Application event bus
+//!
+//! Core code and plugins publish named events (e.g. `manual_entry.created`);
+//! subsystems such as webhooks subscribe to them. Listeners run synchronously
+//! on the emitting thread, so they must be cheap and hand off slow work.
+
+use std::sync::RwLock;
+
+pub const EVENT_MANUAL_ENTRY_CREATED: &str = "manual_entry.created";
+pub const EVENT_MANUAL_ENTRY_UPDATED: &str = "manual_entry.updated";
+pub const EVENT_MANUAL_ENTRY_DELETED: &str = "manual_entry.deleted";
+/// A tracker or data source sample was written; payload has its `timestamp`
+pub const EVENT_ACTIVITY_RECORDED: &str = "activity.recorded";
+/// Recorded history was edited (recategorized, deleted, imported, ...)
+pub const EVENT_ACTIVITIES_CHANGED: &str = "activities.changed";
+pub const EVENT_TRACKING_PAUSED: &str = "tracking.paused";
+pub const EVENT_TRACKING_RESUMED: &str = "tracking.resumed";
+pub const EVENT_PROFILE_SWITCHED: &str = "profile.switched";
+pub const EVENT_WORKSPACE_SWITCHED: &str = "workspace.switched";
+pub const EVENT_DATABASE_RECOVERED: &str = "diagnostics.database_recovered";
+pub const EVENT_SETTINGS_CHANGED: &str = "settings.changed";
+pub const EVENT_APP_SHUTDOWN: &str = "app.shutdown";
+
+/// Event published on the bus
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AppEvent {
+    /// Dotted event name, e.g. `manual_entry.created` or `pomodoro.completed`
+    pub name: String,
+    pub payload: serde_json::Value,
+    pub timestamp: i64,
+}
+
+type Listener = Box<dyn Fn(&AppEvent) + Send + Sync>;
+
+/// Publish/subscribe hub for application events
+pub struct EventBus {
+    listeners: RwLock<Vec<Listener>>,
+}
+
+impl EventBus {
+    /// Create an empty event bus
+    pub fn new() -> Self {
+        Self {
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Register a listener that receives every event
+    pub fn subscribe<F>(&self, listener: F)
+    where
+        F: Fn(&AppEvent) + Send + Sync + 'static,
+    {
+        self.listeners.write().unwrap().push(Box::new(listener));
+    }
+
+    /// Publish an event to all listeners
+    pub fn emit(&self, name: &str, payload: serde_json::Value) {
+        let event = AppEvent {
+            name: name.to_string(),
+            payload,
+            timestamp: chrono::Utc::now().timestamp(),
+        };
+        for listener in self.listeners.read().unwrap().iter() {
+            listener(&event);
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Check whether an event name matches a subscription pattern.
+/// Patterns are exact names, `*` for everything, or a `prefix.*` wildcard.
+pub fn event_matches(pattern: &str, name: &str) -> bool {
+    if pattern == "*" || pattern == name {
+        return true;
+    }
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => name.starts_with(prefix) && name[prefix.len()..].starts_with('.'),
+        None => false,
+    }
+}
@@ -0,0 +1,99 @@
+//! Password-protected exports
+//!
+//! Exported CSV, JSON and Parquet files carry window titles and often end up
+//! in cloud drives. Given a passphrase, an export is written as a zip archive
+//! holding the file AES-256 encrypted (WinZip AE-2, opened by 7-Zip, WinZip
+//! and Keka), named after the file with `.zip` appended. The passphrase comes
+//! from the export request or, for exports nobody types one into (automated
+//! and scheduled ones), the `export_passphrase` secret setting.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{AesMode, CompressionMethod, ZipWriter};
+
+/// Shortest passphrase accepted
+pub const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Setting with the passphrase used when an export request gives none
+pub const PASSPHRASE_SETTING: &str = "export_passphrase";
+
+/// Name of the file inside the archive: the archive's name without `.zip`
+/// (`activities.csv.zip` holds `activities.csv`), else `fallback`
+fn entry_name(path: &Path, fallback: &str) -> String {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(".zip"))
+        .filter(|name| name.contains('.'))
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+/// Where an export to `path` ends up: `path` itself, or with `.zip`
+/// appended when encrypted (`activities.csv` -> `activities.csv.zip`)
+pub fn output_path(path: &Path, encrypted: bool) -> PathBuf {
+    let is_zip = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !encrypted || is_zip {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".zip");
+    PathBuf::from(name)
+}
+
+/// Check a passphrase before anything is exported with it
+pub fn validate_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Export passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    Ok(())
+}
+
+/// Write an export to `path`: as is without a passphrase, otherwise as an
+/// encrypted archive at `output_path` whose entry is named after `path` (or
+/// `fallback`, e.g. `activities.csv`, for a `.zip` path). Returns the path
+/// written.
+pub fn write(path: &Path, contents: &[u8], passphrase: Option<&str>, fallback: &str) -> Result<PathBuf, String> {
+    let Some(passphrase) = passphrase else {
+        std::fs::write(path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        return Ok(path.to_path_buf());
+    };
+    validate_passphrase(passphrase)?;
+    let path = &output_path(path, true);
+
+    let file = File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut archive = ZipWriter::new(file);
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .with_aes_encryption(AesMode::Aes256, passphrase);
+    let failed = |e: zip::result::ZipError| format!("Failed to write encrypted archive: {}", e);
+    archive.start_file(entry_name(path, fallback), options).map_err(failed)?;
+    archive.write_all(contents).map_err(|e| failed(e.into()))?;
+    archive.finish().map_err(failed)?;
+    Ok(path.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn encrypted_exports_need_the_passphrase() {
+        let requested = std::env::temp_dir().join(format!("time-tracker-test-{}.csv", std::process::id()));
+        assert!(write(&requested, b"id,app_name\n1,Editor\n", Some("short"), "activities.csv").is_err());
+        let path = write(&requested, b"id,app_name\n1,Editor\n", Some("correct horse"), "activities.csv").unwrap();
+        assert_eq!(path, output_path(&requested, true));
+        assert!(path.to_string_lossy().ends_with(".csv.zip") && !requested.exists());
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let name = format!("time-tracker-test-{}.csv", std::process::id());
+        assert!(archive.by_name(&name).is_err(), "entry is encrypted");
+        assert!(archive.by_name_decrypt(&name, b"wrong passphrase").is_err());
+        let mut contents = String::new();
+        archive.by_name_decrypt(&name, b"correct horse").unwrap().read_to_string(&mut contents).unwrap();
+        assert_eq!(contents, "id,app_name\n1,Editor\n");
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,332 @@
+//! Focus-mode blocking during pomodoro work sessions
+//!
+//! Active while a `pomodoro.*` event reports a work phase (`phase` of `work`
+//! or `focus`) for a preset listed in `focus_blocking_presets` (JSON array;
+//! empty means every preset) and `focus_blocking_enabled` is `true`.
+//!
+//! While active, a blocked app in the foreground triggers the overlay callback,
+//! which raises the main window above it. Blocked domains are written to the
+//! hosts file when `focus_blocking_hosts_file` is `true`, and rewritten when the
+//! blocklist changes during a session. This needs write access to the hosts
+//! file, so it is opt-in and failures are only logged.
+//!
+//! The hosts file is rewritten in place rather than replaced: only the file
+//! has to be writable, not its directory, and its owner, ACLs and SELinux
+//! label stay as they are. A copy of the previous contents is kept in the data
+//! directory first.
+
+use crate::database::blocklist::{BLOCKLIST_KIND_APP, BLOCKLIST_KIND_DOMAIN};
+use crate::database::Database;
+use crate::events::EventBus;
+use crate::window::WindowTracker;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often the foreground app is checked while blocking
+const POLL_INTERVAL_SECS: u64 = 2;
+
+/// Preset name used when a pomodoro event does not name one
+const DEFAULT_PRESET: &str = "default";
+
+const HOSTS_BLOCK_BEGIN: &str = "# >>> time-tracker focus mode";
+const HOSTS_BLOCK_END: &str = "# <<< time-tracker focus mode";
+
+/// Copy of the hosts file taken before each rewrite, in the data directory
+const HOSTS_BACKUP_FILE: &str = "hosts.backup";
+
+/// Current pomodoro phase as reported by plugin events
+#[derive(Debug, Clone, Default)]
+struct PomodoroState {
+    phase: Option<String>,
+    preset: Option<String>,
+}
+
+/// Focus blocking status shown in the UI
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct FocusBlockingStatus {
+    pub active: bool,
+    pub preset: Option<String>,
+    /// Whether blocked domains are currently in the hosts file
+    pub hosts_file_applied: bool,
+}
+
+/// Shared focus blocker state
+pub struct FocusBlocker {
+    status: Mutex<FocusBlockingStatus>,
+}
+
+impl FocusBlocker {
+    pub fn new() -> Self {
+        Self {
+            status: Mutex::new(FocusBlockingStatus::default()),
+        }
+    }
+
+    /// Get the current blocking status
+    pub fn status(&self) -> FocusBlockingStatus {
+        self.status.lock().unwrap().clone()
+    }
+}
+
+impl Default for FocusBlocker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn hosts_path() -> PathBuf {
+    #[cfg(windows)]
+    {
+        let root = std::env::var("SystemRoot").unwrap_or_else(|_| r"C:\Windows".to_string());
+        PathBuf::from(root).join(r"System32\drivers\etc\hosts")
+    }
+    #[cfg(not(windows))]
+    {
+        PathBuf::from("/etc/hosts")
+    }
+}
+
+/// Whether `domain` is a plain host name (dot-separated `[a-z0-9-]` labels),
+/// so it cannot inject anything else into the hosts file
+pub fn is_valid_domain(domain: &str) -> bool {
+    !domain.is_empty()
+        && domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        })
+}
+
+/// Hosts file contents without our marked block; every other byte (line
+/// endings, a missing final newline) is kept as it was
+fn strip_hosts_block(contents: &str) -> String {
+    let mut result = String::with_capacity(contents.len());
+    let mut inside = false;
+    for line in contents.split_inclusive('\n') {
+        match line.trim() {
+            HOSTS_BLOCK_BEGIN => inside = true,
+            HOSTS_BLOCK_END => inside = false,
+            _ if !inside => result.push_str(line),
+            _ => {}
+        }
+    }
+    result
+}
+
+/// Hosts file `contents` with the marked block replaced by one for `domains`;
+/// an empty list removes it. Invalid domains are skipped.
+fn with_hosts_block(contents: &str, domains: &[String]) -> String {
+    let mut updated = strip_hosts_block(contents);
+    let domains: Vec<&String> = domains.iter().filter(|d| is_valid_domain(d)).collect();
+    if !domains.is_empty() {
+        let newline = if contents.contains("\r\n") { "\r\n" } else { "\n" };
+        if !updated.is_empty() && !updated.ends_with('\n') {
+            updated.push_str(newline);
+        }
+        updated.push_str(HOSTS_BLOCK_BEGIN);
+        updated.push_str(newline);
+        for domain in domains {
+            updated.push_str(&format!("0.0.0.0 {domain}{newline}0.0.0.0 www.{domain}{newline}"));
+        }
+        updated.push_str(HOSTS_BLOCK_END);
+        updated.push_str(newline);
+    }
+    updated
+}
+
+/// Truncate `path` and write `contents` into the same file, synced to disk
+fn overwrite(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new().write(true).truncate(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()
+}
+
+/// Replace the marked block of the hosts file at `path`; an empty list
+/// removes it. The file is only rewritten when it changes, after its contents
+/// are saved to `backup_path`; if writing fails they are put back.
+fn write_hosts_block_at(path: &Path, backup_path: &Path, domains: &[String]) -> std::io::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let updated = with_hosts_block(&contents, domains);
+    if updated == contents {
+        return Ok(());
+    }
+    if let Some(dir) = backup_path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut backup = std::fs::File::create(backup_path)?;
+    backup.write_all(contents.as_bytes())?;
+    backup.sync_all()?;
+    overwrite(path, &updated).inspect_err(|_| {
+        if let Err(e) = overwrite(path, &contents) {
+            eprintln!("Warning: Failed to restore the hosts file; a copy is at {}: {}", backup_path.display(), e);
+        }
+    })
+}
+
+fn write_hosts_block(domains: &[String]) -> std::io::Result<()> {
+    write_hosts_block_at(&hosts_path(), &crate::workspace::data_dir().join(HOSTS_BACKUP_FILE), domains)
+}
+
+/// Preset the blocker should be active for, if any
+fn active_preset(db: &Database, pomodoro: &PomodoroState) -> Option<String> {
+    let phase = pomodoro.phase.as_deref()?;
+    if phase != "work" && phase != "focus" {
+        return None;
+    }
+    if !crate::settings::load(db).focus_blocking_enabled {
+        return None;
+    }
+    let preset = pomodoro.preset.clone().unwrap_or_else(|| DEFAULT_PRESET.to_string());
+    let presets: Vec<String> = db
+        .get_setting("focus_blocking_presets")
+        .ok()
+        .flatten()
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default();
+    if presets.is_empty() || presets.iter().any(|p| p.eq_ignore_ascii_case(&preset)) {
+        Some(preset)
+    } else {
+        None
+    }
+}
+
+fn hosts_file_enabled(db: &Database) -> bool {
+    crate::settings::load(db).focus_blocking_hosts_file
+}
+
+/// Start the focus blocker; `on_blocked` is called with the name of a blocked
+/// app that is in the foreground
+pub fn start<F>(db: Arc<Database>, blocker: Arc<FocusBlocker>, event_bus: &EventBus, on_blocked: F)
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let pomodoro: Arc<Mutex<PomodoroState>> = Arc::new(Mutex::new(PomodoroState::default()));
+
+    let pomodoro_for_events = Arc::clone(&pomodoro);
+    event_bus.subscribe(move |event| {
+        if !event.name.starts_with("pomodoro.") {
+            return;
+        }
+        if let Some(phase) = event.payload.get("phase") {
+            let mut state = pomodoro_for_events.lock().unwrap();
+            state.phase = phase.as_str().map(str::to_string);
+            state.preset = event.payload.get("preset").and_then(|p| p.as_str()).map(str::to_string);
+        }
+    });
+
+    std::thread::spawn(move || {
+        // Remove domains left behind by a crash during a work session
+        if hosts_file_enabled(&db) {
+            if let Err(e) = write_hosts_block(&[]) {
+                eprintln!("Warning: Failed to clean up focus mode hosts entries: {}", e);
+            }
+        }
+
+        let window_tracker = WindowTracker::new();
+        // Domains last written to the hosts file (or attempted, so a failing
+        // write is not retried every poll)
+        let mut hosts_domains: Vec<String> = Vec::new();
+        loop {
+            let state = pomodoro.lock().unwrap().clone();
+            let preset = active_preset(&db, &state);
+            let previous = blocker.status();
+            let blocklist = db.get_blocklist().unwrap_or_default();
+
+            // Follows blocklist edits during a session, and cleans up when the
+            // session ends or the setting is turned off during one
+            let domains: Vec<String> = if preset.is_some() && hosts_file_enabled(&db) {
+                blocklist
+                    .iter()
+                    .filter(|e| e.kind == BLOCKLIST_KIND_DOMAIN)
+                    .map(|e| e.pattern.clone())
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let mut hosts_file_applied = previous.hosts_file_applied;
+            if domains != hosts_domains {
+                match write_hosts_block(&domains) {
+                    Ok(()) => hosts_file_applied = !domains.is_empty(),
+                    Err(e) => eprintln!("Warning: Failed to update hosts file for focus mode: {}", e),
+                }
+                hosts_domains = domains;
+            }
+
+            if preset.is_some() != previous.active || hosts_file_applied != previous.hosts_file_applied {
+                *blocker.status.lock().unwrap() = FocusBlockingStatus {
+                    active: preset.is_some(),
+                    preset: preset.clone(),
+                    hosts_file_applied,
+                };
+            }
+
+            if preset.is_some() {
+                if let Some(window) = window_tracker.get_active_window() {
+                    let app_name = window.app_name.to_lowercase();
+                    let blocked = blocklist
+                        .iter()
+                        .filter(|e| e.kind == BLOCKLIST_KIND_APP)
+                        .any(|e| app_name.contains(&e.pattern.to_lowercase()));
+                    if blocked {
+                        on_blocked(&window.app_name);
+                    }
+                }
+            }
+
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts_block_keeps_the_rest_of_the_file_byte_for_byte() {
+        let original = "127.0.0.1 localhost\r\n::1 localhost";
+        let domains = vec!["example.com".to_string(), "bad host\n1.2.3.4 bank.com".to_string()];
+        let blocked = with_hosts_block(original, &domains);
+        assert_eq!(
+            blocked,
+            "127.0.0.1 localhost\r\n::1 localhost\r\n# >>> time-tracker focus mode\r\n\
+             0.0.0.0 example.com\r\n0.0.0.0 www.example.com\r\n# <<< time-tracker focus mode\r\n"
+        );
+        assert_eq!(with_hosts_block(&blocked, &[]), "127.0.0.1 localhost\r\n::1 localhost\r\n");
+        assert_eq!(with_hosts_block(original, &[]), original);
+    }
+
+    #[test]
+    fn hosts_file_is_rewritten_in_place_after_a_backup() {
+        let dir = std::env::temp_dir().join(format!("time-tracker-hosts-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (path, backup_path) = (dir.join("hosts"), dir.join("data").join(HOSTS_BACKUP_FILE));
+        std::fs::write(&path, "127.0.0.1 localhost\n").unwrap();
+        let original = std::fs::metadata(&path).unwrap();
+
+        write_hosts_block_at(&path, &backup_path, &["example.com".to_string()]).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("0.0.0.0 example.com"));
+        assert_eq!(std::fs::read_to_string(&backup_path).unwrap(), "127.0.0.1 localhost\n");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().ino(), original.ino());
+        }
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions(), original.permissions());
+
+        write_hosts_block_at(&path, &backup_path, &[]).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "127.0.0.1 localhost\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn domains_are_plain_host_names() {
+        assert!(is_valid_domain("news.example-site.co.uk"));
+        assert!(!is_valid_domain(""));
+        assert!(!is_valid_domain("example..com"));
+        assert!(!is_valid_domain("Example.com"));
+        assert!(!is_valid_domain("example.com 1.2.3.4"));
+        assert!(!is_valid_domain("example.com\n0.0.0.0"));
+    }
+}
@@ -0,0 +1,83 @@
+//! Git commit scanning
+//!
+//! Reads commits from local repositories with `git log` so they can be shown
+//! next to tracked time. Repositories are configured in the `git_repositories`
+//! setting (JSON array of paths); `git_author_email` limits scans to the user's
+//! own commits.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Field separator in the `git log` output format
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+/// Commit read from a local repository
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GitCommit {
+    pub hash: String,
+    pub timestamp: i64,
+    pub author_name: String,
+    pub author_email: String,
+    pub subject: String,
+}
+
+/// Check that a path is the root of a git working tree
+pub fn is_repository(path: &Path) -> bool {
+    path.join(".git").exists()
+}
+
+/// Repository display name (its folder name)
+pub fn repository_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// List commits made in `[start, end]`, newest first, optionally by one author
+pub fn scan_commits(
+    repo: &Path,
+    start: i64,
+    end: i64,
+    author_email: Option<&str>,
+) -> Result<Vec<GitCommit>, String> {
+    let mut command = Command::new("git");
+    command
+        .arg("-C")
+        .arg(repo)
+        .arg("log")
+        .arg("--all")
+        .arg("--no-merges")
+        .arg(format!("--since=@{}", start))
+        .arg(format!("--until=@{}", end))
+        .arg("--format=%H%x1f%at%x1f%an%x1f%ae%x1f%s");
+    if let Some(email) = author_email.filter(|e| !e.is_empty()) {
+        command.arg(format!("--author={}", email));
+    }
+
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git log failed for {}: {}",
+            repo.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, FIELD_SEPARATOR);
+            Some(GitCommit {
+                hash: fields.next()?.to_string(),
+                timestamp: fields.next()?.parse().ok()?,
+                author_name: fields.next()?.to_string(),
+                author_email: fields.next()?.to_string(),
+                subject: fields.next().unwrap_or("").to_string(),
+            })
+        })
+        // --since/--until use committer dates; keep the author date range exact
+        .filter(|c| c.timestamp >= start && c.timestamp <= end)
+        .collect())
+}
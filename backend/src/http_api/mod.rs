@@ -0,0 +1,236 @@
+//! REST API for external tools and scripts
+//!
+//! While `http_api_enabled` is `true`, a JSON API listens on
+//! `127.0.0.1:<http_api_port>` (default 9901) under `/api/v1` (see `routes`
+//! for the endpoints). Requests run the same commands as the frontend, so
+//! validation, events and caches behave the same. Every request needs
+//! `Authorization: Bearer <token>` with the token from `get_http_api_info`,
+//! generated locally on first use and stored in the `http_api_token` secret
+//! setting. The server follows the settings as they change; a port that
+//! cannot be bound is retried every `BIND_RETRY_SECS` and the error is shown
+//! in `get_http_api_info`.
+//!
+//! Responses are `{"data": ...}` on success and `{"error": "..."}` otherwise;
+//! rejected inputs add the structured `validation` error.
+
+mod routes;
+
+pub(crate) use routes::{route, Reply};
+
+use crate::commands::AppState;
+use crate::settings::SettingsService;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+use tiny_http::{Header, Request, Response, Server};
+
+/// Setting holding the bearer token
+pub const TOKEN_SETTING: &str = "http_api_token";
+
+/// Path prefix of every endpoint
+pub const PATH_PREFIX: &str = "/api/v1";
+
+/// How long to wait before binding a port that was taken again
+pub(crate) const BIND_RETRY_SECS: u64 = 30;
+
+/// Why the enabled server is not listening (e.g. the port is taken)
+static BIND_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/// Largest accepted request body
+const MAX_BODY_BYTES: u64 = 1024 * 1024;
+
+/// The API token, generated and stored on first use
+pub fn token(settings: &SettingsService) -> Result<String, String> {
+    match settings.get_secret(TOKEN_SETTING)? {
+        Some(token) => Ok(token),
+        None => reset_token(settings),
+    }
+}
+
+/// Replace the API token; clients using the old one are locked out
+pub fn reset_token(settings: &SettingsService) -> Result<String, String> {
+    let token = format!("{}{}", uuid::Uuid::new_v4().simple(), uuid::Uuid::new_v4().simple());
+    settings.set(TOKEN_SETTING, &token)?;
+    Ok(token)
+}
+
+/// Why the server is not listening although enabled, if it failed to start
+pub fn bind_error() -> Option<String> {
+    BIND_ERROR.lock().unwrap().clone()
+}
+
+/// Compare without stopping at the first differing byte
+pub(crate) fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3).and_then(|hex| std::str::from_utf8(hex).ok());
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Split a request URL into its path and decoded query parameters
+pub(crate) fn parse_url(url: &str) -> (String, HashMap<String, String>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let params = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect();
+    (path.to_string(), params)
+}
+
+fn respond(request: Request, reply: Reply) {
+    let response = Response::from_string(reply.body.to_string())
+        .with_status_code(reply.status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("static header"));
+    request.respond(response).ok();
+}
+
+fn handle_request(mut request: Request, state: State<'_, AppState>) {
+    let authorized = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer "))
+        .is_some_and(|given| token(&state.settings).is_ok_and(|expected| token_matches(given.trim(), &expected)));
+    if !authorized {
+        respond(request, Reply::error(401, "Missing or invalid API token"));
+        return;
+    }
+
+    let (path, query) = parse_url(request.url());
+    let Some(path) = path.strip_prefix(PATH_PREFIX).map(str::to_string) else {
+        respond(request, Reply::error(404, "Not found"));
+        return;
+    };
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().take(MAX_BODY_BYTES).read_to_string(&mut body) {
+        respond(request, Reply::error(400, &e.to_string()));
+        return;
+    }
+    let body = match body.trim() {
+        "" => serde_json::Value::Null,
+        body => match serde_json::from_str(body) {
+            Ok(body) => body,
+            Err(e) => {
+                respond(request, Reply::error(400, &format!("Invalid JSON body: {}", e)));
+                return;
+            }
+        },
+    };
+    let method = request.method().clone();
+    respond(request, route(state, &method, &path, &query, body));
+}
+
+/// Running server; dropping it stops the server thread
+struct Listener {
+    server: Arc<Server>,
+}
+
+impl Listener {
+    fn bind(port: u16, app_handle: AppHandle) -> Option<Self> {
+        let server = match Server::http(("127.0.0.1", port)) {
+            Ok(server) => Arc::new(server),
+            Err(e) => {
+                let error = format!("Failed to start HTTP API on port {}: {}", port, e);
+                eprintln!("Warning: {}", error);
+                *BIND_ERROR.lock().unwrap() = Some(error);
+                return None;
+            }
+        };
+        *BIND_ERROR.lock().unwrap() = None;
+        let server_for_thread = Arc::clone(&server);
+        std::thread::spawn(move || {
+            let state = app_handle.state::<AppState>();
+            for request in server_for_thread.incoming_requests() {
+                handle_request(request, state.clone());
+            }
+        });
+        Some(Self { server })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+/// Start the API server supervisor (idle unless enabled in settings)
+pub fn start(settings: Arc<SettingsService>, app_handle: AppHandle) {
+    let changes = settings.watch(&["http_api_enabled", "http_api_port"]);
+    std::thread::spawn(move || {
+        let mut active_port: Option<u16> = None;
+        let mut listener: Option<Listener> = None;
+        loop {
+            let current = settings.get();
+            let port = current.http_api_enabled.then_some(current.http_api_port);
+            if port != active_port || listener.is_none() {
+                // Stop the old server first so the port can be reused
+                drop(listener.take());
+                if port.is_some() {
+                    if let Err(e) = token(&settings) {
+                        eprintln!("Warning: Failed to create HTTP API token: {}", e);
+                    }
+                } else {
+                    *BIND_ERROR.lock().unwrap() = None;
+                }
+                listener = port.and_then(|port| Listener::bind(port, app_handle.clone()));
+                active_port = port;
+            }
+
+            // Wait for a settings change; retry a failed bind meanwhile
+            let woken = if port.is_some() && listener.is_none() {
+                changes.recv_timeout(Duration::from_secs(BIND_RETRY_SECS))
+            } else {
+                changes.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            };
+            if let Err(RecvTimeoutError::Disconnected) = woken {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_and_tokens_are_checked() {
+        let (path, query) = parse_url("/api/v1/activities?start=10&end=20&sources=plugin%3Ajira,tracker");
+        assert_eq!(path, "/api/v1/activities");
+        assert_eq!(query.get("sources").map(String::as_str), Some("plugin:jira,tracker"));
+        assert_eq!(parse_url("/x?q=a%2").1.get("q").map(String::as_str), Some("a%2"));
+
+        assert!(token_matches("abc", "abc"));
+        assert!(!token_matches("abd", "abc"));
+        assert!(!token_matches("", "abc"));
+    }
+}
@@ -0,0 +1,185 @@
+//! REST API endpoints (paths below `/api/v1`)
+//!
+//! - `GET /activities?start=&end=[&limit=&offset=&sources=a,b]`
+//! - `GET|DELETE /activities/{id}`, `PATCH /activities/{id}` with `{"category_id"}`
+//! - `GET|POST /categories`, `PUT|DELETE /categories/{id}`
+//! - `GET /manual-entries?start=&end=`, `POST /manual-entries`,
+//!   `PUT|DELETE /manual-entries/{id}`
+//! - `GET /stats?start=&end=[&sources=a,b]`
+//! - `GET /projects` (with the projects plugin installed)
+//! - `POST /plugins/{plugin_id}/commands/{command}` with the command's params,
+//!   e.g. to create projects or tasks
+//!
+//! Bodies are the requests of the matching commands; IDs in the path win
+//! over IDs in the body.
+
+use crate::commands::{self, AppState};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use tauri::State;
+use time_tracker_plugin_sdk::validation::ValidationError;
+use tiny_http::Method;
+
+/// Status and JSON body of a response
+#[derive(Debug)]
+pub(crate) struct Reply {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Reply {
+    pub(crate) fn error(status: u16, message: &str) -> Self {
+        Self { status, body: json!({ "error": message }) }
+    }
+
+    fn data<T: Serialize>(status: u16, data: T) -> Self {
+        match serde_json::to_value(data) {
+            Ok(data) => Self { status, body: json!({ "data": data }) },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    /// Reply for a command result; rejected inputs (`ValidationError`) are 422
+    fn from_result<T: Serialize>(status: u16, result: Result<T, String>) -> Self {
+        match result {
+            Ok(data) => Self::data(status, data),
+            Err(e) => match serde_json::from_str::<ValidationError>(&e) {
+                Ok(validation) => Self {
+                    status: 422,
+                    body: json!({ "error": validation.message, "validation": validation }),
+                },
+                Err(_) => Self::error(400, &e),
+            },
+        }
+    }
+}
+
+fn query_i64(query: &HashMap<String, String>, key: &str) -> Result<Option<i64>, Reply> {
+    query
+        .get(key)
+        .map(|value| value.parse().map_err(|_| Reply::error(400, &format!("Invalid {}: expected a number", key))))
+        .transpose()
+}
+
+/// `start` and `end` query parameters, both required
+fn query_range(query: &HashMap<String, String>) -> Result<(i64, i64), Reply> {
+    match (query_i64(query, "start")?, query_i64(query, "end")?) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => Err(Reply::error(400, "start and end are required")),
+    }
+}
+
+/// Comma-separated list parameter, e.g. `sources=tracker,ide`
+fn query_list(query: &HashMap<String, String>, key: &str) -> Option<Vec<String>> {
+    query
+        .get(key)
+        .map(|value| value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from).collect())
+}
+
+fn parse_id(id: &str) -> Result<i64, Reply> {
+    id.parse().map_err(|_| Reply::error(404, "Not found"))
+}
+
+/// Deserialize a command request from a body, with `id` from the path when given
+fn request<T: serde::de::DeserializeOwned>(mut body: Value, id: Option<i64>) -> Result<T, Reply> {
+    if let (Some(id), Some(object)) = (id, body.as_object_mut()) {
+        object.insert("id".to_string(), id.into());
+    }
+    serde_json::from_value(body).map_err(|e| Reply::error(400, &format!("Invalid request: {}", e)))
+}
+
+/// Handle a request for `path` (below `/api/v1`)
+pub(crate) fn route(
+    state: State<'_, AppState>,
+    method: &Method,
+    path: &str,
+    query: &HashMap<String, String>,
+    body: Value,
+) -> Reply {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    match handle(state, method, &segments, query, body) {
+        Ok(reply) | Err(reply) => reply,
+    }
+}
+
+fn handle(
+    state: State<'_, AppState>,
+    method: &Method,
+    segments: &[&str],
+    query: &HashMap<String, String>,
+    body: Value,
+) -> Result<Reply, Reply> {
+    Ok(match (method, segments) {
+        (Method::Get, ["activities"]) => {
+            let (start, end) = query_range(query)?;
+            let activities_request = request(
+                json!({
+                    "start": start,
+                    "end": end,
+                    "limit": query_i64(query, "limit")?,
+                    "offset": query_i64(query, "offset")?,
+                    "sources": query_list(query, "sources"),
+                }),
+                None,
+            )?;
+            Reply::from_result(200, commands::get_activities(state, activities_request))
+        }
+        (Method::Get, ["activities", id]) => match commands::get_activity(state, parse_id(id)?) {
+            Ok(None) => Reply::error(404, "Activity not found"),
+            result => Reply::from_result(200, result),
+        },
+        (Method::Patch, ["activities", id]) => {
+            let category_id = match body.get("category_id") {
+                Some(Value::Null) => None,
+                Some(value) => Some(value.as_i64().ok_or_else(|| Reply::error(400, "Invalid category_id"))?),
+                None => return Err(Reply::error(400, "category_id is required")),
+            };
+            let id = parse_id(id)?;
+            let update = commands::UpdateActivityCategoryRequest { activity_id: id, category_id };
+            Reply::from_result(200, commands::update_activity_category(state, update))
+        }
+        (Method::Delete, ["activities", id]) => Reply::from_result(200, commands::delete_activity(state, parse_id(id)?)),
+
+        (Method::Get, ["categories"]) => Reply::from_result(200, commands::get_categories(state)),
+        (Method::Post, ["categories"]) => Reply::from_result(201, commands::create_category(state, request(body, None)?)),
+        (Method::Put, ["categories", id]) => {
+            let update = request(body, Some(parse_id(id)?))?;
+            Reply::from_result(200, commands::update_category(state, update))
+        }
+        (Method::Delete, ["categories", id]) => Reply::from_result(200, commands::delete_category(state, parse_id(id)?)),
+
+        (Method::Get, ["manual-entries"]) => {
+            let (start, end) = query_range(query)?;
+            let request = commands::ManualEntriesRequest { start, end, fields: None };
+            Reply::from_result(200, commands::get_manual_entries(state, request))
+        }
+        (Method::Post, ["manual-entries"]) => {
+            Reply::from_result(201, commands::create_manual_entry(state, request(body, None)?))
+        }
+        (Method::Put, ["manual-entries", id]) => {
+            let update = request(body, Some(parse_id(id)?))?;
+            Reply::from_result(200, commands::update_manual_entry(state, update))
+        }
+        (Method::Delete, ["manual-entries", id]) => {
+            Reply::from_result(200, commands::delete_manual_entry(state, parse_id(id)?))
+        }
+
+        (Method::Get, ["stats"]) => {
+            let (start, end) = query_range(query)?;
+            let request = commands::StatsRequest { start, end, sources: query_list(query, "sources"), devices: None };
+            Reply::from_result(200, commands::get_stats(state, request))
+        }
+        (Method::Get, ["projects"]) => {
+            if !state.db.plugin_table_exists("projects") {
+                return Err(Reply::error(404, "Projects plugin not installed"));
+            }
+            Reply::from_result(200, state.db.plugin_select_table("projects", None, Some("name"), None))
+        }
+        (Method::Post, ["plugins", plugin_id, "commands", command]) => {
+            let params = if body.is_null() { json!({}) } else { body };
+            Reply::from_result(200, commands::plugins::invoke_plugin_command_with_api(&state, plugin_id, command, params))
+        }
+        _ => Reply::error(404, "Not found"),
+    })
+}
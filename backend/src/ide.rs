@@ -0,0 +1,369 @@
+//! Editor integration (WakaTime-compatible heartbeat receiver)
+//!
+//! Listens on `127.0.0.1:<ide_heartbeats_port>` (default 9900) while the
+//! `ide_heartbeats_enabled` setting is `true`. Point the WakaTime plugin of any
+//! editor at it with `api_url = http://localhost:9900/api/v1` in `~/.wakatime.cfg`,
+//! and set `api_key` there to the local API token (see `get_http_api_info`).
+//! The same listener accepts terminal hook reports on `POST /terminal`
+//! (see `terminal`), and serves `GET /metrics` while `metrics_enabled` is
+//! `true` (see `metrics`; the listener then runs for metrics alone too).
+//!
+//! Heartbeats and terminal reports must carry the token, as the WakaTime
+//! `api_key` (Basic auth) or as `Authorization: Bearer <token>`. Requests with
+//! an `Origin` header come from a web page and are rejected, so sites open in
+//! the browser cannot record activities.
+//!
+//! Heartbeats are stored in `editor_heartbeats` for per-file and per-language
+//! stats and turned into activity samples (source `ide`) titled
+//! `<project> - <file>`, where the project is the WakaTime project or the
+//! repository folder containing the file.
+
+use crate::data_sources::{ActivitySample, DataSourceRegistry};
+use crate::database::activities::local_day_start;
+use crate::database::common::SOURCE_IDE;
+use crate::database::{Database, EditorHeartbeat};
+use crate::http_api;
+use crate::metrics::{self, PomodoroCounter};
+use crate::settings::SettingsService;
+use base64::Engine;
+use crate::terminal::{self, TerminalReport};
+use std::io::Read;
+use std::path::Path;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Largest accepted request body (bulk uploads of queued offline heartbeats)
+const MAX_BODY_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Heartbeat as sent by wakatime-cli
+#[derive(Debug, serde::Deserialize)]
+struct WakaTimeHeartbeat {
+    entity: String,
+    #[serde(rename = "type")]
+    entity_type: Option<String>,
+    /// Unix timestamp with fractional seconds
+    time: f64,
+    project: Option<String>,
+    language: Option<String>,
+    branch: Option<String>,
+    #[serde(default)]
+    is_write: bool,
+    user_agent: Option<String>,
+}
+
+/// Editor name from a wakatime user agent, e.g.
+/// `wakatime/v1.73.0 (linux) go1.20 vscode/1.80.0 vscode-wakatime/24.2.0` -> `vscode`
+fn editor_from_user_agent(user_agent: &str) -> Option<String> {
+    user_agent
+        .split_whitespace()
+        .filter_map(|token| token.split_once('/').map(|(name, _)| name))
+        .find(|name| !name.is_empty() && *name != "wakatime")
+        .map(str::to_string)
+}
+
+/// Project name: the one reported by the plugin, else the folder of the
+/// enclosing git repository, else the file's parent folder
+fn detect_project(reported: Option<&str>, entity: &str) -> Option<String> {
+    if let Some(project) = reported.map(str::trim).filter(|p| !p.is_empty()) {
+        return Some(project.to_string());
+    }
+    let path = Path::new(entity);
+    let repo_root = path.ancestors().skip(1).find(|dir| dir.join(".git").exists());
+    repo_root
+        .or_else(|| path.parent())
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+fn to_heartbeat(hb: WakaTimeHeartbeat, header_user_agent: Option<&str>) -> EditorHeartbeat {
+    let editor = hb
+        .user_agent
+        .as_deref()
+        .or(header_user_agent)
+        .and_then(editor_from_user_agent)
+        .unwrap_or_else(|| "editor".to_string());
+    EditorHeartbeat {
+        id: 0,
+        time: hb.time as i64,
+        project: detect_project(hb.project.as_deref(), &hb.entity),
+        entity: hb.entity,
+        entity_type: hb.entity_type.unwrap_or_else(|| "file".to_string()),
+        language: hb.language.filter(|l| !l.is_empty()),
+        branch: hb.branch.filter(|b| !b.is_empty()),
+        is_write: hb.is_write,
+        editor,
+    }
+}
+
+fn to_sample(hb: &EditorHeartbeat) -> ActivitySample {
+    let (title, domain) = match hb.entity_type.as_str() {
+        "file" => {
+            let file_name = Path::new(&hb.entity)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| hb.entity.clone());
+            let title = match &hb.project {
+                Some(project) => format!("{} - {}", project, file_name),
+                None => file_name,
+            };
+            (title, None)
+        }
+        "domain" => (hb.entity.clone(), Some(hb.entity.clone())),
+        _ => (hb.entity.clone(), None),
+    };
+    ActivitySample {
+        app_name: hb.editor.clone(),
+        window_title: Some(title),
+        domain,
+        timestamp: hb.time,
+        source: SOURCE_IDE.to_string(),
+    }
+}
+
+/// Token in an `Authorization` header value: `Bearer <token>`, or Basic auth
+/// whose user name is the token (how wakatime-cli sends its `api_key`)
+fn authorization_token(value: &str) -> Option<String> {
+    let value = value.trim();
+    if let Some(token) = value.strip_prefix("Bearer ") {
+        return Some(token.trim().to_string());
+    }
+    let decoded = base64::engine::general_purpose::STANDARD.decode(value.strip_prefix("Basic ")?.trim()).ok()?;
+    let credentials = String::from_utf8(decoded).ok()?;
+    let user = credentials.split(':').next().unwrap_or_default();
+    Some(user.to_string())
+}
+
+/// Whether a request may record data: not sent by a web page, and carrying
+/// the local API token
+fn is_authorized(request: &Request, settings: &SettingsService) -> bool {
+    let header = |name: &'static str| request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.as_str());
+    if header("Origin").is_some() {
+        return false;
+    }
+    header("Authorization")
+        .and_then(authorization_token)
+        .is_some_and(|given| http_api::token(settings).is_ok_and(|expected| http_api::token_matches(&given, &expected)))
+}
+
+fn json_response(status: u16, body: serde_json::Value) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_string(body.to_string())
+        .with_status_code(status)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("static header"))
+}
+
+/// Read a request body as a string (bounded by `MAX_BODY_BYTES`)
+fn read_body(request: &mut Request) -> Result<String, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    Ok(body)
+}
+
+fn handle_terminal_report(mut request: Request, data_sources: &DataSourceRegistry) {
+    let result = read_body(&mut request)
+        .and_then(|body| serde_json::from_str::<TerminalReport>(&body).map_err(|e| e.to_string()))
+        .and_then(|report| terminal::record_report(data_sources, report));
+    let response = match result {
+        Ok(()) => json_response(201, serde_json::json!({ "data": null })),
+        Err(e) => json_response(400, serde_json::json!({ "error": e })),
+    };
+    request.respond(response).ok();
+}
+
+fn handle_metrics(request: Request, db: &Database, pomodoros: &PomodoroCounter) {
+    let now = chrono::Utc::now().timestamp();
+    let response = match metrics::render(db, pomodoros, local_day_start(), now) {
+        Ok(text) => Response::from_string(text).with_header(
+            Header::from_bytes("Content-Type", "text/plain; version=0.0.4").expect("static header"),
+        ),
+        Err(e) => Response::from_string(e.to_string()).with_status_code(500),
+    };
+    request.respond(response).ok();
+}
+
+fn handle_request(
+    mut request: Request,
+    db: &Database,
+    settings_service: &SettingsService,
+    data_sources: &DataSourceRegistry,
+    pomodoros: &PomodoroCounter,
+) {
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    let settings = settings_service.get();
+    if *request.method() == Method::Get && path == "/metrics" && settings.metrics_enabled {
+        handle_metrics(request, db, pomodoros);
+        return;
+    }
+    if !settings.ide_heartbeats_enabled {
+        request.respond(json_response(404, serde_json::json!({ "error": "Not found" }))).ok();
+        return;
+    }
+    if !is_authorized(&request, settings_service) {
+        request.respond(json_response(401, serde_json::json!({ "error": "Missing or invalid API key" }))).ok();
+        return;
+    }
+    if *request.method() == Method::Post && path == "/terminal" {
+        handle_terminal_report(request, data_sources);
+        return;
+    }
+    let is_bulk = path.ends_with("/heartbeats.bulk");
+    if *request.method() != Method::Post || !(is_bulk || path.ends_with("/heartbeats")) {
+        request.respond(json_response(404, serde_json::json!({ "error": "Not found" }))).ok();
+        return;
+    }
+
+    let user_agent = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("User-Agent"))
+        .map(|h| h.value.as_str().to_string());
+
+    let body = match read_body(&mut request) {
+        Ok(body) => body,
+        Err(e) => {
+            request.respond(json_response(400, serde_json::json!({ "error": e }))).ok();
+            return;
+        }
+    };
+
+    // Single endpoint takes one object, bulk endpoint an array
+    let parsed: Result<Vec<WakaTimeHeartbeat>, _> = if is_bulk {
+        serde_json::from_str(&body)
+    } else {
+        serde_json::from_str(&body).map(|hb| vec![hb])
+    };
+    let heartbeats: Vec<EditorHeartbeat> = match parsed {
+        Ok(items) => items.into_iter().map(|hb| to_heartbeat(hb, user_agent.as_deref())).collect(),
+        Err(e) => {
+            request.respond(json_response(400, serde_json::json!({ "error": e.to_string() }))).ok();
+            return;
+        }
+    };
+
+    if let Err(e) = db.insert_editor_heartbeats(&heartbeats) {
+        eprintln!("Warning: Failed to store editor heartbeats: {}", e);
+        request.respond(json_response(500, serde_json::json!({ "error": e.to_string() }))).ok();
+        return;
+    }
+    let queue = data_sources.queue(SOURCE_IDE);
+    for hb in &heartbeats {
+        queue.push(to_sample(hb));
+    }
+
+    let response = if is_bulk {
+        let responses: Vec<serde_json::Value> = heartbeats
+            .iter()
+            .map(|_| serde_json::json!([{ "data": null }, 201]))
+            .collect();
+        serde_json::json!({ "responses": responses })
+    } else {
+        serde_json::json!({ "data": null })
+    };
+    request.respond(json_response(201, response)).ok();
+}
+
+/// Running listener; dropping it stops the server thread
+struct Listener {
+    server: Arc<Server>,
+}
+
+impl Listener {
+    fn bind(
+        port: u16,
+        db: Arc<Database>,
+        settings: Arc<SettingsService>,
+        data_sources: Arc<DataSourceRegistry>,
+        pomodoros: Arc<PomodoroCounter>,
+    ) -> Option<Self> {
+        let server = match Server::http(("127.0.0.1", port)) {
+            Ok(server) => Arc::new(server),
+            Err(e) => {
+                eprintln!("Warning: Failed to start IDE heartbeat listener on port {}: {}", port, e);
+                return None;
+            }
+        };
+        let server_for_thread = Arc::clone(&server);
+        std::thread::spawn(move || {
+            for request in server_for_thread.incoming_requests() {
+                handle_request(request, &db, &settings, &data_sources, &pomodoros);
+            }
+        });
+        Some(Self { server })
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        self.server.unblock();
+    }
+}
+
+fn configured_port(settings: &SettingsService) -> Option<u16> {
+    let settings = settings.get();
+    (settings.ide_heartbeats_enabled || settings.metrics_enabled).then_some(settings.ide_heartbeats_port)
+}
+
+/// Start the heartbeat receiver supervisor (idle unless enabled in settings).
+/// It follows the settings as they change; a port that cannot be bound is
+/// retried every `http_api::BIND_RETRY_SECS`.
+pub fn start(
+    db: Arc<Database>,
+    settings: Arc<SettingsService>,
+    data_sources: Arc<DataSourceRegistry>,
+    pomodoros: Arc<PomodoroCounter>,
+) {
+    let changes = settings.watch(&["ide_heartbeats_enabled", "ide_heartbeats_port", "metrics_enabled"]);
+    std::thread::spawn(move || {
+        let mut active_port: Option<u16> = None;
+        let mut listener: Option<Listener> = None;
+        loop {
+            let port = configured_port(&settings);
+            if port != active_port || listener.is_none() {
+                // Stop the old listener first so the port can be reused
+                drop(listener.take());
+                listener = port.and_then(|port| {
+                    Listener::bind(
+                        port,
+                        Arc::clone(&db),
+                        Arc::clone(&settings),
+                        Arc::clone(&data_sources),
+                        Arc::clone(&pomodoros),
+                    )
+                });
+                active_port = port;
+            }
+
+            // Wait for a settings change; retry a failed bind meanwhile
+            let woken = if port.is_some() && listener.is_none() {
+                changes.recv_timeout(Duration::from_secs(http_api::BIND_RETRY_SECS))
+            } else {
+                changes.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            };
+            if let Err(RecvTimeoutError::Disconnected) = woken {
+                break;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokens_are_read_from_bearer_and_basic_auth() {
+        assert_eq!(authorization_token("Bearer abc123").as_deref(), Some("abc123"));
+        // wakatime-cli sends base64(api_key)
+        let basic = format!("Basic {}", base64::engine::general_purpose::STANDARD.encode("abc123"));
+        assert_eq!(authorization_token(&basic).as_deref(), Some("abc123"));
+        let with_password = format!("Basic {}", base64::engine::general_purpose::STANDARD.encode("abc123:"));
+        assert_eq!(authorization_token(&with_password).as_deref(), Some("abc123"));
+        assert_eq!(authorization_token("Basic not-base64!"), None);
+        assert_eq!(authorization_token("abc123"), None);
+    }
+}
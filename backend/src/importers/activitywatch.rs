@@ -0,0 +1,177 @@
+//! ActivityWatch import/export
+//!
+//! Uses the bucket/event JSON of ActivityWatch's export (`/api/0/export`):
+//! `{"buckets": {"<id>": {"type": ..., "events": [{"timestamp", "duration", "data"}]}}}`.
+//! Import reads `currentwindow` buckets (`data.app`, `data.title`) and drops
+//! time covered by `afkstatus` events with status `afk`. Export writes a window
+//! bucket for active time and an AFK bucket covering all activities. Buckets
+//! name the host they were recorded on, which imported rows keep.
+
+use crate::database::{Activity, ImportedActivity};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const BUCKET_TYPE_WINDOW: &str = "currentwindow";
+const BUCKET_TYPE_AFK: &str = "afkstatus";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Export {
+    buckets: BTreeMap<String, Bucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Bucket {
+    id: String,
+    #[serde(rename = "type")]
+    bucket_type: String,
+    client: String,
+    hostname: String,
+    created: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Event {
+    timestamp: String,
+    /// Seconds
+    duration: f64,
+    data: serde_json::Value,
+}
+
+/// Parse an event into a (start, end) range of Unix seconds
+fn event_range(event: &Event) -> Option<(i64, i64)> {
+    let start = DateTime::parse_from_rfc3339(&event.timestamp).ok()?.timestamp();
+    Some((start, start + event.duration.round() as i64))
+}
+
+/// Remove the parts of `range` covered by the sorted `gaps`
+fn subtract_ranges(range: (i64, i64), gaps: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut pieces = Vec::new();
+    let mut cursor = range.0;
+    for &(gap_start, gap_end) in gaps {
+        if gap_end <= cursor || gap_start >= range.1 {
+            continue;
+        }
+        if gap_start > cursor {
+            pieces.push((cursor, gap_start));
+        }
+        cursor = cursor.max(gap_end);
+    }
+    if cursor < range.1 {
+        pieces.push((cursor, range.1));
+    }
+    pieces
+}
+
+/// Read window activity from an ActivityWatch export file
+pub fn read(path: &Path) -> Result<Vec<ImportedActivity>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let export: Export = serde_json::from_str(&contents)
+        .map_err(|e| format!("Invalid ActivityWatch export: {}", e))?;
+
+    let mut afk: Vec<(i64, i64)> = export
+        .buckets
+        .values()
+        .filter(|b| b.bucket_type == BUCKET_TYPE_AFK)
+        .flat_map(|b| &b.events)
+        .filter(|e| e.data.get("status").and_then(|s| s.as_str()) == Some("afk"))
+        .filter_map(event_range)
+        .collect();
+    afk.sort_unstable();
+
+    let mut rows: Vec<ImportedActivity> = Vec::new();
+    for (bucket, event) in export
+        .buckets
+        .values()
+        .filter(|b| b.bucket_type == BUCKET_TYPE_WINDOW)
+        .flat_map(|b| b.events.iter().map(move |e| (b, e)))
+    {
+        let Some(app_name) = event.data.get("app").and_then(|a| a.as_str()).filter(|a| !a.is_empty()) else {
+            continue;
+        };
+        let window_title = event
+            .data
+            .get("title")
+            .and_then(|t| t.as_str())
+            .filter(|t| !t.is_empty())
+            .map(str::to_string);
+        let Some(range) = event_range(event) else {
+            continue;
+        };
+        for (start, end) in subtract_ranges(range, &afk) {
+            rows.push(ImportedActivity {
+                app_name: app_name.to_string(),
+                window_title: window_title.clone(),
+                started_at: start,
+                duration_sec: end - start,
+                hostname: Some(bucket.hostname.clone()).filter(|h| !h.is_empty()),
+            });
+        }
+    }
+    rows.sort_by_key(|r| r.started_at);
+    Ok(rows)
+}
+
+fn to_rfc3339(timestamp: i64) -> String {
+    Utc.timestamp_opt(timestamp, 0)
+        .single()
+        .unwrap_or_default()
+        .to_rfc3339()
+}
+
+/// Build an ActivityWatch export of the given activities
+pub fn export(activities: &[Activity], hostname: &str) -> serde_json::Value {
+    let created = Utc::now().to_rfc3339();
+    let window_id = format!("aw-watcher-window_{}", hostname);
+    let afk_id = format!("aw-watcher-afk_{}", hostname);
+
+    let window_events = activities
+        .iter()
+        .filter(|a| !a.is_idle)
+        .map(|a| Event {
+            timestamp: to_rfc3339(a.started_at),
+            duration: a.duration_sec as f64,
+            data: serde_json::json!({
+                "app": a.app_name,
+                "title": a.window_title.clone().unwrap_or_default(),
+            }),
+        })
+        .collect();
+    let afk_events = activities
+        .iter()
+        .map(|a| Event {
+            timestamp: to_rfc3339(a.started_at),
+            duration: a.duration_sec as f64,
+            data: serde_json::json!({ "status": if a.is_idle { "afk" } else { "not-afk" } }),
+        })
+        .collect();
+
+    let mut buckets = BTreeMap::new();
+    buckets.insert(
+        window_id.clone(),
+        Bucket {
+            id: window_id,
+            bucket_type: BUCKET_TYPE_WINDOW.to_string(),
+            client: "aw-watcher-window".to_string(),
+            hostname: hostname.to_string(),
+            created: created.clone(),
+            events: window_events,
+        },
+    );
+    buckets.insert(
+        afk_id.clone(),
+        Bucket {
+            id: afk_id,
+            bucket_type: BUCKET_TYPE_AFK.to_string(),
+            client: "aw-watcher-afk".to_string(),
+            hostname: hostname.to_string(),
+            created,
+            events: afk_events,
+        },
+    );
+    serde_json::to_value(Export { buckets }).unwrap_or_default()
+}
@@ -0,0 +1,40 @@
+//! Importers for OS-level and third-party app usage histories
+//!
+//! Each importer reads a usage database or export file and returns
+//! activities for `Database::import_activities`. Imported rows are
+//! stored with an `import:<format>` source so they can be told apart from
+//! tracked time.
+//! - screen_time: macOS Screen Time (`knowledgeC.db`)
+//! - windows_timeline: Windows Activity History (`ActivitiesCache.db`)
+//! - activitywatch: ActivityWatch bucket/event JSON export (also used for export)
+
+pub mod activitywatch;
+pub mod screen_time;
+pub mod windows_timeline;
+
+use crate::database::ImportedActivity;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+/// Supported import formats
+pub const IMPORT_FORMATS: &[&str] = &["screen_time", "windows_timeline", "activitywatch"];
+
+/// Read activities from a usage database or export file in the given format
+pub fn read_usage_history(format: &str, path: &Path) -> Result<Vec<ImportedActivity>, String> {
+    let read = match format {
+        "activitywatch" => return activitywatch::read(path),
+        "screen_time" => screen_time::read,
+        "windows_timeline" => windows_timeline::read,
+        _ => {
+            return Err(format!(
+                "Unsupported import format: {} (expected one of: {})",
+                format,
+                IMPORT_FORMATS.join(", ")
+            ))
+        }
+    };
+
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    read(&conn).map_err(|e| format!("Failed to read {} history: {}", format, e))
+}
@@ -0,0 +1,43 @@
+//! macOS Screen Time importer
+//!
+//! Reads app usage from a copy of `~/Library/Application Support/Knowledge/knowledgeC.db`.
+//! Foreground app usage is stored in `ZOBJECT` rows with stream `/app/usage`;
+//! timestamps are Core Data seconds since 2001-01-01 UTC.
+
+use crate::database::ImportedActivity;
+use rusqlite::Connection;
+
+/// Seconds between the Unix epoch and the Core Data epoch (2001-01-01)
+const CORE_DATA_EPOCH_OFFSET: i64 = 978_307_200;
+
+/// Turn a bundle identifier into an app name (`com.apple.Safari` -> `Safari`)
+fn app_name_from_bundle_id(bundle_id: &str) -> String {
+    bundle_id.rsplit('.').next().unwrap_or(bundle_id).to_string()
+}
+
+pub fn read(conn: &Connection) -> rusqlite::Result<Vec<ImportedActivity>> {
+    let mut stmt = conn.prepare(
+        "SELECT ZVALUESTRING, ZSTARTDATE, ZENDDATE
+         FROM ZOBJECT
+         WHERE ZSTREAMNAME = '/app/usage' AND ZVALUESTRING IS NOT NULL AND ZENDDATE > ZSTARTDATE
+         ORDER BY ZSTARTDATE ASC",
+    )?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            let bundle_id: String = row.get(0)?;
+            let start: f64 = row.get(1)?;
+            let end: f64 = row.get(2)?;
+            let started_at = start as i64 + CORE_DATA_EPOCH_OFFSET;
+            Ok(ImportedActivity {
+                app_name: app_name_from_bundle_id(&bundle_id),
+                window_title: None,
+                started_at,
+                duration_sec: (end - start).round() as i64,
+                hostname: None,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(rows)
+}
@@ -0,0 +1,63 @@
+//! Windows Activity History importer
+//!
+//! Reads app usage from a copy of
+//! `%LOCALAPPDATA%\ConnectedDevicesPlatform\<account>\ActivitiesCache.db`.
+//! "App in use" records (`ActivityType = 6`) have Unix start/end times and an
+//! `AppId` JSON array of platform-specific identifiers.
+
+use crate::database::ImportedActivity;
+use rusqlite::Connection;
+
+/// Activity type for "app in use / in focus" records
+const ACTIVITY_TYPE_IN_FOCUS: i64 = 6;
+
+/// Pick an app name from the AppId JSON, preferring the executable name
+/// (matching what the tracker records on Windows, e.g. `chrome.exe`)
+fn app_name_from_app_id(app_id: &str) -> Option<String> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(app_id).ok()?;
+    let application = |platform: &str| {
+        entries
+            .iter()
+            .find(|e| e.get("platform").and_then(|p| p.as_str()) == Some(platform))
+            .and_then(|e| e.get("application").and_then(|a| a.as_str()))
+    };
+
+    if let Some(path) = application("x_exe_path") {
+        return path.rsplit(['\\', '/']).next().map(str::to_string);
+    }
+    application("windows_win32")
+        .or_else(|| application("windows_universal"))
+        .or_else(|| entries.first().and_then(|e| e.get("application").and_then(|a| a.as_str())))
+        .map(str::to_string)
+}
+
+pub fn read(conn: &Connection) -> rusqlite::Result<Vec<ImportedActivity>> {
+    let mut stmt = conn.prepare(
+        "SELECT AppId, StartTime, EndTime
+         FROM Activity
+         WHERE ActivityType = ? AND EndTime > StartTime
+         ORDER BY StartTime ASC",
+    )?;
+
+    let rows = stmt
+        .query_map([ACTIVITY_TYPE_IN_FOCUS], |row| {
+            let app_id: String = row.get(0)?;
+            let started_at: i64 = row.get(1)?;
+            let ended_at: i64 = row.get(2)?;
+            Ok((app_id, started_at, ended_at))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(rows
+        .into_iter()
+        .filter_map(|(app_id, started_at, ended_at)| {
+            Some(ImportedActivity {
+                app_name: app_name_from_app_id(&app_id)?,
+                window_title: None,
+                started_at,
+                duration_sec: ended_at - started_at,
+                hostname: None,
+            })
+        })
+        .collect())
+}
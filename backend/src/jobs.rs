@@ -0,0 +1,214 @@
+//! Maintenance jobs
+//!
+//! Long-running maintenance (domain backfill, retention pruning) runs as jobs
+//! in the `jobs` table instead of on ad-hoc threads. A job kind does one
+//! bounded step at a time and returns the cursor the next step continues
+//! from. The runner saves cursor and progress after every step, so a
+//! cancellation takes effect between steps and a job interrupted by quitting
+//! the app resumes where it stopped on the next start. Steps must be safe to
+//! repeat, since a quit can land between a step's writes and its saved cursor.
+
+use crate::database::jobs::{JOB_CANCELLED, JOB_COMPLETED, JOB_FAILED, JOB_QUEUED, JOB_RUNNING};
+use crate::database::{Database, Job};
+use crate::tracker::extract_domain;
+use serde_json::{json, Value};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Fill in missing activity domains from window titles; params `{start, end}`
+pub const JOB_DOMAIN_BACKFILL: &str = "domain_backfill";
+/// Delete activities that ended before `params.before` (see `retention`)
+pub const JOB_RETENTION_PURGE: &str = "retention_purge";
+
+/// Activities read per domain backfill step
+const DOMAIN_BACKFILL_BATCH: i64 = 500;
+
+/// How often the runner looks for queued jobs when nobody wakes it
+const POLL_INTERVAL_SECS: u64 = 60;
+
+/// Outcome of one job step
+pub struct JobStep {
+    /// Where the next step continues
+    pub cursor: Value,
+    /// Items handled by this step
+    pub processed: i64,
+    /// Items to process, if the step found out
+    pub total: Option<i64>,
+    pub done: bool,
+}
+
+fn param(params: &Value, key: &str) -> Result<i64, String> {
+    params[key].as_i64().ok_or_else(|| format!("Missing job parameter: {}", key))
+}
+
+/// Domain backfill step; the cursor holds the last activity ID seen and the
+/// number of domains filled in so far
+fn domain_backfill_step(db: &Database, params: &Value, cursor: Option<&Value>) -> Result<JobStep, String> {
+    let (start, end) = (param(params, "start")?, param(params, "end")?);
+    let after_id = cursor.and_then(|c| c["after_id"].as_i64()).unwrap_or(0);
+    let updated = cursor.and_then(|c| c["updated"].as_i64()).unwrap_or(0);
+    let total = match cursor {
+        Some(_) => None,
+        None => Some(db.count_activities_without_domain(start, end).map_err(|e| e.to_string())?),
+    };
+
+    let batch = db
+        .get_activities_without_domain(start, end, after_id, DOMAIN_BACKFILL_BATCH)
+        .map_err(|e| e.to_string())?;
+    let domains: Vec<(i64, String)> = batch
+        .iter()
+        .filter_map(|(id, app_name, title)| extract_domain(app_name, Some(title)).map(|domain| (*id, domain)))
+        .collect();
+    let updated = updated + db.set_activity_domains(&domains).map_err(|e| e.to_string())? as i64;
+    Ok(JobStep {
+        cursor: json!({
+            "after_id": batch.last().map_or(after_id, |(id, _, _)| *id),
+            "updated": updated,
+        }),
+        processed: batch.len() as i64,
+        total,
+        done: (batch.len() as i64) < DOMAIN_BACKFILL_BATCH,
+    })
+}
+
+/// Run the next step of a job
+fn step(db: &Database, job: &Job) -> Result<JobStep, String> {
+    match job.kind.as_str() {
+        JOB_DOMAIN_BACKFILL => domain_backfill_step(db, &job.params, job.cursor.as_ref()),
+        JOB_RETENTION_PURGE => crate::retention::purge_step(db, &job.params),
+        kind => Err(format!("Unknown job kind: {}", kind)),
+    }
+}
+
+/// Run a job until it completes, fails or is cancelled, calling `on_progress`
+/// after every step. Returns `false` if the job's state could not be saved.
+pub(crate) fn run(db: &Database, job_id: i64, on_progress: &dyn Fn(&Job)) -> bool {
+    loop {
+        let job = match db.get_job(job_id) {
+            Ok(Some(job)) if job.status == JOB_QUEUED || job.status == JOB_RUNNING => job,
+            Ok(_) => return true,
+            Err(e) => {
+                eprintln!("Warning: Failed to load job {}: {}", job_id, e);
+                return false;
+            }
+        };
+        let finished = if job.cancel_requested {
+            Some((JOB_CANCELLED, None))
+        } else {
+            match step(db, &job) {
+                Ok(step) => {
+                    let total = step.total.or(job.total);
+                    if let Err(e) = db.save_job_progress(job_id, &step.cursor, job.processed + step.processed, total) {
+                        eprintln!("Warning: Failed to save progress of job {}: {}", job_id, e);
+                        return false;
+                    }
+                    step.done.then_some((JOB_COMPLETED, None))
+                }
+                Err(e) => Some((JOB_FAILED, Some(e))),
+            }
+        };
+        if let Some((status, error)) = &finished {
+            if let Err(e) = db.finish_job(job_id, status, error.as_deref()) {
+                eprintln!("Warning: Failed to finish job {}: {}", job_id, e);
+                return false;
+            }
+        }
+        if let Ok(Some(job)) = db.get_job(job_id) {
+            on_progress(&job);
+        }
+        if finished.is_some() {
+            return true;
+        }
+    }
+}
+
+/// Queue of maintenance jobs, run one at a time on a background thread
+pub struct JobRunner {
+    db: Arc<Database>,
+    /// Wakes the runner when a job is queued
+    wake: Mutex<Option<Sender<()>>>,
+}
+
+impl JobRunner {
+    /// Create a runner; queued jobs wait until `start`
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            wake: Mutex::new(None),
+        }
+    }
+
+    /// Queue a job
+    pub fn submit(&self, kind: &str, params: Value) -> Result<Job, String> {
+        let id = self.db.insert_job(kind, &params).map_err(|e| e.to_string())?;
+        if let Some(wake) = self.wake.lock().unwrap().as_ref() {
+            wake.send(()).ok();
+        }
+        self.db
+            .get_job(id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Job not found".to_string())
+    }
+
+    /// Queue a job unless one of the same kind is queued or running
+    pub fn submit_once(&self, kind: &str, params: Value) -> Result<Option<Job>, String> {
+        if self.db.has_active_job(kind).map_err(|e| e.to_string())? {
+            return Ok(None);
+        }
+        self.submit(kind, params).map(Some)
+    }
+
+    /// Start running queued jobs, including the ones a quit interrupted;
+    /// `on_progress` gets the job after every step
+    pub fn start<F>(&self, on_progress: F)
+    where
+        F: Fn(&Job) + Send + 'static,
+    {
+        let (wake, woken) = channel();
+        *self.wake.lock().unwrap() = Some(wake);
+        let db = Arc::clone(&self.db);
+        std::thread::spawn(move || loop {
+            loop {
+                match db.next_job() {
+                    Ok(Some(job)) if run(&db, job.id, &on_progress) => {}
+                    Ok(_) => break,
+                    Err(e) => {
+                        eprintln!("Warning: Failed to load queued jobs: {}", e);
+                        break;
+                    }
+                }
+            }
+            match woken.recv_timeout(Duration::from_secs(POLL_INTERVAL_SECS)) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn interrupted_jobs_resume_and_stop_when_cancelled() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let id = db.insert_job(JOB_RETENTION_PURGE, &json!({ "before": 0 })).unwrap();
+        // A quit after the first step leaves the job running with a saved cursor
+        db.save_job_progress(id, &json!({}), 3, None).unwrap();
+        assert_eq!(db.next_job().unwrap().map(|job| job.id), Some(id));
+
+        assert!(db.request_job_cancel(id).unwrap());
+        assert!(run(&db, id, &|_| {}));
+        let job = db.get_job(id).unwrap().unwrap();
+        assert_eq!((job.status.as_str(), job.processed), (JOB_CANCELLED, 3));
+        assert!(!db.request_job_cancel(id).unwrap());
+
+        let id = db.insert_job("unknown", &json!({})).unwrap();
+        assert!(run(&db, id, &|_| {}));
+        assert_eq!(db.get_job(id).unwrap().unwrap().status, JOB_FAILED);
+        assert!(db.next_job().unwrap().is_none());
+    }
+}
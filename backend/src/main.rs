@@ -1,33 +1,92 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod app_metrics;
 mod autostart;
+mod boundaries;
+mod checkins;
+mod classifier;
+mod colors;
 mod commands;
+mod context;
+mod daily_note;
+mod data_sources;
 mod database;
+mod demo_data;
+mod device;
+mod entry_parser;
+mod events;
+mod export_archive;
+mod focus_blocker;
+mod git_activity;
+mod http_api;
+mod ide;
 mod idle;
+mod importers;
+mod jobs;
+mod metrics;
+mod micro_breaks;
+mod parquet_export;
 mod plugin_system;
+mod presence;
+mod profiles;
+mod replay;
+mod report;
+mod report_cache;
+mod retention;
+mod rounding;
+mod settings;
+mod shutdown;
+mod single_instance;
+mod templates;
+mod terminal;
+mod timeline;
 mod tracker;
 mod tray;
+mod updates;
+mod webhooks;
+mod wellness;
 mod window;
+mod workspace;
 
 use commands::AppState;
-use database::Database;
 use plugin_system::{PluginRegistry, ExtensionRegistry};
+use database::storage::Storage;
 use plugin_system::loader::PluginLoader;
 use std::sync::{Arc, Mutex};
 use tauri::Manager;
 
 fn main() {
+    // Only one instance may track; a second launch just shows the first one's window
+    let instance_lock = match single_instance::acquire() {
+        single_instance::Instance::Primary(lock) => Some(lock),
+        single_instance::Instance::Secondary => return,
+        single_instance::Instance::Unguarded => None,
+    };
+
     // Get data directory
-    let data_dir = dirs::data_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("timetracker");
+    let data_dir = workspace::data_dir();
+
+    // Initialize database of the active workspace (salvaged first if corrupted)
+    let db_path = workspace::active_db_path();
+    let (db, startup_recovery) =
+        database::recovery::open_with_recovery(db_path).expect("Failed to initialize database");
+    let db = Arc::new(db);
 
-    // Initialize database
-    let db_path = data_dir.join("data.db");
-    let db = Arc::new(
-        Database::new(db_path).expect("Failed to initialize database"),
-    );
+    // Attribute new activities to this install (older ones are claimed for it)
+    if let Err(e) = db.set_device(device::load_or_create(&data_dir.join("device.json"))) {
+        eprintln!("Failed to set device: {}", e);
+    }
+
+    // Initialize event bus; webhooks queue deliveries for matching events
+    let event_bus = Arc::new(events::EventBus::new());
+    webhooks::register(&event_bus, Arc::clone(&db));
+    if let Some(recovery) = &startup_recovery {
+        event_bus.emit(
+            events::EVENT_DATABASE_RECOVERED,
+            serde_json::to_value(recovery).unwrap_or_default(),
+        );
+    }
 
     // Initialize plugin system
     let extension_registry = Arc::new(ExtensionRegistry::new());
@@ -36,16 +95,30 @@ fn main() {
     // Get plugins directory
     let plugins_dir = data_dir.join("plugins");
     let plugin_loader = PluginLoader::new(plugins_dir);
+    // Installs interrupted by a crash leave their staging directory behind
+    plugin_loader.cleanup_staging();
 
     // Create app state
     let plugin_loader_arc = Arc::new(plugin_loader);
     let app_state = AppState {
         db: Arc::clone(&db),
+        storage: Arc::clone(&db) as Arc<dyn Storage>,
         tracker: Arc::new(Mutex::new(None)),
         thinking_mode_entry_id: Arc::new(Mutex::new(None)),
+        pause_entry: Arc::new(Mutex::new(None)),
         plugin_registry: Some(Arc::clone(&plugin_registry)),
         extension_registry: Some(Arc::clone(&extension_registry)),
         plugin_loader: Some(Arc::clone(&plugin_loader_arc)),
+        plugin_index: Arc::new(plugin_system::PluginIndex::load(data_dir.join("plugin_index.json"))),
+        week_cache: report_cache::WeekCache::subscribe(&event_bus),
+        jobs: Arc::new(jobs::JobRunner::new(Arc::clone(&db))),
+        event_bus: Arc::clone(&event_bus),
+        data_sources: Arc::new(data_sources::DataSourceRegistry::new()),
+        focus_blocker: Arc::new(focus_blocker::FocusBlocker::new()),
+        pomodoro_status: Arc::new(Mutex::new(None)),
+        settings: Arc::new(settings::SettingsService::new(Arc::clone(&db))),
+        startup_recovery,
+        replay: Arc::new(replay::ReplayRunner::new()),
     };
 
     // Build Tauri application
@@ -57,42 +130,61 @@ fn main() {
         })
         .on_window_event(|event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event.event() {
+                // Secondary windows (mini timer, idle prompt) just close
+                if event.window().label() != "main" {
+                    return;
+                }
                 // Check if minimize to tray is enabled
                 if let Some(state) = event.window().app_handle().try_state::<commands::AppState>() {
-                    if let Ok(Some(minimize_to_tray)) = state.db.get_setting("minimize_to_tray")
-                        .map(|v: Option<String>| v.map(|s: String| s == "true"))
-                    {
-                        if minimize_to_tray {
-                            // Hide window instead of closing
-                            event.window().hide().ok();
-                            api.prevent_close();
-                            return;
-                        }
+                    if state.settings.get().minimize_to_tray {
+                        // Hide window instead of closing
+                        event.window().hide().ok();
+                        api.prevent_close();
+                        return;
                     }
                     
-                    // Stop tracker before closing
-                    if let Ok(Some(tracker)) = state.tracker.lock().map(|t| t.clone()) {
-                        tracker.stop();
-                    }
+                    // Flush tracker, timers and plugins before closing
+                    shutdown::run(&state);
                 }
             }
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_activities,
+            commands::get_activities_page,
+            commands::get_timeline,
             commands::get_activity,
             commands::update_activity_category,
+            commands::set_billable_override,
             commands::delete_activity,
             commands::reapply_categorization_rules,
+            commands::get_last_rule_run_report,
+            commands::undo_last_rule_run,
             commands::get_categories,
             commands::create_category,
             commands::update_category,
             commands::delete_category,
             commands::reset_system_category,
+            commands::archive_category,
+            commands::unarchive_category,
+            commands::suggest_category_color,
+            commands::check_category_color,
             commands::get_rules,
             commands::add_rule,
             commands::create_rule,
+            commands::find_similar_rule,
             commands::update_rule,
             commands::delete_rule,
+            commands::export_rules,
+            commands::import_rules,
+            commands::get_task_hints,
+            commands::add_task_hint,
+            commands::delete_task_hint,
+            commands::get_category_suggestions,
+            commands::accept_category_suggestions,
+            commands::find_similar_activities,
+            commands::apply_category_to_activities,
+            commands::get_uncategorized_summary,
+            commands::categorize_group,
             commands::add_manual_entry,
             commands::create_manual_entry,
             commands::update_manual_entry,
@@ -100,8 +192,25 @@ fn main() {
             commands::get_manual_entries,
             commands::start_manual_entry,
             commands::stop_manual_entry,
+            commands::parse_time_entry,
             commands::submit_idle_activity,
+            commands::classify_idle_segments,
+            commands::dismiss_idle_prompt,
+            // Approval commands
+            commands::submit_entries_for_approval,
+            commands::approve_entries,
+            commands::reject_entries,
+            commands::get_entry_approvals,
+            // Rounding commands
+            commands::preview_rounding,
             commands::get_today_total,
+            // Custom field commands
+            commands::get_custom_field_defs,
+            commands::create_custom_field_def,
+            commands::update_custom_field_def,
+            commands::delete_custom_field_def,
+            commands::get_custom_field_values,
+            commands::set_custom_field_value,
             commands::get_setting,
             commands::set_setting,
             commands::get_settings,
@@ -110,11 +219,20 @@ fn main() {
             commands::disable_autostart,
             commands::is_autostart_enabled,
             commands::get_stats,
+            commands::get_devices,
             commands::get_daily_stats,
             commands::get_top_apps,
             commands::get_category_usage,
             commands::get_hourly_activity,
             commands::get_productive_time,
+            commands::get_coding_stats,
+            commands::get_input_intensity,
+            commands::get_wellness_stats,
+            commands::get_boundary_report,
+            // Dashboard commands
+            commands::get_dashboard,
+            commands::get_dashboard_kpis,
+            commands::set_dashboard_kpis,
             commands::pause_tracking,
             commands::resume_tracking,
             commands::get_tracking_status,
@@ -125,14 +243,85 @@ fn main() {
             commands::classify_idle_time,
             commands::export_to_csv,
             commands::export_to_json,
+            commands::export_to_activitywatch,
+            commands::export_parquet,
+            commands::write_daily_note,
+            commands::get_templates,
+            commands::get_template,
+            commands::save_template,
+            commands::reset_template,
+            commands::publish_report,
+            commands::anonymize_copy,
+            commands::import_os_usage,
             commands::show_main_window,
             commands::hide_main_window,
             commands::show_idle_prompt,
+            commands::show_mini_timer,
+            commands::hide_mini_timer,
+            commands::get_pomodoro_status,
             // Domain commands
             commands::get_top_domains,
+            commands::get_top_contexts,
+            commands::backfill_domains,
+            commands::get_domain_ratings,
+            commands::set_domain_rating,
+            commands::delete_domain_rating,
+            // Git activity commands
+            commands::get_git_repositories,
+            commands::set_git_repositories,
+            commands::get_commit_timeline,
+            // Terminal commands
+            commands::report_terminal_activity,
+            commands::start_replay,
+            commands::stop_replay,
+            commands::get_replay_status,
+            commands::generate_demo_data,
+            // Focus blocklist commands
+            commands::get_blocklist,
+            commands::add_blocklist_entry,
+            commands::delete_blocklist_entry,
+            commands::get_focus_blocking_status,
+            // Check-in commands
+            commands::submit_checkin,
+            commands::get_checkins,
+            commands::get_checkin_stats,
+            commands::finish_micro_break,
+            commands::get_micro_break_stats,
+            // Mood journal commands
+            commands::log_mood,
+            commands::get_mood_entries,
+            commands::delete_mood_entry,
+            // Daily plan commands
+            commands::get_daily_plan,
+            commands::get_daily_plans,
+            commands::save_daily_plan,
+            commands::delete_daily_plan,
+            commands::get_daily_plan_review,
+            // Settings profile commands
+            commands::get_profiles,
+            commands::save_profile,
+            commands::delete_profile,
+            commands::switch_profile,
+            commands::get_active_profile,
+            // Workspace commands
+            commands::get_workspaces,
+            commands::switch_workspace,
+            // Diagnostics commands
+            commands::get_startup_diagnostics,
+            commands::get_diagnostics,
+            // Maintenance job commands
+            commands::list_jobs,
+            commands::cancel_job,
+            // Update commands
+            commands::check_for_updates,
+            // App usage commands
+            commands::get_app_usage,
+            commands::clear_app_usage,
             // Plugin commands
             commands::get_plugin_registry,
             commands::search_plugins,
+            commands::get_plugin_categories,
+            commands::refresh_plugin_index,
             commands::get_plugin_info,
             commands::discover_plugin,
             commands::install_plugin,
@@ -143,6 +332,8 @@ fn main() {
             commands::load_plugin,
             commands::unload_plugin,
             commands::invoke_plugin_command,
+            commands::get_plugin_metrics,
+            commands::reset_plugin_metrics,
             commands::is_plugin_installed,
             commands::get_plugin,
             commands::get_plugin_ids,
@@ -150,29 +341,67 @@ fn main() {
             commands::get_plugins_directory,
             commands::check_plugin_installed,
             commands::get_plugin_manifest_path,
+            // Webhook commands
+            commands::get_webhooks,
+            commands::create_webhook,
+            commands::update_webhook,
+            commands::delete_webhook,
+            commands::get_webhook_deliveries,
+            commands::test_webhook,
+            // REST API commands
+            commands::get_http_api_info,
+            commands::reset_http_api_token,
         ])
         .setup(move |app| {
             let app_handle = app.handle();
+
+            // Show the main window when another launch is attempted
+            if let Some(lock) = instance_lock {
+                let app_handle_for_instance = app_handle.clone();
+                single_instance::listen(lock, move || {
+                    if let Some(window) = app_handle_for_instance.get_window("main") {
+                        window.show().ok();
+                        window.unminimize().ok();
+                        window.set_focus().ok();
+                    }
+                });
+            }
             let db_clone = Arc::clone(&db);
 
-            // Start the tracker in a background thread (extension_registry for plugin data hooks)
+            // Start the tracker in a background thread (plugin registries for write and data hooks)
             let extension_registry_for_tracker = app.state::<commands::AppState>().extension_registry.clone();
-            let tracker = Arc::new(tracker::Tracker::new(Arc::clone(&db_clone), extension_registry_for_tracker));
+            let plugin_registry_for_tracker = app.state::<commands::AppState>().plugin_registry.clone();
+            let data_sources = Arc::clone(&app.state::<commands::AppState>().data_sources);
+            let tracker = Arc::new(tracker::Tracker::new(
+                Arc::clone(&db_clone),
+                extension_registry_for_tracker,
+                plugin_registry_for_tracker,
+                data_sources,
+                Arc::clone(&app.state::<commands::AppState>().event_bus),
+            ));
             
             // Load settings from database and apply to tracker
-            if let Ok(settings) = db_clone.get_all_settings() {
-                let idle_threshold_secs = settings
-                    .get("idle_threshold_seconds")
-                    .and_then(|v| v.parse::<u64>().ok())
-                    .unwrap_or(120);
-                let prompt_threshold_secs = settings
-                    .get("idle_prompt_threshold_seconds")
-                    .and_then(|v| v.parse::<u64>().ok())
-                    .unwrap_or(300);
-                
-                tracker.set_idle_threshold(idle_threshold_secs);
-                tracker.set_prompt_threshold(prompt_threshold_secs);
-            }
+            let settings = app.state::<commands::AppState>().settings.get();
+            tracker.set_idle_threshold(settings.idle_threshold_seconds as u64);
+            tracker.set_prompt_threshold(settings.idle_prompt_threshold_seconds as u64);
+            tracker.set_context_capture(settings.capture_context_ref);
+            tracker.set_input_intensity(settings.record_input_intensity);
+
+            // Keep the tracker's idle thresholds, context capture and input counting in sync with settings changes
+            let tracker_for_settings = Arc::clone(&tracker);
+            let db_for_settings = Arc::clone(&db_clone);
+            app.state::<commands::AppState>().settings.subscribe(move |keys| {
+                if keys
+                    .iter()
+                    .any(|k| k.starts_with("idle_") || k == "capture_context_ref" || k == "record_input_intensity")
+                {
+                    let settings = settings::load(&db_for_settings);
+                    tracker_for_settings.set_idle_threshold(settings.idle_threshold_seconds as u64);
+                    tracker_for_settings.set_prompt_threshold(settings.idle_prompt_threshold_seconds as u64);
+                    tracker_for_settings.set_context_capture(settings.capture_context_ref);
+                    tracker_for_settings.set_input_intensity(settings.record_input_intensity);
+                }
+            });
             
             // Store tracker reference in app state
             if let Ok(mut tracker_ref) = app.state::<commands::AppState>().tracker.lock() {
@@ -182,117 +411,150 @@ fn main() {
             // Clone app handle for the closure (needed because it's also used for tray)
             let app_handle_for_tracker = app_handle.clone();
             
+            let settings_for_tracker = Arc::clone(&app.state::<commands::AppState>().settings);
+            let db_for_tracker = Arc::clone(&db_clone);
+            
             tracker.start(move |idle_minutes, started_at| {
-                // Emit idle-return event to frontend
-                if let Some(window) = app_handle_for_tracker.get_window("main") {
-                    window
-                        .emit("idle-return", serde_json::json!({ 
-                            "duration_minutes": idle_minutes,
-                            "started_at": started_at
-                        }))
-                        .ok();
-                    window.show().ok();
-                    window.set_focus().ok();
+                // Only prompt for idle periods at or above the prompt threshold
+                if idle_minutes * 60 < settings_for_tracker.get().idle_prompt_threshold_seconds as u64 {
+                    return;
+                }
+                app_metrics::record(&db_for_tracker, app_metrics::METRIC_IDLE_PROMPT_SHOWN);
+                // Ask in the small prompt window instead of raising the main window
+                if let Err(e) = commands::open_idle_prompt(&app_handle_for_tracker, idle_minutes, started_at) {
+                    eprintln!("Failed to open idle prompt: {}", e);
                 }
             });
 
             // Load plugins asynchronously in background thread (non-blocking)
+            let app_handle_for_plugins = app_handle.clone();
+            std::thread::spawn(move || {
+                eprintln!("Starting async plugin loading...");
+                let app_state = app_handle_for_plugins.state::<commands::AppState>();
+                commands::plugins::load_installed_plugins(&app_state);
+                eprintln!("Plugin loading completed");
+            });
+
+            // Start webhook delivery worker
+            webhooks::start_worker(Arc::clone(&db_clone));
+
             let app_state = app.state::<commands::AppState>();
-            if let (Some(plugin_registry), Some(extension_registry), Some(plugin_loader)) = (
-                app_state.plugin_registry.as_ref(),
-                app_state.extension_registry.as_ref(),
-                app_state.plugin_loader.as_ref(),
-            ) {
-                let db_for_plugins = Arc::clone(&app_state.db);
-                let plugin_registry_for_loading = Arc::clone(plugin_registry);
-                let extension_registry_for_loading = Arc::clone(extension_registry);
-                let plugin_loader_for_loading = Arc::clone(plugin_loader);
-
-                std::thread::spawn(move || {
-                    eprintln!("Starting async plugin loading...");
-
-                    // Load and initialize all installed plugins dynamically
-                    match plugin_loader_for_loading.load_all_installed_plugins(&db_for_plugins) {
-                        Ok(dynamic_plugins) => {
-                            use plugin_system::api::PluginAPI;
-                            use time_tracker_plugin_sdk::PluginAPIInterface;
-
-                            // First pass: Load manifests and register exposed tables
-                            let installed_plugins = db_for_plugins.get_installed_plugins()
-                                .unwrap_or_default();
-                            
-                            for (plugin_id, _name, _version, _description, _repo_url, manifest_path, _frontend_entry, _frontend_components, _author, enabled) in installed_plugins {
-                                if !enabled {
-                                    continue;
-                                }
-                                
-                                if let Some(manifest_path_str) = manifest_path {
-                                    let manifest_path_buf = std::path::PathBuf::from(manifest_path_str);
-                                    if let Ok(manifest) = plugin_loader_for_loading.load_manifest(&manifest_path_buf) {
-                                        if let Some(ref exposed_tables) = manifest.plugin.exposed_tables {
-                                            if let Err(e) = extension_registry_for_loading.register_exposed_tables(&plugin_id, exposed_tables) {
-                                                eprintln!("Warning: Failed to register exposed tables for plugin {}: {}", plugin_id, e);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-
-                            // Second pass: Initialize plugins
-                            for (plugin_id, mut plugin) in dynamic_plugins {
-                                let api = PluginAPI::new(
-                                    Arc::clone(&db_for_plugins),
-                                    Arc::clone(&extension_registry_for_loading),
-                                    plugin_id.clone(),
-                                );
-                                match plugin.initialize(&api as &dyn PluginAPIInterface) {
-                                    Ok(()) => {
-                                        if let Err(e) =
-                                            plugin_registry_for_loading.register(plugin)
-                                        {
-                                            eprintln!(
-                                                "Warning: Failed to register dynamic plugin {}: {}",
-                                                plugin_id, e
-                                            );
-                                        } else {
-                                            eprintln!(
-                                                "Initialized and registered dynamic plugin: {}",
-                                                plugin_id
-                                            );
-                                        }
-                                    }
-                                    Err(e) => {
-                                        eprintln!(
-                                            "Warning: Failed to initialize dynamic plugin {}: {}",
-                                            plugin_id, e
-                                        );
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Failed to load dynamic plugins: {}", e);
-                        }
-                    }
 
-                    // Apply plugin extensions to database schema
-                    if let Err(e) = db_for_plugins
-                        .apply_plugin_extensions(&extension_registry_for_loading)
-                    {
-                        eprintln!("Warning: Failed to apply plugin extensions: {}", e);
+            // Forward settings changes to the event bus, plugins and the frontend
+            let event_bus_for_settings = Arc::clone(&app_state.event_bus);
+            let plugins_for_settings = app_state
+                .plugin_registry
+                .clone()
+                .zip(app_state.extension_registry.clone());
+            let db_for_settings = Arc::clone(&db_clone);
+            let data_sources_for_settings = Arc::clone(&app_state.data_sources);
+            let app_handle_for_settings = app_handle.clone();
+            app_state.settings.subscribe(move |keys| {
+                let payload = serde_json::json!({ "keys": keys });
+                event_bus_for_settings.emit(events::EVENT_SETTINGS_CHANGED, payload.clone());
+                if let Some((plugin_registry, extension_registry)) = &plugins_for_settings {
+                    plugin_registry.notify_settings_changed(keys, |plugin_id| {
+                        plugin_system::api::PluginAPI::new(
+                            Arc::clone(&db_for_settings),
+                            Arc::clone(extension_registry),
+                            Arc::clone(&event_bus_for_settings),
+                            Arc::clone(&data_sources_for_settings),
+                            plugin_id.to_string(),
+                        )
+                    });
+                }
+                app_handle_for_settings.emit_all("settings-changed", payload).ok();
+            });
+
+            // Start MQTT presence publisher (idle unless enabled in settings)
+            presence::start(Arc::clone(&db_clone), Arc::clone(&app_state.tracker), &app_state.event_bus);
+
+            // Start editor heartbeat receiver and metrics endpoint (idle unless enabled in settings)
+            let pomodoros = metrics::PomodoroCounter::subscribe(&app_state.event_bus);
+            ide::start(
+                Arc::clone(&db_clone),
+                Arc::clone(&app_state.settings),
+                Arc::clone(&app_state.data_sources),
+                pomodoros,
+            );
+
+            // Start the REST API for external tools (idle unless enabled in settings)
+            http_api::start(Arc::clone(&app_state.settings), app_handle.clone());
+
+            // Start focus-mode blocker (idle unless enabled in settings)
+            let app_handle_for_blocker = app_handle.clone();
+            focus_blocker::start(
+                Arc::clone(&db_clone),
+                Arc::clone(&app_state.focus_blocker),
+                &app_state.event_bus,
+                move |app_name| {
+                    // Raise the main window over the blocked app and show the overlay
+                    if let Some(window) = app_handle_for_blocker.get_window("main") {
+                        window
+                            .emit("focus-blocked", serde_json::json!({ "app_name": app_name }))
+                            .ok();
+                        window.show().ok();
+                        window.set_focus().ok();
                     }
+                },
+            );
 
-                    eprintln!("Plugin loading completed");
-                });
-            }
+            // Start automatic settings profile switching
+            profiles::start(
+                Arc::clone(&db_clone),
+                Arc::clone(&app_state.settings),
+                Arc::clone(&app_state.event_bus),
+            );
+
+            // Start productivity check-in scheduler (idle unless enabled in settings)
+            let app_handle_for_checkins = app_handle.clone();
+            checkins::start(Arc::clone(&db_clone), move || {
+                if let Some(window) = app_handle_for_checkins.get_window("main") {
+                    window.emit("checkin-prompt", serde_json::json!({})).ok();
+                    window.show().ok();
+                }
+            });
+
+            // Offer eye-strain micro-breaks (idle unless enabled in settings)
+            let app_handle_for_micro_breaks = app_handle.clone();
+            micro_breaks::start(Arc::clone(&db_clone), move |id, seconds| {
+                if let Err(e) = commands::open_micro_break(&app_handle_for_micro_breaks, id, seconds) {
+                    eprintln!("Failed to open micro-break overlay: {}", e);
+                }
+            });
+
+            // Log (and report) work outside work hours (idle unless enabled in settings)
+            let app_handle_for_boundaries = app_handle.clone();
+            boundaries::start(Arc::clone(&db_clone), move |kind, seconds| {
+                if let Some(window) = app_handle_for_boundaries.get_window("main") {
+                    window
+                        .emit("boundary-alert", serde_json::json!({ "kind": kind, "seconds": seconds }))
+                        .ok();
+                }
+            });
+
+            // Write today's daily note in the evening (idle unless enabled in settings)
+            let app_handle_for_daily_note = app_handle.clone();
+            daily_note::start(Arc::clone(&app_state.settings), move |date| {
+                commands::write_daily_note_for(&app_handle_for_daily_note.state::<commands::AppState>(), date, None)
+            });
 
             // Start tray update timer
             let db_for_tray = Arc::clone(&db_clone);
             let app_handle_for_tray = app_handle.clone();
             
+            let settings_for_tray = Arc::clone(&app_state.settings);
+            let tray_interval_changes = settings_for_tray.watch(&["tray_update_interval_seconds"]);
+            
             std::thread::spawn(move || {
                 loop {
-                    std::thread::sleep(std::time::Duration::from_secs(60));
+                    let interval = settings_for_tray.get().tray_update_interval_seconds as u64;
+                    // A changed interval takes effect immediately
+                    if let Err(std::sync::mpsc::RecvTimeoutError::Disconnected) =
+                        tray_interval_changes.recv_timeout(std::time::Duration::from_secs(interval))
+                    {
+                        break;
+                    }
                     
                     if let Ok(total) = db_for_tray.get_today_total() {
                         tray::update_tray_time(&app_handle_for_tray, total);
@@ -300,8 +562,89 @@ fn main() {
                 }
             });
 
+            // Keep tray icon and tooltip in sync with tracking/pomodoro state
+            tray::start_status_updates(
+                app_handle.clone(),
+                Arc::clone(&db_clone),
+                Arc::clone(&app_state.tracker),
+                &app_state.event_bus,
+            );
+
+            // Remember the pomodoro state and forward it to the mini timer window
+            let pomodoro_status = Arc::clone(&app_state.pomodoro_status);
+            let app_handle_for_mini_timer = app_handle.clone();
+            app_state.event_bus.subscribe(move |event| {
+                if !event.name.starts_with("pomodoro.") {
+                    return;
+                }
+                *pomodoro_status.lock().unwrap() = Some(event.clone());
+                if let Some(window) = app_handle_for_mini_timer.get_window(commands::MINI_TIMER_LABEL) {
+                    window.emit("pomodoro-update", event).ok();
+                }
+            });
+
+            // Tell the main window when a timed pause ran out, so it can show tracking as resumed
+            let app_handle_for_pause = app_handle.clone();
+            app_state.event_bus.subscribe(move |event| {
+                if event.name != events::EVENT_TRACKING_RESUMED || event.payload["auto"] != true {
+                    return;
+                }
+                if let Some(window) = app_handle_for_pause.get_window("main") {
+                    window.emit("tracking-auto-resumed", &event.payload).ok();
+                }
+            });
+
+            // Check for app updates daily (idle unless enabled in settings)
+            let app_handle_for_updates = app_handle.clone();
+            updates::start(Arc::clone(&app_state.settings), move |info| {
+                if let Some(window) = app_handle_for_updates.get_window("main") {
+                    window.emit("update-available", info).ok();
+                }
+            });
+
+            // Log slow statements for diagnostics while slow_query_log_ms is set
+            database::slow_queries::set_threshold(app_state.settings.get().slow_query_log_ms);
+            let db_for_slow_queries = Arc::clone(&db_clone);
+            app_state.settings.subscribe(move |keys| {
+                if keys.iter().any(|k| k == "slow_query_log_ms") {
+                    database::slow_queries::set_threshold(settings::load(&db_for_slow_queries).slow_query_log_ms);
+                }
+            });
+
+            // Run maintenance jobs, resuming the ones interrupted by the last quit.
+            // Every job kind rewrites activities, so each step also announces the change.
+            let app_handle_for_jobs = app_handle.clone();
+            let event_bus_for_jobs = Arc::clone(&app_state.event_bus);
+            app_state.jobs.start(move |job| {
+                event_bus_for_jobs.emit(
+                    events::EVENT_ACTIVITIES_CHANGED,
+                    serde_json::json!({ "job_id": job.id, "job_kind": job.kind }),
+                );
+                app_handle_for_jobs.emit_all("job-progress", job).ok();
+            });
+
+            // Start data retention engine (idle unless retention_days is set)
+            retention::start(Arc::clone(&app_state.jobs), Arc::clone(&app_state.settings));
+
+            // Keep the marketplace index fresh (idle while the marketplace is disabled)
+            plugin_system::index::start_background_refresh(
+                Arc::clone(&app_state.plugin_index),
+                Arc::clone(&app_state.settings),
+            );
+
+            // Count local app usage metrics (never reported anywhere)
+            app_metrics::start(Arc::clone(&db_clone), &app_state.event_bus);
+
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Covers exits that bypass the tray and main window (e.g. OS logout)
+            if let tauri::RunEvent::Exit = event {
+                if let Some(state) = app_handle.try_state::<commands::AppState>() {
+                    shutdown::run(&state);
+                }
+            }
+        });
 }
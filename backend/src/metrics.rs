@@ -0,0 +1,122 @@
+//! Prometheus metrics
+//!
+//! While `metrics_enabled` is `true`, the local HTTP listener (see `ide`)
+//! serves `GET /metrics` in the Prometheus text format, so Prometheus can
+//! scrape today's tracked time and Grafana can graph it next to other
+//! personal metrics:
+//!
+//! ```text
+//! scrape_configs:
+//!   - job_name: time-tracker
+//!     static_configs:
+//!       - targets: ["localhost:9900"]
+//! ```
+//!
+//! Pomodoros are counted from `pomodoro.completed` plugin events while the app
+//! runs, so the count starts at 0 after a restart.
+
+use crate::database::activities::local_day_start;
+use crate::database::Database;
+use crate::events::EventBus;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+pub const EVENT_POMODORO_COMPLETED: &str = "pomodoro.completed";
+
+/// Work sessions completed today, from pomodoro plugin events
+#[derive(Default)]
+pub struct PomodoroCounter {
+    /// (local day start, completed work sessions that day)
+    today: Mutex<(i64, u64)>,
+}
+
+impl PomodoroCounter {
+    /// Counter fed by `pomodoro.completed` events of work sessions
+    pub fn subscribe(event_bus: &EventBus) -> Arc<Self> {
+        let counter = Arc::new(Self::default());
+        let counter_for_events = Arc::clone(&counter);
+        event_bus.subscribe(move |event| {
+            let phase = event.payload.get("phase").and_then(|p| p.as_str());
+            if event.name == EVENT_POMODORO_COMPLETED && phase.is_none_or(|p| p == "work" || p == "focus") {
+                counter_for_events.record(local_day_start());
+            }
+        });
+        counter
+    }
+
+    fn record(&self, day_start: i64) {
+        let mut today = self.today.lock().unwrap();
+        if today.0 != day_start {
+            *today = (day_start, 0);
+        }
+        today.1 += 1;
+    }
+
+    pub fn today(&self, day_start: i64) -> u64 {
+        let today = self.today.lock().unwrap();
+        if today.0 == day_start { today.1 } else { 0 }
+    }
+}
+
+/// Escape a label value for the text format
+fn label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: impl std::fmt::Display) {
+    let _ = writeln!(out, "# HELP {} {}\n# TYPE {} gauge\n{} {}", name, help, name, name, value);
+}
+
+/// Metrics for the day starting at `day_start`, up to `now`
+pub fn render(db: &Database, pomodoros: &PomodoroCounter, day_start: i64, now: i64) -> rusqlite::Result<String> {
+    let stats = db.get_stats_for_range(day_start, now, None, None, false)?;
+    let ratio = if stats.total_seconds > 0 {
+        stats.productive_seconds as f64 / stats.total_seconds as f64
+    } else {
+        0.0
+    };
+
+    let mut out = String::new();
+    gauge(&mut out, "time_tracker_seconds_tracked_today", "Seconds tracked since local midnight", stats.total_seconds);
+    gauge(
+        &mut out,
+        "time_tracker_productive_seconds_today",
+        "Seconds in productive categories since local midnight",
+        stats.productive_seconds,
+    );
+    gauge(&mut out, "time_tracker_productive_ratio", "Share of today's tracked time that was productive", ratio);
+    gauge(
+        &mut out,
+        "time_tracker_pomodoros_completed",
+        "Pomodoro work sessions completed today",
+        pomodoros.today(day_start),
+    );
+    let name = "time_tracker_category_seconds_today";
+    let _ = writeln!(out, "# HELP {} Seconds tracked today per category\n# TYPE {} gauge", name, name);
+    for (_, category, _, seconds) in &stats.category_breakdown {
+        let _ = writeln!(out, "{}{{category=\"{}\"}} {}", name, label(category), seconds);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_todays_gauges() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let day_start = 1_700_006_400;
+        db.upsert_activity("Code", Some("main.rs"), None, day_start + 60, "tracker", None).unwrap();
+        let pomodoros = PomodoroCounter::default();
+        pomodoros.record(day_start - 86_400);
+        pomodoros.record(day_start);
+
+        let text = render(&db, &pomodoros, day_start, day_start + 3600).unwrap();
+        assert!(text.contains("\ntime_tracker_seconds_tracked_today 5\n"), "{}", text);
+        assert!(text.contains("\ntime_tracker_pomodoros_completed 1\n"), "{}", text);
+        assert!(text.contains("# TYPE time_tracker_productive_ratio gauge\n"), "{}", text);
+        assert_eq!(label("say \"hi\""), "say \\\"hi\\\"");
+    }
+}
@@ -0,0 +1,44 @@
+//! Eye-strain micro-breaks (the 20-20-20 rule)
+//!
+//! While `micro_breaks_enabled` is `true`, every `micro_break_interval_minutes`
+//! (default 20) of continuous work offers a `micro_break_seconds` (default 20)
+//! break in a small overlay: look at something 20 feet away for 20 seconds.
+//! Being away resets the interval, since that was a break too. Each offer is
+//! logged in `micro_breaks` as completed, skipped or missed, for compliance
+//! in the wellness stats.
+
+use crate::checkins::is_user_active;
+use crate::database::Database;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the schedule (and settings) are checked
+const POLL_INTERVAL_SECS: u64 = 15;
+
+/// Start the micro-break scheduler; `on_break` gets the logged break's ID and
+/// length in seconds and shows the overlay
+pub fn start<F>(db: Arc<Database>, on_break: F)
+where
+    F: Fn(i64, i64) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut working_since: Option<i64> = None;
+        loop {
+            let settings = crate::settings::load(&db);
+            let now = chrono::Utc::now().timestamp();
+            if !settings.micro_breaks_enabled || !is_user_active(&db, now) {
+                working_since = None;
+            } else {
+                let since = *working_since.get_or_insert(now);
+                if now - since >= settings.micro_break_interval_minutes * 60 {
+                    match db.insert_micro_break(now, settings.micro_break_seconds) {
+                        Ok(id) => on_break(id, settings.micro_break_seconds),
+                        Err(e) => eprintln!("Warning: Failed to log micro-break: {}", e),
+                    }
+                    working_since = Some(now + settings.micro_break_seconds);
+                }
+            }
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
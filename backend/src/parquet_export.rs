@@ -0,0 +1,226 @@
+//! Parquet export
+//!
+//! Writes activities and manual entries as typed, columnar Parquet files for
+//! analysis in pandas, polars or DuckDB notebooks (`SELECT * FROM
+//! 'activities.parquet'`). Unlike CSV, timestamps stay timestamps, IDs stay
+//! integers and missing values stay null, and multi-year histories load in
+//! seconds. Timestamps are stored as UTC milliseconds; files are Snappy
+//! compressed and split into row groups of `ROW_GROUP_SIZE` rows. With a
+//! passphrase each file is written as an encrypted archive (see
+//! `export_archive`).
+
+use crate::database::{Activity, Category, ManualEntry};
+use parquet::basic::Compression;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// File names inside the export directory
+pub const ACTIVITIES_FILE: &str = "activities.parquet";
+pub const MANUAL_ENTRIES_FILE: &str = "manual_entries.parquet";
+
+const ROW_GROUP_SIZE: usize = 100_000;
+
+/// Values of one column; `None` is written as null
+enum Values {
+    Int64(Vec<Option<i64>>),
+    /// Unix seconds, written as a UTC millisecond timestamp
+    Timestamp(Vec<Option<i64>>),
+    Text(Vec<Option<String>>),
+    Bool(Vec<Option<bool>>),
+}
+
+struct Column {
+    name: &'static str,
+    required: bool,
+    values: Values,
+}
+
+impl Column {
+    fn required(name: &'static str, values: Values) -> Self {
+        Self { name, required: true, values }
+    }
+
+    fn optional(name: &'static str, values: Values) -> Self {
+        Self { name, required: false, values }
+    }
+
+    fn schema_field(&self) -> String {
+        let repetition = if self.required { "REQUIRED" } else { "OPTIONAL" };
+        let physical = match self.values {
+            Values::Int64(_) => "INT64 {}",
+            Values::Timestamp(_) => "INT64 {} (TIMESTAMP(MILLIS,true))",
+            Values::Text(_) => "BYTE_ARRAY {} (STRING)",
+            Values::Bool(_) => "BOOLEAN {}",
+        };
+        format!("{} {};", repetition, physical.replace("{}", self.name))
+    }
+}
+
+/// Definition levels (1 = present) and the present values of `rows`
+fn split_nulls<T: Clone, U>(rows: &[Option<T>], convert: impl Fn(T) -> U) -> (Vec<i16>, Vec<U>) {
+    let levels = rows.iter().map(|v| v.is_some() as i16).collect();
+    let values = rows.iter().flatten().cloned().map(convert).collect();
+    (levels, values)
+}
+
+/// Definition levels to write; required columns have none
+fn levels<'a>(column: &Column, def_levels: &'a [i16]) -> Option<&'a [i16]> {
+    (!column.required).then_some(def_levels)
+}
+
+/// Write `columns` (all of the same length) as a Parquet file, encrypted
+/// with `passphrase` if given
+fn write_table(path: &Path, passphrase: Option<&str>, table: &str, columns: &[Column]) -> Result<usize, String> {
+    let fields: Vec<String> = columns.iter().map(Column::schema_field).collect();
+    let schema = parse_message_type(&format!("message {} {{ {} }}", table, fields.join(" ")))
+        .map_err(|e| format!("Invalid Parquet schema: {}", e))?;
+    let properties = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+    let mut writer = SerializedFileWriter::new(Vec::new(), Arc::new(schema), Arc::new(properties))
+        .map_err(|e| format!("Failed to write Parquet file: {}", e))?;
+
+    let rows = columns.first().map_or(0, |column| match &column.values {
+        Values::Int64(v) | Values::Timestamp(v) => v.len(),
+        Values::Text(v) => v.len(),
+        Values::Bool(v) => v.len(),
+    });
+    let write_error = |e: parquet::errors::ParquetError| format!("Failed to write Parquet file: {}", e);
+    for start in (0..rows).step_by(ROW_GROUP_SIZE) {
+        let end = (start + ROW_GROUP_SIZE).min(rows);
+        let mut row_group = writer.next_row_group().map_err(write_error)?;
+        for column in columns {
+            let mut column_writer = row_group
+                .next_column()
+                .map_err(write_error)?
+                .ok_or("Parquet schema has fewer columns than the data")?;
+            let result = match &column.values {
+                Values::Int64(v) => {
+                    let (def_levels, values) = split_nulls(&v[start..end], |x| x);
+                    column_writer.typed::<Int64Type>().write_batch(&values, levels(column, &def_levels), None)
+                }
+                Values::Timestamp(v) => {
+                    let (def_levels, values) = split_nulls(&v[start..end], |x| x * 1000);
+                    column_writer.typed::<Int64Type>().write_batch(&values, levels(column, &def_levels), None)
+                }
+                Values::Text(v) => {
+                    let (def_levels, values) = split_nulls(&v[start..end], |x| ByteArray::from(x.into_bytes()));
+                    column_writer.typed::<ByteArrayType>().write_batch(&values, levels(column, &def_levels), None)
+                }
+                Values::Bool(v) => {
+                    let (def_levels, values) = split_nulls(&v[start..end], |x| x);
+                    column_writer.typed::<BoolType>().write_batch(&values, levels(column, &def_levels), None)
+                }
+            };
+            result.map_err(write_error)?;
+            column_writer.close().map_err(write_error)?;
+        }
+        row_group.close().map_err(write_error)?;
+    }
+    let contents = writer.into_inner().map_err(write_error)?;
+    let fallback = path.file_name().map_or(table.to_string(), |name| name.to_string_lossy().into_owned());
+    crate::export_archive::write(path, &contents, passphrase, &fallback)?;
+    Ok(rows)
+}
+
+fn category_names(categories: &[Category]) -> HashMap<i64, String> {
+    categories.iter().map(|c| (c.id, c.name.clone())).collect()
+}
+
+/// Write activities to `path`; returns the number of rows
+pub fn write_activities(
+    path: &Path,
+    passphrase: Option<&str>,
+    activities: &[Activity],
+    categories: &[Category],
+) -> Result<usize, String> {
+    let names = category_names(categories);
+    let column = |f: fn(&Activity) -> Option<i64>| activities.iter().map(f).collect();
+    let text = |f: fn(&Activity) -> Option<String>| activities.iter().map(f).collect();
+    let columns = [
+        Column::required("id", Values::Int64(column(|a| Some(a.id)))),
+        Column::required("started_at", Values::Timestamp(column(|a| Some(a.started_at)))),
+        Column::required("ended_at", Values::Timestamp(column(|a| Some(a.started_at + a.duration_sec)))),
+        Column::required("duration_sec", Values::Int64(column(|a| Some(a.duration_sec)))),
+        Column::required("app_name", Values::Text(text(|a| Some(a.app_name.clone())))),
+        Column::optional("window_title", Values::Text(text(|a| a.window_title.clone()))),
+        Column::optional("domain", Values::Text(text(|a| a.domain.clone()))),
+        Column::optional("category_id", Values::Int64(column(|a| a.category_id))),
+        Column::optional(
+            "category",
+            Values::Text(activities.iter().map(|a| a.category_id.and_then(|id| names.get(&id).cloned())).collect()),
+        ),
+        Column::required("is_idle", Values::Bool(activities.iter().map(|a| Some(a.is_idle)).collect())),
+        Column::required("source", Values::Text(text(|a| Some(a.source.clone())))),
+        Column::optional("billable_override", Values::Bool(activities.iter().map(|a| a.billable_override).collect())),
+        Column::optional("device_name", Values::Text(text(|a| a.device_name.clone()))),
+    ];
+    write_table(path, passphrase, "activity", &columns)
+}
+
+/// Write manual entries to `path`; returns the number of rows
+pub fn write_manual_entries(
+    path: &Path,
+    passphrase: Option<&str>,
+    entries: &[ManualEntry],
+    categories: &[Category],
+) -> Result<usize, String> {
+    let names = category_names(categories);
+    let column = |f: fn(&ManualEntry) -> Option<i64>| entries.iter().map(f).collect();
+    let columns = [
+        Column::required("id", Values::Int64(column(|e| Some(e.id)))),
+        Column::required("started_at", Values::Timestamp(column(|e| Some(e.started_at)))),
+        Column::required("ended_at", Values::Timestamp(column(|e| Some(e.ended_at)))),
+        Column::required("duration_sec", Values::Int64(column(|e| Some(e.ended_at - e.started_at)))),
+        Column::optional("description", Values::Text(entries.iter().map(|e| e.description.clone()).collect())),
+        Column::optional("category_id", Values::Int64(column(|e| e.category_id))),
+        Column::optional(
+            "category",
+            Values::Text(entries.iter().map(|e| e.category_id.and_then(|id| names.get(&id).cloned())).collect()),
+        ),
+        Column::required("source", Values::Text(entries.iter().map(|e| Some(e.source.clone())).collect())),
+        Column::optional("billable_override", Values::Bool(entries.iter().map(|e| e.billable_override).collect())),
+    ];
+    write_table(path, passphrase, "manual_entry", &columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::{Field, RowAccessor};
+    use std::fs::File;
+
+    #[test]
+    fn activities_keep_their_types_and_nulls() {
+        let activity = |id: i64, title: Option<&str>| Activity {
+            id,
+            app_name: "Code".to_string(),
+            window_title: title.map(str::to_string),
+            domain: None,
+            category_id: None,
+            started_at: 1_700_000_000 + id * 60,
+            duration_sec: 60,
+            is_idle: false,
+            source: "tracker".to_string(),
+            billable_override: None,
+            context_ref: None,
+            device_id: None,
+            device_name: None,
+        };
+        let path = std::env::temp_dir().join(format!("time-tracker-test-{}.parquet", std::process::id()));
+        let written = write_activities(&path, None, &[activity(1, Some("main.rs")), activity(2, None)], &[]).unwrap();
+        assert_eq!(written, 2);
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+        let rows: Vec<_> = reader.get_row_iter(None).unwrap().map(Result::unwrap).collect();
+        assert_eq!((rows[0].get_long(0).unwrap(), rows[0].get_timestamp_millis(1).unwrap()), (1, 1_700_000_060_000));
+        assert_eq!(rows[0].get_string(5).unwrap(), "main.rs");
+        assert_eq!(rows[1].get_column_iter().nth(5).map(|(_, value)| value), Some(&Field::Null));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
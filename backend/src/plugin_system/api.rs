@@ -1,8 +1,13 @@
 //! Plugin API - interface for plugins to interact with Core
 
 use crate::database::Database;
+use crate::database::common::SOURCE_PLUGIN_PREFIX;
+use crate::database::sync_ids::sync_table;
+use crate::data_sources::{ActivitySample, DataSourceRegistry};
+use crate::events::EventBus;
 use crate::plugin_system::extensions::{ExtensionRegistry, Extension, ActivityHook, QueryFilter};
 use std::sync::Arc;
+use time_tracker_plugin_sdk::validation;
 use time_tracker_plugin_sdk::{
     PluginAPIInterface, 
     EntityType, ExtensionType, SchemaChange, ModelField,
@@ -17,15 +22,25 @@ use time_tracker_plugin_sdk::{
 pub struct PluginAPI {
     db: Arc<Database>,
     extension_registry: Arc<ExtensionRegistry>,
+    event_bus: Arc<EventBus>,
+    data_sources: Arc<DataSourceRegistry>,
     plugin_id: String,
 }
 
 impl PluginAPI {
     /// Create a new Plugin API instance
-    pub fn new(db: Arc<Database>, extension_registry: Arc<ExtensionRegistry>, plugin_id: String) -> Self {
+    pub fn new(
+        db: Arc<Database>,
+        extension_registry: Arc<ExtensionRegistry>,
+        event_bus: Arc<EventBus>,
+        data_sources: Arc<DataSourceRegistry>,
+        plugin_id: String,
+    ) -> Self {
         Self {
             db,
             extension_registry,
+            event_bus,
+            data_sources,
             plugin_id,
         }
     }
@@ -192,6 +207,60 @@ impl PluginAPIInterface for PluginAPI {
         Err("Query filters conversion not yet implemented".to_string())
     }
     
+    fn emit_event(&self, name: &str, payload: serde_json::Value) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Event name must not be empty".to_string());
+        }
+        self.event_bus.emit(&format!("{}.{}", self.plugin_id, name), payload);
+        Ok(())
+    }
+    
+    fn record_activity_sample(
+        &self,
+        app_name: &str,
+        window_title: Option<&str>,
+        domain: Option<&str>,
+        timestamp: i64,
+    ) -> Result<(), String> {
+        let app_name = app_name.trim();
+        if app_name.is_empty() {
+            return Err("App name must not be empty".to_string());
+        }
+        let source = format!("{}{}", SOURCE_PLUGIN_PREFIX, self.plugin_id);
+        self.data_sources.queue(&source).push(ActivitySample {
+            app_name: app_name.to_string(),
+            window_title: window_title.map(str::to_string),
+            domain: domain.map(str::to_string),
+            timestamp,
+            source,
+        });
+        Ok(())
+    }
+    
+    fn is_in_meeting(&self) -> Result<bool, String> {
+        Ok(crate::presence::in_meeting(&self.db, chrono::Utc::now().timestamp()))
+    }
+    
+    fn get_sync_id(&self, entity_type: &str, id: i64) -> Result<Option<String>, String> {
+        self.db.get_sync_id(entity_type, id).map_err(|e| e.to_string())
+    }
+    
+    fn find_by_sync_id(&self, entity_type: &str, uuid: &str) -> Result<Option<i64>, String> {
+        self.db.find_by_sync_id(entity_type, uuid).map_err(|e| e.to_string())
+    }
+    
+    fn set_sync_id(&self, entity_type: &str, id: i64, uuid: &str) -> Result<(), String> {
+        let table = sync_table(entity_type).ok_or_else(|| format!("Unknown sync entity: {}", entity_type))?;
+        if table != "manual_entries" && !self.extension_registry.plugin_owns_table(&self.plugin_id, table) {
+            return Err(format!("Plugin does not own table: {}", table));
+        }
+        if uuid::Uuid::parse_str(uuid).is_err() {
+            return Err(format!("Invalid UUID: {}", uuid));
+        }
+        self.db.set_sync_id(entity_type, id, uuid).map_err(|e| e.to_string())
+    }
+    
     fn call_db_method(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String> {
         // Route database method calls to the appropriate handler
         let params_map = params.as_object().ok_or("Params must be an object")?;
@@ -200,7 +269,10 @@ impl PluginAPIInterface for PluginAPI {
             // Category methods (return JSON with all columns including plugin-extended fields)
             "create_category" => {
                 let name = params_map["name"].as_str().ok_or("Missing name")?.to_string();
-                let color = params_map["color"].as_str().unwrap_or("#888888").to_string();
+                let color = match params_map["color"].as_str() {
+                    Some(color) => color.to_string(),
+                    None => self.db.suggest_category_color().map_err(|e| e.to_string())?,
+                };
                 let icon = params_map["icon"].as_str().map(|s| s.to_string());
                 let is_productive = params_map["is_productive"].as_bool();
                 let sort_order = params_map["sort_order"].as_i64().unwrap_or(0);
@@ -298,9 +370,12 @@ impl PluginAPIInterface for PluginAPI {
                 let category_ids = params_map.get("category_ids")
                     .and_then(|v| v.as_array())
                     .map(|arr| arr.iter().filter_map(|v| v.as_i64()).collect::<Vec<i64>>());
+                let sources = params_map.get("sources")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(str::to_string)).collect::<Vec<String>>());
                 let activities = self
                     .db
-                    .get_activities(start, end, limit, offset, exclude_idle, category_ids.as_deref())
+                    .get_activities(start, end, limit, offset, exclude_idle, category_ids.as_deref(), sources.as_deref())
                     .map_err(|e| e.to_string())?;
                 Ok(serde_json::to_value(activities).map_err(|e| e.to_string())?)
             }
@@ -310,13 +385,19 @@ impl PluginAPIInterface for PluginAPI {
                 let category_id = params_map["category_id"].as_i64();
                 let started_at = params_map["started_at"].as_i64().ok_or("Missing started_at")?;
                 let ended_at = params_map["ended_at"].as_i64().ok_or("Missing ended_at")?;
+                validation::past_entry(started_at, ended_at, chrono::Utc::now().timestamp())?;
 
                 let id = self.db.add_manual_entry(
                     description.as_deref(),
                     category_id,
                     started_at,
                     ended_at,
+                    &format!("{}{}", SOURCE_PLUGIN_PREFIX, self.plugin_id),
                 ).map_err(|e| e.to_string())?;
+                if let Some(billable_override) = params_map.get("billable_override") {
+                    self.db.set_manual_entries_billable_override(&[id], billable_override.as_bool())
+                        .map_err(|e| e.to_string())?;
+                }
 
                 let entries = self.db.get_manual_entries(started_at.saturating_sub(1), ended_at.saturating_add(1))
                     .map_err(|e| e.to_string())?;
@@ -331,6 +412,7 @@ impl PluginAPIInterface for PluginAPI {
                 let category_id = params_map["category_id"].as_i64();
                 let started_at = params_map["started_at"].as_i64().ok_or("Missing started_at")?;
                 let ended_at = params_map["ended_at"].as_i64().ok_or("Missing ended_at")?;
+                validation::past_entry(started_at, ended_at, chrono::Utc::now().timestamp())?;
 
                 let current = self.db.get_manual_entries(0, i64::MAX).map_err(|e| e.to_string())?
                     .into_iter()
@@ -348,6 +430,10 @@ impl PluginAPIInterface for PluginAPI {
                     started_at,
                     ended_at,
                 ).map_err(|e| e.to_string())?;
+                if let Some(billable_override) = params_map.get("billable_override") {
+                    self.db.set_manual_entries_billable_override(&[id], billable_override.as_bool())
+                        .map_err(|e| e.to_string())?;
+                }
 
                 let entries = self.db.get_manual_entries(0, i64::MAX).map_err(|e| e.to_string())?;
                 let entry = entries.into_iter()
@@ -491,7 +577,10 @@ impl PluginAPIInterface for PluginAPI {
     fn create_category(&self, params: serde_json::Value) -> Result<serde_json::Value, String> {
         let params_map = params.as_object().ok_or("Params must be an object")?;
         let name = params_map["name"].as_str().ok_or("Missing name")?.to_string();
-        let color = params_map["color"].as_str().unwrap_or("#888888").to_string();
+        let color = match params_map["color"].as_str() {
+            Some(color) => color.to_string(),
+            None => self.db.suggest_category_color().map_err(|e| e.to_string())?,
+        };
         let icon = params_map["icon"].as_str().map(|s| s.to_string());
         let is_productive = params_map["is_productive"].as_bool();
         let sort_order = params_map["sort_order"].as_i64().unwrap_or(0);
@@ -590,7 +679,7 @@ impl PluginAPIInterface for PluginAPI {
         let category_ids = filters.as_ref().and_then(|f| f.category_ids.as_ref().map(|v| v.as_slice()));
         let activities = self
             .db
-            .get_activities(start, end, limit, offset, exclude_idle, category_ids)
+            .get_activities(start, end, limit, offset, exclude_idle, category_ids, None)
             .map_err(|e| e.to_string())?;
         Ok(serde_json::to_value(activities).map_err(|e| e.to_string())?)
     }
@@ -606,13 +695,19 @@ impl PluginAPIInterface for PluginAPI {
         let category_id = params_map["category_id"].as_i64();
         let started_at = params_map["started_at"].as_i64().ok_or("Missing started_at")?;
         let ended_at = params_map["ended_at"].as_i64().ok_or("Missing ended_at")?;
+        validation::past_entry(started_at, ended_at, chrono::Utc::now().timestamp())?;
 
         let id = self.db.add_manual_entry(
             description.as_deref(),
             category_id,
             started_at,
             ended_at,
+            &format!("{}{}", SOURCE_PLUGIN_PREFIX, self.plugin_id),
         ).map_err(|e| e.to_string())?;
+        if let Some(billable_override) = params_map.get("billable_override") {
+            self.db.set_manual_entries_billable_override(&[id], billable_override.as_bool())
+                .map_err(|e| e.to_string())?;
+        }
 
         let entries = self.db.get_manual_entries(started_at.saturating_sub(1), ended_at.saturating_add(1))
             .map_err(|e| e.to_string())?;
@@ -629,6 +724,7 @@ impl PluginAPIInterface for PluginAPI {
         let category_id = params_map["category_id"].as_i64();
         let started_at = params_map["started_at"].as_i64().ok_or("Missing started_at")?;
         let ended_at = params_map["ended_at"].as_i64().ok_or("Missing ended_at")?;
+        validation::past_entry(started_at, ended_at, chrono::Utc::now().timestamp())?;
 
         let current = self.db.get_manual_entries(0, i64::MAX).map_err(|e| e.to_string())?
             .into_iter()
@@ -646,6 +742,10 @@ impl PluginAPIInterface for PluginAPI {
             started_at,
             ended_at,
         ).map_err(|e| e.to_string())?;
+        if let Some(billable_override) = params_map.get("billable_override") {
+            self.db.set_manual_entries_billable_override(&[id], billable_override.as_bool())
+                .map_err(|e| e.to_string())?;
+        }
 
         let entries = self.db.get_manual_entries(0, i64::MAX).map_err(|e| e.to_string())?;
         let entry = entries.into_iter()
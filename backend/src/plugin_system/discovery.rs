@@ -19,6 +19,26 @@ pub struct RegistryPlugin {
     pub min_core_version: Option<String>,
     pub max_core_version: Option<String>,
     pub api_version: Option<String>,
+    /// Last release time (ISO 8601)
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Average rating, 0-5
+    #[serde(default)]
+    pub rating: Option<f64>,
+    #[serde(default)]
+    pub rating_count: Option<u64>,
+}
+
+impl RegistryPlugin {
+    /// Whether name, description, id or a tag contains `query_lower`
+    pub fn matches_query(&self, query_lower: &str) -> bool {
+        self.name.to_lowercase().contains(query_lower)
+            || self.description.to_lowercase().contains(query_lower)
+            || self.id.to_lowercase().contains(query_lower)
+            || self.tags.as_ref().is_some_and(|tags| {
+                tags.iter().any(|tag| tag.to_lowercase().contains(query_lower))
+            })
+    }
 }
 
 /// Registry response
@@ -162,27 +182,6 @@ impl PluginDiscovery {
         Ok(registry)
     }
 
-    /// Search plugins in registry
-    pub async fn search_plugins(&mut self, query: &str) -> Result<Vec<RegistryPlugin>, String> {
-        let registry = self.get_registry().await?;
-        let query_lower = query.to_lowercase();
-        
-        let results: Vec<RegistryPlugin> = registry
-            .plugins
-            .into_iter()
-            .filter(|plugin| {
-                plugin.name.to_lowercase().contains(&query_lower)
-                    || plugin.description.to_lowercase().contains(&query_lower)
-                    || plugin.id.to_lowercase().contains(&query_lower)
-                    || plugin.tags.as_ref().map_or(false, |tags| {
-                        tags.iter().any(|tag| tag.to_lowercase().contains(&query_lower))
-                    })
-            })
-            .collect();
-
-        Ok(results)
-    }
-
     /// Get plugin info from registry by ID
     pub async fn get_plugin_by_id(&mut self, plugin_id: &str) -> Result<Option<RegistryPlugin>, String> {
         let registry = self.get_registry().await?;
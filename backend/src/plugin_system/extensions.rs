@@ -77,6 +77,22 @@ impl ExtensionRegistry {
         Ok(())
     }
 
+    /// Drop every extension, owned table and exposed table registered by a plugin.
+    /// Hooks point into the plugin's library, so this must run before it is unloaded.
+    pub fn unregister_plugin(&self, plugin_id: &str) -> Result<(), String> {
+        self.extensions.lock()
+            .map_err(|e| format!("Failed to lock extension registry: {}", e))?
+            .values_mut()
+            .for_each(|extensions| extensions.retain(|ext| ext.plugin_id != plugin_id));
+        self.plugin_tables.lock()
+            .map_err(|e| format!("Failed to lock plugin tables: {}", e))?
+            .retain(|_, owner| owner != plugin_id);
+        self.exposed_tables.lock()
+            .map_err(|e| format!("Failed to lock exposed tables: {}", e))?
+            .retain(|(owner, _), _| owner != plugin_id);
+        Ok(())
+    }
+
     /// Returns true if the given plugin is allowed to access the given table.
     /// Plugins may only access tables they created via CreateTable schema extension.
     pub fn plugin_owns_table(&self, plugin_id: &str, table: &str) -> bool {
@@ -0,0 +1,276 @@
+//! Plugin Index - locally cached marketplace metadata
+//!
+//! Registries are fetched in the background and merged into one index that is
+//! kept in memory and on disk, so browsing, filtering and searching the
+//! marketplace never waits on the network. The index is refreshed when it is
+//! older than `INDEX_TTL_SECS` or the configured registry URLs change.
+
+use super::discovery::{PluginDiscovery, RegistryPlugin};
+use crate::settings::{AppSettings, SettingsService};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+pub const DEFAULT_REGISTRY_URL: &str =
+    "https://raw.githubusercontent.com/tmtrckr/plugins-registry/main/registry.json";
+
+/// Age after which the index is refreshed in the background
+const INDEX_TTL_SECS: i64 = 6 * 60 * 60;
+
+/// How often the background refresher checks the index age
+const REFRESH_POLL_SECS: u64 = 15 * 60;
+
+/// Sort order for marketplace listings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginSort {
+    /// Best name matches first when searching, otherwise most downloaded
+    #[default]
+    Relevance,
+    Downloads,
+    /// Most recently updated first
+    Updated,
+    Rating,
+    Name,
+}
+
+/// Plugin category with the number of plugins in it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginCategory {
+    pub name: String,
+    pub count: usize,
+}
+
+/// Cached index contents (also the on-disk format)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexSnapshot {
+    fetched_at: i64,
+    registry_urls: Vec<String>,
+    plugins: Vec<RegistryPlugin>,
+}
+
+/// Registry URLs from settings, or the default registry
+pub fn registry_urls(settings: &AppSettings) -> Vec<String> {
+    match &settings.plugin_registry_urls {
+        Some(urls) if !urls.is_empty() => urls.clone(),
+        _ => vec![DEFAULT_REGISTRY_URL.to_string()],
+    }
+}
+
+/// Simple version comparison (assumes semver format)
+pub fn compare_versions(v1: &str, v2: &str) -> i32 {
+    let v1_parts: Vec<u32> = v1.split('.').filter_map(|s| s.parse().ok()).collect();
+    let v2_parts: Vec<u32> = v2.split('.').filter_map(|s| s.parse().ok()).collect();
+
+    for (i, &v1_part) in v1_parts.iter().enumerate() {
+        let v2_part = v2_parts.get(i).copied().unwrap_or(0);
+        if v1_part > v2_part {
+            return 1;
+        } else if v1_part < v2_part {
+            return -1;
+        }
+    }
+
+    if v1_parts.len() < v2_parts.len() {
+        -1
+    } else if v1_parts.len() > v2_parts.len() {
+        1
+    } else {
+        0
+    }
+}
+
+/// Fetch all registries and merge them, keeping the newest version of each plugin.
+/// Fails only if every registry failed.
+pub async fn fetch_registries(urls: &[String]) -> Result<Vec<RegistryPlugin>, String> {
+    use futures::future::join_all;
+
+    let fetch_tasks: Vec<_> = urls
+        .iter()
+        .map(|url| {
+            let mut discovery = PluginDiscovery::new(url.clone());
+            async move { discovery.get_registry().await }
+        })
+        .collect();
+
+    let mut by_id: HashMap<String, RegistryPlugin> = HashMap::new();
+    let mut last_error = None;
+    let mut any_ok = false;
+    for result in join_all(fetch_tasks).await {
+        match result {
+            Ok(registry) => {
+                any_ok = true;
+                for plugin in registry.plugins {
+                    let newer = by_id
+                        .get(&plugin.id)
+                        .is_none_or(|existing| compare_versions(&plugin.latest_version, &existing.latest_version) > 0);
+                    if newer {
+                        by_id.insert(plugin.id.clone(), plugin);
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Warning: Failed to fetch registry: {}", e);
+                last_error = Some(e);
+            }
+        }
+    }
+
+    match (any_ok, last_error) {
+        (false, Some(e)) => Err(e),
+        _ => Ok(by_id.into_values().collect()),
+    }
+}
+
+/// Filter by search text and category, then sort
+pub fn filter_plugins(
+    plugins: &[RegistryPlugin],
+    query: Option<&str>,
+    category: Option<&str>,
+    sort: PluginSort,
+) -> Vec<RegistryPlugin> {
+    let query = query.map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+    let mut results: Vec<RegistryPlugin> = plugins
+        .iter()
+        .filter(|plugin| {
+            category.is_none_or(|category| {
+                plugin.category.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(category))
+            })
+        })
+        .filter(|plugin| query.as_deref().is_none_or(|q| plugin.matches_query(q)))
+        .cloned()
+        .collect();
+
+    match (sort, query.as_deref()) {
+        (PluginSort::Relevance, Some(q)) => results.sort_by_key(|p| {
+            let name = p.name.to_lowercase();
+            (!name.starts_with(q), !name.contains(q), std::cmp::Reverse(p.downloads))
+        }),
+        (PluginSort::Relevance, None) | (PluginSort::Downloads, _) => {
+            results.sort_by_key(|p| std::cmp::Reverse(p.downloads))
+        }
+        // ISO 8601 timestamps sort chronologically as strings
+        (PluginSort::Updated, _) => results.sort_by(|a, b| b.updated_at.cmp(&a.updated_at)),
+        (PluginSort::Rating, _) => results.sort_by(|a, b| {
+            b.rating.unwrap_or(0.0).total_cmp(&a.rating.unwrap_or(0.0))
+                .then(b.rating_count.cmp(&a.rating_count))
+        }),
+        (PluginSort::Name, _) => results.sort_by_key(|p| p.name.to_lowercase()),
+    }
+    results
+}
+
+/// Locally cached, merged index of all configured registries
+pub struct PluginIndex {
+    path: PathBuf,
+    snapshot: RwLock<Option<IndexSnapshot>>,
+}
+
+impl PluginIndex {
+    /// Create the index, loading the copy cached at `path` if there is one
+    pub fn load(path: PathBuf) -> Self {
+        let snapshot = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok());
+        Self {
+            path,
+            snapshot: RwLock::new(snapshot),
+        }
+    }
+
+    /// Cached plugins, if the index was built from these registry URLs
+    pub fn plugins(&self, urls: &[String]) -> Option<Vec<RegistryPlugin>> {
+        self.snapshot
+            .read()
+            .unwrap()
+            .as_ref()
+            .filter(|snapshot| snapshot.registry_urls == urls)
+            .map(|snapshot| snapshot.plugins.clone())
+    }
+
+    /// Whether the index is missing, outdated or built from other registries
+    pub fn is_stale(&self, urls: &[String]) -> bool {
+        let now = chrono::Utc::now().timestamp();
+        self.snapshot.read().unwrap().as_ref().is_none_or(|snapshot| {
+            snapshot.registry_urls != urls || now - snapshot.fetched_at >= INDEX_TTL_SECS
+        })
+    }
+
+    /// Fetch the registries and replace the index; returns the plugin count.
+    /// On failure the previous index is kept.
+    pub async fn refresh(&self, urls: &[String]) -> Result<usize, String> {
+        let plugins = fetch_registries(urls).await?;
+        let snapshot = IndexSnapshot {
+            fetched_at: chrono::Utc::now().timestamp(),
+            registry_urls: urls.to_vec(),
+            plugins,
+        };
+        let count = snapshot.plugins.len();
+
+        match serde_json::to_string(&snapshot) {
+            Ok(content) => {
+                if let Err(e) = std::fs::write(&self.path, content) {
+                    eprintln!("Warning: Failed to write plugin index cache: {}", e);
+                }
+            }
+            Err(e) => eprintln!("Warning: Failed to serialize plugin index: {}", e),
+        }
+        *self.snapshot.write().unwrap() = Some(snapshot);
+        Ok(count)
+    }
+
+    /// Cached plugins for these registries, fetching them first if there are none
+    pub async fn get_or_refresh(&self, urls: &[String]) -> Result<Vec<RegistryPlugin>, String> {
+        if let Some(plugins) = self.plugins(urls) {
+            return Ok(plugins);
+        }
+        self.refresh(urls).await?;
+        Ok(self.plugins(urls).unwrap_or_default())
+    }
+}
+
+/// Category names with plugin counts, most populated first
+pub fn categories(plugins: &[RegistryPlugin]) -> Vec<PluginCategory> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for category in plugins.iter().filter_map(|p| p.category.as_deref()) {
+        *counts.entry(category.to_string()).or_default() += 1;
+    }
+    let mut categories: Vec<PluginCategory> = counts
+        .into_iter()
+        .map(|(name, count)| PluginCategory { name, count })
+        .collect();
+    categories.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    categories
+}
+
+/// Keep the index fresh in the background; registry URL changes refresh right away
+pub fn start_background_refresh(index: Arc<PluginIndex>, settings: Arc<SettingsService>) {
+    let changes = settings.watch(&["plugin_registry_urls"]);
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Warning: Failed to start plugin index refresh: {}", e);
+                return;
+            }
+        };
+        loop {
+            let current = settings.get();
+            if current.enable_marketplace {
+                let urls = registry_urls(&current);
+                if index.is_stale(&urls) {
+                    if let Err(e) = runtime.block_on(index.refresh(&urls)) {
+                        eprintln!("Warning: Failed to refresh plugin index: {}", e);
+                    }
+                }
+            }
+            match changes.recv_timeout(Duration::from_secs(REFRESH_POLL_SECS)) {
+                Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    });
+}
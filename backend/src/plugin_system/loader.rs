@@ -8,6 +8,179 @@ use std::collections::HashMap;
 use libloading::Library;
 use crate::plugin_system::discovery::{PluginManifest, GitHubReleaseAsset};
 
+/// Directory under the plugins directory where downloads are staged
+const STAGING_DIR: &str = ".staging";
+
+/// Suffix of the directory a previous plugin version is kept in during an upgrade
+const BACKUP_SUFFIX: &str = ".previous";
+
+/// `<plugin_dir>.previous`, next to the plugin directory
+fn backup_dir_for(plugin_dir: &Path) -> PathBuf {
+    let mut name = plugin_dir.file_name().unwrap_or_default().to_os_string();
+    name.push(BACKUP_SUFFIX);
+    plugin_dir.with_file_name(name)
+}
+
+/// Numeric version parts; a leading `v` and pre-release suffixes are ignored
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim()
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// Check `min_core_version`/`max_core_version` against `core_version`.
+/// Both bounds are inclusive; a shorter max such as `0.3` allows any `0.3.x`.
+pub fn check_core_compatibility(
+    min_core_version: Option<&str>,
+    max_core_version: Option<&str>,
+    core_version: &str,
+) -> Result<(), String> {
+    let core = version_parts(core_version);
+    if let Some(min) = min_core_version.filter(|v| !v.trim().is_empty()) {
+        let mut min_parts = version_parts(min);
+        let mut core_parts = core.clone();
+        let len = min_parts.len().max(core_parts.len());
+        min_parts.resize(len, 0);
+        core_parts.resize(len, 0);
+        if core_parts < min_parts {
+            return Err(format!(
+                "Requires TimeTracker {} or newer (this is {})",
+                min.trim(),
+                core_version
+            ));
+        }
+    }
+    if let Some(max) = max_core_version.filter(|v| !v.trim().is_empty()) {
+        let max_parts = version_parts(max);
+        let mut core_parts = core;
+        core_parts.resize(max_parts.len(), 0);
+        if core_parts > max_parts {
+            return Err(format!(
+                "Supports TimeTracker up to {} (this is {})",
+                max.trim(),
+                core_version
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check a manifest against this build of the app
+pub fn check_manifest_compatibility(manifest: &PluginManifest) -> Result<(), String> {
+    check_core_compatibility(
+        manifest.plugin.min_core_version.as_deref(),
+        manifest.plugin.max_core_version.as_deref(),
+        crate::updates::CURRENT_VERSION,
+    )
+    .map_err(|e| format!("Plugin {} {} is incompatible: {}", manifest.plugin.name, manifest.plugin.version, e))
+}
+
+/// `major.minor` of a version; patch releases of the SDK keep the vtables
+fn major_minor(version: &str) -> (u64, u64) {
+    let mut parts = version_parts(version);
+    parts.resize(2, 0);
+    (parts[0], parts[1])
+}
+
+/// Check the SDK version a plugin library reports (`None` if it exports no
+/// `_plugin_sdk_version`) against the SDK this build uses. Trait objects cross
+/// the library boundary and every SDK minor release may add trait methods, so
+/// major and minor must match; the patch version may differ.
+pub fn check_sdk_version(reported: Option<&str>) -> Result<(), String> {
+    let expected = time_tracker_plugin_sdk::SDK_VERSION;
+    let (major, minor) = major_minor(expected);
+    match reported {
+        Some(version) if major_minor(version) == (major, minor) => Ok(()),
+        Some(version) => Err(format!(
+            "Plugin was built against SDK {} but this app uses SDK {}.{}; rebuild it with the matching SDK",
+            version.trim(), major, minor
+        )),
+        // `_plugin_sdk_version` was added in SDK 1.1
+        None => Err(format!(
+            "Plugin was built against an SDK older than 1.1 (it does not export `_plugin_sdk_version`); rebuild it against SDK {}.{}",
+            major, minor
+        )),
+    }
+}
+
+/// A downloaded and validated plugin that is not installed yet.
+///
+/// `commit` moves it into the plugins directory, keeping any previous version
+/// as a backup. Dropping it without calling `finish` rolls everything back:
+/// the staging directory is removed and the previous version restored.
+pub struct StagedInstall {
+    staging_dir: PathBuf,
+    plugin_dir: PathBuf,
+    backup_dir: Option<PathBuf>,
+    manifest: Option<PluginManifest>,
+    committed: bool,
+    finished: bool,
+}
+
+impl StagedInstall {
+    /// Validated manifest of the staged plugin
+    pub fn manifest(&self) -> &PluginManifest {
+        self.manifest.as_ref().expect("staged install has a validated manifest")
+    }
+
+    /// Move the staged plugin into place; returns the installed manifest path
+    pub fn commit(&mut self) -> Result<PathBuf, String> {
+        if let Some(parent) = self.plugin_dir.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create plugin directory: {}", e))?;
+        }
+        if self.plugin_dir.exists() {
+            let backup_dir = backup_dir_for(&self.plugin_dir);
+            fs::remove_dir_all(&backup_dir).ok();
+            fs::rename(&self.plugin_dir, &backup_dir)
+                .map_err(|e| format!("Failed to move previous plugin version aside: {}", e))?;
+            self.backup_dir = Some(backup_dir);
+        }
+        if let Err(e) = fs::rename(&self.staging_dir, &self.plugin_dir) {
+            return Err(format!("Failed to move plugin into place: {}", e));
+        }
+        self.committed = true;
+        Ok(self.plugin_dir.join("plugin.toml"))
+    }
+
+    /// Keep the installed plugin and discard the previous version
+    pub fn finish(mut self) {
+        self.finished = true;
+        if let Some(backup_dir) = &self.backup_dir {
+            fs::remove_dir_all(backup_dir).ok();
+        }
+    }
+
+    fn rollback(&mut self) {
+        if self.committed {
+            if let Err(e) = fs::remove_dir_all(&self.plugin_dir) {
+                eprintln!("Warning: Failed to remove partially installed plugin: {}", e);
+            }
+        } else {
+            fs::remove_dir_all(&self.staging_dir).ok();
+        }
+        if let Some(backup_dir) = self.backup_dir.take() {
+            if let Err(e) = fs::rename(&backup_dir, &self.plugin_dir) {
+                eprintln!("Warning: Failed to restore previous plugin version: {}", e);
+            }
+        }
+    }
+}
+
+impl Drop for StagedInstall {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.rollback();
+        }
+    }
+}
+
 /// Plugin loader for dynamic libraries
 pub struct PluginLoader {
     plugins_dir: PathBuf,
@@ -49,21 +222,35 @@ impl PluginLoader {
         self.plugins_dir.join(normalized_author).join(plugin_id)
     }
 
-    /// Download and install plugin from GitHub release
-    pub async fn install_from_release(
+    /// Download and extract a GitHub release into a staging directory and
+    /// validate its manifest. Nothing is installed until `StagedInstall::commit`.
+    pub async fn stage_release(
         &self,
         author: &str,
         plugin_id: &str,
         asset: &GitHubReleaseAsset,
-    ) -> Result<PathBuf, String> {
+    ) -> Result<StagedInstall, String> {
         // Validate author is not empty
         if author.is_empty() {
             return Err("Plugin author is required".to_string());
         }
-        
-        let plugin_dir = self.get_plugin_dir(author, plugin_id);
-        fs::create_dir_all(&plugin_dir)
-            .map_err(|e| format!("Failed to create plugin directory: {}", e))?;
+
+        // Stage inside the plugins directory so the final move is a rename on one filesystem
+        let staging_dir = self.plugins_dir.join(STAGING_DIR).join(format!(
+            "{}-{}",
+            plugin_id,
+            chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        fs::create_dir_all(&staging_dir)
+            .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+        let mut staged = StagedInstall {
+            staging_dir,
+            plugin_dir: self.get_plugin_dir(author, plugin_id),
+            backup_dir: None,
+            manifest: None,
+            committed: false,
+            finished: false,
+        };
 
         // Download asset
         let client = reqwest::Client::new();
@@ -83,25 +270,67 @@ impl PluginLoader {
             .map_err(|e| format!("Failed to read download: {}", e))?;
 
         // Save to temporary file
-        let archive_path = plugin_dir.join(&asset.name);
+        let archive_path = staged.staging_dir.join(&asset.name);
         let mut file = fs::File::create(&archive_path)
             .map_err(|e| format!("Failed to create archive file: {}", e))?;
         file.write_all(&bytes)
             .map_err(|e| format!("Failed to write archive: {}", e))?;
 
         // Extract archive
-        self.extract_archive(&archive_path, &plugin_dir)?;
+        self.extract_archive(&archive_path, &staged.staging_dir)?;
 
         // Remove archive file
         fs::remove_file(&archive_path).ok();
 
-        // Find manifest path
-        let manifest_path = plugin_dir.join("plugin.toml");
+        // Find and validate manifest
+        let manifest_path = staged.staging_dir.join("plugin.toml");
         if !manifest_path.exists() {
             return Err("plugin.toml not found in archive".to_string());
         }
+        let manifest = self.load_manifest(&manifest_path)?;
+        self.validate_manifest(&manifest)?;
+        check_manifest_compatibility(&manifest)?;
+        staged.manifest = Some(manifest);
 
-        Ok(manifest_path)
+        Ok(staged)
+    }
+
+    /// Remove staging directories left behind by an interrupted install and put
+    /// back previous versions an interrupted upgrade had moved aside
+    pub fn cleanup_staging(&self) {
+        let staging_root = self.plugins_dir.join(STAGING_DIR);
+        if staging_root.exists() {
+            if let Err(e) = fs::remove_dir_all(&staging_root) {
+                eprintln!("Warning: Failed to remove plugin staging directory: {}", e);
+            }
+        }
+
+        let Ok(authors) = fs::read_dir(&self.plugins_dir) else {
+            return;
+        };
+        for author_dir in authors.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()) {
+            let Ok(entries) = fs::read_dir(&author_dir) else {
+                continue;
+            };
+            for backup_dir in entries.flatten().map(|entry| entry.path()) {
+                let Some(plugin_name) = backup_dir
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_suffix(BACKUP_SUFFIX))
+                    .filter(|name| !name.is_empty())
+                else {
+                    continue;
+                };
+                let plugin_dir = author_dir.join(plugin_name);
+                if plugin_dir.exists() {
+                    fs::remove_dir_all(&plugin_dir).ok();
+                }
+                match fs::rename(&backup_dir, &plugin_dir) {
+                    Ok(()) => eprintln!("Restored previous version of plugin {} after an interrupted upgrade", plugin_name),
+                    Err(e) => eprintln!("Warning: Failed to restore previous version of plugin {}: {}", plugin_name, e),
+                }
+            }
+        }
     }
 
     /// Extract archive (zip or tar.gz)
@@ -279,6 +508,13 @@ impl PluginLoader {
         }
     }
     
+    /// Unload every plugin library. Instances created from them must be dropped first.
+    pub fn unload_all_libraries(&self) {
+        if let Ok(mut libs) = self.loaded_libraries.lock() {
+            libs.clear();
+        }
+    }
+
     /// Check if a plugin library is currently loaded
     pub fn is_plugin_loaded(&self, plugin_id: &str) -> bool {
         let libs = self.loaded_libraries.lock().ok();
@@ -286,14 +522,20 @@ impl PluginLoader {
     }
 
     /// Load a plugin dynamically from its installed directory
-    /// Returns the loaded plugin instance ready for initialization
+    /// Returns the loaded plugin instance ready for initialization.
+    /// Refuses a plugin that is already loaded: replacing its library would
+    /// unmap the code the running instance points into.
     pub fn load_dynamic_plugin(
         &self,
         author: &str,
         plugin_id: &str,
     ) -> Result<Box<dyn time_tracker_plugin_sdk::Plugin>, String> {
         use libloading::{Library, Symbol};
-        use time_tracker_plugin_sdk::PluginCreateFn;
+        
+        if self.is_plugin_loaded(plugin_id) {
+            return Err(format!("Plugin {} is already loaded; unload it first", plugin_id));
+        }
+        use time_tracker_plugin_sdk::{PluginCreateFn, PluginSdkVersionFn};
         
         let plugin_dir = self.get_plugin_dir(author, plugin_id);
         
@@ -337,6 +579,18 @@ impl PluginLoader {
             let lib = Library::new(&lib_path)
                 .map_err(|e| format!("Failed to load plugin library {}: {}", lib_path.display(), e))?;
             
+            // Refuse libraries built against another SDK before touching their vtables
+            let reported_version = match lib.get::<PluginSdkVersionFn>(b"_plugin_sdk_version") {
+                Ok(version_fn) => {
+                    let version_ptr = version_fn();
+                    (!version_ptr.is_null())
+                        .then(|| std::ffi::CStr::from_ptr(version_ptr).to_string_lossy().into_owned())
+                }
+                Err(_) => None,
+            };
+            check_sdk_version(reported_version.as_deref())
+                .map_err(|e| format!("Cannot load plugin {}: {}", plugin_id, e))?;
+            
             // Resolve the _plugin_create symbol
             let create_fn: Symbol<PluginCreateFn> = lib.get(b"_plugin_create")
                 .map_err(|e| format!("Failed to resolve _plugin_create symbol: {}", e))?;
@@ -534,6 +788,10 @@ impl PluginLoader {
             if let Some(ref manifest_path_str) = manifest_path_clone {
                 let manifest_path = std::path::PathBuf::from(manifest_path_str);
                 if let Ok(manifest) = self.load_manifest(&manifest_path) {
+                    if let Err(e) = check_manifest_compatibility(&manifest) {
+                        eprintln!("Warning: Skipping plugin {}: {}", plugin_id, e);
+                        continue;
+                    }
                     plugin_manifests.push((plugin_id.clone(), manifest));
                     plugin_info.insert(plugin_id.clone(), (author.clone(), manifest_path_clone));
                 } else {
@@ -577,3 +835,102 @@ impl PluginLoader {
         Ok(loaded_plugins)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty plugins directory of its own for one test
+    fn scratch_plugins_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("time-tracker-plugins-{}-{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn staged(loader: &PluginLoader, plugin_id: &str, version: &str) -> StagedInstall {
+        let staging_dir = loader.plugins_dir().join(STAGING_DIR).join(plugin_id);
+        fs::create_dir_all(&staging_dir).unwrap();
+        fs::write(staging_dir.join("plugin.toml"), version).unwrap();
+        StagedInstall {
+            staging_dir,
+            plugin_dir: loader.get_plugin_dir("Jane Doe", plugin_id),
+            backup_dir: None,
+            manifest: None,
+            committed: false,
+            finished: false,
+        }
+    }
+
+    #[test]
+    fn upgrades_keep_the_previous_version_next_to_the_plugin_until_they_finish() {
+        let loader = PluginLoader::new(scratch_plugins_dir("upgrade"));
+        let plugin_dir = loader.get_plugin_dir("Jane Doe", "com.example.goals");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("plugin.toml"), "1.0").unwrap();
+
+        let mut upgrade = staged(&loader, "com.example.goals", "2.0");
+        upgrade.commit().unwrap();
+        let backup_dir = plugin_dir.with_file_name("com.example.goals.previous");
+        assert_eq!(fs::read_to_string(backup_dir.join("plugin.toml")).unwrap(), "1.0");
+
+        // Interrupted before `finish`: the next start puts 1.0 back
+        std::mem::forget(upgrade);
+        loader.cleanup_staging();
+        assert_eq!(fs::read_to_string(plugin_dir.join("plugin.toml")).unwrap(), "1.0");
+        assert!(!backup_dir.exists());
+
+        let mut upgrade = staged(&loader, "com.example.goals", "2.0");
+        upgrade.commit().unwrap();
+        upgrade.finish();
+        assert_eq!(fs::read_to_string(plugin_dir.join("plugin.toml")).unwrap(), "2.0");
+        assert!(!backup_dir.exists());
+        fs::remove_dir_all(loader.plugins_dir()).ok();
+    }
+
+    #[test]
+    fn core_version_bounds_are_inclusive_and_padded() {
+        // (min_core_version, max_core_version, core version, accepted)
+        let cases: &[(Option<&str>, Option<&str>, &str, bool)] = &[
+            (None, None, "0.4.1", true),
+            (Some("0.4"), None, "0.4.0", true),
+            (Some("0.4.0"), None, "0.4", true),
+            (Some("0.4.1"), None, "0.4", false),
+            (Some("0.5"), None, "0.4.9", false),
+            (Some("0.10"), None, "0.9.0", false),
+            (Some("v0.3.0"), None, "0.3.0-beta.1", true),
+            // A shorter max covers every release under it
+            (None, Some("0.4"), "0.4.1", true),
+            (None, Some("0.4"), "0.4.99", true),
+            (None, Some("0.4"), "0.5.0", false),
+            (None, Some("1"), "1.9.3", true),
+            (None, Some("0.4.0"), "0.4.1", false),
+            (None, Some("0.4.0"), "0.4", true),
+            (Some("0.3"), Some("0.3"), "0.3.7", true),
+            // Empty or blank bounds are no bounds
+            (Some(""), Some("   "), "0.4.1", true),
+            (Some(" 0.4 "), Some(" 0.4 "), "0.4.2", true),
+            (Some(" 0.5 "), None, "0.4.2", false),
+        ];
+        for &(min, max, core, accepted) in cases {
+            let result = check_core_compatibility(min, max, core);
+            assert_eq!(result.is_ok(), accepted, "min {:?}, max {:?}, core {}: {:?}", min, max, core, result);
+        }
+
+        let too_old = check_core_compatibility(Some(" 0.5 "), None, "0.4.2").unwrap_err();
+        assert_eq!(too_old, "Requires TimeTracker 0.5 or newer (this is 0.4.2)");
+        let too_new = check_core_compatibility(None, Some("0.4"), "0.5.0").unwrap_err();
+        assert_eq!(too_new, "Supports TimeTracker up to 0.4 (this is 0.5.0)");
+    }
+
+    #[test]
+    fn plugins_must_match_the_sdk_version() {
+        let (major, minor) = major_minor(time_tracker_plugin_sdk::SDK_VERSION);
+        assert!(check_sdk_version(Some(time_tracker_plugin_sdk::SDK_VERSION)).is_ok());
+        assert!(check_sdk_version(Some(&format!("{}.{}.99", major, minor))).is_ok());
+        assert!(check_sdk_version(Some(&format!("{}.{}", major, minor + 1))).is_err());
+        assert!(check_sdk_version(Some(&format!("{}.{}.0", major + 1, minor))).is_err());
+        assert!(check_sdk_version(Some("1.0.0")).is_err());
+        assert!(check_sdk_version(None).unwrap_err().contains("older than 1.1"));
+    }
+}
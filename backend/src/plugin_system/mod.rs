@@ -6,15 +6,18 @@
 //! - Dynamic library loading
 //! - Plugin lifecycle management
 //! - Plugin discovery from registry and GitHub
+//! - Cached marketplace index with category browsing
 //! - Plugin installation and loading
 
 pub mod registry;
 pub mod extensions;
 pub mod api;
 pub mod discovery;
+pub mod index;
 pub mod loader;
 
 pub use registry::PluginRegistry;
 pub use extensions::ExtensionRegistry;
 pub use discovery::PluginDiscovery;
+pub use index::PluginIndex;
 pub use loader::PluginLoader;
@@ -2,14 +2,52 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use crate::database::Database;
+use serde::Serialize;
 
 // Re-export SDK types for convenience
 pub use time_tracker_plugin_sdk::Plugin as PluginTrait;
+use time_tracker_plugin_sdk::{ActivityDraft, ActivityWriteDecision};
+
+/// Command name under which `before_activity_save` calls are counted
+pub const HOOK_BEFORE_ACTIVITY_SAVE: &str = "before_activity_save";
+
+/// Call counts and wall time of one plugin command (or hook)
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CommandMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    pub total_ms: f64,
+    pub max_ms: f64,
+}
+
+impl CommandMetrics {
+    fn record(&mut self, elapsed_ms: f64, failed: bool) {
+        self.calls += 1;
+        if failed {
+            self.errors += 1;
+        }
+        self.total_ms += elapsed_ms;
+        self.max_ms = self.max_ms.max(elapsed_ms);
+    }
+}
+
+/// Resource usage of one plugin since the app started
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PluginMetrics {
+    pub plugin_id: String,
+    /// Totals over all commands and hooks
+    #[serde(flatten)]
+    pub totals: CommandMetrics,
+    pub last_called_at: Option<i64>,
+    pub commands: HashMap<String, CommandMetrics>,
+}
 
 /// Registry for managing all loaded plugins
 pub struct PluginRegistry {
     plugins: Arc<Mutex<HashMap<String, Box<dyn PluginTrait>>>>,
+    metrics: Mutex<HashMap<String, PluginMetrics>>,
     db: Arc<Database>,
 }
 
@@ -18,6 +56,7 @@ impl PluginRegistry {
     pub fn new(db: Arc<Database>) -> Self {
         Self {
             plugins: Arc::new(Mutex::new(HashMap::new())),
+            metrics: Mutex::new(HashMap::new()),
             db,
         }
     }
@@ -60,12 +99,106 @@ impl PluginRegistry {
         let plugins = self.plugins.lock().map_err(|e| format!("Failed to lock plugin registry: {}", e))?;
         
         if let Some(plugin) = plugins.get(plugin_id) {
-            plugin.invoke_command(command, params, api)
+            let started = Instant::now();
+            let result = plugin.invoke_command(command, params, api);
+            self.record_call(plugin_id, command, started, result.is_err());
+            result
         } else {
             Err(format!("Plugin {} not found", plugin_id))
         }
     }
     
+    fn record_call(&self, plugin_id: &str, command: &str, started: Instant, failed: bool) {
+        let elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+        let Ok(mut metrics) = self.metrics.lock() else {
+            return;
+        };
+        let entry = metrics.entry(plugin_id.to_string()).or_insert_with(|| PluginMetrics {
+            plugin_id: plugin_id.to_string(),
+            ..Default::default()
+        });
+        entry.totals.record(elapsed_ms, failed);
+        entry.last_called_at = Some(chrono::Utc::now().timestamp());
+        entry.commands.entry(command.to_string()).or_default().record(elapsed_ms, failed);
+    }
+    
+    /// Usage metrics per plugin, most total wall time first
+    pub fn get_metrics(&self) -> Vec<PluginMetrics> {
+        let Ok(metrics) = self.metrics.lock() else {
+            return Vec::new();
+        };
+        let mut result: Vec<PluginMetrics> = metrics.values().cloned().collect();
+        result.sort_by(|a, b| b.totals.total_ms.total_cmp(&a.totals.total_ms));
+        result
+    }
+    
+    /// Clear usage metrics
+    pub fn reset_metrics(&self) {
+        if let Ok(mut metrics) = self.metrics.lock() {
+            metrics.clear();
+        }
+    }
+    
+    /// Tell every plugin which settings changed; `api_for` builds the API for a plugin ID
+    pub fn notify_settings_changed<F, A>(&self, keys: &[String], api_for: F)
+    where
+        F: Fn(&str) -> A,
+        A: time_tracker_plugin_sdk::PluginAPIInterface,
+    {
+        let Ok(plugins) = self.plugins.lock() else {
+            return;
+        };
+        for (plugin_id, plugin) in plugins.iter() {
+            let api = api_for(plugin_id);
+            plugin.on_settings_changed(keys, &api);
+        }
+    }
+    
+    /// Run every plugin's `before_activity_save` hook in plugin ID order, each seeing
+    /// the previous modifications; `None` if a plugin dropped the activity
+    pub fn before_activity_save(&self, activity: ActivityDraft) -> Option<ActivityDraft> {
+        let Ok(plugins) = self.plugins.lock() else {
+            return Some(activity);
+        };
+        let mut plugin_ids: Vec<&String> = plugins.keys().collect();
+        plugin_ids.sort();
+        
+        let mut activity = activity;
+        for plugin_id in plugin_ids {
+            let started = Instant::now();
+            let decision = plugins[plugin_id].before_activity_save(&activity);
+            self.record_call(plugin_id, HOOK_BEFORE_ACTIVITY_SAVE, started, false);
+            match decision {
+                ActivityWriteDecision::Allow => {}
+                ActivityWriteDecision::Modify(modified) => activity = modified,
+                ActivityWriteDecision::Drop => return None,
+            }
+        }
+        Some(activity)
+    }
+    
+    /// Call `shutdown()` on one plugin; returns false if it is not registered
+    pub fn shutdown(&self, plugin_id: &str) -> Result<bool, String> {
+        let plugins = self.plugins.lock()
+            .map_err(|e| format!("Failed to lock plugin registry: {}", e))?;
+        match plugins.get(plugin_id) {
+            Some(plugin) => plugin.shutdown().map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Call `shutdown()` on every plugin; errors are logged and do not stop the others
+    pub fn shutdown_all(&self) {
+        let Ok(plugins) = self.plugins.lock() else {
+            return;
+        };
+        for (plugin_id, plugin) in plugins.iter() {
+            if let Err(e) = plugin.shutdown() {
+                eprintln!("Warning: Plugin {} failed to shut down: {}", plugin_id, e);
+            }
+        }
+    }
+    
     /// Get all registered plugin IDs
     pub fn get_plugin_ids(&self) -> Vec<String> {
         let plugins = self.plugins.lock().ok();
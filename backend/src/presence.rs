@@ -0,0 +1,326 @@
+//! MQTT presence publishing
+//!
+//! Publishes the current tracking state as a retained JSON message so
+//! home-automation setups can react to it (e.g. a "do not disturb" light).
+//! Disabled unless the `mqtt_enabled` setting is `true`. Configuration keys:
+//! `mqtt_host`, `mqtt_port` (default 1883), `mqtt_topic` (default
+//! `timetracker/presence`), `mqtt_username`, `mqtt_password`.
+//!
+//! Activities in categories or projects excluded from export are published
+//! without their app and category (`on_call` is `false` for them).
+//!
+//! Pomodoro phases come from plugin events: any `pomodoro.*` event whose
+//! payload has a `phase` field updates the published phase (`null` clears it).
+//!
+//! The call detection behind `on_call` is also offered to plugins (see
+//! `in_meeting`), e.g. to hold a break prompt until a meeting ends.
+
+use crate::database::{Activity, Database};
+use crate::events::EventBus;
+use crate::tracker::Tracker;
+use rumqttc::{Client, LastWill, MqttOptions, QoS};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const DEFAULT_TOPIC: &str = "timetracker/presence";
+
+/// How often presence is recomputed (and settings re-read)
+const POLL_INTERVAL_SECS: u64 = 10;
+
+/// Unchanged presence is republished at this interval
+const REPUBLISH_INTERVAL_SECS: u64 = 300;
+
+/// An activity older than this is no longer considered current
+const CURRENT_ACTIVITY_WINDOW_SECS: i64 = 60;
+
+/// Category that marks a call or meeting
+const MEETINGS_CATEGORY: &str = "Meetings";
+
+/// Apps that indicate an ongoing call regardless of category
+const CALL_APPS: &[&str] = &["zoom", "microsoft teams", "teams", "facetime", "skype", "webex", "discord"];
+
+/// Domains that indicate an ongoing call regardless of category
+const CALL_DOMAINS: &[&str] = &["meet.google.com", "teams.microsoft.com", "zoom.us", "whereby.com"];
+
+#[derive(Debug, Clone, PartialEq)]
+struct MqttConfig {
+    host: String,
+    port: u16,
+    topic: String,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+struct Publisher {
+    config: MqttConfig,
+    client: Client,
+}
+
+impl Publisher {
+    fn connect(config: MqttConfig) -> Self {
+        let client_id = format!("time-tracker-{}", std::process::id());
+        let mut options = MqttOptions::new(client_id, config.host.clone(), config.port);
+        options.set_keep_alive(Duration::from_secs(30));
+        options.set_last_will(LastWill::new(
+            config.topic.clone(),
+            serde_json::json!({ "state": "offline" }).to_string(),
+            QoS::AtLeastOnce,
+            true,
+        ));
+        if let Some(username) = &config.username {
+            options.set_credentials(username.clone(), config.password.clone().unwrap_or_default());
+        }
+
+        let (client, mut connection) = Client::new(options, 10);
+        std::thread::spawn(move || {
+            // Iterating drives the connection and reconnects after errors;
+            // it ends once the client is dropped.
+            for notification in connection.iter() {
+                if let Err(e) = notification {
+                    eprintln!("Warning: MQTT connection error: {}", e);
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+            }
+        });
+
+        Self { config, client }
+    }
+
+    fn publish(&self, payload: &str) {
+        if let Err(e) = self.client.try_publish(self.config.topic.clone(), QoS::AtLeastOnce, true, payload) {
+            eprintln!("Warning: Failed to publish MQTT presence: {}", e);
+        }
+    }
+}
+
+impl Drop for Publisher {
+    fn drop(&mut self) {
+        self.client.try_disconnect().ok();
+    }
+}
+
+fn load_config(db: &Database) -> Option<MqttConfig> {
+    let typed = crate::settings::load(db);
+    if !typed.mqtt_enabled {
+        return None;
+    }
+    let settings = db.get_all_settings().ok()?;
+    let host = settings.get("mqtt_host").map(|h| h.trim().to_string()).filter(|h| !h.is_empty())?;
+    let non_empty = |key: &str| settings.get(key).cloned().filter(|v| !v.is_empty());
+
+    Some(MqttConfig {
+        host,
+        port: typed.mqtt_port,
+        topic: non_empty("mqtt_topic").unwrap_or_else(|| DEFAULT_TOPIC.to_string()),
+        username: non_empty("mqtt_username"),
+        password: non_empty("mqtt_password"),
+    })
+}
+
+/// The latest activity, if it is still current at `now`
+fn current_activity(db: &Database, now: i64) -> Option<Activity> {
+    db.get_latest_activity()
+        .ok()
+        .flatten()
+        .filter(|a| a.started_at + a.duration_sec >= now - CURRENT_ACTIVITY_WINDOW_SECS)
+}
+
+fn category_name(db: &Database, category_id: Option<i64>) -> Option<String> {
+    let id = category_id?;
+    db.get_categories().ok()?.into_iter().find(|c| c.id == id).map(|c| c.name)
+}
+
+/// Whether `activity` must not leave the machine; errors count as excluded
+fn is_excluded_from_export(db: &Database, activity: &Activity) -> bool {
+    db.get_export_excluded_activity_ids(activity.started_at, activity.started_at)
+        .map(|ids| ids.contains(&activity.id))
+        .unwrap_or(true)
+}
+
+/// Whether `activity` (in `category`) is a call: the Meetings category or a
+/// known call app or site
+fn is_call(activity: &Activity, category: Option<&str>) -> bool {
+    let app_lower = activity.app_name.to_lowercase();
+    category == Some(MEETINGS_CATEGORY)
+        || CALL_APPS.iter().any(|app| app_lower == *app)
+        || activity
+            .domain
+            .as_deref()
+            .map(|d| CALL_DOMAINS.iter().any(|cd| d == *cd || d.ends_with(&format!(".{}", cd))))
+            .unwrap_or(false)
+}
+
+/// Whether the user is in a call or meeting at `now`, judged by the current
+/// activity. Idle time counts as the activity it interrupted, so listening to
+/// a call without touching the keyboard is still a meeting. Turns `false` once
+/// tracking has been stopped or paused for `CURRENT_ACTIVITY_WINDOW_SECS`,
+/// when the latest activity is no longer current.
+pub fn in_meeting(db: &Database, now: i64) -> bool {
+    let activity = match current_activity(db, now) {
+        Some(activity) if activity.is_idle => activity_before_idle(db, &activity),
+        activity => activity,
+    };
+    activity.is_some_and(|activity| is_call(&activity, category_name(db, activity.category_id).as_deref()))
+}
+
+/// The non-idle activity that ran up to the start of `idle`, if any
+fn activity_before_idle(db: &Database, idle: &Activity) -> Option<Activity> {
+    db.get_activities(idle.started_at - 86400, idle.started_at, None, None, Some(true), None, None)
+        .ok()?
+        .into_iter()
+        .filter(|a| a.started_at < idle.started_at)
+        .max_by_key(|a| (a.started_at, a.id))
+        .filter(|a| a.started_at + a.duration_sec >= idle.started_at - CURRENT_ACTIVITY_WINDOW_SECS)
+}
+
+/// App, category ID, category name and `on_call` published for the current
+/// activity; all empty for none or one excluded from export
+fn activity_details(db: &Database, activity: Option<Activity>) -> (Option<String>, Option<i64>, Option<String>, bool) {
+    match activity.filter(|a| !is_excluded_from_export(db, a)) {
+        Some(activity) => {
+            let category = category_name(db, activity.category_id);
+            let on_call = is_call(&activity, category.as_deref());
+            (Some(activity.app_name), activity.category_id, category, on_call)
+        }
+        None => (None, None, None, false),
+    }
+}
+
+/// Build the presence message from tracker state and the latest activity
+fn current_presence(
+    db: &Database,
+    tracker: &Mutex<Option<Arc<Tracker>>>,
+    pomodoro_phase: &Mutex<Option<String>>,
+) -> serde_json::Value {
+    let now = chrono::Utc::now().timestamp();
+    let (is_running, is_paused) = tracker
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|t| (t.is_running(), t.is_paused()))
+        .unwrap_or((false, false));
+
+    let activity = current_activity(db, now);
+
+    let state = if !is_running {
+        "stopped"
+    } else if is_paused {
+        "paused"
+    } else if activity.as_ref().map(|a| a.is_idle).unwrap_or(true) {
+        "idle"
+    } else {
+        "active"
+    };
+
+    let (app, category_id, category, on_call) = activity_details(db, activity.filter(|a| state == "active" && !a.is_idle));
+
+    serde_json::json!({
+        "state": state,
+        "app": app,
+        "category_id": category_id,
+        "category": category,
+        "on_call": on_call,
+        "pomodoro_phase": pomodoro_phase.lock().unwrap().clone(),
+        "updated_at": now,
+    })
+}
+
+/// Start the presence publisher thread
+pub fn start(db: Arc<Database>, tracker: Arc<Mutex<Option<Arc<Tracker>>>>, event_bus: &EventBus) {
+    let pomodoro_phase: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let phase_for_events = Arc::clone(&pomodoro_phase);
+    event_bus.subscribe(move |event| {
+        if !event.name.starts_with("pomodoro.") {
+            return;
+        }
+        if let Some(phase) = event.payload.get("phase") {
+            *phase_for_events.lock().unwrap() = phase.as_str().map(str::to_string);
+        }
+    });
+
+    std::thread::spawn(move || {
+        let mut publisher: Option<Publisher> = None;
+        let mut last_payload: Option<(serde_json::Value, Instant)> = None;
+
+        loop {
+            let config = load_config(&db);
+            if publisher.as_ref().map(|p| &p.config) != config.as_ref() {
+                publisher = config.map(Publisher::connect);
+                last_payload = None;
+            }
+
+            if let Some(publisher) = &publisher {
+                let mut presence = current_presence(&db, &tracker, &pomodoro_phase);
+                // Compare without the timestamp so unchanged state is not republished every poll
+                let updated_at = presence.as_object_mut().and_then(|p| p.remove("updated_at"));
+                let changed = last_payload.as_ref().is_none_or(|(last, sent_at)| {
+                    *last != presence || sent_at.elapsed() >= Duration::from_secs(REPUBLISH_INTERVAL_SECS)
+                });
+                if changed {
+                    let mut message = presence.clone();
+                    if let (Some(obj), Some(updated_at)) = (message.as_object_mut(), updated_at) {
+                        obj.insert("updated_at".to_string(), updated_at);
+                    }
+                    publisher.publish(&message.to_string());
+                    last_payload = Some((presence, Instant::now()));
+                }
+            }
+
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::common::SOURCE_TRACKER;
+    use std::path::PathBuf;
+
+    #[test]
+    fn meetings_are_detected_from_the_current_activity() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let now = 1_700_000_000;
+        assert!(!in_meeting(&db, now));
+
+        let editor = db.upsert_activity("Editor", None, None, now - 30, SOURCE_TRACKER, None).unwrap();
+        assert!(!in_meeting(&db, now));
+        let meetings = db.find_category_by_name(MEETINGS_CATEGORY).unwrap();
+        db.update_activity_category(editor, meetings).unwrap();
+        assert!(in_meeting(&db, now));
+
+        db.upsert_activity("Zoom", None, None, now - 10, SOURCE_TRACKER, None).unwrap();
+        assert!(in_meeting(&db, now));
+        assert!(!in_meeting(&db, now + 3600), "no longer current");
+    }
+
+    #[test]
+    fn idle_during_a_call_is_still_a_meeting() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let now = 1_700_000_000;
+        db.upsert_activity("Zoom", None, None, now - 600, SOURCE_TRACKER, None).unwrap();
+        db.upsert_activity("Zoom", None, None, now - 300, SOURCE_TRACKER, None).unwrap();
+        db.record_idle_start(now - 290).unwrap();
+        db.update_idle_duration(now - 290, 280).unwrap();
+        assert!(in_meeting(&db, now));
+
+        db.upsert_activity("Editor", None, None, now + 60, SOURCE_TRACKER, None).unwrap();
+        db.record_idle_start(now + 90).unwrap();
+        assert!(!in_meeting(&db, now + 100), "idle after leaving the call");
+    }
+
+    #[test]
+    fn activities_excluded_from_export_are_published_without_details() {
+        let db = Database::new(PathBuf::from(":memory:")).unwrap();
+        let now = 1_700_000_000;
+        let id = db.upsert_activity("Zoom", None, None, now - 10, SOURCE_TRACKER, None).unwrap();
+        let meetings = db.find_category_by_name(MEETINGS_CATEGORY).unwrap();
+        db.update_activity_category(id, meetings).unwrap();
+        let (app, _, category, on_call) = activity_details(&db, current_activity(&db, now));
+        assert_eq!((app.as_deref(), category.as_deref(), on_call), (Some("Zoom"), Some(MEETINGS_CATEGORY), true));
+
+        db.set_category_exclude_from_export(meetings.unwrap(), true).unwrap();
+        assert_eq!(activity_details(&db, current_activity(&db, now)), (None, None, None, false));
+    }
+}
@@ -0,0 +1,150 @@
+//! Settings profiles (e.g. work vs personal modes)
+//!
+//! Switching to a profile writes its settings, restricts categorization to
+//! its rules (`profile_rule_ids`) and stores its hidden categories
+//! (`hidden_category_ids`) for the UI. The active profile name is kept in
+//! the `active_profile` setting.
+//!
+//! Profiles with `auto_switch` conditions are activated automatically when
+//! their conditions start to hold (time of day, weekday, Wi-Fi network); a
+//! manual switch sticks until the matching profile changes again.
+
+use crate::database::{Database, ProfileAutoSwitch, SettingsProfile};
+use crate::events::{EventBus, EVENT_PROFILE_SWITCHED};
+use crate::settings::SettingsService;
+use chrono::{Datelike, Local, NaiveTime};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often auto-switch conditions are evaluated
+const POLL_INTERVAL_SECS: u64 = 60;
+
+/// Apply a profile and make it the active one
+pub fn switch_profile(
+    db: &Database,
+    settings_service: &SettingsService,
+    event_bus: &EventBus,
+    name: &str,
+) -> Result<(), String> {
+    let profile = db
+        .get_settings_profile(name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Profile not found: {}", name))?;
+
+    let mut settings = profile.settings.clone();
+    settings.insert("active_profile".to_string(), profile.name.clone());
+    settings.insert(
+        "hidden_category_ids".to_string(),
+        serde_json::to_string(&profile.hidden_category_ids).map_err(|e| e.to_string())?,
+    );
+    settings_service.set_many(&settings)?;
+    match &profile.rule_ids {
+        Some(ids) => db
+            .set_setting("profile_rule_ids", &serde_json::to_string(ids).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?,
+        None => db.delete_setting("profile_rule_ids").map_err(|e| e.to_string())?,
+    }
+
+    event_bus.emit(EVENT_PROFILE_SWITCHED, serde_json::json!({ "name": profile.name }));
+    Ok(())
+}
+
+/// Name of the connected Wi-Fi network, if it can be determined
+pub fn current_network() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    let (program, args, prefix): (&str, &[&str], &str) =
+        ("networksetup", &["-getairportnetwork", "en0"], "Current Wi-Fi Network:");
+    #[cfg(target_os = "windows")]
+    let (program, args, prefix): (&str, &[&str], &str) = ("netsh", &["wlan", "show", "interfaces"], "SSID");
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let (program, args, prefix): (&str, &[&str], &str) = ("iwgetid", &["-r"], "");
+
+    let output = std::process::Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout
+        .lines()
+        .map(str::trim)
+        // netsh also prints a `BSSID` line; only the exact `SSID` key matches
+        .find(|line| line.starts_with(prefix) && !line.starts_with("BSSID"))
+        .map(|line| line[prefix.len()..].trim_start_matches([' ', ':']).trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn parse_time(value: &Option<String>) -> Option<NaiveTime> {
+    value.as_deref().and_then(|v| NaiveTime::parse_from_str(v, "%H:%M").ok())
+}
+
+/// Whether the conditions hold at the given local time and network
+fn conditions_match(conditions: &ProfileAutoSwitch, now: chrono::DateTime<Local>, network: Option<&str>) -> bool {
+    if !conditions.days.is_empty() && !conditions.days.contains(&now.weekday().num_days_from_monday()) {
+        return false;
+    }
+    if let (Some(start), Some(end)) = (parse_time(&conditions.start_time), parse_time(&conditions.end_time)) {
+        let time = now.time();
+        let in_window = if start <= end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        };
+        if !in_window {
+            return false;
+        }
+    }
+    if !conditions.networks.is_empty() {
+        let Some(network) = network else {
+            return false;
+        };
+        if !conditions.networks.iter().any(|n| n.eq_ignore_ascii_case(network)) {
+            return false;
+        }
+    }
+    true
+}
+
+/// First profile (by name) whose auto-switch conditions hold
+fn matching_profile(profiles: &[SettingsProfile]) -> Option<String> {
+    let needs_network = profiles
+        .iter()
+        .filter_map(|p| p.auto_switch.as_ref())
+        .any(|a| !a.networks.is_empty());
+    let network = if needs_network { current_network() } else { None };
+    let now = Local::now();
+    profiles
+        .iter()
+        .find(|p| {
+            p.auto_switch
+                .as_ref()
+                .is_some_and(|a| conditions_match(a, now, network.as_deref()))
+        })
+        .map(|p| p.name.clone())
+}
+
+/// Start the automatic profile switcher
+pub fn start(
+    db: Arc<Database>,
+    settings_service: Arc<SettingsService>,
+    event_bus: Arc<EventBus>,
+) {
+    std::thread::spawn(move || {
+        let mut last_match: Option<String> = None;
+        loop {
+            let profiles = db.get_settings_profiles().unwrap_or_default();
+            let matched = matching_profile(&profiles);
+            if matched != last_match {
+                if let Some(name) = &matched {
+                    let active = db.get_setting("active_profile").ok().flatten();
+                    if active.as_deref() != Some(name.as_str()) {
+                        if let Err(e) = switch_profile(&db, &settings_service, &event_bus, name) {
+                            eprintln!("Warning: Failed to switch to profile {}: {}", name, e);
+                        }
+                    }
+                }
+                last_match = matched;
+            }
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
@@ -0,0 +1,258 @@
+//! Replay of recorded activity samples
+//!
+//! For tracker development: a recording is fed through the same steps as live
+//! samples (plugin hooks, merging and rule-based categorization in
+//! `record_sample`, and the tracker's `IdleState`), so changes to idle
+//! handling, merging or categorization can be checked deterministically
+//! instead of by sitting at the machine for hours.
+//!
+//! A recording is a JSON lines file with one sample per line:
+//!
+//! ```text
+//! {"app_name": "Code", "window_title": "main.rs - tracker", "timestamp": 1700000000}
+//! {"app_name": "Code", "window_title": "main.rs - tracker", "timestamp": 1700000005, "idle_sec": 400}
+//! ```
+//!
+//! `idle_sec` is the input idle time at that moment (what the OS idle monitor
+//! would report, default 0). Samples are stored with source `replay` unless a
+//! line sets `source`; browser domains are parsed from titles as for live
+//! samples when `domain` is missing.
+//!
+//! The replay keeps the recording's timestamps and waits `gap / speed` between
+//! samples (`speed` 0 replays as fast as possible). It runs outside the
+//! tracker's poll loop, whose clock is the wall clock, so results do not depend
+//! on when polls happen. Only available in development builds.
+
+use crate::data_sources::{record_sample, ActivitySample};
+use crate::database::common::SOURCE_REPLAY;
+use crate::database::Database;
+use crate::plugin_system::{ExtensionRegistry, PluginRegistry};
+use crate::tracker::{extract_domain, IdleObservation, IdleState};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// One recorded observation
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct ReplaySample {
+    pub app_name: String,
+    #[serde(default)]
+    pub window_title: Option<String>,
+    #[serde(default)]
+    pub domain: Option<String>,
+    pub timestamp: i64,
+    /// Input idle time in seconds when the sample was taken
+    #[serde(default)]
+    pub idle_sec: u64,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Settings a replay runs with
+#[derive(Debug, Clone, Copy)]
+pub struct ReplayOptions {
+    /// Recording seconds per wall-clock second; 0 replays without waiting
+    pub speed: f64,
+    pub idle_threshold_secs: u64,
+    pub capture_context: bool,
+}
+
+/// Progress of the current (or last) replay
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReplayStatus {
+    pub running: bool,
+    pub file: Option<String>,
+    pub speed: f64,
+    pub total: usize,
+    pub processed: usize,
+    /// Samples written as activities
+    pub recorded: usize,
+    /// Samples dropped by plugins
+    pub dropped: usize,
+    /// Samples taken while idle
+    pub idle: usize,
+    /// Idle periods that ended with a return
+    pub idle_returns: usize,
+    /// Timestamp of the last processed sample
+    pub position: Option<i64>,
+}
+
+/// Read a recording, sorted by timestamp
+pub fn load_recording(path: &Path) -> Result<Vec<ReplaySample>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let mut samples = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            serde_json::from_str::<ReplaySample>(line).map_err(|e| format!("Line {}: {}", index + 1, e))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    samples.sort_by_key(|sample| sample.timestamp);
+    Ok(samples)
+}
+
+/// Pipeline a replay writes through
+pub struct ReplayTarget {
+    pub db: Arc<Database>,
+    pub extension_registry: Option<Arc<ExtensionRegistry>>,
+    pub plugin_registry: Option<Arc<PluginRegistry>>,
+}
+
+impl ReplayTarget {
+    /// Process one sample as the tracker loop would and update `status`
+    fn replay_sample(&self, idle_state: &mut IdleState, sample: &ReplaySample, options: &ReplayOptions, status: &mut ReplayStatus) {
+        status.processed += 1;
+        status.position = Some(sample.timestamp);
+        match idle_state.observe(&self.db, sample.timestamp, sample.idle_sec, options.idle_threshold_secs) {
+            IdleObservation::Idle => {
+                status.idle += 1;
+                return;
+            }
+            IdleObservation::Returned { .. } => status.idle_returns += 1,
+            IdleObservation::Active => {}
+        }
+
+        let activity = ActivitySample {
+            domain: sample
+                .domain
+                .clone()
+                .or_else(|| extract_domain(&sample.app_name, sample.window_title.as_deref())),
+            app_name: sample.app_name.clone(),
+            window_title: sample.window_title.clone(),
+            timestamp: sample.timestamp,
+            source: sample.source.clone().unwrap_or_else(|| SOURCE_REPLAY.to_string()),
+        };
+        match record_sample(
+            &self.db,
+            self.extension_registry.as_ref(),
+            self.plugin_registry.as_ref(),
+            &activity,
+            options.capture_context,
+        ) {
+            Ok(Some(_)) => status.recorded += 1,
+            Ok(None) => status.dropped += 1,
+            Err(e) => eprintln!("Failed to record replayed activity: {}", e),
+        }
+    }
+
+    /// Replay samples in order, waiting between them according to `options.speed`.
+    /// Stops early when `stop` is disconnected.
+    fn replay(&self, samples: &[ReplaySample], options: &ReplayOptions, stop: &Receiver<()>, status: &Mutex<ReplayStatus>) {
+        let mut idle_state = IdleState::default();
+        let mut previous: Option<i64> = None;
+        for sample in samples {
+            let gap = previous.map_or(0, |previous| (sample.timestamp - previous).max(0));
+            let stopped = if options.speed > 0.0 && gap > 0 {
+                let wait = Duration::from_secs_f64(gap as f64 / options.speed);
+                matches!(stop.recv_timeout(wait), Err(RecvTimeoutError::Disconnected))
+            } else {
+                matches!(stop.try_recv(), Err(TryRecvError::Disconnected))
+            };
+            if stopped {
+                break;
+            }
+            previous = Some(sample.timestamp);
+            let mut status = status.lock().unwrap();
+            self.replay_sample(&mut idle_state, sample, options, &mut status);
+        }
+    }
+}
+
+/// Runs one replay at a time on a background thread
+#[derive(Default)]
+pub struct ReplayRunner {
+    status: Arc<Mutex<ReplayStatus>>,
+    /// Dropped to stop the running replay
+    stop: Mutex<Option<Sender<()>>>,
+}
+
+impl ReplayRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Start replaying `samples`; fails while another replay runs
+    pub fn start(&self, target: ReplayTarget, samples: Vec<ReplaySample>, options: ReplayOptions, file: String) -> Result<(), String> {
+        let mut stop = self.stop.lock().unwrap();
+        if self.status().running {
+            return Err("A replay is already running".to_string());
+        }
+        *self.status.lock().unwrap() = ReplayStatus {
+            running: true,
+            file: Some(file),
+            speed: options.speed,
+            total: samples.len(),
+            ..Default::default()
+        };
+        let (sender, receiver) = channel();
+        *stop = Some(sender);
+
+        let status = Arc::clone(&self.status);
+        std::thread::spawn(move || {
+            target.replay(&samples, &options, &receiver, &status);
+            status.lock().unwrap().running = false;
+        });
+        Ok(())
+    }
+
+    /// Stop the running replay after the current sample
+    pub fn stop(&self) {
+        self.stop.lock().unwrap().take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn replays_merging_and_idle_periods() {
+        let db = Arc::new(Database::new(PathBuf::from(":memory:")).unwrap());
+        let sample = |timestamp: i64, idle_sec: u64| ReplaySample {
+            app_name: "Code".to_string(),
+            window_title: Some("main.rs - tracker".to_string()),
+            domain: None,
+            timestamp,
+            idle_sec,
+            source: None,
+        };
+        let start = 1_700_000_000;
+        // Two minutes of work, ten idle minutes, then back to the same file
+        let samples: Vec<ReplaySample> = (0..24)
+            .map(|i| sample(start + i * 5, 0))
+            .chain((0..120).map(|i| sample(start + 120 + i * 5, 180 + i as u64 * 5)))
+            .chain((0..12).map(|i| sample(start + 720 + i * 5, 0)))
+            .collect();
+        let options = ReplayOptions {
+            speed: 0.0,
+            idle_threshold_secs: 120,
+            capture_context: false,
+        };
+        let target = ReplayTarget {
+            db: Arc::clone(&db),
+            extension_registry: None,
+            plugin_registry: None,
+        };
+        let (_sender, stop) = channel();
+        let status = Mutex::new(ReplayStatus::default());
+        target.replay(&samples, &options, &stop, &status);
+
+        let status = status.into_inner().unwrap();
+        assert_eq!((status.processed, status.recorded, status.idle, status.idle_returns), (156, 36, 120, 1));
+        let activities = db.get_activities(start, start + 3600, None, None, None, None, None).unwrap();
+        let summary: Vec<(&str, i64, i64)> = activities
+            .iter()
+            .map(|a| (a.app_name.as_str(), a.started_at - start, a.duration_sec))
+            .collect();
+        assert_eq!(summary, [("Code", 0, 120), ("Idle", 120, 595), ("Code", 720, 60)]);
+    }
+}
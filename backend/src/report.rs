@@ -0,0 +1,152 @@
+//! Static HTML report rendering
+//!
+//! Renders a self-contained dashboard (inline CSS and SVG charts, no scripts
+//! or external requests) that can be handed to a client or hosted anywhere.
+//! The page layout comes from the `report.html` template (see `templates`).
+
+use crate::templates;
+use std::fmt::Write;
+
+/// Report profiles: `full` includes per-app time, `client` only categories
+/// and daily totals (no app or window details)
+pub const REPORT_PROFILES: &[&str] = &["full", "client"];
+
+/// Apps listed in a full report
+const MAX_APPS: usize = 15;
+
+/// Data shown in a published report
+pub struct ReportData {
+    pub title: String,
+    /// Human-readable period, e.g. `2024-03-04 – 2024-03-10`
+    pub period: String,
+    pub total_seconds: i64,
+    pub productive_seconds: i64,
+    /// (name, color, seconds)
+    pub categories: Vec<(String, String, i64)>,
+    /// (app name, seconds); empty for the client profile
+    pub apps: Vec<(String, i64)>,
+    /// (day label, seconds)
+    pub daily: Vec<(String, i64)>,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Only `#rgb`/`#rrggbb` colors are inlined; anything else falls back to grey
+fn safe_color(color: &str) -> &str {
+    let valid = color.starts_with('#')
+        && matches!(color.len(), 4 | 7)
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if valid { color } else { "#888888" }
+}
+
+fn format_duration(seconds: i64) -> String {
+    format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60)
+}
+
+/// Horizontal bar chart with one labelled row per item
+fn bar_chart(rows: &[(String, &str, i64)]) -> String {
+    const ROW_HEIGHT: usize = 28;
+    const LABEL_WIDTH: usize = 180;
+    const BAR_WIDTH: f64 = 360.0;
+    let max = rows.iter().map(|r| r.2).max().unwrap_or(0).max(1) as f64;
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {} {}" width="100%" role="img">"#,
+        LABEL_WIDTH + BAR_WIDTH as usize + 90,
+        rows.len() * ROW_HEIGHT
+    );
+    for (i, (label, color, seconds)) in rows.iter().enumerate() {
+        let y = i * ROW_HEIGHT;
+        let width = (*seconds as f64 / max * BAR_WIDTH).max(1.0);
+        let _ = write!(
+            svg,
+            r#"<text x="0" y="{ty}" class="label">{label}</text><rect x="{LABEL_WIDTH}" y="{ry}" width="{width:.1}" height="18" rx="3" fill="{color}"/><text x="{vx:.1}" y="{ty}" class="value">{value}</text>"#,
+            ty = y + 14,
+            ry = y + 1,
+            label = escape(label),
+            color = safe_color(color),
+            vx = LABEL_WIDTH as f64 + width + 8.0,
+            value = format_duration(*seconds),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Vertical bar chart of daily totals
+fn daily_chart(days: &[(String, i64)]) -> String {
+    const HEIGHT: f64 = 160.0;
+    const SLOT: usize = 40;
+    let max = days.iter().map(|d| d.1).max().unwrap_or(0).max(1) as f64;
+    let mut svg = format!(
+        r#"<svg viewBox="0 0 {} {}" width="100%" role="img">"#,
+        (days.len() * SLOT).max(SLOT),
+        HEIGHT as usize + 36
+    );
+    for (i, (label, seconds)) in days.iter().enumerate() {
+        let height = *seconds as f64 / max * HEIGHT;
+        let x = i * SLOT;
+        let _ = write!(
+            svg,
+            r##"<rect x="{rx}" y="{ry:.1}" width="28" height="{height:.1}" rx="3" fill="#6366f1"><title>{title}</title></rect><text x="{tx}" y="{ty}" class="axis">{label}</text>"##,
+            rx = x + 6,
+            ry = HEIGHT - height,
+            title = format_duration(*seconds),
+            tx = x + 20,
+            ty = HEIGHT as usize + 16,
+            label = escape(label),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Template values for (name, seconds) rows
+fn items<'a>(rows: impl Iterator<Item = (&'a String, i64)>) -> Vec<serde_json::Value> {
+    rows.map(|(name, seconds)| serde_json::json!({ "name": name, "seconds": seconds, "duration": format_duration(seconds) }))
+        .collect()
+}
+
+/// Render the report as a standalone HTML document with the `report.html`
+/// template. Besides the fields of `data` (`title`, `period`, `total`,
+/// `productive`, `productive_percent`, and `categories`, `apps` and `daily`
+/// as lists of `name`/`seconds`/`duration`), it gets the prerendered SVG
+/// charts `daily_chart`, `category_chart` and `app_chart`.
+pub fn render_html(data: &ReportData) -> Result<String, String> {
+    let productive_pct = if data.total_seconds > 0 {
+        data.productive_seconds * 100 / data.total_seconds
+    } else {
+        0
+    };
+    let category_rows: Vec<(String, &str, i64)> = data
+        .categories
+        .iter()
+        .map(|(name, color, seconds)| (name.clone(), color.as_str(), *seconds))
+        .collect();
+    let app_rows: Vec<(String, &str, i64)> = data
+        .apps
+        .iter()
+        .take(MAX_APPS)
+        .map(|(name, seconds)| (name.clone(), "#10b981", *seconds))
+        .collect();
+
+    let mut context = tera::Context::new();
+    context.insert("title", &data.title);
+    context.insert("period", &data.period);
+    context.insert("total", &format_duration(data.total_seconds));
+    context.insert("productive", &format_duration(data.productive_seconds));
+    context.insert("productive_percent", &productive_pct);
+    context.insert("categories", &items(data.categories.iter().map(|(name, _, seconds)| (name, *seconds))));
+    context.insert("apps", &items(data.apps.iter().take(MAX_APPS).map(|(name, seconds)| (name, *seconds))));
+    context.insert("daily", &items(data.daily.iter().map(|(name, seconds)| (name, *seconds))));
+    context.insert("daily_chart", &daily_chart(&data.daily));
+    context.insert("category_chart", &bar_chart(&category_rows));
+    context.insert("app_chart", &bar_chart(&app_rows));
+    context.insert("generated", &chrono::Local::now().format("%Y-%m-%d %H:%M").to_string());
+    templates::render(templates::REPORT, &context)
+}
@@ -0,0 +1,206 @@
+//! Materialized reports for the current week
+//!
+//! The dashboard and reports ask for this week's aggregates on every view
+//! switch. `WeekCache` keeps the results per (week, dimension), so repeated
+//! reads skip the SQL aggregation. The event bus keeps it honest: a recorded
+//! sample drops the week it falls in, and edits to history (`activities.changed`,
+//! manual entries, profile, workspace or database switches) drop everything.
+//! Writes nobody announces, such as plugins updating rows directly, show up
+//! once an entry is `MAX_AGE_SECS` old.
+
+use crate::events::{
+    AppEvent, EventBus, EVENT_ACTIVITIES_CHANGED, EVENT_ACTIVITY_RECORDED, EVENT_DATABASE_RECOVERED,
+    EVENT_MANUAL_ENTRY_CREATED, EVENT_MANUAL_ENTRY_DELETED, EVENT_MANUAL_ENTRY_UPDATED, EVENT_PROFILE_SWITCHED,
+    EVENT_WORKSPACE_SWITCHED,
+};
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeZone};
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Oldest cached report served without recomputing
+const MAX_AGE_SECS: i64 = 300;
+
+/// What a cached report aggregates by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    /// Totals with category and app breakdowns (`get_stats`)
+    Summary,
+    /// Top apps, up to the given limit
+    Apps(i64),
+    /// Time per category
+    Categories,
+}
+
+struct Entry {
+    week_end: i64,
+    computed_at: i64,
+    report: Box<dyn Any + Send>,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// Keyed by (local week start, dimension)
+    entries: HashMap<(i64, Dimension), Entry>,
+    /// Bumped on every invalidation, so a report computed meanwhile is not stored
+    generation: u64,
+}
+
+/// Current-week reports, invalidated from the event bus
+#[derive(Default)]
+pub struct WeekCache {
+    state: Mutex<CacheState>,
+}
+
+/// The week (Monday midnight to the next Monday midnight) containing `now`,
+/// if `start..end` is exactly that week. `end` may be the last second of the
+/// week, as with the frontend's `startOfWeek`/`endOfWeek` ranges.
+fn current_week<Tz: TimeZone>(now: &DateTime<Tz>, start: i64, end: i64) -> Option<(i64, i64)> {
+    let midnight = |date: NaiveDate| {
+        now.timezone()
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0)?)
+            .earliest()
+            .map(|t| t.timestamp())
+    };
+    let today = now.date_naive();
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let week = (midnight(monday)?, midnight(monday + Days::new(7))?);
+    (start == week.0 && (end == week.1 || end == week.1 - 1)).then_some(week)
+}
+
+impl WeekCache {
+    /// Cache invalidated by activity and manual entry events on `event_bus`
+    pub fn subscribe(event_bus: &EventBus) -> Arc<Self> {
+        let cache = Arc::new(Self::default());
+        let cache_for_events = Arc::clone(&cache);
+        event_bus.subscribe(move |event| cache_for_events.invalidate(event));
+        cache
+    }
+
+    fn invalidate(&self, event: &AppEvent) {
+        let mut state = self.state.lock().unwrap();
+        match (event.name.as_str(), event.payload.get("timestamp").and_then(|t| t.as_i64())) {
+            (EVENT_ACTIVITY_RECORDED, Some(t)) => {
+                state.entries.retain(|(week_start, _), entry| t < *week_start || t >= entry.week_end);
+            }
+            (
+                EVENT_ACTIVITY_RECORDED
+                | EVENT_ACTIVITIES_CHANGED
+                | EVENT_MANUAL_ENTRY_CREATED
+                | EVENT_MANUAL_ENTRY_UPDATED
+                | EVENT_MANUAL_ENTRY_DELETED
+                | EVENT_PROFILE_SWITCHED
+                | EVENT_WORKSPACE_SWITCHED
+                | EVENT_DATABASE_RECOVERED,
+                _,
+            ) => state.entries.clear(),
+            _ => return,
+        }
+        state.generation += 1;
+    }
+
+    /// Report for `start..end` from `compute`, served from the cache when the
+    /// range is the current week
+    pub fn get_or_compute<T, E>(
+        &self,
+        start: i64,
+        end: i64,
+        dimension: Dimension,
+        compute: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        T: Clone + Send + 'static,
+    {
+        self.get_or_compute_at(&Local::now(), start, end, dimension, compute)
+    }
+
+    fn get_or_compute_at<Tz, T, E>(
+        &self,
+        now: &DateTime<Tz>,
+        start: i64,
+        end: i64,
+        dimension: Dimension,
+        compute: impl FnOnce() -> Result<T, E>,
+    ) -> Result<T, E>
+    where
+        Tz: TimeZone,
+        T: Clone + Send + 'static,
+    {
+        let Some((week_start, week_end)) = current_week(now, start, end) else {
+            return compute();
+        };
+        let now = now.timestamp();
+        let generation = {
+            let state = self.state.lock().unwrap();
+            let cached = state
+                .entries
+                .get(&(week_start, dimension))
+                .filter(|entry| now - entry.computed_at < MAX_AGE_SECS)
+                .and_then(|entry| entry.report.downcast_ref::<T>());
+            if let Some(report) = cached {
+                return Ok(report.clone());
+            }
+            state.generation
+        };
+
+        let report = compute()?;
+        let mut state = self.state.lock().unwrap();
+        if state.generation == generation {
+            state.entries.insert(
+                (week_start, dimension),
+                Entry {
+                    week_end,
+                    computed_at: now,
+                    report: Box::new(report.clone()),
+                },
+            );
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn event(name: &str, payload: serde_json::Value) -> AppEvent {
+        AppEvent {
+            name: name.to_string(),
+            payload,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn samples_invalidate_only_their_week() {
+        // Wednesday 2024-01-10 12:00 UTC; the week runs from Monday 2024-01-08
+        let now = Utc.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+        let week_start = Utc.with_ymd_and_hms(2024, 1, 8, 0, 0, 0).unwrap().timestamp();
+        let week_end = week_start + 7 * 86400;
+        let cache = WeekCache::default();
+        let computed = std::cell::Cell::new(0);
+        let summary = |cache: &WeekCache, end: i64| {
+            cache
+                .get_or_compute_at(&now, week_start, end, Dimension::Summary, || {
+                    computed.set(computed.get() + 1);
+                    Ok::<_, ()>(computed.get())
+                })
+                .unwrap()
+        };
+
+        assert_eq!(summary(&cache, week_end - 1), 1);
+        assert_eq!(summary(&cache, week_end - 1), 1, "served from the cache");
+        assert_eq!(summary(&cache, week_end + 86400), 2, "other ranges are not cached");
+
+        cache.invalidate(&event(EVENT_ACTIVITY_RECORDED, serde_json::json!({ "timestamp": week_start - 60 })));
+        assert_eq!(summary(&cache, week_end - 1), 1, "sample from last week");
+        cache.invalidate(&event("pomodoro.completed", serde_json::json!({})));
+        assert_eq!(summary(&cache, week_end - 1), 1);
+
+        cache.invalidate(&event(EVENT_ACTIVITY_RECORDED, serde_json::json!({ "timestamp": now.timestamp() })));
+        assert_eq!(summary(&cache, week_end - 1), 3);
+        cache.invalidate(&event(EVENT_ACTIVITIES_CHANGED, serde_json::json!({})));
+        assert_eq!(summary(&cache, week_end - 1), 4);
+    }
+}
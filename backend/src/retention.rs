@@ -0,0 +1,55 @@
+//! Data retention
+//!
+//! When `retention_days` is above 0, tracked activities that ended more than
+//! that many days ago are deleted once a day by a `retention_purge` job (see
+//! `jobs`). Changing the setting queues a purge right away instead of waiting
+//! for the next daily run.
+
+use crate::database::Database;
+use crate::jobs::{JobRunner, JobStep, JOB_RETENTION_PURGE};
+use crate::settings::SettingsService;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often expired activities are purged
+const PURGE_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Activities deleted per purge step
+const PURGE_BATCH: i64 = 5000;
+
+/// Purge job step: delete the next batch of activities that ended before `params.before`
+pub(crate) fn purge_step(db: &Database, params: &serde_json::Value) -> Result<JobStep, String> {
+    let before = params["before"].as_i64().ok_or("Missing job parameter: before")?;
+    let deleted = db.delete_activities_before(before, PURGE_BATCH).map_err(|e| e.to_string())? as i64;
+    Ok(JobStep {
+        cursor: serde_json::json!({}),
+        processed: deleted,
+        total: None,
+        done: deleted < PURGE_BATCH,
+    })
+}
+
+/// Queue a purge of activities older than the configured retention period
+pub fn queue_purge(jobs: &JobRunner, retention_days: i64) -> Result<(), String> {
+    if retention_days <= 0 {
+        return Ok(());
+    }
+    let cutoff = chrono::Utc::now().timestamp() - retention_days * 24 * 60 * 60;
+    jobs.submit_once(JOB_RETENTION_PURGE, serde_json::json!({ "before": cutoff }))?;
+    Ok(())
+}
+
+/// Start the retention engine
+pub fn start(jobs: Arc<JobRunner>, settings: Arc<SettingsService>) {
+    let changes = settings.watch(&["retention_days"]);
+    std::thread::spawn(move || loop {
+        if let Err(e) = queue_purge(&jobs, settings.get().retention_days) {
+            eprintln!("Warning: Failed to queue retention purge: {}", e);
+        }
+        match changes.recv_timeout(Duration::from_secs(PURGE_INTERVAL_SECS)) {
+            Ok(()) | Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    });
+}
@@ -0,0 +1,134 @@
+//! Time entry rounding
+//!
+//! Invoices usually bill each entry in fixed increments (e.g. 6 or 15
+//! minutes), sometimes with a minimum per entry. `preview_rounding` applies a
+//! policy to the entries of a range without changing them, so the billing
+//! impact shows before the policy goes onto an invoice.
+//!
+//! Tracked activities are rounded as the contiguous blocks of the timeline
+//! (see `timeline::merge_activities`), not row by row: the tracker writes a
+//! row every few seconds of app switching, and rounding each of those would
+//! bill hours that were never worked.
+
+use serde::{Deserialize, Serialize};
+use time_tracker_plugin_sdk::validation::{self, ValidationError};
+
+/// Direction entries are rounded in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    /// Half an increment or more rounds up
+    Nearest,
+}
+
+/// How each entry's duration is rounded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct RoundingPolicy {
+    pub increment_minutes: i64,
+    pub mode: RoundingMode,
+    /// Shortest billed duration per entry (after rounding)
+    #[serde(default)]
+    pub minimum_minutes: Option<i64>,
+}
+
+impl RoundingPolicy {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        validation::in_range("increment_minutes", self.increment_minutes, 1, 480)?;
+        if let Some(minimum) = self.minimum_minutes {
+            validation::in_range("minimum_minutes", minimum, 0, 480)?;
+        }
+        Ok(())
+    }
+
+    /// Rounded duration of an entry lasting `seconds`
+    pub fn round(&self, seconds: i64) -> i64 {
+        let increment = self.increment_minutes * 60;
+        let rounded = match self.mode {
+            RoundingMode::Up => (seconds + increment - 1).div_euclid(increment) * increment,
+            RoundingMode::Down => seconds.div_euclid(increment) * increment,
+            RoundingMode::Nearest => (seconds + increment / 2).div_euclid(increment) * increment,
+        };
+        rounded.max(self.minimum_minutes.unwrap_or(0) * 60)
+    }
+}
+
+/// An entry with its raw and rounded duration
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundedEntry {
+    /// `activity` (a block of activities) or `manual_entry`
+    pub entity_type: String,
+    /// The manual entry, or the first activity of the block
+    pub entity_id: i64,
+    /// Description of a manual entry, app name of a block
+    pub label: String,
+    /// Activities merged into the entry; 1 for a manual entry
+    pub activity_count: i64,
+    pub category_id: Option<i64>,
+    pub started_at: i64,
+    pub raw_sec: i64,
+    pub rounded_sec: i64,
+    /// `rounded_sec - raw_sec`; positive when rounding bills more
+    pub delta_sec: i64,
+}
+
+/// Result of `preview_rounding`
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundingPreview {
+    pub entries: Vec<RoundedEntry>,
+    pub raw_total_sec: i64,
+    pub rounded_total_sec: i64,
+    pub delta_sec: i64,
+}
+
+/// Apply `policy` to `entries` (their `rounded_sec` and `delta_sec` are
+/// filled in here), in start order
+pub fn preview(policy: &RoundingPolicy, mut entries: Vec<RoundedEntry>) -> RoundingPreview {
+    entries.sort_by_key(|entry| (entry.started_at, entry.entity_id));
+    for entry in entries.iter_mut() {
+        entry.rounded_sec = policy.round(entry.raw_sec);
+        entry.delta_sec = entry.rounded_sec - entry.raw_sec;
+    }
+    let raw_total_sec = entries.iter().map(|entry| entry.raw_sec).sum();
+    let rounded_total_sec = entries.iter().map(|entry| entry.rounded_sec).sum();
+    RoundingPreview {
+        entries,
+        raw_total_sec,
+        rounded_total_sec,
+        delta_sec: rounded_total_sec - raw_total_sec,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_per_policy() {
+        let policy = |mode, minimum_minutes| RoundingPolicy { increment_minutes: 15, mode, minimum_minutes };
+        let up = policy(RoundingMode::Up, None);
+        assert_eq!((up.round(0), up.round(1), up.round(900), up.round(901)), (0, 900, 900, 1800));
+        let down = policy(RoundingMode::Down, Some(15));
+        assert_eq!((down.round(100), down.round(1799)), (900, 900));
+        let nearest = policy(RoundingMode::Nearest, None);
+        assert_eq!((nearest.round(449), nearest.round(450)), (0, 900));
+        assert!(policy(RoundingMode::Up, Some(-1)).validate().is_err());
+
+        let entry = |entity_id, started_at, raw_sec| RoundedEntry {
+            entity_type: "manual_entry".to_string(),
+            entity_id,
+            label: String::new(),
+            activity_count: 1,
+            category_id: None,
+            started_at,
+            raw_sec,
+            rounded_sec: 0,
+            delta_sec: 0,
+        };
+        let preview = preview(&up, vec![entry(2, 200, 1000), entry(1, 100, 600)]);
+        assert_eq!(preview.entries.iter().map(|e| e.entity_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!((preview.raw_total_sec, preview.rounded_total_sec, preview.delta_sec), (1600, 2700, 1100));
+    }
+}
@@ -0,0 +1,496 @@
+//! Typed application settings
+//!
+//! Settings are stored as strings in the `settings` table. `AppSettings` is the
+//! typed view of the core keys (with defaults), and `SETTING_SCHEMA` lists the
+//! allowed values per key so invalid values are rejected before they are
+//! written. Keys not in the schema (plugin settings, free-form text such as
+//! `mqtt_host`) are stored as given. Deprecated keys (the minute-based idle
+//! thresholds) are written under their canonical keys; values stored by older
+//! versions are converted once by `Database::migrate_settings`.
+//!
+//! All writes go through `SettingsService`, which notifies subscribers with
+//! the keys that changed. Subsystems subscribe (or `watch` keys) to pick up
+//! changes without a restart; main.rs also forwards every change to the event
+//! bus (`settings.changed`), plugins and the frontend.
+
+use crate::database::settings::canonicalize_settings;
+use crate::database::Database;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, RwLock};
+
+/// Allowed values of a setting
+#[derive(Debug, Clone, Copy)]
+pub enum SettingKind {
+    Bool,
+    Integer { min: i64, max: i64 },
+    OneOf(&'static [&'static str]),
+    /// JSON array of strings
+    StringList,
+    /// Credential (see `SECRET_SETTINGS`) of at least `min_len` characters;
+    /// empty clears it
+    Secret { min_len: usize },
+}
+
+/// Screen corners the mini timer window can snap to
+pub const MINI_TIMER_CORNERS: [&str; 4] = ["top-left", "top-right", "bottom-left", "bottom-right"];
+
+/// Validation rules for known keys
+pub const SETTING_SCHEMA: &[(&str, SettingKind)] = &[
+    ("idle_threshold_seconds", SettingKind::Integer { min: 30, max: 86_400 }),
+    ("idle_prompt_threshold_seconds", SettingKind::Integer { min: 60, max: 86_400 }),
+    ("autostart", SettingKind::Bool),
+    ("minimize_to_tray", SettingKind::Bool),
+    ("show_notifications", SettingKind::Bool),
+    ("enable_marketplace", SettingKind::Bool),
+    ("date_format", SettingKind::OneOf(&["YYYY-MM-DD", "MM/DD/YYYY", "DD/MM/YYYY", "DD.MM.YYYY"])),
+    ("time_format", SettingKind::OneOf(&["12h", "24h"])),
+    ("plugin_registry_urls", SettingKind::StringList),
+    ("ide_heartbeats_enabled", SettingKind::Bool),
+    ("ide_heartbeats_port", SettingKind::Integer { min: 1_024, max: 65_535 }),
+    ("metrics_enabled", SettingKind::Bool),
+    ("mqtt_enabled", SettingKind::Bool),
+    ("mqtt_port", SettingKind::Integer { min: 1, max: 65_535 }),
+    ("checkins_enabled", SettingKind::Bool),
+    ("checkins_interval_minutes", SettingKind::Integer { min: 5, max: 1_440 }),
+    ("focus_blocking_enabled", SettingKind::Bool),
+    ("focus_blocking_hosts_file", SettingKind::Bool),
+    ("tray_update_interval_seconds", SettingKind::Integer { min: 5, max: 3_600 }),
+    ("retention_days", SettingKind::Integer { min: 0, max: 36_500 }),
+    ("mini_timer_corner", SettingKind::OneOf(&MINI_TIMER_CORNERS)),
+    ("mini_timer_click_through", SettingKind::Bool),
+    ("close_timers_on_exit", SettingKind::Bool),
+    ("update_check_enabled", SettingKind::Bool),
+    ("capture_context_ref", SettingKind::Bool),
+    ("record_input_intensity", SettingKind::Bool),
+    ("daily_note_enabled", SettingKind::Bool),
+    ("daily_note_hour", SettingKind::Integer { min: 0, max: 23 }),
+    ("micro_breaks_enabled", SettingKind::Bool),
+    ("micro_break_interval_minutes", SettingKind::Integer { min: 5, max: 240 }),
+    ("micro_break_seconds", SettingKind::Integer { min: 5, max: 600 }),
+    ("quiet_hours_start", SettingKind::Integer { min: 0, max: 23 }),
+    ("quiet_hours_end", SettingKind::Integer { min: 0, max: 23 }),
+    ("quiet_weekends", SettingKind::Bool),
+    ("boundary_alerts_enabled", SettingKind::Bool),
+    ("boundary_threshold_minutes", SettingKind::Integer { min: 1, max: 720 }),
+    ("boundary_notifications", SettingKind::Bool),
+    ("slow_query_log_ms", SettingKind::Integer { min: 0, max: 60000 }),
+    ("http_api_enabled", SettingKind::Bool),
+    ("http_api_port", SettingKind::Integer { min: 1_024, max: 65_535 }),
+    ("focus_blocking_presets", SettingKind::StringList),
+    ("git_repositories", SettingKind::StringList),
+    ("dashboard_kpis", SettingKind::StringList),
+    ("export_passphrase", SettingKind::Secret { min_len: crate::export_archive::MIN_PASSPHRASE_LEN }),
+    ("http_api_token", SettingKind::Secret { min_len: 32 }),
+];
+
+/// Keys holding credentials or connection details: not part of `AppSettings`
+/// (read with `SettingsService::get_secret`) and left out of anonymized
+/// database copies
+pub const SECRET_SETTINGS: &[&str] = &["mqtt_host", "mqtt_username", "mqtt_password", "http_api_token", "export_passphrase"];
+
+/// Check a value against the schema of its key
+pub fn validate_value(key: &str, value: &str) -> Result<(), String> {
+    let Some((_, kind)) = SETTING_SCHEMA.iter().find(|(k, _)| *k == key) else {
+        return Ok(());
+    };
+    let valid = match kind {
+        SettingKind::Bool => value == "true" || value == "false",
+        SettingKind::Integer { min, max } => value.parse::<i64>().is_ok_and(|v| (*min..=*max).contains(&v)),
+        SettingKind::OneOf(options) => options.contains(&value),
+        SettingKind::StringList => serde_json::from_str::<Vec<String>>(value).is_ok(),
+        SettingKind::Secret { min_len } => value.is_empty() || value.chars().count() >= *min_len,
+    };
+    if valid {
+        return Ok(());
+    }
+    Err(match kind {
+        SettingKind::Bool => format!("Invalid value for {}: expected true or false", key),
+        SettingKind::Integer { min, max } => {
+            format!("Invalid value for {}: expected a whole number from {} to {}", key, min, max)
+        }
+        SettingKind::OneOf(options) => {
+            format!("Invalid value for {}: expected one of {}", key, options.join(", "))
+        }
+        SettingKind::StringList => format!("Invalid value for {}: expected a JSON array of strings", key),
+        SettingKind::Secret { min_len } => {
+            format!("Invalid value for {}: expected at least {} characters (or empty to clear)", key, min_len)
+        }
+    })
+}
+
+/// Typed view of the core settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub idle_threshold_seconds: i64,
+    pub idle_prompt_threshold_seconds: i64,
+    pub autostart: bool,
+    pub minimize_to_tray: bool,
+    pub show_notifications: bool,
+    pub enable_marketplace: bool,
+    pub date_format: String,
+    pub time_format: String,
+    pub plugin_registry_urls: Option<Vec<String>>,
+    pub ide_heartbeats_enabled: bool,
+    pub ide_heartbeats_port: u16,
+    /// Serve Prometheus metrics on the heartbeat listener's port (see `metrics`)
+    pub metrics_enabled: bool,
+    pub mqtt_enabled: bool,
+    pub mqtt_port: u16,
+    pub checkins_enabled: bool,
+    pub checkins_interval_minutes: i64,
+    pub focus_blocking_enabled: bool,
+    pub focus_blocking_hosts_file: bool,
+    pub tray_update_interval_seconds: i64,
+    /// Tracked activities older than this are deleted; 0 keeps everything
+    pub retention_days: i64,
+    pub mini_timer_corner: String,
+    /// Let clicks pass through the mini timer to the windows below
+    pub mini_timer_click_through: bool,
+    /// Stop running timers (thinking mode, plugin timers) when the app quits
+    pub close_timers_on_exit: bool,
+    pub update_check_enabled: bool,
+    /// Latest version the user was told about
+    pub update_notified_version: Option<String>,
+    /// Store the document/file/URL parsed from window titles with each activity
+    pub capture_context_ref: bool,
+    /// Count samples with keyboard/mouse input per activity (`input_intensity`)
+    pub record_input_intensity: bool,
+    /// Write today's daily note after `daily_note_hour` (see `daily_note`)
+    pub daily_note_enabled: bool,
+    pub daily_note_hour: i64,
+    /// strftime path template of the note, e.g. `~/Vault/Daily/%Y-%m-%d.md`
+    pub daily_note_path: Option<String>,
+    /// Local day (`YYYY-MM-DD`) whose note was last written automatically
+    pub daily_note_last_date: Option<String>,
+    /// Show a short break overlay after `micro_break_interval_minutes` of
+    /// continuous work (see `micro_breaks`)
+    pub micro_breaks_enabled: bool,
+    pub micro_break_interval_minutes: i64,
+    pub micro_break_seconds: i64,
+    /// Quiet hours (local, `quiet_hours_start` up to `quiet_hours_end`) and
+    /// with `quiet_weekends` the whole weekend are outside work
+    pub quiet_hours_start: i64,
+    pub quiet_hours_end: i64,
+    pub quiet_weekends: bool,
+    /// Log days with more than `boundary_threshold_minutes` of work outside
+    /// work hours, and notify with `boundary_notifications` (see `boundaries`)
+    pub boundary_alerts_enabled: bool,
+    pub boundary_threshold_minutes: i64,
+    pub boundary_notifications: bool,
+    /// Log statements slower than this many milliseconds with their query
+    /// plans for `get_diagnostics`; 0 turns it off (see `database::slow_queries`)
+    pub slow_query_log_ms: i64,
+    /// Serve the REST API on `127.0.0.1:<http_api_port>` (see `http_api`)
+    pub http_api_enabled: bool,
+    pub http_api_port: u16,
+    /// Dashboard cards in display order; `None` shows the default cards
+    pub dashboard_kpis: Option<Vec<String>>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            idle_threshold_seconds: 120,
+            idle_prompt_threshold_seconds: 300,
+            autostart: false,
+            minimize_to_tray: false,
+            show_notifications: true,
+            enable_marketplace: true,
+            date_format: "YYYY-MM-DD".to_string(),
+            time_format: "24h".to_string(),
+            plugin_registry_urls: None,
+            ide_heartbeats_enabled: false,
+            ide_heartbeats_port: 9900,
+            metrics_enabled: false,
+            mqtt_enabled: false,
+            mqtt_port: 1883,
+            checkins_enabled: false,
+            checkins_interval_minutes: 90,
+            focus_blocking_enabled: false,
+            focus_blocking_hosts_file: false,
+            tray_update_interval_seconds: 60,
+            retention_days: 0,
+            mini_timer_corner: "top-right".to_string(),
+            mini_timer_click_through: false,
+            close_timers_on_exit: true,
+            update_check_enabled: true,
+            update_notified_version: None,
+            capture_context_ref: false,
+            record_input_intensity: false,
+            daily_note_enabled: false,
+            daily_note_hour: 22,
+            daily_note_path: None,
+            daily_note_last_date: None,
+            micro_breaks_enabled: false,
+            micro_break_interval_minutes: 20,
+            micro_break_seconds: 20,
+            quiet_hours_start: 22,
+            quiet_hours_end: 6,
+            quiet_weekends: true,
+            boundary_alerts_enabled: false,
+            boundary_threshold_minutes: 30,
+            boundary_notifications: true,
+            slow_query_log_ms: 0,
+            http_api_enabled: false,
+            http_api_port: 9901,
+            dashboard_kpis: None,
+        }
+    }
+}
+
+impl AppSettings {
+    /// Read settings from stored values; missing or invalid values use defaults
+    pub fn from_map(map: &HashMap<String, String>) -> Self {
+        let defaults = Self::default();
+        let valid = |key: &str| map.get(key).filter(|v| validate_value(key, v).is_ok());
+        let flag = |key: &str, default: bool| valid(key).map(|v| v == "true").unwrap_or(default);
+        let number = |key: &str| valid(key).and_then(|v| v.parse::<i64>().ok());
+
+        Self {
+            idle_threshold_seconds: number("idle_threshold_seconds").unwrap_or(defaults.idle_threshold_seconds),
+            idle_prompt_threshold_seconds: number("idle_prompt_threshold_seconds")
+                .unwrap_or(defaults.idle_prompt_threshold_seconds),
+            autostart: flag("autostart", defaults.autostart),
+            minimize_to_tray: flag("minimize_to_tray", defaults.minimize_to_tray),
+            show_notifications: flag("show_notifications", defaults.show_notifications),
+            enable_marketplace: flag("enable_marketplace", defaults.enable_marketplace),
+            date_format: valid("date_format").cloned().unwrap_or(defaults.date_format),
+            time_format: valid("time_format").cloned().unwrap_or(defaults.time_format),
+            plugin_registry_urls: valid("plugin_registry_urls").and_then(|v| serde_json::from_str(v).ok()),
+            ide_heartbeats_enabled: flag("ide_heartbeats_enabled", defaults.ide_heartbeats_enabled),
+            ide_heartbeats_port: number("ide_heartbeats_port")
+                .map(|p| p as u16)
+                .unwrap_or(defaults.ide_heartbeats_port),
+            metrics_enabled: flag("metrics_enabled", defaults.metrics_enabled),
+            mqtt_enabled: flag("mqtt_enabled", defaults.mqtt_enabled),
+            mqtt_port: number("mqtt_port").map(|p| p as u16).unwrap_or(defaults.mqtt_port),
+            checkins_enabled: flag("checkins_enabled", defaults.checkins_enabled),
+            checkins_interval_minutes: number("checkins_interval_minutes")
+                .unwrap_or(defaults.checkins_interval_minutes),
+            focus_blocking_enabled: flag("focus_blocking_enabled", defaults.focus_blocking_enabled),
+            focus_blocking_hosts_file: flag("focus_blocking_hosts_file", defaults.focus_blocking_hosts_file),
+            tray_update_interval_seconds: number("tray_update_interval_seconds")
+                .unwrap_or(defaults.tray_update_interval_seconds),
+            retention_days: number("retention_days").unwrap_or(defaults.retention_days),
+            mini_timer_corner: valid("mini_timer_corner").cloned().unwrap_or(defaults.mini_timer_corner),
+            mini_timer_click_through: flag("mini_timer_click_through", defaults.mini_timer_click_through),
+            close_timers_on_exit: flag("close_timers_on_exit", defaults.close_timers_on_exit),
+            update_check_enabled: flag("update_check_enabled", defaults.update_check_enabled),
+            update_notified_version: map.get("update_notified_version").cloned(),
+            capture_context_ref: flag("capture_context_ref", defaults.capture_context_ref),
+            record_input_intensity: flag("record_input_intensity", defaults.record_input_intensity),
+            daily_note_enabled: flag("daily_note_enabled", defaults.daily_note_enabled),
+            daily_note_hour: number("daily_note_hour").unwrap_or(defaults.daily_note_hour),
+            daily_note_path: map.get("daily_note_path").filter(|p| !p.trim().is_empty()).cloned(),
+            daily_note_last_date: map.get("daily_note_last_date").cloned(),
+            micro_breaks_enabled: flag("micro_breaks_enabled", defaults.micro_breaks_enabled),
+            micro_break_interval_minutes: number("micro_break_interval_minutes")
+                .unwrap_or(defaults.micro_break_interval_minutes),
+            micro_break_seconds: number("micro_break_seconds").unwrap_or(defaults.micro_break_seconds),
+            quiet_hours_start: number("quiet_hours_start").unwrap_or(defaults.quiet_hours_start),
+            quiet_hours_end: number("quiet_hours_end").unwrap_or(defaults.quiet_hours_end),
+            quiet_weekends: flag("quiet_weekends", defaults.quiet_weekends),
+            boundary_alerts_enabled: flag("boundary_alerts_enabled", defaults.boundary_alerts_enabled),
+            boundary_threshold_minutes: number("boundary_threshold_minutes")
+                .unwrap_or(defaults.boundary_threshold_minutes),
+            boundary_notifications: flag("boundary_notifications", defaults.boundary_notifications),
+            slow_query_log_ms: number("slow_query_log_ms").unwrap_or(defaults.slow_query_log_ms),
+            http_api_enabled: flag("http_api_enabled", defaults.http_api_enabled),
+            http_api_port: number("http_api_port").map(|p| p as u16).unwrap_or(defaults.http_api_port),
+            dashboard_kpis: valid("dashboard_kpis").and_then(|v| serde_json::from_str(v).ok()),
+        }
+    }
+
+    /// Stored representation of the settings
+    pub fn to_map(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert("idle_threshold_seconds".to_string(), self.idle_threshold_seconds.to_string());
+        map.insert("idle_prompt_threshold_seconds".to_string(), self.idle_prompt_threshold_seconds.to_string());
+        map.insert("autostart".to_string(), self.autostart.to_string());
+        map.insert("minimize_to_tray".to_string(), self.minimize_to_tray.to_string());
+        map.insert("show_notifications".to_string(), self.show_notifications.to_string());
+        map.insert("enable_marketplace".to_string(), self.enable_marketplace.to_string());
+        map.insert("date_format".to_string(), self.date_format.clone());
+        map.insert("time_format".to_string(), self.time_format.clone());
+        if let Some(urls) = &self.plugin_registry_urls {
+            map.insert(
+                "plugin_registry_urls".to_string(),
+                serde_json::to_string(urls).unwrap_or_else(|_| "[]".to_string()),
+            );
+        }
+        map.insert("ide_heartbeats_enabled".to_string(), self.ide_heartbeats_enabled.to_string());
+        map.insert("ide_heartbeats_port".to_string(), self.ide_heartbeats_port.to_string());
+        map.insert("metrics_enabled".to_string(), self.metrics_enabled.to_string());
+        map.insert("mqtt_enabled".to_string(), self.mqtt_enabled.to_string());
+        map.insert("mqtt_port".to_string(), self.mqtt_port.to_string());
+        map.insert("checkins_enabled".to_string(), self.checkins_enabled.to_string());
+        map.insert("checkins_interval_minutes".to_string(), self.checkins_interval_minutes.to_string());
+        map.insert("focus_blocking_enabled".to_string(), self.focus_blocking_enabled.to_string());
+        map.insert("focus_blocking_hosts_file".to_string(), self.focus_blocking_hosts_file.to_string());
+        map.insert("tray_update_interval_seconds".to_string(), self.tray_update_interval_seconds.to_string());
+        map.insert("retention_days".to_string(), self.retention_days.to_string());
+        map.insert("mini_timer_corner".to_string(), self.mini_timer_corner.clone());
+        map.insert("mini_timer_click_through".to_string(), self.mini_timer_click_through.to_string());
+        map.insert("close_timers_on_exit".to_string(), self.close_timers_on_exit.to_string());
+        map.insert("update_check_enabled".to_string(), self.update_check_enabled.to_string());
+        if let Some(version) = &self.update_notified_version {
+            map.insert("update_notified_version".to_string(), version.clone());
+        }
+        map.insert("capture_context_ref".to_string(), self.capture_context_ref.to_string());
+        map.insert("record_input_intensity".to_string(), self.record_input_intensity.to_string());
+        map.insert("daily_note_enabled".to_string(), self.daily_note_enabled.to_string());
+        map.insert("daily_note_hour".to_string(), self.daily_note_hour.to_string());
+        if let Some(path) = &self.daily_note_path {
+            map.insert("daily_note_path".to_string(), path.clone());
+        }
+        if let Some(day) = &self.daily_note_last_date {
+            map.insert("daily_note_last_date".to_string(), day.clone());
+        }
+        map.insert("micro_breaks_enabled".to_string(), self.micro_breaks_enabled.to_string());
+        map.insert("micro_break_interval_minutes".to_string(), self.micro_break_interval_minutes.to_string());
+        map.insert("micro_break_seconds".to_string(), self.micro_break_seconds.to_string());
+        map.insert("quiet_hours_start".to_string(), self.quiet_hours_start.to_string());
+        map.insert("quiet_hours_end".to_string(), self.quiet_hours_end.to_string());
+        map.insert("quiet_weekends".to_string(), self.quiet_weekends.to_string());
+        map.insert("boundary_alerts_enabled".to_string(), self.boundary_alerts_enabled.to_string());
+        map.insert("boundary_threshold_minutes".to_string(), self.boundary_threshold_minutes.to_string());
+        map.insert("boundary_notifications".to_string(), self.boundary_notifications.to_string());
+        map.insert("slow_query_log_ms".to_string(), self.slow_query_log_ms.to_string());
+        map.insert("http_api_enabled".to_string(), self.http_api_enabled.to_string());
+        map.insert("http_api_port".to_string(), self.http_api_port.to_string());
+        if let Some(kpis) = &self.dashboard_kpis {
+            map.insert("dashboard_kpis".to_string(), serde_json::to_string(kpis).unwrap_or_else(|_| "[]".to_string()));
+        }
+        map
+    }
+
+    /// Check every value against the schema
+    pub fn validate(&self) -> Result<(), String> {
+        let map = self.to_map();
+        let mut keys: Vec<&String> = map.keys().collect();
+        keys.sort();
+        for key in keys {
+            validate_value(key, &map[key])?;
+        }
+        Ok(())
+    }
+}
+
+/// Load the typed settings from the database (defaults if unreadable)
+pub fn load(db: &Database) -> AppSettings {
+    db.get_all_settings()
+        .map(|map| AppSettings::from_map(&map))
+        .unwrap_or_default()
+}
+
+type Listener = Box<dyn Fn(&[String]) + Send + Sync>;
+
+/// Validated settings access with change notifications
+pub struct SettingsService {
+    db: Arc<Database>,
+    listeners: RwLock<Vec<Listener>>,
+}
+
+impl SettingsService {
+    pub fn new(db: Arc<Database>) -> Self {
+        Self {
+            db,
+            listeners: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Get the typed settings
+    pub fn get(&self) -> AppSettings {
+        load(&self.db)
+    }
+
+    /// Value of a secret (see `SECRET_SETTINGS`); `None` if unset or empty
+    pub fn get_secret(&self, key: &str) -> Result<Option<String>, String> {
+        let value = self.db.get_setting(key).map_err(|e| e.to_string())?;
+        Ok(value.filter(|v| !v.is_empty()))
+    }
+
+    /// Validate and store all typed settings
+    pub fn update(&self, settings: &AppSettings) -> Result<(), String> {
+        settings.validate()?;
+        self.set_many(&settings.to_map())
+    }
+
+    /// Validate and store a single value
+    pub fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        let mut map = HashMap::new();
+        map.insert(key.to_string(), value.to_string());
+        self.set_many(&map)
+    }
+
+    /// Validate and store several values; subscribers get the keys whose value changed.
+    /// Deprecated keys are stored under their canonical key.
+    pub fn set_many(&self, values: &HashMap<String, String>) -> Result<(), String> {
+        let mut values = values.clone();
+        canonicalize_settings(&mut values);
+        let values = &values;
+        for (key, value) in values {
+            validate_value(key, value)?;
+        }
+        let previous = self.db.get_all_settings().map_err(|e| e.to_string())?;
+        self.db.set_settings(values).map_err(|e| e.to_string())?;
+
+        let mut changed: Vec<String> = values
+            .iter()
+            .filter(|(key, value)| previous.get(*key) != Some(*value))
+            .map(|(key, _)| key.clone())
+            .collect();
+        if !changed.is_empty() {
+            changed.sort();
+            self.notify(&changed);
+        }
+        Ok(())
+    }
+
+    /// Notify subscribers of the keys that differ from `previous` after the
+    /// database underneath was replaced (a workspace switch)
+    pub fn reload(&self, previous: &HashMap<String, String>) -> Result<(), String> {
+        let current = self.db.get_all_settings().map_err(|e| e.to_string())?;
+        let mut changed: Vec<String> = current
+            .keys()
+            .chain(previous.keys())
+            .filter(|key| current.get(*key) != previous.get(*key))
+            .cloned()
+            .collect();
+        if !changed.is_empty() {
+            changed.sort();
+            changed.dedup();
+            self.notify(&changed);
+        }
+        Ok(())
+    }
+
+    /// Register a listener called with the changed keys after each write
+    pub fn subscribe<F>(&self, listener: F)
+    where
+        F: Fn(&[String]) + Send + Sync + 'static,
+    {
+        self.listeners.write().unwrap().push(Box::new(listener));
+    }
+
+    /// Channel that receives a message whenever one of `keys` changes, so
+    /// background loops can wake up early instead of waiting out their sleep
+    pub fn watch(&self, keys: &'static [&'static str]) -> Receiver<()> {
+        let (tx, rx) = channel();
+        self.subscribe(move |changed| {
+            if changed.iter().any(|k| keys.contains(&k.as_str())) {
+                tx.send(()).ok();
+            }
+        });
+        rx
+    }
+
+    fn notify(&self, keys: &[String]) {
+        for listener in self.listeners.read().unwrap().iter() {
+            listener(keys);
+        }
+    }
+}
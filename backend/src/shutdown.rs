@@ -0,0 +1,44 @@
+//! Orchestrated shutdown
+//!
+//! Runs once on quit (tray, window close or app exit), in order:
+//! 1. stop the tracker and write samples still queued by other sources
+//! 2. close running timers if `close_timers_on_exit` is set (thinking mode
+//!    here; plugins close theirs on the `app.shutdown` event)
+//! 3. call every plugin's `shutdown()`
+//! 4. checkpoint the database so no WAL pages are left behind
+
+use crate::commands::AppState;
+use crate::events::EVENT_APP_SHUTDOWN;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static SHUT_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Flush state and stop subsystems; later calls do nothing
+pub fn run(state: &AppState) {
+    if SHUT_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let tracker = state.tracker.lock().unwrap().clone();
+    if let Some(tracker) = tracker {
+        tracker.stop_and_flush();
+    }
+
+    let close_timers = state.settings.get().close_timers_on_exit;
+    if close_timers {
+        if let Err(e) = crate::commands::end_thinking_mode(state) {
+            eprintln!("Warning: Failed to stop thinking mode on exit: {}", e);
+        }
+    }
+    state
+        .event_bus
+        .emit(EVENT_APP_SHUTDOWN, serde_json::json!({ "close_timers": close_timers }));
+
+    if let Some(plugin_registry) = &state.plugin_registry {
+        plugin_registry.shutdown_all();
+    }
+
+    if let Err(e) = state.db.checkpoint() {
+        eprintln!("Warning: Failed to checkpoint database on exit: {}", e);
+    }
+}
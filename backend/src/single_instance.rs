@@ -0,0 +1,151 @@
+//! Single-instance guard
+//!
+//! The first instance takes an exclusive lock on a file in the user's data
+//! directory and listens on a loopback port it writes into that file. A second
+//! launch by the same user finds the file locked, reads the port, asks the
+//! running instance to show its window and exits, so two trackers never record
+//! the same activity twice. The lock belongs to the user's data directory, so
+//! other users signed in on the same machine (fast user switching, RDP) run
+//! their own instance.
+//!
+//! The OS drops the lock when the process exits, even on a crash. If the lock
+//! file can't be opened or locked at all, startup continues without the guard
+//! rather than refusing to start.
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const LOCK_FILE: &str = "instance.lock";
+const SHOW_REQUEST: &str = "time-tracker:show";
+const SHOW_REPLY: &str = "time-tracker:ok";
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Pause between reads of a lock file whose port is not written yet
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The held instance lock and the listener for activation requests
+pub struct InstanceLock {
+    /// Kept open: closing it releases the lock
+    file: File,
+    listener: TcpListener,
+}
+
+/// Result of trying to become the running instance
+pub enum Instance {
+    /// This is the only instance of the user; pass the lock to `listen`
+    Primary(InstanceLock),
+    /// Another instance of the user is running (and was asked to show its window); exit
+    Secondary,
+    /// The lock could not be taken; run without the guard
+    Unguarded,
+}
+
+/// Port the running instance listens on, once it has written it
+fn read_port(file: &mut File) -> Option<u16> {
+    let mut contents = String::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_string(&mut contents).ok()?;
+    contents.trim().parse().ok()
+}
+
+/// Ask the instance holding `file` to show its window; true if it acknowledged
+fn activate_running_instance(file: &mut File) -> bool {
+    // The running instance may still be writing its port
+    let deadline = Instant::now() + HANDSHAKE_TIMEOUT;
+    let port = loop {
+        match read_port(file) {
+            Some(port) => break port,
+            None if Instant::now() < deadline => std::thread::sleep(PORT_POLL_INTERVAL),
+            None => return false,
+        }
+    };
+    let request = || -> std::io::Result<bool> {
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+        let mut stream = TcpStream::connect_timeout(&address, HANDSHAKE_TIMEOUT)?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        writeln!(stream, "{}", SHOW_REQUEST)?;
+        let mut reply = String::new();
+        BufReader::new(stream).read_line(&mut reply)?;
+        Ok(reply.trim() == SHOW_REPLY)
+    };
+    request().unwrap_or(false)
+}
+
+/// Take the instance lock in `dir`, or hand over to the instance that holds it
+fn acquire_in(dir: &Path) -> std::io::Result<Instance> {
+    std::fs::create_dir_all(dir)?;
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(dir.join(LOCK_FILE))?;
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => {
+            if !activate_running_instance(&mut file) {
+                eprintln!("Warning: The running instance did not answer; exiting anyway");
+            }
+            return Ok(Instance::Secondary);
+        }
+        Err(TryLockError::Error(e)) => return Err(e),
+    }
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))?;
+    file.set_len(0)?;
+    write!(file, "{}", listener.local_addr()?.port())?;
+    file.sync_all()?;
+    Ok(Instance::Primary(InstanceLock { file, listener }))
+}
+
+/// Take the user's instance lock, or hand over to the instance that holds it
+pub fn acquire() -> Instance {
+    acquire_in(&crate::workspace::data_dir()).unwrap_or_else(|e| {
+        eprintln!("Warning: Failed to take the instance lock ({}); running without single-instance guard", e);
+        Instance::Unguarded
+    })
+}
+
+/// Serve activation requests from later launches; `on_activate` shows the window
+pub fn listen<F>(lock: InstanceLock, on_activate: F)
+where
+    F: Fn() + Send + 'static,
+{
+    std::thread::spawn(move || {
+        // Held for as long as the process runs
+        let InstanceLock { file: _file, listener } = lock;
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).ok();
+            let mut request = String::new();
+            let read = stream.try_clone().map(|s| BufReader::new(s).read_line(&mut request));
+            if read.is_err() || request.trim() != SHOW_REQUEST {
+                continue;
+            }
+            writeln!(stream, "{}", SHOW_REPLY).ok();
+            on_activate();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn a_second_launch_of_the_same_user_activates_the_first() {
+        let dir = std::env::temp_dir().join(format!("time-tracker-instance-{}", std::process::id()));
+        let Instance::Primary(lock) = acquire_in(&dir).unwrap() else {
+            panic!("the first launch should take the lock");
+        };
+        let (activated, activations) = mpsc::channel();
+        listen(lock, move || activated.send(()).unwrap());
+
+        assert!(matches!(acquire_in(&dir).unwrap(), Instance::Secondary));
+        activations.recv_timeout(HANDSHAKE_TIMEOUT).unwrap();
+
+        // Another user's data directory has its own lock
+        let other_user = dir.join("other-user");
+        assert!(matches!(acquire_in(&other_user).unwrap(), Instance::Primary(_)));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
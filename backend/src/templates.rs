@@ -0,0 +1,117 @@
+//! Templates for reports and summaries
+//!
+//! Markdown and HTML output (the daily note, published reports) is rendered
+//! with [Tera](https://keats.github.io/tera/docs/) templates. Every template
+//! has a built-in default; a file of the same name in the `templates` folder
+//! of the data directory replaces it, so the output can be customized without
+//! a new build. Templates ending in `.html` are autoescaped.
+
+use crate::workspace;
+use serde::Serialize;
+use std::path::PathBuf;
+use tera::{Context, Tera};
+
+/// Daily note summary (see `daily_note`)
+pub const DAILY_NOTE: &str = "daily_note.md";
+/// Published HTML report (see `report`)
+pub const REPORT: &str = "report.html";
+
+/// Built-in defaults, by template name
+const BUILT_IN: &[(&str, &str)] = &[
+    (DAILY_NOTE, include_str!("../templates/daily_note.md")),
+    (REPORT, include_str!("../templates/report.html")),
+];
+
+/// A template and whether the user has replaced it
+#[derive(Debug, Clone, Serialize)]
+pub struct TemplateInfo {
+    pub name: String,
+    pub path: String,
+    pub customized: bool,
+}
+
+/// Folder with user templates
+pub fn dir() -> PathBuf {
+    workspace::data_dir().join("templates")
+}
+
+fn built_in(name: &str) -> Result<&'static str, String> {
+    BUILT_IN
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, source)| *source)
+        .ok_or_else(|| format!("Unknown template: {}", name))
+}
+
+/// All templates
+pub fn list() -> Vec<TemplateInfo> {
+    BUILT_IN
+        .iter()
+        .map(|(name, _)| {
+            let path = dir().join(name);
+            TemplateInfo {
+                name: name.to_string(),
+                customized: path.exists(),
+                path: path.to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Source of `name`: the user's file when present, else the built-in default
+pub fn source(name: &str) -> Result<String, String> {
+    let default = built_in(name)?;
+    match std::fs::read_to_string(dir().join(name)) {
+        Ok(source) => Ok(source),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(default.to_string()),
+        Err(e) => Err(format!("Failed to read template {}: {}", name, e)),
+    }
+}
+
+/// Tera error with its causes (the first line alone rarely says what is wrong)
+fn describe(e: tera::Error) -> String {
+    let mut message = e.to_string();
+    let mut cause = std::error::Error::source(&e);
+    while let Some(e) = cause {
+        message.push_str(": ");
+        message.push_str(&e.to_string());
+        cause = e.source();
+    }
+    message
+}
+
+fn compile(name: &str, source: &str) -> Result<Tera, String> {
+    let mut tera = Tera::default();
+    tera.autoescape_on(vec![".html"]);
+    tera.add_raw_template(name, source).map_err(describe)?;
+    Ok(tera)
+}
+
+/// Render `source` as the template `name`
+pub fn render_source(name: &str, source: &str, context: &Context) -> Result<String, String> {
+    compile(name, source)?.render(name, context).map_err(describe)
+}
+
+/// Render the template `name`
+pub fn render(name: &str, context: &Context) -> Result<String, String> {
+    render_source(name, &source(name)?, context)
+}
+
+/// Replace the template `name` with `source`; fails without saving if it does not parse
+pub fn save(name: &str, source: &str) -> Result<(), String> {
+    built_in(name)?;
+    compile(name, source)?;
+    std::fs::create_dir_all(dir()).map_err(|e| format!("Failed to create {}: {}", dir().display(), e))?;
+    std::fs::write(dir().join(name), source).map_err(|e| format!("Failed to save template {}: {}", name, e))
+}
+
+/// Go back to the built-in default of `name`
+pub fn reset(name: &str) -> Result<(), String> {
+    built_in(name)?;
+    match std::fs::remove_file(dir().join(name)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("Failed to reset template {}: {}", name, e))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,54 @@
+//! Terminal and SSH session attribution
+//!
+//! A shell hook (`scripts/terminal-hook.sh`) reports the host, working
+//! directory and project on every prompt, either to the local listener
+//! (`POST /terminal`, with the local API token like heartbeats, see `ide`)
+//! or through the `report_terminal_activity` command.
+//! Reports become activity samples (source `terminal`) titled
+//! `<project> - <host>:<cwd>`, so rules can attribute long SSH/tmux sessions
+//! whose window is just "Terminal".
+
+use crate::data_sources::{ActivitySample, DataSourceRegistry};
+use crate::database::common::SOURCE_TERMINAL;
+
+/// Report sent by the shell hook
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct TerminalReport {
+    pub host: String,
+    pub cwd: String,
+    /// Repository or project name; defaults to the last `cwd` component
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Whether the shell runs inside an SSH session
+    #[serde(default)]
+    pub remote: bool,
+    /// Defaults to the time the report is received
+    #[serde(default)]
+    pub timestamp: Option<i64>,
+}
+
+/// Queue a terminal report for the tracker
+pub fn record_report(data_sources: &DataSourceRegistry, report: TerminalReport) -> Result<(), String> {
+    let host = report.host.trim();
+    let cwd = report.cwd.trim();
+    if host.is_empty() || cwd.is_empty() {
+        return Err("Host and cwd are required".to_string());
+    }
+    let project = report
+        .project
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .or_else(|| cwd.trim_end_matches('/').rsplit('/').next().filter(|p| !p.is_empty()))
+        .unwrap_or(host);
+
+    data_sources.queue(SOURCE_TERMINAL).push(ActivitySample {
+        app_name: if report.remote { "ssh" } else { "terminal" }.to_string(),
+        window_title: Some(format!("{} - {}:{}", project, host, cwd)),
+        domain: None,
+        timestamp: report.timestamp.unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        source: SOURCE_TERMINAL.to_string(),
+    });
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Timeline blocks
+//!
+//! The tracker writes an activity every few seconds of app switching, so a
+//! day can hold thousands of rows. `merge_activities` turns them into display
+//! blocks: consecutive activities of the same app and category (and idle
+//! state) become one block, and blocks shorter than `min_block_sec` are folded
+//! into the block before them so the timeline stays readable.
+//!
+//! Each block keeps a compact title history (first and last window title and
+//! the titles open longest) so the detail view can show what was open while
+//! the block's rows are collapsed.
+
+use crate::database::Activity;
+use serde::Serialize;
+
+/// Largest gap between two activities that still continues a block
+const MAX_MERGE_GAP_SECS: i64 = 60;
+
+/// Titles listed in `top_titles`
+const MAX_TOP_TITLES: usize = 3;
+
+/// One window title seen in a block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TitleCount {
+    pub title: String,
+    /// Activities with this title
+    pub count: i64,
+    pub duration_sec: i64,
+}
+
+/// Consecutive activities merged for display
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimelineBlock {
+    pub started_at: i64,
+    pub ended_at: i64,
+    /// Tracked seconds in the block; gaps between merged activities are not counted
+    pub duration_sec: i64,
+    pub app_name: String,
+    pub domain: Option<String>,
+    pub category_id: Option<i64>,
+    pub is_idle: bool,
+    /// Number of activities merged into the block
+    pub activity_count: i64,
+    pub first_activity_id: i64,
+    pub first_title: Option<String>,
+    pub last_title: Option<String>,
+    /// Titles open longest, longest first
+    pub top_titles: Vec<TitleCount>,
+}
+
+impl TimelineBlock {
+    fn from_activity(activity: &Activity) -> Self {
+        Self {
+            started_at: activity.started_at,
+            ended_at: activity.started_at + activity.duration_sec,
+            duration_sec: activity.duration_sec,
+            app_name: activity.app_name.clone(),
+            domain: activity.domain.clone(),
+            category_id: activity.category_id,
+            is_idle: activity.is_idle,
+            activity_count: 1,
+            first_activity_id: activity.id,
+            first_title: activity.window_title.clone(),
+            last_title: activity.window_title.clone(),
+            top_titles: activity
+                .window_title
+                .iter()
+                .map(|title| TitleCount {
+                    title: title.clone(),
+                    count: 1,
+                    duration_sec: activity.duration_sec,
+                })
+                .collect(),
+        }
+    }
+
+    fn continues_with(&self, started_at: i64) -> bool {
+        started_at - self.ended_at <= MAX_MERGE_GAP_SECS
+    }
+
+    fn same_kind(&self, app_name: &str, category_id: Option<i64>, is_idle: bool) -> bool {
+        self.app_name == app_name && self.category_id == category_id && self.is_idle == is_idle
+    }
+
+    /// Append a later block (or a single-activity block) to this one
+    fn absorb(&mut self, later: TimelineBlock) {
+        self.ended_at = self.ended_at.max(later.ended_at);
+        self.duration_sec += later.duration_sec;
+        self.activity_count += later.activity_count;
+        if self.domain.is_none() {
+            self.domain = later.domain;
+        }
+        if self.first_title.is_none() {
+            self.first_title = later.first_title;
+        }
+        if later.last_title.is_some() {
+            self.last_title = later.last_title;
+        }
+        for seen in later.top_titles {
+            match self.top_titles.iter_mut().find(|t| t.title == seen.title) {
+                Some(existing) => {
+                    existing.count += seen.count;
+                    existing.duration_sec += seen.duration_sec;
+                }
+                None => self.top_titles.push(seen),
+            }
+        }
+    }
+}
+
+/// Merge activities, sorted by start time, into display blocks
+pub fn merge_activities(activities: &[Activity], min_block_sec: i64) -> Vec<TimelineBlock> {
+    let mut merged: Vec<TimelineBlock> = Vec::new();
+    for activity in activities {
+        match merged.last_mut() {
+            Some(block)
+                if block.same_kind(&activity.app_name, activity.category_id, activity.is_idle)
+                    && block.continues_with(activity.started_at) =>
+            {
+                block.absorb(TimelineBlock::from_activity(activity));
+            }
+            _ => merged.push(TimelineBlock::from_activity(activity)),
+        }
+    }
+
+    // Folding a short block can leave two blocks of the same app next to each other; join those too
+    let mut blocks: Vec<TimelineBlock> = Vec::with_capacity(merged.len());
+    for block in merged {
+        match blocks.last_mut() {
+            Some(previous)
+                if previous.continues_with(block.started_at)
+                    && (block.duration_sec < min_block_sec
+                        || previous.same_kind(&block.app_name, block.category_id, block.is_idle)) =>
+            {
+                previous.absorb(block);
+            }
+            _ => blocks.push(block),
+        }
+    }
+    for block in &mut blocks {
+        block.top_titles.sort_by(|a, b| b.duration_sec.cmp(&a.duration_sec).then(b.count.cmp(&a.count)));
+        block.top_titles.truncate(MAX_TOP_TITLES);
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(id: i64, app: &str, category_id: Option<i64>, started_at: i64, duration_sec: i64) -> Activity {
+        Activity {
+            id,
+            app_name: app.to_string(),
+            window_title: Some(format!("{} {}", app, id % 2)),
+            domain: None,
+            category_id,
+            started_at,
+            duration_sec,
+            is_idle: false,
+            source: "tracker".to_string(),
+            billable_override: None,
+            context_ref: None,
+            device_id: None,
+            device_name: None,
+        }
+    }
+
+    #[test]
+    fn merges_consecutive_activities_of_one_app() {
+        let activities = [
+            activity(1, "Code", Some(1), 0, 5),
+            activity(2, "Code", Some(1), 5, 5),
+            activity(3, "Slack", Some(2), 10, 30),
+            activity(4, "Code", Some(1), 40, 5),
+        ];
+        let blocks = merge_activities(&activities, 0);
+        assert_eq!(blocks.len(), 3);
+        assert_eq!((blocks[0].started_at, blocks[0].ended_at, blocks[0].activity_count), (0, 10, 2));
+        assert_eq!(blocks[1].app_name, "Slack");
+        assert_eq!(blocks[2].first_activity_id, 4);
+        assert_eq!(blocks[0].first_title.as_deref(), Some("Code 1"));
+        assert_eq!(blocks[0].last_title.as_deref(), Some("Code 0"));
+    }
+
+    #[test]
+    fn folds_short_blocks_and_keeps_gaps() {
+        let activities = [
+            activity(1, "Code", Some(1), 0, 300),
+            activity(2, "Finder", None, 300, 5),
+            activity(3, "Code", Some(1), 305, 300),
+            activity(4, "Code", Some(1), 3600, 60),
+        ];
+        let blocks = merge_activities(&activities, 30);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!((blocks[0].ended_at, blocks[0].duration_sec, blocks[0].activity_count), (605, 605, 3));
+        // Too far from the previous block to be folded, even though it is short
+        assert_eq!(blocks[1].started_at, 3600);
+        let top: Vec<(&str, i64, i64)> = blocks[0]
+            .top_titles
+            .iter()
+            .map(|t| (t.title.as_str(), t.count, t.duration_sec))
+            .collect();
+        assert_eq!(top, [("Code 1", 2, 600), ("Finder 0", 1, 5)]);
+    }
+}
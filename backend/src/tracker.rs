@@ -1,17 +1,22 @@
 //! Tracker module - Core tracking loop for monitoring active windows
 
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
 use std::sync::{Arc, Mutex};
-use std::thread;
+use std::thread::{self, JoinHandle};
 use std::time::Duration;
 
+use crate::data_sources::{record_sample, ActivitySample, DataSource, DataSourceRegistry};
+use crate::database::activities::INPUT_SAMPLE_SECS;
+use crate::database::common::SOURCE_TRACKER;
 use crate::database::Database;
+use crate::events::{EventBus, EVENT_ACTIVITY_RECORDED};
 use crate::idle::IdleMonitor;
-use crate::plugin_system::ExtensionRegistry;
+use crate::plugin_system::{ExtensionRegistry, PluginRegistry};
 use crate::window::WindowTracker;
 
 /// Extract domain from browser window title
-fn extract_domain(app_name: &str, window_title: Option<&str>) -> Option<String> {
+pub(crate) fn extract_domain(app_name: &str, window_title: Option<&str>) -> Option<String> {
     // Only process browser windows
     let browser_apps = ["chrome", "firefox", "edge", "safari", "opera", "brave", "vivaldi"];
     if !browser_apps.iter().any(|&browser| app_name.to_lowercase().contains(browser)) {
@@ -112,31 +117,149 @@ fn is_valid_domain(s: &str) -> bool {
     s.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-')
 }
 
+/// Built-in data source: the OS foreground window
+pub struct ForegroundWindowSource {
+    window_tracker: WindowTracker,
+}
+
+impl ForegroundWindowSource {
+    pub fn new() -> Self {
+        Self {
+            window_tracker: WindowTracker::new(),
+        }
+    }
+}
+
+impl DataSource for ForegroundWindowSource {
+    fn id(&self) -> &str {
+        SOURCE_TRACKER
+    }
+
+    fn poll(&self, now: i64) -> Vec<ActivitySample> {
+        self.window_tracker
+            .get_active_window()
+            .map(|window_info| ActivitySample {
+                domain: extract_domain(&window_info.app_name, window_info.title.as_deref()),
+                app_name: window_info.app_name,
+                window_title: window_info.title,
+                timestamp: now,
+                source: SOURCE_TRACKER.to_string(),
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+/// What an idle-time observation means for the sample taken with it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum IdleObservation {
+    /// The user is active; record the sample
+    Active,
+    /// The user is idle; the idle activity was started or extended instead
+    Idle,
+    /// The user is back after `minutes` of idle time that began at `started_at`
+    Returned { minutes: u64, started_at: i64 },
+}
+
+/// Idle state of a tracking loop: records the `Idle` activity while the
+/// input idle time exceeds the threshold and reports the return
+#[derive(Debug, Default)]
+pub(crate) struct IdleState {
+    started_at: Option<i64>,
+}
+
+impl IdleState {
+    /// Feed the input idle time observed at `now`
+    pub(crate) fn observe(&mut self, db: &Database, now: i64, idle_secs: u64, threshold_secs: u64) -> IdleObservation {
+        if idle_secs > threshold_secs {
+            match self.started_at {
+                // Entering idle mode
+                None => {
+                    self.started_at = Some(now);
+                    if let Err(e) = db.record_idle_start(now) {
+                        eprintln!("Failed to record idle start: {}", e);
+                    }
+                }
+                Some(start) => {
+                    if let Err(e) = db.update_idle_duration(start, now - start) {
+                        eprintln!("Failed to update idle duration: {}", e);
+                    }
+                }
+            }
+            return IdleObservation::Idle;
+        }
+        match self.started_at.take() {
+            Some(started_at) => IdleObservation::Returned {
+                minutes: (now - started_at).max(0) as u64 / 60,
+                started_at,
+            },
+            None => IdleObservation::Active,
+        }
+    }
+}
+
+/// Tell event bus listeners (e.g. the report cache) that a sample was written
+fn announce(event_bus: &EventBus, id: i64, sample: &ActivitySample) {
+    event_bus.emit(
+        EVENT_ACTIVITY_RECORDED,
+        serde_json::json!({ "id": id, "timestamp": sample.timestamp, "source": sample.source }),
+    );
+}
+
 /// Tracker service that runs the main tracking loop
 pub struct Tracker {
     db: Arc<Database>,
     extension_registry: Option<Arc<ExtensionRegistry>>,
+    plugin_registry: Option<Arc<PluginRegistry>>,
+    data_sources: Arc<DataSourceRegistry>,
+    /// Receives `activity.recorded` for every written sample
+    event_bus: Arc<EventBus>,
     window_tracker: WindowTracker,
     idle_monitor: Arc<IdleMonitor>,
     running: Arc<AtomicBool>,
     paused: Arc<AtomicBool>,
+    /// When a timed pause ends (Unix seconds); `None` for an open-ended pause
+    paused_until: Mutex<Option<i64>>,
     idle_threshold_secs: Arc<Mutex<u64>>,
     prompt_threshold_secs: Arc<Mutex<u64>>,
+    /// Parse document references from window titles (`capture_context_ref`)
+    capture_context: Arc<AtomicBool>,
+    /// Count samples with keyboard/mouse input (`record_input_intensity`)
+    input_intensity: Arc<AtomicBool>,
+    /// Wakes the loop early so `stop` takes effect without waiting out the poll interval
+    wake: Mutex<Option<Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
 }
 
 impl Tracker {
     /// Create a new tracker instance.
     /// If `extension_registry` is provided, plugin data hooks will be applied after each activity upsert.
-    pub fn new(db: Arc<Database>, extension_registry: Option<Arc<ExtensionRegistry>>) -> Self {
+    /// If `plugin_registry` is provided, plugins can modify or drop samples before they are written.
+    /// Samples from `data_sources` are recorded alongside the foreground window.
+    pub fn new(
+        db: Arc<Database>,
+        extension_registry: Option<Arc<ExtensionRegistry>>,
+        plugin_registry: Option<Arc<PluginRegistry>>,
+        data_sources: Arc<DataSourceRegistry>,
+        event_bus: Arc<EventBus>,
+    ) -> Self {
         Self {
             db,
             extension_registry,
+            plugin_registry,
+            data_sources,
+            event_bus,
             window_tracker: WindowTracker::new(),
             idle_monitor: Arc::new(IdleMonitor::new()),
             running: Arc::new(AtomicBool::new(false)),
             paused: Arc::new(AtomicBool::new(false)),
+            paused_until: Mutex::new(None),
             idle_threshold_secs: Arc::new(Mutex::new(120)), // 2 minutes default
             prompt_threshold_secs: Arc::new(Mutex::new(300)), // 5 minutes default
+            capture_context: Arc::new(AtomicBool::new(false)),
+            input_intensity: Arc::new(AtomicBool::new(false)),
+            wake: Mutex::new(None),
+            handle: Mutex::new(None),
         }
     }
 
@@ -150,19 +273,51 @@ impl Tracker {
         *self.prompt_threshold_secs.lock().unwrap() = secs;
     }
 
+    /// Enable or disable document reference capture
+    pub fn set_context_capture(&self, enabled: bool) {
+        self.capture_context.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Enable or disable input intensity counting
+    pub fn set_input_intensity(&self, enabled: bool) {
+        self.input_intensity.store(enabled, Ordering::SeqCst);
+    }
+
     /// Check if tracker is running
     pub fn is_running(&self) -> bool {
         self.running.load(Ordering::SeqCst)
     }
 
-    /// Pause tracking
+    /// Pause tracking until resumed
     pub fn pause(&self) {
+        self.pause_until(None);
+    }
+
+    /// Pause tracking; `until` records when a timed pause should end.
+    /// The tracker does not resume by itself, see `resume_if_due`.
+    pub fn pause_until(&self, until: Option<i64>) {
+        let mut paused_until = self.paused_until.lock().unwrap();
+        *paused_until = until;
         self.paused.store(true, Ordering::SeqCst);
     }
 
     /// Resume tracking
     pub fn resume(&self) {
+        let mut paused_until = self.paused_until.lock().unwrap();
+        *paused_until = None;
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// Resume if the timed pause ending at `until` is still in effect and `now`
+    /// has reached it. Returns whether tracking was resumed.
+    pub fn resume_if_due(&self, until: i64, now: i64) -> bool {
+        let mut paused_until = self.paused_until.lock().unwrap();
+        if *paused_until != Some(until) || now < until || !self.paused.load(Ordering::SeqCst) {
+            return false;
+        }
+        *paused_until = None;
         self.paused.store(false, Ordering::SeqCst);
+        true
     }
 
     /// Check if tracking is paused
@@ -170,6 +325,11 @@ impl Tracker {
         self.paused.load(Ordering::SeqCst)
     }
 
+    /// End of the current timed pause, if any
+    pub fn paused_until(&self) -> Option<i64> {
+        *self.paused_until.lock().unwrap()
+    }
+
     /// Get current active app name
     pub fn get_current_app(&self) -> Option<String> {
         if self.paused.load(Ordering::SeqCst) {
@@ -182,6 +342,17 @@ impl Tracker {
     /// Stop the tracker
     pub fn stop(&self) {
         self.running.store(false, Ordering::SeqCst);
+        if let Some(wake) = self.wake.lock().unwrap().as_ref() {
+            wake.send(()).ok();
+        }
+    }
+
+    /// Stop the tracker and wait until queued samples are written
+    pub fn stop_and_flush(&self) {
+        self.stop();
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            handle.join().ok();
+        }
     }
 
     /// Start the tracking loop
@@ -196,88 +367,86 @@ impl Tracker {
         let paused = Arc::clone(&self.paused);
         let db = Arc::clone(&self.db);
         let extension_registry = self.extension_registry.clone();
+        let plugin_registry = self.plugin_registry.clone();
+        let data_sources = Arc::clone(&self.data_sources);
+        let event_bus = Arc::clone(&self.event_bus);
         let idle_threshold = Arc::clone(&self.idle_threshold_secs);
+        let capture_context = Arc::clone(&self.capture_context);
+        let input_intensity = Arc::clone(&self.input_intensity);
         let idle_monitor = Arc::clone(&self.idle_monitor);
+        let (wake, woken) = channel();
+        *self.wake.lock().unwrap() = Some(wake);
 
-        thread::spawn(move || {
-            let window_tracker = WindowTracker::new();
-            
-            let mut is_idle_mode = false;
-            let mut idle_start_time: Option<i64> = None;
+        let handle = thread::spawn(move || {
+            let foreground = ForegroundWindowSource::new();
+            let mut idle_state = IdleState::default();
 
             while running.load(Ordering::SeqCst) {
-                // Sleep for 5 seconds between checks
-                thread::sleep(Duration::from_secs(5));
-
-                // Skip if paused
-                if paused.load(Ordering::SeqCst) {
-                    continue;
+                // Wait 5 seconds between checks (or until stopped)
+                woken.recv_timeout(Duration::from_secs(INPUT_SAMPLE_SECS as u64)).ok();
+                if !running.load(Ordering::SeqCst) {
+                    break;
                 }
 
-                let idle_time = idle_monitor.get_idle_time();
                 let now = chrono::Utc::now().timestamp();
+                let capture = capture_context.load(Ordering::SeqCst);
 
-                // Check for idle state
-                let idle_threshold_value = *idle_threshold.lock().unwrap();
-                if idle_time > idle_threshold_value {
-                    if !is_idle_mode {
-                        // Entering idle mode
-                        is_idle_mode = true;
-                        idle_start_time = Some(now);
-                        
-                        if let Err(e) = db.record_idle_start(now) {
-                            eprintln!("Failed to record idle start: {}", e);
-                        }
-                    } else if let Some(start) = idle_start_time {
-                        // Update idle duration
-                        let duration = now - start;
-                        if let Err(e) = db.update_idle_duration(start, duration) {
-                            eprintln!("Failed to update idle duration: {}", e);
-                        }
-                    }
+                // Skip if paused (samples pushed meanwhile are discarded)
+                if paused.load(Ordering::SeqCst) {
+                    data_sources.poll_all(now);
                     continue;
                 }
 
-                // Exiting idle mode
-                if is_idle_mode {
-                    is_idle_mode = false;
-                    
-                    if let Some(start) = idle_start_time {
-                        let idle_duration = (now - start) as u64;
-                        
-                        // Always send idle return event, let frontend decide whether to show prompt
-                        // Frontend will filter based on prompt_threshold and user preferences
-                        on_idle_return(idle_duration / 60, start); // Convert to minutes, pass started_at
+                // Other sources report activity that may happen away from this
+                // machine's keyboard (e.g. SSH sessions), so record them even when idle
+                for sample in data_sources.poll_all(now) {
+                    match record_sample(&db, extension_registry.as_ref(), plugin_registry.as_ref(), &sample, capture) {
+                        Ok(Some(id)) => announce(&event_bus, id, &sample),
+                        Ok(None) => {}
+                        Err(e) => eprintln!("Failed to record {} activity: {}", sample.source, e),
                     }
-                    
-                    idle_start_time = None;
                 }
 
-                // Get active window info
-                if let Some(window_info) = window_tracker.get_active_window() {
-                    let domain = extract_domain(&window_info.app_name, window_info.title.as_deref());
-                    match db.upsert_activity(
-                        &window_info.app_name,
-                        window_info.title.as_deref(),
-                        domain.as_deref(),
-                        now,
-                    ) {
-                        Ok(activity_id) => {
-                            // Apply plugin data hooks if extension registry is available
-                            if let Some(reg) = &extension_registry {
-                                if let Ok(Some(mut activity)) = db.get_activity_by_id(activity_id) {
-                                    if let Err(e) = reg.apply_activity_hooks(&mut activity, &db) {
-                                        eprintln!("Warning: Failed to apply activity hooks: {}", e);
-                                    } else if let Err(e) = db.update_activity_row(&activity) {
-                                        eprintln!("Warning: Failed to persist activity after hooks: {}", e);
-                                    }
+                let idle_time = idle_monitor.get_idle_time();
+                let idle_threshold_value = *idle_threshold.lock().unwrap();
+                match idle_state.observe(&db, now, idle_time, idle_threshold_value) {
+                    IdleObservation::Idle => continue,
+                    // Always report the idle return; the callback applies the prompt threshold
+                    IdleObservation::Returned { minutes, started_at } => on_idle_return(minutes, started_at),
+                    IdleObservation::Active => {}
+                }
+
+                // Record the foreground window; input since the last check means active use
+                let had_input = idle_time < INPUT_SAMPLE_SECS as u64;
+                for sample in foreground.poll(now) {
+                    match record_sample(&db, extension_registry.as_ref(), plugin_registry.as_ref(), &sample, capture) {
+                        Ok(Some(id)) => {
+                            if input_intensity.load(Ordering::SeqCst) {
+                                if let Err(e) = db.add_input_sample(id, had_input) {
+                                    eprintln!("Failed to record input intensity: {}", e);
                                 }
                             }
+                            announce(&event_bus, id, &sample);
                         }
+                        Ok(None) => {}
                         Err(e) => eprintln!("Failed to record activity: {}", e),
                     }
                 }
             }
+
+            // Write samples other sources queued since the last check
+            let samples = data_sources.poll_all(chrono::Utc::now().timestamp());
+            let capture = capture_context.load(Ordering::SeqCst);
+            if !paused.load(Ordering::SeqCst) {
+                for sample in samples {
+                    match record_sample(&db, extension_registry.as_ref(), plugin_registry.as_ref(), &sample, capture) {
+                        Ok(Some(id)) => announce(&event_bus, id, &sample),
+                        Ok(None) => {}
+                        Err(e) => eprintln!("Failed to record {} activity: {}", sample.source, e),
+                    }
+                }
+            }
         });
+        *self.handle.lock().unwrap() = Some(handle);
     }
 }
@@ -1,7 +1,16 @@
 //! System tray module - Manages the system tray icon and menu
+//!
+//! The icon reflects the tracking state (tracking, paused, idle, pomodoro
+//! work/break) and the tooltip shows the active project reported by plugins.
 
+use crate::database::Database;
+use crate::events::{EventBus, EVENT_TRACKING_PAUSED, EVENT_TRACKING_RESUMED};
+use crate::tracker::Tracker;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::{
-    AppHandle, CustomMenuItem, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
+    AppHandle, CustomMenuItem, Icon, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu,
     SystemTrayMenuItem,
 };
 
@@ -19,6 +28,7 @@ pub fn create_tray_menu(today_time: &str) -> SystemTrayMenu {
     let start_activity = CustomMenuItem::new("start_activity", "▶️  Start Activity");
     let thinking_mode = CustomMenuItem::new("thinking_mode", "🧠 Thinking Mode");
     let pause = CustomMenuItem::new("pause", "⏸️  Pause Tracking");
+    let mini_timer = CustomMenuItem::new("mini_timer", "⏱️  Mini Timer");
     
     let separator2 = SystemTrayMenuItem::Separator;
     
@@ -36,6 +46,7 @@ pub fn create_tray_menu(today_time: &str) -> SystemTrayMenu {
         .add_item(start_activity)
         .add_item(thinking_mode)
         .add_item(pause)
+        .add_item(mini_timer)
         .add_native_item(separator2)
         .add_item(dashboard)
         .add_item(reports)
@@ -77,13 +88,9 @@ pub fn handle_tray_event(app: &AppHandle, event: SystemTrayEvent) {
 fn handle_menu_click(app: &AppHandle, id: &str) {
     match id {
         "quit" => {
-            // Stop tracker before exit so last activity is flushed
+            // Flush tracker, timers and plugins before exit
             if let Some(state) = app.try_state::<crate::commands::AppState>() {
-                if let Ok(guard) = state.tracker.lock() {
-                    if let Some(t) = guard.as_ref() {
-                        t.stop();
-                    }
-                }
+                crate::shutdown::run(&state);
             }
             app.exit(0);
         }
@@ -125,6 +132,20 @@ fn handle_menu_click(app: &AppHandle, id: &str) {
                 window.emit("start-thinking-mode", ()).ok();
             }
         }
+        "mini_timer" => {
+            // Toggle; window creation must not block the event loop thread
+            if let Some(window) = app.get_window(crate::commands::MINI_TIMER_LABEL) {
+                window.close().ok();
+            } else if let Some(state) = app.try_state::<crate::commands::AppState>() {
+                let settings = state.settings.get();
+                let app = app.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = crate::commands::open_mini_timer(&app, &settings) {
+                        eprintln!("Failed to open mini timer: {}", e);
+                    }
+                });
+            }
+        }
         "pause" => {
             // Toggle pause state
             if let Some(window) = app.get_window("main") {
@@ -134,3 +155,186 @@ fn handle_menu_click(app: &AppHandle, id: &str) {
         _ => {}
     }
 }
+
+/// Tracking state shown by the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayState {
+    Tracking,
+    Paused,
+    Idle,
+    PomodoroWork,
+    PomodoroBreak,
+}
+
+impl TrayState {
+    fn label(self) -> &'static str {
+        match self {
+            TrayState::Tracking => "Tracking",
+            TrayState::Paused => "Paused",
+            TrayState::Idle => "Idle",
+            TrayState::PomodoroWork => "Pomodoro: work",
+            TrayState::PomodoroBreak => "Pomodoro: break",
+        }
+    }
+
+    /// Badge color; `None` keeps the regular app icon
+    fn color(self) -> Option<[u8; 3]> {
+        match self {
+            TrayState::Tracking => None,
+            TrayState::Paused => Some([142, 142, 147]),
+            TrayState::Idle => Some([255, 179, 64]),
+            TrayState::PomodoroWork => Some([235, 77, 61]),
+            TrayState::PomodoroBreak => Some([64, 156, 255]),
+        }
+    }
+}
+
+/// Activity newer than this counts as current when deciding idle vs tracking
+const CURRENT_ACTIVITY_WINDOW_SECS: i64 = 60;
+
+/// Fallback refresh so idle transitions show up without an event
+const STATUS_POLL_INTERVAL_SECS: u64 = 5;
+
+const BADGE_SIZE: u32 = 32;
+
+/// Draw a round badge in the state color (paused gets pause bars)
+fn badge_icon(state: TrayState, color: [u8; 3]) -> Icon {
+    let size = BADGE_SIZE as i32;
+    let center = (size as f32 - 1.0) / 2.0;
+    let radius = size as f32 / 2.0 - 1.0;
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            // 1px anti-aliased edge
+            let alpha = (radius - distance + 0.5).clamp(0.0, 1.0);
+            let pause_bar = state == TrayState::Paused
+                && (9..=21).contains(&y)
+                && ((10..=13).contains(&x) || (18..=21).contains(&x));
+            let [r, g, b] = if pause_bar { [255, 255, 255] } else { color };
+            rgba.extend_from_slice(&[r, g, b, (alpha * 255.0) as u8]);
+        }
+    }
+    Icon::Rgba {
+        rgba,
+        width: BADGE_SIZE,
+        height: BADGE_SIZE,
+    }
+}
+
+/// Apply the icon and tooltip for a state
+pub fn set_tray_status(app: &AppHandle, state: TrayState, project: Option<&str>) {
+    let tray = app.tray_handle();
+    let result = match state.color() {
+        Some(color) => {
+            #[cfg(target_os = "macos")]
+            tray.set_icon_as_template(false).ok();
+            tray.set_icon(badge_icon(state, color))
+        }
+        None => {
+            #[cfg(target_os = "macos")]
+            tray.set_icon_as_template(true).ok();
+            tray.set_icon(Icon::Raw(include_bytes!("../icons/icon.png").to_vec()))
+        }
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to update tray icon: {}", e);
+    }
+
+    let tooltip = match project {
+        Some(project) => format!("Time Tracker — {} · {}", state.label(), project),
+        None => format!("Time Tracker — {}", state.label()),
+    };
+    tray.set_tooltip(&tooltip).ok();
+}
+
+/// Pomodoro phase and active project reported by plugins
+#[derive(Default)]
+struct PluginStatus {
+    pomodoro_phase: Option<String>,
+    project: Option<String>,
+}
+
+fn current_state(db: &Database, tracker: &Mutex<Option<Arc<Tracker>>>, pomodoro_phase: Option<&str>) -> TrayState {
+    let (is_running, is_paused) = tracker
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|t| (t.is_running(), t.is_paused()))
+        .unwrap_or((false, false));
+    if !is_running || is_paused {
+        return TrayState::Paused;
+    }
+    match pomodoro_phase {
+        Some("work") | Some("focus") => return TrayState::PomodoroWork,
+        Some(phase) if phase.contains("break") => return TrayState::PomodoroBreak,
+        _ => {}
+    }
+    let now = chrono::Utc::now().timestamp();
+    let idle = db
+        .get_latest_activity()
+        .ok()
+        .flatten()
+        .filter(|a| a.started_at + a.duration_sec >= now - CURRENT_ACTIVITY_WINDOW_SECS)
+        .map(|a| a.is_idle)
+        .unwrap_or(true);
+    if idle {
+        TrayState::Idle
+    } else {
+        TrayState::Tracking
+    }
+}
+
+/// Keep the tray icon and tooltip in sync with tracking state
+///
+/// Pause/resume and plugin events (`pomodoro.*` with a `phase`, any event with
+/// a `project` field) refresh the tray right away; idle is picked up by a
+/// short poll since the tracker does not publish it.
+pub fn start_status_updates(
+    app: AppHandle,
+    db: Arc<Database>,
+    tracker: Arc<Mutex<Option<Arc<Tracker>>>>,
+    event_bus: &EventBus,
+) {
+    let status = Arc::new(Mutex::new(PluginStatus::default()));
+    let (tx, rx) = channel::<()>();
+
+    let status_for_events = Arc::clone(&status);
+    let tx = Mutex::new(tx);
+    event_bus.subscribe(move |event| {
+        let mut relevant = event.name == EVENT_TRACKING_PAUSED || event.name == EVENT_TRACKING_RESUMED;
+        let mut status = status_for_events.lock().unwrap();
+        if event.name.starts_with("pomodoro.") {
+            if let Some(phase) = event.payload.get("phase") {
+                status.pomodoro_phase = phase.as_str().map(str::to_string);
+                relevant = true;
+            }
+        }
+        if let Some(project) = event.payload.get("project") {
+            status.project = project.as_str().map(str::to_string);
+            relevant = true;
+        }
+        if relevant {
+            tx.lock().unwrap().send(()).ok();
+        }
+    });
+
+    std::thread::spawn(move || {
+        let mut shown: Option<(TrayState, Option<String>)> = None;
+        loop {
+            let (phase, project) = {
+                let status = status.lock().unwrap();
+                (status.pomodoro_phase.clone(), status.project.clone())
+            };
+            let state = current_state(&db, &tracker, phase.as_deref());
+            let next = (state, project);
+            if shown.as_ref() != Some(&next) {
+                set_tray_status(&app, next.0, next.1.as_deref());
+                shown = Some(next);
+            }
+            if let Err(RecvTimeoutError::Disconnected) = rx.recv_timeout(Duration::from_secs(STATUS_POLL_INTERVAL_SECS)) {
+                break;
+            }
+        }
+    });
+}
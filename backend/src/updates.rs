@@ -0,0 +1,134 @@
+//! Update checking against GitHub releases
+//!
+//! While `update_check_enabled` is `true` (the default), the latest release is
+//! checked once a day. A newer version is reported once through the callback
+//! (with the release notes); `update_notified_version` remembers which
+//! version was announced so users are not reminded on every start.
+
+use crate::settings::SettingsService;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/tmtrckr/time-tracker-app/releases/latest";
+
+/// Version of this build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const CHECK_INTERVAL_SECS: i64 = 24 * 60 * 60;
+
+/// How often the schedule (and settings) are checked
+const POLL_INTERVAL_SECS: u64 = 60 * 60;
+
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    name: Option<String>,
+    body: Option<String>,
+    html_url: String,
+    published_at: Option<String>,
+}
+
+/// Result of an update check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub release_name: Option<String>,
+    /// Release notes (Markdown)
+    pub changelog: Option<String>,
+    pub release_url: String,
+    pub published_at: Option<String>,
+}
+
+/// Numeric version parts; a leading `v` and pre-release suffixes are ignored
+fn version_parts(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn is_newer(latest: &str, current: &str) -> bool {
+    let (mut latest, mut current) = (version_parts(latest), version_parts(current));
+    let len = latest.len().max(current.len());
+    latest.resize(len, 0);
+    current.resize(len, 0);
+    latest > current
+}
+
+/// Fetch the latest release and compare it with this build
+pub async fn check() -> Result<UpdateInfo, String> {
+    let client = reqwest::Client::builder()
+        .user_agent(format!("TimeTracker/{}", CURRENT_VERSION))
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Release API returned status: {}", response.status()));
+    }
+    let release: Release = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release: {}", e))?;
+
+    let latest_version = release.tag_name.trim_start_matches('v').to_string();
+    Ok(UpdateInfo {
+        current_version: CURRENT_VERSION.to_string(),
+        update_available: is_newer(&latest_version, CURRENT_VERSION),
+        latest_version,
+        release_name: release.name,
+        changelog: release.body,
+        release_url: release.html_url,
+        published_at: release.published_at,
+    })
+}
+
+/// Start the daily update checker; `on_update` is called once per new version
+pub fn start<F>(settings: Arc<SettingsService>, on_update: F)
+where
+    F: Fn(&UpdateInfo) + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Warning: Failed to start update checker: {}", e);
+                return;
+            }
+        };
+        let mut last_checked: Option<i64> = None;
+
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            let due = last_checked.is_none_or(|at| now - at >= CHECK_INTERVAL_SECS);
+            if settings.get().update_check_enabled && due {
+                last_checked = Some(now);
+                match runtime.block_on(check()) {
+                    Ok(info) if info.update_available => {
+                        let notified = settings.get().update_notified_version;
+                        if notified.as_deref() != Some(info.latest_version.as_str()) {
+                            on_update(&info);
+                            settings.set("update_notified_version", &info.latest_version).ok();
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("Warning: {}", e),
+                }
+            }
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+        }
+    });
+}
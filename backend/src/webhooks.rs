@@ -0,0 +1,200 @@
+//! Outbound webhooks
+//!
+//! Events from the event bus are matched against the configured webhooks and
+//! written to the `webhook_deliveries` queue. A background worker sends due
+//! deliveries as JSON POST requests, signed with HMAC-SHA256 when the webhook
+//! has a secret, and retries failures with exponential backoff. Events about
+//! records in a category or project excluded from export are never queued.
+//! Delivered and failed deliveries are pruned after `DELIVERY_RETENTION_SECS`.
+
+use crate::database::webhooks::{DELIVERY_STATUS_DELIVERED, DELIVERY_STATUS_FAILED, DELIVERY_STATUS_PENDING};
+use crate::database::{Database, Webhook, WebhookDelivery};
+use crate::events::{event_matches, AppEvent, EventBus, EVENT_ACTIVITY_RECORDED};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the worker looks for due deliveries
+const POLL_INTERVAL_SECS: u64 = 5;
+
+/// Deliveries sent per worker pass
+const BATCH_SIZE: i64 = 20;
+
+/// Attempts before a delivery is marked as failed
+const MAX_ATTEMPTS: i64 = 8;
+
+/// First retry delay; doubles on every attempt up to `MAX_BACKOFF_SECS`
+const BASE_BACKOFF_SECS: i64 = 30;
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Age after which delivered and failed deliveries are deleted
+const DELIVERY_RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+
+/// How often the worker prunes old deliveries
+const PRUNE_INTERVAL_SECS: u64 = 60 * 60;
+
+/// Compute the `X-TimeTracker-Signature` header value for a request body
+pub fn sign_payload(secret: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Build the JSON body sent for an event
+pub fn delivery_body(event: &AppEvent) -> String {
+    serde_json::json!({
+        "event": event.name,
+        "timestamp": event.timestamp,
+        "data": event.payload,
+    })
+    .to_string()
+}
+
+/// Whether a webhook is subscribed to an event. `activity.recorded` fires for
+/// every tracker sample, so only webhooks naming it exactly receive it.
+fn wants_event(webhook: &Webhook, event: &str) -> bool {
+    if event == EVENT_ACTIVITY_RECORDED {
+        return webhook.enabled && webhook.events.iter().any(|p| p == event);
+    }
+    webhook.enabled && (webhook.events.is_empty() || webhook.events.iter().any(|p| event_matches(p, event)))
+}
+
+/// Whether an event's payload names a category or project excluded from export
+fn is_excluded(db: &Database, event: &AppEvent) -> bool {
+    let id = |key: &str| event.payload.get(key).and_then(|v| v.as_i64());
+    match db.is_excluded_from_export(id("category_id"), id("project_id")) {
+        Ok(excluded) => excluded,
+        Err(e) => {
+            // Fail closed: an unreadable flag must not leak a private record
+            eprintln!("Warning: Failed to check export exclusion: {}", e);
+            true
+        }
+    }
+}
+
+/// Subscribe to the event bus and queue deliveries for matching webhooks
+pub fn register(bus: &EventBus, db: Arc<Database>) {
+    bus.subscribe(move |event| {
+        let webhooks = match db.get_webhooks() {
+            Ok(webhooks) => webhooks,
+            Err(e) => {
+                eprintln!("Warning: Failed to load webhooks: {}", e);
+                return;
+            }
+        };
+
+        if webhooks.iter().all(|w| !wants_event(w, &event.name)) || is_excluded(&db, event) {
+            return;
+        }
+
+        let body = delivery_body(event);
+        for webhook in webhooks.iter().filter(|w| wants_event(w, &event.name)) {
+            if let Err(e) = db.enqueue_webhook_delivery(webhook.id, &event.name, &body) {
+                eprintln!("Warning: Failed to queue webhook delivery: {}", e);
+            }
+        }
+    });
+}
+
+/// Start the background delivery worker
+pub fn start_worker(db: Arc<Database>) {
+    std::thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Warning: Failed to start webhook worker: {}", e);
+                return;
+            }
+        };
+        let client = match reqwest::Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+        {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("Warning: Failed to create webhook HTTP client: {}", e);
+                return;
+            }
+        };
+
+        let mut last_pruned: Option<std::time::Instant> = None;
+        loop {
+            std::thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+            runtime.block_on(process_due_deliveries(&db, &client));
+            if last_pruned.is_none_or(|at| at.elapsed() >= Duration::from_secs(PRUNE_INTERVAL_SECS)) {
+                let before = chrono::Utc::now().timestamp() - DELIVERY_RETENTION_SECS;
+                if let Err(e) = db.delete_finished_webhook_deliveries(before) {
+                    eprintln!("Warning: Failed to prune webhook deliveries: {}", e);
+                }
+                last_pruned = Some(std::time::Instant::now());
+            }
+        }
+    });
+}
+
+/// Send all deliveries that are due and record the results
+async fn process_due_deliveries(db: &Database, client: &reqwest::Client) {
+    let now = chrono::Utc::now().timestamp();
+    let deliveries = match db.get_due_webhook_deliveries(now, BATCH_SIZE) {
+        Ok(deliveries) => deliveries,
+        Err(e) => {
+            eprintln!("Warning: Failed to load webhook deliveries: {}", e);
+            return;
+        }
+    };
+
+    for delivery in deliveries {
+        let result = match db.get_webhook(delivery.webhook_id) {
+            Ok(Some(webhook)) if webhook.enabled => send(client, &webhook, &delivery).await,
+            Ok(Some(_)) => Err("Webhook is disabled".to_string()),
+            Ok(None) => Err("Webhook no longer exists".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let recorded = match result {
+            Ok(()) => db.record_webhook_attempt(delivery.id, DELIVERY_STATUS_DELIVERED, None, now),
+            Err(error) => {
+                let attempts = delivery.attempts + 1;
+                if attempts >= MAX_ATTEMPTS {
+                    db.record_webhook_attempt(delivery.id, DELIVERY_STATUS_FAILED, Some(&error), now)
+                } else {
+                    let backoff = (BASE_BACKOFF_SECS << (attempts - 1).min(16)).min(MAX_BACKOFF_SECS);
+                    db.record_webhook_attempt(delivery.id, DELIVERY_STATUS_PENDING, Some(&error), now + backoff)
+                }
+            }
+        };
+        if let Err(e) = recorded {
+            eprintln!("Warning: Failed to record webhook delivery {}: {}", delivery.id, e);
+        }
+    }
+}
+
+/// POST a single delivery
+async fn send(client: &reqwest::Client, webhook: &Webhook, delivery: &WebhookDelivery) -> Result<(), String> {
+    let mut request = client
+        .post(&webhook.url)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", concat!("time-tracker-app/", env!("CARGO_PKG_VERSION")))
+        .header("X-TimeTracker-Event", &delivery.event)
+        .header("X-TimeTracker-Delivery", delivery.id.to_string());
+
+    if let Some(secret) = webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header("X-TimeTracker-Signature", sign_payload(secret, &delivery.payload));
+    }
+
+    let response = request
+        .body(delivery.payload.clone())
+        .send()
+        .await
+        .map_err(|e| format!("Request failed: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Endpoint responded with HTTP {}", response.status()))
+    }
+}
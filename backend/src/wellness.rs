@@ -0,0 +1,177 @@
+//! Wellbeing statistics
+//!
+//! Aggregates tracked (non-idle, non-break) time into what a wellbeing view
+//! shows: screen time per local day, the longest stretches worked without a
+//! break, time worked in quiet hours (`quiet_hours_start` to
+//! `quiet_hours_end`, late at night by default) and on weekends, and
+//! micro-break compliance (see `micro_breaks`).
+
+use crate::database::{Database, MicroBreakStats};
+use crate::settings::AppSettings;
+use chrono::{Datelike, Local, TimeZone, Timelike, Weekday};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// A gap at least this long between activities ends a session
+pub const BREAK_GAP_SECS: i64 = 5 * 60;
+
+/// Sessions listed in `longest_sessions`
+const LONGEST_SESSIONS: usize = 5;
+
+/// Screen time of one local day
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WellnessDay {
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub weekend: bool,
+    pub screen_seconds: i64,
+    /// Time in quiet hours
+    pub late_night_seconds: i64,
+}
+
+/// Stretch of work without a break of `BREAK_GAP_SECS` or more
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkSession {
+    pub started_at: i64,
+    pub ended_at: i64,
+    /// Tracked time; short gaps inside the session are not counted
+    pub duration_sec: i64,
+}
+
+/// Response of `get_wellness_stats`
+#[derive(Debug, Clone, Serialize)]
+pub struct WellnessStats {
+    pub total_screen_seconds: i64,
+    pub late_night_seconds: i64,
+    pub weekend_seconds: i64,
+    /// Days with tracked time, oldest first
+    pub days: Vec<WellnessDay>,
+    /// Longest first
+    pub longest_sessions: Vec<WorkSession>,
+    pub micro_breaks: MicroBreakStats,
+}
+
+/// Local hours outside work (work-life boundaries)
+#[derive(Debug, Clone, Copy)]
+pub struct QuietHours {
+    /// First quiet hour, e.g. 22
+    pub start_hour: u32,
+    /// First working hour after it, e.g. 6; equal to `start_hour` for none
+    pub end_hour: u32,
+    /// Whether weekends are off as a whole
+    pub weekends: bool,
+}
+
+impl QuietHours {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            start_hour: settings.quiet_hours_start as u32,
+            end_hour: settings.quiet_hours_end as u32,
+            weekends: settings.quiet_weekends,
+        }
+    }
+
+    pub fn contains_hour(&self, hour: u32) -> bool {
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Time of `spans` per local day of `tz`, split at hour boundaries so time
+/// past midnight counts for the next day
+pub fn daily_totals<Tz: TimeZone>(spans: &[(i64, i64)], tz: &Tz, quiet: &QuietHours) -> (Vec<WellnessDay>, i64) {
+    let mut days: BTreeMap<String, WellnessDay> = BTreeMap::new();
+    let mut weekend_seconds = 0;
+    for &(start, end) in spans {
+        let mut t = start;
+        while t < end {
+            let Some(local) = tz.timestamp_opt(t, 0).single() else { break };
+            let next = end.min(t + 3600 - (local.minute() * 60 + local.second()) as i64);
+            let seconds = next - t;
+            let date = local.date_naive().format("%Y-%m-%d").to_string();
+            let weekend = matches!(local.weekday(), Weekday::Sat | Weekday::Sun);
+            let day = days.entry(date.clone()).or_insert_with(|| WellnessDay { date, weekend, ..Default::default() });
+            day.screen_seconds += seconds;
+            if quiet.contains_hour(local.hour()) {
+                day.late_night_seconds += seconds;
+            }
+            if weekend {
+                weekend_seconds += seconds;
+            }
+            t = next;
+        }
+    }
+    (days.into_values().collect(), weekend_seconds)
+}
+
+/// Sessions of `spans` (sorted by start), longest first
+pub fn sessions(spans: &[(i64, i64)]) -> Vec<WorkSession> {
+    let mut sessions: Vec<WorkSession> = Vec::new();
+    for &(start, end) in spans {
+        match sessions.last_mut() {
+            Some(session) if start - session.ended_at < BREAK_GAP_SECS => {
+                // Overlapping activities (several sources) are counted once
+                session.duration_sec += end.max(session.ended_at) - start.max(session.ended_at);
+                session.ended_at = session.ended_at.max(end);
+            }
+            _ => sessions.push(WorkSession { started_at: start, ended_at: end, duration_sec: end - start }),
+        }
+    }
+    sessions.sort_by(|a, b| b.duration_sec.cmp(&a.duration_sec).then(a.started_at.cmp(&b.started_at)));
+    sessions
+}
+
+/// Wellbeing stats for a time range, with days in local time
+pub fn get_wellness_stats(db: &Database, start: i64, end: i64) -> rusqlite::Result<WellnessStats> {
+    let spans = db.get_active_spans(start, end)?;
+    let quiet = QuietHours::from_settings(&crate::settings::load(db));
+    let (days, weekend_seconds) = daily_totals(&spans, &Local, &quiet);
+    let mut longest_sessions = sessions(&spans);
+    longest_sessions.truncate(LONGEST_SESSIONS);
+    Ok(WellnessStats {
+        total_screen_seconds: days.iter().map(|d| d.screen_seconds).sum(),
+        late_night_seconds: days.iter().map(|d| d.late_night_seconds).sum(),
+        weekend_seconds,
+        days,
+        longest_sessions,
+        micro_breaks: db.get_micro_break_stats(start, end)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    /// Friday 2024-03-01 00:00 UTC
+    const FRIDAY: i64 = 1_709_251_200;
+
+    #[test]
+    fn late_night_time_counts_for_the_day_it_falls_on() {
+        // Friday 21:30 to Saturday 01:00, then Saturday 10:00 to 10:30
+        let spans = [(FRIDAY + 21 * 3600 + 1800, FRIDAY + 25 * 3600), (FRIDAY + 34 * 3600, FRIDAY + 34 * 3600 + 1800)];
+        let quiet = QuietHours { start_hour: 22, end_hour: 6, weekends: true };
+        let (days, weekend) = daily_totals(&spans, &Utc, &quiet);
+        assert_eq!(
+            days,
+            vec![
+                WellnessDay { date: "2024-03-01".into(), weekend: false, screen_seconds: 9000, late_night_seconds: 7200 },
+                WellnessDay { date: "2024-03-02".into(), weekend: true, screen_seconds: 5400, late_night_seconds: 3600 },
+            ]
+        );
+        assert!(!QuietHours { start_hour: 9, end_hour: 9, weekends: false }.contains_hour(9));
+        assert!(QuietHours { start_hour: 0, end_hour: 7, weekends: false }.contains_hour(6));
+        assert_eq!(weekend, 5400);
+    }
+
+    #[test]
+    fn short_gaps_do_not_end_a_session() {
+        let spans = [(0, 600), (700, 1300), (1200, 1500), (1500 + BREAK_GAP_SECS, 2000 + BREAK_GAP_SECS)];
+        let found = sessions(&spans);
+        assert_eq!(found[0], WorkSession { started_at: 0, ended_at: 1500, duration_sec: 1400 });
+        assert_eq!(found[1].duration_sec, 500);
+    }
+}
@@ -0,0 +1,63 @@
+//! Workspaces: separate database files selectable at runtime
+//!
+//! The active workspace and recently used ones are stored in
+//! `<data dir>/workspace.json`, outside any database, so the app reopens the
+//! last workspace on start. Everything stored in the database (activities,
+//! categories, rules, settings, installed plugins) is per workspace.
+
+use std::path::{Path, PathBuf};
+
+const CONFIG_FILE: &str = "workspace.json";
+const DEFAULT_DB_FILE: &str = "data.db";
+
+/// Recent workspaces kept in the list
+const MAX_RECENT: usize = 10;
+
+/// Active and recently used workspace database paths
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct WorkspaceConfig {
+    pub active: Option<PathBuf>,
+    #[serde(default)]
+    pub recent: Vec<PathBuf>,
+}
+
+/// Application data directory
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("timetracker")
+}
+
+/// Path of the default workspace database
+pub fn default_db_path() -> PathBuf {
+    data_dir().join(DEFAULT_DB_FILE)
+}
+
+/// Load the workspace config (empty if missing or unreadable)
+pub fn load_config() -> WorkspaceConfig {
+    std::fs::read_to_string(data_dir().join(CONFIG_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+/// Database path of the active workspace
+pub fn active_db_path() -> PathBuf {
+    load_config().active.unwrap_or_else(default_db_path)
+}
+
+/// Record `path` as the active workspace
+pub fn set_active(path: &Path) -> Result<WorkspaceConfig, String> {
+    let mut config = load_config();
+    let previous = config.active.take().unwrap_or_else(default_db_path);
+    config.recent.retain(|p| p != path && *p != previous);
+    config.recent.insert(0, previous);
+    config.recent.truncate(MAX_RECENT);
+    config.active = Some(path.to_path_buf());
+
+    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(data_dir()).map_err(|e| e.to_string())?;
+    std::fs::write(data_dir().join(CONFIG_FILE), json)
+        .map_err(|e| format!("Failed to save workspace config: {}", e))?;
+    Ok(config)
+}
@@ -12,18 +12,20 @@ import History from './components/History/History';
 import { Reports } from './components/Reports';
 import Settings from './components/Settings/Settings';
 import { Marketplace } from './components/Marketplace';
-import IdlePrompt from './components/IdlePrompt/IdlePrompt';
+import FocusOverlay from './components/FocusOverlay/FocusOverlay';
+import CheckinPrompt from './components/CheckinPrompt/CheckinPrompt';
+import UpdateNotice from './components/UpdateNotice/UpdateNotice';
 import ManualEntryModal from './components/ManualEntry/ManualEntryModal';
-import type { ManualEntry } from './types';
+import type { ManualEntry, UpdateInfo } from './types';
 import type { PluginRoute } from './types/pluginFrontend';
 
 function App() {
   const [currentView, setCurrentView] = useState<View>('dashboard');
-  const [showIdlePrompt, setShowIdlePrompt] = useState(false);
-  const [idleDuration, setIdleDuration] = useState(0);
-  const [idleStartedAt, setIdleStartedAt] = useState<number>(0);
   const [showManualEntry, setShowManualEntry] = useState(false);
   const [editingEntry, setEditingEntry] = useState<ManualEntry | null>(null);
+  const [blockedApp, setBlockedApp] = useState<string | null>(null);
+  const [showCheckin, setShowCheckin] = useState(false);
+  const [availableUpdate, setAvailableUpdate] = useState<UpdateInfo | null>(null);
 
   const { isLoading: settingsLoading } = useSettings();
   const { refetch: refetchActivities } = useActivities();
@@ -47,6 +49,27 @@ function App() {
     }
   }, [settingsLoading]);
 
+  // Report a database recovery that happened during startup
+  useEffect(() => {
+    const reportRecovery = async () => {
+      try {
+        const { diagnosticsApi } = await import('./services/api/diagnostics');
+        const recovery = await diagnosticsApi.getStartupDiagnostics();
+        if (!recovery) return;
+        const { showError, showInfo } = await import('./utils/toast');
+        if (recovery.status === 'recovered') {
+          const rows = recovery.tables.reduce((sum, t) => sum + t.rows, 0);
+          showInfo(`The database was damaged and has been repaired (${rows} records recovered).`);
+        } else {
+          showError('The database was damaged and could not be recovered; a new one was created.');
+        }
+      } catch {
+        // Running in browser without Tauri - nothing to report
+      }
+    };
+    reportRecovery();
+  }, []);
+
   // Apply dark mode theme on mount and when it changes
   useEffect(() => {
     const htmlElement = document.documentElement;
@@ -59,11 +82,6 @@ function App() {
 
   // Listen for Tauri events
   useTauriEvents({
-    onIdleReturn: (durationMinutes, startedAt) => {
-      setIdleDuration(durationMinutes);
-      setIdleStartedAt(startedAt);
-      setShowIdlePrompt(true);
-    },
     onActivityUpdate: () => {
       refetchActivities();
     },
@@ -76,6 +94,22 @@ function App() {
     onStartThinkingMode: () => {
       refetchActivities();
     },
+    onFocusBlocked: (appName) => {
+      setBlockedApp(appName);
+    },
+    onCheckinPrompt: () => {
+      setShowCheckin(true);
+    },
+    // The idle prompt lives in its own window; its settings link lands here
+    onOpenIdleSettings: () => {
+      const { setScrollToIdlePromptThreshold, setSettingsActiveTab } = useStore.getState();
+      setSettingsActiveTab('general');
+      setScrollToIdlePromptThreshold(true);
+      setCurrentView('settings');
+    },
+    onUpdateAvailable: (info) => {
+      setAvailableUpdate(info);
+    },
   });
 
   // Handle keyboard shortcuts
@@ -88,27 +122,15 @@ function App() {
     },
   });
 
-  const handleIdleSubmit = async (categoryId: number, comment?: string) => {
-    // Update existing idle activity with category and description
+  const handleCheckinSubmit = async (focused: boolean, energy?: number) => {
     try {
       const { invoke } = await import('@tauri-apps/api/tauri');
-      await invoke('submit_idle_activity', {
-        categoryId: categoryId,
-        comment: comment || null,
-        startedAt: idleStartedAt,
-      });
-      const { showSuccess } = await import('./utils/toast');
-      showSuccess('Idle activity updated');
-      refetchActivities();
+      await invoke('submit_checkin', { request: { focused, energy: energy ?? null } });
     } catch (error) {
       const { handleApiError } = await import('./utils/toast');
-      handleApiError(error, 'Failed to update idle activity');
+      handleApiError(error, 'Failed to save check-in');
     }
-    setShowIdlePrompt(false);
-  };
-
-  const handleIdleSkip = () => {
-    setShowIdlePrompt(false);
+    setShowCheckin(false);
   };
 
   const handleManualEntrySubmit = async (entry: {
@@ -125,19 +147,24 @@ function App() {
     try {
       if (editingEntry) {
         await invoke('update_manual_entry', {
-          id: editingEntry.id,
-          description: entry.description,
-          categoryId: entry.categoryId,
-          startedAt: startedAtSec,
-          endedAt: endedAtSec,
+          request: {
+            id: editingEntry.id,
+            description: entry.description,
+            category_id: entry.categoryId,
+            started_at: startedAtSec,
+            ended_at: endedAtSec,
+            billable_override: editingEntry.billable_override ?? null,
+          },
         });
         showSuccess('Manual entry updated');
       } else {
         await invoke('add_manual_entry', {
-          description: entry.description,
-          categoryId: entry.categoryId,
-          startedAt: startedAtSec,
-          endedAt: endedAtSec,
+          request: {
+            description: entry.description,
+            category_id: entry.categoryId,
+            started_at: startedAtSec,
+            ended_at: endedAtSec,
+          },
         });
         showSuccess('Manual entry added');
       }
@@ -235,20 +262,19 @@ function App() {
         {renderView()}
       </Layout>
 
-      {/* Idle Prompt Modal */}
-      {showIdlePrompt && (
-        <IdlePrompt
-          durationMinutes={idleDuration}
-          onSubmit={handleIdleSubmit}
-          onSkip={handleIdleSkip}
-          onNavigateToSettings={() => {
-            const { setScrollToIdlePromptThreshold, setSettingsActiveTab } = useStore.getState();
-            setSettingsActiveTab('general');
-            setScrollToIdlePromptThreshold(true);
-            setCurrentView('settings');
-            setShowIdlePrompt(false);
-          }}
-        />
+      {/* Focus-mode overlay for blocked apps */}
+      {blockedApp && (
+        <FocusOverlay appName={blockedApp} onDismiss={() => setBlockedApp(null)} />
+      )}
+
+      {/* Productivity check-in */}
+      {showCheckin && (
+        <CheckinPrompt onSubmit={handleCheckinSubmit} onSkip={() => setShowCheckin(false)} />
+      )}
+
+      {/* New release notice */}
+      {availableUpdate && (
+        <UpdateNotice update={availableUpdate} onClose={() => setAvailableUpdate(null)} />
       )}
 
       {/* Manual Entry Modal */}
@@ -3,6 +3,7 @@ import { useCategories, useCreateCategory, useUpdateCategory, useDeleteCategory
 import Card from '../Common/Card';
 import Button from '../Common/Button';
 import { Category } from '../../types';
+import { categoriesApi } from '../../services/api/categories';
 import { Plus, Check, X, Edit2, Trash2, Tag } from 'lucide-react';
 
 export const Categories: React.FC = () => {
@@ -81,7 +82,10 @@ export const Categories: React.FC = () => {
             Categories
           </h2>
         </div>
-        <Button onClick={() => setNewCategory({ name: '', color: '#888888', icon: '📁', is_productive: true, is_system: false, is_pinned: false })}>
+        <Button onClick={async () => {
+          const color = await categoriesApi.suggestCategoryColor().catch(() => '#888888');
+          setNewCategory({ name: '', color, icon: '📁', is_productive: true, is_system: false, is_pinned: false });
+        }}>
           <Plus className="w-4 h-4 mr-1" />
           Add Category
         </Button>
@@ -0,0 +1,64 @@
+import React, { useState } from 'react';
+import Button from '../Common/Button';
+
+interface CheckinPromptProps {
+  onSubmit: (focused: boolean, energy?: number) => Promise<void>;
+  onSkip: () => void;
+}
+
+const ENERGY_LEVELS = [1, 2, 3, 4, 5];
+
+const CheckinPrompt: React.FC<CheckinPromptProps> = ({ onSubmit, onSkip }) => {
+  const [energy, setEnergy] = useState<number | null>(null);
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50 backdrop-blur-sm" onClick={onSkip} />
+
+      {/* Modal */}
+      <div className="relative bg-white dark:bg-gray-800 rounded-2xl shadow-2xl p-6 w-full max-w-sm mx-4 animate-scale-in text-center">
+        <h2 className="text-xl font-semibold text-gray-900 dark:text-white mb-4">
+          Quick check-in
+        </h2>
+
+        {/* Energy (optional) */}
+        <p className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">Energy</p>
+        <div className="flex justify-center gap-2 mb-6">
+          {ENERGY_LEVELS.map((level) => (
+            <button
+              key={level}
+              onClick={() => setEnergy(energy === level ? null : level)}
+              className={`w-10 h-10 rounded-full border-2 text-sm font-medium transition-all ${
+                energy === level
+                  ? 'border-primary-500 bg-primary-50 dark:bg-primary-900/20 text-primary-700 dark:text-primary-300'
+                  : 'border-gray-200 dark:border-gray-700 text-gray-700 dark:text-gray-300 hover:border-gray-300 dark:hover:border-gray-600'
+              }`}
+            >
+              {level}
+            </button>
+          ))}
+        </div>
+
+        {/* One tap answers and closes */}
+        <p className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">Focused right now?</p>
+        <div className="flex gap-3">
+          <Button variant="success" className="flex-1" onClick={() => onSubmit(true, energy ?? undefined)}>
+            Yes
+          </Button>
+          <Button variant="secondary" className="flex-1" onClick={() => onSubmit(false, energy ?? undefined)}>
+            No
+          </Button>
+        </div>
+        <button
+          onClick={onSkip}
+          className="mt-4 text-xs text-gray-500 dark:text-gray-400 hover:underline"
+        >
+          Skip
+        </button>
+      </div>
+    </div>
+  );
+};
+
+export default CheckinPrompt;
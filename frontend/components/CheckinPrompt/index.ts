@@ -0,0 +1 @@
+export { default as CheckinPrompt } from './CheckinPrompt';
@@ -3,6 +3,8 @@ import { LineChart, Line, XAxis, YAxis, CartesianGrid, Tooltip, Legend, Responsi
 import { useStore } from '../../store';
 import { useActivities } from '../../hooks';
 import { useCategories } from '../../hooks/useCategories';
+import { useDomainRatings } from '../../hooks/useDomainRatings';
+import { activityProductivity } from '../../utils/categories';
 import LoadingSpinner from '../Common/LoadingSpinner';
 import { differenceInCalendarDays, startOfDay, endOfDay, startOfWeek, endOfWeek, startOfMonth, endOfMonth } from 'date-fns';
 import type { Category } from '../../types';
@@ -27,6 +29,7 @@ export default function DailyTrend() {
   
   const { data: activities = [], isLoading: activitiesLoading } = useActivities();
   const { data: allCategories = [], isLoading: categoriesLoading } = useCategories();
+  const { data: domainRatings = [] } = useDomainRatings();
   
   const { start, end } = useMemo(() => {
     const now = new Date();
@@ -101,10 +104,11 @@ export default function DailyTrend() {
         const category = allCategories.find((c: Category) => c.id === activity.category_id);
         const duration = activity.duration_sec;
         
+        const productivity = activityProductivity(activity, category, domainRatings);
         days[dateKey].total += duration;
-        if (category?.is_productive === true) {
+        if (productivity === true) {
           days[dateKey].productive += duration;
-        } else if (category?.is_productive === false) {
+        } else if (productivity === false) {
           days[dateKey].unproductive += duration;
         }
       }
@@ -117,7 +121,7 @@ export default function DailyTrend() {
       unproductive: Math.round((stats.unproductive / 3600) * 10) / 10,
       total: Math.round((stats.total / 3600) * 10) / 10,
     }));
-  }, [activities, allCategories, start, end, daysDiff]);
+  }, [activities, allCategories, domainRatings, start, end, daysDiff]);
 
   if (isSingleDay) {
     return null;
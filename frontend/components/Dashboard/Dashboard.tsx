@@ -1,5 +1,5 @@
 import { useMemo } from 'react';
-import { useDailyStats, useStatsForRange, useCategories, useTrackerStatus, useActivities } from '../../hooks';
+import { useDailyStats, useDashboard, useStatsForRange, useCategories, useTrackerStatus, useActivities, useTimeline } from '../../hooks';
 import { useStore } from '../../store';
 import { usePluginFrontend } from '../../hooks/usePluginFrontend';
 import { startOfDay, endOfDay, startOfWeek, endOfWeek, startOfMonth, endOfMonth } from 'date-fns';
@@ -12,7 +12,7 @@ import DailyTrend from './DailyTrend';
 import TopWebsites from './TopWebsites';
 import { SkeletonCard } from '../Common/SkeletonLoader';
 import { ErrorBoundary } from '../Common/ErrorBoundary';
-import type { TimelineBlock, CategoryStats, AppStats, Category } from '../../types';
+import type { CategoryStats, AppStats, Category } from '../../types';
 import type { PluginDashboardWidget } from '../../types/pluginFrontend';
 
 export default function Dashboard() {
@@ -21,9 +21,8 @@ export default function Dashboard() {
   useTrackerStatus();
   const { dashboardWidgets: pluginWidgets } = usePluginFrontend();
   
-  // Get date range and categories from store to ensure consistency with activities
+  // Get date range from store to ensure consistency with activities
   // Use selector to prevent re-renders from function reference changes
-  const categories = useStore((state) => state.categories);
   const dateRangePreset = useStore((state) => state.dateRangePreset);
   const customStartTimestamp = useStore((state) => 
     state.dateRangePreset === 'custom' 
@@ -84,6 +83,7 @@ export default function Dashboard() {
   
   const { data: stats, isLoading: statsLoading, error: statsError } = useDailyStats(isSingleDay ? statsDate : undefined);
   const { data: rangeStats, isLoading: rangeStatsLoading, error: rangeStatsError } = useStatsForRange(dateRange);
+  const { data: dashboard } = useDashboard(isSingleDay ? statsDate : undefined);
   const { data: activities, isLoading: activitiesLoading, error: activitiesError } = useActivities();
 
   // Build displayStats from API only (single-day: get_daily_stats; multi-day: get_stats)
@@ -146,27 +146,8 @@ export default function Dashboard() {
     return appsWithoutCategory.size;
   }, [activities]);
 
-  // Build timeline from activities with category info
-  const timelineBlocks: TimelineBlock[] = useMemo(() => {
-    if (!activities || !categories) return [];
-    
-    return activities.map((activity) => {
-      // Find category for this activity
-      const category = activity.category_id 
-        ? categories.find(c => c.id === activity.category_id) || null
-        : null;
-      
-      return {
-        start: activity.started_at * 1000, // Convert to milliseconds
-        end: (activity.started_at + activity.duration_sec) * 1000,
-        app_name: activity.app_name,
-        domain: activity.domain,
-        category: category,
-        is_idle: activity.is_idle,
-        is_manual: false,
-      };
-    });
-  }, [activities, categories]);
+  // Timeline blocks are merged on the backend
+  const { data: timelineBlocks } = useTimeline({ start, end });
 
 
   // Show loading only if we're loading stats (for current range type) and activities, and have no data yet
@@ -227,6 +208,7 @@ export default function Dashboard() {
         <StatsOverview
           totalDuration={displayStats.total_duration_sec}
           productiveDuration={displayStats.productive_duration_sec}
+          kpis={isSingleDay ? dashboard?.kpis : undefined}
         />
         {(displayStats.total_duration_sec > 0 || (activities && activities.length > 0)) && (
           <div className="lg:col-span-2 h-full">
@@ -1,15 +1,46 @@
-import { Clock, TrendingUp, Target } from 'lucide-react';
+import { Clock, TrendingUp, Target, BarChart3 } from 'lucide-react';
 import { formatDuration, calculatePercentage } from '../../utils';
+import type { DashboardKpi } from '../../types';
 
 interface StatsOverviewProps {
   totalDuration: number;
   productiveDuration: number;
+  /** Server-computed KPI cards (single day); the built-in cards are shown without them */
+  kpis?: DashboardKpi[];
 }
 
-export default function StatsOverview({ totalDuration, productiveDuration }: StatsOverviewProps) {
+const percentColors = (percent: number) => ({
+  color: percent >= 70 ? 'text-green-600' : percent >= 50 ? 'text-yellow-600' : 'text-red-600',
+  bgColor: percent >= 70 ? 'bg-green-100 dark:bg-green-900/20' : percent >= 50 ? 'bg-yellow-100 dark:bg-yellow-900/20' : 'bg-red-100 dark:bg-red-900/20',
+});
+
+const formatKpiValue = (kpi: DashboardKpi): string => {
+  switch (kpi.unit) {
+    case 'seconds':
+      return formatDuration(Number(kpi.value));
+    case 'percent':
+      return `${Math.round(Number(kpi.value))}%`;
+    default:
+      return String(kpi.value);
+  }
+};
+
+const kpiToStat = (kpi: DashboardKpi) => {
+  const base = { label: kpi.label, value: formatKpiValue(kpi) };
+  switch (kpi.unit) {
+    case 'seconds':
+      return { ...base, icon: Clock, color: 'text-primary-600', bgColor: 'bg-primary-100 dark:bg-primary-900/20' };
+    case 'percent':
+      return { ...base, icon: Target, ...percentColors(Number(kpi.value)) };
+    default:
+      return { ...base, icon: BarChart3, color: 'text-primary-600', bgColor: 'bg-primary-100 dark:bg-primary-900/20' };
+  }
+};
+
+export default function StatsOverview({ totalDuration, productiveDuration, kpis }: StatsOverviewProps) {
   const productivePercentage = calculatePercentage(productiveDuration, totalDuration);
 
-  const stats = [
+  const defaultStats = [
     {
       label: 'Total Time',
       value: formatDuration(totalDuration),
@@ -28,10 +59,10 @@ export default function StatsOverview({ totalDuration, productiveDuration }: Sta
       label: 'Productivity',
       value: `${productivePercentage}%`,
       icon: Target,
-      color: productivePercentage >= 70 ? 'text-green-600' : productivePercentage >= 50 ? 'text-yellow-600' : 'text-red-600',
-      bgColor: productivePercentage >= 70 ? 'bg-green-100 dark:bg-green-900/20' : productivePercentage >= 50 ? 'bg-yellow-100 dark:bg-yellow-900/20' : 'bg-red-100 dark:bg-red-900/20',
+      ...percentColors(productivePercentage),
     },
   ];
+  const stats = kpis && kpis.length > 0 ? kpis.map(kpiToStat) : defaultStats;
 
   return (
     <div className="bg-white dark:bg-gray-800 rounded-xl shadow-sm border border-gray-200 dark:border-gray-700 p-6 transition-all duration-200 hover:shadow-md hover:shadow-gray-200/50 dark:hover:shadow-gray-900/50 h-full flex flex-col">
@@ -4,7 +4,8 @@ import { Globe } from 'lucide-react';
 import { domainsApi } from '../../services/api/domains';
 import { activitiesApi } from '../../services/api/activities';
 import { useStore } from '../../store';
-import type { Activity } from '../../types';
+import { useDomainRatings, useSetDomainRating } from '../../hooks/useDomainRatings';
+import type { Activity, DomainRatingValue } from '../../types';
 import { startOfDay, endOfDay, startOfWeek, endOfWeek, startOfMonth, endOfMonth } from 'date-fns';
 import { formatDuration, calculatePercentage } from '../../utils/format';
 import Card from '../Common/Card';
@@ -76,6 +77,9 @@ export default function TopWebsites() {
       .reduce((sum: number, a: Activity) => sum + a.duration_sec, 0);
   }, [activities]);
 
+  const { data: domainRatings = [] } = useDomainRatings();
+  const setDomainRating = useSetDomainRating();
+
   const isLoading = domainsLoading || activitiesLoading;
 
   if (isLoading) {
@@ -115,6 +119,7 @@ export default function TopWebsites() {
               .replace(/^https?:\/\//, '')
               .replace(/^www\./, '')
               .split('/')[0]; // Remove path
+            const rating = domainRatings.find((r) => r.domain === displayDomain)?.rating;
 
             return (
               <div key={domain.domain} className="relative group">
@@ -127,6 +132,24 @@ export default function TopWebsites() {
                     <span className="font-semibold text-gray-900 dark:text-white truncate">
                       {displayDomain}
                     </span>
+                    <select
+                      value={rating ?? ''}
+                      onChange={(e) =>
+                        setDomainRating.mutate({
+                          domain: displayDomain,
+                          rating: (e.target.value || null) as DomainRatingValue | null,
+                        })
+                      }
+                      title="Productivity when the category is neutral"
+                      className={`text-xs bg-transparent text-gray-500 dark:text-gray-400 border-none focus:ring-0 cursor-pointer ${
+                        rating ? '' : 'opacity-0 group-hover:opacity-100 focus:opacity-100'
+                      }`}
+                    >
+                      <option value="">Not rated</option>
+                      <option value="productive">✓ Productive</option>
+                      <option value="neutral">○ Neutral</option>
+                      <option value="distracting">✗ Distracting</option>
+                    </select>
                   </div>
                   <div className="text-right flex-shrink-0 ml-4">
                     <span className="font-bold text-gray-900 dark:text-white text-sm">
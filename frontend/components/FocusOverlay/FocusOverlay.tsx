@@ -0,0 +1,31 @@
+import React from 'react';
+import Button from '../Common/Button';
+
+interface FocusOverlayProps {
+  appName: string;
+  onDismiss: () => void;
+}
+
+const FocusOverlay: React.FC<FocusOverlayProps> = ({ appName, onDismiss }) => {
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/60 backdrop-blur-sm" />
+
+      {/* Modal */}
+      <div className="relative bg-white dark:bg-gray-800 rounded-2xl shadow-2xl p-6 w-full max-w-md mx-4 animate-scale-in text-center">
+        <div className="text-4xl mb-2">🍅</div>
+        <h2 className="text-xl font-semibold text-gray-900 dark:text-white">
+          Stay focused
+        </h2>
+        <p className="text-gray-600 dark:text-gray-400 mt-1 mb-6">
+          <span className="font-medium text-primary-700 dark:text-primary-300">{appName}</span> is blocked
+          until the current work session ends.
+        </p>
+        <Button onClick={onDismiss}>Back to work</Button>
+      </div>
+    </div>
+  );
+};
+
+export default FocusOverlay;
@@ -0,0 +1 @@
+export { default as FocusOverlay } from './FocusOverlay';
@@ -61,6 +61,7 @@ export function useHistoryItems({
         started_at: virtualActivityStartTimeRef.current,
         duration_sec: 5,
         is_idle: false,
+        source: 'tracker',
       } as Activity
     ] : sortedActivities;
     
@@ -101,6 +102,7 @@ export function useHistoryItems({
         started_at: virtualActivityStartTimeRef.current,
         duration_sec: 5,
         is_idle: false,
+        source: 'tracker',
       } as Activity
     ] : sortedActivities;
 
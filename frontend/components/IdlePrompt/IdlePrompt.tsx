@@ -2,26 +2,76 @@ import React, { useState } from 'react';
 import { formatDuration } from '../../utils/format';
 import Button from '../Common/Button';
 import { usePinnedCategories } from '../../hooks/useCategories';
-import { Settings } from 'lucide-react';
-import type { Category } from '../../types';
+import { Settings, Scissors, X } from 'lucide-react';
+import type { Category, IdleChunk } from '../../types';
+
+interface IdlePart {
+  categoryId: number | null;
+  minutes: string;
+  comment: string;
+}
 
 interface IdlePromptProps {
   durationMinutes: number;
-  onSubmit: (categoryId: number, comment?: string) => Promise<void>;
+  onSubmit: (chunks: IdleChunk[]) => Promise<void>;
   onSkip: () => void;
   onNavigateToSettings?: () => void;
+  /** 'window' fills its own prompt window instead of overlaying the app */
+  variant?: 'modal' | 'window';
 }
 
-const IdlePrompt: React.FC<IdlePromptProps> = ({ durationMinutes, onSubmit, onSkip, onNavigateToSettings }) => {
+const IdlePrompt: React.FC<IdlePromptProps> = ({
+  durationMinutes,
+  onSubmit,
+  onSkip,
+  onNavigateToSettings,
+  variant = 'modal',
+}) => {
   const { data: pinnedCategories = [] } = usePinnedCategories();
   const [selectedCategoryId, setSelectedCategoryId] = useState<number | null>(null);
   const [description, setDescription] = useState('');
+  // Set when the period is split into consecutive parts ("30m meeting + 15m break")
+  const [parts, setParts] = useState<IdlePart[] | null>(null);
   
   const idleDuration = durationMinutes;
+
+  // Every part but the last needs minutes; the last one covers the rest
+  const splitMinutes = (parts ?? []).slice(0, -1).reduce((sum, part) => sum + (Number(part.minutes) || 0), 0);
+  const splitValid = parts !== null
+    && parts.every((part) => part.categoryId !== null)
+    && parts.slice(0, -1).every((part) => Number(part.minutes) > 0)
+    && splitMinutes < idleDuration;
+  const canSave = parts ? splitValid : !!selectedCategoryId;
+
+  const startSplit = () => {
+    setParts([
+      { categoryId: selectedCategoryId, minutes: '', comment: description },
+      { categoryId: null, minutes: '', comment: '' },
+    ]);
+  };
+
+  const updatePart = (index: number, changes: Partial<IdlePart>) => {
+    setParts((current) => current && current.map((part, i) => (i === index ? { ...part, ...changes } : part)));
+  };
+
+  const removePart = (index: number) => {
+    setParts((current) => {
+      if (!current) return current;
+      const remaining = current.filter((_, i) => i !== index);
+      return remaining.length > 1 ? remaining : null;
+    });
+  };
   
   const handleSave = async () => {
-    if (selectedCategoryId) {
-      await onSubmit(selectedCategoryId, description || undefined);
+    if (parts) {
+      if (!splitValid) return;
+      await onSubmit(parts.map((part, i) => ({
+        category_id: part.categoryId as number,
+        comment: part.comment || null,
+        duration_sec: i < parts.length - 1 ? Number(part.minutes) * 60 : null,
+      })));
+    } else if (selectedCategoryId) {
+      await onSubmit([{ category_id: selectedCategoryId, comment: description || null }]);
     }
   };
   
@@ -39,13 +89,20 @@ const IdlePrompt: React.FC<IdlePromptProps> = ({ durationMinutes, onSubmit, onSk
   return (
     <div className="fixed inset-0 z-50 flex items-center justify-center">
       {/* Backdrop */}
-      <div 
-        className="absolute inset-0 bg-black/50 backdrop-blur-sm"
-        onClick={handleSkip}
-      />
+      {variant === 'modal' && (
+        <div 
+          className="absolute inset-0 bg-black/50 backdrop-blur-sm"
+          onClick={handleSkip}
+        />
+      )}
       
       {/* Modal */}
-      <div className="relative bg-white dark:bg-gray-800 rounded-2xl shadow-2xl p-6 w-full max-w-md mx-4 animate-scale-in">
+      <div
+        data-tauri-drag-region={variant === 'window' ? true : undefined}
+        className={variant === 'window'
+          ? 'relative bg-white dark:bg-gray-800 p-6 w-full h-full overflow-y-auto'
+          : 'relative bg-white dark:bg-gray-800 rounded-2xl shadow-2xl p-6 w-full max-w-md mx-4 animate-scale-in'}
+      >
         {/* Header */}
         <div className="text-center mb-6">
           <div className="text-4xl mb-2">👋</div>
@@ -57,7 +114,67 @@ const IdlePrompt: React.FC<IdlePromptProps> = ({ durationMinutes, onSubmit, onSk
           </p>
         </div>
         
+        {/* Split parts */}
+        {parts && (
+          <div className="mb-6 space-y-2 animate-fade-in">
+            <p className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-1 text-center">
+              Split your time away
+            </p>
+            {parts.map((part, index) => (
+              <div key={index} className="flex items-center gap-2">
+                <select
+                  value={part.categoryId ?? ''}
+                  onChange={(e) => updatePart(index, { categoryId: e.target.value ? Number(e.target.value) : null })}
+                  className="flex-1 min-w-0 px-2 py-1.5 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                >
+                  <option value="">Category…</option>
+                  {pinnedCategories.map((category: Category) => (
+                    <option key={category.id} value={category.id}>
+                      {category.icon} {category.name}
+                    </option>
+                  ))}
+                </select>
+                {index < parts.length - 1 ? (
+                  <input
+                    type="number"
+                    min={1}
+                    value={part.minutes}
+                    onChange={(e) => updatePart(index, { minutes: e.target.value })}
+                    placeholder="min"
+                    className="w-16 px-2 py-1.5 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                  />
+                ) : (
+                  <span className="w-16 text-xs text-center text-gray-500 dark:text-gray-400">
+                    {Math.max(idleDuration - splitMinutes, 0)}m
+                  </span>
+                )}
+                <input
+                  type="text"
+                  value={part.comment}
+                  onChange={(e) => updatePart(index, { comment: e.target.value })}
+                  placeholder="Comment"
+                  className="flex-1 min-w-0 px-2 py-1.5 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+                />
+                <button
+                  onClick={() => removePart(index)}
+                  className="p-1 text-gray-400 hover:text-red-500"
+                  aria-label="Remove part"
+                >
+                  <X className="w-4 h-4" />
+                </button>
+              </div>
+            ))}
+            <button
+              onClick={() => setParts([...parts, { categoryId: null, minutes: '', comment: '' }])}
+              className="text-xs font-medium text-primary-600 dark:text-primary-400 hover:underline"
+            >
+              + Add part
+            </button>
+          </div>
+        )}
+
         {/* Activity Options */}
+        {!parts && (
         <div className="mb-6">
           <p className="text-sm font-medium text-gray-700 dark:text-gray-300 mb-3 text-center">
             What were you doing?
@@ -86,10 +203,18 @@ const IdlePrompt: React.FC<IdlePromptProps> = ({ durationMinutes, onSubmit, onSk
               </button>
             ))}
           </div>
+          <button
+            onClick={startSplit}
+            className="mt-3 w-full flex items-center justify-center gap-1.5 text-xs font-medium text-gray-500 dark:text-gray-400 hover:text-primary-600 dark:hover:text-primary-400"
+          >
+            <Scissors className="w-3.5 h-3.5" />
+            I did more than one thing
+          </button>
         </div>
+        )}
         
         {/* Description Input */}
-        {selectedCategoryId && (
+        {!parts && selectedCategoryId && (
           <div className="mb-6 animate-fade-in">
             <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-2">
               Comment (optional)
@@ -120,7 +245,7 @@ const IdlePrompt: React.FC<IdlePromptProps> = ({ durationMinutes, onSubmit, onSk
             variant="primary"
             className="flex-1"
             onClick={handleSave}
-            disabled={!selectedCategoryId}
+            disabled={!canSave}
           >
             Save
           </Button>
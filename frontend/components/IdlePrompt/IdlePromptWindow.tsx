@@ -0,0 +1,85 @@
+import React, { useEffect, useState } from 'react';
+import { emit, listen } from '@tauri-apps/api/event';
+import { appWindow } from '@tauri-apps/api/window';
+import { idleApi } from '../../services/api/idle';
+import { windowApi } from '../../services/api/window';
+import { useStore } from '../../store';
+import { handleApiError } from '../../utils/toast';
+import IdlePrompt from './IdlePrompt';
+import type { IdleChunk } from '../../types';
+
+interface IdlePeriod {
+  durationMinutes: number;
+  startedAt: number;
+}
+
+// The backend opens this window with the idle period in the query string
+const initialPeriod = (): IdlePeriod => {
+  const params = new URLSearchParams(window.location.search);
+  return {
+    durationMinutes: Number(params.get('duration_minutes')) || 0,
+    startedAt: Number(params.get('started_at')) || 0,
+  };
+};
+
+/** Idle-return prompt shown in its own small window near the tray */
+const IdlePromptWindow: React.FC = () => {
+  const [period, setPeriod] = useState<IdlePeriod>(initialPeriod);
+  const darkMode = useStore((state) => state.settings.darkMode);
+
+  useEffect(() => {
+    document.documentElement.classList.toggle('dark', !!darkMode);
+  }, [darkMode]);
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    // A newer idle period arrived while the prompt was still open
+    listen<{ duration_minutes: number; started_at: number }>('idle-return', (event) => {
+      setPeriod({ durationMinutes: event.payload.duration_minutes, startedAt: event.payload.started_at });
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch(() => {});
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  const close = () => {
+    appWindow.close().catch(() => {});
+  };
+
+  const handleSkip = () => {
+    idleApi.dismissIdlePrompt().catch(() => {});
+    close();
+  };
+
+  const handleSubmit = async (chunks: IdleChunk[]) => {
+    try {
+      await idleApi.submitIdleActivity(period.startedAt, chunks);
+      // Lets the main window refresh its activity list
+      await emit('activity-updated');
+      close();
+    } catch (error) {
+      handleApiError(error, 'Failed to update idle activity');
+    }
+  };
+
+  const handleNavigateToSettings = async () => {
+    await emit('open-idle-settings');
+    await windowApi.showMainWindow().catch(() => {});
+  };
+
+  return (
+    <IdlePrompt
+      variant="window"
+      durationMinutes={period.durationMinutes}
+      onSubmit={handleSubmit}
+      onSkip={handleSkip}
+      onNavigateToSettings={handleNavigateToSettings}
+    />
+  );
+};
+
+export default IdlePromptWindow;
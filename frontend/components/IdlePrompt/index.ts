@@ -1 +1,2 @@
 export { default as IdlePrompt } from './IdlePrompt';
+export { default as IdlePromptWindow } from './IdlePromptWindow';
@@ -4,10 +4,19 @@ import { useStore } from '../../store';
 import DateRangeSelector from './DateRangeSelector';
 import { useStartThinkingMode, useStopThinkingMode, usePauseTracking, useResumeTracking } from '../../hooks/useTracker';
 import { handleApiError, showSuccess } from '../../utils/toast';
-import { manualEntriesApi } from '../../services/api/manualEntries';
 import { exportData } from '../../utils/export';
 import type { Category } from '../../types';
 
+// Timed pause choices (minutes); tracking resumes by itself afterwards
+const PAUSE_DURATIONS = [15, 30, 60, 120];
+
+// Pause reasons, recorded as an entry in the named category for the paused time
+const PAUSE_REASONS = [
+  { label: 'Lunch', category: 'Break' },
+  { label: 'Errand', category: 'Personal' },
+  { label: 'Meeting', category: 'Meetings' },
+];
+
 interface HeaderProps {
   onAddEntry: () => void;
   onMenuClick?: () => void;
@@ -47,11 +56,7 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
       } else {
         // Если Pause активен, выключаем его перед включением Thinking mode
         if (isTrackingPaused) {
-          try {
-            await manualEntriesApi.stopManualEntry();
-          } catch (error) {
-            // Игнорируем ошибку, если нет активной manual entry
-          }
+          // Resuming also closes the pause entry
           await resumeTracking.mutateAsync();
           setIsTrackingPaused(false);
         }
@@ -66,7 +71,7 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
     }
   };
 
-  const handlePauseResume = async () => {
+  const handlePauseResume = async (durationMin?: number, reason?: string) => {
     try {
       if (isTrackingPaused) {
         // Если Thinking mode активен, выключаем его
@@ -76,12 +81,7 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
           setIsTrackingPaused(false);
           showSuccess('Thinking mode stopped, tracking resumed');
         } else {
-          // Останавливаем break entry и возобновляем трекинг
-          try {
-            await manualEntriesApi.stopManualEntry();
-          } catch (error) {
-            // Игнорируем ошибку, если нет активной manual entry
-          }
+          // Возобновляем трекинг (backend закрывает entry паузы)
           await resumeTracking.mutateAsync();
           setIsTrackingPaused(false);
           showSuccess('Tracking resumed');
@@ -92,15 +92,15 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
           await stopThinkingMode.mutateAsync();
           setIsThinkingMode(false);
         }
-        // Создаем break entry для отслеживания времени паузы
-        if (breakCategoryId) {
-          await manualEntriesApi.startManualEntry(breakCategoryId, 'Pause');
-          await pauseTracking.mutateAsync();
-          setIsTrackingPaused(true);
-          showSuccess('Tracking paused');
-        } else {
-          handleApiError(new Error('Break category not found'), 'Failed to pause tracking');
-        }
+        // The paused time is recorded as an entry, under Break unless the reason says otherwise
+        const reasonCategory = PAUSE_REASONS.find((r) => r.label === reason)?.category;
+        const categoryId =
+          categories.find((c: Category) => c.name === reasonCategory)?.id ?? breakCategoryId;
+        await pauseTracking.mutateAsync({ durationMin, reason: reason ?? 'Pause', categoryId });
+        setIsTrackingPaused(true);
+        showSuccess(
+          durationMin ? `Tracking paused for ${durationMin} min` : reason ? `Paused: ${reason}` : 'Tracking paused'
+        );
       }
     } catch (error) {
       handleApiError(error, 'Failed to pause/resume tracking');
@@ -150,7 +150,7 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
 
         {/* Pause/Resume Button */}
         <button
-          onClick={handlePauseResume}
+          onClick={() => handlePauseResume()}
           className={`flex items-center gap-1 sm:gap-2 whitespace-nowrap rounded-lg px-2 sm:px-3 py-1.5 sm:py-2 transition-all duration-200 ${
             isTrackingPaused && !isThinkingMode
               ? 'bg-orange-500 text-white shadow-md hover:bg-orange-600 hover:shadow-lg'
@@ -173,6 +173,34 @@ export default function Header({ onAddEntry, onMenuClick }: HeaderProps) {
             </>
           )}
         </button>
+        {!isTrackingPaused && (
+          <select
+            value=""
+            onChange={(e) => {
+              const [kind, value] = e.target.value.split(':');
+              if (kind === 'for') handlePauseResume(Number(value));
+              if (kind === 'reason') handlePauseResume(undefined, value);
+            }}
+            className="hidden md:block bg-transparent text-sm text-gray-700 dark:text-gray-300 rounded-lg px-1 py-1.5 hover:bg-gray-100 dark:hover:bg-gray-700"
+            title="Pause for a while or for a reason"
+          >
+            <option value="">For…</option>
+            <optgroup label="Pause for">
+              {PAUSE_DURATIONS.map((minutes) => (
+                <option key={minutes} value={`for:${minutes}`}>
+                  {minutes < 60 ? `${minutes} min` : `${minutes / 60} h`}
+                </option>
+              ))}
+            </optgroup>
+            <optgroup label="Away for">
+              {PAUSE_REASONS.map((r) => (
+                <option key={r.label} value={`reason:${r.label}`}>
+                  {r.label}
+                </option>
+              ))}
+            </optgroup>
+          </select>
+        )}
 
         {/* Export Button - hidden on small screens where Pause shows as Export */}
         <button
@@ -2,6 +2,7 @@ import React, { useState, useEffect } from 'react';
 import { useStore } from '../../store';
 import { ManualEntry as ManualEntryType, Category } from '../../types';
 import { formatDuration } from '../../utils/format';
+import { pickableCategories } from '../../utils/categories';
 import Button from '../Common/Button';
 import { manualEntriesApi } from '../../services/api/manualEntries';
 import { usePinnedCategories } from '../../hooks/useCategories';
@@ -68,6 +69,7 @@ const ManualEntryForm: React.FC<ManualEntryFormProps> = ({ isOpen, onClose, edit
         await manualEntriesApi.updateManualEntry({
           id: editEntry.id,
           ...entry,
+          billable_override: editEntry.billable_override ?? null,
         });
       } else {
         await manualEntriesApi.createManualEntry(entry);
@@ -179,7 +181,7 @@ const ManualEntryForm: React.FC<ManualEntryFormProps> = ({ isOpen, onClose, edit
                          focus:ring-2 focus:ring-primary-500 focus:border-transparent"
             >
               <option value="">Select category</option>
-              {categories.map((cat) => (
+              {pickableCategories(categories, categoryId).map((cat) => (
                 <option key={cat.id} value={cat.id}>
                   {cat.icon} {cat.name}
                 </option>
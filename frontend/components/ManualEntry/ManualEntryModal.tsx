@@ -5,6 +5,7 @@ import { useCreateManualEntry, useUpdateManualEntry } from '../../hooks';
 import { useStore } from '../../store';
 import { usePinnedCategories } from '../../hooks/useCategories';
 import type { ManualEntry, Category } from '../../types';
+import { pickableCategories } from '../../utils/categories';
 import Button from '../Common/Button';
 
 interface ManualEntryModalProps {
@@ -29,6 +30,7 @@ export default function ManualEntryModal({ editEntry, onClose }: ManualEntryModa
 
   const [description, setDescription] = useState(editEntry?.description || '');
   const [categoryId, setCategoryId] = useState<number | null>(editEntry?.category_id ?? null);
+  const [billableOverride, setBillableOverride] = useState<boolean | null>(editEntry?.billable_override ?? null);
   const [startDate, setStartDate] = useState(
     editEntry 
       ? format(new Date(editEntry.started_at * 1000), "yyyy-MM-dd'T'HH:mm")
@@ -51,6 +53,7 @@ export default function ManualEntryModal({ editEntry, onClose }: ManualEntryModa
     if (editEntry) {
       setDescription(editEntry.description || '');
       setCategoryId(editEntry.category_id ?? null);
+      setBillableOverride(editEntry.billable_override ?? null);
       setStartDate(format(new Date(editEntry.started_at * 1000), "yyyy-MM-dd'T'HH:mm"));
       setEndDate(format(new Date(editEntry.ended_at * 1000), "yyyy-MM-dd'T'HH:mm"));
     }
@@ -75,6 +78,7 @@ export default function ManualEntryModal({ editEntry, onClose }: ManualEntryModa
           category_id: categoryId,
           started_at: Math.floor(startedAt / 1000),
           ended_at: Math.floor(endedAt / 1000),
+          billable_override: billableOverride,
         });
       } else {
         await createEntry.mutateAsync({
@@ -180,7 +184,7 @@ export default function ManualEntryModal({ editEntry, onClose }: ManualEntryModa
               className="select"
             >
               <option value="">Select category...</option>
-              {categories.map((cat) => (
+              {pickableCategories(categories, categoryId).map((cat) => (
                 <option key={cat.id} value={cat.id}>
                   {cat.icon} {cat.name}
                 </option>
@@ -188,6 +192,23 @@ export default function ManualEntryModal({ editEntry, onClose }: ManualEntryModa
             </select>
           </div>
 
+          {/* Billable override (editing only) */}
+          {editEntry && (
+            <div>
+              <label className="block text-sm font-medium text-gray-700 mb-2">
+                Billable
+              </label>
+              <select
+                value={billableOverride === null ? '' : String(billableOverride)}
+                onChange={(e) => setBillableOverride(e.target.value === '' ? null : e.target.value === 'true')}
+                className="select"
+              >
+                <option value="">Same as project/category</option>
+                <option value="true">Billable</option>
+                <option value="false">Non-billable</option>
+              </select>
+            </div>
+          )}
 
           {/* Description */}
           <div>
@@ -1,10 +1,13 @@
-import { Package, Trash2, Power, PowerOff } from 'lucide-react';
-import type { InstalledPlugin } from '../../types/plugin';
+import { Package, Trash2, Power, PowerOff, Activity } from 'lucide-react';
+import type { InstalledPlugin, PluginMetrics } from '../../types/plugin';
 import LoadingSpinner from '../Common/LoadingSpinner';
 import { showSuccess } from '../../utils/toast';
 
 interface InstalledPluginsProps {
   plugins: InstalledPlugin[];
+  /** Usage since the app started; slow plugins can be disabled here */
+  metrics?: PluginMetrics[];
+  onResetMetrics?: () => void;
   isLoading: boolean;
   enablePlugin: (pluginId: string) => Promise<boolean>;
   disablePlugin: (pluginId: string) => Promise<boolean>;
@@ -12,7 +15,10 @@ interface InstalledPluginsProps {
   onUninstall?: () => void;
 }
 
-export default function InstalledPlugins({ plugins, isLoading, enablePlugin, disablePlugin, uninstallPlugin, onUninstall }: InstalledPluginsProps) {
+const formatMs = (ms: number) => (ms >= 1000 ? `${(ms / 1000).toFixed(1)} s` : `${Math.round(ms)} ms`);
+
+export default function InstalledPlugins({ plugins, metrics = [], onResetMetrics, isLoading, enablePlugin, disablePlugin, uninstallPlugin, onUninstall }: InstalledPluginsProps) {
+  const metricsFor = (pluginId: string) => metrics.find((m) => m.plugin_id === pluginId);
 
   const handleToggle = async (plugin: InstalledPlugin) => {
     if (plugin.enabled) {
@@ -58,6 +64,17 @@ export default function InstalledPlugins({ plugins, isLoading, enablePlugin, dis
 
   return (
     <div className="space-y-4">
+      {metrics.length > 0 && onResetMetrics && (
+        <div className="flex items-center justify-between text-sm text-gray-500 dark:text-gray-400">
+          <span>Usage since the app started</span>
+          <button
+            onClick={onResetMetrics}
+            className="hover:text-primary-600 dark:hover:text-primary-400"
+          >
+            Reset usage
+          </button>
+        </div>
+      )}
       {plugins.map((plugin) => (
         <div
           key={plugin.id}
@@ -101,6 +118,23 @@ export default function InstalledPlugins({ plugins, isLoading, enablePlugin, dis
                     </a>
                   )}
                 </div>
+                {(() => {
+                  const usage = metricsFor(plugin.id);
+                  if (!usage || usage.calls === 0) return null;
+                  const slowest = Object.entries(usage.commands).sort((a, b) => b[1].max_ms - a[1].max_ms)[0];
+                  return (
+                    <div
+                      className="flex items-center gap-2 mt-2 text-xs text-gray-500 dark:text-gray-400"
+                      title={slowest ? `Slowest: ${slowest[0]} (${formatMs(slowest[1].max_ms)})` : undefined}
+                    >
+                      <Activity className="w-3.5 h-3.5" />
+                      <span>
+                        {usage.calls} calls · {formatMs(usage.total_ms)} total · {formatMs(usage.total_ms / usage.calls)} avg · {formatMs(usage.max_ms)} max
+                        {usage.errors > 0 && ` · ${usage.errors} errors`}
+                      </span>
+                    </div>
+                  );
+                })()}
               </div>
             </div>
             <div className="flex items-center gap-2 ml-4">
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import { Package, Settings as SettingsIcon, Plus, Trash2 } from 'lucide-react';
+import { Package, Settings as SettingsIcon, Plus, Trash2, RefreshCw } from 'lucide-react';
 import { usePluginRegistry } from '../../hooks/usePluginRegistry';
 import { usePlugins } from '../../hooks/usePlugins';
 import { useSettings } from '../../hooks/useSettings';
@@ -11,11 +11,19 @@ import PluginSearch from './PluginSearch';
 import InstalledPlugins from './InstalledPlugins';
 import LoadingSpinner from '../Common/LoadingSpinner';
 import Button from '../Common/Button';
-import type { RegistryPlugin } from '../../types/plugin';
+import type { RegistryPlugin, PluginSort } from '../../types/plugin';
 import { showSuccess, handleApiError } from '../../utils/toast';
 
 type Tab = 'discover' | 'installed';
 
+const SORT_OPTIONS: { value: PluginSort; label: string }[] = [
+  { value: 'relevance', label: 'Relevance' },
+  { value: 'downloads', label: 'Most downloaded' },
+  { value: 'updated', label: 'Recently updated' },
+  { value: 'rating', label: 'Top rated' },
+  { value: 'name', label: 'Name' },
+];
+
 export default function Marketplace() {
   const [activeTab, setActiveTab] = useState<Tab>('discover');
   const [selectedPlugin, setSelectedPlugin] = useState<RegistryPlugin | null>(null);
@@ -24,8 +32,29 @@ export default function Marketplace() {
   const [registryUrls, setRegistryUrls] = useState<string[]>([]);
   const [newRegistryUrl, setNewRegistryUrl] = useState('');
   const [isSavingRegistryUrls, setIsSavingRegistryUrls] = useState(false);
-  const { plugins: registryPlugins, isLoading: isLoadingRegistry, searchPlugins, refetch } = usePluginRegistry();
-  const { plugins: installedPlugins, installPlugin, refetch: refetchInstalledPlugins, enablePlugin, disablePlugin, uninstallPlugin } = usePlugins();
+  const [searchQuery, setSearchQuery] = useState('');
+  const [category, setCategory] = useState<string | null>(null);
+  const [sort, setSort] = useState<PluginSort>('relevance');
+  const [isRefreshingIndex, setIsRefreshingIndex] = useState(false);
+  const {
+    plugins: registryPlugins,
+    categories,
+    isLoading: isLoadingRegistry,
+    searchPlugins,
+    refreshIndex,
+    refetch,
+  } = usePluginRegistry();
+  const {
+    plugins: installedPlugins,
+    installPlugin,
+    refetch: refetchInstalledPlugins,
+    enablePlugin,
+    disablePlugin,
+    uninstallPlugin,
+    metrics: pluginMetrics,
+    refetchMetrics: refetchPluginMetrics,
+    resetMetrics: resetPluginMetrics,
+  } = usePlugins();
   const { data: settings } = useSettings();
 
   useEffect(() => {
@@ -41,7 +70,25 @@ export default function Marketplace() {
   }, [settings]);
 
   const handleSearch = (query: string) => {
-    searchPlugins(query);
+    setSearchQuery(query);
+    searchPlugins(query, category, sort);
+  };
+
+  const handleCategoryChange = (next: string | null) => {
+    setCategory(next);
+    searchPlugins(searchQuery, next, sort);
+  };
+
+  const handleSortChange = (next: PluginSort) => {
+    setSort(next);
+    searchPlugins(searchQuery, category, next);
+  };
+
+  const handleRefreshIndex = async () => {
+    setIsRefreshingIndex(true);
+    await refreshIndex();
+    await searchPlugins(searchQuery, category, sort);
+    setIsRefreshingIndex(false);
   };
 
   const handleInstall = async (plugin: RegistryPlugin) => {
@@ -141,7 +188,10 @@ export default function Marketplace() {
               Discover
             </button>
             <button
-              onClick={() => setActiveTab('installed')}
+              onClick={() => {
+                setActiveTab('installed');
+                refetchPluginMetrics();
+              }}
               className={`px-4 py-2 text-sm font-medium transition-colors ${
                 activeTab === 'installed'
                   ? 'text-primary-600 dark:text-primary-400 border-b-2 border-primary-600 dark:border-primary-400'
@@ -159,7 +209,57 @@ export default function Marketplace() {
         {activeTab === 'discover' && (
           <div className="space-y-6">
             {/* Search */}
-            <PluginSearch onSearch={handleSearch} />
+            <div className="flex items-center gap-3">
+              <div className="flex-1">
+                <PluginSearch onSearch={handleSearch} />
+              </div>
+              <select
+                value={sort}
+                onChange={(e) => handleSortChange(e.target.value as PluginSort)}
+                className="px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white focus:outline-none focus:ring-2 focus:ring-primary-500 text-sm"
+                aria-label="Sort plugins"
+              >
+                {SORT_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+              <button
+                onClick={handleRefreshIndex}
+                disabled={isRefreshingIndex}
+                className="p-2 text-gray-500 dark:text-gray-400 hover:text-gray-700 dark:hover:text-gray-300 hover:bg-gray-100 dark:hover:bg-gray-700 rounded-lg transition-colors disabled:opacity-50"
+                title="Refresh plugin list"
+                aria-label="Refresh plugin list"
+              >
+                <RefreshCw className={`w-5 h-5 ${isRefreshingIndex ? 'animate-spin' : ''}`} />
+              </button>
+            </div>
+
+            {/* Categories */}
+            {categories.length > 0 && (
+              <div className="flex flex-wrap gap-2">
+                <button
+                  onClick={() => handleCategoryChange(null)}
+                  className={`px-3 py-1 rounded-full text-sm transition-colors ${
+                    category === null ? 'bg-primary-600 text-white' : 'bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-600'
+                  }`}
+                >
+                  All
+                </button>
+                {categories.map((c) => (
+                  <button
+                    key={c.name}
+                    onClick={() => handleCategoryChange(c.name)}
+                    className={`px-3 py-1 rounded-full text-sm transition-colors ${
+                      category === c.name ? 'bg-primary-600 text-white' : 'bg-gray-100 dark:bg-gray-700 text-gray-700 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-600'
+                    }`}
+                  >
+                    {c.name} ({c.count})
+                  </button>
+                ))}
+              </div>
+            )}
 
             {/* Plugins Grid */}
             {isLoadingRegistry ? (
@@ -191,6 +291,8 @@ export default function Marketplace() {
         {activeTab === 'installed' && (
           <InstalledPlugins 
             plugins={installedPlugins}
+            metrics={pluginMetrics}
+            onResetMetrics={resetPluginMetrics}
             isLoading={false}
             enablePlugin={enablePlugin}
             disablePlugin={disablePlugin}
@@ -1,4 +1,4 @@
-import { Package, Download, CheckCircle2 } from 'lucide-react';
+import { Package, Download, CheckCircle2, Star } from 'lucide-react';
 import type { RegistryPlugin } from '../../types/plugin';
 import Button from '../Common/Button';
 import LoadingSpinner from '../Common/LoadingSpinner';
@@ -34,6 +34,14 @@ export default function PluginCard({ plugin, isInstalled, isInstalling = false,
             Installed
           </span>
         )}
+        {!isInstalled && !plugin.compatible && (
+          <span
+            className="px-2 py-1 text-xs font-medium bg-yellow-100 dark:bg-yellow-900/30 text-yellow-800 dark:text-yellow-300 rounded"
+            title={plugin.compatibility_issue}
+          >
+            Incompatible
+          </span>
+        )}
       </div>
 
       <p className="text-sm text-gray-600 dark:text-gray-300 mb-4 line-clamp-2">
@@ -60,6 +68,12 @@ export default function PluginCard({ plugin, isInstalled, isInstalling = false,
               {plugin.downloads}
             </span>
           )}
+          {plugin.rating != null && (
+            <span className="flex items-center gap-1" title={`${plugin.rating_count ?? 0} ratings`}>
+              <Star className="w-4 h-4" />
+              {plugin.rating.toFixed(1)}
+            </span>
+          )}
         </div>
         {plugin.category && (
           <span className="px-2 py-1 bg-primary-50 dark:bg-primary-900/20 text-primary-700 dark:text-primary-300 rounded text-xs">
@@ -81,7 +95,8 @@ export default function PluginCard({ plugin, isInstalled, isInstalling = false,
         {onInstall && !isInstalled && (
           <Button
             onClick={() => onInstall(plugin)}
-            disabled={isInstalling}
+            disabled={isInstalling || !plugin.compatible}
+            title={plugin.compatibility_issue}
             className="flex-1 flex items-center justify-center gap-2"
           >
             {isInstalling ? (
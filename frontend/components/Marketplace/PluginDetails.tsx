@@ -94,6 +94,9 @@ export default function PluginDetails({ plugin, isInstalled, isInstalling, onClo
                 {plugin.max_core_version && (
                   <p>Maximum version: {plugin.max_core_version}</p>
                 )}
+                {plugin.compatibility_issue && (
+                  <p className="mt-1 text-yellow-600 dark:text-yellow-400">{plugin.compatibility_issue}</p>
+                )}
               </div>
             </div>
           )}
@@ -113,7 +116,7 @@ export default function PluginDetails({ plugin, isInstalled, isInstalling, onClo
             {onInstall && !isInstalled && (
               <Button
                 onClick={() => onInstall(plugin)}
-                disabled={isInstalling}
+                disabled={isInstalling || !plugin.compatible}
                 className="flex-1 flex items-center justify-center gap-2"
               >
                 {isInstalling ? (
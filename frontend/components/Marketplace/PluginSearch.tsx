@@ -1,5 +1,5 @@
 import { Search, X } from 'lucide-react';
-import { useState } from 'react';
+import { useState, useEffect, useRef } from 'react';
 
 interface PluginSearchProps {
   onSearch: (query: string) => void;
@@ -8,6 +8,18 @@ interface PluginSearchProps {
 
 export default function PluginSearch({ onSearch, placeholder = 'Search plugins...' }: PluginSearchProps) {
   const [query, setQuery] = useState('');
+  const isFirstRender = useRef(true);
+
+  // Search as the user types, once typing pauses
+  useEffect(() => {
+    if (isFirstRender.current) {
+      isFirstRender.current = false;
+      return;
+    }
+    const timer = setTimeout(() => onSearch(query), 250);
+    return () => clearTimeout(timer);
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [query]);
 
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
@@ -16,7 +28,6 @@ export default function PluginSearch({ onSearch, placeholder = 'Search plugins..
 
   const handleClear = () => {
     setQuery('');
-    onSearch('');
   };
 
   return (
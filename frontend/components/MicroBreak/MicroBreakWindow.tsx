@@ -0,0 +1,57 @@
+import React, { useEffect, useRef, useState } from 'react';
+import { appWindow } from '@tauri-apps/api/window';
+import { microBreaksApi } from '../../services/api/microBreaks';
+import { useStore } from '../../store';
+
+// The backend opens this window with the logged break in the query string
+const params = new URLSearchParams(window.location.search);
+const breakId = Number(params.get('id')) || 0;
+const breakSeconds = Number(params.get('seconds')) || 20;
+
+/** 20-20-20 micro-break overlay: counts down, then closes itself */
+const MicroBreakWindow: React.FC = () => {
+  const [remaining, setRemaining] = useState(breakSeconds);
+  const finished = useRef(false);
+  const darkMode = useStore((state) => state.settings.darkMode);
+
+  useEffect(() => {
+    document.documentElement.classList.toggle('dark', !!darkMode);
+  }, [darkMode]);
+
+  const finish = (skipped: boolean) => {
+    if (finished.current) return;
+    finished.current = true;
+    microBreaksApi
+      .finishMicroBreak(breakId, skipped)
+      .catch(() => {})
+      .finally(() => appWindow.close().catch(() => {}));
+  };
+
+  useEffect(() => {
+    const timer = setInterval(() => setRemaining((seconds) => Math.max(0, seconds - 1)), 1000);
+    return () => clearInterval(timer);
+  }, []);
+
+  useEffect(() => {
+    if (remaining === 0) finish(false);
+  }, [remaining]);
+
+  return (
+    <div className="h-screen flex flex-col items-center justify-center gap-3 p-4 rounded-xl
+                    bg-white dark:bg-gray-800 text-gray-900 dark:text-white">
+      <p className="text-sm text-gray-600 dark:text-gray-400 text-center">
+        Look at something about 20 feet (6 m) away
+      </p>
+      <p className="text-4xl font-semibold tabular-nums">{remaining}s</p>
+      <button
+        onClick={() => finish(true)}
+        className="px-3 py-1 text-sm rounded-lg text-gray-600 dark:text-gray-300
+                   hover:bg-gray-100 dark:hover:bg-gray-700"
+      >
+        Skip
+      </button>
+    </div>
+  );
+};
+
+export default MicroBreakWindow;
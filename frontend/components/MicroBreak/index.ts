@@ -0,0 +1 @@
+export { default as MicroBreakWindow } from './MicroBreakWindow';
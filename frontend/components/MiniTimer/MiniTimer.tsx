@@ -0,0 +1,84 @@
+import React, { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { trackingApi } from '../../services/api/tracking';
+import { windowApi } from '../../services/api/window';
+import { formatTimerTime } from '../../utils/format';
+import type { AppEvent } from '../../types';
+
+interface TrackingStatus {
+  isTracking: boolean;
+  isPaused: boolean;
+  currentApp: string | null;
+}
+
+// Pomodoro plugins report `ends_at` (unix seconds) or `remaining_sec` at event time
+const remainingSeconds = (event: AppEvent, now: number): number | null => {
+  const { ends_at: endsAt, remaining_sec: remainingSec } = event.payload;
+  if (typeof endsAt === 'number') return Math.max(0, endsAt - now);
+  if (typeof remainingSec === 'number') return Math.max(0, remainingSec - (now - event.timestamp));
+  return null;
+};
+
+const MiniTimer: React.FC = () => {
+  const [pomodoro, setPomodoro] = useState<AppEvent | null>(null);
+  const [status, setStatus] = useState<TrackingStatus | null>(null);
+  const [now, setNow] = useState(() => Math.floor(Date.now() / 1000));
+
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    windowApi.getPomodoroStatus().then(setPomodoro).catch(() => {});
+    listen<AppEvent>('pomodoro-update', (event) => setPomodoro(event.payload))
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch(() => {});
+
+    const refreshStatus = () => trackingApi.getTrackingStatus().then(setStatus).catch(() => {});
+    refreshStatus();
+    const statusTimer = setInterval(refreshStatus, 5000);
+    const clock = setInterval(() => setNow(Math.floor(Date.now() / 1000)), 1000);
+
+    return () => {
+      if (unlisten) unlisten();
+      clearInterval(statusTimer);
+      clearInterval(clock);
+    };
+  }, []);
+
+  const phase = typeof pomodoro?.payload.phase === 'string' ? pomodoro.payload.phase : null;
+  const task = typeof pomodoro?.payload.task === 'string' ? pomodoro.payload.task : null;
+  const remaining = pomodoro && phase ? remainingSeconds(pomodoro, now) : null;
+  const isBreak = phase?.includes('break') ?? false;
+
+  let title: string;
+  let detail: string | null;
+  if (phase && remaining !== null) {
+    title = formatTimerTime(remaining);
+    detail = task ?? (isBreak ? 'Break' : 'Focus');
+  } else if (status?.isPaused || status?.isTracking === false) {
+    title = 'Paused';
+    detail = task;
+  } else {
+    title = task ?? status?.currentApp ?? 'Tracking';
+    detail = task ? status?.currentApp ?? null : null;
+  }
+
+  return (
+    <div
+      data-tauri-drag-region
+      className="fixed inset-0 flex items-center gap-3 px-4 rounded-xl bg-gray-900/90 text-white select-none"
+    >
+      <span
+        className={`h-2.5 w-2.5 shrink-0 rounded-full ${
+          phase ? (isBreak ? 'bg-blue-400' : 'bg-red-500') : status?.isPaused ? 'bg-gray-400' : 'bg-green-400'
+        }`}
+      />
+      <div className="min-w-0" data-tauri-drag-region>
+        <div className="text-lg font-semibold tabular-nums truncate">{title}</div>
+        {detail && <div className="text-xs text-gray-300 truncate">{detail}</div>}
+      </div>
+    </div>
+  );
+};
+
+export default MiniTimer;
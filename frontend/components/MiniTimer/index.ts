@@ -0,0 +1 @@
+export { default as MiniTimer } from './MiniTimer';
@@ -1,6 +1,8 @@
 import React, { useState, useMemo } from 'react';
 import { useActivities } from '../../hooks/useActivities';
 import { useCategories } from '../../hooks/useCategories';
+import { useDomainRatings } from '../../hooks/useDomainRatings';
+import { activityProductivity } from '../../utils/categories';
 import { PieChart, Pie, Cell, BarChart, Bar, XAxis, YAxis, CartesianGrid, Tooltip, ResponsiveContainer, LineChart, Line, Legend } from 'recharts';
 import { formatDuration } from '../../utils/format';
 import { exportData } from '../../utils/export';
@@ -31,6 +33,7 @@ export const Reports: React.FC = () => {
   
   const { data: activities = [], isLoading: activitiesLoading } = useActivities();
   const { data: categories = [], isLoading: categoriesLoading } = useCategories();
+  const { data: domainRatings = [] } = useDomainRatings();
   
   const isLoading = activitiesLoading || categoriesLoading;
   
@@ -106,10 +109,11 @@ export const Reports: React.FC = () => {
       if (days[date]) {
         const category = categories.find(c => c.id === activity.category_id);
         const duration = activity.duration_sec;
+        const productivity = activityProductivity(activity, category, domainRatings);
         days[date].total += duration;
-        if (category?.is_productive === true) {
+        if (productivity === true) {
           days[date].productive += duration;
-        } else if (category?.is_productive === false) {
+        } else if (productivity === false) {
           days[date].unproductive += duration;
         }
       }
@@ -122,7 +126,7 @@ export const Reports: React.FC = () => {
       unproductive: Math.round(stats.unproductive / 3600 * 10) / 10,
       total: Math.round(stats.total / 3600 * 10) / 10,
     }));
-  }, [filteredActivities, period, categories]);
+  }, [filteredActivities, period, categories, domainRatings]);
 
   // Top apps
   const topApps = useMemo(() => {
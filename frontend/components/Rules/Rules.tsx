@@ -4,6 +4,7 @@ import { useCategories } from '../../hooks/useCategories';
 import Card from '../Common/Card';
 import Button from '../Common/Button';
 import { Rule, Category } from '../../types';
+import { pickableCategories } from '../../utils/categories';
 
 export const Rules: React.FC = () => {
   const { rules, isLoading, createRule, deleteRule } = useRules();
@@ -104,7 +105,7 @@ export const Rules: React.FC = () => {
                 onChange={(e) => setNewRule({ ...newRule, category_id: parseInt(e.target.value) })}
                 className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
               >
-                {categories.map((cat: Category) => (
+                {pickableCategories(categories, newRule.category_id).map((cat: Category) => (
                   <option key={cat.id} value={cat.id}>
                     {cat.icon} {cat.name}
                   </option>
@@ -1,4 +1,7 @@
 import React from 'react';
+import { AppUsagePanel } from './AppUsagePanel';
+import Button from '../Common/Button';
+import { exportAnonymizedDatabase } from '../../utils/export';
 
 export const AboutSettings: React.FC = () => {
   return (
@@ -40,6 +43,20 @@ export const AboutSettings: React.FC = () => {
           </p>
         </div>
       </div>
+
+      <AppUsagePanel />
+
+      <div className="bg-white dark:bg-gray-800 rounded-xl p-4 sm:p-6 shadow-sm flex items-center justify-between gap-4">
+        <div>
+          <h3 className="text-lg font-semibold text-gray-900 dark:text-white">Share Data for Debugging</h3>
+          <p className="text-sm text-gray-600 dark:text-gray-400 mt-1">
+            Save a copy of your database with window titles, domains and descriptions replaced by placeholders
+          </p>
+        </div>
+        <Button variant="secondary" size="sm" onClick={exportAnonymizedDatabase}>
+          Save anonymized copy
+        </Button>
+      </div>
     </div>
   );
 };
@@ -0,0 +1,83 @@
+import React, { useMemo } from 'react';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
+import { startOfDay, subDays } from 'date-fns';
+import { appMetricsApi } from '../../services/api/appMetrics';
+import { handleApiError, showSuccess } from '../../utils/toast';
+import Button from '../Common/Button';
+
+const USAGE_DAYS = 30;
+
+const METRIC_LABELS: Record<string, string> = {
+  tracking_paused: 'Tracking paused',
+  tracking_resumed: 'Tracking resumed',
+  idle_prompt_shown: 'Idle prompts shown',
+  idle_prompt_answered: 'Idle prompts answered',
+  idle_prompt_dismissed: 'Idle prompts dismissed',
+  rule_edited: 'Rules edited',
+  manual_entry_created: 'Manual entries added',
+  checkin_answered: 'Check-ins answered',
+  profile_switched: 'Profile switches',
+};
+
+/** How the app itself was used recently; stored only on this device */
+export const AppUsagePanel: React.FC = () => {
+  const queryClient = useQueryClient();
+  const range = useMemo(() => {
+    const now = new Date();
+    return { start: startOfDay(subDays(now, USAGE_DAYS - 1)), end: now };
+  }, []);
+
+  const { data: usage } = useQuery({
+    queryKey: ['appUsage', range.start.getTime()],
+    queryFn: () => appMetricsApi.getAppUsage(range),
+  });
+
+  const totals = usage?.totals ?? [];
+  const countOf = (metric: string) => totals.find((t) => t.metric === metric)?.count ?? 0;
+  const answered = countOf('idle_prompt_answered');
+  const dismissed = countOf('idle_prompt_dismissed');
+
+  const handleClear = async () => {
+    try {
+      await appMetricsApi.clearAppUsage();
+      await queryClient.invalidateQueries({ queryKey: ['appUsage'] });
+      showSuccess('App usage data cleared');
+    } catch (error) {
+      handleApiError(error, 'Failed to clear app usage data');
+    }
+  };
+
+  return (
+    <div className="bg-white dark:bg-gray-800 rounded-xl p-4 sm:p-6 shadow-sm">
+      <div className="flex items-center justify-between gap-4 mb-1">
+        <h3 className="text-lg font-semibold text-gray-900 dark:text-white">App Usage</h3>
+        <Button variant="secondary" size="sm" onClick={handleClear} disabled={totals.length === 0}>
+          Clear
+        </Button>
+      </div>
+      <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+        Last {USAGE_DAYS} days. Kept only on this device and never sent anywhere.
+      </p>
+
+      {totals.length === 0 ? (
+        <p className="text-sm text-gray-500 dark:text-gray-400">Nothing recorded yet</p>
+      ) : (
+        <dl className="grid grid-cols-2 gap-x-6 gap-y-2 text-sm">
+          {totals.map((total) => (
+            <div key={total.metric} className="flex justify-between gap-2">
+              <dt className="text-gray-600 dark:text-gray-400">{METRIC_LABELS[total.metric] ?? total.metric}</dt>
+              <dd className="font-medium text-gray-900 dark:text-white tabular-nums">{total.count}</dd>
+            </div>
+          ))}
+        </dl>
+      )}
+
+      {answered + dismissed > 0 && (
+        <p className="mt-4 text-sm text-gray-600 dark:text-gray-400">
+          {Math.round((answered / (answered + dismissed)) * 100)}% of idle prompts were answered
+          {dismissed > answered && '. Consider raising the idle prompt threshold in General settings.'}
+        </p>
+      )}
+    </div>
+  );
+};
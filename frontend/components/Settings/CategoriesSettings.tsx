@@ -2,9 +2,36 @@ import React, { useState } from 'react';
 import { Category } from '../../types';
 import Button from '../Common/Button';
 import Toggle from '../Common/Toggle';
-import { Check, X, Trash2, Edit2, RotateCcw } from 'lucide-react';
+import { Check, X, Trash2, Edit2, RotateCcw, Archive, ArchiveRestore } from 'lucide-react';
+import { useQuery } from '@tanstack/react-query';
 import { useStore } from '../../store';
-import { useCreateCategory, useUpdateCategory, useDeleteCategory, useResetSystemCategory } from '../../hooks/useCategories';
+import { useCreateCategory, useUpdateCategory, useDeleteCategory, useResetSystemCategory, useSetCategoryArchived } from '../../hooks/useCategories';
+import { categoriesApi } from '../../services/api/categories';
+
+/** Warns when a color is hard to read on the light or dark theme */
+const ColorContrastHint: React.FC<{ color: string; onUse: (color: string) => void }> = ({ color, onUse }) => {
+  const { data: contrast } = useQuery({
+    queryKey: ['categoryColor', color],
+    queryFn: () => categoriesApi.checkCategoryColor(color),
+    enabled: /^#([0-9a-f]{3}|[0-9a-f]{6})$/i.test(color),
+    retry: false,
+  });
+  if (!contrast || contrast.readable) return null;
+
+  return (
+    <p className="mt-1 text-xs text-amber-600 dark:text-amber-400">
+      Hard to read on {contrast.light_ratio < 3 ? 'light' : 'dark'} backgrounds
+      {contrast.suggestion && (
+        <>
+          {' · '}
+          <button type="button" className="underline" onClick={() => onUse(contrast.suggestion!)}>
+            Use {contrast.suggestion}
+          </button>
+        </>
+      )}
+    </p>
+  );
+};
 
 export const CategoriesSettings: React.FC = () => {
   const categories = useStore((state) => state.categories);
@@ -12,6 +39,7 @@ export const CategoriesSettings: React.FC = () => {
   const updateCategoryMutation = useUpdateCategory();
   const deleteCategoryMutation = useDeleteCategory();
   const resetSystemCategoryMutation = useResetSystemCategory();
+  const setArchivedMutation = useSetCategoryArchived();
   
   const [newCategory, setNewCategory] = useState<Partial<Category> | null>(null);
   const [editingCategoryId, setEditingCategoryId] = useState<number | null>(null);
@@ -32,6 +60,7 @@ export const CategoriesSettings: React.FC = () => {
         is_productive: newCategory.is_productive !== undefined ? newCategory.is_productive : true,
         sort_order: categories.length,
         is_pinned: newCategory.is_pinned ?? false,
+        exclude_from_export: newCategory.exclude_from_export ?? false,
       });
       
       const { showSuccess } = await import('../../utils/toast');
@@ -53,6 +82,7 @@ export const CategoriesSettings: React.FC = () => {
       is_productive: category.is_productive,
       sort_order: category.sort_order,
       is_pinned: category.is_pinned ?? false,
+      exclude_from_export: category.exclude_from_export ?? false,
     });
     setNewCategory(null);
   };
@@ -75,6 +105,7 @@ export const CategoriesSettings: React.FC = () => {
           : null,
         sort_order: editingCategory.sort_order ?? 0,
         is_pinned: editingCategory.is_pinned ?? false,
+        exclude_from_export: editingCategory.exclude_from_export ?? false,
       });
       
       const { showSuccess } = await import('../../utils/toast');
@@ -113,8 +144,9 @@ export const CategoriesSettings: React.FC = () => {
           <Button 
             variant="primary" 
             size="sm"
-            onClick={() => {
-              setNewCategory({ name: '', color: '#888888', icon: '📁', is_productive: true, is_pinned: false });
+            onClick={async () => {
+              const color = await categoriesApi.suggestCategoryColor().catch(() => '#888888');
+              setNewCategory({ name: '', color, icon: '📁', is_productive: true, is_pinned: false });
               setEditingCategoryId(null);
               setEditingCategory(null);
             }}
@@ -178,6 +210,10 @@ export const CategoriesSettings: React.FC = () => {
                     placeholder="#888888"
                   />
                 </div>
+                <ColorContrastHint
+                  color={newCategory.color || '#888888'}
+                  onUse={(color) => setNewCategory({ ...newCategory, color })}
+                />
               </div>
               <div>
                 <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
@@ -212,6 +248,19 @@ export const CategoriesSettings: React.FC = () => {
                   description="Show in quick selection forms"
                 />
               </div>
+              <div>
+                <Toggle
+                  checked={newCategory.exclude_from_export === true}
+                  onChange={(checked) => {
+                    setNewCategory({ 
+                      ...newCategory, 
+                      exclude_from_export: checked
+                    });
+                  }}
+                  label="Private"
+                  description="Never include in exports, reports or webhooks"
+                />
+              </div>
             </div>
             <div className="flex gap-2 mt-4">
               <Button 
@@ -290,6 +339,10 @@ export const CategoriesSettings: React.FC = () => {
                           placeholder="#888888"
                         />
                       </div>
+                      <ColorContrastHint
+                        color={editingCategory.color || '#888888'}
+                        onUse={(color) => setEditingCategory({ ...editingCategory, color })}
+                      />
                     </div>
                     <div>
                       <label className="block text-sm font-medium text-gray-700 dark:text-gray-300 mb-1">
@@ -324,6 +377,19 @@ export const CategoriesSettings: React.FC = () => {
                         description="Show in quick selection forms"
                       />
                     </div>
+                    <div>
+                      <Toggle
+                        checked={editingCategory.exclude_from_export === true}
+                        onChange={(checked) => {
+                          setEditingCategory({ 
+                            ...editingCategory, 
+                            exclude_from_export: checked
+                          });
+                        }}
+                        label="Private"
+                        description="Never include in exports, reports or webhooks"
+                      />
+                    </div>
                   </div>
                   <div className="flex justify-between items-center mt-4">
                     <div className="flex gap-2">
@@ -383,19 +449,50 @@ export const CategoriesSettings: React.FC = () => {
                         )}
                       </Button>
                     ) : (
-                      <Button 
-                        size="sm" 
-                        variant="danger" 
-                        onClick={() => {
-                          if (editingCategoryId && confirm('Delete category? Records will be marked as "Uncategorized"')) {
-                            handleDeleteCategory(editingCategoryId);
-                          }
-                        }}
-                        disabled={updateCategoryMutation.isPending}
-                      >
-                        <Trash2 className="w-4 h-4 mr-1" />
-                        Delete
-                      </Button>
+                      <div className="flex gap-2">
+                        <Button 
+                          size="sm" 
+                          variant="secondary" 
+                          onClick={async () => {
+                            try {
+                              await setArchivedMutation.mutateAsync({ id: category.id, archived: !category.is_archived });
+                              const { showSuccess } = await import('../../utils/toast');
+                              showSuccess(category.is_archived ? 'Category restored' : 'Category archived');
+                              setEditingCategoryId(null);
+                              setEditingCategory(null);
+                            } catch (error) {
+                              const { handleApiError } = await import('../../utils/toast');
+                              handleApiError(error, 'Failed to archive category');
+                            }
+                          }}
+                          disabled={setArchivedMutation.isPending || updateCategoryMutation.isPending}
+                        >
+                          {category.is_archived ? (
+                            <>
+                              <ArchiveRestore className="w-4 h-4 mr-1" />
+                              Unarchive
+                            </>
+                          ) : (
+                            <>
+                              <Archive className="w-4 h-4 mr-1" />
+                              Archive
+                            </>
+                          )}
+                        </Button>
+                        <Button 
+                          size="sm" 
+                          variant="danger" 
+                          onClick={() => {
+                            if (editingCategoryId && confirm('Delete category? Records will be marked as "Uncategorized"')) {
+                              handleDeleteCategory(editingCategoryId);
+                            }
+                          }}
+                          disabled={updateCategoryMutation.isPending}
+                        >
+                          <Trash2 className="w-4 h-4 mr-1" />
+                          Delete
+                        </Button>
+                      </div>
                     )}
                   </div>
                 </div>
@@ -416,6 +513,8 @@ export const CategoriesSettings: React.FC = () => {
                       {category.is_productive === true && '✓ Productive'}
                       {category.is_productive === false && '✗ Unproductive'}
                       {category.is_productive === null && '— Neutral'}
+                      {category.exclude_from_export && ' · Private'}
+                      {category.is_archived && ' · Archived'}
                     </p>
                   </div>
                   
@@ -1,5 +1,7 @@
-import React from 'react';
+import React, { useState } from 'react';
 import { Settings as SettingsType } from '../../types';
+import { updatesApi } from '../../services/api/updates';
+import { handleApiError, showInfo } from '../../utils/toast';
 import Toggle from '../Common/Toggle';
 import Button from '../Common/Button';
 
@@ -18,6 +20,24 @@ export const GeneralSettings: React.FC<GeneralSettingsProps> = ({
   onSave,
   isSaving,
 }) => {
+  const [isCheckingUpdates, setIsCheckingUpdates] = useState(false);
+
+  const handleCheckForUpdates = async () => {
+    setIsCheckingUpdates(true);
+    try {
+      const info = await updatesApi.checkForUpdates();
+      showInfo(
+        info.update_available
+          ? `Version ${info.latest_version} is available (you have ${info.current_version})`
+          : `You are on the latest version (${info.current_version})`
+      );
+    } catch (error) {
+      handleApiError(error, 'Failed to check for updates');
+    } finally {
+      setIsCheckingUpdates(false);
+    }
+  };
+
   return (
     <div className="space-y-6">
       {/* Application Settings */}
@@ -73,6 +93,18 @@ export const GeneralSettings: React.FC<GeneralSettingsProps> = ({
             label="Marketplace"
             description="Show plugin marketplace in navigation"
           />
+
+          <div className="flex items-center justify-between gap-4">
+            <Toggle
+              checked={localSettings.update_check_enabled ?? true}
+              onChange={(checked) => onSettingChange('update_check_enabled', checked)}
+              label="Check for Updates"
+              description="Look for new releases once a day and show what changed"
+            />
+            <Button variant="secondary" size="sm" onClick={handleCheckForUpdates} disabled={isCheckingUpdates}>
+              {isCheckingUpdates ? 'Checking...' : 'Check now'}
+            </Button>
+          </div>
         </div>
       </div>
       
@@ -144,6 +176,20 @@ export const GeneralSettings: React.FC<GeneralSettingsProps> = ({
               Time before showing idle prompt dialog
             </p>
           </div>
+
+          <Toggle
+            checked={localSettings.capture_context_ref ?? false}
+            onChange={(checked) => onSettingChange('capture_context_ref', checked)}
+            label="Capture Documents"
+            description="Remember which file, document or URL a window shows (from its title) to report time per document"
+          />
+
+          <Toggle
+            checked={localSettings.record_input_intensity ?? false}
+            onChange={(checked) => onSettingChange('record_input_intensity', checked)}
+            label="Record Input Intensity"
+            description="Count how much of each activity had keyboard or mouse input, to tell active work from reading or watching"
+          />
         </div>
       </div>
       
@@ -1,7 +1,9 @@
 import React, { useState, useEffect } from 'react';
-import { Rule, Category } from '../../types';
+import { Rule, Category, RuleImportStrategy } from '../../types';
+import { pickableCategories } from '../../utils/categories';
 import Button from '../Common/Button';
-import { Check, X, Trash2, Edit2, RotateCcw } from 'lucide-react';
+import { Check, X, Trash2, Edit2, RotateCcw, Download, Upload } from 'lucide-react';
+import { exportRulePack, importRulePack } from '../../utils/export';
 import { useRules } from '../../hooks/useRules';
 import { useStore } from '../../store';
 import { activitiesApi } from '../../services/api/activities';
@@ -17,6 +19,7 @@ export const RulesSettings: React.FC = () => {
   const [editingRuleId, setEditingRuleId] = useState<number | null>(null);
   const [isCreatingRule, setIsCreatingRule] = useState(false);
   const [isReapplyingRules, setIsReapplyingRules] = useState(false);
+  const [importStrategy, setImportStrategy] = useState<RuleImportStrategy>('skip');
   const [newRule, setNewRule] = useState<Partial<Rule>>({
     rule_type: 'app_name',
     pattern: '',
@@ -194,6 +197,16 @@ export const RulesSettings: React.FC = () => {
     }
   };
 
+  const handleImportRules = async () => {
+    const summary = await importRulePack(importStrategy);
+    if (summary) {
+      queryClient.invalidateQueries({ queryKey: ['rules'] });
+      if (summary.categories_created.length) {
+        queryClient.invalidateQueries({ queryKey: ['categories'] });
+      }
+    }
+  };
+
   const getRuleTypeLabel = (type: string) => {
     switch (type) {
       case 'app_name': return '📱 Application';
@@ -213,6 +226,24 @@ export const RulesSettings: React.FC = () => {
           </p>
         </div>
         <div className="flex items-center gap-4">
+          <Button variant="secondary" size="sm" onClick={exportRulePack}>
+            <Download className="w-4 h-4 mr-1" />
+            Export
+          </Button>
+          <select
+            value={importStrategy}
+            onChange={(e) => setImportStrategy(e.target.value as RuleImportStrategy)}
+            title="Rules that already exist"
+            className="px-2 py-1 text-sm border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
+          >
+            <option value="skip">Keep existing</option>
+            <option value="merge">Merge priorities</option>
+            <option value="overwrite">Overwrite</option>
+          </select>
+          <Button variant="secondary" size="sm" onClick={handleImportRules}>
+            <Upload className="w-4 h-4 mr-1" />
+            Import
+          </Button>
           <Button 
             variant="secondary" 
             size="sm"
@@ -281,7 +312,7 @@ export const RulesSettings: React.FC = () => {
                   {categories.length === 0 ? (
                     <option value="">Loading categories...</option>
                   ) : (
-                    categories.map(cat => (
+                    pickableCategories(categories, newRule.category_id).map(cat => (
                       <option key={cat.id} value={cat.id}>
                         {cat.icon} {cat.name}
                       </option>
@@ -405,7 +436,7 @@ export const RulesSettings: React.FC = () => {
                             }}
                             className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-lg bg-white dark:bg-gray-700 text-gray-900 dark:text-white"
                           >
-                            {categories.map(cat => (
+                            {pickableCategories(categories, newRule.category_id).map(cat => (
                               <option key={cat.id} value={cat.id}>
                                 {cat.icon} {cat.name}
                               </option>
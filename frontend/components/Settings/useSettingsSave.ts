@@ -30,6 +30,9 @@ export function useSettingsSave() {
         date_format: localSettings.date_format || 'YYYY-MM-DD',
         time_format: localSettings.time_format || '24h',
         plugin_registry_urls: localSettings.plugin_registry_urls,
+        update_check_enabled: localSettings.update_check_enabled ?? true,
+        capture_context_ref: localSettings.capture_context_ref ?? false,
+        record_input_intensity: localSettings.record_input_intensity ?? false,
       };
       
       await settingsApi.updateSettings(backendSettings);
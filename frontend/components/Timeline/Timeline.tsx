@@ -178,6 +178,14 @@ export default function Timeline({ blocks }: TimelineProps) {
                         </svg>
                         {formatDuration(duration)}
                       </p>
+                      {(block.activity_count ?? 1) > 1 && (
+                        <p className="text-gray-400">{block.activity_count} activities merged</p>
+                      )}
+                      {block.top_titles?.map((title) => (
+                        <p key={title.title} className="text-gray-400">
+                          {truncate(title.title, 40)} · {formatDuration(title.duration_sec)}
+                        </p>
+                      ))}
                       {block.category && (
                         <p className="flex items-center gap-1.5">
                           <span className="w-2 h-2 rounded-full" style={{ backgroundColor: baseColor }} />
@@ -0,0 +1,51 @@
+import React from 'react';
+import { open } from '@tauri-apps/api/shell';
+import Button from '../Common/Button';
+import type { UpdateInfo } from '../../types';
+
+interface UpdateNoticeProps {
+  update: UpdateInfo;
+  onClose: () => void;
+}
+
+const UpdateNotice: React.FC<UpdateNoticeProps> = ({ update, onClose }) => {
+  const handleViewRelease = () => {
+    open(update.release_url).catch(() => {});
+    onClose();
+  };
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center">
+      {/* Backdrop */}
+      <div className="absolute inset-0 bg-black/50 backdrop-blur-sm" onClick={onClose} />
+
+      {/* Modal */}
+      <div className="relative bg-white dark:bg-gray-800 rounded-2xl shadow-2xl p-6 w-full max-w-lg mx-4 animate-scale-in">
+        <h2 className="text-xl font-semibold text-gray-900 dark:text-white">
+          Version {update.latest_version} is available
+        </h2>
+        <p className="text-sm text-gray-500 dark:text-gray-400 mb-4">
+          You are running {update.current_version}
+          {update.release_name && update.release_name !== update.latest_version && ` · ${update.release_name}`}
+        </p>
+
+        {update.changelog && (
+          <div className="max-h-64 overflow-y-auto rounded-lg bg-gray-50 dark:bg-gray-900/50 p-3 mb-6 text-sm text-gray-700 dark:text-gray-300 whitespace-pre-wrap">
+            {update.changelog}
+          </div>
+        )}
+
+        <div className="flex gap-3">
+          <Button variant="primary" className="flex-1" onClick={handleViewRelease}>
+            View release
+          </Button>
+          <Button variant="secondary" className="flex-1" onClick={onClose}>
+            Later
+          </Button>
+        </div>
+      </div>
+    </div>
+  );
+};
+
+export default UpdateNotice;
@@ -0,0 +1 @@
+export { default as UpdateNotice } from './UpdateNotice';
@@ -1,7 +1,10 @@
-export { useActivities, useDailyStats, useStatsForRange, useTimeline, useUpdateActivityCategory, useDeleteActivity, useTodayTotal } from './useActivities';
-export { useCategories, useCreateCategory, useUpdateCategory, useDeleteCategory, useResetSystemCategory, usePinnedCategories } from './useCategories';
+export { useActivities, useDailyStats, useDashboard, useStatsForRange, useTimeline, useUpdateActivityCategory, useDeleteActivity, useTodayTotal } from './useActivities';
+export { useCategories, useCreateCategory, useUpdateCategory, useDeleteCategory, useResetSystemCategory, useSetCategoryArchived, usePinnedCategories } from './useCategories';
 export { useSettings, useUpdateSettings } from './useSettings';
 export { useRules } from './useRules';
+export { useDomainRatings, useSetDomainRating } from './useDomainRatings';
+export { useProfiles, useActiveProfile, useHiddenCategoryIds, useSwitchProfile } from './useProfiles';
+export { useWorkspaces, useSwitchWorkspace } from './useWorkspaces';
 export { useTrackerStatus, usePauseTracking, useResumeTracking, useStartThinkingMode, useStopThinkingMode } from './useTracker';
 export { useManualEntries, useCreateManualEntry, useUpdateManualEntry, useDeleteManualEntry } from './useManualEntries';
 export { usePlugins } from './usePlugins';
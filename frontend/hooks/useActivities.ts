@@ -1,10 +1,11 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { useMemo } from 'react';
 import { statsApi } from '../services/api/stats';
+import { dashboardApi } from '../services/api/dashboard';
 import { activitiesApi } from '../services/api/activities';
 import { trackingApi } from '../services/api/tracking';
 import { useStore } from '../store';
-import type { TimelineBlock, StatsResponse } from '../types';
+import type { DateRange, TimelineBlock, StatsResponse } from '../types';
 
 // Helper function to add timeout to promises
 const withTimeout = <T>(promise: Promise<T>, timeoutMs: number): Promise<T> => {
@@ -69,6 +70,17 @@ export function useDailyStats(date?: Date) {
   });
 }
 
+/** KPI cards for one day, computed in a single backend call; disabled without a date */
+export function useDashboard(date?: Date) {
+  return useQuery({
+    queryKey: ['dashboard', date?.getTime()],
+    queryFn: () => withTimeout(dashboardApi.getDashboard(date as Date), 10000),
+    enabled: !!date,
+    retry: 1,
+    gcTime: 5 * 60 * 1000,
+  });
+}
+
 /** Aggregated stats for a date range (multi-day). Uses get_stats backend (SQL aggregation). */
 export function useStatsForRange(range: { start: Date; end: Date } | null) {
   const isMultiDay = range && range.start.toDateString() !== range.end.toDateString();
@@ -88,32 +100,41 @@ export function useStatsForRange(range: { start: Date; end: Date } | null) {
   });
 }
 
-export function useTimeline(_date?: Date) {
-  const { data: activities, isLoading, error } = useActivities();
+/** Blocks shorter than this are folded into the block before them */
+const TIMELINE_MIN_BLOCK_SEC = 30;
+
+/** Timeline blocks for a range, merged on the backend */
+export function useTimeline(range: DateRange, minBlockSec: number = TIMELINE_MIN_BLOCK_SEC) {
   const categories = useStore((state) => state.categories);
+  const startTime = range.start.getTime();
+  const endTime = range.end.getTime();
+  const { data: segments, isLoading, error } = useQuery({
+    queryKey: ['timeline', startTime, endTime, minBlockSec],
+    queryFn: () => withTimeout(activitiesApi.getTimeline(range, minBlockSec), 10000),
+    retry: 1,
+    retryDelay: 1000,
+    gcTime: 5 * 60 * 1000,
+  });
   
   const timelineData = useMemo(() => {
-    if (!activities || activities.length === 0) {
+    if (!segments || segments.length === 0) {
       return [];
     }
     
-    // Derive timeline blocks from activities
-    return activities.map((activity) => {
-      const category = activity.category_id 
-        ? categories.find(c => c.id === activity.category_id) || null
-        : null;
-      
-      return {
-        start: activity.started_at * 1000, // Convert to milliseconds
-        end: (activity.started_at + activity.duration_sec) * 1000,
-        app_name: activity.app_name,
-        domain: activity.domain,
-        category: category,
-        is_idle: activity.is_idle,
-        is_manual: false,
-      } as TimelineBlock;
-    });
-  }, [activities, categories]);
+    return segments.map((segment): TimelineBlock => ({
+      start: segment.started_at * 1000, // Convert to milliseconds
+      end: segment.ended_at * 1000,
+      app_name: segment.app_name,
+      domain: segment.domain,
+      category: segment.category_id !== null
+        ? categories.find(c => c.id === segment.category_id) || null
+        : null,
+      is_idle: segment.is_idle,
+      is_manual: false,
+      activity_count: segment.activity_count,
+      top_titles: segment.top_titles,
+    }));
+  }, [segments, categories]);
   
   return {
     data: timelineData,
@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { categoriesApi } from '../services/api/categories';
+import { profilesApi } from '../services/api/profiles';
 import { useStore } from '../store';
 import { useEffect } from 'react';
 
@@ -32,6 +33,7 @@ export function useCreateCategory() {
       sort_order: number;
       is_system?: boolean;
       is_pinned?: boolean;
+      exclude_from_export?: boolean;
     }) => categoriesApi.createCategory(category),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['categories'] });
@@ -52,6 +54,7 @@ export function useUpdateCategory() {
       is_productive: boolean | null;
       sort_order: number;
       is_pinned?: boolean;
+      exclude_from_export?: boolean;
     }) => categoriesApi.updateCategory(category),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['categories'] });
@@ -87,8 +90,28 @@ export function useResetSystemCategory() {
 export function usePinnedCategories() {
   const query = useQuery({
     queryKey: ['pinnedCategories'],
-    queryFn: () => categoriesApi.getPinnedCategories(),
+    queryFn: async () => {
+      // Leave out categories hidden by the active settings profile
+      const [pinned, hidden] = await Promise.all([
+        categoriesApi.getPinnedCategories(),
+        profilesApi.getHiddenCategoryIds(),
+      ]);
+      return pinned.filter((category) => !hidden.includes(category.id));
+    },
   });
 
   return query;
 }
+
+export function useSetCategoryArchived() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({ id, archived }: { id: number; archived: boolean }) =>
+      archived ? categoriesApi.archiveCategory(id) : categoriesApi.unarchiveCategory(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['categories'] });
+      queryClient.invalidateQueries({ queryKey: ['pinnedCategories'] });
+    },
+  });
+}
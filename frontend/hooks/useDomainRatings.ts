@@ -0,0 +1,26 @@
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { domainsApi } from '../services/api/domains';
+import type { DomainRatingValue } from '../types';
+
+export function useDomainRatings() {
+  return useQuery({
+    queryKey: ['domainRatings'],
+    queryFn: () => domainsApi.getDomainRatings(),
+  });
+}
+
+export function useSetDomainRating() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    // `null` removes the rating
+    mutationFn: ({ domain, rating }: { domain: string; rating: DomainRatingValue | null }) =>
+      rating ? domainsApi.setDomainRating(domain, rating) : domainsApi.deleteDomainRating(domain),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['domainRatings'] });
+      queryClient.invalidateQueries({ queryKey: ['dailyStats'] });
+      queryClient.invalidateQueries({ queryKey: ['dashboard'] });
+      queryClient.invalidateQueries({ queryKey: ['statsRange'] });
+    },
+  });
+}
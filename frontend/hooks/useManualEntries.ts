@@ -48,6 +48,7 @@ export function useUpdateManualEntry() {
       category_id: number | null;
       started_at: number;
       ended_at: number;
+      billable_override?: boolean | null;
     }) => manualEntriesApi.updateManualEntry(entry),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['manualEntries'] });
@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import type { RegistryPlugin, PluginManifest } from '../types/plugin';
+import type { RegistryPlugin, PluginManifest, PluginCategory, PluginSort } from '../types/plugin';
 import { handleApiError } from '../utils/toast';
 import { isTauriAvailable } from '../utils/tauri';
 
@@ -13,6 +13,7 @@ const invoke = async <T>(cmd: string, args?: Record<string, unknown>): Promise<T
 
 export function usePluginRegistry() {
   const [plugins, setPlugins] = useState<RegistryPlugin[]>([]);
+  const [categories, setCategories] = useState<PluginCategory[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
@@ -24,8 +25,12 @@ export function usePluginRegistry() {
         setError('Plugin registry requires the desktop application.');
         return;
       }
-      const result = await invoke<RegistryPlugin[]>('get_plugin_registry');
+      const [result, categoryList] = await Promise.all([
+        invoke<RegistryPlugin[]>('get_plugin_registry'),
+        invoke<PluginCategory[]>('get_plugin_categories'),
+      ]);
       setPlugins(result);
+      setCategories(categoryList);
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : String(err);
       handleApiError(err, 'Failed to load plugin registry');
@@ -39,23 +44,31 @@ export function usePluginRegistry() {
     fetchRegistry();
   }, []);
 
-  const searchPlugins = async (query: string) => {
+  // Filtering runs against the backend's cached index, so it is cheap per keystroke
+  const searchPlugins = async (query: string, category?: string | null, sort?: PluginSort) => {
     try {
-      setIsLoading(true);
       setError(null);
-      if (query.trim()) {
-        const result = await invoke<RegistryPlugin[]>('search_plugins', { query });
-        setPlugins(result);
-      } else {
-        // Reset to full registry
-        await fetchRegistry();
-      }
+      const result = await invoke<RegistryPlugin[]>('search_plugins', {
+        request: {
+          query,
+          category: category ?? null,
+          sort: sort ?? null,
+        },
+      });
+      setPlugins(result);
     } catch (err) {
       const errorMessage = err instanceof Error ? err.message : String(err);
       handleApiError(err, 'Failed to search plugins');
       setError(errorMessage || 'Failed to search plugins');
-    } finally {
-      setIsLoading(false);
+    }
+  };
+
+  const refreshIndex = async () => {
+    try {
+      await invoke<number>('refresh_plugin_index');
+      await fetchRegistry();
+    } catch (err) {
+      handleApiError(err, 'Failed to refresh plugin index');
     }
   };
 
@@ -81,10 +94,12 @@ export function usePluginRegistry() {
 
   return {
     plugins,
+    categories,
     isLoading,
     error,
     refetch: fetchRegistry,
     searchPlugins,
+    refreshIndex,
     getPluginInfo,
     discoverPlugin,
   };
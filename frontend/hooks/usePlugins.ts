@@ -1,5 +1,5 @@
 import { useState, useEffect } from 'react';
-import type { InstalledPlugin } from '../types/plugin';
+import type { InstalledPlugin, PluginMetrics } from '../types/plugin';
 import { handleApiError } from '../utils/toast';
 import { isTauriAvailable } from '../utils/tauri';
 
@@ -13,6 +13,7 @@ const invoke = async <T>(cmd: string, args?: Record<string, unknown>): Promise<T
 
 export function usePlugins() {
   const [plugins, setPlugins] = useState<InstalledPlugin[]>([]);
+  const [metrics, setMetrics] = useState<PluginMetrics[]>([]);
   const [isLoading, setIsLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
 
@@ -35,13 +36,34 @@ export function usePlugins() {
     }
   };
 
+  const fetchMetrics = async () => {
+    if (!isTauriAvailable()) {
+      return;
+    }
+    try {
+      setMetrics(await invoke<PluginMetrics[]>('get_plugin_metrics'));
+    } catch (err) {
+      handleApiError(err, 'Failed to load plugin metrics');
+    }
+  };
+
+  const resetMetrics = async () => {
+    try {
+      await invoke('reset_plugin_metrics');
+      setMetrics([]);
+    } catch (err) {
+      handleApiError(err, 'Failed to reset plugin metrics');
+    }
+  };
+
   useEffect(() => {
     fetchPlugins();
+    fetchMetrics();
   }, []);
 
-  const installPlugin = async (repositoryUrl: string, version?: string) => {
+  const installPlugin = async (repositoryUrl: string) => {
     try {
-      await invoke('install_plugin', { repositoryUrl, version });
+      await invoke('install_plugin', { repositoryUrl });
       await fetchPlugins();
       return true;
     } catch (err) {
@@ -98,6 +120,9 @@ export function usePlugins() {
     isLoading,
     error,
     refetch: fetchPlugins,
+    metrics,
+    refetchMetrics: fetchMetrics,
+    resetMetrics,
     installPlugin,
     uninstallPlugin,
     enablePlugin,
@@ -0,0 +1,38 @@
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { profilesApi } from '../services/api/profiles';
+
+export function useProfiles() {
+  return useQuery({
+    queryKey: ['profiles'],
+    queryFn: () => profilesApi.getProfiles(),
+  });
+}
+
+export function useActiveProfile() {
+  return useQuery({
+    queryKey: ['activeProfile'],
+    queryFn: () => profilesApi.getActiveProfile(),
+  });
+}
+
+export function useHiddenCategoryIds() {
+  return useQuery({
+    queryKey: ['hiddenCategoryIds'],
+    queryFn: () => profilesApi.getHiddenCategoryIds(),
+  });
+}
+
+export function useSwitchProfile() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (name: string) => profilesApi.switchProfile(name),
+    onSuccess: () => {
+      // A profile can change any setting and the visible categories
+      queryClient.invalidateQueries({ queryKey: ['activeProfile'] });
+      queryClient.invalidateQueries({ queryKey: ['hiddenCategoryIds'] });
+      queryClient.invalidateQueries({ queryKey: ['settings'] });
+      queryClient.invalidateQueries({ queryKey: ['pinnedCategories'] });
+    },
+  });
+}
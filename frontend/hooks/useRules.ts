@@ -1,5 +1,6 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { rulesApi } from '../services/api/rules';
+import { Rule, SimilarRuleAction } from '../types';
 
 export const useRules = () => {
   const queryClient = useQueryClient();
@@ -14,7 +15,8 @@ export const useRules = () => {
   });
 
   const createMutation = useMutation({
-    mutationFn: rulesApi.createRule,
+    mutationFn: ({ rule, onSimilar }: { rule: Omit<Rule, 'id'>; onSimilar?: SimilarRuleAction }) =>
+      rulesApi.createRule(rule, onSimilar),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['rules'] });
     },
@@ -34,11 +36,21 @@ export const useRules = () => {
     },
   });
 
+  // Offer to merge into a similar rule (e.g. `chrome*` next to `Chrome`) instead of adding another
+  const createRule = async (rule: Omit<Rule, 'id'>): Promise<Rule> => {
+    const similar = await rulesApi.findSimilarRule(rule.rule_type, rule.pattern);
+    const onSimilar: SimilarRuleAction =
+      similar && confirm(`A similar rule already exists ("${similar.pattern}"). Merge into it instead of adding another?`)
+        ? 'merge'
+        : 'create';
+    return createMutation.mutateAsync({ rule, onSimilar });
+  };
+
   return {
     rules,
     isLoading,
     error,
-    createRule: createMutation.mutateAsync,
+    createRule,
     updateRule: updateMutation.mutateAsync,
     deleteRule: deleteMutation.mutateAsync,
   };
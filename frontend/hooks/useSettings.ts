@@ -2,6 +2,7 @@ import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import { settingsApi } from '../services/api/settings';
 import { useStore } from '../store';
 import { useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import type { Settings } from '../types';
 
 export function useSettings() {
@@ -27,6 +28,24 @@ export function useSettings() {
     }
   }, [query.data, setSettings]);
 
+  // Settings can change outside this window (profiles, plugins, other commands)
+  const queryClient = useQueryClient();
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+    listen('settings-changed', () => {
+      queryClient.invalidateQueries({ queryKey: ['settings'] });
+    })
+      .then((fn) => {
+        unlisten = fn;
+      })
+      .catch(() => {
+        // Running in browser without Tauri - silently ignore
+      });
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, [queryClient]);
+
   return query;
 }
 
@@ -1,7 +1,8 @@
 import { useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
 import { useStore, type View } from '../store';
-import { showSuccess, handleApiError } from '../utils/toast';
+import type { UpdateInfo } from '../types';
+import { showSuccess, showInfo, handleApiError } from '../utils/toast';
 
 const VALID_VIEWS: View[] = ['dashboard', 'history', 'reports', 'settings', 'marketplace'];
 
@@ -12,6 +13,10 @@ interface UseTauriEventsOptions {
   onOpenManualEntry?: () => void;
   onStartThinkingMode?: () => void;
   onTogglePause?: () => void;
+  onFocusBlocked?: (appName: string) => void;
+  onCheckinPrompt?: () => void;
+  onOpenIdleSettings?: () => void;
+  onUpdateAvailable?: (info: UpdateInfo) => void;
 }
 
 export function useTauriEvents(options: UseTauriEventsOptions = {}) {
@@ -22,6 +27,10 @@ export function useTauriEvents(options: UseTauriEventsOptions = {}) {
     onOpenManualEntry,
     onStartThinkingMode,
     onTogglePause,
+    onFocusBlocked,
+    onCheckinPrompt,
+    onOpenIdleSettings,
+    onUpdateAvailable,
   } = options;
 
   useEffect(() => {
@@ -31,6 +40,12 @@ export function useTauriEvents(options: UseTauriEventsOptions = {}) {
     let unlistenOpenManualEntry: (() => void) | undefined;
     let unlistenStartThinkingMode: (() => void) | undefined;
     let unlistenTogglePause: (() => void) | undefined;
+    let unlistenAutoResumed: (() => void) | undefined;
+    let unlistenFocusBlocked: (() => void) | undefined;
+    let unlistenCheckinPrompt: (() => void) | undefined;
+    let unlistenBoundaryAlert: (() => void) | undefined;
+    let unlistenOpenIdleSettings: (() => void) | undefined;
+    let unlistenUpdateAvailable: (() => void) | undefined;
 
     const setupListeners = async () => {
       try {
@@ -95,7 +110,7 @@ export function useTauriEvents(options: UseTauriEventsOptions = {}) {
               store.setIsTrackingPaused(false);
               showSuccess('Tracking resumed');
             } else {
-              await invoke('pause_tracking');
+              await invoke('pause_tracking', { request: {} });
               store.setIsTrackingPaused(true);
               showSuccess('Tracking paused');
             }
@@ -104,6 +119,44 @@ export function useTauriEvents(options: UseTauriEventsOptions = {}) {
             handleApiError(error, 'Failed to toggle tracking');
           }
         });
+
+        // Listen for timed pauses running out (the backend has closed the pause entry)
+        unlistenAutoResumed = await listen('tracking-auto-resumed', () => {
+          const store = useStore.getState();
+          store.setIsTrackingPaused(false);
+          showSuccess('Pause ended, tracking resumed');
+          onTogglePause?.();
+        });
+
+        // Listen for blocked apps during pomodoro work sessions
+        unlistenFocusBlocked = await listen<{ app_name: string }>('focus-blocked', (event) => {
+          onFocusBlocked?.(event.payload.app_name);
+        });
+
+        // Listen for random productivity check-ins
+        unlistenCheckinPrompt = await listen('checkin-prompt', () => {
+          onCheckinPrompt?.();
+        });
+
+        // Listen for work outside work hours going over the threshold
+        unlistenBoundaryAlert = await listen<{ kind: string; seconds: number }>('boundary-alert', (event) => {
+          const minutes = Math.round(event.payload.seconds / 60);
+          showInfo(
+            event.payload.kind === 'weekend'
+              ? `You've worked ${minutes} minutes this weekend`
+              : `You've worked ${minutes} minutes in quiet hours today`,
+          );
+        });
+
+        // Listen for the idle prompt window's "change when this popup appears" link
+        unlistenOpenIdleSettings = await listen('open-idle-settings', () => {
+          onOpenIdleSettings?.();
+        });
+
+        // Listen for the daily update check finding a new release
+        unlistenUpdateAvailable = await listen<UpdateInfo>('update-available', (event) => {
+          onUpdateAvailable?.(event.payload);
+        });
       } catch (error) {
         // Running in browser without Tauri - silently ignore
       }
@@ -118,6 +171,12 @@ export function useTauriEvents(options: UseTauriEventsOptions = {}) {
       if (unlistenOpenManualEntry) unlistenOpenManualEntry();
       if (unlistenStartThinkingMode) unlistenStartThinkingMode();
       if (unlistenTogglePause) unlistenTogglePause();
+      if (unlistenAutoResumed) unlistenAutoResumed();
+      if (unlistenFocusBlocked) unlistenFocusBlocked();
+      if (unlistenCheckinPrompt) unlistenCheckinPrompt();
+      if (unlistenBoundaryAlert) unlistenBoundaryAlert();
+      if (unlistenOpenIdleSettings) unlistenOpenIdleSettings();
+      if (unlistenUpdateAvailable) unlistenUpdateAvailable();
     };
-  }, [onIdleReturn, onActivityUpdate, onNavigate, onOpenManualEntry, onStartThinkingMode, onTogglePause]);
+  }, [onIdleReturn, onActivityUpdate, onNavigate, onOpenManualEntry, onStartThinkingMode, onTogglePause, onFocusBlocked, onCheckinPrompt, onOpenIdleSettings, onUpdateAvailable]);
 }
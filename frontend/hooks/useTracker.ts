@@ -1,5 +1,5 @@
 import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
-import { trackingApi } from '../services/api/tracking';
+import { trackingApi, type PauseOptions } from '../services/api/tracking';
 
 export function useTrackerStatus() {
   return useQuery({
@@ -13,7 +13,7 @@ export function usePauseTracking() {
   const queryClient = useQueryClient();
   
   return useMutation({
-    mutationFn: () => trackingApi.pauseTracking(),
+    mutationFn: (options?: PauseOptions) => trackingApi.pauseTracking(options),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['trackerStatus'] });
     },
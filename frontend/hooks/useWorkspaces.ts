@@ -0,0 +1,21 @@
+import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
+import { workspaceApi } from '../services/api/workspace';
+
+export function useWorkspaces() {
+  return useQuery({
+    queryKey: ['workspaces'],
+    queryFn: () => workspaceApi.getWorkspaces(),
+  });
+}
+
+export function useSwitchWorkspace() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (path: string) => workspaceApi.switchWorkspace(path),
+    onSuccess: () => {
+      // Every query reads from the workspace database
+      queryClient.invalidateQueries();
+    },
+  });
+}
@@ -4,6 +4,9 @@ import { QueryClient, QueryClientProvider } from '@tanstack/react-query';
 import App from './App';
 import CustomToaster from './components/Common/CustomToast';
 import { ErrorBoundary } from './components/Common/ErrorBoundary';
+import { MiniTimer } from './components/MiniTimer';
+import { IdlePromptWindow } from './components/IdlePrompt';
+import { MicroBreakWindow } from './components/MicroBreak';
 import './index.css';
 
 const queryClient = new QueryClient({
@@ -109,16 +112,45 @@ updateSplashStatus('Preparing application...');
 const rootElement = document.getElementById('root')!;
 const root = ReactDOM.createRoot(rootElement);
 
-root.render(
-  <React.StrictMode>
-    <ErrorBoundary>
+// Secondary windows load the same bundle with a `window` query parameter
+const secondaryWindow = new URLSearchParams(window.location.search).get('window');
+
+if (secondaryWindow === 'mini-timer') {
+  hideSplashScreen();
+  root.render(
+    <React.StrictMode>
+      <MiniTimer />
+    </React.StrictMode>,
+  );
+} else if (secondaryWindow === 'idle-prompt') {
+  hideSplashScreen();
+  root.render(
+    <React.StrictMode>
       <QueryClientProvider client={queryClient}>
-        <App />
+        <IdlePromptWindow />
         <CustomToaster />
       </QueryClientProvider>
-    </ErrorBoundary>
-  </React.StrictMode>,
-);
+    </React.StrictMode>,
+  );
+} else if (secondaryWindow === 'micro-break') {
+  hideSplashScreen();
+  root.render(
+    <React.StrictMode>
+      <MicroBreakWindow />
+    </React.StrictMode>,
+  );
+} else {
+  root.render(
+    <React.StrictMode>
+      <ErrorBoundary>
+        <QueryClientProvider client={queryClient}>
+          <App />
+          <CustomToaster />
+        </QueryClientProvider>
+      </ErrorBoundary>
+    </React.StrictMode>,
+  );
+}
 
 // Update status when React starts mounting
 requestAnimationFrame(() => {
@@ -1,4 +1,4 @@
-import { Activity, DateRange } from '../../types';
+import { Activity, ActivityCategoryRef, ActivityPage, DateRange, ManualEntry, RuleRunReport, TimelineSegment } from '../../types';
 import { invoke, dateRangeToParams } from './utils';
 
 export const activitiesApi = {
@@ -13,8 +13,9 @@ export const activitiesApi = {
    * - getActivities(range) - returns all activities
    * - getActivities(range, 100) - returns first 100 activities
    * - getActivities(range, 100, 50) - returns 100 activities starting from 50th
+   * - getActivities(range, undefined, undefined, ['tracker']) - only tracked activities
    */
-  getActivities: (range: DateRange, limit?: number, offset?: number): Promise<Activity[]> => {
+  getActivities: (range: DateRange, limit?: number, offset?: number, sources?: string[]): Promise<Activity[]> => {
     const params: Record<string, unknown> = {
       ...dateRangeToParams(range),
     };
@@ -22,7 +23,67 @@ export const activitiesApi = {
     // This allows the backend to handle: limit only, limit+offset, or neither
     if (limit !== undefined) params.limit = limit;
     if (offset !== undefined) params.offset = offset;
-    return invoke('get_activities', params);
+    if (sources !== undefined) params.sources = sources;
+    return invoke('get_activities', { request: params });
+  },
+
+  /**
+   * Get activities page by page with a keyset cursor.
+   * Pass the previous page's next_cursor to continue; stable while the tracker is writing.
+   */
+  getActivitiesPage: (
+    range: DateRange,
+    cursor: string | null = null,
+    limit?: number,
+    sources?: string[],
+  ): Promise<ActivityPage> => {
+    return invoke('get_activities_page', {
+      request: {
+        ...dateRangeToParams(range),
+        cursor,
+        limit: limit ?? null,
+        sources: sources ?? null,
+      },
+    });
+  },
+
+  /**
+   * Get only selected activity fields to keep IPC payloads small on large ranges.
+   * With includeCategory, each activity also carries a compact `category` object.
+   *
+   * Example (timeline): getActivityFields(range, ['started_at', 'duration_sec'], { includeCategory: true })
+   */
+  getActivityFields: <K extends keyof Activity>(
+    range: DateRange,
+    fields: K[],
+    options: { includeCategory?: boolean; sources?: string[] } = {},
+  ): Promise<Array<Pick<Activity, K> & { category?: ActivityCategoryRef | null }>> => {
+    return invoke('get_activities', {
+      request: {
+        ...dateRangeToParams(range),
+        fields,
+        include: options.includeCategory ? ['category'] : null,
+        sources: options.sources ?? null,
+      },
+    });
+  },
+
+  /**
+   * Get activities merged into timeline blocks on the backend.
+   * Blocks shorter than minBlockSec are folded into the block before them.
+   */
+  getTimeline: (range: DateRange, minBlockSec?: number): Promise<TimelineSegment[]> => {
+    return invoke('get_timeline', { request: { ...dateRangeToParams(range), min_block_sec: minBlockSec ?? null } });
+  },
+
+  /**
+   * Get only selected manual entry fields.
+   */
+  getManualEntryFields: <K extends keyof ManualEntry>(
+    range: DateRange,
+    fields: K[],
+  ): Promise<Array<Pick<ManualEntry, K>>> => {
+    return invoke('get_manual_entries', { request: { ...dateRangeToParams(range), fields } });
   },
   
   getActivityById: (id: number): Promise<Activity | null> => {
@@ -30,7 +91,18 @@ export const activitiesApi = {
   },
   
   updateActivityCategory: (activityId: number, categoryId: number): Promise<void> => {
-    return invoke('update_activity_category', { activityId, categoryId });
+    return invoke('update_activity_category', { request: { activity_id: activityId, category_id: categoryId } });
+  },
+  
+  /** Bulk edit: `null` makes the entries follow their project/category again */
+  setBillableOverride: (
+    activityIds: number[],
+    manualEntryIds: number[],
+    billableOverride: boolean | null
+  ): Promise<number> => {
+    return invoke('set_billable_override', {
+      request: { activity_ids: activityIds, manual_entry_ids: manualEntryIds, billable_override: billableOverride },
+    });
   },
   
   deleteActivity: (id: number): Promise<void> => {
@@ -40,4 +112,14 @@ export const activitiesApi = {
   reapplyCategorizationRules: (): Promise<void> => {
     return invoke('reapply_categorization_rules');
   },
+
+  /** What the last reapplyCategorizationRules run changed; null before the first run */
+  getLastRuleRunReport: (): Promise<RuleRunReport | null> => {
+    return invoke('get_last_rule_run_report');
+  },
+
+  /** Undo the last rule run; returns the number of activities restored */
+  undoLastRuleRun: (): Promise<number> => {
+    return invoke('undo_last_rule_run');
+  },
 };
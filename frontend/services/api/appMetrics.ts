@@ -0,0 +1,12 @@
+import { AppUsageMetrics, DateRange } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const appMetricsApi = {
+  getAppUsage: (range: DateRange): Promise<AppUsageMetrics> => {
+    return invoke('get_app_usage', { request: dateRangeToParams(range) });
+  },
+
+  clearAppUsage: (): Promise<number> => {
+    return invoke('clear_app_usage');
+  },
+};
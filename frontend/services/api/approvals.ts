@@ -0,0 +1,29 @@
+import { ApprovalSummary, DateRange, EntryApproval } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const approvalsApi = {
+  submitEntriesForApproval: (range: DateRange, projectId?: number): Promise<ApprovalSummary> => {
+    return invoke('submit_entries_for_approval', {
+      request: {
+        ...dateRangeToParams(range),
+        project_id: projectId ?? null,
+      },
+    });
+  },
+
+  approveEntries: (manualEntryIds: number[], activityIds: number[], note?: string): Promise<ApprovalSummary> => {
+    return invoke('approve_entries', {
+      request: { manual_entry_ids: manualEntryIds, activity_ids: activityIds, note: note ?? null },
+    });
+  },
+
+  rejectEntries: (manualEntryIds: number[], activityIds: number[], reason?: string): Promise<ApprovalSummary> => {
+    return invoke('reject_entries', {
+      request: { manual_entry_ids: manualEntryIds, activity_ids: activityIds, reason: reason ?? null },
+    });
+  },
+
+  getEntryApprovals: (range: DateRange): Promise<EntryApproval[]> => {
+    return invoke('get_entry_approvals', { request: dateRangeToParams(range) });
+  },
+};
@@ -0,0 +1,20 @@
+import { BlocklistEntry, BlocklistKind, FocusBlockingStatus } from '../../types';
+import { invoke } from './utils';
+
+export const blocklistApi = {
+  getBlocklist: (): Promise<BlocklistEntry[]> => {
+    return invoke('get_blocklist');
+  },
+
+  addBlocklistEntry: (kind: BlocklistKind, pattern: string): Promise<number> => {
+    return invoke('add_blocklist_entry', { request: { kind, pattern } });
+  },
+
+  deleteBlocklistEntry: (id: number): Promise<void> => {
+    return invoke('delete_blocklist_entry', { id });
+  },
+
+  getFocusBlockingStatus: (): Promise<FocusBlockingStatus> => {
+    return invoke('get_focus_blocking_status');
+  },
+};
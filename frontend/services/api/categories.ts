@@ -1,4 +1,4 @@
-import { Category } from '../../types';
+import { Category, ColorContrast } from '../../types';
 import { invoke, boolToTauriNum } from './utils';
 
 export const categoriesApi = {
@@ -9,26 +9,32 @@ export const categoriesApi = {
   createCategory: (category: Omit<Category, 'id'>): Promise<Category> => {
     // Плагины должны использовать call_db_method для работы с этими полями
     return invoke('create_category', {
-      name: category.name,
-      color: category.color,
-      icon: category.icon ?? null,
-      isProductive: boolToTauriNum(category.is_productive),
-      sortOrder: category.sort_order,
-      isSystem: category.is_system ?? false,
-      isPinned: category.is_pinned ?? false,
+      request: {
+        name: category.name,
+        color: category.color,
+        icon: category.icon ?? null,
+        is_productive: boolToTauriNum(category.is_productive),
+        sort_order: category.sort_order,
+        is_system: category.is_system ?? false,
+        is_pinned: category.is_pinned ?? false,
+        exclude_from_export: category.exclude_from_export ?? false,
+      },
     });
   },
 
   updateCategory: (category: Category): Promise<Category> => {
     // Плагины должны использовать call_db_method для работы с этими полями
     return invoke('update_category', {
-      id: category.id,
-      name: category.name,
-      color: category.color,
-      icon: category.icon ?? null,
-      isProductive: boolToTauriNum(category.is_productive),
-      sortOrder: category.sort_order,
-      isPinned: category.is_pinned ?? false,
+      request: {
+        id: category.id,
+        name: category.name,
+        color: category.color,
+        icon: category.icon ?? null,
+        is_productive: boolToTauriNum(category.is_productive),
+        sort_order: category.sort_order,
+        is_pinned: category.is_pinned ?? false,
+        exclude_from_export: category.exclude_from_export ?? null,
+      },
     });
   },
   
@@ -39,9 +45,26 @@ export const categoriesApi = {
   resetSystemCategory: (id: number): Promise<Category> => {
     return invoke('reset_system_category', { id });
   },
+
+  archiveCategory: (id: number): Promise<Category> => {
+    return invoke('archive_category', { id });
+  },
+
+  unarchiveCategory: (id: number): Promise<Category> => {
+    return invoke('unarchive_category', { id });
+  },
+  
+  /** Readable color for a new category, unlike the colors already in use */
+  suggestCategoryColor: (): Promise<string> => {
+    return invoke('suggest_category_color');
+  },
+
+  checkCategoryColor: (color: string): Promise<ColorContrast> => {
+    return invoke('check_category_color', { color });
+  },
   
   getPinnedCategories: async (): Promise<Category[]> => {
     const categories = await invoke<Category[]>('get_categories');
-    return categories.filter(c => c.is_pinned === true);
+    return categories.filter(c => c.is_pinned === true && !c.is_archived);
   },
 };
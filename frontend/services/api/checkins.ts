@@ -0,0 +1,16 @@
+import { Checkin, CheckinStats, DateRange } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const checkinsApi = {
+  submitCheckin: (focused: boolean, energy?: number): Promise<number> => {
+    return invoke('submit_checkin', { request: { focused, energy: energy ?? null } });
+  },
+
+  getCheckins: (range: DateRange): Promise<Checkin[]> => {
+    return invoke('get_checkins', { request: dateRangeToParams(range) });
+  },
+
+  getCheckinStats: (range: DateRange): Promise<CheckinStats> => {
+    return invoke('get_checkin_stats', { request: dateRangeToParams(range) });
+  },
+};
@@ -0,0 +1,44 @@
+import { CustomFieldDef, CustomFieldEntityType, CustomFieldValue } from '../../types';
+import { invoke } from './utils';
+
+export const customFieldsApi = {
+  getCustomFieldDefs: (entityType?: CustomFieldEntityType): Promise<CustomFieldDef[]> => {
+    return invoke('get_custom_field_defs', { request: { entity_type: entityType ?? null } });
+  },
+
+  createCustomFieldDef: (def: Omit<CustomFieldDef, 'id'>): Promise<CustomFieldDef> => {
+    return invoke('create_custom_field_def', {
+      request: {
+        entity_type: def.entity_type,
+        name: def.name,
+        field_type: def.field_type,
+        options: def.options,
+        sort_order: def.sort_order,
+      },
+    });
+  },
+
+  updateCustomFieldDef: (def: Omit<CustomFieldDef, 'entity_type'>): Promise<CustomFieldDef> => {
+    return invoke('update_custom_field_def', {
+      request: {
+        id: def.id,
+        name: def.name,
+        field_type: def.field_type,
+        options: def.options,
+        sort_order: def.sort_order,
+      },
+    });
+  },
+
+  deleteCustomFieldDef: (id: number): Promise<void> => {
+    return invoke('delete_custom_field_def', { id });
+  },
+
+  getCustomFieldValues: (entityType: CustomFieldEntityType, entityId?: number): Promise<CustomFieldValue[]> => {
+    return invoke('get_custom_field_values', { request: { entity_type: entityType, entity_id: entityId ?? null } });
+  },
+
+  setCustomFieldValue: (fieldId: number, entityId: number, value: string | null): Promise<void> => {
+    return invoke('set_custom_field_value', { request: { field_id: fieldId, entity_id: entityId, value } });
+  },
+};
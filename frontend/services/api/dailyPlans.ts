@@ -0,0 +1,26 @@
+import { DailyPlan, DailyPlanItem, DailyPlanReview, DateRange } from '../../types';
+import { invoke, dateRangeToParams, dateToTimestamp } from './utils';
+
+export const dailyPlansApi = {
+  getDailyPlan: (day: Date): Promise<DailyPlan | null> => {
+    return invoke('get_daily_plan', { date: dateToTimestamp(day) });
+  },
+
+  getDailyPlans: (range: DateRange): Promise<DailyPlan[]> => {
+    return invoke('get_daily_plans', { request: dateRangeToParams(range) });
+  },
+
+  // Replaces the day's items; their order is kept
+  saveDailyPlan: (day: Date, items: DailyPlanItem[]): Promise<DailyPlan> => {
+    return invoke('save_daily_plan', { request: { date: dateToTimestamp(day), items } });
+  },
+
+  deleteDailyPlan: (day: Date): Promise<void> => {
+    return invoke('delete_daily_plan', { date: dateToTimestamp(day) });
+  },
+
+  // Plan vs tracked time for the end-of-day review; null if the day has no plan
+  getDailyPlanReview: (day: Date): Promise<DailyPlanReview | null> => {
+    return invoke('get_daily_plan_review', { date: dateToTimestamp(day) });
+  },
+};
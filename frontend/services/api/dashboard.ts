@@ -0,0 +1,18 @@
+import { Dashboard } from '../../types';
+import { invoke, dateToTimestamp } from './utils';
+
+export const dashboardApi = {
+  getDashboard: (date: Date): Promise<Dashboard> => {
+    const startOfDay = new Date(date);
+    startOfDay.setHours(0, 0, 0, 0);
+    return invoke('get_dashboard', { date: dateToTimestamp(startOfDay) });
+  },
+
+  getDashboardKpis: (): Promise<string[]> => {
+    return invoke('get_dashboard_kpis');
+  },
+
+  setDashboardKpis: (kpis: string[]): Promise<void> => {
+    return invoke('set_dashboard_kpis', { kpis });
+  },
+};
@@ -0,0 +1,9 @@
+import { DemoDataSummary, DemoProfile } from '../../types';
+import { invoke } from './utils';
+
+// Development builds only; replaces demo data from earlier calls
+export const demoDataApi = {
+  generateDemoData: (days: number, profile: DemoProfile): Promise<DemoDataSummary> => {
+    return invoke('generate_demo_data', { request: { days, profile } });
+  },
+};
@@ -0,0 +1,14 @@
+import { DatabaseRecovery, Diagnostics } from '../../types';
+import { invoke } from './utils';
+
+export const diagnosticsApi = {
+  /** Outcome of the startup database recovery, or null if the database was healthy */
+  getStartupDiagnostics: (): Promise<DatabaseRecovery | null> => {
+    return invoke('get_startup_diagnostics');
+  },
+
+  /** Database diagnostics, including slow queries with their query plans */
+  getDiagnostics: (): Promise<Diagnostics> => {
+    return invoke('get_diagnostics');
+  },
+};
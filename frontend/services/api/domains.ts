@@ -1,11 +1,33 @@
-import { DomainStat, DateRange } from '../../types';
+import { ContextStat, DomainRating, DomainRatingValue, DomainStat, DateRange, Job } from '../../types';
 import { invoke, dateRangeToParams } from './utils';
 
 export const domainsApi = {
+  // Queues a `domain_backfill` job; see jobsApi for its progress
+  backfillDomains: (range: DateRange): Promise<Job> => {
+    return invoke('backfill_domains', { request: dateRangeToParams(range) });
+  },
+
   getTopDomains: (range: DateRange, limit?: number): Promise<DomainStat[]> => {
     return invoke('get_top_domains', {
-      ...dateRangeToParams(range),
-      limit: limit ?? 10,
+      request: { ...dateRangeToParams(range), limit: limit ?? 10 },
+    });
+  },
+
+  getTopContexts: (range: DateRange, limit?: number): Promise<ContextStat[]> => {
+    return invoke('get_top_contexts', {
+      request: { ...dateRangeToParams(range), limit: limit ?? 10 },
     });
   },
+
+  getDomainRatings: (): Promise<DomainRating[]> => {
+    return invoke('get_domain_ratings');
+  },
+
+  setDomainRating: (domain: string, rating: DomainRatingValue): Promise<void> => {
+    return invoke('set_domain_rating', { request: { domain, rating } });
+  },
+
+  deleteDomainRating: (domain: string): Promise<void> => {
+    return invoke('delete_domain_rating', { domain });
+  },
 };
@@ -1,18 +1,92 @@
-import { DateRange } from '../../types';
+import { AnonymizeSummary, DateRange, ParquetExport, ReportProfile, TemplateInfo } from '../../types';
 import { invoke, dateRangeToParams } from './utils';
 
 export const exportApi = {
-  exportToCsv: (range: DateRange, filePath: string): Promise<void> => {
+  // Exports are written as AES-encrypted zip archives (`<filePath>.zip`) when given a
+  // passphrase (min. 8 characters) or when the export_passphrase setting is set;
+  // the file exports resolve to the path actually written
+  exportToCsv: (range: DateRange, filePath: string, sources?: string[], passphrase?: string): Promise<string> => {
     return invoke('export_to_csv', {
-      ...dateRangeToParams(range),
-      filePath: filePath,
+      request: {
+        ...dateRangeToParams(range),
+        file_path: filePath,
+        sources: sources ?? null,
+        passphrase: passphrase ?? null,
+      },
     });
   },
   
-  exportToJson: (range: DateRange, filePath: string): Promise<void> => {
+  exportToJson: (range: DateRange, filePath: string, sources?: string[], passphrase?: string): Promise<string> => {
     return invoke('export_to_json', {
-      ...dateRangeToParams(range),
-      filePath: filePath,
+      request: {
+        ...dateRangeToParams(range),
+        file_path: filePath,
+        sources: sources ?? null,
+        passphrase: passphrase ?? null,
+      },
     });
   },
+
+  exportToActivityWatch: (range: DateRange, filePath: string, sources?: string[], passphrase?: string): Promise<string> => {
+    return invoke('export_to_activitywatch', {
+      request: {
+        ...dateRangeToParams(range),
+        file_path: filePath,
+        sources: sources ?? null,
+        passphrase: passphrase ?? null,
+      },
+    });
+  },
+
+  exportParquet: (range: DateRange, directory: string, sources?: string[], passphrase?: string): Promise<ParquetExport> => {
+    return invoke('export_parquet', {
+      request: {
+        ...dateRangeToParams(range),
+        path: directory,
+        sources: sources ?? null,
+        passphrase: passphrase ?? null,
+      },
+    });
+  },
+
+  writeDailyNote: (date: number, pathTemplate?: string): Promise<string> => {
+    return invoke('write_daily_note', { request: { date, path_template: pathTemplate ?? null } });
+  },
+
+  publishReport: (
+    range: DateRange,
+    profile: ReportProfile,
+    filePath: string,
+    options: { title?: string; sources?: string[] } = {}
+  ): Promise<void> => {
+    return invoke('publish_report', {
+      request: {
+        ...dateRangeToParams(range),
+        profile,
+        file_path: filePath,
+        title: options.title ?? null,
+        sources: options.sources ?? null,
+      },
+    });
+  },
+
+  anonymizeCopy: (filePath: string): Promise<AnonymizeSummary> => {
+    return invoke('anonymize_copy', { path: filePath });
+  },
+
+  getTemplates: (): Promise<TemplateInfo[]> => {
+    return invoke('get_templates');
+  },
+
+  getTemplate: (name: string): Promise<string> => {
+    return invoke('get_template', { name });
+  },
+
+  saveTemplate: (name: string, source: string): Promise<void> => {
+    return invoke('save_template', { request: { name, source } });
+  },
+
+  resetTemplate: (name: string): Promise<void> => {
+    return invoke('reset_template', { name });
+  },
 };
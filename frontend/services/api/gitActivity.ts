@@ -0,0 +1,16 @@
+import { DateRange, RepositoryActivity } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const gitActivityApi = {
+  getGitRepositories: (): Promise<string[]> => {
+    return invoke('get_git_repositories');
+  },
+
+  setGitRepositories: (paths: string[]): Promise<void> => {
+    return invoke('set_git_repositories', { paths });
+  },
+
+  getCommitTimeline: (range: DateRange): Promise<RepositoryActivity[]> => {
+    return invoke('get_commit_timeline', { request: dateRangeToParams(range) });
+  },
+};
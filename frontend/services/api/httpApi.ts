@@ -0,0 +1,14 @@
+import { HttpApiInfo } from '../../types';
+import { invoke } from './utils';
+
+export const httpApiApi = {
+  // The token is created on first call
+  getHttpApiInfo: (): Promise<HttpApiInfo> => {
+    return invoke('get_http_api_info');
+  },
+
+  // Clients using the old token are rejected afterwards
+  resetHttpApiToken: (): Promise<HttpApiInfo> => {
+    return invoke('reset_http_api_token');
+  },
+};
@@ -1,4 +1,5 @@
 import { invoke, dateToTimestamp } from './utils';
+import type { IdleChunk, IdleSegment } from '../../types';
 
 export const idleApi = {
   getIdleTime: (): Promise<number> => {
@@ -12,10 +13,33 @@ export const idleApi = {
     description?: string
   ): Promise<void> => {
     return invoke('classify_idle_time', {
-      idle_start: dateToTimestamp(idleStart),
-      idle_end: dateToTimestamp(idleEnd),
-      classification,
-      description,
+      request: {
+        idle_start: dateToTimestamp(idleStart),
+        idle_end: dateToTimestamp(idleEnd),
+        classification,
+        description: description ?? null,
+      },
     });
   },
+
+  submitIdleActivity: (startedAt: number, chunks: IdleChunk[]): Promise<void> => {
+    return invoke('submit_idle_activity', {
+      request: {
+        category_id: chunks[0].category_id,
+        comment: chunks[0].comment ?? null,
+        started_at: startedAt,
+        project_id: chunks[0].project_id ?? null,
+        task_id: chunks[0].task_id ?? null,
+        chunks,
+      },
+    });
+  },
+
+  classifyIdleSegments: (segments: IdleSegment[]): Promise<void> => {
+    return invoke('classify_idle_segments', { segments });
+  },
+
+  dismissIdlePrompt: (): Promise<void> => {
+    return invoke('dismiss_idle_prompt');
+  },
 };
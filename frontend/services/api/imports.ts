@@ -0,0 +1,8 @@
+import { ImportSummary, OsUsageImportFormat } from '../../types';
+import { invoke } from './utils';
+
+export const importsApi = {
+  importOsUsage: (filePath: string, format: OsUsageImportFormat): Promise<ImportSummary> => {
+    return invoke('import_os_usage', { request: { file_path: filePath, format } });
+  },
+};
@@ -2,42 +2,114 @@
 export * from './activities';
 export * from './categories';
 export * from './rules';
+export * from './taskHints';
+export * from './suggestions';
 export * from './manualEntries';
+export * from './approvals';
+export * from './rounding';
+export * from './customFields';
 export * from './settings';
 export * from './stats';
 export * from './tracking';
 export * from './idle';
 export * from './export';
+export * from './imports';
 export * from './window';
 export * from './domains';
+export * from './gitActivity';
+export * from './terminal';
+export * from './blocklist';
+export * from './checkins';
+export * from './microBreaks';
+export * from './mood';
+export * from './dailyPlans';
+export * from './profiles';
+export * from './workspace';
+export * from './diagnostics';
+export * from './jobs';
+export * from './updates';
+export * from './appMetrics';
+export * from './dashboard';
+export * from './webhooks';
+export * from './httpApi';
+export * from './replay';
+export * from './demoData';
 export * from './utils';
 
 // Import individual APIs
 import { activitiesApi } from './activities';
 import { categoriesApi } from './categories';
 import { rulesApi } from './rules';
+import { taskHintsApi } from './taskHints';
+import { suggestionsApi } from './suggestions';
 import { manualEntriesApi } from './manualEntries';
+import { approvalsApi } from './approvals';
+import { roundingApi } from './rounding';
+import { customFieldsApi } from './customFields';
 import { settingsApi } from './settings';
 import { statsApi } from './stats';
 import { trackingApi } from './tracking';
 import { idleApi } from './idle';
 import { exportApi } from './export';
+import { importsApi } from './imports';
 import { windowApi } from './window';
 import { domainsApi } from './domains';
+import { gitActivityApi } from './gitActivity';
+import { terminalApi } from './terminal';
+import { blocklistApi } from './blocklist';
+import { checkinsApi } from './checkins';
+import { microBreaksApi } from './microBreaks';
+import { moodApi } from './mood';
+import { dailyPlansApi } from './dailyPlans';
+import { profilesApi } from './profiles';
+import { workspaceApi } from './workspace';
+import { diagnosticsApi } from './diagnostics';
+import { jobsApi } from './jobs';
+import { updatesApi } from './updates';
+import { appMetricsApi } from './appMetrics';
+import { dashboardApi } from './dashboard';
+import { webhooksApi } from './webhooks';
+import { httpApiApi } from './httpApi';
+import { replayApi } from './replay';
+import { demoDataApi } from './demoData';
 
 // Combined API object for backward compatibility
 export const api: {
   activities: typeof activitiesApi;
   categories: typeof categoriesApi;
   rules: typeof rulesApi;
+  taskHints: typeof taskHintsApi;
+  suggestions: typeof suggestionsApi;
   manualEntries: typeof manualEntriesApi;
+  approvals: typeof approvalsApi;
+  rounding: typeof roundingApi;
+  customFields: typeof customFieldsApi;
   settings: typeof settingsApi;
   stats: typeof statsApi;
   tracking: typeof trackingApi;
   idle: typeof idleApi;
   export: typeof exportApi;
+  imports: typeof importsApi;
   window: typeof windowApi;
   domains: typeof domainsApi;
+  gitActivity: typeof gitActivityApi;
+  terminal: typeof terminalApi;
+  blocklist: typeof blocklistApi;
+  checkins: typeof checkinsApi;
+  microBreaks: typeof microBreaksApi;
+  mood: typeof moodApi;
+  dailyPlans: typeof dailyPlansApi;
+  profiles: typeof profilesApi;
+  workspace: typeof workspaceApi;
+  diagnostics: typeof diagnosticsApi;
+  jobs: typeof jobsApi;
+  updates: typeof updatesApi;
+  appMetrics: typeof appMetricsApi;
+  dashboard: typeof dashboardApi;
+  webhooks: typeof webhooksApi;
+  httpApi: typeof httpApiApi;
+  replay: typeof replayApi;
+  demoData: typeof demoDataApi;
   getRules: typeof rulesApi.getRules;
   createRule: typeof rulesApi.createRule;
   updateRule: typeof rulesApi.updateRule;
@@ -46,14 +118,38 @@ export const api: {
   activities: activitiesApi,
   categories: categoriesApi,
   rules: rulesApi,
+  taskHints: taskHintsApi,
+  suggestions: suggestionsApi,
   manualEntries: manualEntriesApi,
+  approvals: approvalsApi,
+  rounding: roundingApi,
+  customFields: customFieldsApi,
   settings: settingsApi,
   stats: statsApi,
   tracking: trackingApi,
   idle: idleApi,
   export: exportApi,
+  imports: importsApi,
   window: windowApi,
   domains: domainsApi,
+  gitActivity: gitActivityApi,
+  terminal: terminalApi,
+  blocklist: blocklistApi,
+  checkins: checkinsApi,
+  microBreaks: microBreaksApi,
+  mood: moodApi,
+  dailyPlans: dailyPlansApi,
+  profiles: profilesApi,
+  workspace: workspaceApi,
+  diagnostics: diagnosticsApi,
+  jobs: jobsApi,
+  updates: updatesApi,
+  appMetrics: appMetricsApi,
+  dashboard: dashboardApi,
+  webhooks: webhooksApi,
+  httpApi: httpApiApi,
+  replay: replayApi,
+  demoData: demoDataApi,
   // Convenience methods for backward compatibility
   getRules: rulesApi.getRules,
   createRule: rulesApi.createRule,
@@ -0,0 +1,13 @@
+import { Job } from '../../types';
+import { invoke } from './utils';
+
+export const jobsApi = {
+  listJobs: (limit?: number): Promise<Job[]> => {
+    return invoke('list_jobs', { request: { limit: limit ?? null } });
+  },
+
+  // Queued jobs stop right away, running ones after their current step
+  cancelJob: (id: number): Promise<Job> => {
+    return invoke('cancel_job', { id });
+  },
+};
@@ -1,9 +1,9 @@
-import { ManualEntry, DateRange } from '../../types';
+import { ManualEntry, ManualEntryDraft, DateRange } from '../../types';
 import { invoke, dateRangeToParams } from './utils';
 
 export const manualEntriesApi = {
   getManualEntries: (range: DateRange): Promise<ManualEntry[]> => {
-    return invoke('get_manual_entries', dateRangeToParams(range));
+    return invoke('get_manual_entries', { request: dateRangeToParams(range) });
   },
   
   createManualEntry: (entry: {
@@ -14,10 +14,12 @@ export const manualEntriesApi = {
     // Плагины должны использовать call_db_method для работы с этими полями
   }): Promise<ManualEntry> => {
     return invoke('create_manual_entry', {
-      description: entry.description,
-      categoryId: entry.category_id,
-      startedAt: entry.started_at,
-      endedAt: entry.ended_at,
+      request: {
+        description: entry.description,
+        category_id: entry.category_id,
+        started_at: entry.started_at,
+        ended_at: entry.ended_at,
+      },
     });
   },
   
@@ -27,14 +29,18 @@ export const manualEntriesApi = {
     category_id: number | null;
     started_at: number;
     ended_at: number;
+    billable_override?: boolean | null;
     // Плагины должны использовать call_db_method для работы с этими полями
   }): Promise<ManualEntry> => {
     return invoke('update_manual_entry', {
-      id: entry.id,
-      description: entry.description,
-      categoryId: entry.category_id,
-      startedAt: entry.started_at,
-      endedAt: entry.ended_at,
+      request: {
+        id: entry.id,
+        description: entry.description,
+        category_id: entry.category_id,
+        started_at: entry.started_at,
+        ended_at: entry.ended_at,
+        billable_override: entry.billable_override ?? null,
+      },
     });
   },
   
@@ -43,10 +49,14 @@ export const manualEntriesApi = {
   },
   
   startManualEntry: (categoryId: number, description?: string): Promise<number> => {
-    return invoke('start_manual_entry', { categoryId, description });
+    return invoke('start_manual_entry', { request: { category_id: categoryId, description: description ?? null } });
   },
   
   stopManualEntry: (): Promise<ManualEntry> => {
     return invoke('stop_manual_entry');
   },
+
+  parseTimeEntry: (text: string): Promise<ManualEntryDraft> => {
+    return invoke('parse_time_entry', { text });
+  },
 };
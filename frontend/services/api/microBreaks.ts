@@ -0,0 +1,12 @@
+import { DateRange, MicroBreakStats } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const microBreaksApi = {
+  finishMicroBreak: (id: number, skipped: boolean): Promise<void> => {
+    return invoke('finish_micro_break', { request: { id, skipped } });
+  },
+
+  getMicroBreakStats: (range: DateRange): Promise<MicroBreakStats> => {
+    return invoke('get_micro_break_stats', { request: dateRangeToParams(range) });
+  },
+};
@@ -0,0 +1,16 @@
+import { DateRange, MoodEntry } from '../../types';
+import { invoke, dateRangeToParams, dateToTimestamp } from './utils';
+
+export const moodApi = {
+  logMood: (time: Date, score: number, note?: string): Promise<number> => {
+    return invoke('log_mood', { request: { timestamp: dateToTimestamp(time), score, note: note ?? null } });
+  },
+
+  getMoodEntries: (range: DateRange): Promise<MoodEntry[]> => {
+    return invoke('get_mood_entries', { request: dateRangeToParams(range) });
+  },
+
+  deleteMoodEntry: (id: number): Promise<void> => {
+    return invoke('delete_mood_entry', { id });
+  },
+};
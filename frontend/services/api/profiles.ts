@@ -0,0 +1,34 @@
+import { SettingsProfile } from '../../types';
+import { invoke } from './utils';
+
+export const profilesApi = {
+  getProfiles: (): Promise<SettingsProfile[]> => {
+    return invoke('get_profiles');
+  },
+
+  saveProfile: (profile: SettingsProfile): Promise<void> => {
+    return invoke('save_profile', { profile });
+  },
+
+  deleteProfile: (name: string): Promise<void> => {
+    return invoke('delete_profile', { name });
+  },
+
+  switchProfile: (name: string): Promise<void> => {
+    return invoke('switch_profile', { name });
+  },
+
+  getActiveProfile: (): Promise<string | null> => {
+    return invoke('get_active_profile');
+  },
+
+  /** Categories hidden by the active profile */
+  getHiddenCategoryIds: async (): Promise<number[]> => {
+    const value = await invoke<string | null>('get_setting', { key: 'hidden_category_ids' });
+    try {
+      return value ? (JSON.parse(value) as number[]) : [];
+    } catch {
+      return [];
+    }
+  },
+};
@@ -0,0 +1,17 @@
+import { ReplayStatus } from '../../types';
+import { invoke } from './utils';
+
+// Development builds only; release builds reject startReplay
+export const replayApi = {
+  startReplay: (filePath: string, speed?: number): Promise<ReplayStatus> => {
+    return invoke('start_replay', { request: { file_path: filePath, speed: speed ?? null } });
+  },
+
+  stopReplay: (): Promise<void> => {
+    return invoke('stop_replay');
+  },
+
+  getReplayStatus: (): Promise<ReplayStatus> => {
+    return invoke('get_replay_status');
+  },
+};
@@ -0,0 +1,11 @@
+import { DateRange, RoundingPolicy, RoundingPreview } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const roundingApi = {
+  // Applies the policy to each manual entry and timeline block of activities in the range without changing them
+  previewRounding: (range: DateRange, policy: RoundingPolicy): Promise<RoundingPreview> => {
+    return invoke('preview_rounding', {
+      request: { ...dateRangeToParams(range), policy },
+    });
+  },
+};
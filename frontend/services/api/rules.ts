@@ -1,4 +1,4 @@
-import { Rule } from '../../types';
+import { Rule, RuleImportStrategy, RuleImportSummary, SimilarRuleAction } from '../../types';
 import { invoke } from './utils';
 
 export const rulesApi = {
@@ -6,26 +6,53 @@ export const rulesApi = {
     return invoke('get_rules');
   },
   
-  createRule: (rule: Omit<Rule, 'id'>): Promise<Rule> => {
+  createRule: (rule: Omit<Rule, 'id'>, onSimilar?: SimilarRuleAction): Promise<Rule> => {
     return invoke('create_rule', {
-      ruleType: rule.rule_type,
-      pattern: rule.pattern,
-      categoryId: rule.category_id,
-      priority: rule.priority,
+      request: {
+        rule_type: rule.rule_type,
+        pattern: rule.pattern,
+        category_id: rule.category_id,
+        priority: rule.priority,
+        project_id: rule.project_id ?? null,
+        on_similar: onSimilar ?? 'reject',
+      },
+    });
+  },
+
+  findSimilarRule: (ruleType: string, pattern: string, excludeId?: number): Promise<Rule | null> => {
+    return invoke('find_similar_rule', {
+      request: { rule_type: ruleType, pattern, exclude_id: excludeId ?? null },
     });
   },
   
   updateRule: (rule: Rule): Promise<Rule> => {
     return invoke('update_rule', {
-      id: rule.id,
-      ruleType: rule.rule_type,
-      pattern: rule.pattern,
-      categoryId: rule.category_id,
-      priority: rule.priority,
+      request: {
+        id: rule.id,
+        rule_type: rule.rule_type,
+        pattern: rule.pattern,
+        category_id: rule.category_id,
+        priority: rule.priority,
+        project_id: rule.project_id ?? null,
+      },
     });
   },
   
   deleteRule: (id: number): Promise<void> => {
     return invoke('delete_rule', { id });
   },
+
+  exportRules: (filePath: string, categoryIds?: number[], name?: string): Promise<number> => {
+    return invoke('export_rules', {
+      request: {
+        file_path: filePath,
+        category_ids: categoryIds ?? null,
+        name: name ?? null,
+      },
+    });
+  },
+
+  importRules: (filePath: string, strategy: RuleImportStrategy): Promise<RuleImportSummary> => {
+    return invoke('import_rules', { request: { file_path: filePath, strategy } });
+  },
 };
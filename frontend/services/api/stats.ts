@@ -1,4 +1,4 @@
-import { DailyStats, AppUsage, CategoryUsage, HourlyActivity, DateRange, StatsResponse } from '../../types';
+import { DailyStats, AppUsage, CategoryUsage, CodingStats, HourlyActivity, DateRange, InputIntensityStat, DeviceSummary, StatsResponse, WellnessStats, BoundaryReport } from '../../types';
 import { invoke, dateRangeToParams, dateToTimestamp } from './utils';
 
 export const statsApi = {
@@ -9,15 +9,19 @@ export const statsApi = {
     return invoke('get_daily_stats', { date: dateToTimestamp(startOfDay) });
   },
   
-  getTopApps: (range: DateRange, limit?: number): Promise<AppUsage[]> => {
+  // halfLifeDays ranks by decayed time (recent use first), e.g. 7 for "frequent apps" pickers
+  getTopApps: (range: DateRange, limit?: number, halfLifeDays?: number): Promise<AppUsage[]> => {
     return invoke('get_top_apps', {
-      ...dateRangeToParams(range),
-      limit: limit || 10,
+      request: {
+        ...dateRangeToParams(range),
+        limit: limit || 10,
+        half_life_days: halfLifeDays ?? null,
+      },
     });
   },
   
   getCategoryUsage: (range: DateRange): Promise<CategoryUsage[]> => {
-    return invoke('get_category_usage', dateRangeToParams(range));
+    return invoke('get_category_usage', { request: dateRangeToParams(range) });
   },
   
   getHourlyActivity: (date: Date): Promise<HourlyActivity[]> => {
@@ -27,10 +31,32 @@ export const statsApi = {
   },
   
   getProductiveTime: (range: DateRange): Promise<number> => {
-    return invoke('get_productive_time', dateRangeToParams(range));
+    return invoke('get_productive_time', { request: dateRangeToParams(range) });
   },
 
-  getStats: (range: DateRange): Promise<StatsResponse> => {
-    return invoke('get_stats', dateRangeToParams(range));
+  getStats: (range: DateRange, sources?: string[], devices?: string[]): Promise<StatsResponse> => {
+    return invoke('get_stats', {
+      request: { ...dateRangeToParams(range), sources: sources ?? null, devices: devices ?? null },
+    });
+  },
+
+  getDevices: (): Promise<DeviceSummary[]> => {
+    return invoke('get_devices');
+  },
+
+  getCodingStats: (range: DateRange): Promise<CodingStats> => {
+    return invoke('get_coding_stats', { request: dateRangeToParams(range) });
+  },
+
+  getInputIntensity: (range: DateRange): Promise<InputIntensityStat[]> => {
+    return invoke('get_input_intensity', { request: dateRangeToParams(range) });
+  },
+
+  getWellnessStats: (range: DateRange): Promise<WellnessStats> => {
+    return invoke('get_wellness_stats', { request: dateRangeToParams(range) });
+  },
+
+  getBoundaryReport: (weekStart: Date): Promise<BoundaryReport> => {
+    return invoke('get_boundary_report', { weekStart: dateToTimestamp(weekStart) });
   },
 };
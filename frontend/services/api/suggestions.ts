@@ -0,0 +1,41 @@
+import { CategorizeGroupResult, CategorySuggestion, DateRange, SimilarActivity, UncategorizedGroup } from '../../types';
+import { invoke, dateRangeToParams } from './utils';
+
+export const suggestionsApi = {
+  getCategorySuggestions: (activityId: number): Promise<CategorySuggestion[]> => {
+    return invoke('get_category_suggestions', { activityId });
+  },
+
+  /**
+   * Apply the best suggestion to uncategorized activities in the range.
+   * @param minConfidence - Optional: required confidence (backend default 0.9)
+   * @returns Promise resolving to the number of activities categorized
+   */
+  acceptCategorySuggestions: (range: DateRange, minConfidence?: number): Promise<number> => {
+    return invoke('accept_category_suggestions', {
+      request: { ...dateRangeToParams(range), min_confidence: minConfidence ?? null },
+    });
+  },
+
+  findSimilarActivities: (activityId: number, minSimilarity?: number, limit?: number): Promise<SimilarActivity[]> => {
+    return invoke('find_similar_activities', {
+      request: { activity_id: activityId, min_similarity: minSimilarity ?? null, limit: limit ?? null },
+    });
+  },
+
+  applyCategoryToActivities: (activityIds: number[], categoryId: number | null): Promise<void> => {
+    return invoke('apply_category_to_activities', { request: { activity_ids: activityIds, category_id: categoryId } });
+  },
+
+  getUncategorizedSummary: (range: DateRange): Promise<UncategorizedGroup[]> => {
+    return invoke('get_uncategorized_summary', { request: dateRangeToParams(range) });
+  },
+
+  categorizeGroup: (
+    group: Pick<UncategorizedGroup, 'kind' | 'name'>,
+    categoryId: number,
+    createRule: boolean
+  ): Promise<CategorizeGroupResult> => {
+    return invoke('categorize_group', { request: { kind: group.kind, name: group.name, category_id: categoryId, create_rule: createRule } });
+  },
+};
@@ -0,0 +1,16 @@
+import { TaskHint } from '../../types';
+import { invoke } from './utils';
+
+export const taskHintsApi = {
+  getTaskHints: (): Promise<TaskHint[]> => {
+    return invoke('get_task_hints');
+  },
+
+  addTaskHint: (keyword: string, taskId: number): Promise<number> => {
+    return invoke('add_task_hint', { request: { keyword, task_id: taskId } });
+  },
+
+  deleteTaskHint: (id: number): Promise<void> => {
+    return invoke('delete_task_hint', { id });
+  },
+};
@@ -0,0 +1,16 @@
+import { TerminalReport } from '../../types';
+import { invoke } from './utils';
+
+export const terminalApi = {
+  reportTerminalActivity: (report: TerminalReport): Promise<void> => {
+    return invoke('report_terminal_activity', {
+      report: {
+        host: report.host,
+        cwd: report.cwd,
+        project: report.project ?? null,
+        remote: report.remote ?? false,
+        timestamp: report.timestamp ?? null,
+      },
+    });
+  },
+};
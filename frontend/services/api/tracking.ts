@@ -1,19 +1,35 @@
 import { invoke } from './utils';
 
+export interface PauseOptions {
+  durationMin?: number;
+  until?: number;
+  reason?: string;
+  categoryId?: number;
+}
+
 export const trackingApi = {
   getTodayTotal: (): Promise<number> => {
     return invoke('get_today_total');
   },
   
-  pauseTracking: (): Promise<void> => {
-    return invoke('pause_tracking');
+  // Pass `durationMin` or `until` (Unix seconds) for a pause that ends by itself, and
+  // `reason`/`categoryId` to record the paused time as a manual entry
+  pauseTracking: (options: PauseOptions = {}): Promise<void> => {
+    return invoke('pause_tracking', {
+      request: {
+        duration_min: options.durationMin ?? null,
+        until: options.until ?? null,
+        reason: options.reason ?? null,
+        category_id: options.categoryId ?? null,
+      },
+    });
   },
   
   resumeTracking: (): Promise<void> => {
     return invoke('resume_tracking');
   },
   
-  getTrackingStatus: (): Promise<{ isTracking: boolean; isPaused: boolean; currentApp: string | null }> => {
+  getTrackingStatus: (): Promise<{ isTracking: boolean; isPaused: boolean; pausedUntil: number | null; currentApp: string | null }> => {
     return invoke('get_tracking_status');
   },
   
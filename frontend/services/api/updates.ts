@@ -0,0 +1,9 @@
+import { UpdateInfo } from '../../types';
+import { invoke } from './utils';
+
+export const updatesApi = {
+  /** Compare this build with the latest GitHub release */
+  checkForUpdates: (): Promise<UpdateInfo> => {
+    return invoke('check_for_updates');
+  },
+};
@@ -0,0 +1,42 @@
+import { Webhook, WebhookDelivery } from '../../types';
+import { invoke } from './utils';
+
+export const webhooksApi = {
+  getWebhooks: (): Promise<Webhook[]> => {
+    return invoke('get_webhooks');
+  },
+
+  createWebhook: (webhook: { url: string; secret: string | null; events: string[] }): Promise<Webhook> => {
+    return invoke('create_webhook', {
+      request: {
+        url: webhook.url,
+        secret: webhook.secret,
+        events: webhook.events,
+      },
+    });
+  },
+
+  updateWebhook: (webhook: Omit<Webhook, 'created_at'>): Promise<Webhook> => {
+    return invoke('update_webhook', {
+      request: {
+        id: webhook.id,
+        url: webhook.url,
+        secret: webhook.secret,
+        events: webhook.events,
+        enabled: webhook.enabled,
+      },
+    });
+  },
+
+  deleteWebhook: (id: number): Promise<void> => {
+    return invoke('delete_webhook', { id });
+  },
+
+  getWebhookDeliveries: (webhookId?: number, limit?: number): Promise<WebhookDelivery[]> => {
+    return invoke('get_webhook_deliveries', { request: { webhook_id: webhookId ?? null, limit: limit ?? null } });
+  },
+
+  testWebhook: (id: number): Promise<number> => {
+    return invoke('test_webhook', { id });
+  },
+};
@@ -1,3 +1,4 @@
+import { AppEvent, MiniTimerCorner } from '../../types';
 import { invoke, dateToTimestamp } from './utils';
 
 export const windowApi = {
@@ -11,8 +12,24 @@ export const windowApi = {
   
   showIdlePrompt: (idleDuration: number, idleStart: Date): Promise<void> => {
     return invoke('show_idle_prompt', {
-      idle_duration: idleDuration,
-      idle_start: dateToTimestamp(idleStart),
+      request: { idle_duration: idleDuration, idle_start: dateToTimestamp(idleStart) },
     });
   },
+
+  showMiniTimer: (options: { corner?: MiniTimerCorner; clickThrough?: boolean } = {}): Promise<void> => {
+    return invoke('show_mini_timer', {
+      request: {
+        corner: options.corner ?? null,
+        click_through: options.clickThrough ?? null,
+      },
+    });
+  },
+
+  hideMiniTimer: (): Promise<void> => {
+    return invoke('hide_mini_timer');
+  },
+
+  getPomodoroStatus: (): Promise<AppEvent | null> => {
+    return invoke('get_pomodoro_status');
+  },
 };
@@ -0,0 +1,13 @@
+import { WorkspaceConfig } from '../../types';
+import { invoke } from './utils';
+
+export const workspaceApi = {
+  getWorkspaces: (): Promise<WorkspaceConfig> => {
+    return invoke('get_workspaces');
+  },
+
+  /** Switch to another database file (created if missing) */
+  switchWorkspace: (path: string): Promise<WorkspaceConfig> => {
+    return invoke('switch_workspace', { path });
+  },
+};
@@ -8,6 +8,46 @@ export interface Activity {
   started_at: number; // Unix timestamp
   duration_sec: number;
   is_idle: boolean;
+  source: string; // 'tracker', 'idle-classified', 'import:<format>', ...
+  billable_override?: boolean | null; // null follows the project/category
+  context_ref?: string | null; // document/file/URL parsed from the title
+  device_id?: string | null; // install it was recorded on; 'host:<name>' for imported history
+  device_name?: string | null;
+}
+
+// Compact category attached by get_activities with include: ['category']
+export interface ActivityCategoryRef {
+  id: number;
+  name: string;
+  color: string;
+}
+
+export interface ActivityPage<T = Activity> {
+  activities: T[];
+  next_cursor: string | null; // pass back as cursor; null on the last page
+}
+
+export interface CategorySuggestion {
+  category_id: number;
+  confidence: number; // 0..1
+}
+
+export interface SimilarActivity {
+  activity: Activity;
+  similarity: number; // 0..1
+}
+
+// Uncategorized time of one domain (activities with one) or app
+export interface UncategorizedGroup {
+  kind: 'app' | 'domain';
+  name: string;
+  activity_count: number;
+  duration_sec: number;
+}
+
+export interface CategorizeGroupResult {
+  updated: number; // activities categorized
+  rule_id: number | null; // rule created or merged into, with createRule
 }
 
 export interface ActivityWithCategory extends Activity {
@@ -24,6 +64,20 @@ export interface Category {
   sort_order: number;
   is_system?: boolean;
   is_pinned?: boolean;
+  /** Keep activities out of exports, published reports and webhooks */
+  exclude_from_export?: boolean;
+  /** Hidden from pickers and new rules; kept in history and stats */
+  is_archived?: boolean;
+}
+
+/** Contrast of a category color against the light and dark themes */
+export interface ColorContrast {
+  color: string;
+  light_ratio: number;
+  dark_ratio: number;
+  readable: boolean;
+  /** Readable color with the same hue, when this one is not readable */
+  suggestion: string | null;
 }
 
 // Rule types
@@ -35,6 +89,28 @@ export interface Rule {
   pattern: string;
   category_id: number;
   priority: number;
+  project_id?: number | null; // assigns matching activities to a project (projects plugin)
+}
+
+// Window title keyword (e.g. a ticket number) attaching activities to a task
+export interface TaskHint {
+  id: number;
+  keyword: string; // whole word, case-insensitive; the longest matching keyword wins
+  task_id: number;
+  created_at: number;
+}
+
+// Rule packs: what to do with imported rules similar to an existing one
+export type RuleImportStrategy = 'skip' | 'overwrite' | 'merge';
+
+// Creating a rule similar to an existing one (same type, same text ignoring case and wildcards)
+export type SimilarRuleAction = 'reject' | 'create' | 'merge';
+
+export interface RuleImportSummary {
+  added: number;
+  updated: number;
+  skipped: number;
+  categories_created: string[]; // categories the pack referred to that did not exist
 }
 
 // Manual entry types
@@ -44,6 +120,405 @@ export interface ManualEntry {
   category_id: number | null;
   started_at: number;
   ended_at: number;
+  source: string; // 'manual', 'idle-classified', 'plugin:<id>', ...
+  billable_override?: boolean | null; // null follows the project/category
+}
+
+export interface ManualEntryDraft {
+  description: string | null;
+  category_id: number | null;
+  project_id: number | null; // project of the projects plugin named in the text
+  started_at: number;
+  ended_at: number;
+  duration_sec: number;
+  tags: string[];
+}
+
+// Part of an idle period classified from the idle prompt
+export interface IdleChunk {
+  category_id: number;
+  comment?: string | null;
+  duration_sec?: number | null; // the last chunk covers the rest of the period
+  project_id?: number | null; // stored when the projects plugin is installed
+  task_id?: number | null;
+}
+
+// Idle period part with explicit bounds; segments must cover the period without overlap
+export interface IdleSegment {
+  started_at: number;
+  ended_at: number;
+  category_id: number;
+  comment?: string | null;
+  project_id?: number | null;
+  task_id?: number | null;
+}
+
+// Approval types
+export type ApprovalStatus = 'draft' | 'submitted' | 'approved' | 'rejected';
+
+export interface EntryApproval {
+  entity_type: 'manual_entry' | 'activity';
+  entity_id: number;
+  status: ApprovalStatus;
+  note: string | null;
+  submitted_at: number | null;
+  reviewed_at: number | null;
+}
+
+export interface ApprovalSummary {
+  manual_entries: number;
+  activities: number;
+}
+
+// Custom field types
+export type CustomFieldEntityType = 'activity' | 'manual_entry' | 'project' | 'task';
+
+export interface CustomFieldDef {
+  id: number;
+  entity_type: CustomFieldEntityType;
+  name: string;
+  field_type: 'text' | 'number' | 'select';
+  options: string[];
+  sort_order: number;
+}
+
+export interface CustomFieldValue {
+  field_id: number;
+  entity_id: number;
+  value: string;
+}
+
+// Webhook types
+export interface Webhook {
+  id: number;
+  url: string;
+  secret: string | null;
+  events: string[]; // exact names, 'prefix.*' or '*'; empty = all events
+  enabled: boolean;
+  created_at: number;
+}
+
+export interface WebhookDelivery {
+  id: number;
+  webhook_id: number;
+  event: string;
+  payload: string;
+  status: 'pending' | 'delivered' | 'failed';
+  attempts: number;
+  next_attempt_at: number;
+  last_error: string | null;
+  created_at: number;
+  delivered_at: number | null;
+}
+
+// REST API address and token (settings http_api_enabled / http_api_port)
+export interface HttpApiInfo {
+  enabled: boolean;
+  base_url: string; // e.g. http://127.0.0.1:9901/api/v1
+  token: string; // sent as `Authorization: Bearer <token>`
+  error: string | null; // why the enabled API is not listening, e.g. port taken (retried)
+}
+
+// Event published on the Core event bus
+export interface AppEvent {
+  name: string;
+  payload: Record<string, unknown>;
+  timestamp: number;
+}
+
+// Mini timer window types
+export type MiniTimerCorner = 'top-left' | 'top-right' | 'bottom-left' | 'bottom-right';
+
+// Git activity types
+export interface GitCommit {
+  hash: string;
+  timestamp: number;
+  author_name: string;
+  author_email: string;
+  subject: string;
+}
+
+export interface RepositoryActivity {
+  name: string;
+  path: string;
+  commit_count: number;
+  tracked_seconds: number; // tracked time whose window title mentions the repository
+  commits: GitCommit[];
+  error: string | null;
+}
+
+// Terminal session report (sent by scripts/terminal-hook.sh)
+export interface TerminalReport {
+  host: string;
+  cwd: string;
+  project?: string; // defaults to the last cwd component
+  remote?: boolean; // inside an SSH session
+  timestamp?: number;
+}
+
+// Progress of a recorded sample replay (development builds)
+export interface ReplayStatus {
+  running: boolean;
+  file: string | null;
+  speed: number;
+  total: number;
+  processed: number;
+  recorded: number; // samples written as activities
+  dropped: number; // samples dropped by plugins
+  idle: number; // samples taken while idle
+  idle_returns: number;
+  position: number | null; // timestamp of the last processed sample
+}
+
+// Demo data (development builds)
+export type DemoProfile = 'developer' | 'designer' | 'manager';
+
+export interface DemoDataSummary {
+  activities: number;
+  manual_entries: number;
+  skipped: number; // activities overlapping real ones
+  plugins: string[]; // plugins that added their own demo data
+}
+
+// Focus-mode blocklist types
+export type BlocklistKind = 'app' | 'domain';
+
+export interface BlocklistEntry {
+  id: number;
+  kind: BlocklistKind;
+  pattern: string; // app name substring or domain (also blocks www.)
+  created_at: number;
+}
+
+export interface FocusBlockingStatus {
+  active: boolean;
+  preset: string | null;
+  hosts_file_applied: boolean;
+}
+
+// Productivity check-in types
+export interface Checkin {
+  id: number;
+  created_at: number;
+  focused: boolean;
+  energy: number | null; // 1-5
+  activity_id: number | null; // activity current when answering
+  app_name: string | null;
+  category_id: number | null;
+}
+
+export interface CheckinBucket {
+  hour: number | null; // local hour of day for hourly buckets
+  category_id: number | null;
+  count: number;
+  focused_count: number;
+  average_energy: number | null;
+}
+
+export interface CheckinStats {
+  total: number;
+  focused_count: number;
+  average_energy: number | null;
+  by_hour: CheckinBucket[];
+  by_category: CheckinBucket[];
+}
+
+/** Micro-breaks offered in a range (see micro_breaks_enabled) */
+export interface MicroBreakStats {
+  offered: number;
+  completed: number;
+  skipped: number;
+  missed: number; // closed without an answer
+  compliance_percent: number | null; // null when none were offered
+}
+
+/** Screen time of one local day (get_wellness_stats) */
+export interface WellnessDay {
+  date: string; // YYYY-MM-DD
+  weekend: boolean;
+  screen_seconds: number;
+  late_night_seconds: number; // in quiet hours (quiet_hours_start to quiet_hours_end, default 22:00-06:00)
+}
+
+/** Stretch of work without a break of 5 minutes or more */
+export interface WorkSession {
+  started_at: number;
+  ended_at: number;
+  duration_sec: number;
+}
+
+/** Response from get_wellness_stats */
+export interface WellnessStats {
+  total_screen_seconds: number;
+  late_night_seconds: number;
+  weekend_seconds: number;
+  days: WellnessDay[];
+  longest_sessions: WorkSession[]; // longest first, top 5
+  micro_breaks: MicroBreakStats;
+}
+
+/** Day on which work outside work hours went over boundary_threshold_minutes */
+export interface BoundaryViolation {
+  day: string; // YYYY-MM-DD
+  kind: 'quiet_hours' | 'weekend';
+  seconds: number;
+  logged_at: number;
+}
+
+/** Response from get_boundary_report (one week) */
+export interface BoundaryReport {
+  week_start: string; // YYYY-MM-DD
+  days: WellnessDay[];
+  quiet_hours_seconds: number;
+  weekend_seconds: number; // 0 unless quiet_weekends
+  violations: BoundaryViolation[];
+}
+
+export type JobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled';
+
+/** Maintenance job (domain backfill, retention purge); progress arrives as `job-progress` events */
+export interface Job {
+  id: number;
+  kind: 'domain_backfill' | 'retention_purge' | string;
+  params: Record<string, unknown>;
+  status: JobStatus;
+  cursor: Record<string, unknown> | null; // domain_backfill: { after_id, updated }
+  processed: number;
+  total: number | null;
+  error: string | null;
+  cancel_requested: boolean;
+  created_at: number;
+  updated_at: number;
+  finished_at: number | null;
+}
+
+export type RoundingMode = 'up' | 'down' | 'nearest';
+
+/** How each entry's duration is rounded for billing */
+export interface RoundingPolicy {
+  increment_minutes: number; // 1-480
+  mode: RoundingMode;
+  minimum_minutes?: number | null; // shortest billed duration per entry
+}
+
+export interface RoundedEntry {
+  entity_type: 'activity' | 'manual_entry';
+  entity_id: number; // the manual entry, or the first activity of the block
+  label: string; // manual entry description or app name of the activity block
+  activity_count: number; // activities merged into the entry; 1 for a manual entry
+  category_id: number | null;
+  started_at: number;
+  raw_sec: number;
+  rounded_sec: number;
+  delta_sec: number;
+}
+
+export interface RoundingPreview {
+  entries: RoundedEntry[];
+  raw_total_sec: number;
+  rounded_total_sec: number;
+  delta_sec: number; // positive when rounding bills more
+}
+
+/** An activity the last categorization rule run moved to another category */
+export interface RuleRunChange {
+  activity_id: number;
+  old_category_id: number | null;
+  new_category_id: number | null;
+  app_name: string | null; // null once the activity was deleted
+  window_title: string | null;
+  started_at: number | null;
+  duration_sec: number;
+}
+
+/** Activities and time a category gained and lost in a rule run */
+export interface RuleRunCategoryDelta {
+  category_id: number | null;
+  activities_gained: number;
+  activities_lost: number;
+  seconds_gained: number;
+  seconds_lost: number;
+  net_seconds: number;
+}
+
+export interface RuleRunReport {
+  id: number;
+  ran_at: number;
+  activities_checked: number;
+  undone_at: number | null;
+  categories: RuleRunCategoryDelta[]; // largest net change first
+  changes: RuleRunChange[];
+}
+
+/** Structured error returned (as a JSON string) when a command rejects an input value */
+export interface ValidationError {
+  field: string;
+  code: 'required' | 'invalid_range' | 'in_future' | 'out_of_range' | 'overlap' | 'gap';
+  message: string;
+}
+
+/** Local app usage metric count, per day (`day` = UTC day start) or in total */
+export interface AppMetricCount {
+  day: number | null;
+  metric: string;
+  count: number;
+}
+
+/** Local app usage metrics for a range (never sent anywhere) */
+export interface AppUsageMetrics {
+  totals: AppMetricCount[];
+  by_day: AppMetricCount[];
+}
+
+/** One dashboard KPI card (computed by core or a plugin) */
+export interface DashboardKpi {
+  id: string;
+  label: string;
+  /** Number for `seconds`, `percent` and `count`; text otherwise */
+  value: number | string;
+  unit: 'seconds' | 'percent' | 'count' | 'text';
+  detail?: string | null;
+  /** `core` or a plugin ID */
+  source: string;
+}
+
+export interface Dashboard {
+  date: number;
+  kpis: DashboardKpi[];
+  /** Every card ID that can be configured */
+  available: string[];
+}
+
+/** publish_report profile: `client` leaves out per-app details */
+export type ReportProfile = 'full' | 'client';
+
+// Result of anonymize_copy
+export interface AnonymizeSummary {
+  path: string;
+  columns: string[]; // replaced columns as `table.column`
+  values: number; // distinct values replaced by placeholders
+}
+
+export interface ParquetExport {
+  activities_path: string;
+  activities: number; // rows written
+  manual_entries_path: string;
+  manual_entries: number;
+}
+
+/** Report/summary template; `customized` when the user's file replaces the built-in one */
+export interface TemplateInfo {
+  name: string; // e.g. daily_note.md, report.html
+  path: string;
+  customized: boolean;
+}
+
+// OS usage import types
+export type OsUsageImportFormat = 'screen_time' | 'windows_timeline' | 'activitywatch';
+
+export interface ImportSummary {
+  imported: number;
+  skipped: number; // rows overlapping already recorded activities
 }
 
 // Settings types
@@ -67,6 +542,11 @@ export interface Settings {
   darkMode?: boolean; // Frontend-only dark mode setting
   enable_marketplace?: boolean;
   plugin_registry_urls?: string[];
+  tray_update_interval_seconds?: number;
+  retention_days?: number; // 0 keeps tracked activities forever
+  update_check_enabled?: boolean;
+  capture_context_ref?: boolean; // store the document/file/URL parsed from window titles
+  record_input_intensity?: boolean; // count seconds with keyboard/mouse input per activity
 }
 
 // Statistics types
@@ -75,6 +555,110 @@ export interface DailyStats {
   productive_duration_sec: number;
   categories: CategoryStats[];
   top_apps: AppStats[];
+  mood: MoodSummary;
+}
+
+/** Conditions for switching to a profile automatically; all given conditions must hold */
+export interface ProfileAutoSwitch {
+  days: number[]; // 0 = Monday; empty = every day
+  start_time: string | null; // HH:MM local, may wrap past midnight
+  end_time: string | null;
+  networks: string[]; // Wi-Fi names; empty = any network
+}
+
+/** Named settings profile (e.g. work vs personal) */
+export interface SettingsProfile {
+  name: string;
+  settings: Record<string, string>; // applied on switch
+  rule_ids: number[] | null; // null = all rules
+  hidden_category_ids: number[];
+  auto_switch: ProfileAutoSwitch | null;
+  created_at: number;
+}
+
+/** Active and recently used workspace database files */
+export interface WorkspaceConfig {
+  active: string | null;
+  recent: string[];
+}
+
+/** Startup salvage of a corrupted database */
+export interface DatabaseRecovery {
+  status: 'recovered' | 'reset';
+  problem: string; // integrity_check output or open error
+  quarantined_path: string | null; // damaged file moved aside
+  tables: { table: string; rows: number; error: string | null }[];
+  error: string | null;
+}
+
+/** Statement that ran longer than `slow_query_log_ms` */
+export interface SlowQuery {
+  sql: string;
+  count: number;
+  max_ms: number;
+  last_ms: number;
+  last_seen_at: number;
+  plan: string[]; // EXPLAIN QUERY PLAN lines
+  full_scans: string[]; // tables read without an index
+}
+
+export interface Diagnostics {
+  startup_recovery: DatabaseRecovery | null;
+  slow_query_log_ms: number; // 0 while slow query logging is off
+  slow_queries: SlowQuery[];
+}
+
+/** Result of comparing this build with the latest GitHub release */
+export interface UpdateInfo {
+  current_version: string;
+  latest_version: string;
+  update_available: boolean;
+  release_name: string | null;
+  changelog: string | null; // release notes (Markdown)
+  release_url: string;
+  published_at: string | null;
+}
+
+/** Something planned for a day; measured by its task, else its category */
+export interface DailyPlanItem {
+  title: string;
+  category_id?: number | null;
+  task_id?: number | null; // projects plugin task
+  target_minutes: number; // 1-1440
+}
+
+export interface DailyPlan {
+  date: string; // local date, YYYY-MM-DD
+  items: DailyPlanItem[];
+  created_at: number;
+  updated_at: number;
+}
+
+export interface DailyPlanItemReview extends DailyPlanItem {
+  target_sec: number;
+  actual_sec: number | null; // null without a category or task
+  difference_sec: number | null;
+}
+
+export interface DailyPlanReview {
+  date: string;
+  items: DailyPlanItemReview[];
+  planned_sec: number;
+  tracked_sec: number;
+}
+
+/** Energy/mood journal entry (score 1-5) */
+export interface MoodEntry {
+  id: number;
+  timestamp: number;
+  score: number;
+  note: string | null;
+  created_at: number;
+}
+
+export interface MoodSummary {
+  average_score: number | null;
+  entries: MoodEntry[];
 }
 
 export interface CategoryStats {
@@ -89,12 +673,44 @@ export interface AppStats {
   category: Category | null;
 }
 
+/** Coding time for one language, project or file */
+export interface CodingStat {
+  name: string;
+  duration_sec: number;
+}
+
+/** Response from get_coding_stats (from editor heartbeats) */
+export interface CodingStats {
+  total_seconds: number;
+  languages: CodingStat[];
+  projects: CodingStat[];
+  files: CodingStat[]; // top 100
+}
+
+/** Response item from get_input_intensity (activities recorded with record_input_intensity) */
+export interface InputIntensityStat {
+  app_name: string;
+  duration_sec: number;
+  active_seconds: number; // seconds with keyboard/mouse input
+}
+
 /** Response from get_stats (aggregated stats for any date range) */
 export interface StatsResponse {
   total_seconds: number;
   productive_seconds: number;
   category_breakdown: { category_id: number; category_name: string; color: string; seconds: number }[];
   app_breakdown: { app_name: string; seconds: number }[];
+  mood: MoodSummary;
+}
+
+/** Device with recorded activities (from get_devices) */
+export interface DeviceSummary {
+  device_id: string;
+  device_name: string | null;
+  activities: number;
+  first_seen: number;
+  last_seen: number;
+  current: boolean; // the device the app runs on
 }
 
 // Timeline types
@@ -106,6 +722,32 @@ export interface TimelineBlock {
   category: Category | null;
   is_idle: boolean;
   is_manual: boolean;
+  /** Activities merged into this block */
+  activity_count?: number;
+  /** Window titles open longest in this block */
+  top_titles?: TimelineTitle[];
+}
+
+export interface TimelineTitle {
+  title: string;
+  count: number;
+  duration_sec: number;
+}
+
+/** Block of merged activities as returned by get_timeline (timestamps in seconds) */
+export interface TimelineSegment {
+  started_at: number;
+  ended_at: number;
+  duration_sec: number;
+  app_name: string;
+  domain: string | null;
+  category_id: number | null;
+  is_idle: boolean;
+  activity_count: number;
+  first_activity_id: number;
+  first_title: string | null;
+  last_title: string | null;
+  top_titles: TimelineTitle[];
 }
 
 // Date range types
@@ -138,6 +780,21 @@ export interface DomainStat {
   count: number;
 }
 
+// Used for activities on the domain (and its subdomains) whose category is neutral
+export type DomainRatingValue = 'productive' | 'neutral' | 'distracting';
+
+export interface DomainRating {
+  domain: string;
+  rating: DomainRatingValue;
+  updated_at: number;
+}
+
+// Time per document/file/URL (activities captured with capture_context_ref)
+export interface ContextStat {
+  context_ref: string;
+  duration_sec: number;
+}
+
 // Statistics types (additional)
 export interface AppUsage {
   app_name: string;
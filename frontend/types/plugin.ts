@@ -50,6 +50,33 @@ export interface RegistryPlugin {
   min_core_version?: string;
   max_core_version?: string;
   api_version?: string;
+  updated_at?: string;
+  rating?: number;
+  rating_count?: number;
+  /** Whether min/max_core_version allow this app version */
+  compatible: boolean;
+  compatibility_issue?: string;
+}
+
+export type PluginSort = 'relevance' | 'downloads' | 'updated' | 'rating' | 'name';
+
+export interface PluginCategory {
+  name: string;
+  count: number;
+}
+
+export interface PluginCommandMetrics {
+  calls: number;
+  errors: number;
+  total_ms: number;
+  max_ms: number;
+}
+
+/** Plugin resource usage since the app started (totals are flattened in) */
+export interface PluginMetrics extends PluginCommandMetrics {
+  plugin_id: string;
+  last_called_at?: number;
+  commands: Record<string, PluginCommandMetrics>;
 }
 
 export interface InstalledPlugin {
@@ -0,0 +1,31 @@
+import type { Activity, Category, DomainRating } from '../types';
+
+/**
+ * Categories to offer in pickers: archived ones are left out, except the
+ * currently selected one so editing an old entry keeps its category.
+ */
+export const pickableCategories = (categories: Category[], selectedId?: number | null): Category[] =>
+  categories.filter((category) => !category.is_archived || category.id === selectedId);
+
+/**
+ * Whether an activity counts as productive (true), distracting (false) or
+ * neutral (null): the category decides, and for neutral categories the rating
+ * of the most specific rated domain. Mirrors the backend's stats queries.
+ */
+export const activityProductivity = (
+  activity: Pick<Activity, 'domain'>,
+  category: Category | undefined,
+  domainRatings: DomainRating[]
+): boolean | null => {
+  if (category?.is_productive === true || category?.is_productive === false) {
+    return category.is_productive;
+  }
+  const domain = activity.domain;
+  if (!domain) {
+    return null;
+  }
+  const rating = domainRatings
+    .filter((r) => domain === r.domain || domain.endsWith(`.${r.domain}`))
+    .sort((a, b) => b.domain.length - a.domain.length)[0]?.rating;
+  return rating === 'productive' ? true : rating === 'distracting' ? false : null;
+};
@@ -1,7 +1,8 @@
 import { exportApi } from '../services/api/export';
+import { rulesApi } from '../services/api/rules';
 import { showSuccess, handleApiError } from './toast';
 import { format } from 'date-fns';
-import type { DateRange } from '../types';
+import type { DateRange, RuleImportStrategy, RuleImportSummary } from '../types';
 
 /**
  * Export data to CSV or JSON using backend API
@@ -51,15 +52,84 @@ export async function exportData(
     const isJson = filePath.toLowerCase().endsWith('.json');
 
     // Export the data using backend API
-    if (isJson) {
-      await exportApi.exportToJson(normalizedRange, filePath);
-    } else {
-      await exportApi.exportToCsv(normalizedRange, filePath);
-    }
+    const writtenPath = isJson
+      ? await exportApi.exportToJson(normalizedRange, filePath)
+      : await exportApi.exportToCsv(normalizedRange, filePath);
 
-    const fileName = filePath.split('/').pop() || filePath.split('\\').pop() || 'file';
+    const fileName = writtenPath.split('/').pop() || writtenPath.split('\\').pop() || 'file';
     showSuccess(`Data exported successfully to ${fileName}`);
   } catch (error) {
     handleApiError(error, 'Failed to export data');
   }
 }
+
+/**
+ * Save an anonymized copy of the database (titles, domains and descriptions
+ * replaced by placeholders) that can be shared for debugging
+ */
+export async function exportAnonymizedDatabase(): Promise<void> {
+  try {
+    const { save } = await import('@tauri-apps/api/dialog');
+    const filePath = await save({
+      filters: [{ name: 'SQLite database', extensions: ['db'] }],
+      defaultPath: `timetracker-anonymized-${format(new Date(), 'yyyy-MM-dd')}.db`,
+    });
+    if (!filePath) {
+      return;
+    }
+
+    const summary = await exportApi.anonymizeCopy(filePath);
+    const fileName = filePath.split('/').pop() || filePath.split('\\').pop() || 'file';
+    showSuccess(`Anonymized copy saved to ${fileName} (${summary.values} values replaced)`);
+  } catch (error) {
+    handleApiError(error, 'Failed to create anonymized copy');
+  }
+}
+
+/**
+ * Save all rules as a rule pack that can be shared and imported elsewhere
+ */
+export async function exportRulePack(): Promise<void> {
+  try {
+    const { save } = await import('@tauri-apps/api/dialog');
+    const filePath = await save({
+      filters: [{ name: 'Rule pack', extensions: ['json'] }],
+      defaultPath: `timetracker-rules-${format(new Date(), 'yyyy-MM-dd')}.json`,
+    });
+    if (!filePath) {
+      return;
+    }
+
+    const count = await rulesApi.exportRules(filePath);
+    const fileName = filePath.split('/').pop() || filePath.split('\\').pop() || 'file';
+    showSuccess(`${count} rules exported to ${fileName}`);
+  } catch (error) {
+    handleApiError(error, 'Failed to export rules');
+  }
+}
+
+/**
+ * Import a rule pack; returns null when cancelled or failed
+ */
+export async function importRulePack(strategy: RuleImportStrategy): Promise<RuleImportSummary | null> {
+  try {
+    const { open } = await import('@tauri-apps/api/dialog');
+    const filePath = await open({
+      filters: [{ name: 'Rule pack', extensions: ['json'] }],
+      multiple: false,
+    });
+    if (!filePath || Array.isArray(filePath)) {
+      return null;
+    }
+
+    const summary = await rulesApi.importRules(filePath, strategy);
+    const created = summary.categories_created.length
+      ? `, ${summary.categories_created.length} categories created`
+      : '';
+    showSuccess(`${summary.added} rules added, ${summary.updated} updated, ${summary.skipped} skipped${created}`);
+    return summary;
+  } catch (error) {
+    handleApiError(error, 'Failed to import rules');
+    return null;
+  }
+}
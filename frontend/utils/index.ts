@@ -1 +1,2 @@
 export * from './format';
+export * from './categories';
@@ -1,4 +1,5 @@
 import toast from 'react-hot-toast';
+import type { ValidationError } from '../types';
 
 export const showError = (message: string) => {
   toast.error(message, {
@@ -21,7 +22,19 @@ export const showInfo = (message: string) => {
   });
 };
 
+/** Parse a structured validation error from a rejected command, if it is one */
+export const parseValidationError = (error: unknown): ValidationError | null => {
+  if (typeof error !== 'string' || !error.startsWith('{')) return null;
+  try {
+    const parsed = JSON.parse(error);
+    return typeof parsed?.field === 'string' && typeof parsed?.message === 'string' ? parsed : null;
+  } catch {
+    return null;
+  }
+};
+
 export const handleApiError = (error: unknown, defaultMessage = 'An error occurred') => {
-  const message = error instanceof Error ? error.message : String(error);
+  const validationError = parseValidationError(error);
+  const message = validationError?.message ?? (error instanceof Error ? error.message : String(error));
   showError(message || defaultMessage);
 };
@@ -172,4 +172,87 @@ pub trait PluginAPIInterface: Send + Sync {
     /// This method will be removed in a future major version.
     #[deprecated(note = "Use specific methods instead: get_categories(), create_category(), query_own_table(), etc.")]
     fn call_db_method(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, String>;
+    
+    // Methods added after 1.0 go below, at the end of the trait: plugins are
+    // loaded as dynamic libraries and call through the trait object's vtable,
+    // so inserting a method shifts every slot after it. Bump the minor
+    // `SDK_VERSION` with any change to this trait or to `Plugin`.
+    
+    // ============================================================================
+    // Event Methods
+    // ============================================================================
+    
+    /// Publish an event on the Core event bus (delivered to webhooks and other subscribers)
+    /// 
+    /// The Core prefixes the name with the plugin ID, so `completed` emitted by
+    /// the `pomodoro` plugin is published as `pomodoro.completed`.
+    /// 
+    /// The default implementation reports that events are not supported, so
+    /// hosts built against older SDK versions keep compiling.
+    fn emit_event(&self, name: &str, payload: serde_json::Value) -> Result<(), String> {
+        let _ = (name, payload);
+        Err("Events are not supported by this host".to_string())
+    }
+    
+    // ============================================================================
+    // Data Source Methods
+    // ============================================================================
+    
+    /// Contribute an activity sample (e.g. from an SSH session or IDE tracker)
+    /// 
+    /// Samples go through the same merge and categorization pipeline as the
+    /// foreground window tracker and are stored with source `plugin:<plugin_id>`.
+    /// Report the current activity periodically (every few seconds) while it lasts.
+    /// 
+    /// The default implementation reports that data sources are not supported.
+    fn record_activity_sample(
+        &self,
+        app_name: &str,
+        window_title: Option<&str>,
+        domain: Option<&str>,
+        timestamp: i64,
+    ) -> Result<(), String> {
+        let _ = (app_name, window_title, domain, timestamp);
+        Err("Activity samples are not supported by this host".to_string())
+    }
+    
+    /// Whether the user is in a call or meeting right now
+    /// 
+    /// Judged by the current activity: in the Meetings category or in a known
+    /// call app or site (Zoom, Teams, Google Meet, ...). Idle time during a call
+    /// still counts; `false` about a minute after tracking stops or pauses.
+    /// Lets a plugin hold back an interruption, e.g. a break prompt, until the
+    /// meeting ends.
+    /// 
+    /// The default implementation reports that activity context is not supported.
+    fn is_in_meeting(&self) -> Result<bool, String> {
+        Err("Activity context is not supported by this host".to_string())
+    }
+    
+    // ============================================================================
+    // Sync ID Methods
+    // ============================================================================
+    
+    /// UUID of a synced record (`manual_entry`, `project`, `task` or `goal`)
+    /// 
+    /// Integer IDs are local to one device; the UUID identifies the record on
+    /// every device. The default implementation reports that sync IDs are not
+    /// supported.
+    fn get_sync_id(&self, entity_type: &str, id: i64) -> Result<Option<String>, String> {
+        let _ = (entity_type, id);
+        Err("Sync IDs are not supported by this host".to_string())
+    }
+    
+    /// Local ID of the synced record with `uuid`, if it exists and was not deleted
+    fn find_by_sync_id(&self, entity_type: &str, uuid: &str) -> Result<Option<i64>, String> {
+        let _ = (entity_type, uuid);
+        Err("Sync IDs are not supported by this host".to_string())
+    }
+    
+    /// Give a record the UUID it has on another device (after creating a
+    /// record received from there)
+    fn set_sync_id(&self, entity_type: &str, id: i64, uuid: &str) -> Result<(), String> {
+        let _ = (entity_type, id, uuid);
+        Err("Sync IDs are not supported by this host".to_string())
+    }
 }
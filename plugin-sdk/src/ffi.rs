@@ -3,8 +3,12 @@
 //! Plugins compiled as dynamic libraries (.dll/.so/.dylib) must export
 //! these functions to be loadable by the core application.
 
+use std::ffi::{c_char, CStr};
 use crate::plugin::Plugin;
 
+/// `SDK_VERSION` as a C string, returned by `sdk_version_ptr`
+const SDK_VERSION_C: &CStr = c"1.1.0";
+
 /// Function pointer type for creating a plugin instance
 /// Plugins must export a function with this signature: `#[no_mangle] pub extern "C" fn _plugin_create() -> *mut dyn Plugin`
 pub type PluginCreateFn = unsafe extern "C" fn() -> *mut dyn Plugin;
@@ -12,3 +16,23 @@ pub type PluginCreateFn = unsafe extern "C" fn() -> *mut dyn Plugin;
 /// Function pointer type for destroying a plugin instance
 /// Plugins must export a function with this signature: `#[no_mangle] pub extern "C" fn _plugin_destroy(plugin: *mut dyn Plugin)`
 pub type PluginDestroyFn = unsafe extern "C" fn(*mut dyn Plugin);
+
+/// Function pointer type for reporting the SDK version a plugin was built against
+/// Plugins must export a function with this signature: `#[no_mangle] pub extern "C" fn _plugin_sdk_version() -> *const c_char`
+/// that returns `time_tracker_plugin_sdk::ffi::sdk_version_ptr()`
+pub type PluginSdkVersionFn = unsafe extern "C" fn() -> *const c_char;
+
+/// Pointer to the NUL-terminated `SDK_VERSION` of the SDK the caller is built against
+pub fn sdk_version_ptr() -> *const c_char {
+    SDK_VERSION_C.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn c_version_matches_sdk_version() {
+        assert_eq!(SDK_VERSION_C.to_str().unwrap(), crate::SDK_VERSION);
+    }
+}
@@ -0,0 +1,37 @@
+//! Write hooks that let plugins inspect data before Core stores it
+
+use serde::{Deserialize, Serialize};
+
+/// An activity sample about to be written to the database
+///
+/// Core merges the sample into the activity it continues (same app, title and
+/// source) or inserts a new row, then applies categorization rules.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityDraft {
+    pub app_name: String,
+    pub window_title: Option<String>,
+    pub domain: Option<String>,
+    /// Unix timestamp of the observation
+    pub timestamp: i64,
+    /// Activity source, e.g. `tracker`, `ide` or `plugin:<plugin_id>`
+    pub source: String,
+    /// Document, file or URL the activity is about (parsed from the window
+    /// title when `capture_context_ref` is enabled); plugins may set it
+    #[serde(default)]
+    pub context_ref: Option<String>,
+    /// Values for plugin-extended activity columns (e.g. `project_id`),
+    /// written to the stored row; unknown keys are ignored
+    #[serde(default)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+/// What a plugin wants done with an activity write
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActivityWriteDecision {
+    /// Store the activity unchanged
+    Allow,
+    /// Store this version instead (e.g. with a redacted window title)
+    Modify(ActivityDraft),
+    /// Do not store the activity at all
+    Drop,
+}
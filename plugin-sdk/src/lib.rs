@@ -7,11 +7,20 @@ pub mod plugin;
 pub mod extensions;
 pub mod api;
 pub mod ffi;
+pub mod hooks;
+pub mod validation;
 
 pub use plugin::{Plugin, PluginInfo};
 pub use extensions::{EntityType, ExtensionType, SchemaChange, ModelField, QueryFilter, ForeignKey, TableColumn, AutoTimestamp};
 pub use api::{PluginAPIInterface, ActivityFilters};
-pub use ffi::{PluginCreateFn, PluginDestroyFn};
+pub use ffi::{PluginCreateFn, PluginDestroyFn, PluginSdkVersionFn};
+pub use hooks::{ActivityDraft, ActivityWriteDecision};
+pub use validation::{ValidationCode, ValidationError};
 
 /// SDK version for compatibility checking
-pub const SDK_VERSION: &str = "1.0.0";
+///
+/// Plugins report the version they were built against through `_plugin_sdk_version`,
+/// and the Core refuses to load a plugin whose major or minor version differs: the
+/// `Plugin` and `PluginAPIInterface` vtables are only compatible within one minor
+/// version. Patch releases must not change either trait.
+pub const SDK_VERSION: &str = "1.1.0";
@@ -36,4 +36,18 @@ pub trait Plugin: Send + Sync {
     fn get_frontend_bundle(&self) -> Option<Vec<u8>> {
         None
     }
+    
+    // Methods added after 1.0 go below, at the end of the trait, so the vtable
+    // slots of the older ones do not move. Bump the minor `SDK_VERSION` with any change.
+    
+    /// Called after Core settings change, with the keys that changed
+    /// Plugins re-read the settings they depend on through the api
+    fn on_settings_changed(&self, _keys: &[String], _api: &dyn crate::api::PluginAPIInterface) {}
+    
+    /// Called before an activity sample is written to the database
+    /// Return `Modify` to redact or enrich it (e.g. attach a project id) or `Drop` to discard it.
+    /// Runs on the tracker thread for every sample, so keep it fast and do not block.
+    fn before_activity_save(&self, _activity: &crate::hooks::ActivityDraft) -> crate::hooks::ActivityWriteDecision {
+        crate::hooks::ActivityWriteDecision::Allow
+    }
 }
@@ -0,0 +1,216 @@
+//! Shared input validation for entries, goals and projects
+//!
+//! Core commands and plugins validate user input with the same rules so the
+//! frontend sees the same structured error wherever a value is rejected.
+//! Commands return `Result<_, String>`; `ValidationError` converts into a JSON
+//! string (`{"field": ..., "code": ..., "message": ...}`) that the frontend
+//! can parse to highlight the offending field.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Timestamps up to this many seconds ahead of "now" are not treated as the
+/// future, so small clock differences between UI and backend are accepted
+pub const FUTURE_TOLERANCE_SECS: i64 = 60;
+
+/// Why a value was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationCode {
+    /// A required value is missing or blank
+    Required,
+    /// The end of a range lies before its start
+    InvalidRange,
+    /// A timestamp lies in the future
+    InFuture,
+    /// A number is outside its allowed bounds
+    OutOfRange,
+    /// Ranges that must not overlap do
+    Overlap,
+    /// Ranges leave part of the period they must cover uncovered
+    Gap,
+}
+
+/// A rejected input value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValidationError {
+    /// Name of the offending parameter, e.g. `ended_at`
+    pub field: String,
+    pub code: ValidationCode,
+    pub message: String,
+}
+
+impl ValidationError {
+    pub fn new(field: &str, code: ValidationCode, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+impl From<ValidationError> for String {
+    fn from(error: ValidationError) -> Self {
+        serde_json::to_string(&error).unwrap_or(error.message)
+    }
+}
+
+/// Reject a range whose end lies before its start
+pub fn time_range(started_at: i64, ended_at: i64) -> Result<(), ValidationError> {
+    if ended_at < started_at {
+        return Err(ValidationError::new(
+            "ended_at",
+            ValidationCode::InvalidRange,
+            "End time must not be before start time",
+        ));
+    }
+    Ok(())
+}
+
+/// Reject a query range whose end lies before its start; either bound may be open
+pub fn query_range(start: Option<i64>, end: Option<i64>) -> Result<(), ValidationError> {
+    match (start, end) {
+        (Some(start), Some(end)) if end < start => Err(ValidationError::new(
+            "end",
+            ValidationCode::InvalidRange,
+            "Range end must not be before range start",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Reject a timestamp later than `now` (beyond `FUTURE_TOLERANCE_SECS`)
+pub fn not_in_future(field: &str, timestamp: i64, now: i64) -> Result<(), ValidationError> {
+    if timestamp > now.saturating_add(FUTURE_TOLERANCE_SECS) {
+        return Err(ValidationError::new(
+            field,
+            ValidationCode::InFuture,
+            format!("{} must not be in the future", field_label(field)),
+        ));
+    }
+    Ok(())
+}
+
+/// Validate a completed time entry: a valid range that has already happened
+pub fn past_entry(started_at: i64, ended_at: i64, now: i64) -> Result<(), ValidationError> {
+    time_range(started_at, ended_at)?;
+    not_in_future("started_at", started_at, now)?;
+    not_in_future("ended_at", ended_at, now)
+}
+
+/// Check that `ranges` (sorted by start) tile `start..end` exactly: no
+/// overlaps, no gaps, nothing outside the period
+pub fn covers_range(field: &str, ranges: &[(i64, i64)], start: i64, end: i64) -> Result<(), ValidationError> {
+    let mut covered_to = start;
+    for &(range_start, range_end) in ranges {
+        time_range(range_start, range_end).map_err(|e| ValidationError { field: field.to_string(), ..e })?;
+        if range_start < covered_to {
+            return Err(ValidationError::new(field, ValidationCode::Overlap, "Segments must not overlap"));
+        }
+        if range_start > covered_to {
+            return Err(ValidationError::new(field, ValidationCode::Gap, "Segments must cover the whole period"));
+        }
+        covered_to = range_end;
+    }
+    match covered_to.cmp(&end) {
+        std::cmp::Ordering::Less => Err(ValidationError::new(field, ValidationCode::Gap, "Segments must cover the whole period")),
+        std::cmp::Ordering::Greater => Err(ValidationError::new(
+            field,
+            ValidationCode::OutOfRange,
+            "Segments must not extend past the period",
+        )),
+        std::cmp::Ordering::Equal => Ok(()),
+    }
+}
+
+/// Reject a missing or blank string
+pub fn required<'a>(field: &str, value: Option<&'a str>) -> Result<&'a str, ValidationError> {
+    match value.map(str::trim) {
+        Some(value) if !value.is_empty() => Ok(value),
+        _ => Err(ValidationError::new(
+            field,
+            ValidationCode::Required,
+            format!("{} is required", field_label(field)),
+        )),
+    }
+}
+
+/// Reject a number outside `min..=max`
+pub fn in_range<T>(field: &str, value: T, min: T, max: T) -> Result<T, ValidationError>
+where
+    T: PartialOrd + fmt::Display + Copy,
+{
+    if value < min || value > max {
+        return Err(ValidationError::new(
+            field,
+            ValidationCode::OutOfRange,
+            format!("{} must be between {} and {}", field_label(field), min, max),
+        ));
+    }
+    Ok(value)
+}
+
+/// `ended_at` -> `Ended at`
+fn field_label(field: &str) -> String {
+    let label = field.replace('_', " ");
+    let mut chars = label.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => label,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_end_before_start() {
+        let error = time_range(100, 50).unwrap_err();
+        assert_eq!(error.field, "ended_at");
+        assert_eq!(error.code, ValidationCode::InvalidRange);
+        assert!(time_range(50, 50).is_ok());
+    }
+
+    #[test]
+    fn open_query_ranges_are_valid() {
+        assert!(query_range(None, Some(10)).is_ok());
+        assert!(query_range(Some(10), None).is_ok());
+        assert!(query_range(Some(10), Some(5)).is_err());
+    }
+
+    #[test]
+    fn future_timestamps_allow_clock_skew() {
+        assert!(not_in_future("ended_at", 1_000 + FUTURE_TOLERANCE_SECS, 1_000).is_ok());
+        let error = past_entry(900, 2_000, 1_000).unwrap_err();
+        assert_eq!(error.code, ValidationCode::InFuture);
+        assert_eq!(error.message, "Ended at must not be in the future");
+    }
+
+    #[test]
+    fn ranges_must_tile_the_period() {
+        assert!(covers_range("segments", &[(0, 10), (10, 30)], 0, 30).is_ok());
+        let overlap = covers_range("segments", &[(0, 15), (10, 30)], 0, 30).unwrap_err();
+        assert_eq!(overlap.code, ValidationCode::Overlap);
+        let gap = covers_range("segments", &[(0, 10), (12, 30)], 0, 30).unwrap_err();
+        assert_eq!(gap.code, ValidationCode::Gap);
+        assert_eq!(covers_range("segments", &[(0, 10)], 0, 30).unwrap_err().code, ValidationCode::Gap);
+    }
+
+    #[test]
+    fn errors_convert_to_json_strings() {
+        let message: String = required("name", Some("  ")).unwrap_err().into();
+        let parsed: ValidationError = serde_json::from_str(&message).unwrap();
+        assert_eq!(parsed.code, ValidationCode::Required);
+        assert_eq!(parsed.message, "Name is required");
+    }
+}
@@ -0,0 +1,50 @@
+# Time Tracker terminal hook for bash and zsh
+#
+# Reports the host, working directory and project to Time Tracker on every
+# prompt, so long SSH/tmux sessions are attributed to the project you work in
+# rather than just "Terminal". Requires curl and the local listener
+# (Settings: ide_heartbeats_enabled = true, ide_heartbeats_port = 9900).
+#
+# Reports need the local API token (shown with the REST API settings):
+#   export TIME_TRACKER_TOKEN=<token>
+#
+# Usage: add to ~/.bashrc or ~/.zshrc
+#   source /path/to/terminal-hook.sh
+#
+# On remote hosts, source the script there as well and forward the port:
+#   ssh -R 9900:127.0.0.1:9900 user@host
+
+TIME_TRACKER_URL="${TIME_TRACKER_URL:-http://127.0.0.1:9900/terminal}"
+
+__time_tracker_json_escape() {
+  local s=${1//\\/\\\\}
+  s=${s//\"/\\\"}
+  printf '%s' "$s"
+}
+
+__time_tracker_report() {
+  command -v curl >/dev/null 2>&1 || return
+  [ -n "$TIME_TRACKER_TOKEN" ] || return
+  local host cwd project remote toplevel
+  host=$(hostname 2>/dev/null || uname -n)
+  cwd=$PWD
+  toplevel=$(git rev-parse --show-toplevel 2>/dev/null)
+  project=${toplevel:+${toplevel##*/}}
+  if [ -n "$SSH_CONNECTION" ]; then remote=true; else remote=false; fi
+
+  (curl -s -m 2 -o /dev/null -X POST "$TIME_TRACKER_URL" \
+    -H 'Content-Type: application/json' \
+    -H "Authorization: Bearer $TIME_TRACKER_TOKEN" \
+    -d "{\"host\":\"$(__time_tracker_json_escape "$host")\",\"cwd\":\"$(__time_tracker_json_escape "$cwd")\",\"project\":\"$(__time_tracker_json_escape "$project")\",\"remote\":$remote}" \
+    >/dev/null 2>&1 &)
+}
+
+if [ -n "$ZSH_VERSION" ]; then
+  autoload -Uz add-zsh-hook
+  add-zsh-hook precmd __time_tracker_report
+elif [ -n "$BASH_VERSION" ]; then
+  case ";$PROMPT_COMMAND;" in
+    *";__time_tracker_report;"*) ;;
+    *) PROMPT_COMMAND="__time_tracker_report${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+  esac
+fi